@@ -0,0 +1,98 @@
+//! Call Arguments
+//!
+//! Finds the calls in a syntax tree and splits their argument lists into
+//! positional and named (`Width:=10`) arguments, for the passes that check
+//! arguments against the parameters of the called procedure.
+
+use tree_sitter::Node;
+
+/// One argument of a call
+#[derive(Debug, Clone, Copy)]
+pub struct Argument<'t> {
+    /// The name before `:=` of a named argument
+    pub name: Option<Node<'t>>,
+    pub value: Node<'t>,
+    /// Zero-based position in the argument list, counting omitted arguments
+    pub index: usize,
+}
+
+/// Every call in a subtree, as its callee and argument list nodes
+pub fn find_calls<'t>(node: &Node<'t>, source: &str) -> Vec<(Node<'t>, Node<'t>)> {
+    let mut calls = Vec::new();
+    visit(node, source, &mut calls);
+    calls
+}
+
+fn visit<'t>(node: &Node<'t>, source: &str, calls: &mut Vec<(Node<'t>, Node<'t>)>) {
+    let call = match node.kind() {
+        "call_expression" => node.child_by_field_name("function").zip(
+            child_of_kind(node, "argument_list")
+                .and_then(|list| child_of_kind(&list, "argument_list_inner")),
+        ),
+        "implicit_call_stmt" => node
+            .named_child(0)
+            .zip(child_of_kind(node, "argument_list_no_parens").map(|list| {
+                // `Call Inc(v)` parses as a parenthesized argument, but after
+                // Call the parentheses delimit the argument list
+                let after_call = node
+                    .prev_sibling()
+                    .is_some_and(|keyword| text(&keyword, source).eq_ignore_ascii_case("call"));
+                match list.named_child(0) {
+                    Some(only)
+                        if after_call
+                            && list.named_child_count() == 1
+                            && only.kind() == "parenthesized_expression" =>
+                    {
+                        only
+                    }
+                    _ => list,
+                }
+            })),
+        _ => None,
+    };
+    calls.extend(call);
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        visit(&child, source, calls);
+    }
+}
+
+/// The arguments of an argument list, named or positional
+pub fn call_arguments<'t>(arguments: &Node<'t>) -> Vec<Argument<'t>> {
+    let mut result = Vec::new();
+    let mut index = 0;
+    let mut name: Option<Node<'t>> = None;
+
+    let mut cursor = arguments.walk();
+    let children: Vec<Node> = arguments.children(&mut cursor).collect();
+    for (i, child) in children.iter().enumerate() {
+        match child.kind() {
+            "," => {
+                index += 1;
+                name = None;
+            }
+            ":=" => {}
+            _ if !child.is_named() || child.is_missing() => {}
+            "identifier" if children.get(i + 1).is_some_and(|next| next.kind() == ":=") => {
+                name = Some(*child);
+            }
+            _ => result.push(Argument {
+                name,
+                value: *child,
+                index,
+            }),
+        }
+    }
+    result
+}
+
+fn child_of_kind<'t>(node: &Node<'t>, kind: &str) -> Option<Node<'t>> {
+    let mut cursor = node.walk();
+    let child = node.named_children(&mut cursor).find(|child| child.kind() == kind);
+    child
+}
+
+fn text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
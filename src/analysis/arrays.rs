@@ -0,0 +1,259 @@
+//! Array Checking
+//!
+//! `ReDim` resizes a dynamic array. Reports a `ReDim` of an array declared
+//! with bounds (a compile error), a `ReDim` of a name that was never declared
+//! (which quietly declares a new array, even under `Option Explicit`), and a
+//! `ReDim Preserve` that changes more than the last dimension (a run-time error).
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::position::{SourcePosition, SourceRange};
+use super::symbol::SymbolId;
+use super::symbol_table::SymbolTable;
+use super::undeclared::has_option_explicit;
+use crate::parser::ArrayDimension;
+
+/// Diagnostic code for `ReDim` of an array declared with bounds
+pub const REDIM_FIXED_ARRAY: &str = "redim-fixed-array";
+/// Diagnostic code for `ReDim` of a name with no declaration
+pub const REDIM_UNDECLARED: &str = "redim-undeclared";
+/// Diagnostic code for `ReDim Preserve` changing a dimension other than the last
+pub const REDIM_PRESERVE_DIMENSIONS: &str = "redim-preserve-dimensions";
+
+const PROCEDURES: &[&str] = &["sub_declaration", "function_declaration", "property_declaration"];
+
+/// Check the `ReDim` statements of every procedure.
+///
+/// `is_external` resolves names declared in other files of the workspace.
+pub fn find_array_problems(
+    table: &SymbolTable,
+    tree: &Tree,
+    source: &str,
+    is_external: impl Fn(&str) -> bool,
+) -> Vec<Diagnostic> {
+    let option_explicit = has_option_explicit(&tree.root_node(), source);
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        if PROCEDURES.contains(&node.kind()) {
+            let mut statements = Vec::new();
+            collect_redim_statements(&node, &mut statements);
+
+            // The bounds each array was last given in this procedure
+            let mut bounds: HashMap<SymbolId, Vec<ArrayDimension>> = HashMap::new();
+            for statement in statements {
+                let preserve = has_keyword(&statement, "preserve", source);
+                let mut cursor = statement.walk();
+                for variable in statement.named_children(&mut cursor) {
+                    let Some(name) = variable.child_by_field_name("name") else {
+                        continue;
+                    };
+                    if name.kind() != "identifier" {
+                        continue;
+                    }
+                    let text = name.utf8_text(source.as_bytes()).unwrap_or_default();
+                    let position = SourcePosition::from_ts_point(name.start_position());
+                    let scope = table.scope_at_position(position);
+
+                    let Some(symbol) = table.lookup_symbol(text, scope) else {
+                        if option_explicit && !is_external(text) {
+                            diagnostics.push(diagnostic(
+                                &name,
+                                DiagnosticSeverity::WARNING,
+                                REDIM_UNDECLARED,
+                                format!(
+                                    "'{}' is not declared, so ReDim declares a new array; \
+                                     declare it with Dim {}() first",
+                                    text, text
+                                ),
+                            ));
+                        }
+                        continue;
+                    };
+
+                    if symbol.type_info.as_ref().is_some_and(|t| t.is_fixed_array()) {
+                        diagnostics.push(diagnostic(
+                            &name,
+                            DiagnosticSeverity::ERROR,
+                            REDIM_FIXED_ARRAY,
+                            format!(
+                                "'{}' is declared with fixed bounds and cannot be resized; \
+                                 declare it as {}() to use ReDim",
+                                symbol.name, symbol.name
+                            ),
+                        ));
+                        continue;
+                    }
+
+                    let dimensions = redim_dimensions(&variable, &name, source);
+                    if let (true, Some(previous)) = (preserve, bounds.get(&symbol.id)) {
+                        if !only_last_changed(previous, &dimensions) {
+                            diagnostics.push(diagnostic(
+                                &variable,
+                                DiagnosticSeverity::WARNING,
+                                REDIM_PRESERVE_DIMENSIONS,
+                                format!(
+                                    "ReDim Preserve can only change the upper bound of the \
+                                     last dimension of '{}'",
+                                    symbol.name
+                                ),
+                            ));
+                        }
+                    }
+                    bounds.insert(symbol.id, dimensions);
+                }
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+/// The `ReDim` statements below a node, in source order
+fn collect_redim_statements<'a>(node: &Node<'a>, statements: &mut Vec<Node<'a>>) {
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    for child in children {
+        if child.kind() == "redim_statement" {
+            statements.push(child);
+        } else {
+            collect_redim_statements(&child, statements);
+        }
+    }
+}
+
+fn has_keyword(node: &Node, keyword: &str, source: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).any(|child| {
+        !child.is_named()
+            && child.utf8_text(source.as_bytes()).is_ok_and(|t| t.eq_ignore_ascii_case(keyword))
+    });
+    found
+}
+
+/// The dimensions a `ReDim` gives an array. Read from the text: the grammar
+/// does not parse `To` bounds in a ReDim.
+fn redim_dimensions(variable: &Node, name: &Node, source: &str) -> Vec<ArrayDimension> {
+    let text = &source[name.end_byte()..variable.end_byte()];
+    let Some(open) = text.find('(') else {
+        return Vec::new();
+    };
+
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return ArrayDimension::parse_list(&text[open + 1..open + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    Vec::new()
+}
+
+/// Whether new bounds keep every dimension but the last, and its lower bound
+fn only_last_changed(previous: &[ArrayDimension], dimensions: &[ArrayDimension]) -> bool {
+    let normalize = |text: &str| text.split_whitespace().collect::<String>().to_lowercase();
+    let same = |a: &Option<String>, b: &Option<String>| {
+        a.as_deref().map(normalize) == b.as_deref().map(normalize)
+    };
+
+    let (Some((last_previous, previous)), Some((last, dimensions))) =
+        (previous.split_last(), dimensions.split_last())
+    else {
+        return previous.len() == dimensions.len();
+    };
+    previous.len() == dimensions.len()
+        && same(&last_previous.lower, &last.lower)
+        && previous.iter().zip(dimensions).all(|(a, b)| {
+            same(&a.lower, &b.lower) && normalize(&a.upper) == normalize(&b.upper)
+        })
+}
+
+fn diagnostic(
+    node: &Node,
+    severity: DiagnosticSeverity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        range: SourceRange::from_ts_node(node).to_lsp(),
+        severity: Some(severity),
+        code: Some(NumberOrString::String(code.to_string())),
+        message,
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    fn problems(source: &str) -> Vec<(u32, String)> {
+        run_pass(source, |table, tree| {
+            find_array_problems(table, tree, source, |name| name == "gShared")
+        })
+            .into_iter()
+            .map(|d| match d.code {
+                Some(NumberOrString::String(code)) => (d.range.start.line, code),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_redim_fixed_and_undeclared() {
+        let source = "Option Explicit\n\
+Private mFixed(1 To 10) As Long\n\
+Private mItems() As String\n\
+Sub Main()\n\
+Dim v As Variant\n\
+ReDim mFixed(20)\n\
+ReDim mItems(5), v(2)\n\
+ReDim mMissing(3)\n\
+ReDim gShared(3)\n\
+End Sub\n";
+        assert_eq!(
+            problems(source),
+            vec![(5, REDIM_FIXED_ARRAY.to_string()), (7, REDIM_UNDECLARED.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_undeclared_needs_option_explicit() {
+        let source = "Sub Main()\nReDim mMissing(3)\nEnd Sub\n";
+        assert!(problems(source).is_empty());
+    }
+
+    #[test]
+    fn test_redim_preserve_last_dimension() {
+        let source = "Sub Main()\n\
+Dim grid() As Long\n\
+ReDim grid(1 To 5, 10)\n\
+ReDim Preserve grid(1 To 5, 20)\n\
+ReDim Preserve grid(1 To 6, 20)\n\
+ReDim Preserve grid(30)\n\
+End Sub\n";
+        assert_eq!(
+            problems(source),
+            vec![
+                (4, REDIM_PRESERVE_DIMENSIONS.to_string()),
+                (5, REDIM_PRESERVE_DIMENSIONS.to_string()),
+            ]
+        );
+    }
+}
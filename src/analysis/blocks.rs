@@ -0,0 +1,590 @@
+//! Block Balance Checking
+//!
+//! Matches block openers (`If ... Then`, `For`, `Do`, `Sub`, ...) with their
+//! closers by scanning statements, so an unbalanced block is reported where
+//! it happens instead of as a generic syntax error. The matched pairs also
+//! drive linked editing of `Sub`/`End Sub` and `For i`/`Next i`.
+
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString,
+    Position, Range, Url,
+};
+
+use crate::parser::InactiveRegion;
+
+/// Diagnostic code for a block without its closing keyword (or vice versa)
+pub const UNBALANCED_BLOCK: &str = "unbalanced-block";
+
+/// Kind of block opened by a statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    If,
+    For,
+    Do,
+    While,
+    With,
+    Select,
+    Sub,
+    Function,
+    Property,
+    Type,
+    Enum,
+}
+
+impl Block {
+    /// Keyword that opens the block
+    fn name(self) -> &'static str {
+        match self {
+            Block::If => "If",
+            Block::For => "For",
+            Block::Do => "Do",
+            Block::While => "While",
+            Block::With => "With",
+            Block::Select => "Select Case",
+            Block::Sub => "Sub",
+            Block::Function => "Function",
+            Block::Property => "Property",
+            Block::Type => "Type",
+            Block::Enum => "Enum",
+        }
+    }
+
+    /// Keyword that closes the block
+    fn closer(self) -> &'static str {
+        match self {
+            Block::If => "End If",
+            Block::For => "Next",
+            Block::Do => "Loop",
+            Block::While => "Wend",
+            Block::With => "End With",
+            Block::Select => "End Select",
+            Block::Sub => "End Sub",
+            Block::Function => "End Function",
+            Block::Property => "End Property",
+            Block::Type => "End Type",
+            Block::Enum => "End Enum",
+        }
+    }
+
+    /// Whether the block can only appear at module level (procedures, Type, Enum)
+    fn is_module_level(self) -> bool {
+        matches!(
+            self,
+            Block::Sub | Block::Function | Block::Property | Block::Type | Block::Enum
+        )
+    }
+}
+
+/// What a statement does to the block structure
+enum Keyword {
+    Open(Block),
+    /// Closes blocks; `Next j, i` closes two
+    Close(Block, usize),
+    /// `Else` or `ElseIf`, which need an enclosing If
+    Else(&'static str),
+    /// A single-line If, which owns the rest of the line
+    SingleLineIf,
+    None,
+}
+
+/// An opened block waiting for its closer
+struct OpenBlock {
+    block: Block,
+    range: Range,
+    /// The opening statement
+    text: String,
+}
+
+/// Words that may precede Sub, Function, Property, Type, and Enum
+const MODIFIERS: &[&str] = &["public", "private", "friend", "static", "global"];
+
+/// Find blocks whose opening and closing keywords do not match.
+///
+/// Code excluded by `#If` directives is skipped, as is the designer header
+/// of forms and classes.
+pub fn find_unbalanced_blocks(
+    uri: &Url,
+    source: &str,
+    inactive: &[InactiveRegion],
+) -> Vec<Diagnostic> {
+    scan_blocks(uri, source, inactive, |_, _, _, _| {})
+}
+
+/// Ranges that are edited together with the word at a position: the `Sub` of
+/// `Sub` and `End Sub` (likewise If, With, Select, Function, Property, Type,
+/// and Enum), or the loop variable of `For i` and `Next i`
+pub fn linked_editing_ranges(
+    uri: &Url,
+    source: &str,
+    inactive: &[InactiveRegion],
+    position: Position,
+) -> Option<Vec<Range>> {
+    let mut linked = None;
+    scan_blocks(uri, source, inactive, |open, closer, closer_text, index| {
+        if linked.is_some() {
+            return;
+        }
+        let ranges = match open.block {
+            Block::For => word_range(open.range, &open.text, loop_variable(&open.text))
+                .zip(word_range(closer, closer_text, 1 + index)),
+            Block::Do | Block::While => None,
+            _ => word_range(open.range, &open.text, keyword_index(&open.text))
+                .zip(word_range(closer, closer_text, 1)),
+        };
+        // The ranges must hold the same word to be edited together
+        let Some(((opener, opener_word), (closer, closer_word))) = ranges else {
+            return;
+        };
+        if !opener_word.eq_ignore_ascii_case(&closer_word) {
+            return;
+        }
+        let contains = |range: Range| {
+            range.start.line == position.line
+                && (range.start.character..=range.end.character).contains(&position.character)
+        };
+        if contains(opener) || contains(closer) {
+            linked = Some(vec![opener, closer]);
+        }
+    });
+    linked
+}
+
+/// Match the blocks of a document, calling `on_close` with each matched opener,
+/// the range and text of its closer, and which of the blocks the closer closes
+/// (`Next j, i` closes two)
+fn scan_blocks(
+    uri: &Url,
+    source: &str,
+    inactive: &[InactiveRegion],
+    mut on_close: impl FnMut(&OpenBlock, Range, &str, usize),
+) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut stack: Vec<OpenBlock> = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    let mut line_idx = code_start(&lines);
+    while line_idx < lines.len() {
+        // Join lines continued with " _"
+        let first = line_idx;
+        let mut text: Vec<char> = lines[line_idx].chars().collect();
+        while text.iter().collect::<String>().trim_end().ends_with(" _")
+            && line_idx + 1 < lines.len()
+        {
+            while text.last().is_some_and(|c| c.is_whitespace()) {
+                text.pop();
+            }
+            text.pop();
+            line_idx += 1;
+            text.extend(lines[line_idx].chars());
+        }
+        line_idx += 1;
+
+        if inactive.iter().any(|r| r.contains_line(first)) {
+            continue;
+        }
+        let first_len = lines[first].chars().count();
+
+        for (start, end) in split_statements(&text) {
+            let statement: String = text[start..end].iter().collect();
+            let range = Range {
+                start: Position::new(first as u32, start.min(first_len) as u32),
+                end: Position::new(first as u32, end.min(first_len) as u32),
+            };
+
+            match classify(&statement) {
+                Keyword::Open(block) => {
+                    // A new procedure means everything still open was never closed
+                    if block.is_module_level() {
+                        while let Some(open) = stack.pop() {
+                            diagnostics.push(expected_closer(uri, &open, range));
+                        }
+                    }
+                    stack.push(OpenBlock { block, range, text: statement });
+                }
+                Keyword::Close(block, count) => {
+                    for index in 0..count {
+                        match close_block(uri, &mut stack, block, range, &mut diagnostics) {
+                            Some(open) => on_close(&open, range, &statement, index),
+                            None => break,
+                        }
+                    }
+                }
+                Keyword::Else(keyword) => {
+                    if stack.last().map(|open| open.block) != Some(Block::If) {
+                        diagnostics.push(diagnostic(
+                            range,
+                            format!("'{}' without matching 'If'", keyword),
+                        ));
+                    }
+                }
+                Keyword::SingleLineIf => break,
+                Keyword::None => {}
+            }
+        }
+    }
+
+    for open in stack {
+        diagnostics.push(diagnostic(
+            open.range,
+            format!("'{}' without matching '{}'", open.block.name(), open.block.closer()),
+        ));
+    }
+
+    diagnostics
+}
+
+/// Close the innermost block of a kind, reporting any blocks left open inside it.
+///
+/// Returns the closed block, or None if no such block is open.
+fn close_block(
+    uri: &Url,
+    stack: &mut Vec<OpenBlock>,
+    block: Block,
+    range: Range,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<OpenBlock> {
+    // Statement blocks never match across a procedure boundary
+    let matching = stack
+        .iter()
+        .rposition(|open| {
+            open.block == block || (!block.is_module_level() && open.block.is_module_level())
+        })
+        .filter(|&i| stack[i].block == block);
+
+    let Some(index) = matching else {
+        diagnostics.push(diagnostic(
+            range,
+            format!("'{}' without matching '{}'", block.closer(), block.name()),
+        ));
+        return None;
+    };
+
+    let mut closed = stack.drain(index..);
+    let open = closed.next();
+    for inner in closed.rev() {
+        diagnostics.push(expected_closer(uri, &inner, range));
+    }
+    open
+}
+
+/// The `index`th word of a statement and its range, if it is on the
+/// statement's first line
+fn word_range(range: Range, statement: &str, index: usize) -> Option<(Range, String)> {
+    let (start, end) = *words(statement).get(index)?;
+    let word = statement.chars().skip(start).take(end - start).collect();
+    let start = range.start.character + start as u32;
+    let end = range.start.character + end as u32;
+    let word_range = Range {
+        start: Position::new(range.start.line, start),
+        end: Position::new(range.start.line, end),
+    };
+    (end <= range.end.character).then_some((word_range, word))
+}
+
+/// Character spans of the identifier-like words of a statement
+fn words(statement: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in statement.chars().chain(std::iter::once(' ')).enumerate() {
+        match (c.is_alphanumeric() || c == '_', start) {
+            (true, None) => start = Some(i),
+            (false, Some(begin)) => {
+                spans.push((begin, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// Index of the declaring keyword of a block opener, after any modifiers
+/// (`Sub` in `Private Static Sub`)
+fn keyword_index(statement: &str) -> usize {
+    statement
+        .split_whitespace()
+        .take_while(|word| MODIFIERS.contains(&word.to_lowercase().as_str()))
+        .count()
+}
+
+/// Index of the loop variable of `For i = ...` or `For Each item In ...`
+fn loop_variable(statement: &str) -> usize {
+    match statement.split_whitespace().nth(1) {
+        Some(word) if word.eq_ignore_ascii_case("each") => 2,
+        _ => 1,
+    }
+}
+
+/// "Expected 'Next' to close 'For' opened at line N", pointing back at the opener
+fn expected_closer(uri: &Url, open: &OpenBlock, range: Range) -> Diagnostic {
+    Diagnostic {
+        related_information: Some(vec![DiagnosticRelatedInformation {
+            location: Location {
+                uri: uri.clone(),
+                range: open.range,
+            },
+            message: format!("'{}' opened here", open.block.name()),
+        }]),
+        ..diagnostic(
+            range,
+            format!(
+                "Expected '{}' to close '{}' opened at line {}",
+                open.block.closer(),
+                open.block.name(),
+                open.range.start.line + 1
+            ),
+        )
+    }
+}
+
+fn diagnostic(range: Range, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(UNBALANCED_BLOCK.to_string())),
+        message,
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Classify a statement by its leading keywords
+fn classify(statement: &str) -> Keyword {
+    let words: Vec<String> = statement
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+    let first = words.first().map(String::as_str).unwrap_or("");
+    let second = words.get(1).map(String::as_str).unwrap_or("");
+
+    match (first, second) {
+        ("if", _) => {
+            if words.last().is_some_and(|word| word == "then") {
+                Keyword::Open(Block::If)
+            } else {
+                Keyword::SingleLineIf
+            }
+        }
+        ("elseif", _) => Keyword::Else("ElseIf"),
+        ("else", _) => Keyword::Else("Else"),
+        ("for", _) => Keyword::Open(Block::For),
+        ("next", _) => {
+            // `Next j, i` closes one loop per variable
+            let variables = statement.split(',').count();
+            Keyword::Close(Block::For, variables.max(1))
+        }
+        ("do", _) => Keyword::Open(Block::Do),
+        ("loop", _) => Keyword::Close(Block::Do, 1),
+        ("while", _) => Keyword::Open(Block::While),
+        ("wend", _) => Keyword::Close(Block::While, 1),
+        ("with", _) => Keyword::Open(Block::With),
+        ("select", "case") => Keyword::Open(Block::Select),
+        ("end", closed) => match closed {
+            "if" => Keyword::Close(Block::If, 1),
+            "with" => Keyword::Close(Block::With, 1),
+            "select" => Keyword::Close(Block::Select, 1),
+            "sub" => Keyword::Close(Block::Sub, 1),
+            "function" => Keyword::Close(Block::Function, 1),
+            "property" => Keyword::Close(Block::Property, 1),
+            "type" => Keyword::Close(Block::Type, 1),
+            "enum" => Keyword::Close(Block::Enum, 1),
+            // The End statement
+            _ => Keyword::None,
+        },
+        _ => {
+            let mut declaration = words.iter().skip_while(|w| MODIFIERS.contains(&w.as_str()));
+            let keyword = declaration.next().map(String::as_str);
+            match (keyword, declaration.next().map(String::as_str)) {
+                (Some("sub"), _) => Keyword::Open(Block::Sub),
+                (Some("function"), _) => Keyword::Open(Block::Function),
+                (Some("property"), Some("get" | "let" | "set")) => Keyword::Open(Block::Property),
+                (Some("type"), Some(_)) => Keyword::Open(Block::Type),
+                (Some("enum"), Some(_)) => Keyword::Open(Block::Enum),
+                _ => Keyword::None,
+            }
+        }
+    }
+}
+
+/// Split a logical line into statements (by `:`), returning their trimmed
+/// character spans. Comments, string literals, and date literals are skipped.
+fn split_statements(text: &[char]) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut in_string = false;
+    let mut end = text.len();
+
+    let mut i = 0;
+    while i < text.len() {
+        let c = text[i];
+        if in_string {
+            in_string = c != '"';
+            i += 1;
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '\'' => {
+                end = i;
+                break;
+            }
+            // Date literals such as #12:30:00 PM# contain colons
+            '#' => {
+                let close = text[i + 1..].iter().position(|&c| c == '#');
+                if let Some(close) = close.filter(|&close| {
+                    text[i + 1..i + 1 + close]
+                        .iter()
+                        .all(|c| c.is_ascii_alphanumeric() || " /:-.,".contains(*c))
+                }) {
+                    i += close + 1;
+                }
+            }
+            // Statement separator (but not the := of a named argument)
+            ':' if text.get(i + 1) != Some(&'=') => {
+                spans.push((start, i));
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    spans.push((start, end));
+
+    spans
+        .into_iter()
+        .filter_map(|(start, end)| {
+            let mut start = start;
+            let mut end = end;
+            while start < end && text[start].is_whitespace() {
+                start += 1;
+            }
+            // Line numbers label a statement
+            let digits = text[start..end].iter().take_while(|c| c.is_ascii_digit()).count();
+            if digits > 0 && text.get(start + digits).is_some_and(|c| c.is_whitespace()) {
+                start += digits;
+                while start < end && text[start].is_whitespace() {
+                    start += 1;
+                }
+            }
+            while end > start && text[end - 1].is_whitespace() {
+                end -= 1;
+            }
+            let statement: String = text[start..end].iter().collect();
+            let lower = statement.to_lowercase();
+            // Rem comments and preprocessor directives are not statements
+            let skip = lower == "rem" || lower.starts_with("rem ") || lower.starts_with('#');
+            (start < end && !skip).then_some((start, end))
+        })
+        .collect()
+}
+
+/// Index of the first line of code, after the designer header of a form or class
+fn code_start(lines: &[&str]) -> usize {
+    let has_header = lines
+        .iter()
+        .find(|line| !line.trim().is_empty())
+        .is_some_and(|line| line.trim_start().to_lowercase().starts_with("version "));
+    if !has_header {
+        return 0;
+    }
+    lines
+        .iter()
+        .position(|line| line.trim_start().to_lowercase().starts_with("attribute "))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unbalanced(source: &str) -> Vec<Diagnostic> {
+        let uri = Url::parse("file:///test.bas").unwrap();
+        find_unbalanced_blocks(&uri, source, &[])
+    }
+
+    #[test]
+    fn test_balanced_blocks() {
+        let source = "VERSION 5.00\n\
+Begin VB.Form Form1\n\
+End\n\
+Attribute VB_Name = \"Form1\"\n\
+Private Type Point\n    X As Long\nEnd Type\n\
+Private Sub Main() ' If this were a comment\n\
+    Dim i As Long, j As Long\n\
+    For i = 1 To 3: For j = 1 To 3\n\
+        If i = j Then Debug.Print \"a:b\": Exit For\n\
+        If i > j _\n            Then\n            Do While j > 0: j = j - 1: Loop\n\
+        ElseIf i < j Then\n            MsgBox Prompt:=\"x\"\n\
+        Else\n            d = #12:30:00 PM#\n        End If\n\
+    Next j, i\n\
+    Select Case i\n        Case Else\n    End Select\n\
+10  With Me\n    End With\n\
+    While False\n    Wend\n\
+End Sub\n\
+Public Property Get Value() As Long\nEnd Property\n";
+        assert!(unbalanced(source).is_empty(), "{:?}", unbalanced(source));
+    }
+
+    #[test]
+    fn test_missing_closer() {
+        let source = "Sub Main()\n    For i = 1 To 3\n        x = i\nEnd Sub\n";
+        let diagnostics = unbalanced(source);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Expected 'Next' to close 'For' opened at line 2");
+        assert_eq!(diagnostics[0].range.start, Position::new(3, 0));
+        let related = diagnostics[0].related_information.as_ref().unwrap();
+        assert_eq!(related[0].location.range.start, Position::new(1, 4));
+    }
+
+    #[test]
+    fn test_unmatched_closers_and_unclosed_procedure() {
+        let source = "Sub Main()\n    End If\n    Else\n    Loop\nEnd Sub\n\
+Function Total() As Long\n    If x Then\n    End If\n";
+        let messages: Vec<(u32, String)> = unbalanced(source)
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                (1, "'End If' without matching 'If'".to_string()),
+                (2, "'Else' without matching 'If'".to_string()),
+                (3, "'Loop' without matching 'Do'".to_string()),
+                (5, "'Function' without matching 'End Function'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_linked_editing_ranges() {
+        let source = "Private Sub Main()\n\
+For i = 1 To 3: For Each item In items\n\
+With item\n\
+End With\n\
+Next item, i\n\
+Do\n\
+Loop\n\
+End Sub\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let linked = |line, character| -> Option<Vec<(u32, u32, u32)>> {
+            let ranges =
+                linked_editing_ranges(&uri, source, &[], Position::new(line, character))?;
+            let spans = ranges.iter().map(|r| (r.start.line, r.start.character, r.end.character));
+            Some(spans.collect())
+        };
+
+        // Sub and End Sub, from either end
+        assert_eq!(linked(0, 9), Some(vec![(0, 8, 11), (7, 4, 7)]));
+        assert_eq!(linked(7, 5), Some(vec![(0, 8, 11), (7, 4, 7)]));
+        // Loop variables, including those closed by one Next
+        assert_eq!(linked(1, 4), Some(vec![(1, 4, 5), (4, 11, 12)]));
+        assert_eq!(linked(4, 6), Some(vec![(1, 25, 29), (4, 5, 9)]));
+        assert_eq!(linked(3, 4), Some(vec![(2, 0, 4), (3, 4, 8)]));
+        // Do and Loop are different words
+        assert_eq!(linked(5, 1), None);
+        assert_eq!(linked(1, 12), None);
+    }
+}
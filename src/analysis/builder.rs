@@ -2,13 +2,19 @@
 //!
 //! Builds a symbol table by walking the tree-sitter parse tree.
 
-use tree_sitter::{Node, Tree};
+use tree_sitter::{InputEdit, Node, Tree};
 use tower_lsp::lsp_types::Url;
 
 use super::position::{SourcePosition, SourceRange};
 use super::scope::{ScopeId, ScopeKind};
-use super::symbol::{ParameterInfo, SymbolId, SymbolKind, TypeInfo, Visibility};
+use super::symbol::{
+    ApiEntryPoint, ControlArrayElement, ParameterInfo, SymbolId, SymbolKind, TypeInfo, Visibility,
+};
+use super::constants::evaluate_constants;
+use super::inference::infer_assigned_type;
 use super::symbol_table::SymbolTable;
+use super::undeclared::has_option_explicit;
+use crate::parser::{array_dimensions, convert_declare_statement, InactiveRegion};
 
 /// Builds a symbol table from a tree-sitter parse tree
 pub struct SymbolTableBuilder<'a> {
@@ -16,8 +22,16 @@ pub struct SymbolTableBuilder<'a> {
     table: SymbolTable,
     /// Stack of current scopes (innermost last)
     scope_stack: Vec<ScopeId>,
+    /// Regions excluded by #If directives (skipped entirely)
+    inactive: &'a [InactiveRegion],
+    /// ID for the next procedure symbol, when re-walking a procedure of an existing table
+    restored_procedure: Option<SymbolId>,
 }
 
+/// Top-level nodes that an incremental update can re-walk on their own
+const PROCEDURE_KINDS: &[&str] =
+    &["sub_declaration", "function_declaration", "property_declaration"];
+
 impl<'a> SymbolTableBuilder<'a> {
     /// Create a new builder
     pub fn new(uri: Url, source: &'a str) -> Self {
@@ -28,21 +42,155 @@ impl<'a> SymbolTableBuilder<'a> {
             source,
             table,
             scope_stack: vec![module_scope],
+            inactive: &[],
+            restored_procedure: None,
         }
     }
 
+    /// Skip code in regions excluded by conditional compilation
+    pub fn with_inactive_regions(mut self, inactive: &'a [InactiveRegion]) -> Self {
+        self.inactive = inactive;
+        self
+    }
+
+    /// Check if a node lies in a region excluded by conditional compilation
+    fn is_inactive(&self, node: &Node) -> bool {
+        self.inactive.iter().any(|r| r.contains_node(node))
+    }
+
     /// Build the symbol table from a parse tree
     pub fn build(mut self, tree: &Tree) -> SymbolTable {
+        self.table.set_line_count(self.source.lines().count());
+
         // First pass: collect all symbol definitions
         self.visit_node(&tree.root_node());
+        evaluate_constants(&mut self.table, tree, self.source);
 
         // Second pass: collect all references to symbols
         self.scope_stack = vec![self.table.module_scope];
         self.collect_references(&tree.root_node());
 
+        // Third pass: without Option Explicit, untyped locals are usually
+        // implicit variables, so record the type of their first assignment
+        if !has_option_explicit(&tree.root_node(), self.source) {
+            self.infer_local_types(&tree.root_node());
+        }
+
         self.table
     }
 
+    /// Update `previous` in place after `edits`, re-walking only the procedures
+    /// they touch. `old_tree` is the previous tree with the edits applied.
+    ///
+    /// Returns None when the edits need a full rebuild: they reach module-level
+    /// code or change how a procedure is declared, the file has #If regions, or
+    /// earlier updates left more removed entries in the table than live ones.
+    pub fn update(
+        mut self,
+        previous: SymbolTable,
+        edits: &[InputEdit],
+        old_tree: &Tree,
+        tree: &Tree,
+    ) -> Option<SymbolTable> {
+        if !self.inactive.is_empty() || previous.is_sparse() {
+            return None;
+        }
+
+        let old_root = old_tree.root_node();
+        let root = tree.root_node();
+        let mut old_cursor = old_root.walk();
+        let old_items: Vec<Node> = old_root.named_children(&mut old_cursor).collect();
+        let mut cursor = root.walk();
+        let items: Vec<Node> = root.named_children(&mut cursor).collect();
+        if old_items.len() != items.len() {
+            return None;
+        }
+
+        let module_scope = previous.module_scope;
+        let procedure_scopes: Vec<ScopeId> = previous
+            .get_scope(module_scope)?
+            .children
+            .iter()
+            .copied()
+            .filter(|&id| previous.get_scope(id).is_some_and(|s| s.kind == ScopeKind::Procedure))
+            .collect();
+        let changed: Vec<tree_sitter::Range> = old_tree.changed_ranges(tree).collect();
+        let map = |position| edits.iter().fold(position, map_position);
+
+        // Pair each procedure with its scope in the previous table
+        let mut procedures = procedure_scopes.iter();
+        let mut touched = Vec::new();
+        for (index, (old, new)) in old_items.iter().zip(&items).enumerate() {
+            if old.kind() != new.kind() {
+                return None;
+            }
+            let is_touched = old.has_changes()
+                || old.byte_range() != new.byte_range()
+                || changed
+                    .iter()
+                    .any(|r| r.start_byte < new.end_byte() && new.start_byte() < r.end_byte);
+
+            if PROCEDURE_KINDS.contains(&new.kind()) {
+                let scope = *procedures.next()?;
+                if is_touched {
+                    touched.push((scope, *new));
+                } else {
+                    let start = map(previous.get_scope(scope)?.range.start);
+                    if start != SourcePosition::from_ts_point(new.start_position()) {
+                        return None;
+                    }
+                }
+            } else if is_touched {
+                // A comment may be the documentation of the procedure below it
+                let documents_procedure =
+                    items.get(index + 1).is_some_and(|n| PROCEDURE_KINDS.contains(&n.kind()));
+                if new.kind() != "comment" || documents_procedure {
+                    return None;
+                }
+            }
+        }
+        if procedures.next().is_some() {
+            return None;
+        }
+
+        let mut table = previous;
+        table.set_line_count(self.source.lines().count());
+        let mut restored = Vec::new();
+        for &(scope, node) in &touched {
+            let procedure = table.get_scope(scope)?.defining_symbol?;
+            let signature = table.get_symbol(procedure)?.format_signature();
+            table.remove_scope(scope);
+            table.remove_symbol(procedure);
+            restored.push((procedure, signature, node));
+        }
+        let first_edit = edits.iter().map(|e| SourcePosition::from_ts_point(e.start_position));
+        table.shift_positions(first_edit.min()?, map);
+        self.table = table;
+
+        // Other procedures see a procedure only through its declaration line
+        for (procedure, signature, node) in &restored {
+            self.scope_stack = vec![module_scope];
+            self.restored_procedure = Some(*procedure);
+            self.visit_node(node);
+            let restored = self.table.get_symbol(*procedure)?;
+            if self.restored_procedure.is_some() || restored.format_signature() != *signature {
+                return None;
+            }
+        }
+        evaluate_constants(&mut self.table, tree, self.source);
+
+        let option_explicit = has_option_explicit(&root, self.source);
+        for (_, _, node) in &restored {
+            self.scope_stack = vec![module_scope];
+            self.collect_references(node);
+            if !option_explicit {
+                self.infer_local_types(node);
+            }
+        }
+
+        Some(self.table)
+    }
+
     /// Get the current scope
     fn current_scope(&self) -> ScopeId {
         *self.scope_stack.last().unwrap()
@@ -73,6 +221,28 @@ impl<'a> SymbolTableBuilder<'a> {
         SourceRange::from_ts_node(node)
     }
 
+    /// The comment lines directly above a declaration, without their `'` or `Rem`
+    fn leading_comment(&self, node: &Node) -> Option<String> {
+        let mut lines = Vec::new();
+        let mut row = node.start_position().row;
+        let mut previous = node.prev_named_sibling();
+        while let Some(comment) = previous
+            .filter(|n| n.kind() == "comment" && n.end_position().row + 1 == row)
+        {
+            let text = self.node_text(&comment).trim_start_matches('\'');
+            let text = match text.get(..3) {
+                Some(keyword) if keyword.eq_ignore_ascii_case("rem") => &text[3..],
+                _ => text,
+            };
+            lines.push(text.trim());
+            row = comment.start_position().row;
+            previous = comment.prev_named_sibling();
+        }
+        lines.reverse();
+        let doc = lines.join("\n").trim().to_string();
+        (!doc.is_empty()).then_some(doc)
+    }
+
     /// Extract visibility from a declaration node
     fn extract_visibility(&self, node: &Node) -> Visibility {
         let mut cursor = node.walk();
@@ -89,6 +259,22 @@ impl<'a> SymbolTableBuilder<'a> {
         Visibility::Private // Default
     }
 
+    /// Visibility of a procedure, Type, or Enum, which are Public without a modifier
+    fn extract_member_visibility(&self, node: &Node) -> Visibility {
+        if self.has_visibility_keyword(node) {
+            self.extract_visibility(node)
+        } else {
+            Visibility::Public
+        }
+    }
+
+    /// Check if a declaration starts with Public, Private, Friend, or Global
+    fn has_visibility_keyword(&self, node: &Node) -> bool {
+        ["public", "private", "friend", "global"]
+            .iter()
+            .any(|keyword| self.has_child_keyword(node, keyword))
+    }
+
     /// Find a child node by field name
     fn find_field<'b>(&self, node: &'b Node<'b>, field_name: &str) -> Option<Node<'b>> {
         node.child_by_field_name(field_name)
@@ -127,7 +313,9 @@ impl<'a> SymbolTableBuilder<'a> {
             Some(TypeInfo {
                 name: name.trim_end_matches("()").to_string(),
                 is_array,
+                dimensions: Vec::new(),
                 is_new,
+                is_inferred: false,
             })
         } else {
             None
@@ -144,6 +332,43 @@ impl<'a> SymbolTableBuilder<'a> {
         None
     }
 
+    /// Extract the type of a variable or Type member. Array bounds follow the
+    /// name, not the As clause, and make an untyped name a Variant array.
+    fn extract_declarator_type(&self, node: &Node) -> Option<TypeInfo> {
+        let type_info = self.extract_type(node);
+        let Some(bounds) = self.find_children_by_kind(node, "array_bounds").into_iter().next()
+        else {
+            return type_info;
+        };
+        let mut type_info = type_info.unwrap_or_else(|| TypeInfo::new("Variant"));
+        type_info.is_array = true;
+        type_info.dimensions = array_dimensions(&bounds, self.source);
+        Some(type_info)
+    }
+
+    /// Extract the type of a parameter; `values()` makes it an array, of Variant without As
+    fn extract_parameter_type(&self, param: &Node) -> Option<TypeInfo> {
+        let type_info = self.extract_type(param);
+        if !self.has_child_keyword(param, "(") {
+            return type_info;
+        }
+        let mut type_info = type_info.unwrap_or_else(|| TypeInfo::new("Variant"));
+        type_info.is_array = true;
+        Some(type_info)
+    }
+
+    /// The innermost procedure scope on the stack (the current scope outside procedures)
+    fn procedure_scope(&self) -> ScopeId {
+        self.scope_stack
+            .iter()
+            .rev()
+            .copied()
+            .find(|&id| {
+                self.table.get_scope(id).is_some_and(|scope| scope.kind == ScopeKind::Procedure)
+            })
+            .unwrap_or_else(|| self.current_scope())
+    }
+
     /// Check if currently in module scope
     fn is_module_scope(&self) -> bool {
         if let Some(scope) = self.table.get_scope(self.current_scope()) {
@@ -155,6 +380,10 @@ impl<'a> SymbolTableBuilder<'a> {
 
     /// Visit a node and its children
     fn visit_node(&mut self, node: &Node) {
+        if self.is_inactive(node) {
+            return;
+        }
+
         match node.kind() {
             // Form designer property lines - skip (Height, Width, Left, etc.)
             "form_property_line" | "form_property_block" |
@@ -175,6 +404,7 @@ impl<'a> SymbolTableBuilder<'a> {
             "property_declaration" => self.visit_property_declaration(node),
             "declare_statement" => self.visit_declare_statement(node),
             "event_statement" => self.visit_event_statement(node),
+            "implements_statement" => self.visit_implements_statement(node),
 
             // Scope-creating constructs
             "with_statement" => self.visit_with_statement(node),
@@ -231,7 +461,7 @@ impl<'a> SymbolTableBuilder<'a> {
                     );
 
                     // Extract type info
-                    if let Some(type_info) = self.extract_type(&vd) {
+                    if let Some(type_info) = self.extract_declarator_type(&vd) {
                         self.table.set_type_info(symbol_id, type_info);
                     }
                 }
@@ -281,7 +511,7 @@ impl<'a> SymbolTableBuilder<'a> {
 
     /// Visit type declaration (User-Defined Type)
     fn visit_type_declaration(&mut self, node: &Node) {
-        let visibility = self.extract_visibility(node);
+        let visibility = self.extract_member_visibility(node);
 
         if let Some(name_node) = self.find_field(node, "name") {
             let name = self.node_text(&name_node).to_string();
@@ -313,7 +543,7 @@ impl<'a> SymbolTableBuilder<'a> {
                         self.current_scope(),
                     );
 
-                    if let Some(type_info) = self.extract_type(&tm) {
+                    if let Some(type_info) = self.extract_declarator_type(&tm) {
                         self.table.set_type_info(member_id, type_info);
                     }
 
@@ -325,7 +555,7 @@ impl<'a> SymbolTableBuilder<'a> {
 
     /// Visit enum declaration
     fn visit_enum_declaration(&mut self, node: &Node) {
-        let visibility = self.extract_visibility(node);
+        let visibility = self.extract_member_visibility(node);
 
         if let Some(name_node) = self.find_field(node, "name") {
             let name = self.node_text(&name_node).to_string();
@@ -398,22 +628,44 @@ impl<'a> SymbolTableBuilder<'a> {
 
     /// Common procedure handling
     fn visit_procedure(&mut self, node: &Node, kind: SymbolKind) {
-        let visibility = self.extract_visibility(node);
+        let visibility = self.extract_member_visibility(node);
 
         if let Some(name_node) = self.find_field(node, "name") {
             let name = self.node_text(&name_node).to_string();
             let definition_range = self.node_range(node);
             let name_range = self.node_range(&name_node);
+            let binding = (kind == SymbolKind::Sub)
+                .then(|| self.table.control_event_binding(&name))
+                .flatten();
 
             // Create the procedure symbol
-            let symbol_id = self.table.create_symbol(
-                name,
-                kind,
-                visibility,
-                definition_range,
-                name_range,
-                self.current_scope(),
-            );
+            let symbol_id = match self.restored_procedure.take() {
+                Some(id) => self.table.restore_symbol(
+                    id,
+                    name,
+                    kind,
+                    visibility,
+                    definition_range,
+                    name_range,
+                    self.current_scope(),
+                ),
+                None => self.table.create_symbol(
+                    name,
+                    kind,
+                    visibility,
+                    definition_range,
+                    name_range,
+                    self.current_scope(),
+                ),
+            };
+
+            if let Some(binding) = binding {
+                self.table.set_event_binding(symbol_id, binding);
+            }
+
+            if let Some(doc) = self.leading_comment(node) {
+                self.table.set_documentation(symbol_id, doc);
+            }
 
             // Extract return type for functions/property get
             if matches!(kind, SymbolKind::Function | SymbolKind::PropertyGet) {
@@ -453,10 +705,12 @@ impl<'a> SymbolTableBuilder<'a> {
                     let by_ref = !param_text.contains("BYVAL");
                     let optional = param_text.contains("OPTIONAL");
 
+                    let is_param_array = self.has_child_keyword(&param, "paramarray");
+
                     let default_value = self.find_field(&param, "default")
                         .map(|v| self.node_text(&v).to_string());
 
-                    let type_info = self.extract_type(&param);
+                    let type_info = self.extract_parameter_type(&param);
 
                     let param_range = self.node_range(&param);
                     let name_range = self.node_range(&name_node);
@@ -480,6 +734,7 @@ impl<'a> SymbolTableBuilder<'a> {
                         type_info,
                         by_ref,
                         optional,
+                        is_param_array,
                         default_value,
                         range: param_range,
                         name_range,
@@ -493,7 +748,14 @@ impl<'a> SymbolTableBuilder<'a> {
 
     /// Visit Declare statement (API declaration)
     fn visit_declare_statement(&mut self, node: &Node) {
-        let visibility = self.extract_visibility(node);
+        // Without a modifier a Declare is Public, which only standard modules allow
+        let visibility = if self.has_visibility_keyword(node) {
+            self.extract_visibility(node)
+        } else if self.table.uri.path().to_lowercase().ends_with(".bas") {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        };
 
         if let Some(name_node) = self.find_field(node, "name") {
             let name = self.node_text(&name_node).to_string();
@@ -516,6 +778,17 @@ impl<'a> SymbolTableBuilder<'a> {
                 self.current_scope(),
             );
 
+            if let Some(declaration) = convert_declare_statement(node, self.source) {
+                let alias = declaration
+                    .alias
+                    .filter(|alias| !alias.eq_ignore_ascii_case(&declaration.name));
+                let entry_point = ApiEntryPoint {
+                    library: declaration.library,
+                    alias,
+                };
+                self.table.set_entry_point(symbol_id, entry_point);
+            }
+
             // Extract parameters (declares don't create a scope)
             let parameters = self.extract_parameters_no_scope(node);
             self.table.set_parameters(symbol_id, parameters);
@@ -542,16 +815,19 @@ impl<'a> SymbolTableBuilder<'a> {
                     let by_ref = !param_text.contains("BYVAL");
                     let optional = param_text.contains("OPTIONAL");
 
+                    let is_param_array = self.has_child_keyword(&param, "paramarray");
+
                     let default_value = self.find_field(&param, "default")
                         .map(|v| self.node_text(&v).to_string());
 
-                    let type_info = self.extract_type(&param);
+                    let type_info = self.extract_parameter_type(&param);
 
                     params.push(ParameterInfo {
                         name,
                         type_info,
                         by_ref,
                         optional,
+                        is_param_array,
                         default_value,
                         range: self.node_range(&param),
                         name_range: self.node_range(&name_node),
@@ -586,6 +862,14 @@ impl<'a> SymbolTableBuilder<'a> {
         }
     }
 
+    /// Visit Implements statement (records the interface, which has no symbol here)
+    fn visit_implements_statement(&mut self, node: &Node) {
+        if let Some(name_node) = node.named_child(0) {
+            let name = self.node_text(&name_node).to_string();
+            self.table.add_interface(name, self.node_range(&name_node));
+        }
+    }
+
     /// Visit With statement (creates implicit object scope)
     fn visit_with_statement(&mut self, node: &Node) {
         let range = self.node_range(node);
@@ -660,7 +944,7 @@ impl<'a> SymbolTableBuilder<'a> {
         self.pop_scope();
     }
 
-    /// Visit Label
+    /// Visit Label (labels belong to the procedure, even inside a With or For block)
     fn visit_label(&mut self, node: &Node) {
         // Labels are the first child (identifier or integer)
         if let Some(label_node) = node.child(0) {
@@ -674,66 +958,65 @@ impl<'a> SymbolTableBuilder<'a> {
                 Visibility::Private,
                 range,
                 name_range,
-                self.current_scope(),
+                self.procedure_scope(),
             );
         }
     }
 
     /// Visit form block (creates FormControl symbol for controls like TextBox, Label, etc.)
     fn visit_form_block(&mut self, node: &Node) {
+        self.visit_control_block(node, None);
+    }
+
+    /// Visit a control's Begin ... End block and the controls nested inside it
+    fn visit_control_block(&mut self, node: &Node, parent: Option<SymbolId>) {
         // form_block has: Begin <type> <name> ... End
         // type field: "VB.TextBox", "VB.Label", etc.
         // name field: control name like "txtAmountWithdrawn"
-
-        // Debug: log what we're seeing
         tracing::debug!("visit_form_block: node kind={}", node.kind());
 
+        let mut symbol_id = None;
+
         if let Some(name_node) = self.find_field(node, "name") {
             let name = self.node_text(&name_node).to_string();
-            let definition_range = self.node_range(node);
             let name_range = self.node_range(&name_node);
-
-            tracing::debug!("Creating FormControl symbol: {}", name);
-
-            // Get the control type (e.g., "VB.TextBox" -> "TextBox")
-            let type_info = self.find_field(node, "type").map(|type_node| {
-                let full_type = self.node_text(&type_node).to_string();
-                // Extract just the control type (after the dot)
-                let type_name = full_type.split('.').last().unwrap_or(&full_type).to_string();
-                TypeInfo {
-                    name: type_name,
-                    is_array: false,
-                    is_new: false,
-                }
-            });
-
-            let symbol_id = self.table.create_symbol(
-                name,
-                SymbolKind::FormControl,
-                Visibility::Private, // Controls are private to the form
-                definition_range,
-                name_range,
-                self.current_scope(),
+            let index = self.control_index(node);
+
+            // Elements of a control array share one symbol
+            let existing = index.and(
+                self.table
+                    .lookup_symbol(&name, self.current_scope())
+                    .filter(|s| s.kind == SymbolKind::FormControl && !s.control_array.is_empty())
+                    .map(|s| s.id),
             );
 
-            if let Some(ti) = type_info {
-                self.table.set_type_info(symbol_id, ti);
+            let id = match existing {
+                Some(id) => id,
+                None => self.create_control_symbol(node, name, name_range, parent),
+            };
+
+            if let Some(index) = index {
+                self.table
+                    .add_control_array_element(id, ControlArrayElement { index, name_range });
             }
+
+            symbol_id = Some(id);
         }
 
         // Recurse into children to find nested form_block elements (nested controls)
         // Controls are nested inside form_element nodes:
         // form_block -> form_element -> form_block (nested control)
+        let container = symbol_id.or(parent);
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
             match child.kind() {
-                "form_block" => self.visit_form_block(&child),
+                "form_block" => self.visit_control_block(&child, container),
                 "form_element" => {
                     // form_element can contain form_block (nested controls)
                     let mut inner_cursor = child.walk();
                     for inner_child in child.children(&mut inner_cursor) {
                         if inner_child.kind() == "form_block" {
-                            self.visit_form_block(&inner_child);
+                            self.visit_control_block(&inner_child, container);
                         }
                     }
                 }
@@ -742,12 +1025,78 @@ impl<'a> SymbolTableBuilder<'a> {
         }
     }
 
+    /// Create the FormControl symbol for a control and attach it to its container
+    fn create_control_symbol(
+        &mut self,
+        node: &Node,
+        name: String,
+        name_range: SourceRange,
+        parent: Option<SymbolId>,
+    ) -> SymbolId {
+        tracing::debug!("Creating FormControl symbol: {}", name);
+
+        // Get the control type (e.g., "VB.TextBox" -> "TextBox")
+        let type_info = self.find_field(node, "type").map(|type_node| {
+            let full_type = self.node_text(&type_node).to_string();
+            // Extract just the control type (after the dot)
+            let type_name = full_type.rsplit('.').next().unwrap_or(&full_type).to_string();
+            TypeInfo::new(type_name)
+        });
+
+        let path = match parent
+            .and_then(|id| self.table.get_symbol(id))
+            .and_then(|p| p.control_path.clone())
+        {
+            Some(parent_path) => format!("{}.{}", parent_path, name),
+            None => name.clone(),
+        };
+
+        let symbol_id = self.table.create_symbol(
+            name,
+            SymbolKind::FormControl,
+            Visibility::Private, // Controls are private to the form
+            self.node_range(node),
+            name_range,
+            self.current_scope(),
+        );
+
+        if let Some(ti) = type_info {
+            self.table.set_type_info(symbol_id, ti);
+        }
+        self.table.set_control_path(symbol_id, path);
+        if let Some(parent) = parent {
+            self.table.add_member(parent, symbol_id);
+        }
+
+        symbol_id
+    }
+
+    /// Read the `Index = n` property of a control block (set for control array elements)
+    fn control_index(&self, node: &Node) -> Option<i32> {
+        let mut cursor = node.walk();
+        let elements: Vec<Node> = node.children(&mut cursor).collect();
+
+        elements.iter().find_map(|element| {
+            let line = element.named_child(0).filter(|n| n.kind() == "form_property_line")?;
+            let name = line.named_child(0)?;
+            if !self.node_text(&name).eq_ignore_ascii_case("Index") {
+                return None;
+            }
+            let value = line.named_child(1)?;
+            self.node_text(&value).trim().parse().ok()
+        })
+    }
+
     // ==========================================
     // Second Pass: Reference Collection
     // ==========================================
 
     /// Collect references by walking all identifier nodes
     fn collect_references(&mut self, node: &Node) {
+        if self.is_inactive(node) {
+            return;
+        }
+
         match node.kind() {
             // Skip nodes that contain declarations (names are definitions, not references)
             "form_property_line" | "form_property_block" |
@@ -773,11 +1122,30 @@ impl<'a> SymbolTableBuilder<'a> {
                 return;
             }
 
+            // Jump targets name a label of the procedure, never another symbol
+            "identifier" | "integer_literal" if self.is_jump_target(node) => {
+                self.add_label_reference(node);
+            }
+
             // Identifiers - check if this is a reference (not a declaration)
             "identifier" => {
                 self.try_add_reference(node);
             }
 
+            // Type names (As Class1, New Class1, Implements IShape)
+            "dotted_name" => {
+                let names_type = node.parent().is_some_and(|parent| {
+                    matches!(
+                        parent.kind(),
+                        "as_clause" | "new_expression" | "implements_statement"
+                    )
+                });
+                if names_type {
+                    let name = self.node_text(node).to_string();
+                    self.table.add_type_reference(name, self.node_range(node));
+                }
+            }
+
             // Default: recurse into children
             _ => {}
         }
@@ -791,13 +1159,10 @@ impl<'a> SymbolTableBuilder<'a> {
 
     /// Collect references within a procedure (Sub/Function/Property)
     fn collect_references_in_procedure(&mut self, node: &Node) {
-        // Find the procedure scope - look for the procedure by name
-        if let Some(name_node) = self.find_field(node, "name") {
-            let name = self.node_text(&name_node).to_string();
-            // Find the scope for this procedure
-            if let Some(scope_id) = self.find_procedure_scope(&name) {
-                self.scope_stack.push(scope_id);
-            }
+        // Find the procedure scope by its range: a Property Get and Let share a name
+        let range = self.node_range(node);
+        if let Some(scope_id) = self.find_scope_at_range(&range, ScopeKind::Procedure) {
+            self.scope_stack.push(scope_id);
         }
 
         // Recurse into children (skipping the parameter list which contains declarations)
@@ -863,23 +1228,6 @@ impl<'a> SymbolTableBuilder<'a> {
         }
     }
 
-    /// Find the scope for a procedure by looking up the symbol and then finding its scope
-    fn find_procedure_scope(&self, name: &str) -> Option<ScopeId> {
-        // Find the symbol for this procedure
-        let name_lower = name.to_lowercase();
-        for symbol in self.table.all_symbols() {
-            if symbol.name.to_lowercase() == name_lower && symbol.kind.creates_scope() {
-                // Find the scope that has this symbol as its defining symbol
-                for scope in self.table.all_scopes() {
-                    if scope.defining_symbol == Some(symbol.id) {
-                        return Some(scope.id);
-                    }
-                }
-            }
-        }
-        None
-    }
-
     /// Find a scope at a specific range with a specific kind
     fn find_scope_at_range(&self, range: &SourceRange, kind: ScopeKind) -> Option<ScopeId> {
         for scope in self.table.all_scopes() {
@@ -899,6 +1247,16 @@ impl<'a> SymbolTableBuilder<'a> {
 
         let name = self.node_text(node).to_string();
         let range = self.node_range(node);
+
+        // Members of other objects don't refer to symbols in this scope, but
+        // those of an undeclared name may be another module's
+        if self.is_foreign_member(node) {
+            if let Some(object) = self.unresolved_object(node) {
+                self.table.add_member_use(object, name, range);
+            }
+            return;
+        }
+
         let scope_id = self.current_scope();
 
         // Check if this is an assignment target
@@ -908,6 +1266,78 @@ impl<'a> SymbolTableBuilder<'a> {
         if let Some(symbol) = self.table.lookup_symbol(&name, scope_id) {
             let symbol_id = symbol.id;
             self.table.add_reference(symbol_id, range, scope_id, is_assignment);
+        } else if self.is_value_use(node) && !self.is_redim_target(node) {
+            self.table.add_unresolved_reference(name, range);
+        }
+    }
+
+    /// Whether an identifier is the array a `ReDim` resizes; an undeclared one
+    /// is reported by the array checks instead
+    fn is_redim_target(&self, node: &Node) -> bool {
+        node.parent().is_some_and(|parent| {
+            parent.kind() == "redim_variable"
+                && self.find_field(&parent, "name").map(|n| n.id()) == Some(node.id())
+        })
+    }
+
+    /// Whether a node is the label a `GoTo`, `GoSub`, `Resume`, `On Error GoTo`,
+    /// or `On ... GoTo` jumps to (`0` in `On Error GoTo 0` and `Resume 0` is not)
+    fn is_jump_target(&self, node: &Node) -> bool {
+        let Some(parent) = node.parent() else {
+            return false;
+        };
+        let is_target = match parent.kind() {
+            "goto_statement" | "gosub_statement" | "on_error_statement" | "resume_statement" => {
+                true
+            }
+            // Not the selector expression
+            "on_goto_statement" | "on_gosub_statement" => {
+                parent.named_child(0).map(|c| c.id()) != Some(node.id())
+            }
+            _ => false,
+        };
+        is_target && self.node_text(node) != "0"
+    }
+
+    /// Resolve a jump target to a label of the enclosing procedure
+    fn add_label_reference(&mut self, node: &Node) {
+        let name = self.node_text(node).to_string();
+        let range = self.node_range(node);
+        let scope_id = self.procedure_scope();
+
+        let label = self
+            .table
+            .get_scope(scope_id)
+            .and_then(|scope| scope.lookup_local(&name))
+            .filter(|&id| self.table.get_symbol(id).is_some_and(|s| s.kind == SymbolKind::Label));
+        match label {
+            Some(id) => self.table.add_reference(id, range, self.current_scope(), false),
+            None => self.table.add_undefined_label(name, range),
+        }
+    }
+
+    /// Check if an identifier is used as a variable, constant, or procedure
+    /// (as opposed to a type name, jump target, named argument, or directive)
+    fn is_value_use(&self, node: &Node) -> bool {
+        // Named argument (e.g., `Prompt` in `MsgBox Prompt:="Hi"`)
+        if node.next_sibling().is_some_and(|n| n.kind() == ":=") {
+            return false;
+        }
+
+        let parent = match node.parent() {
+            Some(p) => p,
+            None => return false,
+        };
+
+        match parent.kind() {
+            "dotted_name" | "label" | "goto_statement" | "gosub_statement" | "on_error_statement"
+            | "resume_statement" | "option_statement" | "attribute_statement"
+            | "letter_range" => false,
+            // Only the selector expression, not the jump targets
+            "on_goto_statement" | "on_gosub_statement" => {
+                parent.named_child(0).map(|c| c.id()) == Some(node.id())
+            }
+            kind => !kind.starts_with("preproc_"),
         }
     }
 
@@ -939,6 +1369,54 @@ impl<'a> SymbolTableBuilder<'a> {
         false
     }
 
+    /// Check if an identifier is the member of an access on another object
+    /// (e.g., `Text` in `txtName.Text`, `.Text` in a With block, or `rs!Name`)
+    fn is_foreign_member(&self, node: &Node) -> bool {
+        let parent = match node.parent() {
+            Some(p) => p,
+            None => return false,
+        };
+
+        match parent.kind() {
+            "with_member_expression" => true,
+            "dictionary_access" => {
+                parent.child_by_field_name("key").map(|k| k.id()) == Some(node.id())
+            }
+            "member_expression" => {
+                if parent.child_by_field_name("member").map(|m| m.id()) != Some(node.id()) {
+                    return false;
+                }
+                let object = match parent.child_by_field_name("object") {
+                    Some(o) => o,
+                    None => return true,
+                };
+                let object_text = self.node_text(&object);
+
+                // Me.Member refers to this module
+                if object_text.eq_ignore_ascii_case("me") {
+                    return false;
+                }
+
+                // Qualified enum member (e.g., Colors.Red)
+                !matches!(
+                    self.table.lookup_symbol(object_text, self.current_scope()),
+                    Some(symbol) if symbol.kind == SymbolKind::Enum
+                )
+            }
+            _ => false,
+        }
+    }
+
+    /// The object of a member access, when it is a plain name that resolves to
+    /// no symbol in the document
+    fn unresolved_object(&self, node: &Node) -> Option<String> {
+        let parent = node.parent().filter(|parent| parent.kind() == "member_expression")?;
+        let object = parent.child_by_field_name("object").filter(|o| o.kind() == "identifier")?;
+        let name = self.node_text(&object);
+        let scope_id = self.current_scope();
+        self.table.lookup_symbol(name, scope_id).is_none().then(|| name.to_string())
+    }
+
     /// Check if an identifier is an assignment target (left side of assignment)
     fn is_assignment_target(&self, node: &Node) -> bool {
         if let Some(parent) = node.parent() {
@@ -957,6 +1435,44 @@ impl<'a> SymbolTableBuilder<'a> {
         false
     }
 
+    /// Give untyped local variables the type of the first value assigned to them
+    fn infer_local_types(&mut self, node: &Node) {
+        if self.is_inactive(node) {
+            return;
+        }
+
+        if matches!(node.kind(), "assignment_statement" | "set_statement") {
+            let target = self
+                .find_field(node, "target")
+                .filter(|target| target.kind() == "identifier");
+            if let Some(target) = target {
+                let position = SourcePosition::from_ts_point(target.start_position());
+                let untyped_local = self
+                    .table
+                    .reference_at_position(position)
+                    .and_then(|reference| self.table.get_symbol(reference.symbol_id))
+                    .filter(|symbol| {
+                        symbol.kind == SymbolKind::LocalVariable && symbol.type_info.is_none()
+                    })
+                    .map(|symbol| symbol.id);
+
+                if let Some(symbol_id) = untyped_local {
+                    let scope = self.table.scope_at_position(position);
+                    if let Some(type_info) =
+                        infer_assigned_type(&self.table, scope, node, self.source)
+                    {
+                        self.table.set_type_info(symbol_id, type_info.inferred());
+                    }
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.infer_local_types(&child);
+        }
+    }
+
     /// Check if node is a descendant of ancestor
     fn is_descendant_of(&self, node: &Node, ancestor: &Node) -> bool {
         let mut current = node.parent();
@@ -976,6 +1492,55 @@ pub fn build_symbol_table(uri: Url, source: &str, tree: &Tree) -> SymbolTable {
     builder.build(tree)
 }
 
+/// Build a symbol table, skipping regions excluded by conditional compilation
+pub fn build_symbol_table_excluding(
+    uri: Url,
+    source: &str,
+    tree: &Tree,
+    inactive: &[InactiveRegion],
+) -> SymbolTable {
+    let builder = SymbolTableBuilder::new(uri, source).with_inactive_regions(inactive);
+    builder.build(tree)
+}
+
+/// Update the symbol table of a document after edits, re-walking only the
+/// procedures they touch and rebuilding it in full when that isn't enough.
+///
+/// `old_tree` is the tree `previous` was built from, with `edits` applied to it
+/// in order (as passed to the parser for the new `tree`).
+pub fn update_symbol_table(
+    previous: SymbolTable,
+    edits: &[InputEdit],
+    old_tree: &Tree,
+    source: &str,
+    tree: &Tree,
+    inactive: &[InactiveRegion],
+) -> SymbolTable {
+    let uri = previous.uri.clone();
+    SymbolTableBuilder::new(uri.clone(), source)
+        .with_inactive_regions(inactive)
+        .update(previous, edits, old_tree, tree)
+        .unwrap_or_else(|| build_symbol_table_excluding(uri, source, tree, inactive))
+}
+
+/// Where a position ends up after an edit (positions inside the replaced text
+/// move to its start)
+fn map_position(position: SourcePosition, edit: &InputEdit) -> SourcePosition {
+    let start = SourcePosition::from_ts_point(edit.start_position);
+    let old_end = SourcePosition::from_ts_point(edit.old_end_position);
+    let new_end = SourcePosition::from_ts_point(edit.new_end_position);
+
+    if position < start {
+        position
+    } else if position < old_end {
+        start
+    } else if position.line == old_end.line {
+        SourcePosition::new(new_end.line, new_end.column + position.column - old_end.column)
+    } else {
+        SourcePosition::new(position.line - old_end.line + new_end.line, position.column)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -998,6 +1563,20 @@ mod tests {
         assert_eq!(symbols[0].kind, SymbolKind::Variable);
     }
 
+    #[test]
+    fn test_array_dimensions() {
+        let source = "Dim a(1 To 10) As Long\nDim b(5, MAX - 1)\nDim c() As String\n";
+        let table = parse_and_build(source);
+        let signature = |name: &str| {
+            let symbol = table.all_symbols().find(|s| s.name == name).unwrap();
+            (symbol.format_signature(), symbol.type_info.as_ref().unwrap().is_fixed_array())
+        };
+
+        assert_eq!(signature("a"), ("Private a(1 To 10) As Long".to_string(), true));
+        assert_eq!(signature("b"), ("Private b(5, MAX - 1) As Variant".to_string(), true));
+        assert_eq!(signature("c"), ("Private c() As String".to_string(), false));
+    }
+
     #[test]
     fn test_sub_declaration() {
         let source = r#"
@@ -1047,6 +1626,31 @@ End Enum
         assert_eq!(enums[0].members.len(), 3);
     }
 
+    #[test]
+    fn test_procedure_documentation() {
+        let source = "' Not about Add\n\nRem Add two numbers\n'   and return the sum\n\
+Function Add(a As Integer, b As Integer) As Integer\nEnd Function\nSub Main()\nEnd Sub\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let mut old_tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(uri, source, &old_tree);
+        let documentation = |table: &SymbolTable, name: &str| {
+            table.all_symbols().find(|s| s.name == name).unwrap().documentation.clone()
+        };
+
+        let doc = documentation(&table, "Add");
+        assert_eq!(doc.as_deref(), Some("Add two numbers\nand return the sum"));
+        assert_eq!(documentation(&table, "Main"), None);
+
+        // Editing the comment updates the procedure below it
+        let (edited, edit) = edit_source(source, "return the sum", "return their sum");
+        old_tree.edit(&edit);
+        let tree = parser.parse(&edited, Some(&old_tree)).unwrap();
+        let updated = update_symbol_table(table, &[edit], &old_tree, &edited, &tree, &[]);
+        let doc = documentation(&updated, "Add");
+        assert_eq!(doc.as_deref(), Some("Add two numbers\nand return their sum"));
+    }
+
     #[test]
     fn test_scope_hierarchy() {
         let source = r#"
@@ -1066,4 +1670,166 @@ End Sub
         let local_var = table.lookup_symbol("localVar", table.module_scope);
         assert!(local_var.is_none());
     }
+
+    #[test]
+    fn test_member_access_references() {
+        let source = r#"
+Dim Text As String
+
+Sub Test()
+    txtName.Text = "a"
+    Me.Text = "b"
+    Text = "c"
+End Sub
+"#;
+        let table = parse_and_build(source);
+
+        let text = table.lookup_symbol("Text", table.module_scope).unwrap();
+        let refs = table.get_references(text.id);
+
+        // Me.Text and Text refer to the module variable; txtName.Text does not
+        assert_eq!(refs.len(), 2);
+        assert!(refs.iter().all(|r| r.range.start.line != 4));
+    }
+
+    #[test]
+    fn test_infers_untyped_locals_without_option_explicit() {
+        let source = r#"
+Sub Test()
+    Dim count
+    Dim items
+    Dim name As String
+    count = 40000
+    count = "later"
+    Set items = New Collection
+    name = 1
+End Sub
+"#;
+        let table = parse_and_build(source);
+        let type_of = |name: &str| {
+            table
+                .all_symbols()
+                .find(|s| s.name == name)
+                .and_then(|s| s.type_info.clone())
+        };
+
+        let count = type_of("count").unwrap();
+        assert_eq!((count.name.as_str(), count.is_inferred), ("Long", true));
+        assert_eq!(count.display(), "Variant ' inferred Long");
+        assert!(type_of("items").unwrap().is_new);
+        assert!(!type_of("name").unwrap().is_inferred);
+
+        let explicit = parse_and_build(&format!("Option Explicit\n{}", source));
+        let count = explicit.all_symbols().find(|s| s.name == "count").unwrap();
+        assert!(count.type_info.is_none());
+    }
+
+    /// Replace `old` with `new` in `source`, returning the edited source and the edit
+    fn edit_source(source: &str, old: &str, new: &str) -> (String, InputEdit) {
+        let point = |text: &str, byte: usize| tree_sitter::Point {
+            row: text[..byte].matches('\n').count(),
+            column: byte - text[..byte].rfind('\n').map_or(0, |i| i + 1),
+        };
+        let start_byte = source.find(old).unwrap();
+        let edited = source.replacen(old, new, 1);
+        let edit = InputEdit {
+            start_byte,
+            old_end_byte: start_byte + old.len(),
+            new_end_byte: start_byte + new.len(),
+            start_position: point(source, start_byte),
+            old_end_position: point(source, start_byte + old.len()),
+            new_end_position: point(&edited, start_byte + new.len()),
+        };
+        (edited, edit)
+    }
+
+    /// Symbols, references, and unresolved names, in position order
+    fn describe(table: &SymbolTable) -> Vec<String> {
+        let mut lines: Vec<String> = table
+            .all_symbols()
+            .map(|s| {
+                let scope = table.get_scope(s.scope_id).map(|scope| (scope.kind, scope.range));
+                let range = s.definition_range;
+                format!("{:?} {} {:?} {:?} {:?}", range, s.name, s.kind, s.type_info, scope)
+            })
+            .collect();
+        lines.extend(table.all_references().iter().map(|r| {
+            let name = &table.get_symbol(r.symbol_id).unwrap().name;
+            format!("{:?} -> {} {}", r.range, name, r.is_assignment)
+        }));
+        let unresolved = table.unresolved_references().iter();
+        lines.extend(unresolved.map(|r| format!("{:?} ? {}", r.range, r.name)));
+        lines.sort();
+        lines
+    }
+
+    #[test]
+    fn test_update_rewalks_edited_procedures() {
+        let source = "Private total As Long\n\nSub First()\n    Dim a As Long\n    a = total\nEnd Sub\n\n\
+Function Second() As Long\n    Dim b As Long\n    Second = b + total\n    First\nEnd Function\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let mut old_tree = parser.parse(source, None).unwrap();
+        let previous = build_symbol_table(uri.clone(), source, &old_tree);
+        let id_of = |table: &SymbolTable, name: &str| {
+            table.all_symbols().find(|s| s.name == name).map(|s| s.id)
+        };
+
+        // Add lines to the body of First: Second moves down but keeps its IDs
+        let added = "a = total\n    Dim extra\n    extra = a\n";
+        let (edited, edit) = edit_source(source, "a = total\n", added);
+        old_tree.edit(&edit);
+        let tree = parser.parse(&edited, Some(&old_tree)).unwrap();
+        let updated =
+            update_symbol_table(previous.clone(), &[edit], &old_tree, &edited, &tree, &[]);
+
+        assert_eq!(describe(&updated), describe(&build_symbol_table(uri.clone(), &edited, &tree)));
+        for name in ["total", "First", "Second", "b"] {
+            assert_eq!(id_of(&updated, name), id_of(&previous, name), "{}", name);
+        }
+        let extra = updated.lookup_at_position("extra", SourcePosition::new(6, 4)).unwrap();
+        assert_eq!(extra.kind, SymbolKind::LocalVariable);
+        let second = updated.symbol_at_position(SourcePosition::new(9, 10)).unwrap();
+        assert_eq!(second.name, "Second");
+
+        // A module-level change is seen by every procedure, so everything is rebuilt
+        let mut old_tree = tree;
+        let (retyped, edit) = edit_source(&edited, "total As Long", "total As String");
+        old_tree.edit(&edit);
+        let tree = parser.parse(&retyped, Some(&old_tree)).unwrap();
+        let rebuilt =
+            update_symbol_table(updated.clone(), &[edit], &old_tree, &retyped, &tree, &[]);
+        assert_eq!(describe(&rebuilt), describe(&build_symbol_table(uri, &retyped, &tree)));
+        assert_eq!(rebuilt.symbol_count(), updated.symbol_count());
+    }
+
+    #[test]
+    fn test_repeated_updates_stay_compact() {
+        let source = "Private total As Long\n\nSub First()\n    Dim a As Long\n    a = total\nEnd Sub\n\n\
+Function Second() As Long\n    Second = total\nEnd Function\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let mut tree = parser.parse(source, None).unwrap();
+        let mut table = build_symbol_table(uri.clone(), source, &tree);
+        let mut current = source.to_string();
+
+        // Every edit of First removes its local; the freed IDs must not pile up
+        for round in 0..30 {
+            let (old, new) = if round % 2 == 0 {
+                ("a = total\n", "a = total + 1\n")
+            } else {
+                ("a = total + 1\n", "a = total\n")
+            };
+            let (edited, edit) = edit_source(&current, old, new);
+            let mut old_tree = tree;
+            old_tree.edit(&edit);
+            tree = parser.parse(&edited, Some(&old_tree)).unwrap();
+            table = update_symbol_table(table, &[edit], &old_tree, &edited, &tree, &[]);
+            current = edited;
+
+            let highest = table.all_symbols().map(|s| s.id.0 as usize).max().unwrap();
+            assert!(highest < 3 * table.symbol_count(), "round {}: {}", round, highest);
+        }
+        assert_eq!(describe(&table), describe(&build_symbol_table(uri, &current, &tree)));
+    }
 }
@@ -0,0 +1,223 @@
+//! ByRef Argument Checking
+//!
+//! Flags arguments whose type differs from the explicitly typed `ByRef`
+//! parameter they are passed to. VB6 refuses a mismatched variable at
+//! compile time ("ByRef argument type mismatch") and a Variant silently
+//! changes behavior, so both are worth catching in the editor.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::arguments::{call_arguments, find_calls};
+use super::position::{SourcePosition, SourceRange};
+use super::symbol::{ParameterInfo, Symbol, SymbolKind};
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for an argument that does not match its ByRef parameter
+pub const BYREF_MISMATCH: &str = "byref-type-mismatch";
+
+/// Types whose values VB6 can compare for a ByRef match
+const INTRINSIC_TYPES: &[&str] = &[
+    "boolean", "byte", "currency", "date", "double", "integer", "long", "single", "string",
+    "variant",
+];
+
+/// Check the arguments of every call to a procedure declared in the document
+pub fn find_byref_mismatches(table: &SymbolTable, tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (callee, arguments) in find_calls(&tree.root_node(), source) {
+        check_call(&callee, &arguments, table, source, &mut diagnostics);
+    }
+    diagnostics
+}
+
+fn check_call(
+    callee: &Node,
+    arguments: &Node,
+    table: &SymbolTable,
+    source: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if callee.kind() != "identifier" {
+        return;
+    }
+    let position = SourcePosition::from_ts_point(callee.start_position());
+    let Some(procedure) = table
+        .lookup_at_position(text(callee, source), position)
+        .filter(|symbol| symbol.kind.is_procedure())
+    else {
+        return;
+    };
+
+    for (parameter, argument) in match_arguments(procedure, arguments, source) {
+        if !parameter.by_ref {
+            continue;
+        }
+        let Some(expected) = parameter.type_info.as_ref().filter(|t| !t.is_array) else {
+            continue;
+        };
+        let expected = expected.name.to_lowercase();
+        if expected == "variant" || !INTRINSIC_TYPES.contains(&expected.as_str()) {
+            continue;
+        }
+
+        let Some(actual) = argument_type(&argument, table, source) else {
+            continue;
+        };
+        if compatible(&actual, &expected, &argument, source) {
+            continue;
+        }
+
+        diagnostics.push(Diagnostic {
+            range: SourceRange::from_ts_node(&argument).to_lsp(),
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(BYREF_MISMATCH.to_string())),
+            message: format!(
+                "ByRef argument type mismatch: '{}' is {} but parameter '{}' is ByRef As {}",
+                text(&argument, source),
+                display_type(&actual),
+                parameter.name,
+                parameter.type_info.as_ref().map(|t| t.name.as_str()).unwrap_or(""),
+            ),
+            source: Some("vb6-lsp".to_string()),
+            ..Default::default()
+        });
+    }
+}
+
+/// Pair each argument with its parameter, by position or by `name:=` syntax
+fn match_arguments<'a, 't>(
+    procedure: &'a Symbol,
+    arguments: &Node<'t>,
+    source: &str,
+) -> Vec<(&'a ParameterInfo, Node<'t>)> {
+    call_arguments(arguments)
+        .into_iter()
+        .filter_map(|argument| {
+            let parameter = match argument.name {
+                Some(name) => procedure
+                    .parameters
+                    .iter()
+                    .find(|p| p.name.eq_ignore_ascii_case(text(&name, source))),
+                None => procedure.parameters.get(argument.index),
+            };
+            parameter.map(|parameter| (parameter, argument.value))
+        })
+        .collect()
+}
+
+/// Infer the type of a plain variable or literal argument (lowercase).
+///
+/// Anything else (expressions, parenthesized arguments that VB passes by
+/// value, undeclared names, objects) is not checked.
+fn argument_type(argument: &Node, table: &SymbolTable, source: &str) -> Option<String> {
+    match argument.kind() {
+        "identifier" => {
+            let position = SourcePosition::from_ts_point(argument.start_position());
+            let symbol = table.lookup_at_position(text(argument, source), position)?;
+            if !matches!(
+                symbol.kind,
+                SymbolKind::Variable
+                    | SymbolKind::LocalVariable
+                    | SymbolKind::Parameter
+                    | SymbolKind::ForLoopVariable
+            ) {
+                return None;
+            }
+            match &symbol.type_info {
+                Some(type_info) if type_info.is_array => None,
+                // An inferred type is only what the Variant currently holds
+                Some(type_info) if type_info.is_inferred => Some("variant".to_string()),
+                Some(type_info) => Some(type_info.name.to_lowercase()),
+                // Untyped declarations are Variants
+                None => Some("variant".to_string()),
+            }
+        }
+        "literal" => match argument.named_child(0)?.kind() {
+            "string_literal" => Some("string".to_string()),
+            "integer_literal" | "float_literal" => Some("number".to_string()),
+            "boolean_literal" => Some("boolean".to_string()),
+            "date_literal" => Some("date".to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+    .filter(|t| t == "number" || INTRINSIC_TYPES.contains(&t.as_str()))
+}
+
+/// Whether an argument of type `actual` can be passed to a ByRef `expected`.
+///
+/// Variables must match exactly; VB copies literals into a temporary, so
+/// they only need to convert (`"abc"` cannot become a Long).
+fn compatible(actual: &str, expected: &str, argument: &Node, source: &str) -> bool {
+    if argument.kind() != "literal" {
+        return actual == expected;
+    }
+    if actual != "string" || matches!(expected, "string" | "date") {
+        return true;
+    }
+    let contents = text(argument, source).trim_matches('"').trim();
+    contents.parse::<f64>().is_ok()
+}
+
+/// Display name of an inferred type (e.g., "Long")
+fn display_type(type_name: &str) -> String {
+    if type_name == "number" {
+        return "a numeric literal".to_string();
+    }
+    let mut chars = type_name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    fn mismatches(source: &str) -> Vec<(u32, String)> {
+        run_pass(source, |table, tree| find_byref_mismatches(table, tree, source))
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_variable_type_mismatch() {
+        let source = "Sub Inc(ByRef x As Long)\n    x = x + 1\nEnd Sub\n\
+Sub Main()\n    Dim s As String\n    Dim n As Long\n    Dim v\n    Inc s\n    Inc n\n    Call Inc(v)\nEnd Sub\n";
+        let found = mismatches(source);
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, 7);
+        assert_eq!(
+            found[0].1,
+            "ByRef argument type mismatch: 's' is String but parameter 'x' is ByRef As Long"
+        );
+        assert_eq!(found[1].0, 9);
+        assert!(found[1].1.contains("'v' is Variant"));
+    }
+
+    #[test]
+    fn test_skips_byval_variant_and_parenthesized() {
+        let source = "Sub Work(ByVal a As Long, b As Variant, c)\nEnd Sub\n\
+Sub Inc(x As Long)\nEnd Sub\n\
+Sub Main()\n    Dim s As String\n    Work s, s, s\n    Inc (s)\n    Inc 5\nEnd Sub\n";
+        assert!(mismatches(source).is_empty());
+    }
+
+    #[test]
+    fn test_named_arguments_and_literals() {
+        let source = "Function Parse(text As String, ByRef value As Integer) As Boolean\nEnd Function\n\
+Sub Main()\n    Dim n As Long\n    ok = Parse(value:=n, text:=\"1\")\n    Parse \"x\", \"abc\"\nEnd Sub\n";
+        let lines: Vec<u32> = mismatches(source).into_iter().map(|(line, _)| line).collect();
+
+        assert_eq!(lines, vec![4, 5]);
+    }
+}
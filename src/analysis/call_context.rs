@@ -0,0 +1,312 @@
+//! Call Context Detection
+//!
+//! Finds the procedure call enclosing a cursor position by scanning the
+//! statement text, used for signature help.
+
+use tower_lsp::lsp_types::Position;
+
+/// The call enclosing a cursor position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallContext {
+    /// Qualifier before the callee (e.g., "txtName" in "txtName.Move")
+    pub qualifier: Option<String>,
+    /// Name of the called procedure or method
+    pub name: String,
+    /// Zero-based index of the argument the cursor is in
+    pub active_parameter: u32,
+    /// Name before `:=` when the cursor is in a named argument
+    pub named_argument: Option<String>,
+}
+
+/// An open parenthesis seen while scanning
+struct OpenParen {
+    /// Callee text before the parenthesis (empty for grouping parens)
+    callee: String,
+    /// Number of top-level commas seen inside the parenthesis
+    commas: u32,
+    /// Name of the current argument, if passed by name
+    named: Option<String>,
+}
+
+/// Keywords that start a statement but are not implicit calls
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "call", "dim", "private", "public", "friend", "global", "static", "const", "set", "let",
+    "if", "elseif", "else", "for", "next", "do", "loop", "while", "wend", "with", "select",
+    "case", "exit", "end", "return", "goto", "gosub", "on", "redim", "erase", "sub",
+    "function", "property", "type", "enum", "declare", "event", "option", "attribute",
+    "resume", "open", "close", "print", "get", "put", "raiseevent",
+];
+
+/// Find the call enclosing the cursor position.
+///
+/// Handles nested calls, string literals containing commas, line continuations,
+/// and VB6 statement calls without parentheses (e.g., `MsgBox "Hi", vbOKOnly`).
+pub fn find_call_context(source: &str, position: Position) -> Option<CallContext> {
+    let text = statement_before_cursor(source, position)?;
+
+    let mut stack: Vec<OpenParen> = Vec::new();
+    let mut top_level_commas = 0u32;
+    let mut top_level_named: Option<String> = None;
+    let mut statement_start = 0usize;
+    let mut in_string = false;
+
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            // Cursor is inside a comment
+            '\'' => return None,
+            '(' => {
+                let callee = callee_before(&chars[statement_start..i]);
+                stack.push(OpenParen {
+                    callee,
+                    commas: 0,
+                    named: None,
+                });
+            }
+            ')' => {
+                stack.pop();
+            }
+            ',' => match stack.last_mut() {
+                Some(open) => {
+                    open.commas += 1;
+                    open.named = None;
+                }
+                None => {
+                    top_level_commas += 1;
+                    top_level_named = None;
+                }
+            },
+            // Named argument (e.g., `Width:=10`)
+            ':' if chars.get(i + 1) == Some(&'=') => {
+                let name = name_before(&chars[statement_start..i]);
+                match stack.last_mut() {
+                    Some(open) => open.named = name,
+                    None => top_level_named = name,
+                }
+            }
+            // Statement separator
+            ':' if stack.is_empty() => {
+                top_level_commas = 0;
+                top_level_named = None;
+                statement_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    // Innermost parenthesis that belongs to a call
+    if let Some(open) = stack.iter().rev().find(|open| !open.callee.is_empty()) {
+        return split_callee(&open.callee, open.commas, open.named.clone());
+    }
+
+    if !stack.is_empty() {
+        return None;
+    }
+
+    implicit_call(&chars[statement_start..], top_level_commas, top_level_named)
+}
+
+/// Collect the statement text up to the cursor, joining continued lines
+fn statement_before_cursor(source: &str, position: Position) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let line_idx = position.line as usize;
+    let line = lines.get(line_idx)?;
+
+    let current: String = line.chars().take(position.character as usize).collect();
+
+    // Walk back over lines ending with the " _" continuation marker
+    let mut parts = vec![current];
+    let mut idx = line_idx;
+    while idx > 0 {
+        let prev = lines[idx - 1].trim_end();
+        match prev.strip_suffix(" _") {
+            Some(stripped) => {
+                parts.push(format!("{} ", stripped));
+                idx -= 1;
+            }
+            None => break,
+        }
+    }
+
+    parts.reverse();
+    Some(parts.concat())
+}
+
+/// Extract a (possibly dotted) callee name immediately before a parenthesis
+fn callee_before(chars: &[char]) -> String {
+    let mut end = chars.len();
+    while end > 0 && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+
+    // Skip a type hint suffix (e.g., Left$)
+    if end > 0 && matches!(chars[end - 1], '$' | '%' | '&' | '!' | '#' | '@') {
+        end -= 1;
+    }
+
+    let mut start = end;
+    while start > 0 && (is_identifier_char(chars[start - 1]) || chars[start - 1] == '.') {
+        start -= 1;
+    }
+
+    chars[start..end].iter().collect::<String>().trim_start_matches('.').to_string()
+}
+
+/// The identifier immediately before a `:=`
+fn name_before(chars: &[char]) -> Option<String> {
+    let mut end = chars.len();
+    while end > 0 && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    let mut start = end;
+    while start > 0 && is_identifier_char(chars[start - 1]) {
+        start -= 1;
+    }
+    (start < end).then(|| chars[start..end].iter().collect())
+}
+
+/// Recognize a statement call without parentheses (e.g., `DoWork a, b`)
+fn implicit_call(chars: &[char], commas: u32, named: Option<String>) -> Option<CallContext> {
+    let text: String = chars.iter().collect();
+    let trimmed = text.trim_start();
+
+    let callee_len = trimmed
+        .find(|c: char| !is_identifier_char(c) && c != '.')
+        .unwrap_or(trimmed.len());
+    let (callee, rest) = trimmed.split_at(callee_len);
+
+    // Callee must be followed by whitespace before the arguments
+    if callee.is_empty() || !rest.starts_with(|c: char| c.is_whitespace()) {
+        return None;
+    }
+
+    if STATEMENT_KEYWORDS.contains(&callee.to_lowercase().as_str()) {
+        return None;
+    }
+
+    // An assignment is not a call
+    if rest.trim_start().starts_with('=') {
+        return None;
+    }
+
+    split_callee(callee, commas, named)
+}
+
+/// Split "obj.Method" into qualifier and name
+fn split_callee(callee: &str, commas: u32, named: Option<String>) -> Option<CallContext> {
+    let (qualifier, name) = match callee.rfind('.') {
+        Some(dot) => (Some(&callee[..dot]), &callee[dot + 1..]),
+        None => (None, callee),
+    };
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(CallContext {
+        qualifier: qualifier.filter(|q| !q.is_empty()).map(|q| q.to_string()),
+        name: name.to_string(),
+        active_parameter: commas,
+        named_argument: named,
+    })
+}
+
+/// Check if a character is valid in a VB6 identifier
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context_at_end(source: &str) -> Option<CallContext> {
+        let line = source.lines().count().saturating_sub(1) as u32;
+        let character = source.lines().last().unwrap_or("").chars().count() as u32;
+        find_call_context(source, Position { line, character })
+    }
+
+    #[test]
+    fn test_simple_call() {
+        let ctx = context_at_end("    x = Foo(").unwrap();
+        assert_eq!(ctx.name, "Foo");
+        assert_eq!(ctx.qualifier, None);
+        assert_eq!(ctx.active_parameter, 0);
+
+        let ctx = context_at_end("    x = Foo(1, 2, ").unwrap();
+        assert_eq!(ctx.active_parameter, 2);
+    }
+
+    #[test]
+    fn test_nested_call() {
+        let ctx = context_at_end("Foo(Bar(x, y), ").unwrap();
+        assert_eq!(ctx.name, "Foo");
+        assert_eq!(ctx.active_parameter, 1);
+
+        let ctx = context_at_end("Foo(a, Bar(x, ").unwrap();
+        assert_eq!(ctx.name, "Bar");
+        assert_eq!(ctx.active_parameter, 1);
+    }
+
+    #[test]
+    fn test_string_with_commas() {
+        let ctx = context_at_end("Foo(\"a, b, \"\"c\"\", d\", ").unwrap();
+        assert_eq!(ctx.name, "Foo");
+        assert_eq!(ctx.active_parameter, 1);
+    }
+
+    #[test]
+    fn test_qualified_call() {
+        let ctx = context_at_end("txtName.Move(100, ").unwrap();
+        assert_eq!(ctx.qualifier.as_deref(), Some("txtName"));
+        assert_eq!(ctx.name, "Move");
+        assert_eq!(ctx.active_parameter, 1);
+    }
+
+    #[test]
+    fn test_implicit_call() {
+        let ctx = context_at_end("    DoWork a, ").unwrap();
+        assert_eq!(ctx.name, "DoWork");
+        assert_eq!(ctx.active_parameter, 1);
+
+        assert!(context_at_end("    Dim a, ").is_none());
+        assert!(context_at_end("    x = 1").is_none());
+    }
+
+    #[test]
+    fn test_named_argument() {
+        let ctx = context_at_end("    Resize Height:=20, Width:=").unwrap();
+        assert_eq!(ctx.name, "Resize");
+        assert_eq!(ctx.active_parameter, 1);
+        assert_eq!(ctx.named_argument.as_deref(), Some("Width"));
+
+        let ctx = context_at_end("    x = Foo(Bar(a:=1), ").unwrap();
+        assert_eq!(ctx.name, "Foo");
+        assert_eq!(ctx.named_argument, None);
+
+        let ctx = context_at_end("    Foo a:=1: Bar ").unwrap();
+        assert_eq!(ctx.name, "Bar");
+        assert_eq!(ctx.named_argument, None);
+    }
+
+    #[test]
+    fn test_line_continuation() {
+        let ctx = context_at_end("x = Foo(a, _\n    b, ").unwrap();
+        assert_eq!(ctx.name, "Foo");
+        assert_eq!(ctx.active_parameter, 2);
+    }
+
+    #[test]
+    fn test_comment_and_closed_call() {
+        assert!(context_at_end("Foo(1) ' Bar(").is_none());
+        assert!(context_at_end("x = Foo(1) + ").is_none());
+    }
+}
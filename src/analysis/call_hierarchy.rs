@@ -0,0 +1,139 @@
+//! Call Hierarchy
+//!
+//! Extracts the calls each procedure makes from a symbol table, for
+//! `callHierarchy/incomingCalls` and `callHierarchy/outgoingCalls`.
+
+use tower_lsp::lsp_types::CallHierarchyItem;
+
+use super::position::{SourcePosition, SourceRange};
+use super::symbol::{Symbol, SymbolId};
+use super::symbol_table::SymbolTable;
+
+/// A call made from inside a procedure
+#[derive(Debug, Clone)]
+pub struct CallSite {
+    /// The procedure containing the call
+    pub caller: SymbolId,
+    /// The callee name as written
+    pub callee_name: String,
+    /// The callee, if it is declared in the same document
+    pub callee: Option<SymbolId>,
+    /// The range of the callee name at the call
+    pub range: SourceRange,
+}
+
+/// Find all calls made from procedure bodies in a document.
+///
+/// Names that do not resolve in the document are included without a
+/// `callee`; they may be procedures in other modules and must be resolved
+/// against the workspace.
+pub fn find_call_sites(table: &SymbolTable) -> Vec<CallSite> {
+    let mut sites = Vec::new();
+
+    for reference in table.all_references() {
+        // Assigning to a Function's name sets its return value; it is not a call
+        if reference.is_assignment {
+            continue;
+        }
+        let Some(callee) = table.get_symbol(reference.symbol_id) else {
+            continue;
+        };
+        if !callee.kind.is_procedure() {
+            continue;
+        }
+        if let Some(caller) = enclosing_procedure(table, reference.range.start) {
+            sites.push(CallSite {
+                caller: caller.id,
+                callee_name: callee.name.clone(),
+                callee: Some(callee.id),
+                range: reference.range,
+            });
+        }
+    }
+
+    for unresolved in table.unresolved_references() {
+        if let Some(caller) = enclosing_procedure(table, unresolved.range.start) {
+            sites.push(CallSite {
+                caller: caller.id,
+                callee_name: unresolved.name.clone(),
+                callee: None,
+                range: unresolved.range,
+            });
+        }
+    }
+
+    sites.sort_by_key(|site| (site.range.start.line, site.range.start.column));
+    sites
+}
+
+/// Find the procedure whose body contains a position
+fn enclosing_procedure(table: &SymbolTable, pos: SourcePosition) -> Option<&Symbol> {
+    table
+        .procedures()
+        .filter(|symbol| symbol.kind.creates_scope())
+        .find(|symbol| symbol.definition_range.contains(pos))
+}
+
+/// Build the call hierarchy item for a procedure
+pub fn call_hierarchy_item(table: &SymbolTable, symbol: &Symbol) -> CallHierarchyItem {
+    let detail = table
+        .uri
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .map(|file| format!("{} ({})", symbol.kind.display_name(), file));
+
+    CallHierarchyItem {
+        name: symbol.name.clone(),
+        kind: symbol.kind.to_lsp(),
+        tags: None,
+        detail,
+        uri: table.uri.clone(),
+        range: symbol.definition_range.to_lsp(),
+        selection_range: symbol.name_range.to_lsp(),
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    #[test]
+    fn test_call_sites() {
+        let source = "Sub Foo(a)\n\
+End Sub\n\
+\n\
+Function Bar(a) As Long\n\
+    Bar = a\n\
+End Function\n\
+\n\
+Sub Main()\n\
+    Call Foo(1)\n\
+    foo 2\n\
+    x = BAR(3)\n\
+    Remote 4\n\
+End Sub\n";
+        let sites: Vec<(String, bool, u32)> = run_pass(source, |table, _| {
+            find_call_sites(table)
+                .into_iter()
+                .map(|site| {
+                    let caller = table.get_symbol(site.caller).unwrap();
+                    assert_eq!(caller.name, "Main");
+                    (site.callee_name, site.callee.is_some(), site.range.start.line)
+                })
+                .collect()
+        });
+
+        assert_eq!(
+            sites,
+            vec![
+                ("Foo".to_string(), true, 8),
+                ("Foo".to_string(), true, 9),
+                ("x".to_string(), false, 10),
+                ("Bar".to_string(), true, 10),
+                ("Remote".to_string(), false, 11),
+            ]
+        );
+    }
+}
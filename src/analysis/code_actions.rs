@@ -0,0 +1,1965 @@
+//! Code Actions
+//!
+//! Quick fixes for the diagnostics the analyzer produces, and refactorings.
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, Diagnostic, NumberOrString, Position, Range, TextEdit, Url,
+    WorkspaceEdit,
+};
+use tree_sitter::{Node, Point, Tree};
+
+use super::inference;
+use super::position::{SourcePosition, SourceRange};
+use super::form_properties::{named_values, property_line_at, INVALID_PROPERTY_VALUE};
+use super::ptr_safe::x64_declare_edits;
+use super::scope::ScopeKind;
+use super::suppressions::DISABLE_NEXT_LINE;
+use super::symbol::{Symbol, SymbolKind};
+use super::symbol_table::SymbolTable;
+use crate::controls::{get_property, EventDef};
+
+/// Diagnostic code for a module without `Option Explicit`
+pub const MISSING_OPTION_EXPLICIT: &str = "missing-option-explicit";
+
+/// Check if a diagnostic carries the given code
+pub fn has_code(diagnostic: &Diagnostic, code: &str) -> bool {
+    matches!(&diagnostic.code, Some(NumberOrString::String(c)) if c == code)
+}
+
+/// Offer to insert `Option Explicit` for a missing-Option-Explicit diagnostic
+pub fn add_option_explicit(uri: &Url, source: &str, diagnostic: &Diagnostic) -> Option<CodeAction> {
+    let already_present = source.lines().any(|line| {
+        let code = line.split('\'').next().unwrap_or("");
+        code.split_whitespace()
+            .map(str::to_lowercase)
+            .eq(["option", "explicit"])
+    });
+    if already_present {
+        return None;
+    }
+
+    let line = option_insert_line(source) as u32;
+    let position = Position { line, character: 0 };
+    let eol = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    Some(CodeAction {
+        title: "Add Option Explicit".to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(single_edit(
+            uri,
+            TextEdit {
+                range: Range {
+                    start: position,
+                    end: position,
+                },
+                new_text: format!("Option Explicit{}", eol),
+            },
+        )),
+        is_preferred: Some(true),
+        ..Default::default()
+    })
+}
+
+/// Offer to replace the `+` of a plus-concatenation diagnostic with `&`
+pub fn use_ampersand(uri: &Url, diagnostic: &Diagnostic) -> CodeAction {
+    CodeAction {
+        title: "Replace '+' with '&'".to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(single_edit(
+            uri,
+            TextEdit {
+                range: diagnostic.range,
+                new_text: "&".to_string(),
+            },
+        )),
+        is_preferred: Some(true),
+        ..Default::default()
+    }
+}
+
+/// Offer to prepend `Set` to an object assignment (replacing an explicit `Let`)
+pub fn add_set(uri: &Url, source: &str, diagnostic: &Diagnostic) -> Option<CodeAction> {
+    let start = diagnostic.range.start;
+    let line = source.lines().nth(start.line as usize)?;
+    let statement = line.get(start.character as usize..)?;
+    let has_let = statement
+        .get(..4)
+        .is_some_and(|word| word.eq_ignore_ascii_case("let "));
+    let end = if has_let {
+        start.character + 4 + (statement[4..].len() - statement[4..].trim_start().len()) as u32
+    } else {
+        start.character
+    };
+
+    Some(CodeAction {
+        title: "Add 'Set'".to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(single_edit(
+            uri,
+            TextEdit {
+                range: Range {
+                    start,
+                    end: Position::new(start.line, end),
+                },
+                new_text: "Set ".to_string(),
+            },
+        )),
+        is_preferred: Some(true),
+        ..Default::default()
+    })
+}
+
+/// Offer to remove `Set` from an assignment to a value type
+pub fn remove_set(uri: &Url, source: &str, diagnostic: &Diagnostic) -> Option<CodeAction> {
+    let range = diagnostic.range;
+    let line = source.lines().nth(range.start.line as usize)?;
+    let after = line.get(range.end.character as usize..)?;
+    let spaces = (after.len() - after.trim_start().len()) as u32;
+
+    Some(CodeAction {
+        title: "Remove 'Set'".to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(single_edit(
+            uri,
+            TextEdit {
+                range: Range {
+                    start: range.start,
+                    end: Position::new(range.end.line, range.end.character + spaces),
+                },
+                new_text: String::new(),
+            },
+        )),
+        is_preferred: Some(true),
+        ..Default::default()
+    })
+}
+
+/// Offer to rewrite a Declare for 64-bit VBA: `Declare PtrSafe`, with `LongPtr`
+/// for its handles and pointers
+pub fn make_ptr_safe(
+    uri: &Url,
+    source: &str,
+    tree: &Tree,
+    diagnostic: &Diagnostic,
+) -> Option<CodeAction> {
+    let start = diagnostic.range.start;
+    let point = Point::new(start.line as usize, start.character as usize);
+    let mut node = tree.root_node().descendant_for_point_range(point, point)?;
+    while node.kind() != "declare_statement" {
+        node = node.parent()?;
+    }
+
+    Some(CodeAction {
+        title: "Make Declare PtrSafe for 64-bit".to_string(),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), x64_declare_edits(&node, source))])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Offer to silence a diagnostic with a `'vb6-lsp-disable-next-line` comment above
+/// its line, or by adding its code to the comment already there
+pub fn disable_for_line(uri: &Url, source: &str, diagnostic: &Diagnostic) -> Option<CodeAction> {
+    let Some(NumberOrString::String(code)) = &diagnostic.code else {
+        return None;
+    };
+    // The file header and designer block can't hold comments
+    if code == MISSING_OPTION_EXPLICIT || code == INVALID_PROPERTY_VALUE {
+        return None;
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let number = diagnostic.range.start.line as usize;
+    let line = lines.get(number)?;
+    let previous = number.checked_sub(1).and_then(|i| lines.get(i));
+    // A comment can't go between the lines of a continued statement
+    if previous.is_some_and(|p| p.trim_end().ends_with(" _")) {
+        return None;
+    }
+
+    let existing = previous.filter(|p| {
+        p.trim_start()
+            .strip_prefix('\'')
+            .is_some_and(|comment| comment.trim_start().starts_with(DISABLE_NEXT_LINE))
+    });
+    let edit = match existing {
+        Some(previous) => {
+            let end = Position::new(number as u32 - 1, previous.trim_end().len() as u32);
+            TextEdit {
+                range: Range { start: end, end },
+                new_text: format!(", {}", code),
+            }
+        }
+        None => {
+            let start = Position::new(number as u32, 0);
+            let eol = if source.contains("\r\n") { "\r\n" } else { "\n" };
+            TextEdit {
+                range: Range { start, end: start },
+                new_text: format!("{}'{} {}{}", line_indent(line), DISABLE_NEXT_LINE, code, eol),
+            }
+        }
+    };
+
+    Some(CodeAction {
+        title: format!("Disable {} for this line", code),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(single_edit(uri, edit)),
+        ..Default::default()
+    })
+}
+
+/// Offer to declare an undeclared variable, locally (as Variant or an inferred type)
+/// and as a module-level Private variable
+pub fn declare_variable(
+    uri: &Url,
+    source: &str,
+    table: &SymbolTable,
+    diagnostic: &Diagnostic,
+) -> Vec<CodeAction> {
+    let range = SourceRange::from_lsp(diagnostic.range);
+    let lines: Vec<&str> = source.lines().collect();
+    let Some(name) = lines
+        .get(range.start.line as usize)
+        .and_then(|line| text_in_columns(line, range.start.column, range.end.column))
+    else {
+        return Vec::new();
+    };
+
+    let inferred = infer_assigned_type(&lines, &range);
+    let types: Vec<&str> = if inferred == "Variant" {
+        vec!["Variant"]
+    } else {
+        vec![inferred, "Variant"]
+    };
+    let eol = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut actions = Vec::new();
+
+    if let Some((line, indent)) = procedure_body_start(&lines, table, range.start) {
+        for (i, type_name) in types.iter().enumerate() {
+            actions.push(declaration_action(
+                uri,
+                diagnostic,
+                format!("Declare '{}' as {}", name, type_name),
+                line,
+                format!("{}Dim {} As {}{}", indent, name, type_name, eol),
+                i == 0,
+            ));
+        }
+    }
+
+    actions.push(declaration_action(
+        uri,
+        diagnostic,
+        format!("Declare '{}' as Private module variable", name),
+        module_declaration_line(&lines),
+        format!("Private {} As {}{}", name, types[0], eol),
+        actions.is_empty(),
+    ));
+
+    actions
+}
+
+fn declaration_action(
+    uri: &Url,
+    diagnostic: &Diagnostic,
+    title: String,
+    line: usize,
+    new_text: String,
+    is_preferred: bool,
+) -> CodeAction {
+    let position = Position {
+        line: line as u32,
+        character: 0,
+    };
+    CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(single_edit(
+            uri,
+            TextEdit {
+                range: Range {
+                    start: position,
+                    end: position,
+                },
+                new_text,
+            },
+        )),
+        is_preferred: Some(is_preferred),
+        ..Default::default()
+    }
+}
+
+/// Text of a line between two UTF-16 columns
+fn text_in_columns(line: &str, start: u32, end: u32) -> Option<String> {
+    let units: Vec<u16> = line.encode_utf16().collect();
+    let text = String::from_utf16(units.get(start as usize..end as usize)?).ok()?;
+    (!text.is_empty()).then_some(text)
+}
+
+/// Type for a variable from a literal assigned to it (`x = 5` -> Long)
+fn infer_assigned_type(lines: &[&str], range: &SourceRange) -> &'static str {
+    let Some(line) = lines.get(range.end.line as usize) else {
+        return "Variant";
+    };
+    let after: Vec<u16> = line
+        .encode_utf16()
+        .skip(range.end.column as usize)
+        .collect();
+    let after = String::from_utf16_lossy(&after);
+    let Some(value) = after.trim_start().strip_prefix('=') else {
+        return "Variant";
+    };
+    let value = value.split('\'').next().unwrap_or("").trim();
+
+    if value.starts_with('"') && value.ends_with('"') && value.len() >= 2 {
+        "String"
+    } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        "Boolean"
+    } else if value.parse::<i64>().is_ok() || is_hex_literal(value) {
+        "Long"
+    } else if value.parse::<f64>().is_ok() {
+        "Double"
+    } else {
+        "Variant"
+    }
+}
+
+/// `&HFF` or `&HFF&`
+fn is_hex_literal(value: &str) -> bool {
+    let upper = value.to_uppercase();
+    upper
+        .strip_prefix("&H")
+        .map(|digits| digits.trim_end_matches('&'))
+        .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// First body line of the procedure enclosing a position, and the body's indentation
+fn procedure_body_start(
+    lines: &[&str],
+    table: &SymbolTable,
+    pos: SourcePosition,
+) -> Option<(usize, String)> {
+    let mut scope = table.get_scope(table.scope_at_position(pos))?;
+    while scope.kind != ScopeKind::Procedure {
+        scope = table.get_scope(scope.parent?)?;
+    }
+
+    // Skip the header's continuation lines
+    let mut line = scope.range.start.line as usize;
+    while lines
+        .get(line)
+        .is_some_and(|l| l.trim_end().ends_with(" _"))
+    {
+        line += 1;
+    }
+    let body = line + 1;
+    let end = scope.range.end.line as usize;
+
+    let header = lines.get(scope.range.start.line as usize)?;
+    let header_indent = &header[..header.len() - header.trim_start().len()];
+    let indent = lines
+        .iter()
+        .take(end)
+        .skip(body)
+        .find(|l| !l.trim().is_empty())
+        .map(|l| l[..l.len() - l.trim_start().len()].to_string())
+        .filter(|indent| indent.len() > header_indent.len())
+        .unwrap_or_else(|| format!("{}    ", header_indent));
+
+    Some((body, indent))
+}
+
+/// Line for a new module-level declaration: after the `Option` statements
+fn module_declaration_line(lines: &[&str]) -> usize {
+    let mut line = option_insert_line(&lines.join("\n"));
+    for (i, text) in lines.iter().enumerate().skip(line) {
+        let first = text.split_whitespace().next().unwrap_or("");
+        if first.eq_ignore_ascii_case("option") {
+            line = i + 1;
+        } else if !text.trim().is_empty() && !text.trim_start().starts_with('\'') {
+            break;
+        }
+    }
+    line
+}
+
+/// Statements that name a label as a jump target
+const LABEL_TARGET_KINDS: &[&str] = &[
+    "gosub_statement",
+    "on_gosub_statement",
+    "goto_statement",
+    "on_goto_statement",
+    "on_error_statement",
+    "resume_statement",
+];
+
+/// Statements that may not appear inside a block moved out of its procedure
+const JUMP_KINDS: &[&str] = &[
+    "label",
+    "gosub_statement",
+    "on_gosub_statement",
+    "goto_statement",
+    "on_goto_statement",
+    "on_error_statement",
+    "resume_statement",
+    "return_statement",
+];
+
+/// Offer to turn the `GoSub` under the cursor, and its `label:` ... `Return` block,
+/// into a call to a new `Private Sub` appended after the current procedure.
+///
+/// Procedure locals used by the block become `ByRef` parameters. Returns `None`
+/// when the label has other jump targets, or the block jumps across its boundary.
+pub fn convert_gosub(
+    uri: &Url,
+    source: &str,
+    tree: &Tree,
+    table: &SymbolTable,
+    position: Position,
+) -> Option<CodeAction> {
+    let point = Point::new(position.line as usize, position.character as usize);
+    let mut gosub = tree.root_node().descendant_for_point_range(point, point)?;
+    while gosub.kind() != "gosub_statement" {
+        gosub = gosub.parent()?;
+    }
+    let target = gosub.named_child(0)?;
+    let name = node_text(&target, source);
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let procedure = enclosing_procedure(gosub)?;
+    if count_label_targets(&procedure, source, name) != 1 {
+        return None;
+    }
+
+    // The label and its Return must be statements of the procedure's own block
+    let mut cursor = procedure.walk();
+    let block = procedure
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "block")?;
+    let mut cursor = block.walk();
+    let statements: Vec<Node> = block.named_children(&mut cursor).collect();
+    let label_index = statements.iter().position(|statement| {
+        statement.kind() == "label"
+            && statement
+                .named_child(0)
+                .is_some_and(|id| node_text(&id, source).eq_ignore_ascii_case(name))
+    })?;
+    let return_index = label_index
+        + statements[label_index..]
+            .iter()
+            .position(|statement| statement.kind() == "return_statement")?;
+    let body = &statements[label_index + 1..return_index];
+    if body
+        .iter()
+        .any(|statement| escapes(statement, source, false))
+    {
+        return None;
+    }
+
+    let lines: Vec<&str> = source.lines().collect();
+    let label_line = statements[label_index].start_position().row;
+    let return_line = statements[return_index].start_position().row;
+    let alone = |line: usize, text: &str| {
+        lines.get(line).is_some_and(|l| {
+            let code = l.split('\'').next().unwrap_or("").trim();
+            code.trim_end_matches(':').eq_ignore_ascii_case(text)
+        })
+    };
+    if !alone(label_line, name) || !alone(return_line, "Return") {
+        return None;
+    }
+
+    // The new Sub may not collide with an existing module-level name
+    if table
+        .module_symbols()
+        .iter()
+        .any(|symbol| symbol.name.eq_ignore_ascii_case(name))
+    {
+        return None;
+    }
+
+    let label_pos = SourcePosition::new(label_line as u32, 0);
+    let mut scope = table.get_scope(table.scope_at_position(label_pos))?;
+    while scope.kind != ScopeKind::Procedure {
+        scope = table.get_scope(scope.parent?)?;
+    }
+    let owner = scope.defining_symbol;
+
+    // Locals and parameters of the procedure used by the block, in order of first use
+    let mut locals = Vec::new();
+    for reference in table.all_references() {
+        let line = reference.range.start.line as usize;
+        if line <= label_line || line >= return_line {
+            continue;
+        }
+        if Some(reference.symbol_id) == owner && reference.is_assignment {
+            // Assigning a Function's return value
+            return None;
+        }
+        let Some(symbol) = table.get_symbol(reference.symbol_id) else {
+            continue;
+        };
+        if is_local(symbol) && !locals.iter().any(|(id, _)| *id == symbol.id) {
+            locals.push((symbol.id, (symbol.name.clone(), parameter(symbol, "ByRef"))));
+        }
+    }
+    let (arguments, parameters): (Vec<String>, Vec<String>) =
+        locals.into_iter().map(|(_, pair)| pair).unzip();
+
+    let eol = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let indent = line_indent(lines.get(procedure.start_position().row)?);
+    let body_text: String = lines[label_line + 1..return_line]
+        .iter()
+        .map(|line| format!("{}{}", line, eol))
+        .collect();
+    let end = procedure_end(&lines, &procedure)?;
+
+    let call = if arguments.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {}", name, arguments.join(", "))
+    };
+    let new_sub = format!(
+        "{eol}{eol}{indent}Private Sub {name}({}){eol}{body_text}{indent}End Sub",
+        parameters.join(", ")
+    );
+
+    let edits = vec![
+        TextEdit {
+            range: Range {
+                start: SourcePosition::from_ts_point(gosub.start_position()).to_lsp(),
+                end: SourcePosition::from_ts_point(target.end_position()).to_lsp(),
+            },
+            new_text: call,
+        },
+        TextEdit {
+            range: Range {
+                start: Position {
+                    line: label_line as u32,
+                    character: 0,
+                },
+                end: Position {
+                    line: return_line as u32 + 1,
+                    character: 0,
+                },
+            },
+            new_text: String::new(),
+        },
+        TextEdit {
+            range: Range { start: end, end },
+            new_text: new_sub,
+        },
+    ];
+
+    Some(refactor_action(
+        uri,
+        format!("Convert GoSub '{}' to a Sub", name),
+        edits,
+    ))
+}
+
+/// Offer to give the Function whose signature is under the cursor an explicit
+/// return type in place of its implicit Variant.
+///
+/// The type is inferred from the `Name = value` assignments in the body; when
+/// they disagree or can't be inferred, only `As Variant` is offered.
+pub fn declare_return_type(
+    uri: &Url,
+    source: &str,
+    tree: &Tree,
+    table: &SymbolTable,
+    position: Position,
+) -> Vec<CodeAction> {
+    let point = Point::new(position.line as usize, position.character as usize);
+    let Some(function) = tree
+        .root_node()
+        .descendant_for_point_range(point, point)
+        .and_then(enclosing_procedure)
+        .filter(|procedure| procedure.kind() == "function_declaration")
+    else {
+        return Vec::new();
+    };
+
+    // Only on the signature, and only when it has no `As` clause or type character
+    let Some(name) = function.child_by_field_name("name") else {
+        return Vec::new();
+    };
+    let mut cursor = function.walk();
+    let children: Vec<Node> = function.named_children(&mut cursor).collect();
+    if children.iter().any(|child| matches!(child.kind(), "as_clause" | "type_hint")) {
+        return Vec::new();
+    }
+    let signature_end = children
+        .iter()
+        .find(|child| child.kind() == "parameter_list")
+        .unwrap_or(&name)
+        .end_position();
+    if point.row < function.start_position().row || point.row > signature_end.row {
+        return Vec::new();
+    }
+
+    let function_name = node_text(&name, source);
+    let mut types = Vec::new();
+    let mut stack: Vec<Node> = children.iter().filter(|c| c.kind() == "block").copied().collect();
+    while let Some(node) = stack.pop() {
+        let is_return = matches!(node.kind(), "assignment_statement" | "set_statement")
+            && node.child_by_field_name("target").is_some_and(|target| {
+                target.kind() == "identifier"
+                    && node_text(&target, source).eq_ignore_ascii_case(function_name)
+            });
+        if is_return {
+            let scope =
+                table.scope_at_position(SourcePosition::from_ts_point(node.start_position()));
+            types.push(
+                inference::infer_assigned_type(table, scope, &node, source)
+                    .filter(|t| !t.is_array && !t.is_inferred)
+                    .map(|t| t.name),
+            );
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+
+    let insert_at = SourcePosition::from_ts_point(signature_end).to_lsp();
+    let action = |type_name: &str| {
+        refactor_action(
+            uri,
+            format!("Declare '{}' As {}", function_name, type_name),
+            vec![TextEdit {
+                range: Range {
+                    start: insert_at,
+                    end: insert_at,
+                },
+                new_text: format!(" As {}", type_name),
+            }],
+        )
+    };
+
+    let mut actions = Vec::new();
+    if let Some(inferred) = common_type(&types).filter(|t| !t.eq_ignore_ascii_case("variant")) {
+        actions.push(action(&inferred));
+    }
+    actions.push(action("Variant"));
+    actions
+}
+
+/// Offer to replace a literal property value with the constant it stands for,
+/// in a control block line (`Alignment = 2`) or a control property assignment
+/// in code (`Text1.BackColor = &HFF&`)
+///
+/// When several constants share the value, each is offered and none is preferred.
+pub fn use_named_value(
+    uri: &Url,
+    source: &str,
+    tree: &Tree,
+    table: Option<&SymbolTable>,
+    position: Position,
+) -> Vec<CodeAction> {
+    let Some((names, range)) = property_line_at(tree, source, position)
+        .map(|(property, text, range)| (named_values(property, text), range))
+        .or_else(|| assigned_property_value(source, tree, table?, position))
+    else {
+        return Vec::new();
+    };
+
+    let preferred = names.len() == 1;
+    names
+        .into_iter()
+        .map(|name| CodeAction {
+            is_preferred: Some(preferred),
+            ..refactor_action(
+                uri,
+                format!("Replace with {}", name),
+                vec![TextEdit {
+                    range: range.to_lsp(),
+                    new_text: name.to_string(),
+                }],
+            )
+        })
+        .collect()
+}
+
+/// The constants for the value of a `Control.Property = value` statement
+/// under the cursor, and the value's range
+fn assigned_property_value(
+    source: &str,
+    tree: &Tree,
+    table: &SymbolTable,
+    position: Position,
+) -> Option<(Vec<&'static str>, SourceRange)> {
+    let point = Point::new(position.line as usize, position.character as usize);
+    let mut node = tree.root_node().descendant_for_point_range(point, point)?;
+    while node.kind() != "assignment_statement" {
+        node = node.parent()?;
+    }
+
+    let target = node.child_by_field_name("target")?;
+    let value = node.child_by_field_name("value")?;
+    if target.kind() != "member_expression" {
+        return None;
+    }
+    let object = target.child_by_field_name("object")?;
+    let member = target.child_by_field_name("member")?;
+
+    let control = table
+        .lookup_at_position(
+            node_text(&object, source),
+            SourcePosition::from_ts_point(object.start_position()),
+        )
+        .filter(|symbol| symbol.kind == SymbolKind::FormControl)?;
+    let type_name = &control.type_info.as_ref()?.name;
+    let type_name = type_name.rsplit('.').next().unwrap_or(type_name);
+    let property = get_property(type_name, node_text(&member, source))?;
+
+    Some((named_values(property, node_text(&value, source)), SourceRange::from_ts_node(&value)))
+}
+
+/// The single type of a set of return values; Integer and Long values make a Long
+fn common_type(types: &[Option<String>]) -> Option<String> {
+    let mut common: Option<String> = None;
+    for type_name in types {
+        let type_name = type_name.as_ref()?;
+        common = match common {
+            None => Some(type_name.clone()),
+            Some(current) if current.eq_ignore_ascii_case(type_name) => Some(current),
+            Some(current)
+                if [current.as_str(), type_name.as_str()]
+                    .iter()
+                    .all(|t| t.eq_ignore_ascii_case("integer") || t.eq_ignore_ascii_case("long")) =>
+            {
+                Some("Long".to_string())
+            }
+            Some(_) => return None,
+        };
+    }
+    common
+}
+
+/// Offer to add a stub handler for each event of the control under the cursor
+/// that has none. Handlers of control arrays take the element `Index` first.
+pub fn add_event_handlers(
+    uri: &Url,
+    source: &str,
+    table: &SymbolTable,
+    position: Position,
+) -> Vec<CodeAction> {
+    let Some(control) = table
+        .symbol_at_position(SourcePosition::from_lsp(position))
+        .filter(|s| s.kind == SymbolKind::FormControl)
+    else {
+        return Vec::new();
+    };
+    // The form itself raises its events as `Form_Event`
+    if !control.control_path.as_ref().is_some_and(|path| path.contains('.')) {
+        return Vec::new();
+    }
+    let Some(definition) = control
+        .type_info
+        .as_ref()
+        .and_then(|t| crate::controls::get_control(&t.name))
+    else {
+        return Vec::new();
+    };
+
+    // Append after the last line of the file
+    let lines: Vec<&str> = source.lines().collect();
+    let (insert_at, separator) = match lines.last() {
+        Some(last) if !source.ends_with('\n') => (
+            Position {
+                line: lines.len() as u32 - 1,
+                character: last.encode_utf16().count() as u32,
+            },
+            "\n\n",
+        ),
+        _ => (
+            Position {
+                line: lines.len() as u32,
+                character: 0,
+            },
+            if lines.is_empty() { "" } else { "\n" },
+        ),
+    };
+
+    definition
+        .events
+        .iter()
+        .filter(|event| table.event_handler(control.id, event.name).is_none())
+        .map(|event| {
+            let name = format!("{}_{}", control.name, event.name);
+            refactor_action(
+                uri,
+                format!("Add '{}' event handler", name),
+                vec![TextEdit {
+                    range: Range {
+                        start: insert_at,
+                        end: insert_at,
+                    },
+                    new_text: format!(
+                        "{}Private Sub {}({})\n\nEnd Sub\n",
+                        separator,
+                        name,
+                        handler_parameters(control, event)
+                    ),
+                }],
+            )
+        })
+        .collect()
+}
+
+/// Parameter list of a handler for an event of a control
+pub fn handler_parameters(control: &Symbol, event: &EventDef) -> String {
+    let mut parameters = Vec::new();
+    if !control.control_array.is_empty() {
+        parameters.push("Index As Integer");
+    }
+    if !event.parameters.is_empty() {
+        parameters.push(event.parameters);
+    }
+    parameters.join(", ")
+}
+
+/// Kind of the "Organize declarations" source action
+pub const ORGANIZE_DECLARATIONS: CodeActionKind =
+    CodeActionKind::new("source.organizeDeclarations");
+
+/// A module-level declaration with the comment lines directly above it
+struct DeclarationUnit<'s> {
+    group: usize,
+    name: String,
+    lines: &'s [&'s str],
+}
+
+/// Offer to regroup the declarations at the top of a module: constants, then
+/// Enums and Types, Declares, Events, and finally Public and Private variables,
+/// each group sorted by name.
+///
+/// Only the contiguous block of declarations before the first procedure (or
+/// other statement) is rewritten. Comment lines above a declaration move with
+/// it. Constants and types that refer to each other keep their order.
+pub fn organize_declarations(uri: &Url, source: &str, tree: &Tree) -> Option<CodeAction> {
+    let lines: Vec<&str> = source.lines().collect();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+
+    let mut units: Vec<DeclarationUnit> = Vec::new();
+    let mut comment_start: Option<usize> = None;
+    let mut last_line: Option<usize> = None;
+    for node in root.named_children(&mut cursor) {
+        let row = node.start_position().row;
+        if node.kind() == "comment" {
+            comment_start.get_or_insert(row);
+            continue;
+        }
+        let Some(group) = declaration_group(&node, source) else {
+            if !units.is_empty() {
+                break;
+            }
+            comment_start = None;
+            continue;
+        };
+        // Statements sharing a line (`Dim a: Dim b`) can't be moved apart
+        if node.has_error() || last_line.is_some_and(|line| line >= row) {
+            return None;
+        }
+        let end = last_row(&node);
+        let start = comment_start.take().unwrap_or(row);
+        units.push(DeclarationUnit {
+            group,
+            name: node
+                .child_by_field_name("name")
+                .or_else(|| first_declarator_name(&node))
+                .map(|name| node_text(&name, source).to_lowercase())
+                .unwrap_or_default(),
+            lines: lines.get(start..=end)?,
+        });
+        last_line = Some(end);
+    }
+    let first_line = units.first()?.lines.as_ptr();
+    let first = lines.iter().position(|line| std::ptr::eq(line, first_line))?;
+    let last = last_line?;
+
+    let mut groups: Vec<Vec<DeclarationUnit>> = Vec::new();
+    units.sort_by_key(|unit| unit.group);
+    for unit in units {
+        match groups.last_mut() {
+            Some(group) if group[0].group == unit.group => group.push(unit),
+            _ => groups.push(vec![unit]),
+        }
+    }
+    for group in &mut groups {
+        if !refers_to_each_other(group) {
+            group.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+
+    let eol = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let organized = groups
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .flat_map(|unit| unit.lines.iter())
+                .map(|line| format!("{}{}", line, eol))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(eol);
+    let current: String = lines[first..=last]
+        .iter()
+        .map(|line| format!("{}{}", line, eol))
+        .collect();
+    if organized == current {
+        return None;
+    }
+
+    Some(CodeAction {
+        title: "Organize declarations".to_string(),
+        kind: Some(ORGANIZE_DECLARATIONS),
+        edit: Some(single_edit(
+            uri,
+            TextEdit {
+                range: Range {
+                    start: Position::new(first as u32, 0),
+                    end: Position::new(last as u32 + 1, 0),
+                },
+                new_text: organized,
+            },
+        )),
+        ..Default::default()
+    })
+}
+
+/// Group of a module-level declaration, in output order
+fn declaration_group(node: &Node, source: &str) -> Option<usize> {
+    let group = match node.kind() {
+        "constant_declaration" => 0,
+        "type_declaration" | "enum_declaration" => 1,
+        "declare_statement" => 2,
+        "event_statement" => 3,
+        "variable_declaration" => {
+            let keyword = node_text(node, source)
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .to_lowercase();
+            if matches!(keyword.as_str(), "public" | "global") {
+                4
+            } else {
+                5
+            }
+        }
+        _ => return None,
+    };
+    Some(group)
+}
+
+/// Name of the first variable or constant a declaration statement declares
+fn first_declarator_name<'t>(node: &Node<'t>) -> Option<Node<'t>> {
+    let mut stack = vec![*node];
+    while let Some(current) = stack.pop() {
+        if matches!(current.kind(), "variable_declarator" | "constant_declarator") {
+            return current.child_by_field_name("name");
+        }
+        let mut cursor = current.walk();
+        let children: Vec<Node> = current.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    None
+}
+
+/// Whether a declaration mentions another one of its group (`Const B = A + 1`)
+fn refers_to_each_other(group: &[DeclarationUnit]) -> bool {
+    group.iter().any(|unit| {
+        let words: Vec<String> = unit
+            .lines
+            .iter()
+            .map(|line| line.split('\'').next().unwrap_or(""))
+            .flat_map(|code| code.split(|c: char| !c.is_alphanumeric() && c != '_'))
+            .map(str::to_lowercase)
+            .collect();
+        group
+            .iter()
+            .any(|other| other.name != unit.name && words.contains(&other.name))
+    })
+}
+
+/// Intrinsic types a Function can return without `Set`
+const VALUE_TYPES: &[&str] = &[
+    "Boolean", "Byte", "Currency", "Date", "Double", "Integer", "Long", "Single", "String",
+];
+
+/// Default name for an extracted procedure
+const EXTRACTED_NAME: &str = "ExtractedProc";
+
+/// How a local is used by the statements being extracted
+struct LocalUse<'s> {
+    symbol: &'s Symbol,
+    /// Read before the statements assign it unconditionally
+    read: bool,
+    written: bool,
+    /// Assigned by one of the selected statements themselves (not a nested one)
+    assigned: bool,
+    used_after: bool,
+}
+
+/// Offer to extract the statements covered by a selection into a new procedure
+/// appended after the current one.
+///
+/// Locals read by the statements become `ByVal` parameters. A single local written
+/// and used afterward becomes the return value of a Function; several become
+/// `ByRef` parameters of a Sub. Returns `None` when the selection does not cover
+/// whole statements of one block, or the statements jump out of it.
+pub fn extract_procedure(
+    uri: &Url,
+    source: &str,
+    tree: &Tree,
+    table: &SymbolTable,
+    range: Range,
+) -> Option<CodeAction> {
+    let lines: Vec<&str> = source.lines().collect();
+
+    // Selected lines, ignoring blank lines around the statements
+    let mut first = range.start.line as usize;
+    let mut last = range.end.line as usize;
+    if range.end.character == 0 && last > first {
+        last -= 1;
+    }
+    while first < last && lines.get(first).is_some_and(|l| l.trim().is_empty()) {
+        first += 1;
+    }
+    while last > first && lines.get(last).is_some_and(|l| l.trim().is_empty()) {
+        last -= 1;
+    }
+
+    let start = Point::new(first, line_indent(lines.get(first)?).len());
+    let node = tree.root_node().descendant_for_point_range(start, start)?;
+    let procedure = enclosing_procedure(node)?;
+    let statements = selected_statements(&procedure, first, last)?;
+    if statements
+        .iter()
+        .any(|statement| escapes(statement, source, false) || uses_outer_with(statement))
+    {
+        return None;
+    }
+
+    let owner = table
+        .get_scope(table.scope_at_position(SourcePosition::new(first as u32, 0)))
+        .and_then(|scope| scope.defining_symbol);
+    let procedure_end_line = last_row(&procedure);
+
+    // Lines of the selected statements that assign unconditionally
+    let assignment_lines: Vec<usize> = statements
+        .iter()
+        .filter(|statement| statement.kind() == "assignment_statement")
+        .map(|statement| statement.start_position().row)
+        .collect();
+    let mut selected: Vec<_> = table
+        .all_references()
+        .iter()
+        .filter(|reference| {
+            let line = reference.range.start.line as usize;
+            line >= first && line <= last
+        })
+        .collect();
+    // On an assignment's line, the value is read before the target is written
+    selected.sort_by_key(|reference| {
+        (
+            reference.range.start.line,
+            reference.is_assignment,
+            reference.range.start.column,
+        )
+    });
+
+    let mut uses: Vec<LocalUse> = Vec::new();
+    for reference in selected {
+        if Some(reference.symbol_id) == owner && reference.is_assignment {
+            // Assigning a Function's return value
+            return None;
+        }
+        let Some(symbol) = table
+            .get_symbol(reference.symbol_id)
+            .filter(|s| is_local(s))
+        else {
+            continue;
+        };
+
+        let index = uses
+            .iter()
+            .position(|u| u.symbol.id == symbol.id)
+            .unwrap_or_else(|| {
+                uses.push(LocalUse {
+                    symbol,
+                    read: false,
+                    written: false,
+                    assigned: false,
+                    used_after: false,
+                });
+                uses.len() - 1
+            });
+        let local = &mut uses[index];
+        if reference.is_assignment {
+            local.written = true;
+            let line = reference.range.start.line as usize;
+            local.assigned |= assignment_lines.contains(&line);
+        } else if !local.assigned {
+            local.read = true;
+        }
+    }
+    for reference in table.all_references() {
+        let line = reference.range.start.line as usize;
+        if line > last && line <= procedure_end_line {
+            if let Some(local) = uses.iter_mut().find(|u| u.symbol.id == reference.symbol_id) {
+                local.used_after = true;
+            }
+        }
+    }
+
+    let declared_inside = |symbol: &Symbol| {
+        let line = symbol.definition_range.start.line as usize;
+        line >= first && line <= last
+    };
+    if uses
+        .iter()
+        .any(|u| u.used_after && declared_inside(u.symbol))
+    {
+        return None;
+    }
+    uses.retain(|u| !declared_inside(u.symbol));
+
+    let by_reference = |symbol: &Symbol| {
+        symbol.type_info.as_ref().is_some_and(|t| {
+            t.is_array
+                || table
+                    .symbols_of_kind(SymbolKind::UserDefinedType)
+                    .any(|udt| udt.name.eq_ignore_ascii_case(&t.name))
+        })
+    };
+    let outputs: Vec<&LocalUse> = uses.iter().filter(|u| u.written && u.used_after).collect();
+    let result = match outputs.as_slice() {
+        [only] if !by_reference(only.symbol) => {
+            let type_name = only
+                .symbol
+                .type_info
+                .as_ref()
+                .map_or("Variant", |t| t.name.as_str());
+            VALUE_TYPES
+                .iter()
+                .chain(["Variant"].iter())
+                .find(|t| t.eq_ignore_ascii_case(type_name))
+                .map(|t| (only.symbol, *t))
+        }
+        _ => None,
+    };
+
+    let mut arguments = Vec::new();
+    let mut parameters = Vec::new();
+    let mut declarations = Vec::new();
+    for local in &uses {
+        let is_result = result.is_some_and(|(symbol, _)| symbol.id == local.symbol.id);
+        if !local.read && (is_result || (local.assigned && !local.used_after)) {
+            // Only needed inside the new procedure
+            declarations.push(local.symbol);
+            continue;
+        }
+        let passing =
+            if by_reference(local.symbol) || (local.written && local.used_after && !is_result) {
+                "ByRef"
+            } else {
+                "ByVal"
+            };
+        arguments.push(local.symbol.name.clone());
+        parameters.push(parameter(local.symbol, passing));
+    }
+
+    let mut name = EXTRACTED_NAME.to_string();
+    let mut suffix = 1;
+    while table
+        .module_symbols()
+        .iter()
+        .any(|symbol| symbol.name.eq_ignore_ascii_case(&name))
+    {
+        suffix += 1;
+        name = format!("{}{}", EXTRACTED_NAME, suffix);
+    }
+
+    let eol = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let header_indent = line_indent(lines.get(procedure.start_position().row)?);
+    let body_indent = format!("{}    ", header_indent);
+    let common = lines[first..=last]
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| line_indent(l).len())
+        .min()
+        .unwrap_or(0);
+    let mut body: String = declarations
+        .iter()
+        .map(|symbol| format!("{}Dim {}{}", body_indent, declaration(symbol), eol))
+        .collect();
+    body.extend(lines[first..=last].iter().map(|l| {
+        if l.trim().is_empty() {
+            eol.to_string()
+        } else {
+            format!("{}{}{}", body_indent, &l[common..], eol)
+        }
+    }));
+
+    let parameters = parameters.join(", ");
+    let arguments = arguments.join(", ");
+    let (header, footer, call) = match result {
+        Some((symbol, type_name)) => {
+            body.push_str(&format!("{}{} = {}{}", body_indent, name, symbol.name, eol));
+            (
+                format!("Private Function {}({}) As {}", name, parameters, type_name),
+                "End Function",
+                format!("{} = {}({})", symbol.name, name, arguments),
+            )
+        }
+        None if arguments.is_empty() => {
+            (format!("Private Sub {}()", name), "End Sub", name.clone())
+        }
+        None => (
+            format!("Private Sub {}({})", name, parameters),
+            "End Sub",
+            format!("{} {}", name, arguments),
+        ),
+    };
+
+    let end = procedure_end(&lines, &procedure)?;
+    let edits = vec![
+        TextEdit {
+            range: Range {
+                start: Position {
+                    line: first as u32,
+                    character: 0,
+                },
+                end: Position {
+                    line: last as u32 + 1,
+                    character: 0,
+                },
+            },
+            new_text: format!("{}{}{}", line_indent(lines[first]), call, eol),
+        },
+        TextEdit {
+            range: Range { start: end, end },
+            new_text: format!(
+                "{eol}{eol}{header_indent}{header}{eol}{body}{header_indent}{footer}"
+            ),
+        },
+    ];
+
+    let kind = if result.is_some() { "Function" } else { "Sub" };
+    Some(refactor_action(
+        uri,
+        format!("Extract to {} '{}'", kind, name),
+        edits,
+    ))
+}
+
+/// Statements of a single block within a procedure that exactly cover lines `first..=last`
+fn selected_statements<'t>(
+    procedure: &Node<'t>,
+    first: usize,
+    last: usize,
+) -> Option<Vec<Node<'t>>> {
+    // The innermost block spanning the selection
+    let mut block = None;
+    let mut stack = vec![*procedure];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "block" && node.start_position().row <= first && last_row(&node) >= last {
+            block = Some(node);
+        }
+        let mut cursor = node.walk();
+        stack.extend(
+            node.named_children(&mut cursor)
+                .filter(|child| child.start_position().row <= first && last_row(child) >= last),
+        );
+    }
+
+    let block = block?;
+    let mut cursor = block.walk();
+    let statements: Vec<Node> = block
+        .named_children(&mut cursor)
+        .filter(|statement| statement.start_position().row <= last && last_row(statement) >= first)
+        .collect();
+    let contained = statements
+        .iter()
+        .all(|statement| statement.start_position().row >= first && last_row(statement) <= last);
+
+    (contained && !statements.is_empty()).then_some(statements)
+}
+
+/// Check if a statement uses `.Member` of a With block it is not part of
+fn uses_outer_with(node: &Node) -> bool {
+    match node.kind() {
+        "with_statement" => false,
+        "with_member_expression" => true,
+        _ => {
+            let mut cursor = node.walk();
+            let nested = node
+                .named_children(&mut cursor)
+                .any(|child| uses_outer_with(&child));
+            nested
+        }
+    }
+}
+
+/// Number of jump statements in a procedure that target a label
+fn count_label_targets(node: &Node, source: &str, label: &str) -> usize {
+    let targets = node.kind() == "identifier"
+        && node
+            .parent()
+            .is_some_and(|p| LABEL_TARGET_KINDS.contains(&p.kind()))
+        && node_text(node, source).eq_ignore_ascii_case(label);
+
+    let mut cursor = node.walk();
+    let nested: usize = node
+        .named_children(&mut cursor)
+        .map(|child| count_label_targets(&child, source, label))
+        .sum();
+    nested + usize::from(targets)
+}
+
+/// Check if a statement jumps out of (or into) the block containing it.
+/// `in_loop` is set when an enclosing loop is part of the block.
+fn escapes(node: &Node, source: &str, in_loop: bool) -> bool {
+    if JUMP_KINDS.contains(&node.kind()) {
+        return true;
+    }
+
+    // Matched on the text too, since single-line Ifs don't always parse `Exit Sub`
+    let is_exit = node.kind() == "exit_statement"
+        || (node.kind() == "implicit_call_stmt"
+            && node
+                .named_child(0)
+                .is_some_and(|name| node_text(&name, source).eq_ignore_ascii_case("exit")));
+    if is_exit {
+        let words: Vec<String> = node_text(node, source)
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        match words.get(1).map(String::as_str) {
+            Some("sub" | "function" | "property") => return true,
+            Some("for" | "do") if !in_loop => return true,
+            _ => {}
+        }
+    }
+
+    let in_loop = in_loop
+        || matches!(
+            node.kind(),
+            "for_statement" | "for_each_statement" | "do_statement" | "while_statement"
+        );
+    let mut cursor = node.walk();
+    let nested = node
+        .named_children(&mut cursor)
+        .any(|child| escapes(&child, source, in_loop));
+    nested
+}
+
+/// The Sub, Function, or Property containing a node
+fn enclosing_procedure(node: Node) -> Option<Node> {
+    let mut procedure = node.parent()?;
+    while !matches!(
+        procedure.kind(),
+        "sub_declaration" | "function_declaration" | "property_declaration"
+    ) {
+        procedure = procedure.parent()?;
+    }
+    Some(procedure)
+}
+
+/// End of a procedure's `End` line, where a new procedure is appended
+fn procedure_end(lines: &[&str], procedure: &Node) -> Option<Position> {
+    let line = last_row(procedure);
+    let character = lines.get(line)?.encode_utf16().count() as u32;
+    Some(Position {
+        line: line as u32,
+        character,
+    })
+}
+
+/// Parameters, locals, and loop variables of a procedure
+fn is_local(symbol: &Symbol) -> bool {
+    matches!(
+        symbol.kind,
+        SymbolKind::Parameter
+            | SymbolKind::LocalVariable
+            | SymbolKind::LocalConstant
+            | SymbolKind::ForLoopVariable
+            | SymbolKind::ForEachVariable
+    )
+}
+
+/// Parameter declaration passing a local (`ByRef total As Long`)
+fn parameter(symbol: &Symbol, passing: &str) -> String {
+    format!("{} {}", passing, declaration(symbol))
+}
+
+/// Name and type of a local (`items() As String`)
+fn declaration(symbol: &Symbol) -> String {
+    let type_name = symbol
+        .type_info
+        .as_ref()
+        .map_or("Variant", |t| t.name.as_str());
+    let array = if symbol.type_info.as_ref().is_some_and(|t| t.is_array) {
+        "()"
+    } else {
+        ""
+    };
+    format!("{}{} As {}", symbol.name, array, type_name)
+}
+
+fn refactor_action(uri: &Url, title: String, edits: Vec<TextEdit>) -> CodeAction {
+    CodeAction {
+        title,
+        kind: Some(CodeActionKind::REFACTOR),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), edits)])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn line_indent(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn node_text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+/// Last row containing text of a node (statements end with their newline terminator)
+fn last_row(node: &Node) -> usize {
+    let start = node.start_position();
+    let end = node.end_position();
+    if end.column == 0 && end.row > start.row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Line before which module options go: after the designer header, `Attribute`
+/// lines, and leading comments, but before the first declaration
+fn option_insert_line(source: &str) -> usize {
+    let mut insert_line = 0;
+    let mut designer_depth = 0usize;
+
+    for (i, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        let first = trimmed
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+
+        if designer_depth > 0 {
+            match first.as_str() {
+                "begin" | "beginproperty" => designer_depth += 1,
+                "end" | "endproperty" => designer_depth -= 1,
+                _ => {}
+            }
+            insert_line = i + 1;
+            continue;
+        }
+
+        match first.as_str() {
+            "begin" => designer_depth = 1,
+            "version" | "object" | "attribute" => {}
+            _ if trimmed.starts_with('\'') => {}
+            _ if trimmed.is_empty() => continue,
+            _ => break,
+        }
+        insert_line = i + 1;
+    }
+
+    insert_line
+}
+
+/// A workspace edit with a single text edit in one document
+fn single_edit(uri: &Url, edit: TextEdit) -> WorkspaceEdit {
+    WorkspaceEdit {
+        changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::build_symbol_table;
+    use crate::analysis::undeclared::find_undeclared_variables;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn inserted_at(source: &str) -> Option<u32> {
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let action = add_option_explicit(&uri, source, &Diagnostic::default())?;
+        let edits = &action.edit?.changes?[&uri];
+        assert_eq!(edits[0].new_text, "Option Explicit\n");
+        Some(edits[0].range.start.line)
+    }
+
+    #[test]
+    fn test_inserts_after_attributes_and_comments() {
+        let source = "Attribute VB_Name = \"Module1\"\n' Helpers\n\nPrivate x As Long\n";
+        assert_eq!(inserted_at(source), Some(2));
+
+        assert_eq!(inserted_at("Sub Main()\nEnd Sub\n"), Some(0));
+    }
+
+    #[test]
+    fn test_skips_designer_header() {
+        let source = "VERSION 5.00\n\
+Begin VB.Form Form1\n\
+   Caption = \"Main\"\n\
+   Begin VB.Label Label1\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"Form1\"\n\
+Private Sub Form_Load()\n\
+End Sub\n";
+        assert_eq!(inserted_at(source), Some(7));
+    }
+
+    fn declarations(source: &str) -> Vec<(String, u32, String)> {
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(uri.clone(), source, &tree);
+        let diagnostic = find_undeclared_variables(&table, &tree, source, |name| name == "MsgBox")
+            .into_iter()
+            .next()
+            .unwrap();
+
+        declare_variable(&uri, source, &table, &diagnostic)
+            .into_iter()
+            .map(|action| {
+                let edit = action.edit.unwrap().changes.unwrap()[&uri][0].clone();
+                (action.title, edit.range.start.line, edit.new_text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_declare_variable_with_inferred_type() {
+        let source = "Option Explicit\n\nPrivate Sub Main(ByVal a As Long, _\n    ByVal b As Long)\n        Dim total As Long\n        count = 10\nEnd Sub\n";
+
+        assert_eq!(
+            declarations(source),
+            vec![
+                (
+                    "Declare 'count' as Long".to_string(),
+                    4,
+                    "        Dim count As Long\n".to_string()
+                ),
+                (
+                    "Declare 'count' as Variant".to_string(),
+                    4,
+                    "        Dim count As Variant\n".to_string()
+                ),
+                (
+                    "Declare 'count' as Private module variable".to_string(),
+                    1,
+                    "Private count As Long\n".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_declare_variable_without_assignment() {
+        let source = "Option Explicit\nSub Main()\n    MsgBox greeting\nEnd Sub\n";
+        let titles: Vec<String> = declarations(source)
+            .into_iter()
+            .map(|(title, _, _)| title)
+            .collect();
+
+        assert_eq!(
+            titles,
+            vec![
+                "Declare 'greeting' as Variant",
+                "Declare 'greeting' as Private module variable"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_duplicate_option_explicit() {
+        assert_eq!(inserted_at("option   explicit ' required\nDim x\n"), None);
+    }
+
+    /// Apply a refactoring's edits to a source (ASCII sources only)
+    fn apply(source: &str, action: CodeAction) -> String {
+        let uri = Url::parse("file:///test.bas").unwrap();
+        assert_eq!(action.kind, Some(CodeActionKind::REFACTOR));
+        let mut edits = action.edit.unwrap().changes.unwrap()[&uri].clone();
+        edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+
+        let offset = |pos: Position| {
+            let line_start: usize = source
+                .split_inclusive('\n')
+                .take(pos.line as usize)
+                .map(str::len)
+                .sum();
+            line_start + pos.character as usize
+        };
+        let mut result = source.to_string();
+        for edit in edits.iter().rev() {
+            result.replace_range(
+                offset(edit.range.start)..offset(edit.range.end),
+                &edit.new_text,
+            );
+        }
+        result
+    }
+
+    /// Apply the GoSub conversion at a line
+    fn convert(source: &str, line: u32) -> Option<String> {
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(uri.clone(), source, &tree);
+        let position = Position { line, character: 6 };
+
+        let action = convert_gosub(&uri, source, &tree, &table, position)?;
+        Some(apply(source, action))
+    }
+
+    /// Extract whole lines `first..=last` of a source, returning the title and result
+    fn extract(source: &str, first: u32, last: u32) -> Option<(String, String)> {
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(uri.clone(), source, &tree);
+        let range = Range {
+            start: Position {
+                line: first,
+                character: 0,
+            },
+            end: Position {
+                line: last + 1,
+                character: 0,
+            },
+        };
+
+        let action = extract_procedure(&uri, source, &tree, &table, range)?;
+        Some((action.title.clone(), apply(source, action)))
+    }
+
+    #[test]
+    fn test_convert_gosub_to_sub() {
+        let source = "Private Sub Main(ByVal count As Long)\n    Dim total As Long\n    GoSub AddCount\n    MsgBox total\n    Exit Sub\nAddCount:\n    total = total + count\n    Return\nEnd Sub\n";
+
+        assert_eq!(
+            convert(source, 2).unwrap(),
+            "Private Sub Main(ByVal count As Long)\n    Dim total As Long\n    AddCount total, count\n    MsgBox total\n    Exit Sub\nEnd Sub\n\nPrivate Sub AddCount(ByRef total As Long, ByRef count As Long)\n    total = total + count\nEnd Sub\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_gosub_without_locals() {
+        let source = "Sub Main()\n    GoSub Cleanup\nCleanup:\n    Beep\n    Return\nEnd Sub\n";
+
+        assert_eq!(
+            convert(source, 1).unwrap(),
+            "Sub Main()\n    Cleanup\nEnd Sub\n\nPrivate Sub Cleanup()\n    Beep\nEnd Sub\n"
+        );
+    }
+
+    #[test]
+    fn test_convert_gosub_bails_out() {
+        // Label targeted twice
+        let twice = "Sub Main()\n    GoSub Work\n    GoSub Work\n    Exit Sub\nWork:\n    Beep\n    Return\nEnd Sub\n";
+        assert_eq!(convert(twice, 1), None);
+
+        // Block leaves the procedure
+        let exits = "Sub Main()\n    GoSub Work\n    Exit Sub\nWork:\n    If x Then Exit Sub\n    Return\nEnd Sub\n";
+        assert_eq!(convert(exits, 1), None);
+
+        // Block jumps elsewhere
+        let jumps = "Sub Main()\n    GoSub Work\n    Exit Sub\nWork:\n    GoTo Done\n    Return\nDone:\nEnd Sub\n";
+        assert_eq!(convert(jumps, 1), None);
+    }
+
+    /// Titles and results of the return type actions for the cursor on a line
+    fn return_types(source: &str, line: u32) -> Vec<(String, String)> {
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(uri.clone(), source, &tree);
+        let position = Position { line, character: 2 };
+
+        declare_return_type(&uri, source, &tree, &table, position)
+            .into_iter()
+            .map(|action| (action.title.clone(), apply(source, action)))
+            .collect()
+    }
+
+    #[test]
+    fn test_declare_inferred_return_type() {
+        let source = "Private Function Total(ByVal a As Long, _\n    ByVal b As Long) ' sum\n    If a > 0 Then\n        Total = 0\n    Else\n        Total = a + b\n        Total = 100000\n    End If\nEnd Function\n";
+        let found = return_types(source, 0);
+
+        // `a + b` can't be inferred, so only Variant is offered
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, "Declare 'Total' As Variant");
+
+        let source = "Function Label(ByVal n As Long)\n    Dim s As String\n    If n = 0 Then\n        Label = \"none\"\n    Else\n        Label = s\n    End If\nEnd Function\n";
+        let found = return_types(source, 0);
+        assert_eq!(found[0].0, "Declare 'Label' As String");
+        assert_eq!(
+            found[0].1,
+            "Function Label(ByVal n As Long) As String\n    Dim s As String\n    If n = 0 Then\n        Label = \"none\"\n    Else\n        Label = s\n    End If\nEnd Function\n"
+        );
+        assert_eq!(found[1].0, "Declare 'Label' As Variant");
+
+        // Integer and Long literals widen to Long
+        let source = "Function Count\n    Count = 0\n    Count = 100000\nEnd Function\n";
+        assert_eq!(
+            return_types(source, 0)[0].1,
+            "Function Count As Long\n    Count = 0\n    Count = 100000\nEnd Function\n"
+        );
+    }
+
+    #[test]
+    fn test_declare_return_type_skips_typed_functions() {
+        let typed = "Function A() As Long\n    A = 1\nEnd Function\nFunction B$()\n    B = \"x\"\nEnd Function\n";
+        assert!(return_types(typed, 0).is_empty());
+        assert!(return_types(typed, 3).is_empty());
+
+        // Not on the signature
+        let body = "Function A()\n    A = 1\nEnd Function\n";
+        assert!(return_types(body, 1).is_empty());
+    }
+
+    #[test]
+    fn test_add_event_handler_stubs() {
+        let source = "Begin VB.Form frmMain\n\
+Begin VB.CommandButton cmdOK\n\
+End\n\
+Begin VB.TextBox txtName\n\
+Index = 0\n\
+End\n\
+End\n\
+Private Sub cmdOK_Click()\n\
+End Sub\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///frmMain.frm").unwrap(), source, &tree);
+        let handlers = |line, character| -> Vec<(String, String)> {
+            add_event_handlers(&uri, source, &table, Position { line, character })
+                .into_iter()
+                .map(|action| (action.title.clone(), apply(source, action)))
+                .collect()
+        };
+
+        // Click already has a handler
+        let button = handlers(1, 24);
+        assert!(button.iter().all(|(title, _)| title != "Add 'cmdOK_Click' event handler"));
+        assert!(button.iter().any(|(title, _)| title == "Add 'cmdOK_GotFocus' event handler"));
+
+        let (_, result) = handlers(3, 20)
+            .into_iter()
+            .find(|(title, _)| title == "Add 'txtName_KeyPress' event handler")
+            .unwrap();
+        assert!(result.ends_with(
+            "End Sub\n\nPrivate Sub txtName_KeyPress(Index As Integer, KeyAscii As Integer)\n\nEnd Sub\n"
+        ));
+
+        // Not on the form itself
+        assert!(handlers(0, 16).is_empty());
+    }
+
+    #[test]
+    fn test_use_named_value() {
+        let source = "Begin VB.Form frmMain\n\
+BackColor = &HFF&\n\
+Begin VB.Label lblTitle\n\
+Alignment = 2\n\
+BorderStyle = 1\n\
+End\n\
+End\n\
+Private Sub Form_Load()\n\
+lblTitle.ForeColor = &H80000012&\n\
+lblTitle.Enabled = -1\n\
+lblTitle.Alignment = 7\n\
+End Sub\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///frmMain.frm").unwrap(), source, &tree);
+        let replace = |line, character| -> Vec<(String, String)> {
+            use_named_value(&uri, source, &tree, Some(&table), Position { line, character })
+                .into_iter()
+                .map(|action| {
+                    assert_eq!(action.is_preferred, Some(true));
+                    let result = apply(source, action.clone());
+                    (action.title, result.lines().nth(line as usize).unwrap().to_string())
+                })
+                .collect()
+        };
+
+        assert_eq!(replace(1, 3), vec![("Replace with vbRed".into(), "BackColor = vbRed".into())]);
+        assert_eq!(
+            replace(3, 13),
+            vec![("Replace with vbCenter".into(), "Alignment = vbCenter".into())]
+        );
+        assert_eq!(
+            replace(8, 0),
+            vec![("Replace with vbButtonText".into(), "lblTitle.ForeColor = vbButtonText".into())]
+        );
+        assert_eq!(
+            replace(9, 20),
+            vec![("Replace with True".into(), "lblTitle.Enabled = True".into())]
+        );
+
+        // Values with only descriptive names, and values with no name at all
+        assert!(replace(4, 3).is_empty());
+        assert!(replace(10, 3).is_empty());
+        assert!(replace(7, 3).is_empty());
+    }
+
+    #[test]
+    fn test_extract_to_function() {
+        let source = "Private Sub Main(ByVal price As Double)\n    Dim tax As Double\n    Dim rate As Double\n    rate = 0.2\n    tax = price * rate\n    tax = tax + 1\n    MsgBox tax\nEnd Sub\n";
+
+        let (title, result) = extract(source, 4, 5).unwrap();
+        assert_eq!(title, "Extract to Function 'ExtractedProc'");
+        assert_eq!(
+            result,
+            "Private Sub Main(ByVal price As Double)\n    Dim tax As Double\n    Dim rate As Double\n    rate = 0.2\n    tax = ExtractedProc(price, rate)\n    MsgBox tax\nEnd Sub\n\nPrivate Function ExtractedProc(ByVal price As Double, ByVal rate As Double) As Double\n    Dim tax As Double\n    tax = price * rate\n    tax = tax + 1\n    ExtractedProc = tax\nEnd Function\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_to_sub_with_by_ref_outputs() {
+        let source = "Sub Main()\n    Dim a As Long, b As Long\n    If True Then\n        a = 1\n        b = a + 1\n    End If\n    MsgBox a + b\nEnd Sub\n";
+
+        let (title, result) = extract(source, 3, 4).unwrap();
+        assert_eq!(title, "Extract to Sub 'ExtractedProc'");
+        assert_eq!(
+            result,
+            "Sub Main()\n    Dim a As Long, b As Long\n    If True Then\n        ExtractedProc a, b\n    End If\n    MsgBox a + b\nEnd Sub\n\nPrivate Sub ExtractedProc(ByRef a As Long, ByRef b As Long)\n    a = 1\n    b = a + 1\nEnd Sub\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_refuses_block_boundaries() {
+        let source = "Sub Main()\n    Dim a As Long\n    If True Then\n        a = 1\n    End If\n    For a = 1 To 3\n        If a = 2 Then Exit For\n    Next\nEnd Sub\n";
+
+        // Starts inside the If, ends after it
+        assert_eq!(extract(source, 3, 5), None);
+        // Exits a loop outside the selection
+        assert_eq!(extract(source, 6, 6), None);
+        // The whole If is fine
+        assert!(extract(source, 2, 4).is_some());
+    }
+
+    #[test]
+    fn test_organize_declarations() {
+        let source = "Attribute VB_Name = \"Module1\"\nOption Explicit\n\nPrivate mCount As Long\n' Shared with the forms\nPublic gUser As String\nConst MAX_ITEMS = 10\nPrivate Type Point\n    X As Long\nEnd Type\nDim mAlpha As Integer ' first\nPublic Const LIMIT = MAX_ITEMS * 2\nPublic Declare Function GetTickCount Lib \"kernel32\" () As Long\n\nSub Main()\n    Dim z As Long\nEnd Sub\n";
+
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let action = organize_declarations(&uri, source, &tree).unwrap();
+        assert_eq!(action.kind, Some(ORGANIZE_DECLARATIONS));
+
+        let edit = &action.edit.unwrap().changes.unwrap()[&uri][0];
+        assert_eq!((edit.range.start.line, edit.range.end.line), (3, 13));
+        assert_eq!(
+            edit.new_text,
+            "Const MAX_ITEMS = 10\nPublic Const LIMIT = MAX_ITEMS * 2\n\nPrivate Type Point\n    X As Long\nEnd Type\n\nPublic Declare Function GetTickCount Lib \"kernel32\" () As Long\n\n' Shared with the forms\nPublic gUser As String\n\nDim mAlpha As Integer ' first\nPrivate mCount As Long\n"
+        );
+
+        // Already organized
+        let organized = "Const A = 1\n\nDim b As Long\n\nSub Main()\nEnd Sub\n";
+        let tree = parser.parse(organized, None).unwrap();
+        assert!(organize_declarations(&uri, organized, &tree).is_none());
+    }
+
+    #[test]
+    fn test_add_and_remove_set() {
+        let source = "Sub Main()\n    Let items = New Collection\n    Set n =  5\nEnd Sub\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let edit = |action: Option<CodeAction>| {
+            let changes = action.unwrap().edit.unwrap().changes.unwrap();
+            let edit = &changes[&uri][0];
+            (edit.range.start.character, edit.range.end.character, edit.new_text.clone())
+        };
+
+        let missing = Diagnostic {
+            range: Range::new(Position::new(1, 4), Position::new(1, 30)),
+            ..Default::default()
+        };
+        assert_eq!(edit(add_set(&uri, source, &missing)), (4, 8, "Set ".to_string()));
+
+        let on_value = Diagnostic {
+            range: Range::new(Position::new(2, 4), Position::new(2, 7)),
+            ..Default::default()
+        };
+        assert_eq!(edit(remove_set(&uri, source, &on_value)), (4, 8, String::new()));
+    }
+
+    #[test]
+    fn test_disable_for_line() {
+        let source = "Sub Main()\n    s = a + b\n    'vb6-lsp-disable-next-line undeclared-variable\n\
+    t = s + c\n    u = 1 + _\n        v\nEnd Sub\n";
+        let uri = Url::parse("file:///test.bas").unwrap();
+        let diagnostic = |line| Diagnostic {
+            range: Range::new(Position::new(line, 4), Position::new(line, 9)),
+            code: Some(NumberOrString::String("plus-concatenation".to_string())),
+            ..Default::default()
+        };
+        let edit = |line| {
+            let action = disable_for_line(&uri, source, &diagnostic(line)).unwrap();
+            assert_eq!(action.title, "Disable plus-concatenation for this line");
+            let changes = action.edit.unwrap().changes.unwrap();
+            let edit = &changes[&uri][0];
+            (edit.range.start.line, edit.range.start.character, edit.new_text.clone())
+        };
+
+        assert_eq!(
+            edit(1),
+            (1, 0, "    'vb6-lsp-disable-next-line plus-concatenation\n".to_string())
+        );
+        assert_eq!(edit(3), (2, 50, ", plus-concatenation".to_string()));
+        assert!(disable_for_line(&uri, source, &diagnostic(5)).is_none());
+    }
+}
@@ -0,0 +1,127 @@
+//! String Concatenation Checking
+//!
+//! Flags `+` used to join strings. When the other operand holds a number VB6
+//! adds instead of concatenating (or raises "Type mismatch"), while `&`
+//! always converts both sides to strings.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::inference::infer_expression_type;
+use super::position::{SourcePosition, SourceRange};
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for `+` with a String operand
+pub const PLUS_CONCATENATION: &str = "plus-concatenation";
+
+/// Report every `+` that has an operand known to be a String
+pub fn find_plus_concatenation(table: &SymbolTable, tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        if let Some(operator) = operator(&node).filter(|op| op.kind() == "+") {
+            let operands = [node.named_child(0), node.named_child(1)];
+            if operands.iter().flatten().any(|operand| is_string(operand, table, source)) {
+                diagnostics.push(Diagnostic {
+                    range: SourceRange::from_ts_node(&operator).to_lsp(),
+                    severity: Some(DiagnosticSeverity::INFORMATION),
+                    code: Some(NumberOrString::String(PLUS_CONCATENATION.to_string())),
+                    message: "Use '&' to concatenate strings; '+' adds when an operand is numeric"
+                        .to_string(),
+                    source: Some("vb6-lsp".to_string()),
+                    ..Default::default()
+                });
+            }
+        }
+
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+/// The operator token of a binary expression
+fn operator<'t>(node: &Node<'t>) -> Option<Node<'t>> {
+    if node.kind() != "binary_expression" {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let operator = node.children(&mut cursor).find(|child| !child.is_named());
+    operator
+}
+
+/// Whether an expression is known to produce a String
+fn is_string(node: &Node, table: &SymbolTable, source: &str) -> bool {
+    match node.kind() {
+        "parenthesized_expression" => {
+            node.named_child(0).is_some_and(|inner| is_string(&inner, table, source))
+        }
+        // `a & b` is always a String; `a + b` is one when it concatenates
+        "binary_expression" => match operator(node).map(|op| op.kind()) {
+            Some("&") => true,
+            Some("+") => {
+                let mut cursor = node.walk();
+                let is_concatenation = node
+                    .named_children(&mut cursor)
+                    .any(|operand| is_string(&operand, table, source));
+                is_concatenation
+            }
+            _ => false,
+        },
+        // `name$` and `Left$(...)` are Strings by their type character
+        "typed_identifier" | "call_expression" if has_string_hint(node, source) => true,
+        _ => {
+            let position = SourcePosition::from_ts_point(node.start_position());
+            let scope = table.scope_at_position(position);
+            infer_expression_type(table, scope, node, source)
+                .is_some_and(|t| !t.is_array && t.name.eq_ignore_ascii_case("string"))
+        }
+    }
+}
+
+fn has_string_hint(node: &Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    let has_hint = node
+        .named_children(&mut cursor)
+        .any(|child| child.kind() == "type_hint" && child.utf8_text(source.as_bytes()) == Ok("$"));
+    has_hint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    fn flagged(source: &str) -> Vec<(u32, u32)> {
+        run_pass(source, |table, tree| find_plus_concatenation(table, tree, source))
+            .into_iter()
+            .map(|d| (d.range.start.line, d.range.start.character))
+            .collect()
+    }
+
+    #[test]
+    fn test_flags_string_operands() {
+        let source = "Function Greeting() As String\nEnd Function\n\
+Sub Main()\n    Dim s As String\n    Dim n As Long\n\
+msg = \"Total: \" + n + \" items\"\n\
+msg = s + Greeting()\n\
+msg = Left$(s, 1) + n\n\
+End Sub\n";
+
+        assert_eq!(flagged(source), vec![(5, 16), (5, 20), (6, 8), (7, 18)]);
+    }
+
+    #[test]
+    fn test_ignores_numeric_and_unknown_operands() {
+        let source = "Sub Main()\n    Dim a As Long\n    Dim v\n\
+total = a + 1\n\
+total = v + Unknown(2)\n\
+total = (a + 2) * 3\n\
+End Sub\n";
+
+        assert!(flagged(source).is_empty());
+    }
+}
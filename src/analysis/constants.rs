@@ -0,0 +1,476 @@
+//! Constant Evaluation
+//!
+//! Folds the values of `Const` declarations and enum members so hover can
+//! show `Const MAX = 10 * 2` as 20. Expressions may refer to other constants
+//! and enum members of the module; enum members without a value continue
+//! from the previous member. Constants that depend on themselves are reported.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::position::SourcePosition;
+use super::scope::ScopeId;
+use super::symbol::{SymbolId, SymbolKind};
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for a constant whose value depends on itself
+pub const CIRCULAR_CONSTANT: &str = "circular-constant";
+
+/// The value of a constant expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Integer(i64),
+    Double(f64),
+    String(String),
+    Boolean(bool),
+}
+
+impl ConstValue {
+    /// The VB6 type of the value, as an untyped `Const` would get it
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ConstValue::Integer(n) if i16::try_from(*n).is_ok() => "Integer",
+            ConstValue::Integer(n) if i32::try_from(*n).is_ok() => "Long",
+            ConstValue::Integer(_) | ConstValue::Double(_) => "Double",
+            ConstValue::String(_) => "String",
+            ConstValue::Boolean(_) => "Boolean",
+        }
+    }
+
+    /// The value as a whole number (True is -1)
+    fn as_integer(&self) -> Option<i64> {
+        match self {
+            ConstValue::Integer(n) => Some(*n),
+            ConstValue::Boolean(b) => Some(if *b { -1 } else { 0 }),
+            _ => None,
+        }
+    }
+
+    fn as_double(&self) -> Option<f64> {
+        match self {
+            ConstValue::Double(d) => Some(*d),
+            other => other.as_integer().map(|n| n as f64),
+        }
+    }
+
+    /// The value converted to a String, as `&` does
+    fn as_text(&self) -> String {
+        match self {
+            ConstValue::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstValue::Integer(n) => write!(f, "{}", n),
+            ConstValue::Double(d) => write!(f, "{}", d),
+            ConstValue::String(s) => write!(f, "\"{}\"", s.replace('"', "\"\"")),
+            ConstValue::Boolean(true) => write!(f, "True"),
+            ConstValue::Boolean(false) => write!(f, "False"),
+        }
+    }
+}
+
+/// How the value of a constant or enum member is given
+#[derive(Clone, Copy)]
+enum Definition<'t> {
+    Expression(Node<'t>),
+    /// Enum member without a value: one more than the previous member, or 0
+    Next(Option<SymbolId>),
+}
+
+/// Evaluate every constant and enum member in the table, storing the values
+/// on their symbols and recording the constants that depend on themselves
+pub fn evaluate_constants(table: &mut SymbolTable, tree: &Tree, source: &str) {
+    let definitions = collect_definitions(table, tree);
+    let mut evaluator = Evaluator {
+        table,
+        source,
+        definitions: &definitions,
+        values: HashMap::new(),
+        visiting: Vec::new(),
+        circular: HashSet::new(),
+    };
+    let mut ids: Vec<SymbolId> = definitions.keys().copied().collect();
+    ids.sort_by_key(|id| id.0);
+    for &id in &ids {
+        evaluator.value_of(id);
+    }
+
+    let Evaluator {
+        values, circular, ..
+    } = evaluator;
+    for (id, value) in values {
+        if let Some(value) = value {
+            table.set_resolved_value(id, value);
+        }
+    }
+    let mut circular: Vec<SymbolId> = circular.into_iter().collect();
+    circular.sort_by_key(|id| id.0);
+    for id in circular {
+        table.mark_circular_constant(id);
+    }
+}
+
+/// Evaluate an expression outside a declaration (e.g., the value of an
+/// assignment), using the values already stored on constants
+pub fn evaluate_expression(
+    table: &SymbolTable,
+    node: &Node,
+    scope: ScopeId,
+    source: &str,
+) -> Option<ConstValue> {
+    let definitions = HashMap::new();
+    let mut evaluator = Evaluator {
+        table,
+        source,
+        definitions: &definitions,
+        values: HashMap::new(),
+        visiting: Vec::new(),
+        circular: HashSet::new(),
+    };
+    evaluator.evaluate(node, scope)
+}
+
+/// Report constants whose definition refers back to themselves
+pub fn find_circular_constants(table: &SymbolTable) -> Vec<Diagnostic> {
+    table
+        .circular_constants()
+        .iter()
+        .filter_map(|&id| table.get_symbol(id))
+        .map(|symbol| Diagnostic {
+            range: symbol.name_range.to_lsp(),
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: Some(NumberOrString::String(CIRCULAR_CONSTANT.to_string())),
+            message: format!("Circular reference in the value of constant '{}'", symbol.name),
+            source: Some("vb6-lsp".to_string()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Map the constants and enum members of the table to their definitions
+fn collect_definitions<'t>(
+    table: &SymbolTable,
+    tree: &'t Tree,
+) -> HashMap<SymbolId, Definition<'t>> {
+    let by_name: HashMap<SourcePosition, SymbolId> = table
+        .all_symbols()
+        .filter(|s| {
+            matches!(
+                s.kind,
+                SymbolKind::Constant | SymbolKind::LocalConstant | SymbolKind::EnumMember
+            )
+        })
+        .map(|s| (s.name_range.start, s.id))
+        .collect();
+    let symbol_for = |node: &Node| {
+        let name = node.child_by_field_name("name")?;
+        by_name.get(&SourcePosition::from_ts_point(name.start_position())).copied()
+    };
+
+    let mut definitions = HashMap::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            "constant_declarator" => {
+                let value = node.child_by_field_name("value");
+                if let (Some(id), Some(value)) = (symbol_for(&node), value) {
+                    definitions.insert(id, Definition::Expression(value));
+                }
+            }
+            "enum_declaration" => {
+                let mut previous = None;
+                let mut cursor = node.walk();
+                for member in node.named_children(&mut cursor) {
+                    if member.kind() != "enum_member" {
+                        continue;
+                    }
+                    let Some(id) = symbol_for(&member) else {
+                        continue;
+                    };
+                    let definition = match member.child_by_field_name("value") {
+                        Some(value) => Definition::Expression(value),
+                        None => Definition::Next(previous),
+                    };
+                    definitions.insert(id, definition);
+                    previous = Some(id);
+                }
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    definitions
+}
+
+struct Evaluator<'a, 't> {
+    table: &'a SymbolTable,
+    source: &'a str,
+    definitions: &'a HashMap<SymbolId, Definition<'t>>,
+    values: HashMap<SymbolId, Option<ConstValue>>,
+    /// Symbols whose value is being computed, outermost first
+    visiting: Vec<SymbolId>,
+    circular: HashSet<SymbolId>,
+}
+
+impl Evaluator<'_, '_> {
+    fn value_of(&mut self, id: SymbolId) -> Option<ConstValue> {
+        if let Some(value) = self.values.get(&id) {
+            return value.clone();
+        }
+        if let Some(start) = self.visiting.iter().position(|&v| v == id) {
+            self.circular.extend(&self.visiting[start..]);
+            return None;
+        }
+        // Outside evaluate_constants, constants already have their values
+        let Some(&definition) = self.definitions.get(&id) else {
+            return self.table.get_symbol(id)?.resolved_value.clone();
+        };
+        let scope = self.table.get_symbol(id)?.scope_id;
+
+        self.visiting.push(id);
+        let value = match definition {
+            Definition::Expression(node) => self.evaluate(&node, scope),
+            Definition::Next(None) => Some(ConstValue::Integer(0)),
+            Definition::Next(Some(previous)) => self
+                .value_of(previous)
+                .and_then(|v| v.as_integer())
+                .map(|n| ConstValue::Integer(n + 1)),
+        };
+        self.visiting.pop();
+
+        // Every member of a cycle gets no value, however it was entered
+        let value = value.filter(|_| !self.circular.contains(&id));
+        self.values.insert(id, value.clone());
+        value
+    }
+
+    fn evaluate(&mut self, node: &Node, scope: ScopeId) -> Option<ConstValue> {
+        let text = node.utf8_text(self.source.as_bytes()).ok()?;
+        match node.kind() {
+            "literal" | "parenthesized_expression" => self.evaluate(&node.named_child(0)?, scope),
+            "integer_literal" => parse_integer(text),
+            "float_literal" => text
+                .trim_end_matches(['!', '#', '@'])
+                .parse()
+                .ok()
+                .map(ConstValue::Double),
+            "string_literal" => {
+                let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+                Some(ConstValue::String(inner.replace("\"\"", "\"")))
+            }
+            "boolean_literal" => Some(ConstValue::Boolean(text.eq_ignore_ascii_case("true"))),
+            "identifier" => {
+                let symbol = self.table.lookup_symbol(text, scope)?;
+                if !matches!(
+                    symbol.kind,
+                    SymbolKind::Constant | SymbolKind::LocalConstant | SymbolKind::EnumMember
+                ) {
+                    return None;
+                }
+                self.value_of(symbol.id)
+            }
+            // `Colors.Red`
+            "member_expression" => {
+                let object = node.child_by_field_name("object")?;
+                let member = node.child_by_field_name("member")?;
+                let object = object.utf8_text(self.source.as_bytes()).ok()?;
+                let member = member.utf8_text(self.source.as_bytes()).ok()?;
+                let enumeration = self.table.lookup_symbol(object, scope)?;
+                if enumeration.kind != SymbolKind::Enum {
+                    return None;
+                }
+                let id = enumeration.members.iter().copied().find(|&id| {
+                    self.table
+                        .get_symbol(id)
+                        .is_some_and(|m| m.name.eq_ignore_ascii_case(member))
+                })?;
+                self.value_of(id)
+            }
+            "unary_expression" => {
+                let operator = operator_text(node, self.source)?;
+                let operand = self.evaluate(&node.named_child(0)?, scope)?;
+                unary(&operator, operand)
+            }
+            "binary_expression" => {
+                let operator = operator_text(node, self.source)?;
+                let left = self.evaluate(&node.named_child(0)?, scope)?;
+                let right = self.evaluate(&node.named_child(1)?, scope)?;
+                binary(&operator, left, right)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The lowercased operator token of a unary or binary expression
+fn operator_text(node: &Node, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let operator = node.children(&mut cursor).find(|child| !child.is_named())?;
+    Some(operator.utf8_text(source.as_bytes()).ok()?.to_lowercase())
+}
+
+/// Parse a decimal, `&H` or `&O` literal; hex and octal literals wrap around
+/// to negative values like VB6 does (`&HFFFF` is -1, `&HFFFF&` is 65535)
+fn parse_integer(text: &str) -> Option<ConstValue> {
+    let upper = text.to_uppercase();
+    let is_long = upper.ends_with('&');
+    let digits = upper.trim_end_matches(['%', '&', '!', '#', '@']);
+    let radix = if let Some(hex) = digits.strip_prefix("&H") {
+        Some((hex, 16))
+    } else {
+        digits.strip_prefix("&O").map(|octal| (octal, 8))
+    };
+    let Some((digits, radix)) = radix else {
+        return digits.parse().ok().map(ConstValue::Integer);
+    };
+
+    let value = i64::from_str_radix(digits, radix).ok()?;
+    let value = if value <= 0xFFFF && !is_long {
+        value as u16 as i16 as i64
+    } else {
+        value as u32 as i32 as i64
+    };
+    Some(ConstValue::Integer(value))
+}
+
+fn unary(operator: &str, operand: ConstValue) -> Option<ConstValue> {
+    match (operator, operand) {
+        ("not", ConstValue::Boolean(b)) => Some(ConstValue::Boolean(!b)),
+        ("not", operand) => operand.as_integer().map(|n| ConstValue::Integer(!n)),
+        ("-", ConstValue::Double(d)) => Some(ConstValue::Double(-d)),
+        ("-", operand) => operand.as_integer().map(|n| ConstValue::Integer(-n)),
+        ("+", operand) => Some(operand),
+        _ => None,
+    }
+}
+
+fn binary(operator: &str, left: ConstValue, right: ConstValue) -> Option<ConstValue> {
+    use ConstValue::{Boolean, Double, Integer};
+
+    if operator == "&" {
+        return Some(ConstValue::String(left.as_text() + &right.as_text()));
+    }
+    if let (ConstValue::String(l), ConstValue::String(r)) = (&left, &right) {
+        return match operator {
+            "+" => Some(ConstValue::String(format!("{}{}", l, r))),
+            "=" => Some(Boolean(l == r)),
+            "<>" => Some(Boolean(l != r)),
+            _ => None,
+        };
+    }
+    if let (Boolean(l), Boolean(r)) = (&left, &right) {
+        match operator {
+            "and" => return Some(Boolean(*l && *r)),
+            "or" => return Some(Boolean(*l || *r)),
+            "xor" => return Some(Boolean(*l != *r)),
+            _ => {}
+        }
+    }
+
+    let integers = left.as_integer().zip(right.as_integer());
+    if let Some((l, r)) = integers {
+        let value = match operator {
+            "+" => l.checked_add(r),
+            "-" => l.checked_sub(r),
+            "*" => l.checked_mul(r),
+            "\\" => l.checked_div(r),
+            "mod" => l.checked_rem(r),
+            "and" => Some(l & r),
+            "or" => Some(l | r),
+            "xor" => Some(l ^ r),
+            _ => None,
+        };
+        if let Some(value) = value {
+            return Some(Integer(value));
+        }
+    }
+
+    let (l, r) = left.as_double().zip(right.as_double())?;
+    let value = match operator {
+        "+" => l + r,
+        "-" => l - r,
+        "*" => l * r,
+        "/" if r != 0.0 => l / r,
+        "^" => l.powf(r),
+        "=" => return Some(Boolean(l == r)),
+        "<>" => return Some(Boolean(l != r)),
+        "<" => return Some(Boolean(l < r)),
+        ">" => return Some(Boolean(l > r)),
+        "<=" => return Some(Boolean(l <= r)),
+        ">=" => return Some(Boolean(l >= r)),
+        _ => return None,
+    };
+    Some(Double(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::build_symbol_table;
+    use crate::parser::TreeSitterVb6Parser;
+    use tower_lsp::lsp_types::Url;
+
+    fn build(source: &str) -> SymbolTable {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        build_symbol_table(Url::parse("file:///test.bas").unwrap(), source, &tree)
+    }
+
+    fn value(table: &SymbolTable, name: &str) -> Option<String> {
+        let symbol = table.lookup_symbol(name, table.module_scope)?;
+        symbol.resolved_value.as_ref().map(|v| v.to_string())
+    }
+
+    #[test]
+    fn test_evaluates_constant_expressions() {
+        let source = "Const MAX_ITEMS = LIMIT * 2\n\
+Private Const LIMIT = (3 + 2) Mod 4 + &H10\n\
+Public Const TITLE = \"Say \"\"hi\"\" \" & LIMIT * 2\n\
+Const RATIO = 3 / 4\n\
+Const MASK = &HFFFF\n\
+Const FLAGS = Red Or Colors.Blue\n\
+Const UNKNOWN = OtherModule.Value + 1\n\
+Enum Colors\n    Red = 1\n    Green\n    Blue = Green * 2\n    Alpha\nEnd Enum\n";
+        let table = build(source);
+
+        assert_eq!(value(&table, "LIMIT").as_deref(), Some("17"));
+        assert_eq!(value(&table, "MAX_ITEMS").as_deref(), Some("34"));
+        assert_eq!(value(&table, "TITLE").as_deref(), Some("\"Say \"\"hi\"\" 34\""));
+        assert_eq!(value(&table, "RATIO").as_deref(), Some("0.75"));
+        assert_eq!(value(&table, "MASK").as_deref(), Some("-1"));
+        assert_eq!(value(&table, "Green").as_deref(), Some("2"));
+        assert_eq!(value(&table, "Alpha").as_deref(), Some("5"));
+        assert_eq!(value(&table, "FLAGS").as_deref(), Some("5"));
+        assert_eq!(value(&table, "UNKNOWN"), None);
+        assert!(find_circular_constants(&table).is_empty());
+    }
+
+    #[test]
+    fn test_reports_circular_constants() {
+        let source = "Const A = B + 1\nConst B = A * 2\nConst C = A\nConst D = D\n";
+        let table = build(source);
+
+        let circular: Vec<(u32, String)> = find_circular_constants(&table)
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect();
+        assert_eq!(
+            circular,
+            vec![
+                (0, "Circular reference in the value of constant 'A'".to_string()),
+                (1, "Circular reference in the value of constant 'B'".to_string()),
+                (3, "Circular reference in the value of constant 'D'".to_string()),
+            ]
+        );
+        assert_eq!(value(&table, "C"), None);
+    }
+}
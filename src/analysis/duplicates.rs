@@ -0,0 +1,121 @@
+//! Duplicate Procedure Detection
+//!
+//! Two procedures of one module with the same name do not compile
+//! ("Ambiguous name detected"). The only exception is a property, whose
+//! `Get`, `Let`, and `Set` share a name. Every definition is reported,
+//! pointing at the others.
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString,
+};
+
+use super::symbol::{Symbol, SymbolKind};
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for a procedure name defined twice in one module
+pub const DUPLICATE_PROCEDURE: &str = "duplicate-procedure";
+
+/// Report the procedures of a module that share a name
+pub fn find_duplicate_procedures(table: &SymbolTable) -> Vec<Diagnostic> {
+    // Lowercase name -> procedures, in declaration order
+    let mut procedures: HashMap<String, Vec<&Symbol>> = HashMap::new();
+    for symbol in table.all_symbols() {
+        if symbol.scope_id == table.module_scope && is_procedure(symbol.kind) {
+            procedures.entry(symbol.name.to_lowercase()).or_default().push(symbol);
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for group in procedures.values().filter(|group| group.len() > 1) {
+        for symbol in group {
+            let others: Vec<&Symbol> = group
+                .iter()
+                .copied()
+                .filter(|other| other.id != symbol.id && conflicts(symbol.kind, other.kind))
+                .collect();
+            if others.is_empty() {
+                continue;
+            }
+
+            let related = others
+                .iter()
+                .map(|other| DiagnosticRelatedInformation {
+                    location: Location::new(table.uri.clone(), other.name_range.to_lsp()),
+                    message: format!("'{}' is also defined here", other.name),
+                })
+                .collect();
+            diagnostics.push(Diagnostic {
+                range: symbol.name_range.to_lsp(),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String(DUPLICATE_PROCEDURE.to_string())),
+                message: format!(
+                    "Ambiguous name: '{}' is defined more than once in this module",
+                    symbol.name
+                ),
+                source: Some("vb6-lsp".to_string()),
+                related_information: Some(related),
+                ..Default::default()
+            });
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+fn is_procedure(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Sub
+            | SymbolKind::Function
+            | SymbolKind::PropertyGet
+            | SymbolKind::PropertyLet
+            | SymbolKind::PropertySet
+            | SymbolKind::DeclareSub
+            | SymbolKind::DeclareFunction
+    )
+}
+
+fn is_property(kind: SymbolKind) -> bool {
+    matches!(kind, SymbolKind::PropertyGet | SymbolKind::PropertyLet | SymbolKind::PropertySet)
+}
+
+/// Whether two procedures of the same name cannot both exist; the different
+/// accessors of one property can
+fn conflicts(a: SymbolKind, b: SymbolKind) -> bool {
+    !(is_property(a) && is_property(b) && a != b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    #[test]
+    fn test_duplicate_procedures() {
+        let source = "Public Sub Save()\n\
+End Sub\n\
+Private Function save() As Boolean\n\
+End Function\n\
+Property Get Count() As Long\n\
+End Property\n\
+Property Let Count(ByVal value As Long)\n\
+End Property\n\
+Property Get Name() As String\n\
+End Property\n\
+Property Get Name() As String\n\
+End Property\n\
+Sub Other()\n\
+Dim Save As Long\n\
+End Sub\n";
+        let diagnostics = run_pass(source, |table, _| find_duplicate_procedures(table));
+        let lines: Vec<u32> = diagnostics.iter().map(|d| d.range.start.line).collect();
+        assert_eq!(lines, vec![0, 2, 8, 10]);
+
+        let related = diagnostics[0].related_information.as_ref().unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.range.start.line, 2);
+    }
+}
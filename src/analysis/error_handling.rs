@@ -0,0 +1,226 @@
+//! Error Handling Checks
+//!
+//! Follows the `On Error` statements of each procedure in source order and
+//! flags an `On Error Resume Next` left active over the statements after it,
+//! which silently swallows every error they raise. Jump targets are checked
+//! with the other labels in `labels.rs`.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::position::SourceRange;
+
+/// Diagnostic code for `On Error Resume Next` that is never turned off
+pub const RESUME_NEXT_NOT_RESET: &str = "resume-next-not-reset";
+
+/// How many statements may run under `On Error Resume Next` before it is
+/// reported; a couple of guarded calls followed by `On Error GoTo 0` is the
+/// usual idiom
+const RESUME_NEXT_STATEMENT_LIMIT: usize = 3;
+
+/// Statements that do nothing at run time
+const NON_EXECUTABLE: &[&str] = &[
+    "comment",
+    "label",
+    "variable_declaration",
+    "constant_declaration",
+    "preproc_if_statement",
+];
+
+const PROCEDURES: &[&str] = &["sub_declaration", "function_declaration", "property_declaration"];
+
+/// The effect of an `On Error` statement
+enum ErrorMode {
+    ResumeNext,
+    Disabled,
+    GoTo,
+}
+
+/// Check the `On Error` statements of every procedure
+pub fn find_error_handling_problems(tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        if PROCEDURES.contains(&node.kind()) {
+            check_procedure(&node, source, &mut diagnostics);
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+fn check_procedure(procedure: &Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let name = procedure
+        .child_by_field_name("name")
+        .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+        .unwrap_or_default();
+    let Some(block) = named_child_of_kind(procedure, "block") else {
+        return;
+    };
+
+    let mut statements = Vec::new();
+    collect_statements(&block, &mut statements);
+
+    // The active Resume Next and how many statements have run under it
+    let mut resume_next: Option<(Node, usize)> = None;
+    for statement in &statements {
+        if statement.kind() == "on_error_statement" {
+            match error_mode(statement, source) {
+                ErrorMode::ResumeNext => {
+                    resume_next.get_or_insert((*statement, 0));
+                }
+                ErrorMode::Disabled | ErrorMode::GoTo => resume_next = None,
+            }
+            continue;
+        }
+
+        if let Some((_, count)) = resume_next.as_mut() {
+            if !NON_EXECUTABLE.contains(&statement.kind()) {
+                *count += 1;
+            }
+        }
+    }
+
+    if let Some((statement, count)) = resume_next {
+        if count > RESUME_NEXT_STATEMENT_LIMIT {
+            diagnostics.push(diagnostic(
+                &statement,
+                source,
+                DiagnosticSeverity::INFORMATION,
+                RESUME_NEXT_NOT_RESET,
+                format!(
+                    "On Error Resume Next stays active for the rest of '{}' and hides any \
+                     error; turn it off with On Error GoTo 0",
+                    name
+                ),
+            ));
+        }
+    }
+}
+
+/// The statements of a block and of the blocks nested in it, in source order
+fn collect_statements<'a>(block: &Node<'a>, statements: &mut Vec<Node<'a>>) {
+    let mut cursor = block.walk();
+    for statement in block.named_children(&mut cursor) {
+        statements.push(statement);
+        let mut inner = statement.walk();
+        let nested: Vec<Node> = statement.named_children(&mut inner).collect();
+        for child in nested {
+            collect_nested_blocks(&child, statements);
+        }
+    }
+}
+
+/// Statements of the blocks below a node (If branches, Case clauses, loop bodies)
+fn collect_nested_blocks<'a>(node: &Node<'a>, statements: &mut Vec<Node<'a>>) {
+    if node.kind() == "block" {
+        collect_statements(node, statements);
+        return;
+    }
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    for child in children {
+        collect_nested_blocks(&child, statements);
+    }
+}
+
+fn error_mode(statement: &Node, source: &str) -> ErrorMode {
+    match statement.named_child(0) {
+        Some(target) if target.utf8_text(source.as_bytes()) != Ok("0") => ErrorMode::GoTo,
+        Some(_) => ErrorMode::Disabled,
+        None => {
+            let text = statement.utf8_text(source.as_bytes()).unwrap_or_default();
+            if text.to_lowercase().contains("resume") {
+                ErrorMode::ResumeNext
+            } else {
+                ErrorMode::Disabled
+            }
+        }
+    }
+}
+
+fn named_child_of_kind<'a>(node: &Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    let child = node.named_children(&mut cursor).find(|c| c.kind() == kind);
+    child
+}
+
+fn diagnostic(
+    node: &Node,
+    source: &str,
+    severity: DiagnosticSeverity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    let mut range = SourceRange::from_ts_node(node).to_lsp();
+    // A statement ends at column 0 of the next line, after its terminator
+    if range.end.line > range.start.line && range.end.character == 0 {
+        let line = source.lines().nth(range.start.line as usize).unwrap_or_default();
+        range.end.line = range.start.line;
+        range.end.character = line.trim_end().len() as u32;
+    }
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: Some(NumberOrString::String(code.to_string())),
+        message,
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn problems(source: &str) -> Vec<(u32, String)> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        find_error_handling_problems(&tree, source)
+            .into_iter()
+            .map(|d| match d.code {
+                Some(NumberOrString::String(code)) => (d.range.start.line, code),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resume_next_left_active() {
+        let source = "Sub Main()\n\
+On Error Resume Next\n\
+x = 1\n\
+If x Then\n\
+y = 2\n\
+End If\n\
+z = 3\n\
+End Sub\n";
+        assert_eq!(problems(source), vec![(1, RESUME_NEXT_NOT_RESET.to_string())]);
+    }
+
+    #[test]
+    fn test_resume_next_reset() {
+        let source = "Sub Main()\n\
+Dim x As Long\n\
+On Error Resume Next\n\
+Kill \"a.tmp\"\n\
+On Error GoTo 0\n\
+x = 1\n\
+x = 2\n\
+x = 3\n\
+x = 4\n\
+End Sub\n\
+Sub Short()\n\
+On Error Resume Next\n\
+' only a guarded call or two\n\
+Kill \"b.tmp\"\n\
+End Sub\n";
+        assert!(problems(source).is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+//! Event Handler Checking
+//!
+//! In the code behind a form, `Private Sub cmdOK_Click()` handles the Click
+//! event of the control `cmdOK`. VB6 silently treats a handler for a control
+//! that was renamed or deleted, or for an event the control doesn't raise, as
+//! an ordinary Sub that never runs. Both are reported.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for a handler whose control is not on the form
+pub const UNKNOWN_CONTROL: &str = "unknown-control";
+
+/// Diagnostic code for a handler of an event its control doesn't raise
+pub const UNKNOWN_EVENT: &str = "unknown-event";
+
+/// Report event handlers whose control is missing or doesn't raise the event.
+///
+/// Events are only checked for the intrinsic controls, whose events are known.
+pub fn find_event_handler_problems(table: &SymbolTable) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for symbol in table.procedures() {
+        let Some(binding) = &symbol.handles else {
+            continue;
+        };
+        let Some(control) = binding.control.and_then(|id| table.get_symbol(id)) else {
+            diagnostics.push(diagnostic(
+                symbol.name_range.to_lsp(),
+                UNKNOWN_CONTROL,
+                format!("No control named '{}'", binding.control_name),
+            ));
+            continue;
+        };
+
+        let Some(control_type) = control.type_info.as_ref().map(|t| t.name.as_str()) else {
+            continue;
+        };
+        if crate::controls::get_control(control_type).is_some()
+            && crate::controls::get_event(control_type, &binding.event).is_none()
+        {
+            diagnostics.push(diagnostic(
+                symbol.name_range.to_lsp(),
+                UNKNOWN_EVENT,
+                format!(
+                    "{} '{}' has no event named '{}'",
+                    control_type, control.name, binding.event
+                ),
+            ));
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+fn diagnostic(range: tower_lsp::lsp_types::Range, code: &str, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(code.to_string())),
+        message,
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{build_symbol_table, run_pass_at};
+    use crate::parser::TreeSitterVb6Parser;
+    use tower_lsp::lsp_types::Url;
+
+    fn flagged(uri: &str, source: &str) -> Vec<(u32, String, String)> {
+        run_pass_at(uri, source, |table, _| find_event_handler_problems(table))
+            .into_iter()
+            .map(|d| {
+                let Some(NumberOrString::String(code)) = d.code else {
+                    panic!("expected a string code");
+                };
+                (d.range.start.line, code, d.message)
+            })
+            .collect()
+    }
+
+    const FORM: &str = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Begin VB.CommandButton cmdOK\n\
+      Caption         =   \"OK\"\n\
+   End\n\
+   Begin MSComctlLib.ListView lvwItems\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Implements IPlugin\n\
+Private WithEvents mTimer As clsTimer\n\
+Private Sub Form_Load()\n\
+End Sub\n\
+Private Sub cmdOK_Click()\n\
+End Sub\n\
+Private Sub cmdOK_Change()\n\
+End Sub\n\
+Private Sub cmdCancel_Click()\n\
+End Sub\n\
+Private Sub lvwItems_ItemClick(ByVal Item As Object)\n\
+End Sub\n\
+Private Sub mTimer_Tick()\n\
+End Sub\n\
+Private Sub IPlugin_Click()\n\
+End Sub\n\
+Private Sub Save_Settings()\n\
+End Sub\n";
+
+    #[test]
+    fn test_flags_missing_controls_and_events() {
+        assert_eq!(
+            flagged("file:///frmMain.frm", FORM),
+            vec![
+                (
+                    15,
+                    UNKNOWN_EVENT.to_string(),
+                    "CommandButton 'cmdOK' has no event named 'Change'".to_string()
+                ),
+                (17, UNKNOWN_CONTROL.to_string(), "No control named 'cmdCancel'".to_string()),
+            ]
+        );
+        assert!(flagged("file:///Module1.bas", "Sub cmdCancel_Click()\nEnd Sub\n").is_empty());
+    }
+
+    #[test]
+    fn test_links_handlers_to_controls() {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(FORM, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///frmMain.frm").unwrap(), FORM, &tree);
+
+        let button = table.lookup_symbol("cmdOK", table.module_scope).unwrap();
+        let handler = table.event_handler(button.id, "click").unwrap();
+        assert_eq!(handler.name, "cmdOK_Click");
+        assert_eq!(handler.handles.as_ref().unwrap().event, "Click");
+        assert!(table.event_handler(button.id, "GotFocus").is_none());
+
+        let load = table.lookup_symbol("Form_Load", table.module_scope).unwrap();
+        assert!(load.handles.is_none());
+    }
+}
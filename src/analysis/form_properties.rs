@@ -0,0 +1,305 @@
+//! Form Property Validation
+//!
+//! Checks the property values of controls in `.frm`-style `Begin ... End`
+//! blocks against the values the control definitions allow, describes
+//! color values on hover, and names the constants a literal value stands for.
+
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, Hover, HoverContents, MarkupContent, MarkupKind,
+    NumberOrString, Position,
+};
+use tree_sitter::{Node, Point, Tree};
+
+use super::position::{SourcePosition, SourceRange};
+use crate::controls::{color_constants, get_property, PropertyDef, PropertyType, VB6Color};
+
+/// Diagnostic code for a property value the control doesn't accept
+pub const INVALID_PROPERTY_VALUE: &str = "invalid-property-value";
+
+/// Find enumerated property values that the control does not define, and malformed color literals
+pub fn find_invalid_property_values(tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    visit(&tree.root_node(), source, &mut diagnostics);
+    diagnostics
+}
+
+/// Recursively check every control block in the tree
+fn visit(node: &Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if node.kind() == "form_block" {
+        check_control(node, source, diagnostics);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        visit(&child, source, diagnostics);
+    }
+}
+
+/// Check the property lines directly inside one control block
+fn check_control(block: &Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(control_type) = control_type(block, source) else {
+        return;
+    };
+
+    let mut cursor = block.walk();
+    for element in block.named_children(&mut cursor) {
+        let Some(line) = element.named_child(0).filter(|n| n.kind() == "form_property_line") else {
+            continue;
+        };
+        let (Some(name), Some(value)) = (line.named_child(0), line.named_child(1)) else {
+            continue;
+        };
+        let name = name.utf8_text(source.as_bytes()).unwrap_or("");
+
+        let Some(property) = get_property(control_type, name) else {
+            continue;
+        };
+        if property.property_type == PropertyType::Color {
+            check_color(&line, source, diagnostics);
+            continue;
+        }
+        if property.property_type != PropertyType::Enum || property.valid_values.is_empty() {
+            continue;
+        }
+
+        // Symbolic constants and expressions can't be checked
+        let Ok(number) = value.utf8_text(source.as_bytes()).unwrap_or("").trim().parse::<i32>() else {
+            continue;
+        };
+        if property.valid_values.iter().any(|v| v.value == number) {
+            continue;
+        }
+
+        let valid: Vec<String> = property
+            .valid_values
+            .iter()
+            .map(|v| format!("{} ({})", v.value, v.name))
+            .collect();
+
+        diagnostics.push(Diagnostic {
+            range: SourceRange::from_ts_node(&value).to_lsp(),
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(INVALID_PROPERTY_VALUE.to_string())),
+            message: format!(
+                "{} is not a valid {} for {}. Valid values: {}",
+                number,
+                property.name,
+                control_type,
+                valid.join(", ")
+            ),
+            source: Some("vb6-lsp".to_string()),
+            ..Default::default()
+        });
+    }
+}
+
+/// Flag a color property whose value is not a well-formed color literal
+fn check_color(line: &Node, source: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let Some((text, range)) = property_value(line, source) else {
+        return;
+    };
+
+    if let Err(message) = VB6Color::parse_literal(text) {
+        diagnostics.push(Diagnostic {
+            range: range.to_lsp(),
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(INVALID_PROPERTY_VALUE.to_string())),
+            message,
+            source: Some("vb6-lsp".to_string()),
+            ..Default::default()
+        });
+    }
+}
+
+/// Describe the color value of a color property under the cursor
+pub fn color_hover(tree: &Tree, source: &str, position: Position) -> Option<Hover> {
+    let (property, text, range) = property_line_at(tree, source, position)?;
+    if property.property_type != PropertyType::Color {
+        return None;
+    }
+    if !range.contains(SourcePosition::from_lsp(position)) {
+        return None;
+    }
+    let color = VB6Color::parse_literal(text).ok()?;
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: color.describe(),
+        }),
+        range: Some(range.to_lsp()),
+    })
+}
+
+/// The property set by the control block line under the cursor, with its raw
+/// value and the value's range
+pub fn property_line_at<'a>(
+    tree: &Tree,
+    source: &'a str,
+    position: Position,
+) -> Option<(&'static PropertyDef, &'a str, SourceRange)> {
+    let point = Point::new(position.line as usize, position.character as usize);
+    let mut node = tree.root_node().descendant_for_point_range(point, point)?;
+    while node.kind() != "form_property_line" {
+        node = node.parent()?;
+    }
+
+    let block = node.parent().and_then(|element| element.parent())?;
+    let name = node.named_child(0)?.utf8_text(source.as_bytes()).ok()?;
+    let property = get_property(control_type(&block, source)?, name)?;
+    let (text, range) = property_value(&node, source)?;
+    Some((property, text, range))
+}
+
+/// The named constants whose value is the literal `text` for a property:
+/// `vbCenter` for an Alignment of 2, `vbRed` for a color of `&HFF&`
+pub fn named_values(property: &PropertyDef, text: &str) -> Vec<&'static str> {
+    let text = text.trim();
+    match property.property_type {
+        PropertyType::Color => match VB6Color::parse_literal(text) {
+            Ok(color) => color_constants()
+                .iter()
+                .filter(|constant| constant.color == color)
+                .map(|constant| constant.name)
+                .collect(),
+            Err(_) => Vec::new(),
+        },
+        PropertyType::Enum | PropertyType::Boolean => match text.parse::<i32>() {
+            // Some values only have descriptive names ("Fixed Single")
+            Ok(number) => property
+                .valid_values
+                .iter()
+                .filter(|v| v.value == number)
+                .map(|v| v.name)
+                .filter(|name| {
+                    name.get(..2).is_some_and(|prefix| prefix.eq_ignore_ascii_case("vb"))
+                        || ["True", "False"].contains(name)
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Control type of a `Begin` block ("VB.CommandButton" -> "CommandButton")
+fn control_type<'a>(block: &Node, source: &'a str) -> Option<&'a str> {
+    block
+        .child_by_field_name("type")
+        .and_then(|t| t.utf8_text(source.as_bytes()).ok())
+        .and_then(|t| t.rsplit('.').next())
+}
+
+/// Raw text and range of a property value.
+///
+/// Malformed values may be split across error nodes, so this spans
+/// everything after the property name up to any trailing comment.
+fn property_value<'a>(line: &Node, source: &'a str) -> Option<(&'a str, SourceRange)> {
+    let mut cursor = line.walk();
+    let parts: Vec<Node> = line
+        .named_children(&mut cursor)
+        .skip(1)
+        .filter(|n| n.kind() != "comment")
+        .collect();
+    let (first, last) = (parts.first()?, parts.last()?);
+
+    let text = source.get(first.start_byte()..last.end_byte())?;
+    let range = SourceRange::new(
+        SourcePosition::from_ts_point(first.start_position()),
+        SourcePosition::from_ts_point(last.end_position()),
+    );
+    Some((text, range))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn messages(source: &str) -> Vec<String> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        find_invalid_property_values(&tree, source)
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_out_of_range_enum_value() {
+        let source = "VERSION 5.00\n\
+Begin VB.Form Form1\n\
+   BorderStyle     =   9\n\
+   Caption         =   \"Main\"\n\
+   Begin VB.TextBox Text1\n\
+      Appearance      =   0  'Flat\n\
+      BorderStyle     =   4\n\
+   End\n\
+End\n";
+
+        assert_eq!(
+            messages(source),
+            vec![
+                "9 is not a valid BorderStyle for Form. Valid values: 0 (None), 1 (Fixed Single), 2 (Sizable), 3 (Fixed Dialog), 4 (Fixed ToolWindow), 5 (Sizable ToolWindow)",
+                "4 is not a valid BorderStyle for TextBox. Valid values: 0 (None), 1 (Fixed Single)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_symbolic_values_are_skipped() {
+        let source = "Begin VB.Form Form1\n\
+   BorderStyle     =   vbSizable\n\
+   WindowState     =   2\n\
+End\n";
+
+        assert!(messages(source).is_empty());
+    }
+
+    #[test]
+    fn test_malformed_color_literals() {
+        let source = "Begin VB.Form Form1\n\
+   BackColor       =   &H8000000F&\n\
+   ForeColor       =   &H80000012\n\
+   FillColor       =   &H00GG00&\n\
+End\n";
+
+        assert_eq!(
+            messages(source),
+            vec![
+                "Color literal '&H80000012' is missing its trailing '&'",
+                "'G' is not a hex digit in color literal '&H00GG00&'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_color_hover() {
+        let source = "Begin VB.Form Form1\n\
+   BackColor       =   &H8000000F&\n\
+   Begin VB.Label Label1\n\
+      ForeColor       =   &H00FF8000&\n\
+      Caption         =   \"Hi\"\n\
+   End\n\
+End\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let hover_text = |line, character| {
+            color_hover(&tree, source, Position { line, character }).map(|h| match h.contents {
+                HoverContents::Markup(m) => m.value,
+                _ => String::new(),
+            })
+        };
+
+        let system = hover_text(1, 25).unwrap();
+        assert!(system.contains("vbButtonFace"));
+        assert!(system.contains("Button face"));
+
+        let rgb = hover_text(3, 27).unwrap();
+        assert!(rgb.contains("RGB(0, 128, 255)"));
+
+        // Property names and non-color properties have no color hover
+        assert!(hover_text(1, 5).is_none());
+        assert!(hover_text(4, 27).is_none());
+    }
+}
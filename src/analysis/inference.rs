@@ -0,0 +1,200 @@
+//! Expression Type Inference
+//!
+//! Resolves the type of simple expressions: literals, declared names,
+//! `New ClassName`, and calls to functions with a declared return type.
+
+use tree_sitter::Node;
+
+use super::scope::ScopeId;
+use super::symbol::{SymbolKind, TypeInfo};
+use super::symbol_table::SymbolTable;
+
+/// Infer the type of an expression, looking names up from `scope`.
+///
+/// Returns `None` for expressions whose type cannot be determined without
+/// evaluating them (operators, member accesses, names declared elsewhere).
+pub fn infer_expression_type(
+    table: &SymbolTable,
+    scope: ScopeId,
+    node: &Node,
+    source: &str,
+) -> Option<TypeInfo> {
+    let text = node.utf8_text(source.as_bytes()).unwrap_or("");
+
+    match node.kind() {
+        "literal" | "parenthesized_expression" => {
+            infer_expression_type(table, scope, &node.named_child(0)?, source)
+        }
+        "string_literal" => Some(TypeInfo::new("String")),
+        "integer_literal" => Some(TypeInfo::new(integer_type(text)?)),
+        "float_literal" => Some(TypeInfo::new("Double")),
+        "boolean_literal" => Some(TypeInfo::new("Boolean")),
+        "date_literal" => Some(TypeInfo::new("Date")),
+        "color_literal" => Some(TypeInfo::new("Long")),
+        "new_expression" => {
+            let class = node.named_child(0)?.utf8_text(source.as_bytes()).ok()?;
+            Some(TypeInfo {
+                is_new: true,
+                ..TypeInfo::new(class)
+            })
+        }
+        "identifier" => {
+            let symbol = table.lookup_symbol(text, scope)?;
+            match symbol.kind {
+                SymbolKind::Variable
+                | SymbolKind::LocalVariable
+                | SymbolKind::Parameter
+                | SymbolKind::Constant
+                | SymbolKind::LocalConstant
+                | SymbolKind::ForLoopVariable
+                | SymbolKind::ForEachVariable
+                // A function named without arguments is a call
+                | SymbolKind::Function
+                | SymbolKind::PropertyGet
+                | SymbolKind::DeclareFunction => symbol.type_info.clone(),
+                SymbolKind::EnumMember => Some(TypeInfo::new("Long")),
+                _ => None,
+            }
+        }
+        "call_expression" => {
+            let function = node.child_by_field_name("function")?;
+            if function.kind() != "identifier" {
+                return None;
+            }
+            let name = function.utf8_text(source.as_bytes()).ok()?;
+            let symbol = table.lookup_symbol(name, scope)?;
+            match symbol.kind {
+                SymbolKind::Function | SymbolKind::PropertyGet | SymbolKind::DeclareFunction => {
+                    Some(
+                        symbol
+                            .type_info
+                            .clone()
+                            .unwrap_or_else(|| TypeInfo::new("Variant")),
+                    )
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Infer the type of the value assigned by an assignment or `Set` statement
+pub fn infer_assigned_type(
+    table: &SymbolTable,
+    scope: ScopeId,
+    statement: &Node,
+    source: &str,
+) -> Option<TypeInfo> {
+    let value = statement.child_by_field_name("value")?;
+
+    // `Set x = New Class` keeps New as a keyword of the statement
+    let mut cursor = statement.walk();
+    let is_new = statement.kind() == "set_statement"
+        && statement.children(&mut cursor).any(|child| {
+            !child.is_named()
+                && child
+                    .utf8_text(source.as_bytes())
+                    .is_ok_and(|keyword| keyword.eq_ignore_ascii_case("new"))
+        });
+    if is_new {
+        let class = value.utf8_text(source.as_bytes()).ok()?;
+        return Some(TypeInfo {
+            is_new: true,
+            ..TypeInfo::new(class)
+        });
+    }
+
+    infer_expression_type(table, scope, &value, source)
+}
+
+/// Type of an integer literal: Integer if it fits in 16 bits, else Long
+/// (or Double for decimal literals beyond the range of a Long)
+fn integer_type(text: &str) -> Option<&'static str> {
+    let upper = text.to_uppercase();
+    // Hex and octal literals wrap around, so &HFFFF is the Integer -1
+    let radix = if let Some(hex) = upper.strip_prefix("&H") {
+        Some((hex, 16))
+    } else {
+        upper.strip_prefix("&O").map(|octal| (octal, 8))
+    };
+    if let Some((digits, radix)) = radix {
+        let value = i64::from_str_radix(digits, radix).ok()?;
+        return Some(if value <= 0xFFFF { "Integer" } else { "Long" });
+    }
+
+    let value: i64 = text.parse().ok()?;
+    Some(if value <= i16::MAX as i64 {
+        "Integer"
+    } else if value <= i32::MAX as i64 {
+        "Long"
+    } else {
+        "Double"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::position::SourcePosition;
+    use crate::analysis::run_pass;
+
+    /// Infer the type of each assignment's value, in order
+    fn assigned_types(source: &str) -> Vec<Option<String>> {
+        let mut types = run_pass(source, |table, tree| {
+            let mut types = Vec::new();
+            let mut stack = vec![tree.root_node()];
+            while let Some(node) = stack.pop() {
+                if matches!(node.kind(), "assignment_statement" | "set_statement") {
+                    let position = SourcePosition::from_ts_point(node.start_position());
+                    let scope = table.scope_at_position(position);
+                    let inferred = infer_assigned_type(table, scope, &node, source);
+                    types.push((node.start_byte(), inferred.map(|t| t.display())));
+                }
+                let mut cursor = node.walk();
+                stack.extend(node.named_children(&mut cursor));
+            }
+            types
+        });
+        types.sort();
+        types.into_iter().map(|(_, t)| t).collect()
+    }
+
+    #[test]
+    fn test_literals() {
+        let source = "Sub Main()\n    a = \"x\"\n    a = 123\n    a = 40000\n    a = &HFFFF\n    a = 1.5\n    a = #1/1/2000#\n    a = True\n    a = (7)\nEnd Sub\n";
+        let inferred = assigned_types(source);
+
+        assert_eq!(
+            inferred.iter().map(|t| t.as_deref()).collect::<Vec<_>>(),
+            vec![
+                Some("String"),
+                Some("Integer"),
+                Some("Long"),
+                Some("Integer"),
+                Some("Double"),
+                Some("Date"),
+                Some("Boolean"),
+                Some("Integer"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_names_calls_and_new() {
+        let source = "Option Explicit\n\
+Private Function Total(ByVal n As Long) As Currency\nEnd Function\n\
+Sub Main()\n    Dim s As String\n    Dim t As String\n    Dim c As Currency\n    Dim o As Object\n    Dim u\n\
+    t = s\n    c = Total(1)\n    Set o = New Collection\n    u = Unknown(1)\nEnd Sub\n";
+
+        assert_eq!(
+            assigned_types(source),
+            vec![
+                Some("String".to_string()),
+                Some("Currency".to_string()),
+                Some("Collection".to_string()),
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Integer Arithmetic Checking
+//!
+//! Two classes of silent bugs in legacy code:
+//!
+//! - An `Integer` holds -32768 to 32767; assigning a larger constant value
+//!   raises "Overflow" at run time (or does not compile for a `Const`).
+//! - `\` and `Mod` round their operands to whole numbers first, so
+//!   `7.5 \ 2` is 4, not 3. On a `Single` or `Double` that is rarely meant.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::constants::{evaluate_expression, ConstValue};
+use super::inference::infer_expression_type;
+use super::position::{SourcePosition, SourceRange};
+use super::scope::ScopeId;
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for a constant value outside the range of an Integer
+pub const INTEGER_OVERFLOW: &str = "integer-overflow";
+
+/// Diagnostic code for `\` or `Mod` applied to a floating-point operand
+pub const INTEGER_DIVISION: &str = "integer-division";
+
+/// Report Integer overflows and integer division of floating-point values
+pub fn find_integer_arithmetic_problems(
+    table: &SymbolTable,
+    tree: &Tree,
+    source: &str,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        let scope =
+            || table.scope_at_position(SourcePosition::from_ts_point(node.start_position()));
+        match node.kind() {
+            "assignment_statement" => {
+                if let Some((target, value)) =
+                    node.child_by_field_name("target").zip(node.child_by_field_name("value"))
+                {
+                    check_overflow(table, scope(), &target, &value, source, &mut diagnostics);
+                }
+            }
+            "constant_declarator" => {
+                if let Some((name, value)) =
+                    node.child_by_field_name("name").zip(node.child_by_field_name("value"))
+                {
+                    check_overflow(table, scope(), &name, &value, source, &mut diagnostics);
+                }
+            }
+            "binary_expression" => check_division(table, scope(), &node, source, &mut diagnostics),
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+/// Flag a constant value assigned to an Integer that does not fit in one
+fn check_overflow(
+    table: &SymbolTable,
+    scope: ScopeId,
+    target: &Node,
+    value: &Node,
+    source: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if target.kind() != "identifier" {
+        return;
+    }
+    let is_integer = infer_expression_type(table, scope, target, source)
+        .is_some_and(|t| !t.is_array && !t.is_inferred && t.name.eq_ignore_ascii_case("integer"));
+    if !is_integer {
+        return;
+    }
+    let Some(ConstValue::Integer(number)) = evaluate_expression(table, value, scope, source) else {
+        return;
+    };
+    if i16::try_from(number).is_ok() {
+        return;
+    }
+
+    diagnostics.push(diagnostic(
+        value,
+        INTEGER_OVERFLOW,
+        format!(
+            "Overflow: {} is outside the range of Integer (-32768 to 32767); \
+             declare '{}' As Long",
+            number,
+            text(target, source)
+        ),
+    ));
+}
+
+/// Flag `\` and `Mod` with an operand known to be a Single or Double
+fn check_division(
+    table: &SymbolTable,
+    scope: ScopeId,
+    node: &Node,
+    source: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut cursor = node.walk();
+    let Some(operator) = node.children(&mut cursor).find(|child| !child.is_named()) else {
+        return;
+    };
+    let operator = text(&operator, source);
+    if operator != "\\" && !operator.eq_ignore_ascii_case("mod") {
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for operand in node.named_children(&mut cursor) {
+        let Some(type_info) = infer_expression_type(table, scope, &operand, source) else {
+            continue;
+        };
+        if type_info.is_array
+            || type_info.is_inferred
+            || !["single", "double"].contains(&type_info.name.to_lowercase().as_str())
+        {
+            continue;
+        }
+        diagnostics.push(diagnostic(
+            &operand,
+            INTEGER_DIVISION,
+            format!(
+                "'{}' rounds the {} '{}' to a whole number first; use '/' if the fraction matters",
+                operator,
+                type_info.name,
+                text(&operand, source)
+            ),
+        ));
+    }
+}
+
+fn diagnostic(node: &Node, code: &str, message: String) -> Diagnostic {
+    Diagnostic {
+        range: SourceRange::from_ts_node(node).to_lsp(),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(code.to_string())),
+        message,
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+fn text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    #[test]
+    fn test_integer_overflow_and_division() {
+        let source = "Private Const LIMIT = 20000\n\
+Private Const BIG As Integer = LIMIT * 2\n\
+Sub Main()\n\
+    Dim n As Integer, total As Long, ratio As Double\n\
+    n = 40000\n\
+    n = -32768\n\
+    n = LIMIT + 1\n\
+    total = 40000\n\
+    n = ratio \\ 2\n\
+    n = 7.5 Mod 2\n\
+    n = total \\ 2\n\
+End Sub\n";
+        let diagnostics =
+            run_pass(source, |table, tree| find_integer_arithmetic_problems(table, tree, source));
+        let found: Vec<(u32, String)> = diagnostics
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    1,
+                    "Overflow: 40000 is outside the range of Integer (-32768 to 32767); \
+                     declare 'BIG' As Long"
+                        .to_string()
+                ),
+                (
+                    4,
+                    "Overflow: 40000 is outside the range of Integer (-32768 to 32767); \
+                     declare 'n' As Long"
+                        .to_string()
+                ),
+                (
+                    8,
+                    "'\\' rounds the Double 'ratio' to a whole number first; \
+                     use '/' if the fraction matters"
+                        .to_string()
+                ),
+                (
+                    9,
+                    "'Mod' rounds the Double '7.5' to a whole number first; \
+                     use '/' if the fraction matters"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! VB6 Runtime Functions
+//!
+//! Signatures and descriptions of the functions built into the VB6 runtime
+//! (VBA library), used for hover, signature help, and completion.
+
+/// Built-in runtime function or statement
+#[derive(Debug, Clone)]
+pub struct IntrinsicFunction {
+    /// Function name (e.g., "Left")
+    pub name: &'static str,
+    /// Parameters, with optional ones in brackets (e.g., "[compare]")
+    pub parameters: &'static [&'static str],
+    /// Return type (`None` for statements such as `Kill`)
+    pub return_type: Option<&'static str>,
+    /// Description for hover info
+    pub description: &'static str,
+    /// Whether a `$` form returning String exists (e.g., `Left$`)
+    pub has_string_form: bool,
+}
+
+impl IntrinsicFunction {
+    /// Format the signature, optionally as the `$` form
+    /// (e.g., "Left$(string, length) As String", or "Kill pathname" for a statement)
+    pub fn format_signature(&self, string_form: bool) -> String {
+        let name = if string_form {
+            format!("{}$", self.name)
+        } else {
+            self.name.to_string()
+        };
+        let parameters = self.parameters.join(", ");
+        match (string_form, self.return_type) {
+            (true, _) => format!("{}({}) As String", name, parameters),
+            (false, Some(return_type)) => format!("{}({}) As {}", name, parameters, return_type),
+            (false, None) => format!("{} {}", name, parameters).trim_end().to_string(),
+        }
+    }
+}
+
+const fn function(
+    name: &'static str,
+    parameters: &'static [&'static str],
+    return_type: &'static str,
+    description: &'static str,
+) -> IntrinsicFunction {
+    IntrinsicFunction {
+        name,
+        parameters,
+        return_type: Some(return_type),
+        description,
+        has_string_form: false,
+    }
+}
+
+/// A function with a `$` form that returns String instead of Variant
+const fn string_function(
+    name: &'static str,
+    parameters: &'static [&'static str],
+    description: &'static str,
+) -> IntrinsicFunction {
+    IntrinsicFunction {
+        name,
+        parameters,
+        return_type: Some("Variant"),
+        description,
+        has_string_form: true,
+    }
+}
+
+const fn statement(
+    name: &'static str,
+    parameters: &'static [&'static str],
+    description: &'static str,
+) -> IntrinsicFunction {
+    IntrinsicFunction {
+        name,
+        parameters,
+        return_type: None,
+        description,
+        has_string_form: false,
+    }
+}
+
+/// All runtime functions, grouped by family
+pub static INTRINSIC_FUNCTIONS: &[IntrinsicFunction] = &[
+    // =========================================================================
+    // String functions
+    // =========================================================================
+    function("Asc", &["string"], "Integer", "Returns the character code of the first letter in a string"),
+    function("AscW", &["string"], "Integer", "Returns the Unicode character code of the first letter in a string"),
+    string_function("Chr", &["charcode"], "Returns the character for a character code"),
+    string_function("ChrW", &["charcode"], "Returns the character for a Unicode character code"),
+    function("Filter", &["sourcearray", "match", "[include]", "[compare]"], "Variant", "Returns an array of the strings in sourcearray that contain (or, with include False, do not contain) match"),
+    string_function("Format", &["expression", "[format]", "[firstdayofweek]", "[firstweekofyear]"], "Formats an expression according to a format string (e.g., \"0.00\", \"yyyy-mm-dd\")"),
+    function("FormatCurrency", &["expression", "[numdigitsafterdecimal]", "[includeleadingdigit]", "[useparensfornegativenumbers]", "[groupdigits]"], "String", "Formats an expression as a currency value using the system currency symbol"),
+    function("FormatDateTime", &["date", "[namedformat]"], "String", "Formats an expression as a date or time (vbGeneralDate, vbLongDate, vbShortDate, vbLongTime, vbShortTime)"),
+    function("FormatNumber", &["expression", "[numdigitsafterdecimal]", "[includeleadingdigit]", "[useparensfornegativenumbers]", "[groupdigits]"], "String", "Formats an expression as a number"),
+    function("FormatPercent", &["expression", "[numdigitsafterdecimal]", "[includeleadingdigit]", "[useparensfornegativenumbers]", "[groupdigits]"], "String", "Formats an expression as a percentage (multiplied by 100) with a trailing %"),
+    string_function("Hex", &["number"], "Returns the hexadecimal representation of a number"),
+    function("InStr", &["[start]", "string1", "string2", "[compare]"], "Long", "Returns the position of the first occurrence of string2 within string1, or 0 if it is not found"),
+    function("InStrRev", &["stringcheck", "stringmatch", "[start]", "[compare]"], "Long", "Returns the position of the last occurrence of stringmatch within stringcheck, or 0 if it is not found"),
+    function("Join", &["sourcearray", "[delimiter]"], "String", "Joins the strings of an array, separated by delimiter (a space by default)"),
+    string_function("LCase", &["string"], "Returns a string converted to lowercase"),
+    string_function("Left", &["string", "length"], "Returns the specified number of characters from the left side of a string"),
+    function("Len", &["string | varname"], "Long", "Returns the number of characters in a string, or the number of bytes needed to store a variable"),
+    string_function("LTrim", &["string"], "Returns a string without leading spaces"),
+    string_function("Mid", &["string", "start", "[length]"], "Returns length characters of a string starting at position start (1-based)"),
+    string_function("Oct", &["number"], "Returns the octal representation of a number"),
+    function("Replace", &["expression", "find", "replace", "[start]", "[count]", "[compare]"], "String", "Returns a string in which occurrences of find have been replaced by replace"),
+    string_function("Right", &["string", "length"], "Returns the specified number of characters from the right side of a string"),
+    string_function("RTrim", &["string"], "Returns a string without trailing spaces"),
+    string_function("Space", &["number"], "Returns a string of the specified number of spaces"),
+    function("Split", &["expression", "[delimiter]", "[limit]", "[compare]"], "Variant", "Returns a zero-based array of the substrings of expression separated by delimiter (a space by default)"),
+    string_function("Str", &["number"], "Returns the string representation of a number, with a leading space for positive numbers"),
+    function("StrComp", &["string1", "string2", "[compare]"], "Integer", "Compares two strings, returning -1, 0, or 1"),
+    function("StrConv", &["string", "conversion", "[lcid]"], "Variant", "Converts a string (vbUpperCase, vbLowerCase, vbProperCase, vbUnicode, vbFromUnicode)"),
+    string_function("String", &["number", "character"], "Returns a string of a repeated character"),
+    function("StrReverse", &["expression"], "String", "Returns a string with its characters in reverse order"),
+    string_function("Trim", &["string"], "Returns a string without leading and trailing spaces"),
+    string_function("UCase", &["string"], "Returns a string converted to uppercase"),
+    function("Val", &["string"], "Double", "Returns the number at the start of a string, stopping at the first character it does not recognize"),
+    // =========================================================================
+    // Math functions
+    // =========================================================================
+    function("Abs", &["number"], "Variant", "Returns the absolute value of a number"),
+    function("Atn", &["number"], "Double", "Returns the arctangent of a number, in radians"),
+    function("Cos", &["number"], "Double", "Returns the cosine of an angle in radians"),
+    function("Exp", &["number"], "Double", "Returns e raised to a power"),
+    function("Fix", &["number"], "Variant", "Returns the integer portion of a number, truncating toward zero"),
+    function("Int", &["number"], "Variant", "Returns the integer portion of a number, rounding down (Int(-8.4) is -9)"),
+    function("Log", &["number"], "Double", "Returns the natural logarithm of a number"),
+    statement("Randomize", &["[number]"], "Initializes the random-number generator"),
+    function("Rnd", &["[number]"], "Single", "Returns a random number greater than or equal to 0 and less than 1"),
+    function("Round", &["expression", "[numdecimalplaces]"], "Variant", "Returns a number rounded to a number of decimal places, using banker's rounding"),
+    function("Sgn", &["number"], "Integer", "Returns -1, 0, or 1 for the sign of a number"),
+    function("Sin", &["number"], "Double", "Returns the sine of an angle in radians"),
+    function("Sqr", &["number"], "Double", "Returns the square root of a number"),
+    function("Tan", &["number"], "Double", "Returns the tangent of an angle in radians"),
+    // =========================================================================
+    // Conversion functions
+    // =========================================================================
+    function("CBool", &["expression"], "Boolean", "Converts an expression to a Boolean"),
+    function("CByte", &["expression"], "Byte", "Converts an expression to a Byte"),
+    function("CCur", &["expression"], "Currency", "Converts an expression to a Currency"),
+    function("CDate", &["expression"], "Date", "Converts an expression to a Date"),
+    function("CDbl", &["expression"], "Double", "Converts an expression to a Double"),
+    function("CDec", &["expression"], "Variant", "Converts an expression to a Variant of subtype Decimal"),
+    function("CInt", &["expression"], "Integer", "Converts an expression to an Integer, rounding fractions to the nearest even number"),
+    function("CLng", &["expression"], "Long", "Converts an expression to a Long, rounding fractions to the nearest even number"),
+    function("CSng", &["expression"], "Single", "Converts an expression to a Single"),
+    function("CStr", &["expression"], "String", "Converts an expression to a String"),
+    function("CVar", &["expression"], "Variant", "Converts an expression to a Variant"),
+    function("CVErr", &["errornumber"], "Variant", "Returns a Variant of subtype Error containing an error number"),
+    // =========================================================================
+    // Date and time functions
+    // =========================================================================
+    string_function("Date", &[], "Returns the current system date"),
+    function("DateAdd", &["interval", "number", "date"], "Date", "Returns a date with a time interval added (interval is \"yyyy\", \"q\", \"m\", \"d\", \"ww\", \"h\", \"n\", \"s\", ...)"),
+    function("DateDiff", &["interval", "date1", "date2", "[firstdayofweek]", "[firstweekofyear]"], "Long", "Returns the number of time intervals between two dates"),
+    function("DatePart", &["interval", "date", "[firstdayofweek]", "[firstweekofyear]"], "Integer", "Returns the specified part of a date"),
+    function("DateSerial", &["year", "month", "day"], "Date", "Returns the date for a year, month, and day"),
+    function("DateValue", &["date"], "Date", "Returns the date represented by a string"),
+    function("Day", &["date"], "Integer", "Returns the day of the month (1 to 31)"),
+    function("Hour", &["time"], "Integer", "Returns the hour of the day (0 to 23)"),
+    function("Minute", &["time"], "Integer", "Returns the minute of the hour (0 to 59)"),
+    function("Month", &["date"], "Integer", "Returns the month of the year (1 to 12)"),
+    function("MonthName", &["month", "[abbreviate]"], "String", "Returns the name of a month"),
+    function("Now", &[], "Date", "Returns the current system date and time"),
+    function("Second", &["time"], "Integer", "Returns the second of the minute (0 to 59)"),
+    string_function("Time", &[], "Returns the current system time"),
+    function("Timer", &[], "Single", "Returns the number of seconds elapsed since midnight"),
+    function("TimeSerial", &["hour", "minute", "second"], "Date", "Returns the time for an hour, minute, and second"),
+    function("TimeValue", &["time"], "Date", "Returns the time represented by a string"),
+    function("Weekday", &["date", "[firstdayofweek]"], "Integer", "Returns the day of the week (vbSunday to vbSaturday)"),
+    function("WeekdayName", &["weekday", "[abbreviate]", "[firstdayofweek]"], "String", "Returns the name of a day of the week"),
+    function("Year", &["date"], "Integer", "Returns the year of a date"),
+    // =========================================================================
+    // File I/O and file system
+    // =========================================================================
+    statement("ChDir", &["path"], "Changes the current directory"),
+    statement("ChDrive", &["drive"], "Changes the current drive"),
+    string_function("CurDir", &["[drive]"], "Returns the current path"),
+    string_function("Dir", &["[pathname]", "[attributes]"], "Returns the first file matching a pattern, or the next match when called without arguments"),
+    function("EOF", &["filenumber"], "Boolean", "Returns True when the end of a file opened for Random or sequential Input has been reached"),
+    function("FileAttr", &["filenumber", "returntype"], "Long", "Returns the file mode of an open file"),
+    statement("FileCopy", &["source", "destination"], "Copies a file"),
+    function("FileDateTime", &["pathname"], "Date", "Returns the date and time a file was created or last modified"),
+    function("FileLen", &["pathname"], "Long", "Returns the length of a file in bytes"),
+    function("FreeFile", &["[rangenumber]"], "Integer", "Returns the next file number available for the Open statement"),
+    function("GetAttr", &["pathname"], "Integer", "Returns the attributes of a file or directory"),
+    string_function("Input", &["number", "[#]filenumber"], "Returns characters read from a file opened for Input or Binary"),
+    statement("Kill", &["pathname"], "Deletes files matching a path"),
+    function("Loc", &["filenumber"], "Long", "Returns the current read/write position within an open file"),
+    function("LOF", &["filenumber"], "Long", "Returns the size in bytes of an open file"),
+    statement("MkDir", &["path"], "Creates a directory"),
+    statement("Reset", &[], "Closes all files opened with the Open statement"),
+    statement("RmDir", &["path"], "Removes an empty directory"),
+    function("Seek", &["filenumber"], "Long", "Returns the current read/write position within an open file"),
+    statement("SetAttr", &["pathname", "attributes"], "Sets the attributes of a file"),
+    // =========================================================================
+    // Arrays and inspection
+    // =========================================================================
+    function("Array", &["arglist"], "Variant", "Returns a Variant containing an array of the arguments"),
+    function("IsArray", &["varname"], "Boolean", "Returns whether a variable is an array"),
+    function("IsDate", &["expression"], "Boolean", "Returns whether an expression can be converted to a date"),
+    function("IsEmpty", &["expression"], "Boolean", "Returns whether a variable has been initialized"),
+    function("IsError", &["expression"], "Boolean", "Returns whether an expression is an error value"),
+    function("IsMissing", &["argname"], "Boolean", "Returns whether an Optional Variant argument was passed"),
+    function("IsNull", &["expression"], "Boolean", "Returns whether an expression is Null"),
+    function("IsNumeric", &["expression"], "Boolean", "Returns whether an expression can be evaluated as a number"),
+    function("IsObject", &["identifier"], "Boolean", "Returns whether an identifier is an object variable"),
+    function("LBound", &["arrayname", "[dimension]"], "Long", "Returns the smallest subscript of an array dimension"),
+    function("TypeName", &["varname"], "String", "Returns the name of the type of a variable"),
+    function("UBound", &["arrayname", "[dimension]"], "Long", "Returns the largest subscript of an array dimension"),
+    function("VarType", &["varname"], "VbVarType", "Returns the subtype of a variable"),
+    // =========================================================================
+    // Interaction and system
+    // =========================================================================
+    statement("AppActivate", &["title", "[wait]"], "Activates an application window"),
+    statement("Beep", &[], "Sounds a tone through the computer's speaker"),
+    function("CallByName", &["object", "procname", "calltype", "[args()]"], "Variant", "Calls a method or accesses a property of an object by name"),
+    function("Choose", &["index", "choice-1", "[choice-2, ...]"], "Variant", "Returns the value from a list of choices at a 1-based index"),
+    string_function("Command", &[], "Returns the command-line arguments of the program"),
+    function("CreateObject", &["class", "[servername]"], "Object", "Creates an instance of a COM object (e.g., \"Scripting.FileSystemObject\")"),
+    function("DoEvents", &[], "Integer", "Yields execution so the operating system can process other events"),
+    string_function("Environ", &["envstring | number"], "Returns the value of an environment variable"),
+    string_function("Error", &["[errornumber]"], "Returns the message for an error number"),
+    function("GetAllSettings", &["appname", "section"], "Variant", "Returns a two-dimensional array of the registry settings in a section"),
+    function("GetObject", &["[pathname]", "[class]"], "Object", "Returns a reference to a running or file-based COM object"),
+    function("GetSetting", &["appname", "section", "key", "[default]"], "String", "Returns a value saved in the application's registry section"),
+    function("IIf", &["expr", "truepart", "falsepart"], "Variant", "Returns truepart if expr is True, otherwise falsepart (both are always evaluated)"),
+    function("InputBox", &["prompt", "[title]", "[default]", "[xpos]", "[ypos]", "[helpfile]", "[context]"], "String", "Displays a prompt in a dialog box and returns the text the user entered"),
+    function("LoadPicture", &["[filename]", "[size]", "[colordepth]", "[x]", "[y]"], "IPictureDisp", "Loads a picture from a file"),
+    function("MsgBox", &["prompt", "[buttons]", "[title]", "[helpfile]", "[context]"], "VbMsgBoxResult", "Displays a message in a dialog box and returns which button the user clicked"),
+    function("QBColor", &["color"], "Long", "Returns the RGB color for a QuickBasic color number (0 to 15)"),
+    function("RGB", &["red", "green", "blue"], "Long", "Returns the color value for red, green, and blue components (0 to 255)"),
+    statement("SaveSetting", &["appname", "section", "key", "setting"], "Saves a value in the application's registry section"),
+    statement("SendKeys", &["string", "[wait]"], "Sends keystrokes to the active window"),
+    function("Shell", &["pathname", "[windowstyle]"], "Double", "Runs an executable program and returns its task ID"),
+    function("Switch", &["expr-1", "value-1", "[expr-2, value-2, ...]"], "Variant", "Returns the value paired with the first expression that is True"),
+];
+
+/// Look up a runtime function by name, accepting the `$` form (e.g., "Left$").
+///
+/// Returns the function and whether the `$` form was named.
+pub fn get_intrinsic(name: &str) -> Option<(&'static IntrinsicFunction, bool)> {
+    let (base, string_form) = match name.strip_suffix('$') {
+        Some(base) => (base, true),
+        None => (name, false),
+    };
+
+    INTRINSIC_FUNCTIONS
+        .iter()
+        .find(|f| f.name.eq_ignore_ascii_case(base))
+        .filter(|f| !string_form || f.has_string_form)
+        .map(|f| (f, string_form))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_and_string_form() {
+        let (left, string_form) = get_intrinsic("left$").unwrap();
+        assert!(string_form);
+        assert_eq!(left.format_signature(true), "Left$(string, length) As String");
+        assert_eq!(left.format_signature(false), "Left(string, length) As Variant");
+
+        let (instr, _) = get_intrinsic("InStr").unwrap();
+        assert_eq!(instr.format_signature(false), "InStr([start], string1, string2, [compare]) As Long");
+
+        assert!(get_intrinsic("InStr$").is_none());
+        assert_eq!(get_intrinsic("Kill").unwrap().0.format_signature(false), "Kill pathname");
+    }
+}
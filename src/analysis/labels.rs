@@ -0,0 +1,116 @@
+//! Label Checking
+//!
+//! Jump targets (`GoTo`, `GoSub`, `Resume`, `On Error GoTo`, `On ... GoTo`)
+//! resolve to the labels of their own procedure. Reports targets that name no
+//! label and labels that nothing jumps to.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString};
+
+use super::symbol::SymbolKind;
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for a jump to a label the procedure does not have
+pub const UNDEFINED_LABEL: &str = "undefined-label";
+/// Diagnostic code for a label that nothing jumps to
+pub const UNUSED_LABEL: &str = "unused-label";
+
+/// Report undefined jump targets and unused labels
+pub fn find_label_problems(table: &SymbolTable) -> Vec<Diagnostic> {
+    let undefined = table.undefined_labels().iter().map(|target| Diagnostic {
+        range: target.range.to_lsp(),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(UNDEFINED_LABEL.to_string())),
+        message: format!("Label '{}' is not defined in this procedure", target.name),
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    });
+
+    let unused = table
+        .symbols_of_kind(SymbolKind::Label)
+        .filter(|label| table.get_references(label.id).is_empty())
+        .map(|label| Diagnostic {
+            range: label.name_range.to_lsp(),
+            severity: Some(DiagnosticSeverity::HINT),
+            code: Some(NumberOrString::String(UNUSED_LABEL.to_string())),
+            message: format!("Label '{}' is never jumped to", label.name),
+            source: Some("vb6-lsp".to_string()),
+            tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+            ..Default::default()
+        });
+
+    let mut diagnostics: Vec<Diagnostic> = undefined.chain(unused).collect();
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    fn problems(source: &str) -> Vec<(u32, String)> {
+        run_pass(source, |table, _| find_label_problems(table))
+            .into_iter()
+            .map(|d| match d.code {
+                Some(NumberOrString::String(code)) => (d.range.start.line, code),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_named_labels() {
+        let source = "Sub Main()\n\
+On Error GoTo ErrHandler\n\
+For i = 1 To 2\n\
+Retry:\n\
+Next\n\
+GoTo Missing\n\
+Exit Sub\n\
+errhandler:\n\
+Resume Next\n\
+End Sub\n";
+        assert_eq!(
+            problems(source),
+            vec![(3, UNUSED_LABEL.to_string()), (5, UNDEFINED_LABEL.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_numeric_labels() {
+        let source = "Sub Main()\n\
+On Error GoTo 100\n\
+GoSub 200\n\
+Exit Sub\n\
+100:\n\
+Resume 0\n\
+300:\n\
+End Sub\n";
+        assert_eq!(
+            problems(source),
+            vec![(2, UNDEFINED_LABEL.to_string()), (6, UNUSED_LABEL.to_string())]
+        );
+    }
+
+    #[test]
+    fn test_labels_are_procedure_local() {
+        let source = "Sub First()\n\
+Done:\n\
+End Sub\n\
+Sub Second()\n\
+GoTo Done\n\
+End Sub\n\
+Property Get Value() As Long\n\
+On Error GoTo Failed\n\
+Failed:\n\
+End Property\n\
+Property Let Value(ByVal v As Long)\n\
+On Error GoTo Failed\n\
+Failed:\n\
+End Property\n";
+        assert_eq!(
+            problems(source),
+            vec![(1, UNUSED_LABEL.to_string()), (4, UNDEFINED_LABEL.to_string())]
+        );
+    }
+}
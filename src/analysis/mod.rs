@@ -3,33 +3,166 @@
 //! Provides semantic analysis, diagnostics, and code intelligence.
 //! Includes a symbol table for precise position-based lookups.
 
+mod arguments;
+mod arrays;
+mod blocks;
 mod builder;
+mod byref;
+mod call_context;
+mod call_hierarchy;
+mod code_actions;
+mod concatenation;
+mod constants;
+mod duplicates;
+mod error_handling;
+mod event_handlers;
+mod form_properties;
+mod inference;
+mod integer_arithmetic;
+mod intrinsics;
+mod labels;
+mod named_arguments;
 mod position;
+mod ptr_safe;
 mod scope;
+mod set_assignment;
+mod shadowing;
+mod snippets;
+mod suppressions;
 mod symbol;
 mod symbol_table;
+mod undeclared;
+mod unreachable;
+mod user_controls;
 
 // Re-export symbol table types
-pub use builder::build_symbol_table;
+use arrays::find_array_problems;
+use blocks::{find_unbalanced_blocks, linked_editing_ranges};
+pub use builder::{build_symbol_table, build_symbol_table_excluding, update_symbol_table};
+use byref::find_byref_mismatches;
+pub use call_hierarchy::{call_hierarchy_item, find_call_sites, CallSite};
+use call_context::{find_call_context, CallContext};
+use code_actions::{
+    add_event_handlers, add_option_explicit, add_set, convert_gosub, declare_return_type,
+    declare_variable, disable_for_line, extract_procedure, has_code, make_ptr_safe,
+    organize_declarations, remove_set, use_ampersand, use_named_value,
+};
+use constants::find_circular_constants;
+use concatenation::find_plus_concatenation;
+use duplicates::find_duplicate_procedures;
+use error_handling::find_error_handling_problems;
+use event_handlers::find_event_handler_problems;
+use form_properties::{color_hover, find_invalid_property_values};
+use inference::infer_expression_type;
+use integer_arithmetic::find_integer_arithmetic_problems;
+use intrinsics::{get_intrinsic, IntrinsicFunction, INTRINSIC_FUNCTIONS};
+use labels::find_label_problems;
+use named_arguments::find_unknown_named_arguments;
+use ptr_safe::find_x64_declares;
+use set_assignment::find_set_mismatches;
+use shadowing::{find_shadowed_symbols, shadowing_note};
+use snippets::{Snippet, SNIPPETS};
+pub use suppressions::remove_suppressed;
+use undeclared::{find_undeclared_variables, DEFAULT_ALLOWED_IDENTIFIERS};
+use unreachable::find_unreachable_code;
+pub use user_controls::{register_user_control, user_control_name};
 pub use position::{SourcePosition, SourceRange};
 pub use scope::{Scope, ScopeId, ScopeKind};
 pub use symbol::{ParameterInfo, Symbol, SymbolId, SymbolKind, TypeInfo, Visibility};
-pub use symbol_table::{SymbolReference, SymbolTable};
-
-use std::collections::HashMap;
-
+pub use symbol_table::{MemberUse, SymbolReference, SymbolTable};
+
+// Diagnostic codes, to tell which pass reported a diagnostic
+pub use arrays::{REDIM_FIXED_ARRAY, REDIM_PRESERVE_DIMENSIONS, REDIM_UNDECLARED};
+pub use blocks::UNBALANCED_BLOCK;
+pub use byref::BYREF_MISMATCH;
+pub use code_actions::MISSING_OPTION_EXPLICIT;
+pub use concatenation::PLUS_CONCATENATION;
+pub use constants::CIRCULAR_CONSTANT;
+pub use duplicates::DUPLICATE_PROCEDURE;
+pub use error_handling::RESUME_NEXT_NOT_RESET;
+pub use event_handlers::{UNKNOWN_CONTROL, UNKNOWN_EVENT};
+pub use form_properties::INVALID_PROPERTY_VALUE;
+pub use integer_arithmetic::{INTEGER_DIVISION, INTEGER_OVERFLOW};
+pub use labels::{UNDEFINED_LABEL, UNUSED_LABEL};
+pub use named_arguments::UNKNOWN_NAMED_ARGUMENT;
+pub use ptr_safe::NOT_PTR_SAFE;
+pub use set_assignment::{MISSING_SET, SET_ON_VALUE};
+pub use shadowing::SHADOWED_SYMBOL;
+pub use undeclared::UNDECLARED_VARIABLE;
+pub use unreachable::UNREACHABLE_CODE;
+
+/// Diagnostic code for a syntax error
+pub const SYNTAX_ERROR: &str = "syntax-error";
+
+/// Diagnostic code for a variable declared twice in a module
+pub const DUPLICATE_DECLARATION: &str = "duplicate-declaration";
+
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
 use tower_lsp::lsp_types::*;
 
-use crate::parser::{Procedure, ProcedureType, Vb6Ast, Visibility as AstVisibility};
+use crate::parser::{
+    ClassAttributes, InactiveRegion, ProcedureType, Vb6Ast, Visibility as AstVisibility,
+};
+
+/// Payload attached to completion items so documentation can be filled in on resolve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CompletionData {
+    /// A symbol from a document's symbol table
+    #[serde(rename_all = "camelCase")]
+    Symbol { uri: Url, symbol_id: u32 },
+    /// A property or method of a built-in control
+    #[serde(rename_all = "camelCase")]
+    ControlMember { control_type: String, member: String },
+    /// A VB6 runtime function
+    Intrinsic { name: String },
+}
+
+/// What the prefix of a member access resolves to
+enum MemberOwner<'a> {
+    /// A control on a form, or a variable declared as a control type
+    Control {
+        type_name: String,
+        /// The table of the form the control is on (if it is one)
+        table: Option<&'a SymbolTable>,
+        /// Controls placed inside this one
+        children: Vec<&'a Symbol>,
+    },
+    /// A variable of a user-defined type
+    UserType { table: &'a SymbolTable, symbol: &'a Symbol },
+    /// A form, class, or module (`Me`, a form name, or a class variable)
+    Module(&'a SymbolTable),
+}
 
 /// Code analyzer with symbol table support
 pub struct Analyzer {
-    // Analysis state (reserved for future use)
+    /// Names treated as declared under Option Explicit (lowercase)
+    allowed_identifiers: RwLock<HashSet<String>>,
 }
 
 impl Analyzer {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            allowed_identifiers: RwLock::new(Self::default_allowed_identifiers()),
+        }
+    }
+
+    /// The VB runtime names that never need a declaration
+    fn default_allowed_identifiers() -> HashSet<String> {
+        DEFAULT_ALLOWED_IDENTIFIERS
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect()
+    }
+
+    /// Set extra names to treat as declared, in addition to the VB runtime
+    pub fn set_allowed_identifiers(&self, names: &[String]) {
+        let mut allowed = Self::default_allowed_identifiers();
+        allowed.extend(names.iter().map(|name| name.to_lowercase()));
+        *self.allowed_identifiers.write().unwrap() = allowed;
     }
 
     // ==========================================
@@ -56,6 +189,7 @@ impl Analyzer {
                         },
                     },
                     severity: Some(DiagnosticSeverity::WARNING),
+                    code: Some(NumberOrString::String(DUPLICATE_DECLARATION.to_string())),
                     message: format!(
                         "Variable '{}' already declared at line {}",
                         var.name,
@@ -69,36 +203,6 @@ impl Analyzer {
             }
         }
 
-        // Check for procedures without End Sub/Function
-        for proc in &ast.procedures {
-            if proc.end_line.is_none() {
-                diagnostics.push(Diagnostic {
-                    range: Range {
-                        start: Position {
-                            line: proc.line as u32,
-                            character: 0,
-                        },
-                        end: Position {
-                            line: proc.line as u32,
-                            character: 50,
-                        },
-                    },
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    message: format!(
-                        "{} '{}' is missing End statement",
-                        match proc.proc_type {
-                            ProcedureType::Sub => "Sub",
-                            ProcedureType::Function => "Function",
-                            _ => "Property",
-                        },
-                        proc.name
-                    ),
-                    source: Some("vb6-lsp".to_string()),
-                    ..Default::default()
-                });
-            }
-        }
-
         // Warn about Option Explicit
         if !ast
             .options
@@ -117,6 +221,7 @@ impl Analyzer {
                     },
                 },
                 severity: Some(DiagnosticSeverity::INFORMATION),
+                code: Some(NumberOrString::String(MISSING_OPTION_EXPLICIT.to_string())),
                 message: "Consider adding 'Option Explicit' to require variable declarations"
                     .to_string(),
                 source: Some("vb6-lsp".to_string()),
@@ -191,8 +296,16 @@ impl Analyzer {
             });
         }
 
-        // Add keywords
+        // Runtime functions, unless a declaration shadows them
+        for intrinsic in INTRINSIC_FUNCTIONS {
+            if !items.iter().any(|item| item.label.eq_ignore_ascii_case(intrinsic.name)) {
+                items.push(intrinsic_completion_item(intrinsic));
+            }
+        }
+
+        // Add keywords and templates
         items.extend(self.get_keyword_completions());
+        items.extend(SNIPPETS.iter().map(snippet_completion_item));
 
         items
     }
@@ -204,21 +317,8 @@ impl Analyzer {
         // Check procedures
         for proc in &ast.procedures {
             if proc.line == line {
-                let params: Vec<String> = proc
-                    .parameters
-                    .iter()
-                    .map(|p| {
-                        let mut s = format!(
-                            "{} {}",
-                            if p.by_ref { "ByRef" } else { "ByVal" },
-                            p.name
-                        );
-                        if let Some(ref t) = p.param_type {
-                            s.push_str(&format!(" As {}", t));
-                        }
-                        s
-                    })
-                    .collect();
+                let params: Vec<String> =
+                    proc.parameters.iter().map(|p| p.format_signature()).collect();
 
                 let mut signature = format!(
                     "{} {}{}({})",
@@ -270,10 +370,28 @@ impl Analyzer {
                     info.push_str(" (Array)");
                 }
 
+                let mut value = format!("```vb\n{}\n```", info);
+
+                // List the fields of a user-defined type
+                if let Some(user_type) = var.var_type.as_ref().and_then(|t| {
+                    ast.user_types.iter().find(|u| u.name.eq_ignore_ascii_case(t))
+                }) {
+                    if !user_type.members.is_empty() {
+                        value.push_str("\n\n**Members:**");
+                        for member in &user_type.members {
+                            let suffix = if member.is_array { "()" } else { "" };
+                            value.push_str(&format!(
+                                "\n- `{}{} As {}`",
+                                member.name, suffix, member.member_type
+                            ));
+                        }
+                    }
+                }
+
                 return Some(Hover {
                     contents: HoverContents::Markup(MarkupContent {
                         kind: MarkupKind::Markdown,
-                        value: format!("```vb\n{}\n```", info),
+                        value,
                     }),
                     range: None,
                 });
@@ -314,14 +432,33 @@ impl Analyzer {
         None
     }
 
-    /// Get references to a symbol (legacy - stub)
+    /// Get references to a symbol by scanning the source text (legacy)
+    ///
+    /// Used before a symbol table is available. Matches whole identifiers
+    /// case-insensitively, ignoring comments and string literals.
     pub fn get_references(
         &self,
         _ast: &Vb6Ast,
-        _position: Position,
-        _uri: &Url,
+        source: &str,
+        position: Position,
+        uri: &Url,
     ) -> Vec<Location> {
-        Vec::new()
+        let word = match self.word_at_position(source, position) {
+            Some(w) => w,
+            None => return Vec::new(),
+        };
+
+        if RESERVED_WORDS.contains(&word.to_lowercase().as_str()) {
+            return Vec::new();
+        }
+
+        find_identifier_occurrences(source, &word)
+            .into_iter()
+            .map(|range| Location {
+                uri: uri.clone(),
+                range,
+            })
+            .collect()
     }
 
     /// Get document symbols (legacy)
@@ -403,14 +540,52 @@ impl Analyzer {
         symbols
     }
 
-    /// Get code actions (legacy - stub)
+    /// Get quick fixes for the diagnostics in the request context, and
+    /// refactorings for the requested range
     pub fn get_code_actions(
         &self,
-        _ast: &Vb6Ast,
-        _range: Range,
-        _context: &CodeActionContext,
+        uri: &Url,
+        source: &str,
+        tree: Option<&tree_sitter::Tree>,
+        table: Option<&SymbolTable>,
+        range: Range,
+        context: &CodeActionContext,
     ) -> Vec<CodeActionOrCommand> {
-        Vec::new()
+        let mut actions = Vec::new();
+
+        for diagnostic in &context.diagnostics {
+            if has_code(diagnostic, MISSING_OPTION_EXPLICIT) {
+                actions.extend(add_option_explicit(uri, source, diagnostic));
+            } else if let (true, Some(table)) = (has_code(diagnostic, UNDECLARED_VARIABLE), table) {
+                actions.extend(declare_variable(uri, source, table, diagnostic));
+            } else if has_code(diagnostic, PLUS_CONCATENATION) {
+                actions.push(use_ampersand(uri, diagnostic));
+            } else if has_code(diagnostic, MISSING_SET) {
+                actions.extend(add_set(uri, source, diagnostic));
+            } else if has_code(diagnostic, SET_ON_VALUE) {
+                actions.extend(remove_set(uri, source, diagnostic));
+            } else if let (true, Some(tree)) = (has_code(diagnostic, NOT_PTR_SAFE), tree) {
+                actions.extend(make_ptr_safe(uri, source, tree, diagnostic));
+            }
+            if diagnostic.source.as_deref() == Some("vb6-lsp") {
+                actions.extend(disable_for_line(uri, source, diagnostic));
+            }
+        }
+
+        if let (Some(tree), Some(table)) = (tree, table) {
+            actions.extend(convert_gosub(uri, source, tree, table, range.start));
+            actions.extend(declare_return_type(uri, source, tree, table, range.start));
+            actions.extend(add_event_handlers(uri, source, table, range.start));
+            if range.start != range.end {
+                actions.extend(extract_procedure(uri, source, tree, table, range));
+            }
+        }
+        if let Some(tree) = tree {
+            actions.extend(use_named_value(uri, source, tree, table, range.start));
+            actions.extend(organize_declarations(uri, source, tree));
+        }
+
+        actions.into_iter().map(CodeActionOrCommand::CodeAction).collect()
     }
 
     /// Rename a symbol (legacy - stub)
@@ -424,58 +599,499 @@ impl Analyzer {
         None
     }
 
+    // ==========================================
+    // Tree-based diagnostics
+    // ==========================================
+
+    /// Analyze the tree-sitter tree and produce control-flow, error handling, form
+    /// property, and 64-bit Declare diagnostics
+    pub fn analyze_tree(&self, tree: &tree_sitter::Tree, source: &str) -> Vec<Diagnostic> {
+        let mut diagnostics = find_unreachable_code(tree, source);
+        diagnostics.extend(find_error_handling_problems(tree, source));
+        diagnostics.extend(find_invalid_property_values(tree, source));
+        diagnostics.extend(find_x64_declares(tree, source));
+        diagnostics
+    }
+
+    /// Report If/For/Do/... blocks and procedures whose closing keyword is missing
+    /// or unmatched, skipping code excluded by `#If`
+    pub fn find_unbalanced_blocks(
+        &self,
+        uri: &Url,
+        source: &str,
+        inactive: &[InactiveRegion],
+    ) -> Vec<Diagnostic> {
+        find_unbalanced_blocks(uri, source, inactive)
+    }
+
+    /// Block keywords and loop variables edited together with the word at a position
+    pub fn get_linked_editing_ranges(
+        &self,
+        uri: &Url,
+        source: &str,
+        inactive: &[InactiveRegion],
+        position: Position,
+    ) -> Option<Vec<Range>> {
+        linked_editing_ranges(uri, source, inactive, position)
+    }
+
+    /// Describe a form color property value (system color name or RGB breakdown)
+    pub fn get_color_hover(
+        &self,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        position: Position,
+    ) -> Option<Hover> {
+        color_hover(tree, source, position)
+    }
+
     // ==========================================
     // Symbol Table-based methods (enhanced)
     // ==========================================
 
+    /// Report undeclared variables when the module uses Option Explicit
+    ///
+    /// `is_external` resolves names declared in other files of the workspace.
+    pub fn find_undeclared_with_symbols(
+        &self,
+        table: &SymbolTable,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        is_external: impl Fn(&str) -> bool,
+    ) -> Vec<Diagnostic> {
+        let allowed = self.allowed_identifiers.read().unwrap();
+
+        // Code behind a designer can use the form's members unqualified
+        let is_designer = table.is_designer_module();
+
+        find_undeclared_variables(table, tree, source, |name| {
+            allowed.contains(&name.to_lowercase())
+                || crate::controls::get_control(name).is_some()
+                || (is_designer
+                    && (crate::controls::get_property("Form", name).is_some()
+                        || crate::controls::get_method("Form", name).is_some()))
+                || is_external(name)
+        })
+    }
+
+    /// Report `ReDim` of fixed-size or undeclared arrays, and `ReDim Preserve`
+    /// changing more than the last dimension
+    ///
+    /// `is_external` resolves names declared in other files of the workspace.
+    pub fn find_array_problems_with_symbols(
+        &self,
+        table: &SymbolTable,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        is_external: impl Fn(&str) -> bool,
+    ) -> Vec<Diagnostic> {
+        find_array_problems(table, tree, source, is_external)
+    }
+
+    /// Report arguments that do not match the type of their ByRef parameter
+    pub fn find_byref_mismatches_with_symbols(
+        &self,
+        table: &SymbolTable,
+        tree: &tree_sitter::Tree,
+        source: &str,
+    ) -> Vec<Diagnostic> {
+        find_byref_mismatches(table, tree, source)
+    }
+
+    /// Report named arguments (`Name:=value`) that the callee has no parameter for
+    pub fn find_unknown_named_arguments_with_symbols(
+        &self,
+        table: &SymbolTable,
+        tree: &tree_sitter::Tree,
+        source: &str,
+    ) -> Vec<Diagnostic> {
+        find_unknown_named_arguments(table, tree, source)
+    }
+
+    /// Report Integer overflows and `\` or `Mod` applied to floating-point values
+    pub fn find_integer_arithmetic_problems_with_symbols(
+        &self,
+        table: &SymbolTable,
+        tree: &tree_sitter::Tree,
+        source: &str,
+    ) -> Vec<Diagnostic> {
+        find_integer_arithmetic_problems(table, tree, source)
+    }
+
+    /// Report `+` used to concatenate an operand known to be a String
+    pub fn find_plus_concatenation_with_symbols(
+        &self,
+        table: &SymbolTable,
+        tree: &tree_sitter::Tree,
+        source: &str,
+    ) -> Vec<Diagnostic> {
+        find_plus_concatenation(table, tree, source)
+    }
+
+    /// Report object assignments without `Set`, and `Set` on value types.
+    /// `is_class` tells whether a name is a class or form of the project.
+    pub fn find_set_mismatches_with_symbols(
+        &self,
+        table: &SymbolTable,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        is_class: impl Fn(&str) -> bool,
+    ) -> Vec<Diagnostic> {
+        find_set_mismatches(table, tree, source, is_class)
+    }
+
+    /// Report procedures defined more than once in a module
+    pub fn find_duplicate_procedures_with_symbols(&self, table: &SymbolTable) -> Vec<Diagnostic> {
+        find_duplicate_procedures(table)
+    }
+
+    /// Report jumps to labels the procedure does not have, and labels nothing jumps to
+    pub fn find_label_problems_with_symbols(&self, table: &SymbolTable) -> Vec<Diagnostic> {
+        find_label_problems(table)
+    }
+
+    /// Report constants whose value depends on themselves
+    pub fn find_circular_constants_with_symbols(&self, table: &SymbolTable) -> Vec<Diagnostic> {
+        find_circular_constants(table)
+    }
+
+    /// Report event handlers for controls missing from the form, or for events
+    /// their control doesn't raise
+    pub fn find_event_handler_problems_with_symbols(&self, table: &SymbolTable) -> Vec<Diagnostic> {
+        find_event_handler_problems(table)
+    }
+
+    /// Report locals and parameters that hide a module-level symbol of the same name
+    pub fn find_shadowed_symbols_with_symbols(&self, table: &SymbolTable) -> Vec<Diagnostic> {
+        find_shadowed_symbols(table)
+    }
+
+    /// Infer the type of a simple expression (literal, name, `New` or function call)
+    pub fn infer_expression_type(
+        &self,
+        table: &SymbolTable,
+        scope: ScopeId,
+        expr: &tree_sitter::Node,
+        source: &str,
+    ) -> Option<TypeInfo> {
+        infer_expression_type(table, scope, expr, source)
+    }
+
     /// Get hover information using symbol table
     pub fn get_hover_with_symbols(
         &self,
         table: &SymbolTable,
+        source: &str,
         position: Position,
     ) -> Option<Hover> {
         let pos = SourcePosition::from_lsp(position);
 
-        // Find symbol at position
-        let symbol = table.symbol_at_position(pos)?;
+        // Find symbol at position; runtime functions have no declaration
+        let Some(symbol) = table.symbol_at_position(pos) else {
+            return self
+                .get_member_hover(table, source, position)
+                .or_else(|| self.get_intrinsic_hover(source, position))
+                .or_else(|| self.get_color_constant_hover(source, position));
+        };
 
         // Build hover content
-        let signature = symbol.format_signature();
+        let mut value = format!("```vb\n{}\n```", symbol.format_signature());
+
+        // `Const MAX = 10 * 2` shows its value, so mention how it was written
+        if let (Some(expression), Some(resolved)) = (&symbol.value, &symbol.resolved_value) {
+            if *expression != resolved.to_string() {
+                value.push_str(&format!("\n\nEvaluated from `{}`", expression));
+            }
+        }
+
+        // List the fields of a UDT declaration or of a variable of UDT type
+        let user_type = if symbol.kind == SymbolKind::UserDefinedType {
+            Some(symbol)
+        } else {
+            self.resolve_user_type(table, symbol, pos)
+        };
+        if let Some(user_type) = user_type {
+            value.push_str(&self.members_documentation(table, user_type));
+        }
+
+        if let Some(shadowed) = table.find_shadowed(symbol) {
+            value.push_str(&format!("\n\n{}", shadowing_note(table, shadowed)));
+        }
+
+        // Command1(3) names one element of a control array
+        if !symbol.control_array.is_empty() {
+            let index = table
+                .reference_at_position(pos)
+                .and_then(|r| index_after(source, r.range.end));
+            value.push_str(&control_array_documentation(symbol, index));
+        }
 
         Some(Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
-                value: format!("```vb\n{}\n```", signature),
+                value,
             }),
             range: Some(symbol.name_range.to_lsp()),
         })
     }
 
+    /// Get hover for a member after a dot (e.g., `Text` in `txtName.Text`, or
+    /// `.Text` in a With block)
+    fn get_member_hover(
+        &self,
+        table: &SymbolTable,
+        source: &str,
+        position: Position,
+    ) -> Option<Hover> {
+        let line = source.lines().nth(position.line as usize)?;
+        let col = position.character as usize;
+        let start = line.get(..col)?.trim_end_matches(is_identifier_char).len();
+        let end = col + line[col..].find(|c| !is_identifier_char(c)).unwrap_or(line.len() - col);
+        let member = &line[start..end];
+        let chain = dotted_name_before(line[..start].strip_suffix('.')?)?;
+
+        let pos = SourcePosition::from_lsp(position);
+        let owner = self.resolve_member_chain(table, pos, &chain, &|_| None)?;
+        let value = match owner {
+            MemberOwner::Control { type_name, table, children } => {
+                match children.iter().find(|c| c.name.eq_ignore_ascii_case(member)) {
+                    Some(child) => self.symbol_documentation(table?, child),
+                    None => self.control_member_documentation(&type_name, member)?,
+                }
+            }
+            MemberOwner::UserType { table, symbol } => {
+                let field = symbol
+                    .members
+                    .iter()
+                    .filter_map(|&id| table.get_symbol(id))
+                    .find(|field| field.name.eq_ignore_ascii_case(member))?;
+                self.symbol_documentation(table, field)
+            }
+            MemberOwner::Module(table) => {
+                let symbol = table.lookup_symbol(member, table.module_scope)?;
+                self.symbol_documentation(table, symbol)
+            }
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(Range {
+                start: Position::new(position.line, start as u32),
+                end: Position::new(position.line, end as u32),
+            }),
+        })
+    }
+
+    /// Get hover for a VB6 runtime function (e.g., `Left$`)
+    fn get_intrinsic_hover(&self, source: &str, position: Position) -> Option<Hover> {
+        let (intrinsic, string_form) = self.intrinsic_at_position(source, position)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: intrinsic_documentation(intrinsic, string_form),
+            }),
+            range: None,
+        })
+    }
+
+    /// Get hover for a color constant (e.g., `vbButtonFace`), decoding its value
+    fn get_color_constant_hover(&self, source: &str, position: Position) -> Option<Hover> {
+        if self.qualifier_at_position(source, position).is_some() {
+            return None;
+        }
+        let word = self.word_at_position(source, position)?;
+        let constant = crate::controls::get_color_constant(&word)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```vb
+Const {}
+```
+
+{}", constant.name, constant.color.describe()),
+            }),
+            range: None,
+        })
+    }
+
+    /// Get hover for a class name, showing its instancing
+    pub fn get_class_hover(
+        &self,
+        source: &str,
+        position: Position,
+        class_attributes: impl Fn(&str) -> Option<ClassAttributes>,
+    ) -> Option<Hover> {
+        let word = self.word_at_position(source, position)?;
+        let attributes = class_attributes(&word)?;
+        let name = attributes.name.as_deref().unwrap_or(&word);
+
+        let mut value = format!(
+            "```vb\nClass {}\n```\n**Instancing:** {}",
+            name,
+            attributes.instancing()
+        );
+        if attributes.predeclared_id {
+            value.push_str(&format!("\n\n`{}` refers to a predeclared default instance", name));
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
     /// Get definition location using symbol table
+    ///
+    /// Names not defined in this file are passed to `resolve_external` with their
+    /// module qualifier, if any (`modUtils.DoThing` -> `(Some("modUtils"), "DoThing")`).
     pub fn get_definition_with_symbols(
         &self,
         table: &SymbolTable,
         source: &str,
         position: Position,
+        resolve_external: impl Fn(Option<&str>, &str) -> Option<Location>,
     ) -> Option<GotoDefinitionResponse> {
         let pos = SourcePosition::from_lsp(position);
+        let word = self.word_at_position(source, position);
+
+        // A member qualified by something other than a local name may be in another module
+        let qualifier = self.qualifier_at_position(source, position);
+        if let (Some(word), Some(qualifier)) = (&word, qualifier) {
+            match table.lookup_at_position(&qualifier, pos) {
+                None => {
+                    if let Some(location) = resolve_external(Some(&qualifier), word) {
+                        return Some(GotoDefinitionResponse::Scalar(location));
+                    }
+                }
+                // A member of a UserControl placed on the form is in its .ctl
+                Some(control) if control.kind == SymbolKind::FormControl => {
+                    let user_control = control
+                        .type_info
+                        .as_ref()
+                        .and_then(|t| crate::controls::get_user_control(&t.name));
+                    if let Some(user_control) = user_control {
+                        if let Some(location) = resolve_external(Some(user_control.name), word) {
+                            return Some(GotoDefinitionResponse::Scalar(location));
+                        }
+                    }
+                }
+                Some(_) => {}
+            }
+        }
 
         // Try to find symbol at cursor position
         if let Some(symbol) = table.symbol_at_position(pos) {
+            // The name of an event handler goes to the Begin block of its control
+            let control = symbol
+                .handles
+                .as_ref()
+                .filter(|_| symbol.name_range.contains(pos))
+                .and_then(|binding| table.get_symbol(binding.control?));
+            if let Some(control) = control {
+                return Some(GotoDefinitionResponse::Scalar(Location {
+                    uri: table.uri.clone(),
+                    range: control.definition_range.to_lsp(),
+                }));
+            }
+
+            // Text1(2) jumps to the Begin line of element 2 of a control array
+            let index = table
+                .reference_at_position(pos)
+                .and_then(|r| index_after(source, r.range.end));
+            let element = control_array_element(symbol, index);
+
             return Some(GotoDefinitionResponse::Scalar(Location {
                 uri: table.uri.clone(),
-                range: symbol.name_range.to_lsp(),
+                range: element.unwrap_or(symbol.name_range).to_lsp(),
             }));
         }
 
         // Try to find word at position and look it up
+        let word = word?;
+        if let Some(symbol) = table.lookup_at_position(&word, pos) {
+            return Some(GotoDefinitionResponse::Scalar(Location {
+                uri: table.uri.clone(),
+                range: symbol.name_range.to_lsp(),
+            }));
+        }
+
+        resolve_external(None, &word).map(GotoDefinitionResponse::Scalar)
+    }
+
+    /// Go to the declaration of the type of the symbol at a position: a Type
+    /// or Enum of the file, or one resolved by `resolve_external` (classes and
+    /// the Public types of other modules). A control goes to its Begin block.
+    pub fn get_type_definition_with_symbols(
+        &self,
+        table: &SymbolTable,
+        source: &str,
+        position: Position,
+        resolve_external: impl Fn(&str) -> Option<Location>,
+    ) -> Option<GotoDefinitionResponse> {
+        let pos = SourcePosition::from_lsp(position);
+        let symbol = table.symbol_at_position(pos).or_else(|| {
+            let word = self.word_at_position(source, position)?;
+            table.lookup_at_position(&word, pos)
+        })?;
+        let local = |range: SourceRange| {
+            Some(GotoDefinitionResponse::Scalar(Location {
+                uri: table.uri.clone(),
+                range: range.to_lsp(),
+            }))
+        };
+
+        match symbol.kind {
+            SymbolKind::UserDefinedType | SymbolKind::Enum => return local(symbol.name_range),
+            SymbolKind::FormControl => return local(symbol.definition_range),
+            _ => {}
+        }
+
+        let type_name = &symbol.type_info.as_ref()?.name;
+        let declared = table
+            .lookup_symbol(type_name, symbol.scope_id)
+            .filter(|s| matches!(s.kind, SymbolKind::UserDefinedType | SymbolKind::Enum));
+        match declared {
+            Some(declared) => local(declared.name_range),
+            None => resolve_external(type_name).map(GotoDefinitionResponse::Scalar),
+        }
+    }
+
+    /// The interface, and member of it, whose implementations to find from a
+    /// position: a class name (`Implements IShape`, `As IShape`), a procedure of
+    /// the class `module` itself, or a member called on an object (`shape.Draw`)
+    pub fn implementation_target(
+        &self,
+        table: &SymbolTable,
+        source: &str,
+        position: Position,
+        module: &str,
+    ) -> Option<(String, Option<String>)> {
+        let pos = SourcePosition::from_lsp(position);
         let word = self.word_at_position(source, position)?;
-        let symbol = table.lookup_at_position(&word, pos)?;
 
-        Some(GotoDefinitionResponse::Scalar(Location {
-            uri: table.uri.clone(),
-            range: symbol.name_range.to_lsp(),
-        }))
+        if let Some(qualifier) = self.qualifier_at_position(source, position) {
+            let interface = match table.lookup_at_position(&qualifier, pos) {
+                Some(symbol) => symbol.type_info.as_ref()?.name.clone(),
+                None => qualifier,
+            };
+            return Some((interface, Some(word)));
+        }
+
+        match table.lookup_at_position(&word, pos) {
+            Some(symbol) if symbol.kind.is_procedure() && symbol.scope_id == table.module_scope => {
+                Some((module.to_string(), Some(symbol.name.clone())))
+            }
+            Some(symbol) => Some((symbol.type_info.as_ref()?.name.clone(), None)),
+            None => Some((word, None)),
+        }
     }
 
     /// Get references using symbol table
@@ -496,157 +1112,660 @@ impl Analyzer {
             .collect()
     }
 
-    /// Get completions using symbol table
-    pub fn get_completions_with_symbols(
+    /// Get highlights for every occurrence of the symbol at a position.
+    ///
+    /// The declaration and assignment targets are writes; other uses are reads.
+    pub fn get_document_highlights_with_symbols(
         &self,
         table: &SymbolTable,
         position: Position,
+    ) -> Vec<DocumentHighlight> {
+        let pos = SourcePosition::from_lsp(position);
+
+        table
+            .find_all_references(pos)
+            .into_iter()
+            .enumerate()
+            .map(|(i, range)| {
+                // The first range is the declaration itself
+                let is_write = i == 0
+                    || table
+                        .reference_at_position(range.start)
+                        .is_some_and(|r| r.is_assignment);
+
+                DocumentHighlight {
+                    range: range.to_lsp(),
+                    kind: Some(if is_write {
+                        DocumentHighlightKind::WRITE
+                    } else {
+                        DocumentHighlightKind::READ
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Check that the symbol at a position can be renamed and get its range
+    ///
+    /// Returns an error message for keywords, literals, member accesses on
+    /// other objects, and form controls.
+    pub fn prepare_rename_with_symbols(
+        &self,
+        table: &SymbolTable,
+        position: Position,
+    ) -> std::result::Result<Range, String> {
+        let pos = SourcePosition::from_lsp(position);
+
+        let symbol = table
+            .symbol_at_position(pos)
+            .ok_or_else(|| "No renameable symbol at this position".to_string())?;
+
+        if symbol.kind == SymbolKind::FormControl {
+            return Err(CONTROL_RENAME_ERROR.to_string());
+        }
+
+        // Range of the identifier under the cursor (definition or usage)
+        let range = table
+            .reference_at_position(pos)
+            .map(|r| r.range)
+            .unwrap_or(symbol.name_range);
+
+        Ok(range.to_lsp())
+    }
+
+    /// Rename a symbol and all its references using symbol table
+    ///
+    /// Returns an error message if the new name is not a valid identifier or
+    /// collides with another symbol in the same scope.
+    pub fn rename_with_symbols(
+        &self,
+        table: &SymbolTable,
+        position: Position,
+        new_name: &str,
+    ) -> std::result::Result<Option<WorkspaceEdit>, String> {
+        let pos = SourcePosition::from_lsp(position);
+
+        let symbol = match table.symbol_at_position(pos) {
+            Some(s) => s,
+            None => return Ok(None),
+        };
+
+        if symbol.kind == SymbolKind::FormControl {
+            return Err(CONTROL_RENAME_ERROR.to_string());
+        }
+
+        if !is_valid_identifier(new_name) {
+            return Err(format!("'{}' is not a valid VB6 identifier", new_name));
+        }
+
+        // Changing only the case of a name never collides (VB6 is case-insensitive)
+        if !new_name.eq_ignore_ascii_case(&symbol.name) {
+            if let Some(scope) = table.get_scope(symbol.scope_id) {
+                if scope.has_symbol(new_name) {
+                    return Err(format!("'{}' is already declared in this scope", new_name));
+                }
+            }
+        }
+
+        // Property Get/Let/Set accessors share a name and are renamed together
+        let is_property = |kind: SymbolKind| {
+            matches!(
+                kind,
+                SymbolKind::PropertyGet | SymbolKind::PropertyLet | SymbolKind::PropertySet
+            )
+        };
+        let related: Vec<&Symbol> = if is_property(symbol.kind) {
+            table
+                .all_symbols()
+                .filter(|s| {
+                    is_property(s.kind)
+                        && s.scope_id == symbol.scope_id
+                        && s.name.eq_ignore_ascii_case(&symbol.name)
+                })
+                .collect()
+        } else {
+            vec![symbol]
+        };
+
+        let mut ranges: Vec<SourceRange> = Vec::new();
+        for s in related {
+            ranges.push(s.name_range);
+            ranges.extend(table.get_references(s.id).iter().map(|r| r.range));
+        }
+        ranges.sort_by_key(|r| r.start);
+        ranges.dedup();
+
+        let edits: Vec<TextEdit> = ranges
+            .into_iter()
+            .map(|range| TextEdit {
+                range: range.to_lsp(),
+                new_text: new_name.to_string(),
+            })
+            .collect();
+
+        let mut changes = HashMap::new();
+        changes.insert(table.uri.clone(), edits);
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }))
+    }
+
+    /// Get completions using symbol table
+    ///
+    /// `module_table` finds the symbol table of another form, class, or module
+    /// by name, so member access chains can walk into it.
+    pub fn get_completions_with_symbols<'a>(
+        &self,
+        table: &'a SymbolTable,
+        position: Position,
         source: &str,
+        predeclared_objects: &[String],
+        module_table: impl Fn(&str) -> Option<&'a SymbolTable>,
     ) -> Vec<CompletionItem> {
         let pos = SourcePosition::from_lsp(position);
         let mut items = Vec::new();
 
         // Check if we're completing after a dot (member access)
-        if let Some(member_completions) = self.get_member_completions(table, position, source) {
+        if let Some(member_completions) =
+            self.get_member_completions(table, position, source, &module_table)
+        {
             return member_completions;
         }
 
+        // Assigning to an enum-typed property or variable offers its values
+        if let Some(values) =
+            self.get_assignment_value_completions(table, position, source, &module_table)
+        {
+            return values;
+        }
+
         // Get visible symbols at this position
         for symbol in table.visible_symbols(pos) {
-            items.push(self.symbol_to_completion_item(symbol));
+            items.push(self.symbol_to_completion_item(&table.uri, symbol));
+        }
+
+        // Between procedures of a form, whole handlers for control events
+        let module_level = table.scope_at_position(pos) == table.module_scope;
+        if module_level {
+            items.extend(self.event_handler_completions(table));
         }
 
-        // Add keywords
+        // Forms and predeclared classes of the project are global objects
+        for name in predeclared_objects {
+            if !items.iter().any(|item| item.label.eq_ignore_ascii_case(name)) {
+                items.push(CompletionItem {
+                    label: name.clone(),
+                    kind: Some(CompletionItemKind::CLASS),
+                    detail: Some("Predeclared instance".to_string()),
+                    ..Default::default()
+                });
+            }
+        }
+
+        // Runtime functions, unless a declaration shadows them
+        for intrinsic in INTRINSIC_FUNCTIONS {
+            if !items.iter().any(|item| item.label.eq_ignore_ascii_case(intrinsic.name)) {
+                items.push(intrinsic_completion_item(intrinsic));
+            }
+        }
+
+        // Add keywords, and the templates for declarations or statements
         items.extend(self.get_keyword_completions());
+        items.extend(
+            SNIPPETS
+                .iter()
+                .filter(|snippet| snippet.module_level == module_level)
+                .map(snippet_completion_item),
+        );
+
+        items
+    }
 
+    /// Handler stubs for the events of each control that the module doesn't handle yet
+    fn event_handler_completions(&self, table: &SymbolTable) -> Vec<CompletionItem> {
+        let mut items = Vec::new();
+        if !table.is_designer_module() {
+            return items;
+        }
+
+        // The form itself raises its events as `Form_Event`
+        let controls = table
+            .symbols_of_kind(SymbolKind::FormControl)
+            .filter(|s| s.control_path.as_ref().is_some_and(|path| path.contains('.')));
+        for control in controls {
+            let Some(type_info) = &control.type_info else {
+                continue;
+            };
+            for event_name in crate::controls::get_event_names(&type_info.name) {
+                let Some(event) = crate::controls::get_event(&type_info.name, event_name) else {
+                    continue;
+                };
+                if table.event_handler(control.id, event.name).is_some() {
+                    continue;
+                }
+                let name = format!("{}_{}", control.name, event.name);
+                items.push(CompletionItem {
+                    label: name.clone(),
+                    kind: Some(CompletionItemKind::EVENT),
+                    detail: Some(format!("{} event of {}", event.name, type_info.name)),
+                    documentation: Some(Documentation::String(event.description.to_string())),
+                    insert_text: Some(format!(
+                        "Private Sub {}({})\n    $0\nEnd Sub",
+                        name,
+                        code_actions::handler_parameters(control, event)
+                    )),
+                    insert_text_format: Some(InsertTextFormat::SNIPPET),
+                    ..Default::default()
+                });
+            }
+        }
         items
     }
 
-    /// Get member completions (e.g., after typing "txtName.")
-    fn get_member_completions(
+    /// Get member completions after a dotted prefix (e.g., "txtName." or "Me.Text1.")
+    fn get_member_completions<'a>(
         &self,
-        table: &SymbolTable,
+        table: &'a SymbolTable,
         position: Position,
         source: &str,
+        module_table: impl Fn(&str) -> Option<&'a SymbolTable>,
     ) -> Option<Vec<CompletionItem>> {
-        use tower_lsp::lsp_types::CompletionItemKind;
+        let chain = member_access_chain(source, position)?;
+        let pos = SourcePosition::from_lsp(position);
+        let owner = self.resolve_member_chain(table, pos, &chain, &module_table)?;
 
-        // Get the line up to cursor position
-        let line_idx = position.line as usize;
-        let char_idx = position.character as usize;
+        // A module qualifying itself by name (not through a variable) reaches
+        // its own Private members
+        let own_module = matches!(owner, MemberOwner::Module(target) if target.uri == table.uri)
+            && chain.len() == 1
+            && !chain[0].eq_ignore_ascii_case("me")
+            && table.lookup_at_position(&chain[0], pos).is_none();
 
-        let lines: Vec<&str> = source.lines().collect();
-        if line_idx >= lines.len() {
-            return None;
-        }
+        let completions = self.member_owner_completions(&owner, own_module);
+        (!completions.is_empty()).then_some(completions)
+    }
 
-        let line = lines[line_idx];
-        if char_idx > line.len() {
-            return None;
+    /// Offer the values of an enum-typed target after `=` (e.g., "Text1.Alignment = ")
+    fn get_assignment_value_completions<'a>(
+        &self,
+        table: &'a SymbolTable,
+        position: Position,
+        source: &str,
+        module_table: impl Fn(&str) -> Option<&'a SymbolTable>,
+    ) -> Option<Vec<CompletionItem>> {
+        let chain = assignment_target_chain(source, position)?;
+        let pos = SourcePosition::from_lsp(position);
+        let (target, owner_chain) = chain.split_last()?;
+
+        // The declared type of the target, and the table to look it up in
+        let (type_table, scope, type_name) = if owner_chain.is_empty() {
+            let symbol = table.lookup_at_position(target, pos)?;
+            (table, table.scope_at_position(pos), symbol.type_info.as_ref()?.name.clone())
+        } else {
+            match self.resolve_member_chain(table, pos, owner_chain, &module_table)? {
+                MemberOwner::Control { type_name, .. } => {
+                    let property = crate::controls::get_property(&type_name, target)?;
+                    if property.property_type == crate::controls::PropertyType::Color {
+                        return Some(color_constant_completions());
+                    }
+                    let completions = property_value_completions(property.valid_values);
+                    return (!completions.is_empty()).then_some(completions);
+                }
+                MemberOwner::UserType { table, symbol } => {
+                    let field = symbol
+                        .members
+                        .iter()
+                        .filter_map(|&id| table.get_symbol(id))
+                        .find(|field| field.name.eq_ignore_ascii_case(target))?;
+                    (table, table.module_scope, field.type_info.as_ref()?.name.clone())
+                }
+                MemberOwner::Module(table) => {
+                    let member = table
+                        .lookup_symbol(target, table.module_scope)
+                        .filter(|s| s.visibility != Visibility::Private)?;
+                    (table, table.module_scope, member.type_info.as_ref()?.name.clone())
+                }
+            }
+        };
+
+        if type_name.eq_ignore_ascii_case("OLE_COLOR") {
+            return Some(color_constant_completions());
         }
+        let enumeration = type_table
+            .lookup_symbol(&type_name, scope)
+            .filter(|s| s.kind == SymbolKind::Enum)?;
+        Some(enum_member_completions(type_table, enumeration))
+    }
 
-        let before_cursor = &line[..char_idx];
+    /// Resolve every name of a member access chain
+    fn resolve_member_chain<'a>(
+        &self,
+        table: &'a SymbolTable,
+        pos: SourcePosition,
+        chain: &[String],
+        module_table: &impl Fn(&str) -> Option<&'a SymbolTable>,
+    ) -> Option<MemberOwner<'a>> {
+        let (head, rest) = chain.split_first()?;
+        let mut owner = self.resolve_member_head(table, pos, head, module_table)?;
+        for segment in rest {
+            owner = self.resolve_member(owner, segment, module_table)?;
+        }
+        Some(owner)
+    }
 
-        // Check if we just typed a dot
-        if !before_cursor.ends_with('.') {
-            return None;
+    /// Resolve the first name of a member access chain
+    fn resolve_member_head<'a>(
+        &self,
+        table: &'a SymbolTable,
+        pos: SourcePosition,
+        name: &str,
+        module_table: &impl Fn(&str) -> Option<&'a SymbolTable>,
+    ) -> Option<MemberOwner<'a>> {
+        if name.eq_ignore_ascii_case("me") {
+            return Some(MemberOwner::Module(table));
         }
 
-        // Get the identifier before the dot
-        let before_dot = before_cursor.trim_end_matches('.');
-        let last_word = before_dot
-            .split(|c: char| !c.is_alphanumeric() && c != '_')
-            .last()?;
+        // `.Text` in a With block is a member of the innermost With object
+        if name.is_empty() {
+            let with_block = table.with_block_at(pos)?;
+            let chain = dotted_name_before(with_block.with_object.as_deref()?)?;
+            return self.resolve_member_chain(table, with_block.range.start, &chain, module_table);
+        }
 
-        if last_word.is_empty() {
-            return None;
+        match table.lookup_at_position(name, pos) {
+            Some(symbol) => {
+                let scope = table.scope_at_position(pos);
+                self.member_owner_of(table, scope, symbol, module_table)
+            }
+            // A form, class, or module of the project
+            None => module_table(name).map(MemberOwner::Module),
         }
+    }
 
-        // Look up the symbol
-        let pos = SourcePosition::from_lsp(position);
-        let symbol = table.lookup_at_position(last_word, pos)?;
+    /// Resolve the next name of a member access chain within its owner
+    fn resolve_member<'a>(
+        &self,
+        owner: MemberOwner<'a>,
+        name: &str,
+        module_table: &impl Fn(&str) -> Option<&'a SymbolTable>,
+    ) -> Option<MemberOwner<'a>> {
+        match owner {
+            MemberOwner::Control { type_name, table, children } => {
+                if let Some(child) = children.iter().find(|c| c.name.eq_ignore_ascii_case(name)) {
+                    return self.member_owner_of(table?, table?.module_scope, child, module_table);
+                }
+                let member_type = crate::controls::get_property(&type_name, name)
+                    .map(|prop| prop.property_type.vb6_type())
+                    .or_else(|| crate::controls::get_method(&type_name, name)?.return_type)?;
+                self.member_owner_of_type(table, None, member_type, module_table)
+            }
+            MemberOwner::UserType { table, symbol } => {
+                let field = symbol
+                    .members
+                    .iter()
+                    .filter_map(|&id| table.get_symbol(id))
+                    .find(|field| field.name.eq_ignore_ascii_case(name))?;
+                self.member_owner_of(table, table.module_scope, field, module_table)
+            }
+            MemberOwner::Module(table) => {
+                let member = table
+                    .lookup_symbol(name, table.module_scope)
+                    .filter(|s| {
+                        s.kind == SymbolKind::FormControl || s.visibility != Visibility::Private
+                    })?;
+                self.member_owner_of(table, table.module_scope, member, module_table)
+            }
+        }
+    }
 
-        // Check if it's a form control
+    /// What a symbol's members are: a control's, or those of its declared type
+    fn member_owner_of<'a>(
+        &self,
+        table: &'a SymbolTable,
+        scope: ScopeId,
+        symbol: &'a Symbol,
+        module_table: &impl Fn(&str) -> Option<&'a SymbolTable>,
+    ) -> Option<MemberOwner<'a>> {
+        let type_info = symbol.type_info.as_ref().filter(|t| !t.is_array)?;
         if symbol.kind == SymbolKind::FormControl {
-            let type_name = symbol.type_info.as_ref()?.name.clone();
-            let control = crate::controls::get_control(&type_name)?;
-
-            let mut completions = Vec::new();
-
-            // Add properties
-            for prop in control.properties {
-                let mut item = CompletionItem {
-                    label: prop.name.to_string(),
-                    kind: Some(CompletionItemKind::PROPERTY),
-                    detail: Some(prop.description.to_string()),
-                    documentation: Some(Documentation::String(format!(
-                        "**Type:** {}\n\n{}\n\n**Default:** {}",
-                        prop.property_type.vb6_type(),
-                        prop.description,
-                        prop.default_value.unwrap_or("(none)")
-                    ))),
-                    insert_text: None,
-                    insert_text_format: None,
-                    ..Default::default()
-                };
+            return Some(MemberOwner::Control {
+                type_name: type_info.name.clone(),
+                table: Some(table),
+                children: symbol.members.iter().filter_map(|&id| table.get_symbol(id)).collect(),
+            });
+        }
+        self.member_owner_of_type(Some(table), Some(scope), &type_info.name, module_table)
+    }
 
-                // For enum properties, show valid values
-                if !prop.valid_values.is_empty() {
-                    let mut doc = format!(
-                        "**Type:** {}\n\n{}\n\n**Valid Values:**\n",
-                        prop.property_type.vb6_type(),
-                        prop.description
-                    );
-                    for value in prop.valid_values.iter().take(10) {
-                        doc.push_str(&format!("\n- `{}` ({}): {}", value.value, value.name, value.description));
-                    }
-                    if prop.valid_values.len() > 10 {
-                        doc.push_str(&format!("\n- ... and {} more values", prop.valid_values.len() - 10));
-                    }
-                    item.documentation = Some(Documentation::String(doc));
+    /// What the members of a type name are: a user-defined type, a control
+    /// type, or another form or class of the project
+    fn member_owner_of_type<'a>(
+        &self,
+        table: Option<&'a SymbolTable>,
+        scope: Option<ScopeId>,
+        type_name: &str,
+        module_table: &impl Fn(&str) -> Option<&'a SymbolTable>,
+    ) -> Option<MemberOwner<'a>> {
+        // Qualified names such as VB.TextBox
+        let type_name = type_name.rsplit('.').next().unwrap_or(type_name);
+
+        if let Some(table) = table {
+            let scope = scope.unwrap_or(table.module_scope);
+            if let Some(user_type) = table
+                .lookup_symbol(type_name, scope)
+                .filter(|s| s.kind == SymbolKind::UserDefinedType)
+            {
+                return Some(MemberOwner::UserType { table, symbol: user_type });
+            }
+        }
+
+        if let Some(control) = crate::controls::get_control(type_name) {
+            return Some(MemberOwner::Control {
+                type_name: control.name.to_string(),
+                table: None,
+                children: Vec::new(),
+            });
+        }
+
+        module_table(type_name).map(MemberOwner::Module)
+    }
+
+    /// Completion items for the members of a resolved owner; a module's Private
+    /// members only with `include_private`
+    fn member_owner_completions(
+        &self,
+        owner: &MemberOwner,
+        include_private: bool,
+    ) -> Vec<CompletionItem> {
+        match owner {
+            MemberOwner::Control { type_name, children, .. } => {
+                // Controls placed inside this container (Frame, PictureBox, the form itself)
+                let mut completions: Vec<CompletionItem> = children
+                    .iter()
+                    .map(|child| control_completion_item(child))
+                    .collect();
+                completions.extend(control_type_completions(type_name));
+                completions
+            }
+            MemberOwner::UserType { table, symbol } => symbol
+                .members
+                .iter()
+                .filter_map(|&id| table.get_symbol(id))
+                .map(|member| self.symbol_to_completion_item(&table.uri, member))
+                .collect(),
+            MemberOwner::Module(table) => {
+                let mut completions = Vec::new();
+
+                // A form's controls and the properties of the form itself
+                let controls: Vec<&Symbol> =
+                    table.symbols_of_kind(SymbolKind::FormControl).collect();
+                let root = controls
+                    .iter()
+                    .find(|c| c.control_path.as_deref().is_some_and(|path| !path.contains('.')));
+                for control in controls.iter().filter(|c| Some(c.id) != root.map(|r| r.id)) {
+                    completions.push(control_completion_item(control));
                 }
 
-                completions.push(item);
-            }
-
-            // Add methods
-            for method in control.methods {
-                completions.push(CompletionItem {
-                    label: method.name.to_string(),
-                    kind: Some(CompletionItemKind::METHOD),
-                    detail: Some(method.description.to_string()),
-                    documentation: Some(Documentation::String(format!(
-                        "{}\n\n**Signature:** `{}`",
-                        method.description,
-                        method.signature
-                    ))),
-                    insert_text: Some(format!("{}($1)", method.name)),
-                    insert_text_format: Some(InsertTextFormat::SNIPPET),
-                    ..Default::default()
-                });
+                completions.extend(
+                    table
+                        .module_symbols()
+                        .into_iter()
+                        .filter(|s| include_private || s.visibility != Visibility::Private)
+                        // Events are raised, not called through the object
+                        .filter(|s| {
+                            !matches!(
+                                s.kind,
+                                SymbolKind::FormControl
+                                    | SymbolKind::TypeMember
+                                    | SymbolKind::EnumMember
+                                    | SymbolKind::Event
+                            )
+                        })
+                        .map(|s| self.symbol_to_completion_item(&table.uri, s)),
+                );
+
+                if let Some(form_type) = root.and_then(|r| r.type_info.as_ref()) {
+                    completions.extend(control_type_completions(&form_type.name));
+                }
+                completions
             }
+        }
+    }
 
-            return Some(completions);
+    /// Find the user-defined type a symbol is declared as (e.g., "Dim c As Customer")
+    fn resolve_user_type<'a>(
+        &self,
+        table: &'a SymbolTable,
+        symbol: &Symbol,
+        pos: SourcePosition,
+    ) -> Option<&'a Symbol> {
+        let type_name = &symbol.type_info.as_ref()?.name;
+        table
+            .lookup_at_position(type_name, pos)
+            .filter(|s| s.kind == SymbolKind::UserDefinedType)
+    }
+
+    /// Fill in documentation for a completion item using its data payload
+    ///
+    /// `table` is the symbol table of the file named in the payload (if any).
+    pub fn resolve_completion(
+        &self,
+        mut item: CompletionItem,
+        table: Option<&SymbolTable>,
+    ) -> CompletionItem {
+        let data = match item
+            .data
+            .clone()
+            .and_then(|d| serde_json::from_value::<CompletionData>(d).ok())
+        {
+            Some(d) => d,
+            None => return item,
+        };
+
+        let doc = match data {
+            CompletionData::Symbol { symbol_id, .. } => table
+                .and_then(|t| t.get_symbol(SymbolId(symbol_id)).map(|s| (t, s)))
+                .map(|(t, s)| self.symbol_documentation(t, s)),
+            CompletionData::ControlMember { control_type, member } => {
+                self.control_member_documentation(&control_type, &member)
+            }
+            CompletionData::Intrinsic { name } => get_intrinsic(&name)
+                .map(|(intrinsic, string_form)| intrinsic_documentation(intrinsic, string_form)),
+        };
+
+        if let Some(doc) = doc {
+            item.documentation = Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: doc,
+            }));
         }
 
-        None
+        item
+    }
+
+    /// Get signature help for the call enclosing the cursor using symbol table
+    pub fn get_signature_help_with_symbols(
+        &self,
+        table: &SymbolTable,
+        source: &str,
+        position: Position,
+    ) -> Option<SignatureHelp> {
+        let context = find_call_context(source, position)?;
+        let pos = SourcePosition::from_lsp(position);
+
+        let signatures: Vec<SignatureInformation> = match context.qualifier {
+            // Method call on a form control (e.g., txtName.Move)
+            Some(ref qualifier) => {
+                let object_name = qualifier.rsplit('.').next()?;
+                let object = table.lookup_at_position(object_name, pos)?;
+                if object.kind != SymbolKind::FormControl {
+                    return None;
+                }
+                let type_name = &object.type_info.as_ref()?.name;
+                let method = crate::controls::get_method(type_name, &context.name)?;
+                vec![self.method_to_signature(method)]
+            }
+            // Procedure in this document; Property Get/Let/Set share a name
+            None => match table.lookup_at_position(&context.name, pos) {
+                Some(symbol) => table
+                    .all_symbols()
+                    .filter(|s| {
+                        s.kind.is_callable()
+                            && s.scope_id == symbol.scope_id
+                            && s.name.eq_ignore_ascii_case(&symbol.name)
+                    })
+                    .map(|s| self.symbol_to_signature(s, &context))
+                    .collect(),
+                // Runtime function (e.g., InStr)
+                None => {
+                    let (intrinsic, string_form) = get_intrinsic(&context.name)?;
+                    vec![self.intrinsic_to_signature(intrinsic, string_form)]
+                }
+            },
+        };
+
+        if signatures.is_empty() {
+            return None;
+        }
+
+        Some(SignatureHelp {
+            signatures,
+            active_signature: Some(0),
+            active_parameter: Some(context.active_parameter),
+        })
     }
 
     /// Get document symbols using symbol table
     pub fn get_document_symbols_with_symbols(&self, table: &SymbolTable) -> Vec<DocumentSymbol> {
         let mut symbols = Vec::new();
 
-        for symbol in table.module_symbols() {
+        let mut module_symbols = table.module_symbols();
+        module_symbols.sort_by_key(|symbol| symbol.definition_range.start);
+
+        for symbol in module_symbols {
             // Skip form controls from document outline - they're for go-to-definition only
             if symbol.kind == SymbolKind::FormControl {
                 continue;
             }
+            // Enum members and Type fields are nested under their parent
+            if matches!(symbol.kind, SymbolKind::EnumMember | SymbolKind::TypeMember) {
+                continue;
+            }
+
+            // Event handlers are methods of the form, control, or object they handle
+            let kind = if table.is_event_handler(symbol) {
+                tower_lsp::lsp_types::SymbolKind::METHOD
+            } else {
+                symbol.kind.to_lsp()
+            };
 
             #[allow(deprecated)]
             symbols.push(DocumentSymbol {
                 name: symbol.name.clone(),
                 detail: symbol.type_info.as_ref().map(|t| t.display()),
-                kind: symbol.kind.to_lsp(),
+                kind,
                 range: symbol.definition_range.to_lsp(),
                 selection_range: symbol.name_range.to_lsp(),
                 children: self.get_child_symbols(table, symbol),
@@ -658,6 +1777,25 @@ impl Analyzer {
         symbols
     }
 
+    /// Get document symbols as a flat list, for clients without hierarchical
+    /// document symbol support. Each entry names its container: the module for
+    /// top-level symbols, the Enum or Type for members.
+    pub fn get_flat_document_symbols(&self, table: &SymbolTable) -> Vec<SymbolInformation> {
+        let module = table
+            .uri
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .map(|file_name| file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem))
+            .unwrap_or_default()
+            .to_string();
+
+        let mut symbols = Vec::new();
+        for symbol in self.get_document_symbols_with_symbols(table) {
+            flatten_document_symbol(&table.uri, symbol, &module, &mut symbols);
+        }
+        symbols
+    }
+
     // ==========================================
     // Helper methods
     // ==========================================
@@ -693,17 +1831,85 @@ impl Analyzer {
         }
     }
 
-    fn symbol_to_completion_item(&self, symbol: &Symbol) -> CompletionItem {
+    fn symbol_to_signature(&self, symbol: &Symbol, context: &CallContext) -> SignatureInformation {
+        let label = symbol.format_signature();
+        let params: Vec<String> = symbol
+            .parameters
+            .iter()
+            .map(|p| p.format_signature())
+            .collect();
+        let search_from = label.find('(').unwrap_or(0);
+
+        // A named argument is its parameter wherever it appears, and every
+        // argument from a ParamArray on belongs to it
+        let named = context.named_argument.as_ref().and_then(|name| {
+            symbol.parameters.iter().position(|p| p.name.eq_ignore_ascii_case(name))
+        });
+        let active_parameter = match (named, symbol.parameters.last()) {
+            (Some(index), _) => Some(index as u32),
+            (None, Some(last)) if last.is_param_array => {
+                Some(context.active_parameter.min(symbol.parameters.len() as u32 - 1))
+            }
+            _ => None,
+        };
+
+        SignatureInformation {
+            parameters: Some(parameter_information(&label, search_from, &params)),
+            label,
+            documentation: symbol
+                .documentation
+                .as_ref()
+                .map(|d| Documentation::String(d.clone())),
+            active_parameter,
+        }
+    }
+
+    fn method_to_signature(&self, method: &crate::controls::MethodDef) -> SignatureInformation {
+        // Control method signatures look like "Move Left, [Top], [Width], [Height]"
+        let label = method.signature.to_string();
+        let search_from = method.name.len().min(label.len());
+        let params: Vec<String> = label[search_from..]
+            .split(',')
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        SignatureInformation {
+            parameters: Some(parameter_information(&label, search_from, &params)),
+            label,
+            documentation: Some(Documentation::String(method.description.to_string())),
+            active_parameter: None,
+        }
+    }
+
+    fn intrinsic_to_signature(
+        &self,
+        intrinsic: &IntrinsicFunction,
+        string_form: bool,
+    ) -> SignatureInformation {
+        let label = intrinsic.format_signature(string_form);
+        let params: Vec<String> = intrinsic.parameters.iter().map(|p| p.to_string()).collect();
+        let search_from = label.find('(').unwrap_or(intrinsic.name.len());
+
+        SignatureInformation {
+            parameters: Some(parameter_information(&label, search_from, &params)),
+            label,
+            documentation: Some(Documentation::String(intrinsic.description.to_string())),
+            active_parameter: None,
+        }
+    }
+
+    fn symbol_to_completion_item(&self, uri: &Url, symbol: &Symbol) -> CompletionItem {
         let detail = symbol.type_info.as_ref().map(|t| t.display());
+        let data = CompletionData::Symbol {
+            uri: uri.clone(),
+            symbol_id: symbol.id.0,
+        };
 
         CompletionItem {
             label: symbol.name.clone(),
             kind: Some(symbol.kind.to_completion_kind()),
             detail,
-            documentation: symbol
-                .documentation
-                .as_ref()
-                .map(|d| Documentation::String(d.clone())),
             insert_text: if symbol.kind.is_callable() {
                 Some(format!("{}($1)", symbol.name))
             } else {
@@ -714,10 +1920,104 @@ impl Analyzer {
             } else {
                 None
             },
+            data: serde_json::to_value(data).ok(),
             ..Default::default()
         }
     }
 
+    /// Build markdown documentation for a symbol
+    fn symbol_documentation(&self, table: &SymbolTable, symbol: &Symbol) -> String {
+        // Form control signatures are already markdown
+        let mut doc = if symbol.kind == SymbolKind::FormControl {
+            symbol.format_signature()
+        } else {
+            format!("```vb\n{}\n```", symbol.format_signature())
+        };
+
+        if let Some(ref text) = symbol.documentation {
+            doc.push_str(&format!("\n\n{}", text));
+        }
+
+        if !symbol.parameters.is_empty() {
+            doc.push_str("\n\n**Parameters:**");
+            for param in &symbol.parameters {
+                let type_str = param
+                    .type_info
+                    .as_ref()
+                    .map(|t| t.display())
+                    .unwrap_or_else(|| "Variant".to_string());
+                doc.push_str(&format!("\n- `{}` As {}", param.name, type_str));
+                if param.is_param_array {
+                    doc.push_str(" (ParamArray)");
+                }
+                if param.optional {
+                    match param.default_value {
+                        Some(ref default) => doc.push_str(&format!(" (Optional, default `{}`)", default)),
+                        None => doc.push_str(" (Optional)"),
+                    }
+                }
+            }
+        }
+
+        doc.push_str(&self.members_documentation(table, symbol));
+
+        doc
+    }
+
+    /// Build a markdown list of a Type or Enum's members (empty if it has none)
+    fn members_documentation(&self, table: &SymbolTable, symbol: &Symbol) -> String {
+        let mut doc = String::new();
+
+        let members: Vec<&Symbol> = symbol
+            .members
+            .iter()
+            .filter_map(|&id| table.get_symbol(id))
+            .collect();
+        if !members.is_empty() {
+            doc.push_str("\n\n**Members:**");
+            for member in members {
+                doc.push_str(&format!("\n- `{}`", member.format_signature()));
+            }
+        }
+
+        doc
+    }
+
+    /// Build markdown documentation for a control property or method
+    fn control_member_documentation(&self, control_type: &str, member: &str) -> Option<String> {
+        if let Some(prop) = crate::controls::get_property(control_type, member) {
+            let mut doc = format!(
+                "**Type:** {}\n\n{}\n\n**Default:** {}",
+                prop.property_type.vb6_type(),
+                prop.description,
+                prop.default_value.unwrap_or("(none)")
+            );
+
+            if prop.read_only {
+                doc.push_str("\n\n*Read-only at run time*");
+            }
+
+            if !prop.valid_values.is_empty() {
+                doc.push_str("\n\n| Value | Constant | Description |\n|---|---|---|");
+                for value in prop.valid_values {
+                    doc.push_str(&format!(
+                        "\n| {} | {} | {} |",
+                        value.value, value.name, value.description
+                    ));
+                }
+            }
+
+            return Some(doc);
+        }
+
+        let method = crate::controls::get_method(control_type, member)?;
+        let mut doc = format!("{}\n\n```vb\n{}\n```", method.description, method.signature);
+        if let Some(return_type) = method.return_type {
+            doc.push_str(&format!("\n\n**Returns:** {}", return_type));
+        }
+        Some(doc)
+    }
+
     fn get_keyword_completions(&self) -> Vec<CompletionItem> {
         let keywords = [
             "If",
@@ -809,6 +2109,63 @@ impl Analyzer {
             Some(chars[start..end].iter().collect())
         }
     }
+
+    /// Find the runtime function named at a position, and whether its `$` form
+    /// is used.
+    ///
+    /// Member names (`Text1.Left`) and type names (`As String`, `For Input`)
+    /// that share a function's name are skipped.
+    fn intrinsic_at_position(
+        &self,
+        source: &str,
+        position: Position,
+    ) -> Option<(&'static IntrinsicFunction, bool)> {
+        if self.qualifier_at_position(source, position).is_some() {
+            return None;
+        }
+        let word = self.word_at_position(source, position)?;
+        let chars: Vec<char> = source.lines().nth(position.line as usize)?.chars().collect();
+
+        let mut start = (position.character as usize).min(chars.len());
+        while start > 0 && is_identifier_char(chars[start - 1]) {
+            start -= 1;
+        }
+        let before: String = chars[..start].iter().collect();
+        let previous_word = before.split_whitespace().last().unwrap_or("").to_lowercase();
+        if matches!(previous_word.as_str(), "as" | "for" | "on" | "line" | "new") {
+            return None;
+        }
+
+        let end = start + word.chars().count();
+        if chars.get(end) == Some(&'$') {
+            get_intrinsic(&format!("{}$", word))
+        } else {
+            get_intrinsic(&word)
+        }
+    }
+
+    /// Get the identifier before the `.` preceding the word at a position
+    /// (`modUtils` in `modUtils.DoThing`)
+    fn qualifier_at_position(&self, source: &str, position: Position) -> Option<String> {
+        let line = source.lines().nth(position.line as usize)?;
+        let chars: Vec<char> = line.chars().collect();
+
+        let mut start = (position.character as usize).min(chars.len());
+        while start > 0 && is_identifier_char(chars[start - 1]) {
+            start -= 1;
+        }
+        if start == 0 || chars[start - 1] != '.' {
+            return None;
+        }
+
+        let end = start - 1;
+        let mut qualifier_start = end;
+        while qualifier_start > 0 && is_identifier_char(chars[qualifier_start - 1]) {
+            qualifier_start -= 1;
+        }
+
+        (qualifier_start < end).then(|| chars[qualifier_start..end].iter().collect())
+    }
 }
 
 impl Default for Analyzer {
@@ -818,6 +2175,1629 @@ impl Default for Analyzer {
 }
 
 /// Check if a character is valid in a VB6 identifier
+/// Append a document symbol and its children to a flat list
+fn flatten_document_symbol(
+    uri: &Url,
+    symbol: DocumentSymbol,
+    container: &str,
+    symbols: &mut Vec<SymbolInformation>,
+) {
+    #[allow(deprecated)]
+    symbols.push(SymbolInformation {
+        name: symbol.name.clone(),
+        kind: symbol.kind,
+        tags: symbol.tags,
+        deprecated: None,
+        location: Location::new(uri.clone(), symbol.range),
+        container_name: Some(container.to_string()),
+    });
+    for child in symbol.children.unwrap_or_default() {
+        flatten_document_symbol(uri, child, &symbol.name, symbols);
+    }
+}
+
 fn is_identifier_char(c: char) -> bool {
     c.is_alphanumeric() || c == '_'
 }
+
+/// Find whole-identifier, case-insensitive occurrences of a name in source text,
+/// skipping comments (`'` and `Rem`) and string literals
+fn find_identifier_occurrences(source: &str, name: &str) -> Vec<Range> {
+    let mut ranges = Vec::new();
+
+    for (line_idx, line) in source.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut in_string = false;
+        let mut statement_start = true;
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+
+            if in_string {
+                if c == '"' {
+                    in_string = false;
+                }
+                i += 1;
+                continue;
+            }
+
+            if c == '"' {
+                in_string = true;
+                statement_start = false;
+                i += 1;
+                continue;
+            }
+
+            // Comment tail
+            if c == '\'' {
+                break;
+            }
+
+            if c == ':' {
+                statement_start = true;
+                i += 1;
+                continue;
+            }
+
+            if c.is_alphabetic() || c == '_' {
+                let start = i;
+                while i < chars.len() && is_identifier_char(chars[i]) {
+                    i += 1;
+                }
+                let ident: String = chars[start..i].iter().collect();
+
+                // Rem comment at the start of a statement
+                if statement_start && ident.eq_ignore_ascii_case("rem") {
+                    break;
+                }
+                statement_start = false;
+
+                if ident.eq_ignore_ascii_case(name) {
+                    ranges.push(Range {
+                        start: Position {
+                            line: line_idx as u32,
+                            character: start as u32,
+                        },
+                        end: Position {
+                            line: line_idx as u32,
+                            character: i as u32,
+                        },
+                    });
+                }
+
+                // Optional type-suffix character (e.g., Name$)
+                if i < chars.len() && matches!(chars[i], '$' | '%' | '&' | '!' | '#' | '@') {
+                    i += 1;
+                }
+                continue;
+            }
+
+            if !c.is_whitespace() {
+                statement_start = false;
+            }
+            // Skip digits so numeric literals like 1E5 aren't read as identifiers
+            if c.is_ascii_digit() {
+                while i < chars.len() && is_identifier_char(chars[i]) {
+                    i += 1;
+                }
+                continue;
+            }
+            i += 1;
+        }
+    }
+
+    ranges
+}
+
+/// Build the completion data payload for a control property or method
+/// The index in parentheses right after a reference (e.g., `3` in `Command1(3)`)
+fn index_after(source: &str, after: SourcePosition) -> Option<&str> {
+    let line = source.lines().nth(after.line as usize)?;
+    let rest = line.get(after.column as usize..)?.trim_start().strip_prefix('(')?;
+    let index = rest[..rest.find(')')?].trim();
+    (!index.is_empty()).then_some(index)
+}
+
+/// Find the declaration of a control array element.
+///
+/// A literal index selects that element; otherwise the Index 0 element is used.
+fn control_array_element(symbol: &Symbol, index: Option<&str>) -> Option<SourceRange> {
+    let elements = &symbol.control_array;
+    let literal = index.and_then(|i| i.parse::<i32>().ok());
+
+    literal
+        .and_then(|i| elements.iter().find(|element| element.index == i))
+        .or_else(|| elements.iter().find(|element| element.index == 0))
+        .or(elements.first())
+        .map(|element| element.name_range)
+}
+
+/// Describe a control array and the indexed element for hover
+fn control_array_documentation(symbol: &Symbol, index: Option<&str>) -> String {
+    let indices: Vec<String> = symbol
+        .control_array
+        .iter()
+        .map(|element| element.index.to_string())
+        .collect();
+
+    let mut doc = format!(
+        "\n\n**Control array** of {} elements (Index {})",
+        indices.len(),
+        indices.join(", ")
+    );
+    if let Some(index) = index {
+        doc.push_str(&format!("\n\nElement `{}({})`", symbol.name, index));
+    }
+    doc
+}
+
+fn control_member_data(control_type: &str, member: &str) -> Option<serde_json::Value> {
+    serde_json::to_value(CompletionData::ControlMember {
+        control_type: control_type.to_string(),
+        member: member.to_string(),
+    })
+    .ok()
+}
+
+/// Markdown documentation for a runtime function
+fn intrinsic_documentation(intrinsic: &IntrinsicFunction, string_form: bool) -> String {
+    let mut doc = format!(
+        "```vb\n{}\n```\n\n{}",
+        intrinsic.format_signature(string_form),
+        intrinsic.description
+    );
+    if intrinsic.has_string_form && !string_form {
+        doc.push_str(&format!("\n\n`{}$` returns a String instead of a Variant", intrinsic.name));
+    }
+    doc
+}
+
+/// Completion item for a runtime function
+fn intrinsic_completion_item(intrinsic: &IntrinsicFunction) -> CompletionItem {
+    let is_function = intrinsic.return_type.is_some() && !intrinsic.parameters.is_empty();
+    let data = CompletionData::Intrinsic {
+        name: intrinsic.name.to_string(),
+    };
+
+    CompletionItem {
+        label: intrinsic.name.to_string(),
+        kind: Some(CompletionItemKind::FUNCTION),
+        detail: Some(intrinsic.format_signature(false)),
+        insert_text: is_function.then(|| format!("{}($1)", intrinsic.name)),
+        insert_text_format: is_function.then_some(InsertTextFormat::SNIPPET),
+        data: serde_json::to_value(data).ok(),
+        ..Default::default()
+    }
+}
+
+fn snippet_completion_item(snippet: &Snippet) -> CompletionItem {
+    CompletionItem {
+        label: snippet.label.to_string(),
+        kind: Some(CompletionItemKind::SNIPPET),
+        detail: Some(snippet.description.to_string()),
+        filter_text: Some(snippet.prefix.to_string()),
+        insert_text: Some(snippet.body.to_string()),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        ..Default::default()
+    }
+}
+
+/// Completion items for the color constants, for Color properties and
+/// `OLE_COLOR` variables
+fn color_constant_completions() -> Vec<CompletionItem> {
+    crate::controls::color_constants()
+        .iter()
+        .enumerate()
+        .map(|(i, constant)| CompletionItem {
+            label: constant.name.to_string(),
+            kind: Some(CompletionItemKind::COLOR),
+            detail: Some(constant.color.to_vb6_string()),
+            documentation: Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: constant.color.describe(),
+            })),
+            sort_text: Some(format!("{:04}", i)),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Completion items for the values of an enumerated control property
+fn property_value_completions(values: &[crate::controls::PropertyValue]) -> Vec<CompletionItem> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| CompletionItem {
+            label: value.name.to_string(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            detail: Some(format!("= {}", value.value)),
+            documentation: Some(Documentation::String(value.description.to_string())),
+            sort_text: Some(format!("{:04}", i)),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Completion items for the members of a user-defined enum, with their values
+fn enum_member_completions(table: &SymbolTable, enumeration: &Symbol) -> Vec<CompletionItem> {
+    // Members without an explicit value count up from the previous one
+    let mut next = 0i64;
+    enumeration
+        .members
+        .iter()
+        .filter_map(|&id| table.get_symbol(id))
+        .enumerate()
+        .map(|(i, member)| {
+            let value = match member.value.as_deref() {
+                Some(text) => match parse_enum_value(text) {
+                    Some(value) => {
+                        next = value + 1;
+                        value.to_string()
+                    }
+                    None => text.to_string(),
+                },
+                None => {
+                    next += 1;
+                    (next - 1).to_string()
+                }
+            };
+            CompletionItem {
+                label: member.name.clone(),
+                kind: Some(CompletionItemKind::ENUM_MEMBER),
+                detail: Some(format!("{}.{} = {}", enumeration.name, member.name, value)),
+                sort_text: Some(format!("{:04}", i)),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Parse a decimal or `&H` enum member value
+fn parse_enum_value(text: &str) -> Option<i64> {
+    let text = text.trim().trim_end_matches('&');
+    match text.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("&h") => {
+            i64::from_str_radix(&text[2..], 16).ok()
+        }
+        _ => text.parse().ok(),
+    }
+}
+
+/// Completion item for a control on a form
+fn control_completion_item(control: &Symbol) -> CompletionItem {
+    CompletionItem {
+        label: control.name.clone(),
+        kind: Some(CompletionItemKind::FIELD),
+        detail: control.type_info.as_ref().map(|t| t.display()),
+        ..Default::default()
+    }
+}
+
+/// Properties and methods of a built-in control type (documentation is filled in on resolve)
+fn control_type_completions(type_name: &str) -> Vec<CompletionItem> {
+    let Some(control) = crate::controls::get_control(type_name) else {
+        return Vec::new();
+    };
+
+    let properties = control.properties.iter().map(|prop| CompletionItem {
+        label: prop.name.to_string(),
+        kind: Some(CompletionItemKind::PROPERTY),
+        detail: Some(prop.description.to_string()),
+        data: control_member_data(control.name, prop.name),
+        ..Default::default()
+    });
+    let methods = control.methods.iter().map(|method| CompletionItem {
+        label: method.name.to_string(),
+        kind: Some(CompletionItemKind::METHOD),
+        detail: Some(method.description.to_string()),
+        insert_text: Some(format!("{}($1)", method.name)),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        data: control_member_data(control.name, method.name),
+        ..Default::default()
+    });
+    properties.chain(methods).collect()
+}
+
+/// The names before the last dot of a member access ending at the cursor
+/// (e.g., `["Me", "Text1"]` for `Me.Text1.`), with indices such as `Text1(0)` dropped
+fn member_access_chain(source: &str, position: Position) -> Option<Vec<String>> {
+    let line = source.lines().nth(position.line as usize)?;
+    let before_cursor = line.get(..position.character as usize)?;
+    dotted_name_before(before_cursor.strip_suffix('.')?)
+}
+
+/// The target of an assignment being typed at the cursor (e.g., `["Text1", "Alignment"]`
+/// for `Text1.Alignment = vbC`)
+fn assignment_target_chain(source: &str, position: Position) -> Option<Vec<String>> {
+    let line = source.lines().nth(position.line as usize)?;
+    let before_cursor = line.get(..position.character as usize)?;
+
+    // Only a partial name may follow the `=`
+    let value_start = before_cursor
+        .trim_end_matches(|c: char| c.is_alphanumeric() || c == '_')
+        .trim_end();
+    let target = value_start.strip_suffix('=')?;
+    // Not a named argument (:=) or a comparison (<=, >=)
+    if target.ends_with([':', '<', '>']) {
+        return None;
+    }
+    dotted_name_before(target.trim_end())
+}
+
+/// Split the dotted name ending a piece of text into its names, dropping
+/// indices such as `Text1(0)`
+fn dotted_name_before(prefix: &str) -> Option<Vec<String>> {
+    // Scan back over names, dots, and balanced parentheses
+    let chars: Vec<char> = prefix.chars().collect();
+    let mut start = chars.len();
+    let mut depth = 0;
+    while start > 0 {
+        let c = chars[start - 1];
+        match c {
+            ')' => depth += 1,
+            '(' if depth > 0 => depth -= 1,
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() || c == '_' || c == '.' => {}
+            _ => break,
+        }
+        start -= 1;
+    }
+    if depth > 0 {
+        return None;
+    }
+
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for &c in &chars[start..] {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth > 0 => {}
+            '.' => segments.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    segments.push(current);
+
+    // Only the first name may be empty: a leading dot is a With block member
+    if segments.iter().skip(1).any(|segment| segment.is_empty()) {
+        return None;
+    }
+    Some(segments)
+}
+
+/// Error reported when trying to rename a form control
+const CONTROL_RENAME_ERROR: &str = "Form controls must be renamed in the form designer";
+
+/// Reserved words that cannot be used as identifiers
+const RESERVED_WORDS: &[&str] = &[
+    "and", "as", "boolean", "byref", "byte", "byval", "call", "case", "const", "currency",
+    "date", "declare", "dim", "do", "double", "each", "else", "elseif", "empty", "end", "enum",
+    "eqv", "erase", "event", "exit", "false", "for", "friend", "function", "get", "global",
+    "gosub", "goto", "if", "imp", "implements", "in", "integer", "is", "let", "like", "long",
+    "loop", "me", "mod", "new", "next", "not", "nothing", "null", "object", "on", "option",
+    "optional", "or", "paramarray", "preserve", "private", "property", "public", "raiseevent",
+    "redim", "resume", "return", "select", "set", "single", "static", "step", "stop", "string",
+    "sub", "then", "to", "true", "type", "typeof", "until", "variant", "wend", "while", "with",
+    "withevents", "xor",
+];
+
+/// Check if a name is a valid VB6 identifier (letter first, max 255 chars, not reserved)
+fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let starts_with_letter = chars.next().is_some_and(|c| c.is_alphabetic());
+
+    starts_with_letter
+        && name.len() <= 255
+        && name.chars().all(is_identifier_char)
+        && !RESERVED_WORDS.contains(&name.to_lowercase().as_str())
+}
+
+/// Build parameter information with label offsets into a signature label
+fn parameter_information(label: &str, search_from: usize, params: &[String]) -> Vec<ParameterInformation> {
+    let mut cursor = search_from;
+    let mut result = Vec::new();
+
+    for param in params {
+        let label_param = match label[cursor..].find(param.as_str()) {
+            Some(offset) => {
+                let start = cursor + offset;
+                let end = start + param.len();
+                cursor = end;
+                // Offsets are in UTF-16 code units
+                ParameterLabel::LabelOffsets([
+                    label[..start].encode_utf16().count() as u32,
+                    label[..end].encode_utf16().count() as u32,
+                ])
+            }
+            None => ParameterLabel::Simple(param.clone()),
+        };
+
+        result.push(ParameterInformation {
+            label: label_param,
+            documentation: None,
+        });
+    }
+
+    result
+}
+
+/// Parse a test module, build its symbol table, and run a pass over them
+#[cfg(test)]
+pub(crate) fn run_pass<T>(
+    source: &str,
+    pass: impl FnOnce(&SymbolTable, &tree_sitter::Tree) -> T,
+) -> T {
+    run_pass_at("file:///test.bas", source, pass)
+}
+
+/// Like `run_pass`, for a test file of the kind named by `uri`
+#[cfg(test)]
+pub(crate) fn run_pass_at<T>(
+    uri: &str,
+    source: &str,
+    pass: impl FnOnce(&SymbolTable, &tree_sitter::Tree) -> T,
+) -> T {
+    let mut parser = crate::parser::TreeSitterVb6Parser::new().unwrap();
+    let tree = parser.parse(source, None).unwrap();
+    let table = build_symbol_table(Url::parse(uri).unwrap(), source, &tree);
+    pass(&table, &tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn parse_and_build(source: &str) -> SymbolTable {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        build_symbol_table(Url::parse("file:///test.bas").unwrap(), source, &tree)
+    }
+
+    #[test]
+    fn test_no_missing_end_for_balanced_file() {
+        let source = "Option Explicit\n\
+Private Declare Function GetTickCount Lib \"kernel32\" () As Long\n\
+Public Event Changed()\n\
+\n\
+Public Sub Main()\n\
+End Sub\n\
+\n\
+Public Property Get Value() As Long\n\
+End Property\n";
+        let mut parser = crate::parser::Vb6Parser::new();
+        let ast = parser.parse(source).unwrap();
+
+        let diagnostics = Analyzer::new().analyze(&ast);
+        assert!(diagnostics.iter().all(|d| !d.message.contains("missing End")));
+    }
+
+    #[test]
+    fn test_constant_value_hover() {
+        let source = "Public Const MAX As Long = BASE * 2\nConst BASE = 10\nEnum Level\n    Low\n    High\nEnd Enum\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+
+        let hover_text = |line, character| {
+            let hover = analyzer
+                .get_hover_with_symbols(&table, source, Position { line, character })
+                .unwrap();
+            let HoverContents::Markup(content) = hover.contents else {
+                panic!("expected markup");
+            };
+            content.value
+        };
+        assert_eq!(
+            hover_text(0, 14),
+            "```vb\nPublic Const MAX As Long = 20\n```\n\nEvaluated from `BASE * 2`"
+        );
+        assert_eq!(hover_text(1, 7), "```vb\nPrivate Const BASE As Integer = 10\n```");
+        assert_eq!(hover_text(4, 5), "```vb\nHigh = 1\n```");
+    }
+
+    #[test]
+    fn test_shadowing_hover() {
+        let source = "Private mCount As Long\n\
+Public Sub Add(ByVal mCount As Long)\n\
+    Dim total As Long\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+
+        let hover_text = |line, character| {
+            let hover = analyzer
+                .get_hover_with_symbols(&table, source, Position { line, character })
+                .unwrap();
+            let HoverContents::Markup(content) = hover.contents else {
+                panic!("expected markup");
+            };
+            content.value
+        };
+        assert!(hover_text(1, 23)
+            .ends_with("\n\nShadows module-level variable 'mCount' declared at line 1"));
+        assert!(!hover_text(2, 9).contains("Shadows"));
+        assert!(!hover_text(0, 10).contains("Shadows"));
+    }
+
+    #[test]
+    fn test_declare_hover() {
+        let source = "Declare Function GetTickCount Lib \"kernel32\" () As Long\n\
+Private Declare Function FindWindow Lib \"user32\" Alias \"FindWindowA\" (ByVal lpClassName As String, ByVal lpWindowName As String) As Long\n\
+Sub Main()\n    Debug.Print GetTickCount\nEnd Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let hover_text = |line, character| {
+            let hover = analyzer
+                .get_hover_with_symbols(&table, source, Position { line, character })
+                .unwrap();
+            let HoverContents::Markup(content) = hover.contents else {
+                panic!("expected markup");
+            };
+            content.value
+        };
+
+        assert!(hover_text(3, 18).starts_with(
+            "```vb\nPublic Declare Function GetTickCount Lib \"kernel32\" () As Long\n```"
+        ));
+        assert!(hover_text(1, 28).starts_with(
+            "```vb\nPrivate Declare Function FindWindow Lib \"user32\" Alias \"FindWindowA\" \
+(ByVal lpClassName As String, ByVal lpWindowName As String) As Long\n```"
+        ));
+    }
+
+    #[test]
+    fn test_user_type_fields() {
+        let source = "Private Type Customer
+    Name As String
+    Scores(1 To 10) As Long
+End Type
+
+Sub Main()
+    Dim c As Customer
+    c.
+End Sub
+";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+
+        let items = analyzer.get_completions_with_symbols(&table, Position { line: 7, character: 6 }, source, &[], |_| None);
+        let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["Name", "Scores"]);
+
+        let hover = analyzer.get_hover_with_symbols(&table, source, Position { line: 6, character: 8 }).unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup hover");
+        };
+        assert!(content.value.contains("`Name As String`"));
+        assert!(content.value.contains("`Scores(1 To 10) As Long`"));
+    }
+
+    #[test]
+    fn test_document_highlights() {
+        let source = "Sub Main()\n    Dim count As Long\n    count = 1\n    MsgBox count\nEnd Sub\n";
+        let table = parse_and_build(source);
+
+        let highlights = Analyzer::new()
+            .get_document_highlights_with_symbols(&table, Position { line: 3, character: 12 });
+        let kinds: Vec<(u32, Option<DocumentHighlightKind>)> = highlights
+            .iter()
+            .map(|h| (h.range.start.line, h.kind))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (1, Some(DocumentHighlightKind::WRITE)),
+                (2, Some(DocumentHighlightKind::WRITE)),
+                (3, Some(DocumentHighlightKind::READ)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_controls_and_control_arrays() {
+        let source = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Caption = \"Main\"\n\
+   Begin VB.Frame Frame1\n\
+      Caption = \"Options\"\n\
+      Begin VB.TextBox Text1\n\
+         Index = 0\n\
+      End\n\
+      Begin VB.TextBox Text1\n\
+         Index = 1\n\
+      End\n\
+   End\n\
+   Begin VB.CommandButton cmdOK\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private Sub cmdOK_Click()\n\
+    Text1(1).Text = \"\"\n\
+    Frame1.\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+
+        let text1 = table.symbols_of_kind(SymbolKind::FormControl).find(|s| s.name == "Text1").unwrap();
+        assert_eq!(text1.control_path.as_deref(), Some("frmMain.Frame1.Text1"));
+        let indices: Vec<i32> = text1.control_array.iter().map(|e| e.index).collect();
+        assert_eq!(indices, vec![0, 1]);
+
+        let form = table.symbols_of_kind(SymbolKind::FormControl).find(|s| s.name == "frmMain").unwrap();
+        let children: Vec<&str> = form
+            .members
+            .iter()
+            .map(|id| table.get_symbol(*id).unwrap().name.as_str())
+            .collect();
+        assert_eq!(children, vec!["Frame1", "cmdOK"]);
+
+        // Members of the Frame include its contained controls
+        let items = analyzer.get_completions_with_symbols(&table, Position { line: 18, character: 7 }, source, &[], |_| None);
+        assert_eq!(items[0].label, "Text1");
+        assert_eq!(items[0].kind, Some(CompletionItemKind::FIELD));
+
+        // Go to definition on Text1(1) lands on the second Begin block
+        let definition = analyzer
+            .get_definition_with_symbols(&table, source, Position { line: 17, character: 2 }, |_, _| None)
+            .unwrap();
+        let GotoDefinitionResponse::Scalar(location) = definition else {
+            panic!("expected a single location");
+        };
+        assert_eq!(location.range.start.line, 8);
+    }
+
+    #[test]
+    fn test_control_array_hover_and_definition() {
+        let source = "Begin VB.Form frmMain\n\
+Begin VB.CommandButton Command1\n\
+Index = 1\n\
+End\n\
+Begin VB.CommandButton Command1\n\
+Index = 0\n\
+End\n\
+End\n\
+Private Sub Form_Load()\n\
+Command1(i).Caption = \"Go\"\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let position = Position { line: 9, character: 3 };
+
+        let hover = analyzer.get_hover_with_symbols(&table, source, position).unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup hover");
+        };
+        assert!(content.value.contains("Command1 As CommandButton"));
+        assert!(content.value.contains("**Control array** of 2 elements (Index 1, 0)"));
+        assert!(content.value.contains("Element `Command1(i)`"));
+
+        // A non-literal index goes to the Index 0 element
+        let definition = analyzer
+            .get_definition_with_symbols(&table, source, position, |_, _| None)
+            .unwrap();
+        let GotoDefinitionResponse::Scalar(location) = definition else {
+            panic!("expected a single location");
+        };
+        assert_eq!(location.range.start.line, 4);
+    }
+
+    #[test]
+    fn test_event_handler_definition_goes_to_control() {
+        let source = "Begin VB.Form frmMain\n\
+Begin VB.CommandButton cmdOK\n\
+End\n\
+End\n\
+Private Sub cmdOK_Click()\n\
+End Sub\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///frmMain.frm").unwrap(), source, &tree);
+
+        let position = Position { line: 4, character: 14 };
+        let definition = Analyzer::new()
+            .get_definition_with_symbols(&table, source, position, |_, _| None)
+            .unwrap();
+        let GotoDefinitionResponse::Scalar(location) = definition else {
+            panic!("expected a single location");
+        };
+        assert_eq!((location.range.start.line, location.range.end.line), (1, 3));
+    }
+
+    #[test]
+    fn test_prepare_rename() {
+        let source = "Begin VB.Form frmMain\n\
+Begin VB.CommandButton cmdOK\n\
+End\n\
+End\n\
+Private Sub cmdOK_Click()\n\
+Dim total As Long\n\
+total = total + 1\n\
+MsgBox total\n\
+End Sub\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///frmMain.frm").unwrap(), source, &tree);
+        let analyzer = Analyzer::new();
+        let prepare = |line, character| {
+            analyzer.prepare_rename_with_symbols(&table, Position { line, character })
+        };
+        let range = |line, start, end| Range {
+            start: Position { line, character: start },
+            end: Position { line, character: end },
+        };
+
+        // The local variable, from its declaration and from a use
+        assert_eq!(prepare(5, 6), Ok(range(5, 4, 9)));
+        assert_eq!(prepare(6, 10), Ok(range(6, 8, 13)));
+
+        // Keywords and built-in functions
+        assert!(prepare(5, 1).is_err());
+        assert!(prepare(7, 2).is_err());
+
+        // Controls are renamed in the form designer
+        assert_eq!(prepare(1, 25), Err(CONTROL_RENAME_ERROR.to_string()));
+    }
+
+    #[test]
+    fn test_rename() {
+        let source = "Private mCount As Long\n\
+Public Sub DoThing()\n\
+Dim total As Long\n\
+End Sub\n\
+Sub Main()\n\
+DOTHING\n\
+Call dothing\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let rename = |line, character, new_name| {
+            analyzer.rename_with_symbols(&table, Position { line, character }, new_name)
+        };
+
+        // References are found whatever their case
+        let edit = rename(5, 2, "Process").unwrap().unwrap();
+        let edits = &edit.changes.unwrap()[&table.uri];
+        let ranges: Vec<(u32, u32, u32)> = edits
+            .iter()
+            .map(|e| (e.range.start.line, e.range.start.character, e.range.end.character))
+            .collect();
+        assert_eq!(ranges, vec![(1, 11, 18), (5, 0, 7), (6, 5, 12)]);
+        assert!(edits.iter().all(|e| e.new_text == "Process"));
+
+        // The new name is already taken in the same scope, whatever its case
+        assert_eq!(
+            rename(1, 13, "MCOUNT"),
+            Err("'MCOUNT' is already declared in this scope".to_string())
+        );
+        // A local of another procedure doesn't collide
+        assert!(rename(1, 13, "total").is_ok());
+    }
+
+    #[test]
+    fn test_goto_definition_of_labels() {
+        let source = "Sub First()\n\
+On Error GoTo ErrHandler\n\
+GoTo 100\n\
+100:\n\
+Exit Sub\n\
+ErrHandler:\n\
+End Sub\n\
+Sub Second()\n\
+GoTo ErrHandler\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let definition_line = |line, character| {
+            let position = Position { line, character };
+            match analyzer.get_definition_with_symbols(&table, source, position, |_, _| None) {
+                Some(GotoDefinitionResponse::Scalar(location)) => Some(location.range.start.line),
+                _ => None,
+            }
+        };
+
+        assert_eq!(definition_line(1, 16), Some(5));
+        assert_eq!(definition_line(2, 6), Some(3));
+        // The label of another procedure is not visible
+        assert_eq!(definition_line(8, 7), None);
+    }
+
+    #[test]
+    fn test_event_handler_stub_completions() {
+        let source = "Begin VB.Form frmMain\n\
+Begin VB.CommandButton cmdOK\n\
+End\n\
+End\n\
+Private Sub cmdOK_Click()\n\
+\n\
+End Sub\n\
+cmd\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///frmMain.frm").unwrap(), source, &tree);
+        let analyzer = Analyzer::new();
+        let complete = |line| {
+            let position = Position { line, character: 3 };
+            analyzer.get_completions_with_symbols(&table, position, source, &[], |_| None)
+        };
+
+        let items = complete(7);
+        let stubs: Vec<&str> = items
+            .iter()
+            .filter(|item| item.kind == Some(CompletionItemKind::EVENT))
+            .map(|item| item.label.as_str())
+            .collect();
+        assert!(!stubs.contains(&"cmdOK_Click"));
+        assert!(stubs.iter().all(|label| label.starts_with("cmdOK_")));
+        let got_focus = items.iter().find(|item| item.label == "cmdOK_GotFocus").unwrap();
+        assert_eq!(got_focus.kind, Some(CompletionItemKind::EVENT));
+        assert_eq!(
+            got_focus.insert_text.as_deref(),
+            Some("Private Sub cmdOK_GotFocus()\n    $0\nEnd Sub")
+        );
+        assert!(matches!(got_focus.documentation, Some(Documentation::String(_))));
+
+        // Not inside a procedure
+        assert!(!complete(5).iter().any(|item| item.label == "cmdOK_GotFocus"));
+    }
+
+    #[test]
+    fn test_with_block_members() {
+        let source = "Begin VB.Form frmMain
+Begin VB.TextBox Text1
+End
+End
+Private Type Point
+    X As Long
+    Y As Long
+End Type
+Private Sub Form_Load()
+    Dim p As Point
+    With Text1
+        .Text = \"\"
+        .
+        With p
+            .
+        End With
+        With .Font
+        End With
+    End With
+End Sub
+";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let labels = |line, character| {
+            let position = Position { line, character };
+            analyzer
+                .get_completions_with_symbols(&table, position, source, &[], |_| None)
+                .into_iter()
+                .map(|item| item.label)
+                .collect::<Vec<_>>()
+        };
+
+        let text_box = labels(12, 9);
+        assert!(text_box.iter().any(|label| label == "Text"));
+        assert!(text_box.iter().any(|label| label == "SelStart"));
+        // The inner With shadows the outer one
+        assert_eq!(labels(14, 13), vec!["X", "Y"]);
+
+        let hover = analyzer
+            .get_hover_with_symbols(&table, source, Position { line: 11, character: 10 })
+            .unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup hover");
+        };
+        assert!(content.value.starts_with("**Type:** String"));
+        assert_eq!(hover.range.unwrap().start, Position::new(11, 9));
+    }
+
+    #[test]
+    fn test_resolve_completion() {
+        let source = "' Say hello to someone\n\
+Public Function Greet(ByVal name As String, Optional times As Integer = 1) As String\n\
+End Function\n\
+Sub Main()\n\
+Gr\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let items = analyzer.get_completions_with_symbols(
+            &table,
+            Position { line: 4, character: 2 },
+            source,
+            &[],
+            |_| None,
+        );
+        let item = items.into_iter().find(|item| item.label == "Greet").unwrap();
+        assert!(item.documentation.is_none());
+
+        let resolved = analyzer.resolve_completion(item, Some(&table));
+        let Some(Documentation::MarkupContent(content)) = resolved.documentation else {
+            panic!("expected markup");
+        };
+        assert_eq!(
+            content.value,
+            "```vb\nPublic Function Greet(ByVal name As String, \
+Optional ByRef times As Integer = 1) As String\n```\n\n\
+Say hello to someone\n\n\
+**Parameters:**\n- `name` As String\n- `times` As Integer (Optional, default `1`)"
+        );
+
+        // Items without a payload, such as keywords, are returned unchanged
+        let keyword = CompletionItem {
+            label: "Dim".to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            ..Default::default()
+        };
+        assert_eq!(analyzer.resolve_completion(keyword.clone(), Some(&table)), keyword);
+    }
+
+    #[test]
+    fn test_snippet_completions() {
+        let source = "Option Explicit\n\
+\n\
+Sub Main()\n\
+\n\
+End Sub\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///test.cls").unwrap(), source, &tree);
+        let analyzer = Analyzer::new();
+        let snippets = |line| {
+            let position = Position { line, character: 0 };
+            analyzer
+                .get_completions_with_symbols(&table, position, source, &[], |_| None)
+                .into_iter()
+                .filter(|item| item.kind == Some(CompletionItemKind::SNIPPET))
+                .collect::<Vec<_>>()
+        };
+
+        // Statements inside a procedure
+        let inside = snippets(3);
+        let for_next = inside.iter().find(|item| item.label == "For ... Next").unwrap();
+        assert_eq!(for_next.filter_text.as_deref(), Some("for"));
+        assert_eq!(for_next.insert_text_format, Some(InsertTextFormat::SNIPPET));
+        assert_eq!(
+            for_next.insert_text.as_deref(),
+            Some("For ${1:i} = ${2:0} To ${3:n}\n    $0\nNext $1")
+        );
+        assert!(inside.iter().any(|item| item.label == "Select Case ... End Select"));
+        assert!(!inside.iter().any(|item| item.label.starts_with("Property")));
+
+        // Declarations between procedures
+        let outside = snippets(1);
+        let property = outside.iter().find(|item| item.label == "Property Get/Let").unwrap();
+        assert_eq!(
+            property.insert_text.as_deref(),
+            Some(
+                "Private m${1:Name} As ${2:Long}\n\n\
+                 Public Property Get $1() As $2\n    $1 = m$1\nEnd Property\n\n\
+                 Public Property Let $1(ByVal value As $2)\n    m$1 = value\nEnd Property\n$0"
+            )
+        );
+        assert!(outside.iter().any(|item| item.label == "Property Get/Set"));
+        assert!(outside.iter().any(|item| item.filter_text.as_deref() == Some("withevents")));
+        assert!(!outside.iter().any(|item| item.label == "For ... Next"));
+    }
+
+    #[test]
+    fn test_predeclared_class_completion_and_hover() {
+        let source = "Sub Main()\n    \n    Dim log As clsLogger\nEnd Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+
+        let globals = vec!["frmMain".to_string(), "clsLogger".to_string()];
+        let items = analyzer.get_completions_with_symbols(&table, Position { line: 1, character: 4 }, source, &globals, |_| None);
+        let logger = items.iter().find(|item| item.label == "clsLogger").unwrap();
+        assert_eq!(logger.kind, Some(CompletionItemKind::CLASS));
+        assert!(items.iter().any(|item| item.label == "frmMain"));
+
+        let mut attributes = ClassAttributes::default();
+        attributes.set("VB_Name", "\"clsLogger\"");
+        attributes.set("VB_Exposed", "True");
+        attributes.set("VB_PredeclaredId", "True");
+        let lookup = |name: &str| name.eq_ignore_ascii_case("clsLogger").then(|| attributes.clone());
+
+        let hover = analyzer
+            .get_class_hover(source, Position { line: 2, character: 18 }, lookup)
+            .unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup hover");
+        };
+        assert!(content.value.contains("Class clsLogger"));
+        assert!(content.value.contains("**Instancing:** PublicNotCreatable"));
+        assert!(content.value.contains("predeclared default instance"));
+
+        assert!(analyzer.get_class_hover(source, Position { line: 2, character: 9 }, lookup).is_none());
+    }
+
+    #[test]
+    fn test_definition_falls_back_to_external_resolver() {
+        let source = "Private Sub Main()\n    Dim modUtils As Long\n    modUtils.DoThing\n    frmMain.Show\n    Helper\nEnd Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let external = Location {
+            uri: Url::parse("file:///other.bas").unwrap(),
+            range: Range::default(),
+        };
+        let resolve = |qualifier: Option<&str>, name: &str| {
+            let key = format!("{}|{}", qualifier.unwrap_or(""), name);
+            (key == "frmMain|Show" || key == "|Helper").then(|| external.clone())
+        };
+
+        // Qualified by a module name, not a local
+        let definition = analyzer
+            .get_definition_with_symbols(&table, source, Position { line: 3, character: 13 }, resolve)
+            .unwrap();
+        assert_eq!(definition, GotoDefinitionResponse::Scalar(external.clone()));
+
+        // Unqualified names missing locally
+        let definition = analyzer
+            .get_definition_with_symbols(&table, source, Position { line: 4, character: 6 }, resolve)
+            .unwrap();
+        assert_eq!(definition, GotoDefinitionResponse::Scalar(external.clone()));
+
+        // A local qualifier is not treated as a module
+        assert!(analyzer
+            .get_definition_with_symbols(&table, source, Position { line: 2, character: 14 }, resolve)
+            .is_none());
+    }
+
+    #[test]
+    fn test_implementation_target() {
+        let source = "Implements IShape\n\
+Public Sub Draw()\n\
+Dim shape As IShape\n\
+shape.Draw\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let target = |line, character| {
+            let position = Position { line, character };
+            analyzer.implementation_target(&table, source, position, "IShape")
+        };
+        let interface =
+            |member: Option<&str>| Some(("IShape".to_string(), member.map(String::from)));
+
+        assert_eq!(target(0, 12), interface(None));
+        assert_eq!(target(1, 12), interface(Some("Draw")));
+        assert_eq!(target(2, 5), interface(None));
+        assert_eq!(target(3, 8), interface(Some("Draw")));
+    }
+
+    #[test]
+    fn test_type_definition() {
+        let source = "Private Type Point\n    X As Long\nEnd Type\n\
+Private Enum Shade\n    Dark\nEnd Enum\n\
+Private Origin As Point\n\
+Private Function Pick(ByVal s As Shade) As Point\n\
+Dim log As clsLogger\n\
+Dim n As Long\n\
+End Function\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let external = Location {
+            uri: Url::parse("file:///clsLogger.cls").unwrap(),
+            range: Range::default(),
+        };
+        let resolve = |name: &str| (name == "clsLogger").then(|| external.clone());
+        let target = |line, character| {
+            let position = Position { line, character };
+            match analyzer.get_type_definition_with_symbols(&table, source, position, resolve) {
+                Some(GotoDefinitionResponse::Scalar(location)) => Some(location),
+                _ => None,
+            }
+        };
+        let local = |line, character| Location {
+            uri: table.uri.clone(),
+            range: Range {
+                start: Position { line, character },
+                end: Position { line, character: character + 5 },
+            },
+        };
+
+        // A variable, a function's return type, and a parameter
+        assert_eq!(target(6, 9), Some(local(0, 13)));
+        assert_eq!(target(7, 18), Some(local(0, 13)));
+        assert_eq!(target(7, 28), Some(local(3, 13)));
+        // A class from the workspace
+        assert_eq!(target(8, 4), Some(external.clone()));
+        // Intrinsic types have no declaration
+        assert_eq!(target(9, 4), None);
+    }
+
+    #[test]
+    fn test_find_identifier_occurrences() {
+        let source = "Dim Total As Long\n\
+total = TOTAL + 1 ' Total in comment\n\
+MsgBox \"Total: \" & Total$\n\
+Rem Total again\n\
+x = 1: Rem Total\n\
+Subtotal = myTotal\n";
+
+        let ranges = find_identifier_occurrences(source, "Total");
+        let positions: Vec<(u32, u32)> = ranges
+            .iter()
+            .map(|r| (r.start.line, r.start.character))
+            .collect();
+
+        assert_eq!(positions, vec![(0, 4), (1, 0), (1, 8), (2, 19)]);
+        assert_eq!(ranges[3].end.character, 24);
+    }
+
+    #[test]
+    fn test_member_completion_chains() {
+        let form = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Begin VB.Frame Frame1\n\
+      Begin VB.TextBox Text1\n\
+      End\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Public Buyer As Customer\n\
+Private Type Address\n\
+    City As String\n\
+End Type\n\
+Private Type Customer\n\
+    Home As Address\n\
+End Type\n\
+Private Sub Form_Load()\n\
+    Me.Text1.\n\
+    Me.\n\
+    Buyer.Home.\n\
+    Dim other As clsOrder\n\
+    other.Owner.\n\
+End Sub\n";
+        let order = "Public Owner As frmMain\nPrivate secret As Long\nPublic Sub Ship()\nEnd Sub\n";
+        let table = parse_and_build(form);
+        let order_table = parse_and_build(order);
+        let analyzer = Analyzer::new();
+        let modules = |name: &str| -> Option<&SymbolTable> {
+            match name.to_lowercase().as_str() {
+                "clsorder" => Some(&order_table),
+                "frmmain" => Some(&table),
+                _ => None,
+            }
+        };
+        let labels = |line: u32, character: u32| -> Vec<String> {
+            analyzer
+                .get_completions_with_symbols(
+                    &table,
+                    Position::new(line, character),
+                    form,
+                    &[],
+                    modules,
+                )
+                .into_iter()
+                .map(|item| item.label)
+                .collect()
+        };
+
+        // A control reached through Me offers its TextBox members
+        let text1 = labels(16, 9);
+        assert!(text1.contains(&"SelStart".to_string()));
+        assert!(!text1.contains(&"Frame1".to_string()));
+
+        // Me lists nested controls, public members, and the form's own properties
+        let me = labels(17, 3);
+        assert!(me.contains(&"Frame1".to_string()) && me.contains(&"Text1".to_string()));
+        assert!(me.contains(&"Buyer".to_string()) && me.contains(&"Caption".to_string()));
+
+        // Fields of a nested user-defined type
+        assert_eq!(labels(18, 11), vec!["City".to_string()]);
+
+        // A class variable's public member of a form type
+        let owner = labels(20, 12);
+        assert!(owner.contains(&"Text1".to_string()));
+        assert!(!owner.contains(&"secret".to_string()));
+    }
+
+    #[test]
+    fn test_module_and_class_member_completions() {
+        let utils = "Attribute VB_Name = \"modUtils\"\n\
+Public Const LIMIT = 5\n\
+Private mCount As Long\n\
+Function Helper() As Long\n\
+End Function\n\
+Private Sub Secret()\n\
+End Sub\n\
+Sub Run()\n\
+modUtils.\n\
+End Sub\n";
+        let logger = "Public Event Logged()\n\
+Public Sub Write(ByVal text As String)\n\
+End Sub\n\
+Private Sub Flush()\n\
+End Sub\n\
+Property Get Level() As Long\n\
+End Property\n";
+        let main = "Sub Main()\n\
+Dim log As New clsLogger\n\
+modUtils.\n\
+log.\n\
+End Sub\n";
+        let build = |uri: &str, source: &str| {
+            let mut parser = TreeSitterVb6Parser::new().unwrap();
+            let tree = parser.parse(source, None).unwrap();
+            build_symbol_table(Url::parse(uri).unwrap(), source, &tree)
+        };
+        let utils_table = build("file:///project/modUtils.bas", utils);
+        let logger_table = build("file:///project/clsLogger.cls", logger);
+        let main_table = build("file:///project/modMain.bas", main);
+        let modules = |name: &str| -> Option<&SymbolTable> {
+            match name.to_lowercase().as_str() {
+                "modutils" => Some(&utils_table),
+                "clslogger" => Some(&logger_table),
+                _ => None,
+            }
+        };
+        let analyzer = Analyzer::new();
+        // Completions in modUtils itself, or else in modMain
+        let labels = |in_utils: bool, line: u32, character: u32| {
+            let (table, source) =
+                if in_utils { (&utils_table, utils) } else { (&main_table, main) };
+            let mut labels: Vec<String> = analyzer
+                .get_completions_with_symbols(
+                    table,
+                    Position::new(line, character),
+                    source,
+                    &[],
+                    modules,
+                )
+                .into_iter()
+                .map(|item| item.label)
+                .collect();
+            labels.sort();
+            labels
+        };
+
+        // Procedures without a modifier are Public; Private members stay hidden
+        assert_eq!(labels(false, 2, 9), vec!["Helper", "LIMIT", "Run"]);
+        // A class variable lists callable members, not events
+        assert_eq!(labels(false, 3, 4), vec!["Level", "Write"]);
+        // A module naming itself reaches its own Private members
+        assert_eq!(
+            labels(true, 8, 9),
+            vec!["Helper", "LIMIT", "Run", "Secret", "mCount"]
+        );
+    }
+
+    #[test]
+    fn test_user_control_members() {
+        let control = "VERSION 5.00\n\
+Begin VB.UserControl ucGridTest\n\
+End\n\
+Attribute VB_Name = \"ucGridTest\"\n\
+Public Event RowClick(ByVal Row As Long)\n\
+Public Property Get Rows() As Long\n\
+End Property\n\
+Public Property Let Rows(ByVal Value As Long)\n\
+End Property\n\
+Public Property Get Caption() As String\n\
+End Property\n\
+Public Sub Refresh()\n\
+End Sub\n\
+Private Sub Redraw()\n\
+End Sub\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(control, None).unwrap();
+        let uri = Url::parse("file:///ucGridTest.ctl").unwrap();
+        let control_table = build_symbol_table(uri, control, &tree);
+        assert_eq!(user_control_name(&control_table).as_deref(), Some("ucGridTest"));
+        register_user_control(&control_table);
+
+        let rows = crate::controls::get_property("ucGridTest", "Rows").unwrap();
+        assert!(!rows.read_only);
+        assert_eq!(rows.property_type.vb6_type(), "Long");
+        assert!(crate::controls::get_property("ucGridTest", "Caption").unwrap().read_only);
+        assert!(crate::controls::get_method("ucGridTest", "Redraw").is_none());
+        let event = crate::controls::get_event("ucGridTest", "RowClick").unwrap();
+        assert_eq!(event.parameters, "ByVal Row As Long");
+
+        let form = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Begin Project1.ucGridTest Grid1\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private Sub Form_Load()\n\
+Grid1.\n\
+Grid1.Rows = 2\n\
+End Sub\n";
+        let table = parse_and_build(form);
+        let analyzer = Analyzer::new();
+        let labels: Vec<String> = analyzer
+            .get_completions_with_symbols(&table, Position::new(7, 6), form, &[], |_| None)
+            .into_iter()
+            .map(|item| item.label)
+            .collect();
+        assert!(labels.contains(&"Rows".to_string()) && labels.contains(&"Refresh".to_string()));
+        assert!(labels.contains(&"Left".to_string()));
+        assert!(!labels.contains(&"Redraw".to_string()));
+
+        // A member goes to its declaration in the .ctl
+        let external = Location {
+            uri: Url::parse("file:///ucGridTest.ctl").unwrap(),
+            range: Range::default(),
+        };
+        let resolve = |qualifier: Option<&str>, name: &str| {
+            (qualifier == Some("ucGridTest") && name == "Rows").then(|| external.clone())
+        };
+        let definition = analyzer
+            .get_definition_with_symbols(&table, form, Position::new(8, 7), resolve)
+            .unwrap();
+        assert_eq!(definition, GotoDefinitionResponse::Scalar(external));
+    }
+
+    #[test]
+    fn test_enum_value_completions() {
+        let form = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Begin VB.TextBox Text1\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private Enum Shade\n\
+    Light\n\
+    Dark = &H10\n\
+    Darker\n\
+End Enum\n\
+Private Sub Form_Load()\n\
+    Dim s As Shade\n\
+    Text1.Alignment = \n\
+    s = D\n\
+    If s <= \n\
+End Sub\n";
+        let table = parse_and_build(form);
+        let analyzer = Analyzer::new();
+        let items = |line: u32, character: u32| {
+            analyzer.get_completions_with_symbols(
+                &table,
+                Position::new(line, character),
+                form,
+                &[],
+                |_| None,
+            )
+        };
+
+        let alignment = items(13, 18);
+        let labels: Vec<&str> = alignment.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, vec!["vbLeftJustify", "vbRightJustify", "vbCenter"]);
+        assert_eq!(alignment[2].detail.as_deref(), Some("= 2"));
+
+        let shade = items(14, 5);
+        let details: Vec<&str> = shade.iter().filter_map(|item| item.detail.as_deref()).collect();
+        assert_eq!(details, vec!["Shade.Light = 0", "Shade.Dark = 16", "Shade.Darker = 17"]);
+
+        // A comparison is not an assignment
+        assert!(items(15, 8).iter().any(|item| item.label == "Form_Load"));
+    }
+
+    #[test]
+    fn test_color_constant_completions_and_hover() {
+        let form = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Begin VB.TextBox Text1\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private mHighlight As OLE_COLOR\n\
+Private Sub Form_Load()\n\
+    Text1.BackColor = \n\
+    mHighlight = vbR\n\
+    Text1.ForeColor = vbButtonFace\n\
+End Sub\n";
+        let table = parse_and_build(form);
+        let analyzer = Analyzer::new();
+        let items = |line: u32, character: u32| {
+            analyzer.get_completions_with_symbols(
+                &table,
+                Position::new(line, character),
+                form,
+                &[],
+                |_| None,
+            )
+        };
+
+        let back_color = items(8, 18);
+        let red = back_color.iter().find(|item| item.label == "vbRed").unwrap();
+        assert_eq!(red.kind, Some(CompletionItemKind::COLOR));
+        assert_eq!(red.detail.as_deref(), Some("&H000000FF&"));
+        assert!(back_color.iter().any(|item| item.label == "vbWindowBackground"));
+        assert!(back_color.iter().all(|item| item.kind == Some(CompletionItemKind::COLOR)));
+
+        let variable = items(9, 16);
+        assert!(variable.iter().any(|item| item.label == "vbButtonFace"));
+
+        let hover = analyzer
+            .get_hover_with_symbols(&table, form, Position::new(10, 25))
+            .unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup");
+        };
+        assert!(content.value.contains("vbButtonFace"));
+        assert!(content.value.contains("&H8000000F&"));
+    }
+
+    #[test]
+    fn test_intrinsic_function_hover_signature_and_completion() {
+        let source = "Sub Main()\n\
+    Dim s As String\n\
+    s = Left$(s, 2)\n\
+    n = InStr(1, s, \n\
+    MsgBox s\n\
+    n = Text1.Left\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+        let hover_text = |line: u32, character: u32| {
+            analyzer
+                .get_hover_with_symbols(&table, source, Position::new(line, character))
+                .map(|hover| match hover.contents {
+                    HoverContents::Markup(markup) => markup.value,
+                    _ => String::new(),
+                })
+        };
+
+        let left = hover_text(2, 6).unwrap();
+        assert!(left.contains("Left$(string, length) As String"));
+        assert!(hover_text(4, 1).unwrap().contains("MsgBox(prompt, [buttons]"));
+        // Neither a type name nor a control property is the runtime function
+        assert!(!hover_text(1, 12).unwrap_or_default().contains("repeated character"));
+        assert!(hover_text(5, 12).is_none());
+
+        let help = analyzer
+            .get_signature_help_with_symbols(&table, source, Position::new(3, 15))
+            .unwrap();
+        assert_eq!(help.signatures[0].label, "InStr([start], string1, string2, [compare]) As Long");
+        assert_eq!(help.active_parameter, Some(2));
+
+        let position = Position::new(4, 0);
+        let completions =
+            analyzer.get_completions_with_symbols(&table, position, source, &[], |_| None);
+        let msgbox = completions.iter().find(|item| item.label == "MsgBox").unwrap();
+        assert_eq!(msgbox.kind, Some(CompletionItemKind::FUNCTION));
+        let resolved = analyzer.resolve_completion(msgbox.clone(), None);
+        assert!(resolved.documentation.is_some());
+    }
+
+    #[test]
+    fn test_param_array_signature_help() {
+        let source = "Sub Log(Optional ByVal level As Long = 2, ParamArray args() As Variant)\n\
+End Sub\n\
+Sub Main()\n\
+Log 1, \"a\", \"b\", \n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+
+        let hover = analyzer
+            .get_hover_with_symbols(&table, source, Position::new(0, 5))
+            .unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("expected markup");
+        };
+        assert!(content.value.contains(
+            "Sub Log(Optional ByVal level As Long = 2, ParamArray args() As Variant)"
+        ));
+
+        let active_parameter = |character| {
+            let help = analyzer
+                .get_signature_help_with_symbols(&table, source, Position::new(3, character))
+                .unwrap();
+            help.signatures[0].active_parameter.or(help.active_parameter)
+        };
+        assert_eq!(active_parameter(4), Some(0));
+        assert_eq!(active_parameter(7), Some(1));
+        assert_eq!(active_parameter(17), Some(1));
+    }
+
+    #[test]
+    fn test_named_argument_signature_help() {
+        let source = "Sub Resize(ByVal Width As Long, Optional ByVal Height As Long)\n\
+End Sub\n\
+Sub Main()\n\
+    Resize Height:=20, Width:=\n\
+End Sub\n";
+        let table = parse_and_build(source);
+        let analyzer = Analyzer::new();
+
+        let active_parameter = |character| {
+            let help = analyzer
+                .get_signature_help_with_symbols(&table, source, Position::new(3, character))
+                .unwrap();
+            help.signatures[0].active_parameter.or(help.active_parameter)
+        };
+        assert_eq!(active_parameter(15), Some(1));
+        assert_eq!(active_parameter(26), Some(0));
+    }
+
+    #[test]
+    fn test_document_symbol_outline() {
+        let source = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Begin VB.CommandButton cmdOK\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private Const MAX_ITEMS As Long = 10\n\
+Private Enum Mode\n    ModeOff\n    ModeOn\nEnd Enum\n\
+Private Type Point\n    X As Long\n    Y As Long\nEnd Type\n\
+Public Event Changed()\n\
+Private Sub Form_Load()\nEnd Sub\n\
+Private Sub cmdOK_Click()\nEnd Sub\n\
+Private Sub Refresh_All()\nEnd Sub\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = build_symbol_table(Url::parse("file:///frmMain.frm").unwrap(), source, &tree);
+
+        let outline: Vec<(String, tower_lsp::lsp_types::SymbolKind, Vec<String>)> = Analyzer::new()
+            .get_document_symbols_with_symbols(&table)
+            .into_iter()
+            .map(|symbol| {
+                let children = symbol
+                    .children
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|child| format!("{} {:?}", child.name, child.kind))
+                    .collect();
+                (symbol.name, symbol.kind, children)
+            })
+            .collect();
+
+        use tower_lsp::lsp_types::SymbolKind as Lsp;
+        assert_eq!(
+            outline,
+            vec![
+                ("MAX_ITEMS".to_string(), Lsp::CONSTANT, vec![]),
+                (
+                    "Mode".to_string(),
+                    Lsp::ENUM,
+                    vec![
+                        format!("ModeOff {:?}", Lsp::ENUM_MEMBER),
+                        format!("ModeOn {:?}", Lsp::ENUM_MEMBER),
+                    ]
+                ),
+                (
+                    "Point".to_string(),
+                    Lsp::STRUCT,
+                    vec![format!("X {:?}", Lsp::FIELD), format!("Y {:?}", Lsp::FIELD)]
+                ),
+                ("Changed".to_string(), Lsp::EVENT, vec![]),
+                ("Form_Load".to_string(), Lsp::METHOD, vec![]),
+                ("cmdOK_Click".to_string(), Lsp::METHOD, vec![]),
+                ("Refresh_All".to_string(), Lsp::FUNCTION, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flat_document_symbols() {
+        let source = "Attribute VB_Name = \"modMain\"\n\
+Private Type SomeType\n    X As Long\nEnd Type\n\
+Public Sub Main()\nEnd Sub\n";
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let uri = Url::parse("file:///project/modMain.bas").unwrap();
+        let table = build_symbol_table(uri.clone(), source, &tree);
+
+        let symbols = Analyzer::new().get_flat_document_symbols(&table);
+        let entries: Vec<(&str, Option<&str>, u32)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.container_name.as_deref(), s.location.range.start.line))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("SomeType", Some("modMain"), 1),
+                ("X", Some("SomeType"), 2),
+                ("Main", Some("modMain"), 4),
+            ]
+        );
+        assert!(symbols.iter().all(|s| s.location.uri == uri));
+    }
+}
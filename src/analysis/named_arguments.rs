@@ -0,0 +1,96 @@
+//! Named Argument Checking
+//!
+//! A call may pass arguments by name (`Resize Width:=10, Height:=20`). VB6
+//! refuses a name that is not a parameter of the procedure ("Named argument
+//! not found"), so calls to procedures declared in the document are checked.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::arguments::{call_arguments, find_calls};
+use super::position::{SourcePosition, SourceRange};
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for a named argument the callee has no parameter for
+pub const UNKNOWN_NAMED_ARGUMENT: &str = "unknown-named-argument";
+
+/// Report named arguments that match no parameter of the called procedure
+pub fn find_unknown_named_arguments(
+    table: &SymbolTable,
+    tree: &Tree,
+    source: &str,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (callee, arguments) in find_calls(&tree.root_node(), source) {
+        if callee.kind() != "identifier" {
+            continue;
+        }
+        let position = SourcePosition::from_ts_point(callee.start_position());
+        let Some(procedure) = table
+            .lookup_at_position(text(&callee, source), position)
+            .filter(|symbol| symbol.kind.is_procedure())
+        else {
+            continue;
+        };
+
+        for name in call_arguments(&arguments).into_iter().filter_map(|a| a.name) {
+            let name_text = text(&name, source);
+            if procedure
+                .parameters
+                .iter()
+                .any(|p| p.name.eq_ignore_ascii_case(name_text))
+            {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                range: SourceRange::from_ts_node(&name).to_lsp(),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String(UNKNOWN_NAMED_ARGUMENT.to_string())),
+                message: format!(
+                    "Named argument not found: '{}' has no parameter '{}'",
+                    procedure.name, name_text
+                ),
+                source: Some("vb6-lsp".to_string()),
+                ..Default::default()
+            });
+        }
+    }
+
+    diagnostics
+}
+
+fn text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    #[test]
+    fn test_unknown_named_arguments() {
+        let source = "Sub Resize(ByVal Width As Long, Optional ByVal Height As Long)\n\
+End Sub\n\
+Sub Main()\n\
+    Resize Width:=10, height:=20\n\
+    Resize 10, Heigth:=20\n\
+    Call Resize(Depth:=1)\n\
+    MsgBox Prompt:=\"Hi\"\n\
+End Sub\n";
+        let diagnostics =
+            run_pass(source, |table, tree| find_unknown_named_arguments(table, tree, source));
+        let found: Vec<(u32, u32, String)> = diagnostics
+            .into_iter()
+            .map(|d| (d.range.start.line, d.range.start.character, d.message))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (4, 11, "Named argument not found: 'Resize' has no parameter 'Heigth'".to_string()),
+                (5, 12, "Named argument not found: 'Resize' has no parameter 'Depth'".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! 64-bit Declare Checking
+//!
+//! 64-bit VBA only accepts `Declare` statements marked `PtrSafe`, and its
+//! handles and pointers are 8 bytes wide, so a `ByVal hWnd As Long` that
+//! worked in VB6 truncates them. Declares that need porting are reported as
+//! information, with the edits that rewrite them to `Declare PtrSafe` and
+//! `LongPtr`.
+//!
+//! Which `Long`s hold pointers is guessed from the Hungarian names Windows
+//! APIs use (`hWnd`, `lpData`) and from well-known handle-returning functions.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Range, TextEdit};
+use tree_sitter::{Node, Tree};
+
+use super::position::{SourcePosition, SourceRange};
+use crate::parser::convert_declare_statement;
+
+/// Diagnostic code for a Declare that won't load in 64-bit VBA
+pub const NOT_PTR_SAFE: &str = "not-ptrsafe";
+
+/// Functions returning a handle or pointer, without their A/W suffix
+const HANDLE_FUNCTIONS: &[&str] = &[
+    "createcompatiblebitmap", "createcompatibledc", "createevent", "createfile",
+    "createfont", "createmutex", "createsolidbrush", "createthread", "createwindowex", "findwindow",
+    "findwindowex", "getactivewindow", "getdc", "getdesktopwindow", "getforegroundwindow",
+    "getmodulehandle", "getparent", "getprocaddress", "getwindow", "getwindowdc", "globalalloc",
+    "globallock", "heapalloc", "loadcursor", "loadicon", "loadimage", "loadlibrary",
+    "localalloc", "openprocess", "selectobject", "setparent", "virtualalloc",
+];
+
+/// Report Declare statements that are not `PtrSafe`
+pub fn find_x64_declares(tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        if node.kind() != "declare_statement" {
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+            continue;
+        }
+        let Some(declaration) = convert_declare_statement(&node, source) else {
+            continue;
+        };
+        if declaration.ptr_safe || node.has_error() {
+            continue;
+        }
+
+        let pointers: Vec<String> = pointer_types(&node, source)
+            .into_iter()
+            .map(|(_, what)| what)
+            .collect();
+        let message = match pointers.as_slice() {
+            [] => format!("Declare '{}' is not PtrSafe", declaration.name),
+            _ => format!(
+                "Declare '{}' is not PtrSafe, and {} should be LongPtr on 64-bit",
+                declaration.name,
+                pointers.join(", ")
+            ),
+        };
+        diagnostics.push(Diagnostic {
+            range: SourceRange::from_ts_node(&node).to_lsp(),
+            severity: Some(DiagnosticSeverity::INFORMATION),
+            code: Some(NumberOrString::String(NOT_PTR_SAFE.to_string())),
+            message,
+            source: Some("vb6-lsp".to_string()),
+            ..Default::default()
+        });
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+/// Edits rewriting a Declare to `Declare PtrSafe`, with `LongPtr` for its pointers
+pub fn x64_declare_edits(node: &Node, source: &str) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    let mut cursor = node.walk();
+    let keyword = node
+        .children(&mut cursor)
+        .find(|child| node_text(child, source).eq_ignore_ascii_case("declare"));
+    if let Some(keyword) = keyword {
+        let end = SourcePosition::from_ts_point(keyword.end_position()).to_lsp();
+        edits.push(TextEdit {
+            range: Range { start: end, end },
+            new_text: " PtrSafe".to_string(),
+        });
+    }
+    for (type_node, _) in pointer_types(node, source) {
+        edits.push(TextEdit {
+            range: SourceRange::from_ts_node(&type_node).to_lsp(),
+            new_text: "LongPtr".to_string(),
+        });
+    }
+    edits
+}
+
+/// `As Long` types of a Declare that hold pointers, with what they belong to
+/// ("'hWnd'" or "the return value")
+fn pointer_types<'t>(node: &Node<'t>, source: &str) -> Vec<(Node<'t>, String)> {
+    let mut pointers = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "parameter_list" => {
+                let mut inner = child.walk();
+                for parameter in child.named_children(&mut inner) {
+                    let Some(name) = parameter.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let name = node_text(&name, source);
+                    if !is_pointer(name) {
+                        continue;
+                    }
+                    if let Some(long) = long_type(&parameter, source) {
+                        pointers.push((long, format!("'{}'", name)));
+                    }
+                }
+            }
+            "as_clause" => {
+                let name = node
+                    .child_by_field_name("name")
+                    .map(|n| node_text(&n, source).to_lowercase())
+                    .unwrap_or_default();
+                let returns_handle = HANDLE_FUNCTIONS.contains(&name.as_str())
+                    || name
+                        .strip_suffix(['a', 'w'])
+                        .is_some_and(|base| HANDLE_FUNCTIONS.contains(&base));
+                if returns_handle {
+                    if let Some(long) = as_clause_long(&child, source) {
+                        pointers.push((long, "the return value".to_string()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    pointers
+}
+
+/// The type node of a parameter declared `As Long`
+fn long_type<'t>(parameter: &Node<'t>, source: &str) -> Option<Node<'t>> {
+    let mut cursor = parameter.walk();
+    let as_clause = parameter
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "as_clause")?;
+    as_clause_long(&as_clause, source)
+}
+
+fn as_clause_long<'t>(as_clause: &Node<'t>, source: &str) -> Option<Node<'t>> {
+    as_clause
+        .child_by_field_name("type")
+        .filter(|t| node_text(t, source).eq_ignore_ascii_case("long"))
+}
+
+/// Whether a parameter name denotes a handle or pointer (`hWnd`, `lpBuffer`, `pData`)
+fn is_pointer(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    let hungarian = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .and_then(|rest| rest.chars().next())
+            .is_some_and(|c| c.is_ascii_uppercase())
+    };
+    lower == "hwnd"
+        || hungarian("h")
+        || hungarian("lp")
+        || hungarian("p")
+        || ["ptr", "handle", "address"].iter().any(|word| lower.contains(word))
+}
+
+fn node_text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    const SOURCE: &str = "Private Declare Function FindWindowA Lib \"user32\" (ByVal lpClassName As String, ByVal lpWindowName As String) As Long\n\
+Private Declare Function SendMessage Lib \"user32\" Alias \"SendMessageA\" (ByVal hWnd As Long, ByVal wMsg As Long, ByVal wParam As Long, lParam As Any) As Long\n\
+Private Declare Sub Sleep Lib \"kernel32\" (ByVal dwMilliseconds As Long)\n";
+
+    #[test]
+    fn test_flags_declares_that_are_not_ptr_safe() {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(SOURCE, None).unwrap();
+
+        let messages: Vec<(u32, String)> = find_x64_declares(&tree, SOURCE)
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    0,
+                    "Declare 'FindWindowA' is not PtrSafe, and the return value should be LongPtr \
+on 64-bit"
+                        .to_string()
+                ),
+                (
+                    1,
+                    "Declare 'SendMessage' is not PtrSafe, and 'hWnd' should be LongPtr on 64-bit"
+                        .to_string()
+                ),
+                (2, "Declare 'Sleep' is not PtrSafe".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_x64_declare_edits() {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(SOURCE, None).unwrap();
+        let declare = tree.root_node().named_child(1).unwrap();
+
+        let edits: Vec<(u32, String)> = x64_declare_edits(&declare, SOURCE)
+            .into_iter()
+            .map(|edit| (edit.range.start.character, edit.new_text))
+            .collect();
+        assert_eq!(edits, vec![(15, " PtrSafe".to_string()), (86, "LongPtr".to_string())]);
+    }
+}
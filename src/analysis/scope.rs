@@ -87,6 +87,14 @@ impl Scope {
         self.symbols.insert(name.to_lowercase(), symbol_id);
     }
 
+    /// Remove a symbol from this scope, unless its name now maps to another symbol
+    pub fn remove_symbol(&mut self, name: &str, symbol_id: SymbolId) {
+        let key = name.to_lowercase();
+        if self.symbols.get(&key) == Some(&symbol_id) {
+            self.symbols.remove(&key);
+        }
+    }
+
     /// Look up a symbol by name in this scope only (case-insensitive)
     pub fn lookup_local(&self, name: &str) -> Option<SymbolId> {
         self.symbols.get(&name.to_lowercase()).copied()
@@ -0,0 +1,231 @@
+//! Set Assignment Checking
+//!
+//! Object references must be assigned with `Set`: `x = New Collection` fails
+//! at run time with "Object variable not set" or "Object doesn't support this
+//! property or method". The reverse, `Set n = 5` for a value type, does not
+//! compile. Both are reported when the types involved are known.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::inference::infer_expression_type;
+use super::position::{SourcePosition, SourceRange};
+use super::scope::ScopeId;
+use super::symbol::{SymbolKind, TypeInfo};
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for an object assigned without `Set`
+pub const MISSING_SET: &str = "missing-set";
+
+/// Diagnostic code for `Set` assigning to a variable of a value type
+pub const SET_ON_VALUE: &str = "set-on-value";
+
+/// Intrinsic types that hold values rather than object references
+const VALUE_TYPES: &[&str] = &[
+    "boolean", "byte", "integer", "long", "single", "double", "currency", "decimal", "date",
+    "string",
+];
+
+/// Classes of the VB runtime that variables are commonly declared as
+const RUNTIME_CLASSES: &[&str] = &[
+    "collection", "control", "dataobject", "errobject", "form", "mdiform", "propertybag",
+    "stdfont", "stdpicture", "usercontrol",
+];
+
+/// Report object assignments that lack `Set`, and `Set` on value types.
+///
+/// `is_class` tells whether a name is a class or form of the project.
+pub fn find_set_mismatches(
+    table: &SymbolTable,
+    tree: &Tree,
+    source: &str,
+    is_class: impl Fn(&str) -> bool,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        let is_set = match node.kind() {
+            "set_statement" => true,
+            "assignment_statement" => false,
+            _ => {
+                let mut cursor = node.walk();
+                stack.extend(node.named_children(&mut cursor));
+                continue;
+            }
+        };
+        let (Some(target), Some(value)) =
+            (node.child_by_field_name("target"), node.child_by_field_name("value"))
+        else {
+            continue;
+        };
+        if target.kind() != "identifier" || node.has_error() {
+            continue;
+        }
+
+        let scope = table.scope_at_position(SourcePosition::from_ts_point(node.start_position()));
+        let target_is_object = infer_expression_type(table, scope, &target, source)
+            .and_then(|t| is_object_type(table, scope, &t, &is_class));
+
+        if !is_set
+            && target_is_object == Some(true)
+            && is_object_expression(table, scope, &value, source, &is_class)
+        {
+            diagnostics.push(diagnostic(
+                SourceRange::from_ts_node(&node),
+                MISSING_SET,
+                "Object assignment requires 'Set'".to_string(),
+            ));
+        } else if is_set && target_is_object == Some(false) {
+            let keyword = node.child(0).unwrap_or(node);
+            diagnostics.push(diagnostic(
+                SourceRange::from_ts_node(&keyword),
+                SET_ON_VALUE,
+                format!(
+                    "'Set' can only assign object references; '{}' is not an object",
+                    target.utf8_text(source.as_bytes()).unwrap_or("")
+                ),
+            ));
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+fn diagnostic(range: SourceRange, code: &str, message: String) -> Diagnostic {
+    Diagnostic {
+        range: range.to_lsp(),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(code.to_string())),
+        message,
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Whether an expression is known to produce an object reference
+fn is_object_expression(
+    table: &SymbolTable,
+    scope: ScopeId,
+    node: &Node,
+    source: &str,
+    is_class: &impl Fn(&str) -> bool,
+) -> bool {
+    match node.kind() {
+        "new_expression" => return true,
+        "literal" => return node.named_child(0).is_some_and(|n| n.kind() == "nothing_literal"),
+        // Controls are objects, though their default property makes `s = Text1` valid
+        "identifier" => {
+            let name = node.utf8_text(source.as_bytes()).unwrap_or("");
+            let symbol = table.lookup_symbol(name, scope);
+            if symbol.is_some_and(|s| s.kind == SymbolKind::FormControl) {
+                return true;
+            }
+        }
+        _ => {}
+    }
+    infer_expression_type(table, scope, node, source)
+        .and_then(|t| is_object_type(table, scope, &t, is_class))
+        .unwrap_or(false)
+}
+
+/// Whether a type holds object references; None for Variant and unknown types
+fn is_object_type(
+    table: &SymbolTable,
+    scope: ScopeId,
+    type_info: &TypeInfo,
+    is_class: &impl Fn(&str) -> bool,
+) -> Option<bool> {
+    // Untyped variables are Variants, whatever was assigned to them first
+    if type_info.is_inferred {
+        return None;
+    }
+    if type_info.is_array {
+        return Some(false);
+    }
+    if type_info.is_new {
+        return Some(true);
+    }
+
+    let name = type_info.name.to_lowercase();
+    if VALUE_TYPES.contains(&name.as_str()) {
+        return Some(false);
+    }
+    match name.as_str() {
+        "object" => return Some(true),
+        "variant" => return None,
+        _ => {}
+    }
+
+    let declared = table.lookup_symbol(&type_info.name, scope).map(|s| s.kind);
+    if matches!(declared, Some(SymbolKind::UserDefinedType | SymbolKind::Enum)) {
+        return Some(false);
+    }
+
+    // Library types such as ADODB.Recordset are qualified by their library
+    let is_object = name.contains('.')
+        || RUNTIME_CLASSES.contains(&name.as_str())
+        || crate::controls::get_control(&type_info.name).is_some()
+        || is_class(&type_info.name);
+    is_object.then_some(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    fn flagged(source: &str) -> Vec<(u32, u32, String)> {
+        run_pass(source, |table, tree| {
+            find_set_mismatches(table, tree, source, |name| name.eq_ignore_ascii_case("clsLogger"))
+        })
+            .into_iter()
+            .map(|d| {
+                let Some(NumberOrString::String(code)) = d.code else {
+                    panic!("expected a string code");
+                };
+                (d.range.start.line, d.range.start.character, code)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_flags_object_assignment_without_set() {
+        let source = "Sub Main()\n    Dim items As Collection\n    Dim log As clsLogger\n\
+    Dim other As clsLogger\n\
+items = New Collection\n\
+log = other\n\
+log = Nothing\n\
+Set items = New Collection\n\
+Set log = other\n\
+End Sub\n";
+
+        assert_eq!(
+            flagged(source),
+            vec![
+                (4, 0, MISSING_SET.to_string()),
+                (5, 0, MISSING_SET.to_string()),
+                (6, 0, MISSING_SET.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flags_set_on_value_types() {
+        let source = "Private Type Point\n    X As Long\nEnd Type\n\
+Sub Main()\n    Dim n As Long\n    Dim p As Point\n    Dim v As Variant\n    Dim r As Unknown\n\
+Set n = 5\n\
+Set p = q\n\
+Set v = Nothing\n\
+Set r = Nothing\n\
+r = s\n\
+n = v\n\
+End Sub\n";
+
+        assert_eq!(
+            flagged(source),
+            vec![(8, 0, SET_ON_VALUE.to_string()), (9, 0, SET_ON_VALUE.to_string())]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Shadowing Detection
+//!
+//! A local variable, constant, or parameter may have the name of a
+//! module-level symbol, which it then hides for the rest of the procedure.
+//! That is legal VB6 but often unintended: assignments meant for the module
+//! variable go to the local instead. Each such declaration is reported.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+
+use super::symbol::Symbol;
+use super::symbol_table::SymbolTable;
+
+/// Diagnostic code for a local that hides a module-level symbol
+pub const SHADOWED_SYMBOL: &str = "shadowed-symbol";
+
+/// Report locals and parameters that hide a symbol of an enclosing scope
+pub fn find_shadowed_symbols(table: &SymbolTable) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = table
+        .all_symbols()
+        .filter_map(|symbol| {
+            let shadowed = table.find_shadowed(symbol)?;
+            Some(Diagnostic {
+                range: symbol.name_range.to_lsp(),
+                severity: Some(DiagnosticSeverity::INFORMATION),
+                code: Some(NumberOrString::String(SHADOWED_SYMBOL.to_string())),
+                message: shadowing_note(table, shadowed),
+                source: Some("vb6-lsp".to_string()),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    diagnostics
+}
+
+/// "Shadows module-level variable 'x' declared at line N"
+pub fn shadowing_note(table: &SymbolTable, shadowed: &Symbol) -> String {
+    let level = if shadowed.scope_id == table.module_scope {
+        "module-level"
+    } else {
+        "outer"
+    };
+    format!(
+        "Shadows {} {} '{}' declared at line {}",
+        level,
+        shadowed.kind.display_name().to_lowercase(),
+        shadowed.name,
+        shadowed.name_range.start.line + 1
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::run_pass;
+
+    #[test]
+    fn test_flags_locals_and_parameters_that_shadow() {
+        let source = "Private mCount As Long\n\
+Private Const LIMIT = 10\n\
+Public Sub Add(ByVal mCount As Long)\n\
+    Dim LIMIT As Integer\n\
+    Dim total As Long\n\
+End Sub\n\
+Public Sub Reset()\n\
+    Dim total As Long\n\
+    mCount = 0\n\
+End Sub\n";
+        let messages: Vec<(u32, String)> = run_pass(source, |table, _| find_shadowed_symbols(table))
+            .into_iter()
+            .map(|d| (d.range.start.line, d.message))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (2, "Shadows module-level variable 'mCount' declared at line 1".to_string()),
+                (3, "Shadows module-level constant 'LIMIT' declared at line 2".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Code Snippets
+//!
+//! Templates for common VB6 constructs, offered as completions. A tab stop
+//! that appears more than once (`$1`) is filled in once and mirrored, such as
+//! the loop variable after `Next` or the type of a property's backing field.
+
+/// A completion template
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    /// What the user types to find it (e.g., "for")
+    pub prefix: &'static str,
+    /// Completion label (e.g., "For ... Next")
+    pub label: &'static str,
+    pub description: &'static str,
+    /// Snippet text with tab stops, indented with four spaces
+    pub body: &'static str,
+    /// Whether it is a declaration for the module level rather than a
+    /// statement inside a procedure
+    pub module_level: bool,
+}
+
+/// All snippets
+pub static SNIPPETS: &[Snippet] = &[
+    Snippet {
+        prefix: "for",
+        label: "For ... Next",
+        description: "Counted loop",
+        body: "For ${1:i} = ${2:0} To ${3:n}\n    $0\nNext $1",
+        module_level: false,
+    },
+    Snippet {
+        prefix: "if",
+        label: "If ... Else ... End If",
+        description: "Conditional block with an Else branch",
+        body: "If ${1:condition} Then\n    $2\nElse\n    $0\nEnd If",
+        module_level: false,
+    },
+    Snippet {
+        prefix: "select",
+        label: "Select Case ... End Select",
+        description: "Branch on the value of an expression",
+        body: "Select Case ${1:expression}\n    Case ${2:value}\n        $3\n    \
+               Case Else\n        $0\nEnd Select",
+        module_level: false,
+    },
+    Snippet {
+        prefix: "with",
+        label: "With ... End With",
+        description: "Statements on the members of one object",
+        body: "With ${1:object}\n    .$0\nEnd With",
+        module_level: false,
+    },
+    Snippet {
+        prefix: "property",
+        label: "Property Get/Let",
+        description: "Property with a Private backing field",
+        body: "Private m${1:Name} As ${2:Long}\n\n\
+               Public Property Get $1() As $2\n    $1 = m$1\nEnd Property\n\n\
+               Public Property Let $1(ByVal value As $2)\n    m$1 = value\nEnd Property\n$0",
+        module_level: true,
+    },
+    Snippet {
+        prefix: "property",
+        label: "Property Get/Set",
+        description: "Object property with a Private backing field",
+        body: "Private m${1:Name} As ${2:Object}\n\n\
+               Public Property Get $1() As $2\n    Set $1 = m$1\nEnd Property\n\n\
+               Public Property Set $1(ByVal value As $2)\n    Set m$1 = value\nEnd Property\n$0",
+        module_level: true,
+    },
+    Snippet {
+        prefix: "withevents",
+        label: "WithEvents variable",
+        description: "Object variable whose events are handled in this module",
+        body: "Private WithEvents ${1:mObject} As ${2:ClassName}\n\n\
+               Private Sub $1_${3:EventName}()\n    $0\nEnd Sub",
+        module_level: true,
+    },
+];
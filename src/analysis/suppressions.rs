@@ -0,0 +1,137 @@
+//! Diagnostic Suppression Comments
+//!
+//! A comment silences diagnostics reported on its own line or the next one:
+//!
+//! ```vb
+//! 'vb6-lsp-disable-next-line plus-concatenation
+//! s = a + b
+//! x = 1 + "2" 'vb6-lsp-disable-line plus-concatenation, undeclared-variable
+//! ```
+//!
+//! Rules are named by diagnostic code; a comment without rules silences
+//! every diagnostic of the line.
+
+use std::collections::HashMap;
+
+use tower_lsp::lsp_types::{Diagnostic, NumberOrString};
+
+/// Comment that silences diagnostics on the line after it
+pub const DISABLE_NEXT_LINE: &str = "vb6-lsp-disable-next-line";
+
+/// Comment that silences diagnostics on its own line
+pub const DISABLE_LINE: &str = "vb6-lsp-disable-line";
+
+/// Remove the diagnostics that a suppression comment silences
+pub fn remove_suppressed(diagnostics: &mut Vec<Diagnostic>, source: &str) {
+    let suppressions = suppressions(source);
+    if suppressions.is_empty() {
+        return;
+    }
+
+    diagnostics.retain(|diagnostic| {
+        let rules = match suppressions.get(&diagnostic.range.start.line) {
+            None => return true,
+            Some(None) => return false,
+            Some(Some(rules)) => rules,
+        };
+        let code = match &diagnostic.code {
+            Some(NumberOrString::String(code)) => code.as_str(),
+            _ => return true,
+        };
+        !rules.iter().any(|rule| rule.eq_ignore_ascii_case(code))
+    });
+}
+
+/// Rules silenced on each line; None silences them all
+fn suppressions(source: &str) -> HashMap<u32, Option<Vec<&str>>> {
+    let mut lines: HashMap<u32, Option<Vec<&str>>> = HashMap::new();
+
+    for (number, line) in source.lines().enumerate() {
+        let Some(comment) = comment(line) else {
+            continue;
+        };
+        let comment = comment.trim_start();
+        let (target, rest) = if let Some(rest) = comment.strip_prefix(DISABLE_NEXT_LINE) {
+            (number + 1, rest)
+        } else if let Some(rest) = comment.strip_prefix(DISABLE_LINE) {
+            (number, rest)
+        } else {
+            continue;
+        };
+        // `'vb6-lsp-disable-lines` is not a pragma
+        if rest.starts_with(|c: char| !c.is_whitespace() && c != ',') {
+            continue;
+        }
+
+        let named: Vec<&str> = rest
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|rule| !rule.is_empty())
+            .collect();
+        let rules = lines.entry(target as u32).or_insert(Some(Vec::new()));
+        if named.is_empty() {
+            *rules = None;
+        } else if let Some(rules) = rules {
+            rules.extend(named);
+        }
+    }
+    lines
+}
+
+/// Text of a line's comment, after its `'`
+fn comment(line: &str) -> Option<&str> {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '\'' if !in_string => return Some(&line[i + 1..]),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::{Position, Range};
+
+    fn diagnostic(line: u32, code: Option<&str>) -> Diagnostic {
+        Diagnostic {
+            range: Range::new(Position::new(line, 0), Position::new(line, 1)),
+            code: code.map(|c| NumberOrString::String(c.to_string())),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_remove_suppressed() {
+        let source = "'vb6-lsp-disable-next-line plus-concatenation\n\
+s = a + b\n\
+x = 1 + \"2\" ' vb6-lsp-disable-line plus-concatenation, undeclared-variable\n\
+y = 1 'vb6-lsp-disable-line\n\
+MsgBox \"'vb6-lsp-disable-line\"\n\
+z = 2 'vb6-lsp-disable-lines\n";
+        let mut diagnostics = vec![
+            diagnostic(1, Some("plus-concatenation")),
+            diagnostic(1, Some("undeclared-variable")),
+            diagnostic(2, Some("plus-concatenation")),
+            diagnostic(2, Some("undeclared-variable")),
+            diagnostic(3, Some("unreachable-code")),
+            diagnostic(3, None),
+            diagnostic(4, None),
+            diagnostic(5, None),
+        ];
+        remove_suppressed(&mut diagnostics, source);
+
+        let remaining: Vec<(u32, Option<NumberOrString>)> =
+            diagnostics.into_iter().map(|d| (d.range.start.line, d.code)).collect();
+        assert_eq!(
+            remaining,
+            vec![
+                (1, Some(NumberOrString::String("undeclared-variable".to_string()))),
+                (4, None),
+                (5, None),
+            ]
+        );
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! Defines the Symbol struct and related types for the symbol table.
 
+use super::constants::ConstValue;
+use crate::parser::ArrayDimension;
 use super::position::SourceRange;
 use super::scope::ScopeId;
 
@@ -178,8 +180,12 @@ pub struct TypeInfo {
     pub name: String,
     /// Whether this is an array type
     pub is_array: bool,
+    /// Declared bounds of a fixed-size array; empty for a dynamic array
+    pub dimensions: Vec<ArrayDimension>,
     /// Whether this is a New expression type (for classes)
     pub is_new: bool,
+    /// Whether this was inferred from an assignment to an untyped (Variant) variable
+    pub is_inferred: bool,
 }
 
 impl TypeInfo {
@@ -187,7 +193,9 @@ impl TypeInfo {
         Self {
             name: name.into(),
             is_array: false,
+            dimensions: Vec::new(),
             is_new: false,
+            is_inferred: false,
         }
     }
 
@@ -195,20 +203,77 @@ impl TypeInfo {
         Self {
             name: name.into(),
             is_array: true,
+            dimensions: Vec::new(),
             is_new: false,
+            is_inferred: false,
         }
     }
 
+    /// Type of an untyped variable, inferred from a value assigned to it
+    pub fn inferred(mut self) -> Self {
+        self.is_inferred = true;
+        self
+    }
+
+    /// Whether this is an array declared with bounds, which `ReDim` cannot resize
+    pub fn is_fixed_array(&self) -> bool {
+        self.is_array && !self.dimensions.is_empty()
+    }
+
+    /// The parentheses that follow an array's name in its declaration
+    /// (e.g., "(1 To 10, 5)", "()"); empty for other types
+    pub fn array_suffix(&self) -> String {
+        if !self.is_array {
+            return String::new();
+        }
+        let dimensions: Vec<String> = self.dimensions.iter().map(|d| d.to_string()).collect();
+        format!("({})", dimensions.join(", "))
+    }
+
     /// Format for display (e.g., "Integer()" for arrays)
     pub fn display(&self) -> String {
-        if self.is_array {
+        let name = if self.is_array {
             format!("{}()", self.name)
         } else {
             self.name.clone()
+        };
+        if self.is_inferred {
+            format!("Variant ' inferred {}", name)
+        } else {
+            name
         }
     }
 }
 
+/// One element of a form control array (controls sharing a name, told apart by Index)
+#[derive(Debug, Clone)]
+pub struct ControlArrayElement {
+    /// The Index property of the element
+    pub index: i32,
+    /// The range of the name on the element's Begin line
+    pub name_range: SourceRange,
+}
+
+/// The control event a Sub of a form handles, by its name (`cmdOK_Click`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventBinding {
+    /// The control symbol, or None when the form has no control of that name
+    pub control: Option<SymbolId>,
+    /// The control name as written in the Sub name
+    pub control_name: String,
+    /// The event name as written in the Sub name
+    pub event: String,
+}
+
+/// The DLL function a Declare statement binds to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiEntryPoint {
+    /// DLL name from the `Lib` clause (e.g., "kernel32")
+    pub library: String,
+    /// Exported name from the `Alias` clause, when it differs from the declared name
+    pub alias: Option<String>,
+}
+
 /// Parameter information for procedures
 #[derive(Debug, Clone)]
 pub struct ParameterInfo {
@@ -220,6 +285,8 @@ pub struct ParameterInfo {
     pub by_ref: bool,
     /// Whether optional
     pub optional: bool,
+    /// Whether declared `ParamArray`, taking all remaining arguments
+    pub is_param_array: bool,
     /// Default value expression (for optional params)
     pub default_value: Option<String>,
     /// Position range of the entire parameter declaration
@@ -237,16 +304,25 @@ impl ParameterInfo {
             parts.push("Optional".to_string());
         }
 
-        if self.by_ref {
+        if self.is_param_array {
+            parts.push("ParamArray".to_string());
+        } else if self.by_ref {
             parts.push("ByRef".to_string());
         } else {
             parts.push("ByVal".to_string());
         }
 
-        parts.push(self.name.clone());
-
-        if let Some(ref ti) = self.type_info {
-            parts.push(format!("As {}", ti.display()));
+        // Array parameters are written `values() As Long`
+        match self.type_info {
+            Some(ref ti) if ti.is_array => {
+                parts.push(format!("{}()", self.name));
+                parts.push(format!("As {}", ti.name));
+            }
+            Some(ref ti) => {
+                parts.push(self.name.clone());
+                parts.push(format!("As {}", ti.display()));
+            }
+            None => parts.push(self.name.clone()),
         }
 
         if let Some(ref default) = self.default_value {
@@ -284,6 +360,16 @@ pub struct Symbol {
     pub documentation: Option<String>,
     /// Value (for constants and enum members)
     pub value: Option<String>,
+    /// Evaluated value (for constants and enum members that can be folded)
+    pub resolved_value: Option<ConstValue>,
+    /// For form controls: the containers from the form down (e.g., "frmMain.Frame1.Text1")
+    pub control_path: Option<String>,
+    /// For control arrays: the elements in declaration order (empty for single controls)
+    pub control_array: Vec<ControlArrayElement>,
+    /// For event handlers of form controls: the control and event handled
+    pub handles: Option<EventBinding>,
+    /// For Declare statements: the library and alias
+    pub entry_point: Option<ApiEntryPoint>,
 }
 
 impl Symbol {
@@ -310,17 +396,56 @@ impl Symbol {
             members: Vec::new(),
             documentation: None,
             value: None,
+            resolved_value: None,
+            control_path: None,
+            control_array: Vec::new(),
+            handles: None,
+            entry_point: None,
+        }
+    }
+
+    /// The value of a constant or enum member, evaluated where possible
+    pub fn value_text(&self) -> Option<String> {
+        match &self.resolved_value {
+            Some(value) => Some(value.to_string()),
+            None => self.value.clone(),
         }
     }
 
     /// Format the symbol as a signature for hover display
     pub fn format_signature(&self) -> String {
         match self.kind {
-            SymbolKind::Sub | SymbolKind::DeclareSub => {
+            SymbolKind::Sub => {
                 let params = self.format_parameters();
                 format!("{} Sub {}({})", self.visibility.as_str(), self.name, params)
             }
-            SymbolKind::Function | SymbolKind::DeclareFunction => {
+            SymbolKind::DeclareSub => {
+                let params = self.format_parameters();
+                format!(
+                    "{} Declare Sub {}{} ({})",
+                    self.visibility.as_str(),
+                    self.name,
+                    self.format_entry_point(),
+                    params
+                )
+            }
+            SymbolKind::DeclareFunction => {
+                let params = self.format_parameters();
+                let ret_type = self
+                    .type_info
+                    .as_ref()
+                    .map(|t| t.display())
+                    .unwrap_or_else(|| "Variant".to_string());
+                format!(
+                    "{} Declare Function {}{} ({}) As {}",
+                    self.visibility.as_str(),
+                    self.name,
+                    self.format_entry_point(),
+                    params,
+                    ret_type
+                )
+            }
+            SymbolKind::Function => {
                 let params = self.format_parameters();
                 let ret_type = self
                     .type_info
@@ -369,20 +494,21 @@ impl Symbol {
                 )
             }
             SymbolKind::Variable | SymbolKind::LocalVariable => {
+                format!("{} {}", self.visibility.as_str(), self.format_declarator())
+            }
+            SymbolKind::Constant | SymbolKind::LocalConstant => {
                 let type_str = self
                     .type_info
                     .as_ref()
                     .map(|t| t.display())
-                    .unwrap_or_else(|| "Variant".to_string());
-                format!("{} {} As {}", self.visibility.as_str(), self.name, type_str)
-            }
-            SymbolKind::Constant | SymbolKind::LocalConstant => {
-                let value = self.value.as_deref().unwrap_or("?");
+                    .or_else(|| self.resolved_value.as_ref().map(|v| v.type_name().to_string()));
+                let type_str = type_str.map(|t| format!(" As {}", t)).unwrap_or_default();
                 format!(
-                    "{} Const {} = {}",
+                    "{} Const {}{} = {}",
                     self.visibility.as_str(),
                     self.name,
-                    value
+                    type_str,
+                    self.value_text().unwrap_or_else(|| "?".to_string())
                 )
             }
             SymbolKind::Parameter => {
@@ -404,20 +530,13 @@ impl Symbol {
                 format!("{} Enum {}", self.visibility.as_str(), self.name)
             }
             SymbolKind::EnumMember => {
-                if let Some(ref val) = self.value {
+                if let Some(val) = self.value_text() {
                     format!("{} = {}", self.name, val)
                 } else {
                     self.name.clone()
                 }
             }
-            SymbolKind::TypeMember => {
-                let type_str = self
-                    .type_info
-                    .as_ref()
-                    .map(|t| t.display())
-                    .unwrap_or_else(|| "Variant".to_string());
-                format!("{} As {}", self.name, type_str)
-            }
+            SymbolKind::TypeMember => self.format_declarator(),
             SymbolKind::ForLoopVariable | SymbolKind::ForEachVariable => {
                 format!("(loop variable) {}", self.name)
             }
@@ -475,11 +594,31 @@ impl Symbol {
         }
     }
 
-    fn format_parameters(&self) -> String {
+    /// Format a variable or Type member as declared (e.g., "Grid(1 To 10, 5) As Long")
+    fn format_declarator(&self) -> String {
+        match self.type_info {
+            Some(ref t) if t.is_array => format!("{}{} As {}", self.name, t.array_suffix(), t.name),
+            Some(ref t) => format!("{} As {}", self.name, t.display()),
+            None => format!("{} As Variant", self.name),
+        }
+    }
+
+    pub(crate) fn format_parameters(&self) -> String {
         self.parameters
             .iter()
             .map(|p| p.format_signature())
             .collect::<Vec<_>>()
             .join(", ")
     }
+
+    /// The ` Lib "..." Alias "..."` clauses of a Declare
+    fn format_entry_point(&self) -> String {
+        let Some(entry_point) = &self.entry_point else {
+            return String::new();
+        };
+        match &entry_point.alias {
+            Some(alias) => format!(" Lib \"{}\" Alias \"{}\"", entry_point.library, alias),
+            None => format!(" Lib \"{}\"", entry_point.library),
+        }
+    }
 }
@@ -6,9 +6,17 @@ use std::collections::HashMap;
 
 use tower_lsp::lsp_types::Url;
 
+use super::constants::ConstValue;
 use super::position::{SourcePosition, SourceRange};
 use super::scope::{Scope, ScopeId, ScopeKind};
-use super::symbol::{ParameterInfo, Symbol, SymbolId, SymbolKind, TypeInfo, Visibility};
+use super::symbol::{
+    ApiEntryPoint, ControlArrayElement, EventBinding, ParameterInfo, Symbol, SymbolId, SymbolKind,
+    TypeInfo, Visibility,
+};
+
+/// Objects whose events a designer or class handles as `Object_Event`
+const EVENT_OBJECTS: &[&str] =
+    &["form", "mdiform", "class", "usercontrol", "userdocument", "propertypage"];
 
 /// A reference to a symbol (usage site)
 #[derive(Debug, Clone)]
@@ -25,17 +33,38 @@ pub struct SymbolReference {
     pub qualifying_reference: Option<Box<SymbolReference>>,
 }
 
+/// An identifier use that did not resolve to any symbol in the document
+#[derive(Debug, Clone)]
+pub struct UnresolvedReference {
+    /// The identifier as written
+    pub name: String,
+    /// The range of the identifier
+    pub range: SourceRange,
+}
+
+/// A member used through an object name that resolved to no symbol in the
+/// document, which may name another module (`modUtils.DoThing`)
+#[derive(Debug, Clone)]
+pub struct MemberUse {
+    /// The object as written
+    pub object: String,
+    /// The member as written
+    pub member: String,
+    /// The range of the member
+    pub range: SourceRange,
+}
+
 /// The complete symbol table for a document
 #[derive(Debug, Clone)]
 pub struct SymbolTable {
     /// Document URI
     pub uri: Url,
 
-    /// All symbols, indexed by ID
-    symbols: Vec<Symbol>,
+    /// All symbols, indexed by ID (None where an incremental update removed one)
+    symbols: Vec<Option<Symbol>>,
 
-    /// All scopes, indexed by ID
-    scopes: Vec<Scope>,
+    /// All scopes, indexed by ID (None where an incremental update removed one)
+    scopes: Vec<Option<Scope>>,
 
     /// The module-level (root) scope
     pub module_scope: ScopeId,
@@ -43,6 +72,24 @@ pub struct SymbolTable {
     /// All references to symbols
     references: Vec<SymbolReference>,
 
+    /// Identifier uses that resolved to no symbol (possibly defined elsewhere)
+    unresolved: Vec<UnresolvedReference>,
+
+    /// Members of objects that resolved to no symbol
+    member_uses: Vec<MemberUse>,
+
+    /// Type names used in `As` clauses, `New` expressions, and `Implements`
+    type_references: Vec<UnresolvedReference>,
+
+    /// Interfaces named by `Implements` statements
+    interfaces: Vec<UnresolvedReference>,
+
+    /// Jump targets (`GoTo`, `Resume`, ...) naming no label of their procedure
+    undefined_labels: Vec<UnresolvedReference>,
+
+    /// Constants whose value depends on themselves
+    circular_constants: Vec<SymbolId>,
+
     /// Spatial index: map from line number to symbols defined on that line
     symbols_by_line: HashMap<u32, Vec<SymbolId>>,
 
@@ -54,6 +101,9 @@ pub struct SymbolTable {
 
     /// Next scope ID to allocate
     next_scope_id: u32,
+
+    /// Number of lines in the source
+    line_count: usize,
 }
 
 impl SymbolTable {
@@ -65,10 +115,17 @@ impl SymbolTable {
             scopes: Vec::new(),
             module_scope: ScopeId(0),
             references: Vec::new(),
+            unresolved: Vec::new(),
+            member_uses: Vec::new(),
+            type_references: Vec::new(),
+            interfaces: Vec::new(),
+            undefined_labels: Vec::new(),
+            circular_constants: Vec::new(),
             symbols_by_line: HashMap::new(),
             scopes_by_line: HashMap::new(),
             next_symbol_id: 0,
             next_scope_id: 0,
+            line_count: 0,
         };
 
         // Create the module scope (covers entire file)
@@ -100,36 +157,90 @@ impl SymbolTable {
 
         let symbol = Symbol::new(
             id,
-            name.clone(),
+            name,
             kind,
             visibility,
             definition_range,
             name_range,
             scope_id,
         );
+        self.insert_symbol(symbol, true);
+        id
+    }
+
+    /// Recreate a symbol removed with `remove_symbol` under its old ID,
+    /// so that references to it stay valid. A symbol of the same name that
+    /// is still in the scope (a Property Let beside its Get) keeps the name.
+    #[allow(clippy::too_many_arguments)]
+    pub fn restore_symbol(
+        &mut self,
+        id: SymbolId,
+        name: String,
+        kind: SymbolKind,
+        visibility: Visibility,
+        definition_range: SourceRange,
+        name_range: SourceRange,
+        scope_id: ScopeId,
+    ) -> SymbolId {
+        let symbol = Symbol::new(
+            id,
+            name,
+            kind,
+            visibility,
+            definition_range,
+            name_range,
+            scope_id,
+        );
+        self.insert_symbol(symbol, false);
+        id
+    }
+
+    fn insert_symbol(&mut self, symbol: Symbol, replace: bool) {
+        let id = symbol.id;
 
         // Add to spatial index (index by name_range lines for precise lookup)
-        for line in name_range.start.line..=name_range.end.line {
+        for line in symbol.name_range.start.line..=symbol.name_range.end.line {
             self.symbols_by_line.entry(line).or_default().push(id);
         }
 
         // Add to scope
-        if let Some(scope) = self.scopes.get_mut(scope_id.0 as usize) {
-            scope.add_symbol(&name, id);
+        if let Some(scope) = self.get_scope_mut(symbol.scope_id) {
+            if replace || !scope.has_symbol(&symbol.name) {
+                scope.add_symbol(&symbol.name, id);
+            }
         }
 
-        self.symbols.push(symbol);
-        id
+        let index = id.0 as usize;
+        if index >= self.symbols.len() {
+            self.symbols.resize_with(index + 1, || None);
+        }
+        self.symbols[index] = Some(symbol);
+    }
+
+    /// Remove a symbol, leaving its ID unused
+    pub fn remove_symbol(&mut self, id: SymbolId) {
+        let Some(symbol) = self.symbols.get_mut(id.0 as usize).and_then(Option::take) else {
+            return;
+        };
+        for line in symbol.name_range.start.line..=symbol.name_range.end.line {
+            if let Some(ids) = self.symbols_by_line.get_mut(&line) {
+                ids.retain(|&other| other != id);
+            }
+        }
+        if let Some(scope) = self.get_scope_mut(symbol.scope_id) {
+            scope.remove_symbol(&symbol.name, id);
+        }
+        self.circular_constants.retain(|&other| other != id);
     }
 
     /// Get a symbol by ID
     pub fn get_symbol(&self, id: SymbolId) -> Option<&Symbol> {
-        self.symbols.get(id.0 as usize)
+        self.symbols.get(id.0 as usize).and_then(Option::as_ref)
     }
 
     /// Get a mutable symbol by ID
     pub fn get_symbol_mut(&mut self, id: SymbolId) -> Option<&mut Symbol> {
-        self.symbols.get_mut(id.0 as usize)
+        self.symbols.get_mut(id.0 as usize).and_then(Option::as_mut)
     }
 
     /// Set type info for a symbol
@@ -146,6 +257,25 @@ impl SymbolTable {
         }
     }
 
+    /// Set the evaluated value of a constant or enum member
+    pub fn set_resolved_value(&mut self, id: SymbolId, value: ConstValue) {
+        if let Some(symbol) = self.get_symbol_mut(id) {
+            symbol.resolved_value = Some(value);
+        }
+    }
+
+    /// Record a constant whose value depends on itself
+    pub fn mark_circular_constant(&mut self, id: SymbolId) {
+        if !self.circular_constants.contains(&id) {
+            self.circular_constants.push(id);
+        }
+    }
+
+    /// Constants whose value depends on themselves
+    pub fn circular_constants(&self) -> &[SymbolId] {
+        &self.circular_constants
+    }
+
     /// Add parameters to a procedure symbol
     pub fn set_parameters(&mut self, id: SymbolId, parameters: Vec<ParameterInfo>) {
         if let Some(symbol) = self.get_symbol_mut(id) {
@@ -160,6 +290,34 @@ impl SymbolTable {
         }
     }
 
+    /// Set the container path of a form control
+    pub fn set_control_path(&mut self, id: SymbolId, path: String) {
+        if let Some(symbol) = self.get_symbol_mut(id) {
+            symbol.control_path = Some(path);
+        }
+    }
+
+    /// Add an element to a form control array
+    pub fn add_control_array_element(&mut self, id: SymbolId, element: ControlArrayElement) {
+        if let Some(symbol) = self.get_symbol_mut(id) {
+            symbol.control_array.push(element);
+        }
+    }
+
+    /// Record the control event a Sub handles
+    pub fn set_event_binding(&mut self, id: SymbolId, binding: EventBinding) {
+        if let Some(symbol) = self.get_symbol_mut(id) {
+            symbol.handles = Some(binding);
+        }
+    }
+
+    /// Record the library and alias of a Declare
+    pub fn set_entry_point(&mut self, id: SymbolId, entry_point: ApiEntryPoint) {
+        if let Some(symbol) = self.get_symbol_mut(id) {
+            symbol.entry_point = Some(entry_point);
+        }
+    }
+
     /// Set documentation for a symbol
     pub fn set_documentation(&mut self, id: SymbolId, doc: String) {
         if let Some(symbol) = self.get_symbol_mut(id) {
@@ -189,25 +347,68 @@ impl SymbolTable {
             self.scopes_by_line.entry(line).or_default().push(id);
         }
 
-        // Add as child to parent
+        // Add as child to parent, keeping children in document order
         if let Some(parent_id) = parent {
-            if let Some(parent_scope) = self.scopes.get_mut(parent_id.0 as usize) {
-                parent_scope.add_child(id);
+            let position = self.get_scope(parent_id).map(|parent_scope| {
+                parent_scope.children.partition_point(|&child| {
+                    self.get_scope(child).is_some_and(|c| c.range.start <= range.start)
+                })
+            });
+            if let (Some(position), Some(parent_scope)) =
+                (position, self.get_scope_mut(parent_id))
+            {
+                parent_scope.children.insert(position, id);
             }
         }
 
-        self.scopes.push(scope);
+        self.scopes.push(Some(scope));
         id
     }
 
     /// Get a scope by ID
     pub fn get_scope(&self, id: ScopeId) -> Option<&Scope> {
-        self.scopes.get(id.0 as usize)
+        self.scopes.get(id.0 as usize).and_then(Option::as_ref)
     }
 
     /// Get a mutable scope by ID
     pub fn get_scope_mut(&mut self, id: ScopeId) -> Option<&mut Scope> {
-        self.scopes.get_mut(id.0 as usize)
+        self.scopes.get_mut(id.0 as usize).and_then(Option::as_mut)
+    }
+
+    /// Remove a scope and the scopes nested in it, with the symbols declared
+    /// and the references made inside them
+    pub fn remove_scope(&mut self, id: ScopeId) {
+        let Some(range) = self.get_scope(id).map(|scope| scope.range) else {
+            return;
+        };
+        if let Some(parent) = self.get_scope(id).and_then(|scope| scope.parent) {
+            if let Some(parent_scope) = self.get_scope_mut(parent) {
+                parent_scope.children.retain(|&child| child != id);
+            }
+        }
+
+        let mut removed = Vec::new();
+        let mut pending = vec![id];
+        while let Some(scope_id) = pending.pop() {
+            let Some(scope) = self.scopes.get_mut(scope_id.0 as usize).and_then(Option::take)
+            else {
+                continue;
+            };
+            for symbol_id in scope.symbols() {
+                self.remove_symbol(symbol_id);
+            }
+            pending.extend(scope.children.iter().copied());
+            removed.push(scope_id);
+        }
+
+        for ids in self.scopes_by_line.values_mut() {
+            ids.retain(|scope_id| !removed.contains(scope_id));
+        }
+        self.references.retain(|r| !removed.contains(&r.scope_id));
+        self.unresolved.retain(|r| !range.contains_range(&r.range));
+        self.member_uses.retain(|r| !range.contains_range(&r.range));
+        self.type_references.retain(|r| !range.contains_range(&r.range));
+        self.undefined_labels.retain(|r| !range.contains_range(&r.range));
     }
 
     /// Link a procedure symbol to its scope
@@ -263,6 +464,61 @@ impl SymbolTable {
         });
     }
 
+    /// Record an identifier use that did not resolve to any symbol
+    pub fn add_unresolved_reference(&mut self, name: String, range: SourceRange) {
+        self.unresolved.push(UnresolvedReference { name, range });
+    }
+
+    /// Get all identifier uses that did not resolve to any symbol
+    pub fn unresolved_references(&self) -> &[UnresolvedReference] {
+        &self.unresolved
+    }
+
+    /// Record a member used through an object name that resolved to no symbol
+    pub fn add_member_use(&mut self, object: String, member: String, range: SourceRange) {
+        self.member_uses.push(MemberUse { object, member, range });
+    }
+
+    /// Members used through object names that resolved to no symbol
+    pub fn member_uses(&self) -> &[MemberUse] {
+        &self.member_uses
+    }
+
+    /// Record a type name used in an `As` clause, `New` expression, or `Implements`
+    pub fn add_type_reference(&mut self, name: String, range: SourceRange) {
+        self.type_references.push(UnresolvedReference { name, range });
+    }
+
+    /// Get all type names used in the document, as written (e.g., "ADODB.Recordset")
+    pub fn type_references(&self) -> &[UnresolvedReference] {
+        &self.type_references
+    }
+
+    /// Record an interface the class implements
+    pub fn add_interface(&mut self, name: String, range: SourceRange) {
+        self.interfaces.push(UnresolvedReference { name, range });
+    }
+
+    /// Get the interfaces named by `Implements` statements, as written
+    pub fn interfaces(&self) -> &[UnresolvedReference] {
+        &self.interfaces
+    }
+
+    /// Record a jump target that names no label of its procedure
+    pub fn add_undefined_label(&mut self, name: String, range: SourceRange) {
+        self.undefined_labels.push(UnresolvedReference { name, range });
+    }
+
+    /// Get the jump targets that name no label of their procedure
+    pub fn undefined_labels(&self) -> &[UnresolvedReference] {
+        &self.undefined_labels
+    }
+
+    /// Get all resolved references in the document
+    pub fn all_references(&self) -> &[SymbolReference] {
+        &self.references
+    }
+
     /// Get all references to a symbol
     pub fn get_references(&self, symbol_id: SymbolId) -> Vec<&SymbolReference> {
         self.references
@@ -271,10 +527,100 @@ impl SymbolTable {
             .collect()
     }
 
+    // ==========================================
+    // Incremental Updates
+    // ==========================================
+
+    /// Whether removed symbols and scopes outnumber the live ones, so that a
+    /// full rebuild would compact the table
+    pub fn is_sparse(&self) -> bool {
+        let removed = self.symbols.iter().filter(|s| s.is_none()).count()
+            + self.scopes.iter().filter(|s| s.is_none()).count();
+        removed > self.symbol_count() + self.scope_count()
+    }
+
+    /// Move the positions at or after `from`, e.g. to follow an edit of the
+    /// source starting there; everything before it stays in place
+    pub fn shift_positions(
+        &mut self,
+        from: SourcePosition,
+        map: impl Fn(SourcePosition) -> SourcePosition,
+    ) {
+        let map_range = |range: &mut SourceRange| {
+            if range.end >= from {
+                range.start = map(range.start);
+                range.end = map(range.end);
+            }
+        };
+
+        for symbol in self.symbols.iter_mut().flatten() {
+            map_range(&mut symbol.definition_range);
+            map_range(&mut symbol.name_range);
+            for parameter in &mut symbol.parameters {
+                map_range(&mut parameter.range);
+                map_range(&mut parameter.name_range);
+            }
+            for element in &mut symbol.control_array {
+                map_range(&mut element.name_range);
+            }
+        }
+        // The module scope always covers the whole file
+        for scope in self.scopes.iter_mut().flatten() {
+            if scope.kind != ScopeKind::Module {
+                map_range(&mut scope.range);
+            }
+        }
+        for reference in &mut self.references {
+            let mut current = Some(reference);
+            while let Some(reference) = current {
+                map_range(&mut reference.range);
+                current = reference.qualifying_reference.as_deref_mut();
+            }
+        }
+        let names = self.unresolved.iter_mut().chain(&mut self.type_references);
+        let names = names.chain(&mut self.interfaces).chain(&mut self.undefined_labels);
+        for reference in names {
+            map_range(&mut reference.range);
+        }
+        for member_use in &mut self.member_uses {
+            map_range(&mut member_use.range);
+        }
+
+        // Only the lines from the first moved one need indexing again
+        self.symbols_by_line.retain(|&line, _| line < from.line);
+        for symbol in self.symbols.iter().flatten() {
+            let start_line = symbol.name_range.start.line.max(from.line);
+            for line in start_line..=symbol.name_range.end.line {
+                self.symbols_by_line.entry(line).or_default().push(symbol.id);
+            }
+        }
+        self.scopes_by_line.retain(|&line, _| line < from.line);
+        for scope in self.scopes.iter().flatten() {
+            let start_line = scope.range.start.line.max(from.line);
+            let end_line = scope.range.end.line.min(scope.range.start.line + 10000);
+            for line in start_line..=end_line {
+                self.scopes_by_line.entry(line).or_default().push(scope.id);
+            }
+        }
+    }
+
     // ==========================================
     // Query Methods
     // ==========================================
 
+    /// Find the innermost `With` block whose body contains a position
+    pub fn with_block_at(&self, pos: SourcePosition) -> Option<&Scope> {
+        let mut current = Some(self.scope_at_position(pos));
+        while let Some(scope) = current.and_then(|id| self.get_scope(id)) {
+            // The `With` line itself belongs to the enclosing block (`With .Font`)
+            if scope.kind == ScopeKind::WithBlock && scope.range.start.line < pos.line {
+                return Some(scope);
+            }
+            current = scope.parent;
+        }
+        None
+    }
+
     /// Find the innermost scope containing a position
     pub fn scope_at_position(&self, pos: SourcePosition) -> ScopeId {
         // Get candidate scopes from spatial index
@@ -319,6 +665,19 @@ impl SymbolTable {
         None
     }
 
+    /// The symbol of an enclosing scope that a local variable, constant, or
+    /// parameter hides by having the same name
+    pub fn find_shadowed(&self, symbol: &Symbol) -> Option<&Symbol> {
+        if !matches!(
+            symbol.kind,
+            SymbolKind::LocalVariable | SymbolKind::LocalConstant | SymbolKind::Parameter
+        ) {
+            return None;
+        }
+        let parent = self.get_scope(symbol.scope_id)?.parent?;
+        self.lookup_symbol(&symbol.name, parent).filter(|s| s.id != symbol.id)
+    }
+
     /// Look up a symbol at a specific position
     pub fn lookup_at_position(&self, name: &str, pos: SourcePosition) -> Option<&Symbol> {
         let scope = self.scope_at_position(pos);
@@ -372,12 +731,12 @@ impl SymbolTable {
 
     /// Get all symbols (for document outline)
     pub fn all_symbols(&self) -> impl Iterator<Item = &Symbol> {
-        self.symbols.iter()
+        self.symbols.iter().flatten()
     }
 
     /// Get all symbols of a specific kind
     pub fn symbols_of_kind(&self, kind: SymbolKind) -> impl Iterator<Item = &Symbol> {
-        self.symbols.iter().filter(move |s| s.kind == kind)
+        self.all_symbols().filter(move |s| s.kind == kind)
     }
 
     /// Find definition of a symbol by name and position
@@ -431,24 +790,105 @@ impl SymbolTable {
         visible
     }
 
+    /// Whether a Sub handles an event of the module itself (`Form_Load`), one of
+    /// its controls (`cmdOK_Click`), or a module-level `WithEvents` variable
+    pub fn is_event_handler(&self, symbol: &Symbol) -> bool {
+        // Standard modules cannot receive events
+        if symbol.kind != SymbolKind::Sub || self.uri.path().to_lowercase().ends_with(".bas") {
+            return false;
+        }
+        let Some((object, _)) = symbol.name.rsplit_once('_') else {
+            return false;
+        };
+        EVENT_OBJECTS.contains(&object.to_lowercase().as_str())
+            || self
+                .lookup_symbol(object, self.module_scope)
+                .is_some_and(|s| matches!(s.kind, SymbolKind::FormControl | SymbolKind::Variable))
+    }
+
+    /// Whether the module is the code behind a designer (form, user control, ...)
+    pub fn is_designer_module(&self) -> bool {
+        let path = self.uri.path().to_lowercase();
+        [".frm", ".ctl", ".pag", ".dob"].iter().any(|ext| path.ends_with(ext))
+    }
+
+    /// The control event a Sub named `Control_Event` would handle in this module.
+    ///
+    /// Names of module events (`Form_Load`), `WithEvents` variables, and
+    /// implemented interfaces are not control events. A name whose control is
+    /// missing is only taken for a handler when its event is one controls raise.
+    pub fn control_event_binding(&self, name: &str) -> Option<EventBinding> {
+        if !self.is_designer_module() {
+            return None;
+        }
+        let (object, event) = name.rsplit_once('_')?;
+        if object.is_empty() || event.is_empty() {
+            return None;
+        }
+        if EVENT_OBJECTS.contains(&object.to_lowercase().as_str())
+            || self.interfaces.iter().any(|i| i.name.eq_ignore_ascii_case(object))
+        {
+            return None;
+        }
+        let control = match self.lookup_symbol(object, self.module_scope) {
+            Some(symbol) if symbol.kind == SymbolKind::FormControl => Some(symbol.id),
+            Some(_) => return None,
+            None if crate::controls::is_control_event(event) => None,
+            None => return None,
+        };
+        Some(EventBinding {
+            control,
+            control_name: object.to_string(),
+            event: event.to_string(),
+        })
+    }
+
+    /// The Sub handling an event of a control, if the module has one
+    pub fn event_handler(&self, control: SymbolId, event: &str) -> Option<&Symbol> {
+        self.all_symbols().find(|s| {
+            s.handles
+                .as_ref()
+                .is_some_and(|b| b.control == Some(control) && b.event.eq_ignore_ascii_case(event))
+        })
+    }
+
     /// Get procedures (for document outline)
     pub fn procedures(&self) -> impl Iterator<Item = &Symbol> {
-        self.symbols.iter().filter(|s| s.kind.is_procedure())
+        self.all_symbols().filter(|s| s.kind.is_procedure())
     }
 
     /// Get all scopes
     pub fn all_scopes(&self) -> impl Iterator<Item = &super::scope::Scope> {
-        self.scopes.iter()
+        self.scopes.iter().flatten()
     }
 
     /// Get the count of symbols
     pub fn symbol_count(&self) -> usize {
-        self.symbols.len()
+        self.all_symbols().count()
+    }
+
+    /// Number of lines in the source the table was built from
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    pub fn set_line_count(&mut self, line_count: usize) {
+        self.line_count = line_count;
+    }
+
+    /// Number of controls on the form, counting each element of a control array
+    pub fn control_count(&self) -> usize {
+        self.all_symbols()
+            .filter(|s| s.kind == SymbolKind::FormControl)
+            // The form itself has no container in its path
+            .filter(|s| s.control_path.as_ref().is_some_and(|path| path.contains('.')))
+            .map(|s| s.control_array.len().max(1))
+            .sum()
     }
 
     /// Get the count of scopes
     pub fn scope_count(&self) -> usize {
-        self.scopes.len()
+        self.all_scopes().count()
     }
 
     /// Get the count of references
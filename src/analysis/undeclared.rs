@@ -0,0 +1,175 @@
+//! Undeclared Variable Detection
+//!
+//! Under `Option Explicit`, flags identifiers that resolve to no declaration
+//! in the document, the VB runtime, or the rest of the project.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Tree};
+
+use super::symbol_table::SymbolTable;
+
+/// VB runtime functions, statements, and global objects that need no declaration
+pub const DEFAULT_ALLOWED_IDENTIFIERS: &[&str] = &[
+    // Conversion
+    "CBool", "CByte", "CCur", "CDate", "CDbl", "CDec", "CInt", "CLng", "CSng", "CStr", "CVar",
+    "CVErr", "CVDate", "Fix", "Int", "Hex", "Oct", "Str", "Val",
+    // Strings
+    "Asc", "AscB", "AscW", "Chr", "ChrB", "ChrW", "Filter", "Format", "FormatCurrency",
+    "FormatDateTime", "FormatNumber", "FormatPercent", "InStr", "InStrB", "InStrRev", "Join",
+    "LCase", "Left", "LeftB", "Len", "LenB", "LTrim", "Mid", "MidB", "Replace", "Right", "RightB",
+    "RTrim", "Space", "Split", "StrComp", "StrConv", "String", "StrReverse", "Trim", "UCase",
+    // Math
+    "Abs", "Atn", "Cos", "Exp", "Log", "Randomize", "Rnd", "Round", "Sgn", "Sin", "Sqr", "Tan",
+    // Date and time
+    "Date", "DateAdd", "DateDiff", "DatePart", "DateSerial", "DateValue", "Day", "Hour", "Minute",
+    "Month", "MonthName", "Now", "Second", "Time", "Timer", "TimeSerial", "TimeValue", "Weekday",
+    "WeekdayName", "Year",
+    // Financial
+    "DDB", "FV", "IPmt", "IRR", "MIRR", "NPer", "NPV", "Pmt", "PPmt", "PV", "Rate", "SLN", "SYD",
+    // Inspection
+    "IsArray", "IsDate", "IsEmpty", "IsError", "IsMissing", "IsNull", "IsNumeric", "IsObject",
+    "TypeName", "VarType", "LBound", "UBound",
+    // Control flow and interaction
+    "Array", "CallByName", "Choose", "Command", "DoEvents", "Environ", "IIf", "InputBox", "MsgBox",
+    "Partition", "Shell", "Switch", "AppActivate", "Beep", "SendKeys", "QBColor", "RGB",
+    // Objects and resources
+    "CreateObject", "GetObject", "LoadPicture", "SavePicture", "LoadResString", "LoadResPicture",
+    "LoadResData", "ObjPtr", "StrPtr", "VarPtr",
+    // File system
+    "CurDir", "Dir", "EOF", "FileAttr", "FileDateTime", "FileLen", "FreeFile", "GetAttr", "Input",
+    "InputB", "Loc", "LOF", "Seek", "Spc", "Tab",
+    // Registry
+    "GetSetting", "GetAllSettings", "SaveSetting", "DeleteSetting",
+    // Errors
+    "Error",
+    // Global objects
+    "App", "Clipboard", "Debug", "Err", "Forms", "Global", "Licenses", "Printer", "Printers",
+    "Screen", "Me", "UserControl", "Extender", "Ambient", "PropertyPage", "UserDocument",
+];
+
+/// Diagnostic code for a use of an undeclared variable
+pub const UNDECLARED_VARIABLE: &str = "undeclared-variable";
+
+/// Find identifiers that are not declared anywhere, if the module uses `Option Explicit`.
+///
+/// `is_known` is consulted for names not declared in the document itself
+/// (runtime functions, controls, other modules).
+pub fn find_undeclared_variables(
+    table: &SymbolTable,
+    tree: &Tree,
+    source: &str,
+    is_known: impl Fn(&str) -> bool,
+) -> Vec<Diagnostic> {
+    if !has_option_explicit(&tree.root_node(), source) {
+        return Vec::new();
+    }
+
+    let mut diagnostics = Vec::new();
+
+    for unresolved in table.unresolved_references() {
+        // Intrinsic constants (vbCrLf, vbOKOnly, ...)
+        if is_intrinsic_constant(&unresolved.name) || is_known(&unresolved.name) {
+            continue;
+        }
+
+        diagnostics.push(Diagnostic {
+            range: unresolved.range.to_lsp(),
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: Some(NumberOrString::String(UNDECLARED_VARIABLE.to_string())),
+            message: format!("Variable not defined: '{}'", unresolved.name),
+            source: Some("vb6-lsp".to_string()),
+            ..Default::default()
+        });
+    }
+
+    diagnostics
+}
+
+/// Check if the module declares `Option Explicit`
+pub fn has_option_explicit(root: &Node, source: &str) -> bool {
+    let mut cursor = root.walk();
+    let found = root.named_children(&mut cursor).any(|child| {
+        child.kind() == "option_statement"
+            && child
+                .utf8_text(source.as_bytes())
+                .is_ok_and(|text| text.to_lowercase().contains("explicit"))
+    });
+    found
+}
+
+/// Check if a name looks like a VB intrinsic constant (e.g., vbCrLf, vbYesNo)
+fn is_intrinsic_constant(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!((chars.next(), chars.next(), chars.next()), (Some('v'), Some('b'), Some(c)) if c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use crate::analysis::run_pass;
+
+    fn undeclared(source: &str) -> Vec<String> {
+        let allowed: HashSet<String> = DEFAULT_ALLOWED_IDENTIFIERS
+            .iter()
+            .map(|n| n.to_lowercase())
+            .collect();
+
+        run_pass(source, |table, tree| {
+            find_undeclared_variables(table, tree, source, |name| {
+                allowed.contains(&name.to_lowercase())
+            })
+        })
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_undeclared_variable() {
+        let source = "Option Explicit\n\
+Private mCount As Long\n\
+\n\
+Sub Main(ByVal n As Long)\n\
+    Dim total As Long\n\
+    total = n + mCount + missing\n\
+    MsgBox Len(\"abc\") & vbCrLf, vbOKOnly\n\
+    Helper total\n\
+    Undefined total\n\
+End Sub\n\
+\n\
+Sub Helper(ByVal v As Long)\n\
+    total = v\n\
+End Sub\n";
+
+        assert_eq!(
+            undeclared(source),
+            vec![
+                "Variable not defined: 'missing'",
+                "Variable not defined: 'Undefined'",
+                "Variable not defined: 'total'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_requires_option_explicit() {
+        let source = "Sub Main()\n    x = y\nEnd Sub\n";
+        assert!(undeclared(source).is_empty());
+    }
+
+    #[test]
+    fn test_ignores_labels_types_and_named_arguments() {
+        let source = "Option Explicit\n\
+Sub Main()\n\
+    Dim c As Collection\n\
+    Dim f As Form\n\
+    On Error GoTo Handler\n\
+    MsgBox Prompt:=\"Hi\"\n\
+    Exit Sub\n\
+Handler:\n\
+    Resume Next\n\
+End Sub\n";
+        assert!(undeclared(source).is_empty());
+    }
+}
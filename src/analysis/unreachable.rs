@@ -0,0 +1,129 @@
+//! Unreachable Code Detection
+//!
+//! Flags statements that follow an unconditional `Exit`, `End`, or `GoTo`
+//! within the same block.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use tree_sitter::{Node, Tree};
+
+/// Diagnostic code for a statement that can never execute
+pub const UNREACHABLE_CODE: &str = "unreachable-code";
+
+/// Statement kinds that unconditionally leave the current block
+const JUMP_STATEMENTS: &[&str] = &["exit_statement", "end_statement", "goto_statement"];
+
+/// Find statements that can never execute
+pub fn find_unreachable_code(tree: &Tree, source: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut diagnostics = Vec::new();
+    visit(&tree.root_node(), &lines, &mut diagnostics);
+    diagnostics
+}
+
+/// Recursively check every block in the tree
+fn visit(node: &Node, lines: &[&str], diagnostics: &mut Vec<Diagnostic>) {
+    if node.kind() == "block" {
+        check_block(node, lines, diagnostics);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        visit(&child, lines, diagnostics);
+    }
+}
+
+/// Check the statements of a single block.
+///
+/// Nested blocks (If branches, Case clauses, loops) are checked separately,
+/// so a jump inside them never affects the statements of the enclosing block.
+fn check_block(block: &Node, lines: &[&str], diagnostics: &mut Vec<Diagnostic>) {
+    let mut unreachable = false;
+
+    let mut cursor = block.walk();
+    for statement in block.named_children(&mut cursor) {
+        match statement.kind() {
+            "comment" => continue,
+            // A label can be jumped to, and a #If branch may hold the jump's alternative
+            "label" | "preproc_if_statement" => {
+                unreachable = false;
+                continue;
+            }
+            _ => {}
+        }
+
+        if unreachable {
+            diagnostics.push(Diagnostic {
+                range: statement_range(&statement, lines),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(UNREACHABLE_CODE.to_string())),
+                message: "Unreachable code".to_string(),
+                source: Some("vb6-lsp".to_string()),
+                ..Default::default()
+            });
+        } else if JUMP_STATEMENTS.contains(&statement.kind()) {
+            unreachable = true;
+        }
+    }
+}
+
+/// Range of a statement, excluding its trailing line terminator
+fn statement_range(node: &Node, lines: &[&str]) -> Range {
+    let start = node.start_position();
+    let mut end = node.end_position();
+
+    // The terminator newline ends the node at column 0 of the next line
+    if end.column == 0 && end.row > start.row {
+        end.row -= 1;
+        end.column = lines.get(end.row).map(|l| l.trim_end_matches('\r').len()).unwrap_or(0);
+    }
+
+    Range {
+        start: Position {
+            line: start.row as u32,
+            character: start.column as u32,
+        },
+        end: Position {
+            line: end.row as u32,
+            character: end.column as u32,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn unreachable_lines(source: &str) -> Vec<u32> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        find_unreachable_code(&tree, source)
+            .iter()
+            .map(|d| d.range.start.line)
+            .collect()
+    }
+
+    #[test]
+    fn test_statements_after_exit() {
+        let source = "Sub Main()\n    x = 1\n    Exit Sub\n    x = 2\n    ' comment\n    x = 3\nEnd Sub\n";
+        assert_eq!(unreachable_lines(source), vec![3, 5]);
+    }
+
+    #[test]
+    fn test_exit_inside_nested_if() {
+        let source = "Sub Main()\n    If x Then\n        Exit Sub\n    End If\n    x = 2\nEnd Sub\n";
+        assert!(unreachable_lines(source).is_empty());
+    }
+
+    #[test]
+    fn test_label_resets_reachability() {
+        let source = "Sub Main()\n    On Error GoTo Handler\n    x = 1\n    Exit Sub\nHandler:\n    x = 2\nEnd Sub\n";
+        assert!(unreachable_lines(source).is_empty());
+    }
+
+    #[test]
+    fn test_goto_and_case_blocks() {
+        let source = "Sub Main()\n    Select Case x\n        Case 1\n            GoTo Done\n            y = 1\n        Case 2\n            y = 2\n    End Select\nDone:\nEnd Sub\n";
+        assert_eq!(unreachable_lines(source), vec![4]);
+    }
+}
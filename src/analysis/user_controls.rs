@@ -0,0 +1,96 @@
+//! Register the UserControls (.ctl files) of the workspace as controls
+//!
+//! A UserControl's Public properties, methods and events are the members of
+//! its instances on forms, so they are added to the control lookup that
+//! completion and hover use for the built-in controls.
+
+use crate::controls::UserControlMember;
+
+use super::symbol::{SymbolKind, Visibility};
+use super::symbol_table::SymbolTable;
+
+/// The `VB_Name` of the UserControl a symbol table was built from: the name of
+/// its root `Begin` block, or the file name when it has none
+pub fn user_control_name(table: &SymbolTable) -> Option<String> {
+    let path = table.uri.path();
+    if !path.to_lowercase().ends_with(".ctl") {
+        return None;
+    }
+
+    let root = table
+        .symbols_of_kind(SymbolKind::FormControl)
+        .find(|s| s.control_path.as_deref().is_some_and(|path| !path.contains('.')));
+    match root {
+        Some(root) => Some(root.name.clone()),
+        None => {
+            let file_name = path.rsplit('/').next()?;
+            Some(file_name[..file_name.len() - ".ctl".len()].to_string())
+        }
+    }
+}
+
+/// The Public members of a UserControl
+pub fn user_control_members(table: &SymbolTable) -> Vec<UserControlMember> {
+    // Not `module_symbols`, whose scope keeps only one of a Get/Let pair
+    let public = table
+        .all_symbols()
+        .filter(|s| s.scope_id == table.module_scope && s.visibility == Visibility::Public);
+
+    let mut members: Vec<UserControlMember> = Vec::new();
+    for symbol in public {
+        let member = match symbol.kind {
+            SymbolKind::PropertyGet | SymbolKind::PropertyLet | SymbolKind::PropertySet => {
+                let read_only = symbol.kind == SymbolKind::PropertyGet;
+                // A Let or Set makes the property writable; the Get gives its type
+                let type_name = match symbol.kind {
+                    SymbolKind::PropertyGet => symbol.type_info.as_ref().map(|t| t.name.clone()),
+                    _ => None,
+                };
+                let existing = members.iter_mut().find_map(|m| match m {
+                    UserControlMember::Property { name, type_name, read_only }
+                        if name.eq_ignore_ascii_case(&symbol.name) =>
+                    {
+                        Some((type_name, read_only))
+                    }
+                    _ => None,
+                });
+                if let Some((existing_type, existing_read_only)) = existing {
+                    *existing_read_only &= read_only;
+                    if type_name.is_some() {
+                        *existing_type = type_name;
+                    }
+                    continue;
+                }
+                UserControlMember::Property { name: symbol.name.clone(), type_name, read_only }
+            }
+            SymbolKind::Sub | SymbolKind::Function => UserControlMember::Method {
+                name: symbol.name.clone(),
+                signature: format!("{}({})", symbol.name, symbol.format_parameters()),
+                return_type: match symbol.kind {
+                    SymbolKind::Function => Some(
+                        symbol
+                            .type_info
+                            .as_ref()
+                            .map(|t| t.display())
+                            .unwrap_or_else(|| "Variant".to_string()),
+                    ),
+                    _ => None,
+                },
+            },
+            SymbolKind::Event => UserControlMember::Event {
+                name: symbol.name.clone(),
+                parameters: symbol.format_parameters(),
+            },
+            _ => continue,
+        };
+        members.push(member);
+    }
+    members
+}
+
+/// Register the control of a .ctl file's symbol table; other files are ignored
+pub fn register_user_control(table: &SymbolTable) {
+    if let Some(name) = user_control_name(table) {
+        crate::controls::register_user_control(&name, user_control_members(table));
+    }
+}
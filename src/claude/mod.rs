@@ -2,22 +2,85 @@
 //!
 //! Provides AI-powered code assistance using Claude Sonnet.
 
+use std::collections::VecDeque;
+use std::sync::RwLock;
+
+use futures::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
 
+/// Error from a streamed response (sendable, so streams can be consumed in spawned tasks)
+pub type StreamError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Model used unless configured otherwise
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+
+/// Response token limit used unless configured otherwise
+pub const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+/// Largest response token limit the API accepts
+pub const MAX_TOKENS_LIMIT: u32 = 64_000;
+
 /// Claude API client
 pub struct ClaudeClient {
     api_key: String,
     client: reqwest::Client,
-    model: String,
+    model: RwLock<String>,
+    max_tokens: RwLock<u32>,
 }
 
 impl ClaudeClient {
     pub fn new(api_key: String) -> Self {
-        Self {
+        Self::with_config(api_key, DEFAULT_MODEL.to_string(), DEFAULT_MAX_TOKENS)
+    }
+
+    /// Create a client for a specific model and response token limit
+    pub fn with_config(api_key: String, model: String, max_tokens: u32) -> Self {
+        let client = Self {
             api_key,
             client: reqwest::Client::new(),
-            model: "claude-sonnet-4-20250514".to_string(),
+            model: RwLock::new(DEFAULT_MODEL.to_string()),
+            max_tokens: RwLock::new(DEFAULT_MAX_TOKENS),
+        };
+        client.set_model(model);
+        client.set_max_tokens(u64::from(max_tokens));
+        client
+    }
+
+    /// Use a different model (an empty name keeps the current one)
+    pub fn set_model(&self, model: String) {
+        let model = model.trim();
+        if model.is_empty() {
+            tracing::warn!("Ignoring empty Claude model name");
+            return;
         }
+        *self.model.write().unwrap() = model.to_string();
+    }
+
+    /// Set the response token limit, falling back to the default when out of range
+    pub fn set_max_tokens(&self, max_tokens: u64) {
+        let max_tokens = match u32::try_from(max_tokens) {
+            Ok(value) if (1..=MAX_TOKENS_LIMIT).contains(&value) => value,
+            _ => {
+                tracing::warn!(
+                    "Claude max tokens {} is outside 1..={}, using {}",
+                    max_tokens,
+                    MAX_TOKENS_LIMIT,
+                    DEFAULT_MAX_TOKENS
+                );
+                DEFAULT_MAX_TOKENS
+            }
+        };
+        *self.max_tokens.write().unwrap() = max_tokens;
+    }
+
+    /// The configured model
+    pub fn model(&self) -> String {
+        self.model.read().unwrap().clone()
+    }
+
+    /// The configured response token limit
+    pub fn max_tokens(&self) -> u32 {
+        *self.max_tokens.read().unwrap()
     }
 
     /// Get code completion suggestions from Claude
@@ -71,53 +134,115 @@ impl ClaudeClient {
         self.send_message(&prompt).await
     }
 
-    /// Generate documentation using Claude
-    pub async fn generate_documentation(
+    /// Generate documentation using Claude, streamed as it is written
+    pub fn generate_documentation(
         &self,
         code: &str,
-    ) -> Result<String, Box<dyn std::error::Error>> {
+    ) -> impl Stream<Item = Result<String, StreamError>> + Send + 'static {
         let prompt = format!(
             "Generate concise documentation comments for this Visual Basic 6 code:\n\n{}",
             code
         );
 
-        self.send_message(&prompt).await
+        self.send_message_streaming(&prompt)
     }
 
-    /// Suggest migration to VB.NET/C# using Claude
-    pub async fn suggest_migration(
+    /// Suggest migration to VB.NET/C# using Claude, streamed as it is written
+    pub fn suggest_migration(
         &self,
         code: &str,
         target_language: &str,
-    ) -> Result<String, Box<dyn std::error::Error>> {
+    ) -> impl Stream<Item = Result<String, StreamError>> + Send + 'static {
         let prompt = format!(
             "Convert this Visual Basic 6 code to {}. Explain any important changes:\n\n{}",
             target_language, code
         );
 
-        self.send_message(&prompt).await
+        self.send_message_streaming(&prompt)
     }
 
-    /// Send a message to Claude API
-    async fn send_message(&self, prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Build a Messages API request
+    fn request(&self, prompt: &str, stream: bool) -> reqwest::RequestBuilder {
         let request = ClaudeRequest {
-            model: self.model.clone(),
-            max_tokens: 1024,
+            model: self.model(),
+            max_tokens: self.max_tokens(),
             messages: vec![Message {
                 role: "user".to_string(),
                 content: prompt.to_string(),
             }],
+            stream,
         };
 
-        let response = self
-            .client
+        self.client
             .post("https://api.anthropic.com/v1/messages")
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("content-type", "application/json")
             .json(&request)
-            .send()
-            .await?;
+    }
+
+    /// Send a message to Claude API, yielding text chunks as they arrive.
+    ///
+    /// Parses the `content_block_delta` server-sent events of a streamed response.
+    pub fn send_message_streaming(
+        &self,
+        prompt: &str,
+    ) -> impl Stream<Item = Result<String, StreamError>> + Send + 'static {
+        let request = self.request(prompt, true);
+
+        let start = stream::once(async move {
+            let mut state = StreamState {
+                response: None,
+                buffer: Vec::new(),
+                pending: VecDeque::new(),
+            };
+            match request.send().await {
+                Ok(response) if response.status().is_success() => state.response = Some(response),
+                Ok(response) => {
+                    let error_text = response.text().await.unwrap_or_default();
+                    let error = format!("Claude API error: {}", error_text);
+                    state.pending.push_back(Err(error.into()));
+                }
+                Err(error) => state.pending.push_back(Err(error.into())),
+            }
+            state
+        });
+
+        stream::StreamExt::flat_map(start, |state| {
+            stream::unfold(state, |mut state| async move {
+                loop {
+                    if let Some(item) = state.pending.pop_front() {
+                        return Some((item, state));
+                    }
+                    let response = state.response.as_mut()?;
+                    match response.chunk().await {
+                        Ok(Some(bytes)) => {
+                            state.buffer.extend(bytes.iter().filter(|&&b| b != b'\r'));
+                            // Events end with a blank line; the rest may be incomplete
+                            while let Some(end) = state.buffer.windows(2).position(|w| w == b"\n\n") {
+                                let event: Vec<u8> = state.buffer.drain(..end + 2).collect();
+                                state.pending.extend(parse_event(&String::from_utf8_lossy(&event)));
+                            }
+                        }
+                        Ok(None) => {
+                            state.response = None;
+                            let event = String::from_utf8_lossy(&state.buffer).into_owned();
+                            state.pending.extend(parse_event(&event));
+                            state.buffer.clear();
+                        }
+                        Err(error) => {
+                            state.response = None;
+                            state.pending.push_back(Err(error.into()));
+                        }
+                    }
+                }
+            })
+        })
+    }
+
+    /// Send a message to Claude API
+    async fn send_message(&self, prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let response = self.request(prompt, false).send().await?;
 
         if !response.status().is_success() {
             let error_text = response.text().await?;
@@ -140,6 +265,8 @@ struct ClaudeRequest {
     model: String,
     max_tokens: u32,
     messages: Vec<Message>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -158,6 +285,43 @@ struct ContentBlock {
     text: String,
 }
 
+/// Progress through a streamed response
+struct StreamState {
+    response: Option<reqwest::Response>,
+    /// Received bytes not yet split into events
+    buffer: Vec<u8>,
+    /// Parsed items not yet yielded
+    pending: VecDeque<Result<String, StreamError>>,
+}
+
+/// Parse one server-sent event; `Some(Ok(text))` for text deltas, `Some(Err(_))` for errors
+fn parse_event(event: &str) -> Option<Result<String, StreamError>> {
+    let data: String = event
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim_start)
+        .collect();
+    let value: serde_json::Value = serde_json::from_str(&data).ok()?;
+
+    match value.get("type")?.as_str()? {
+        "content_block_delta" => {
+            let delta = value.get("delta")?;
+            if delta.get("type")?.as_str()? != "text_delta" {
+                return None;
+            }
+            Some(Ok(delta.get("text")?.as_str()?.to_string()))
+        }
+        "error" => {
+            let message = value
+                .pointer("/error/message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("unknown error");
+            Some(Err(format!("Claude API error: {}", message).into()))
+        }
+        _ => None,
+    }
+}
+
 /// Utility to get code context around a position
 pub fn get_code_context(full_text: &str, line: usize, character: usize, context_lines: usize) -> String {
     let lines: Vec<&str> = full_text.lines().collect();
@@ -178,3 +342,39 @@ pub fn get_code_context(full_text: &str, line: usize, character: usize, context_
 
     context
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_delta_event() {
+        let event = "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Dim x\"}}\n\n";
+        assert_eq!(parse_event(event).unwrap().unwrap(), "Dim x");
+
+        let ping = "event: ping\ndata: {\"type\": \"ping\"}\n\n";
+        assert!(parse_event(ping).is_none());
+    }
+
+    #[test]
+    fn test_parse_error_event() {
+        let event = "event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n";
+        let error = parse_event(event).unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "Claude API error: Overloaded");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let client = ClaudeClient::with_config("key".to_string(), "claude-haiku".to_string(), 4096);
+        assert_eq!(client.model(), "claude-haiku");
+        assert_eq!(client.max_tokens(), 4096);
+
+        client.set_max_tokens(0);
+        assert_eq!(client.max_tokens(), DEFAULT_MAX_TOKENS);
+        client.set_max_tokens(u64::from(MAX_TOKENS_LIMIT) + 1);
+        assert_eq!(client.max_tokens(), DEFAULT_MAX_TOKENS);
+
+        client.set_model("  ".to_string());
+        assert_eq!(client.model(), "claude-haiku");
+    }
+}
@@ -5,6 +5,8 @@
 
 use std::fmt;
 
+use once_cell::sync::Lazy;
+
 /// VB6 system color constants
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SystemColor {
@@ -154,6 +156,38 @@ impl SystemColor {
         }
     }
 
+    /// The color in the Windows Classic scheme, for previews; the actual
+    /// color depends on the user's settings
+    pub fn default_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            SystemColor::ScrollBar => (212, 208, 200),
+            SystemColor::Background => (58, 110, 165),
+            SystemColor::ActiveCaption => (10, 36, 106),
+            SystemColor::InactiveCaption => (128, 128, 128),
+            SystemColor::Menu => (212, 208, 200),
+            SystemColor::Window => (255, 255, 255),
+            SystemColor::WindowFrame => (0, 0, 0),
+            SystemColor::MenuText => (0, 0, 0),
+            SystemColor::WindowText => (0, 0, 0),
+            SystemColor::CaptionText => (255, 255, 255),
+            SystemColor::ActiveBorder => (212, 208, 200),
+            SystemColor::InactiveBorder => (212, 208, 200),
+            SystemColor::AppWorkspace => (128, 128, 128),
+            SystemColor::Highlight => (10, 36, 106),
+            SystemColor::HighlightText => (255, 255, 255),
+            SystemColor::ButtonFace => (212, 208, 200),
+            SystemColor::ButtonShadow => (128, 128, 128),
+            SystemColor::GrayText => (128, 128, 128),
+            SystemColor::ButtonText => (0, 0, 0),
+            SystemColor::InactiveCaptionText => (212, 208, 200),
+            SystemColor::ButtonHighlight => (255, 255, 255),
+            SystemColor::DarkShadow3D => (64, 64, 64),
+            SystemColor::Light3D => (212, 208, 200),
+            SystemColor::InfoText => (0, 0, 0),
+            SystemColor::InfoBackground => (255, 255, 225),
+        }
+    }
+
     /// Parse a system color from its numeric value
     pub fn from_value(value: u32) -> Option<Self> {
         match value {
@@ -257,6 +291,33 @@ impl VB6Color {
         Self::from_u32(value)
     }
 
+    /// Parse a color literal as written in a form file, explaining why it is malformed.
+    ///
+    /// Accepts `&HBBGGRR&`, `&H800000xx&` (system colors), and plain decimal values.
+    pub fn parse_literal(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+
+        let Some(digits) = s.strip_prefix("&H").or_else(|| s.strip_prefix("&h")) else {
+            return match s.parse::<u32>() {
+                Ok(value) => Self::from_u32(value).ok_or_else(|| format!("{} is not a known system color", value)),
+                Err(_) => Err(format!("'{}' is not a color literal (expected &HBBGGRR&)", s)),
+            };
+        };
+
+        let Some(digits) = digits.strip_suffix('&') else {
+            return Err(format!("Color literal '{}' is missing its trailing '&'", s));
+        };
+        if let Some(bad) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(format!("'{}' is not a hex digit in color literal '{}'", bad, s));
+        }
+        if digits.is_empty() || digits.len() > 8 {
+            return Err(format!("Color literal '{}' must have 1 to 8 hex digits", s));
+        }
+
+        let value = u32::from_str_radix(digits, 16).map_err(|e| e.to_string())?;
+        Self::from_u32(value).ok_or_else(|| format!("{} is not a known system color", s))
+    }
+
     /// Create from a u32 value
     pub fn from_u32(value: u32) -> Option<Self> {
         // Check if it's a system color (high bit set)
@@ -291,11 +352,60 @@ impl VB6Color {
         }
     }
 
+    /// Markdown breakdown of the color for hover
+    pub fn describe(&self) -> String {
+        match self {
+            VB6Color::Rgb { red, green, blue } => format!(
+                "**RGB({}, {}, {})**\n\nRed: {}, Green: {}, Blue: {} (`#{:02X}{:02X}{:02X}`)\n\n`{}`",
+                red, green, blue, red, green, blue, red, green, blue,
+                self.to_vb6_string()
+            ),
+            VB6Color::System(sys) => format!(
+                "**{}** (system color)\n\n{}\n\n`{}`",
+                sys.vb6_name(),
+                sys.description(),
+                self.to_vb6_string()
+            ),
+        }
+    }
+
     /// Format as VB6 hex string
     pub fn to_vb6_string(&self) -> String {
         format!("&H{:08X}&", self.to_u32())
     }
 
+    /// Red, green, and blue components; system colors use their default
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            VB6Color::Rgb { red, green, blue } => (*red, *green, *blue),
+            VB6Color::System(sys) => sys.default_rgb(),
+        }
+    }
+
+    /// The color `QBColor(number)` returns, for numbers 0 to 15
+    pub fn qb_color(number: u32) -> Option<Self> {
+        const PALETTE: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (0, 0, 128),
+            (0, 128, 0),
+            (0, 128, 128),
+            (128, 0, 0),
+            (128, 0, 128),
+            (128, 128, 0),
+            (192, 192, 192),
+            (128, 128, 128),
+            (0, 0, 255),
+            (0, 255, 0),
+            (0, 255, 255),
+            (255, 0, 0),
+            (255, 0, 255),
+            (255, 255, 0),
+            (255, 255, 255),
+        ];
+        let (red, green, blue) = *PALETTE.get(number as usize)?;
+        Some(VB6Color::Rgb { red, green, blue })
+    }
+
     /// Check if this is a system color
     pub fn is_system_color(&self) -> bool {
         matches!(self, VB6Color::System(_))
@@ -361,6 +471,35 @@ pub static VB6_COLOR_CONSTANTS: &[(&str, u32)] = &[
     ("vbWhite", 0x00FFFFFF),
 ];
 
+/// A named color constant of the VB runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorConstant {
+    /// Constant name (e.g., "vbRed", "vbButtonFace")
+    pub name: &'static str,
+    pub color: VB6Color,
+}
+
+static COLOR_CONSTANTS: Lazy<Vec<ColorConstant>> = Lazy::new(|| {
+    let rgb = VB6_COLOR_CONSTANTS.iter().filter_map(|&(name, value)| {
+        Some(ColorConstant { name, color: VB6Color::from_u32(value)? })
+    });
+    let system = SystemColor::all().iter().map(|&sys| ColorConstant {
+        name: sys.vb6_name(),
+        color: VB6Color::System(sys),
+    });
+    rgb.chain(system).collect()
+});
+
+/// The `vbColor` (RGB) and `vbSystemColor` constants of the VB runtime
+pub fn color_constants() -> &'static [ColorConstant] {
+    &COLOR_CONSTANTS
+}
+
+/// Look up a color constant by name (case-insensitive)
+pub fn get_color_constant(name: &str) -> Option<&'static ColorConstant> {
+    color_constants().iter().find(|c| c.name.eq_ignore_ascii_case(name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -396,6 +535,42 @@ mod tests {
         assert!(sys.description().contains("Button"));
     }
 
+    #[test]
+    fn test_parse_literal() {
+        assert_eq!(VB6Color::parse_literal("&H000000FF&"), Ok(VB6Color::red()));
+        assert_eq!(VB6Color::parse_literal("255"), Ok(VB6Color::red()));
+        assert_eq!(
+            VB6Color::parse_literal("&H8000000F&"),
+            Ok(VB6Color::System(SystemColor::ButtonFace))
+        );
+
+        assert!(VB6Color::parse_literal("&H8000000F").unwrap_err().contains("trailing '&'"));
+        assert!(VB6Color::parse_literal("&H00GG00&").unwrap_err().contains("'G' is not a hex digit"));
+        assert!(VB6Color::parse_literal("&H80000030&").unwrap_err().contains("system color"));
+    }
+
+    #[test]
+    fn test_describe() {
+        let rgb = VB6Color::parse("&H00FF8000&").unwrap().describe();
+        assert!(rgb.contains("RGB(0, 128, 255)"));
+        assert!(rgb.contains("`#0080FF`"));
+
+        let system = VB6Color::System(SystemColor::ButtonFace).describe();
+        assert!(system.contains("vbButtonFace"));
+        assert!(system.contains("Button face"));
+    }
+
+    #[test]
+    fn test_color_constants() {
+        assert_eq!(color_constants().len(), 33);
+        assert_eq!(get_color_constant("VBRED").unwrap().color, VB6Color::red());
+        assert_eq!(
+            get_color_constant("vbButtonFace").unwrap().color,
+            VB6Color::System(SystemColor::ButtonFace)
+        );
+        assert!(get_color_constant("vbCrLf").is_none());
+    }
+
     #[test]
     fn test_color_round_trip() {
         let original = "&H8000000F&";
@@ -9,6 +9,8 @@ use std::fs::File;
 use std::io::{self, Read, Seek, SeekFrom};
 use std::path::Path;
 
+use crate::utils::decode_vb6_bytes;
+
 /// Represents a parsed FRX resource file
 #[derive(Debug, Clone)]
 pub struct FrxFile {
@@ -148,8 +150,7 @@ impl FrxFile {
         file.read_exact(&mut data)?;
 
         // Convert from Windows-1252 to UTF-8
-        // For simplicity, we'll use lossy conversion
-        let string = String::from_utf8_lossy(&data).into_owned();
+        let string = decode_vb6_bytes(&data);
 
         let total_size = 4 + string_length;
 
@@ -192,7 +193,7 @@ impl FrxFile {
             file.read_exact(&mut data)?;
             total_bytes += item_length;
 
-            let item_text = String::from_utf8_lossy(&data).into_owned();
+            let item_text = decode_vb6_bytes(&data);
             items.push(item_text);
         }
 
@@ -8,11 +8,20 @@
 mod properties;
 mod colors;
 pub mod frx;
+mod ocx;
+mod user_controls;
 
-pub use colors::{SystemColor, VB6Color};
+pub use colors::{
+    color_constants, get_color_constant, ColorConstant, SystemColor, VB6Color, VB6_COLOR_CONSTANTS,
+};
+pub use ocx::ocx_controls;
 pub use properties::{PropertyDef, PropertyType, PropertyValue};
+pub use user_controls::{
+    get_user_control, register_user_control, unregister_user_control, UserControlMember,
+};
 
 use std::collections::HashMap;
+use std::sync::RwLock;
 use once_cell::sync::Lazy;
 
 /// Control definition with all its properties
@@ -68,26 +77,68 @@ pub enum MenuShortcut {
 }
 
 impl MenuShortcut {
-    /// Parse a VB6 shortcut string
+    /// Parse a VB6 shortcut string (e.g., "^S", "+{F5}", "{DEL}")
     pub fn from_str(s: &str) -> Option<Self> {
-        match s.trim() {
-            "^A" => Some(Self::CtrlA), "^B" => Some(Self::CtrlB), "^C" => Some(Self::CtrlC),
-            "^D" => Some(Self::CtrlD), "^E" => Some(Self::CtrlE), "^F" => Some(Self::CtrlF),
-            "^G" => Some(Self::CtrlG), "^H" => Some(Self::CtrlH), "^I" => Some(Self::CtrlI),
-            "^J" => Some(Self::CtrlJ), "^K" => Some(Self::CtrlK), "^L" => Some(Self::CtrlL),
-            "^M" => Some(Self::CtrlM), "^N" => Some(Self::CtrlN), "^O" => Some(Self::CtrlO),
-            "^P" => Some(Self::CtrlP), "^Q" => Some(Self::CtrlQ), "^R" => Some(Self::CtrlR),
-            "^S" => Some(Self::CtrlS), "^T" => Some(Self::CtrlT), "^U" => Some(Self::CtrlU),
-            "^V" => Some(Self::CtrlV), "^W" => Some(Self::CtrlW), "^X" => Some(Self::CtrlX),
-            "^Y" => Some(Self::CtrlY), "^Z" => Some(Self::CtrlZ),
-            "{F1}" => Some(Self::F1), "{F2}" => Some(Self::F2), "{F3}" => Some(Self::F3),
-            "{F4}" => Some(Self::F4), "{F5}" => Some(Self::F5), "{F6}" => Some(Self::F6),
-            "{F7}" => Some(Self::F7), "{F8}" => Some(Self::F8), "{F9}" => Some(Self::F9),
-            "{F10}" => Some(Self::F10), "{F11}" => Some(Self::F11), "{F12}" => Some(Self::F12),
-            _ => None,
+        let s = s.trim();
+        Self::all()
+            .iter()
+            .copied()
+            .find(|shortcut| shortcut.to_vb6_string().eq_ignore_ascii_case(s))
+    }
+
+    /// Get the shortcut string as written in a form file (inverse of `from_str`)
+    pub fn to_vb6_string(&self) -> &'static str {
+        match self {
+            Self::None => "", Self::CtrlA => "^A", Self::CtrlB => "^B", Self::CtrlC => "^C",
+            Self::CtrlD => "^D", Self::CtrlE => "^E", Self::CtrlF => "^F", Self::CtrlG => "^G",
+            Self::CtrlH => "^H", Self::CtrlI => "^I", Self::CtrlJ => "^J", Self::CtrlK => "^K",
+            Self::CtrlL => "^L", Self::CtrlM => "^M", Self::CtrlN => "^N", Self::CtrlO => "^O",
+            Self::CtrlP => "^P", Self::CtrlQ => "^Q", Self::CtrlR => "^R", Self::CtrlS => "^S",
+            Self::CtrlT => "^T", Self::CtrlU => "^U", Self::CtrlV => "^V", Self::CtrlW => "^W",
+            Self::CtrlX => "^X", Self::CtrlY => "^Y", Self::CtrlZ => "^Z", Self::F1 => "{F1}",
+            Self::F2 => "{F2}", Self::F3 => "{F3}", Self::F4 => "{F4}", Self::F5 => "{F5}",
+            Self::F6 => "{F6}", Self::F7 => "{F7}", Self::F8 => "{F8}", Self::F9 => "{F9}",
+            Self::F10 => "{F10}", Self::F11 => "{F11}", Self::F12 => "{F12}",
+            Self::CtrlF1 => "^{F1}", Self::CtrlF2 => "^{F2}", Self::CtrlF3 => "^{F3}",
+            Self::CtrlF4 => "^{F4}", Self::CtrlF5 => "^{F5}", Self::CtrlF6 => "^{F6}",
+            Self::CtrlF7 => "^{F7}", Self::CtrlF8 => "^{F8}", Self::CtrlF9 => "^{F9}",
+            Self::CtrlF10 => "^{F10}", Self::CtrlF11 => "^{F11}", Self::CtrlF12 => "^{F12}",
+            Self::ShiftF1 => "+{F1}", Self::ShiftF2 => "+{F2}", Self::ShiftF3 => "+{F3}",
+            Self::ShiftF4 => "+{F4}", Self::ShiftF5 => "+{F5}", Self::ShiftF6 => "+{F6}",
+            Self::ShiftF7 => "+{F7}", Self::ShiftF8 => "+{F8}", Self::ShiftF9 => "+{F9}",
+            Self::ShiftF10 => "+{F10}", Self::ShiftF11 => "+{F11}", Self::ShiftF12 => "+{F12}",
+            Self::CtrlShiftF1 => "^+{F1}", Self::CtrlShiftF2 => "^+{F2}",
+            Self::CtrlShiftF3 => "^+{F3}", Self::CtrlShiftF4 => "^+{F4}",
+            Self::CtrlShiftF5 => "^+{F5}", Self::CtrlShiftF6 => "^+{F6}",
+            Self::CtrlShiftF7 => "^+{F7}", Self::CtrlShiftF8 => "^+{F8}",
+            Self::CtrlShiftF9 => "^+{F9}", Self::CtrlShiftF10 => "^+{F10}",
+            Self::CtrlShiftF11 => "^+{F11}", Self::CtrlShiftF12 => "^+{F12}",
+            Self::CtrlIns => "^{INSERT}", Self::ShiftIns => "+{INSERT}", Self::Del => "{DEL}",
+            Self::ShiftDel => "+{DEL}", Self::AltBksp => "%{BKSP}", Self::CtrlBksp => "^{BKSP}",
         }
     }
 
+    /// Get all shortcuts
+    pub fn all() -> &'static [MenuShortcut] {
+        &[
+            Self::None, Self::CtrlA, Self::CtrlB, Self::CtrlC, Self::CtrlD, Self::CtrlE,
+            Self::CtrlF, Self::CtrlG, Self::CtrlH, Self::CtrlI, Self::CtrlJ, Self::CtrlK,
+            Self::CtrlL, Self::CtrlM, Self::CtrlN, Self::CtrlO, Self::CtrlP, Self::CtrlQ,
+            Self::CtrlR, Self::CtrlS, Self::CtrlT, Self::CtrlU, Self::CtrlV, Self::CtrlW,
+            Self::CtrlX, Self::CtrlY, Self::CtrlZ, Self::F1, Self::F2, Self::F3, Self::F4, Self::F5,
+            Self::F6, Self::F7, Self::F8, Self::F9, Self::F10, Self::F11, Self::F12, Self::CtrlF1,
+            Self::CtrlF2, Self::CtrlF3, Self::CtrlF4, Self::CtrlF5, Self::CtrlF6, Self::CtrlF7,
+            Self::CtrlF8, Self::CtrlF9, Self::CtrlF10, Self::CtrlF11, Self::CtrlF12, Self::ShiftF1,
+            Self::ShiftF2, Self::ShiftF3, Self::ShiftF4, Self::ShiftF5, Self::ShiftF6,
+            Self::ShiftF7, Self::ShiftF8, Self::ShiftF9, Self::ShiftF10, Self::ShiftF11,
+            Self::ShiftF12, Self::CtrlShiftF1, Self::CtrlShiftF2, Self::CtrlShiftF3,
+            Self::CtrlShiftF4, Self::CtrlShiftF5, Self::CtrlShiftF6, Self::CtrlShiftF7,
+            Self::CtrlShiftF8, Self::CtrlShiftF9, Self::CtrlShiftF10, Self::CtrlShiftF11,
+            Self::CtrlShiftF12, Self::CtrlIns, Self::ShiftIns, Self::Del, Self::ShiftDel,
+            Self::AltBksp, Self::CtrlBksp,
+        ]
+    }
+
     /// Get display string for the shortcut
     pub fn display(&self) -> &'static str {
         match self {
@@ -707,7 +758,82 @@ static CONTROL_REGISTRY: Lazy<HashMap<&'static str, &'static ControlDef>> = Lazy
     map
 });
 
-/// Get a control definition by type name (case-insensitive)
+/// Controls added at runtime: the OCX controls referenced by projects and the
+/// UserControls of the workspace
+#[derive(Debug, Default)]
+pub struct ControlRegistry {
+    /// Definitions indexed by lowercase type name
+    controls: HashMap<String, &'static ControlDef>,
+}
+
+impl ControlRegistry {
+    /// Add a definition, replacing any control of the same name
+    pub fn insert(&mut self, def: &'static ControlDef) {
+        self.controls.insert(def.name.to_lowercase(), def);
+    }
+
+    /// Remove a control by name (case-insensitive)
+    pub fn remove(&mut self, name: &str) -> Option<&'static ControlDef> {
+        self.controls.remove(&name.to_lowercase())
+    }
+
+    /// Get a control by name (case-insensitive)
+    pub fn get(&self, name: &str) -> Option<&'static ControlDef> {
+        self.controls.get(&name.to_lowercase()).copied()
+    }
+
+    /// All registered controls
+    pub fn controls(&self) -> impl Iterator<Item = &'static ControlDef> + '_ {
+        self.controls.values().copied()
+    }
+}
+
+static RUNTIME_REGISTRY: Lazy<RwLock<ControlRegistry>> =
+    Lazy::new(|| RwLock::new(ControlRegistry::default()));
+
+/// Register a control at runtime; built-in controls of the same name take precedence
+pub fn register_control(def: ControlDef) -> &'static ControlDef {
+    let def: &'static ControlDef = Box::leak(Box::new(def));
+    insert_runtime_control(def);
+    def
+}
+
+/// Remove a control registered at runtime
+pub fn unregister_control(name: &str) {
+    if let Ok(mut registry) = RUNTIME_REGISTRY.write() {
+        registry.remove(name);
+    }
+}
+
+/// Register the bundled definitions of the controls of an OCX file (as named
+/// by an `Object=` line of a project), returning how many were known
+pub fn register_ocx(filename: &str) -> usize {
+    let controls = ocx_controls(filename);
+    for &def in controls {
+        insert_runtime_control(def);
+    }
+    controls.len()
+}
+
+fn insert_runtime_control(def: &'static ControlDef) {
+    if let Ok(mut registry) = RUNTIME_REGISTRY.write() {
+        registry.insert(def);
+    }
+}
+
+fn runtime_control(name: &str) -> Option<&'static ControlDef> {
+    RUNTIME_REGISTRY.read().ok()?.get(name)
+}
+
+fn runtime_controls() -> Vec<&'static ControlDef> {
+    RUNTIME_REGISTRY
+        .read()
+        .map(|registry| registry.controls().collect())
+        .unwrap_or_default()
+}
+
+/// Get a control definition by type name (case-insensitive), falling back to
+/// the controls registered at runtime
 pub fn get_control(type_name: &str) -> Option<&'static ControlDef> {
     // Try exact match first
     if let Some(def) = CONTROL_REGISTRY.get(type_name) {
@@ -722,12 +848,16 @@ pub fn get_control(type_name: &str) -> Option<&'static ControlDef> {
         }
     }
 
-    None
+    runtime_control(type_name)
 }
 
 /// Get all available control names
 pub fn get_control_names() -> Vec<&'static str> {
-    CONTROL_REGISTRY.keys().copied().collect()
+    CONTROL_REGISTRY
+        .keys()
+        .copied()
+        .chain(runtime_controls().into_iter().map(|def| def.name))
+        .collect()
 }
 
 /// Get property definition for a control
@@ -748,6 +878,15 @@ pub fn get_event(control_type: &str, event_name: &str) -> Option<&'static EventD
         .find(|e| e.name.to_lowercase() == event_lower)
 }
 
+/// Whether any control raises an event of this name (case-insensitive)
+pub fn is_control_event(event_name: &str) -> bool {
+    CONTROL_REGISTRY
+        .values()
+        .copied()
+        .chain(runtime_controls())
+        .any(|control| control.events.iter().any(|e| e.name.eq_ignore_ascii_case(event_name)))
+}
+
 /// Get method definition for a control
 pub fn get_method(control_type: &str, method_name: &str) -> Option<&'static MethodDef> {
     let control = get_control(control_type)?;
@@ -807,9 +946,57 @@ mod tests {
         assert_eq!(event.unwrap().name, "Click");
     }
 
+    #[test]
+    fn test_register_control() {
+        assert!(get_control("RegistryTestGrid").is_none());
+        let def = register_control(ControlDef {
+            name: "RegistryTestGrid",
+            full_name: "TestLib.RegistryTestGrid",
+            description: "A grid",
+            properties: &[],
+            events: &[EventDef { name: "RegistryTestEvent", description: "", parameters: "" }],
+            methods: &[],
+            is_container: false,
+        });
+        assert!(std::ptr::eq(get_control("registrytestgrid").unwrap(), def));
+        assert!(get_control_names().contains(&"RegistryTestGrid"));
+        assert!(is_control_event("RegistryTestEvent"));
+
+        // Built-in controls take precedence over runtime ones
+        register_control(ControlDef { name: "Label", ..def.clone() });
+        assert_eq!(get_control("Label").unwrap().full_name, "VB.Label");
+
+        unregister_control("RegistryTestGrid");
+        unregister_control("Label");
+        assert!(get_control("RegistryTestGrid").is_none());
+    }
+
+    #[test]
+    fn test_register_ocx() {
+        assert_eq!(ocx_controls(r"C:\Windows\System32\mscomctl.ocx").len(), 7);
+        assert!(ocx_controls("UNKNOWN.OCX").is_empty());
+
+        assert_eq!(register_ocx("MSFLXGRD.OCX"), 1);
+        assert!(get_property("MSFlexGrid", "TextMatrix").is_some());
+        assert!(get_method("MSFlexGrid", "AddItem").is_some());
+        assert_eq!(get_control("MSFlexGrid").unwrap().full_name, "MSFlexGridLib.MSFlexGrid");
+    }
+
     #[test]
     fn test_menu_shortcut() {
         assert_eq!(MenuShortcut::CtrlS.display(), "Ctrl+S");
         assert_eq!(MenuShortcut::from_str("^S"), Some(MenuShortcut::CtrlS));
+        assert_eq!(MenuShortcut::from_str("^+{F5}"), Some(MenuShortcut::CtrlShiftF5));
+        assert_eq!(MenuShortcut::from_str("+{INSERT}"), Some(MenuShortcut::ShiftIns));
+        assert_eq!(MenuShortcut::from_str("%{BKSP}"), Some(MenuShortcut::AltBksp));
+        assert_eq!(MenuShortcut::from_str("^{F13}"), None);
+    }
+
+    #[test]
+    fn test_menu_shortcut_round_trip() {
+        for &shortcut in MenuShortcut::all() {
+            assert_eq!(MenuShortcut::from_str(shortcut.to_vb6_string()), Some(shortcut));
+        }
+        assert_eq!(MenuShortcut::all().len(), 81);
     }
 }
@@ -0,0 +1,867 @@
+//! Definitions of Common ActiveX (OCX) Controls
+//!
+//! The controls shipped with VB6 in separate OCX files (Common Controls,
+//! Common Dialog, MSFlexGrid, Winsock, ...). They are registered when a
+//! project references their OCX with an `Object=` line.
+
+use super::properties::{PropertyDef, PropertyType, BOOLEAN_VALUES};
+use super::{ControlDef, EventDef, MethodDef};
+
+/// CommonDialog control (MSComDlg)
+pub static COMMONDIALOG_DEF: ControlDef = ControlDef {
+    name: "CommonDialog",
+    full_name: "MSComDlg.CommonDialog",
+    description: "Displays the standard Open, Save As, Color, Font, Print, and Help dialogs",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CancelError", description: "Returns/sets whether choosing Cancel raises an error", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "DialogTitle", description: "Returns/sets the title of the dialog", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FileName", description: "Returns/sets the path and name of the selected file", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FileTitle", description: "Returns the name of the selected file without its path", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Filter", description: "Returns/sets the file filters, e.g. \"Text (*.txt)|*.txt\"", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FilterIndex", description: "Returns/sets the default filter", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "DefaultExt", description: "Returns/sets the default file extension", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "InitDir", description: "Returns/sets the initial folder", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MaxFileSize", description: "Returns/sets the maximum length of FileName", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Flags", description: "Returns/sets the options of the dialog", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Color", description: "Returns/sets the selected color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FontName", description: "Returns/sets the selected font name", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FontSize", description: "Returns/sets the selected font size", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FontBold", description: "Returns/sets whether the selected font is bold", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "FontItalic", description: "Returns/sets whether the selected font is italic", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Copies", description: "Returns/sets the number of copies to print", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FromPage", description: "Returns/sets the first page to print", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToPage", description: "Returns/sets the last page to print", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "HelpFile", description: "Returns/sets the help file to show", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "HelpCommand", description: "Returns/sets the type of help to show", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[],
+    methods: &[
+        MethodDef { name: "ShowOpen", description: "Shows the Open dialog", signature: "ShowOpen", return_type: None },
+        MethodDef { name: "ShowSave", description: "Shows the Save As dialog", signature: "ShowSave", return_type: None },
+        MethodDef { name: "ShowColor", description: "Shows the Color dialog", signature: "ShowColor", return_type: None },
+        MethodDef { name: "ShowFont", description: "Shows the Font dialog", signature: "ShowFont", return_type: None },
+        MethodDef { name: "ShowPrinter", description: "Shows the Print dialog", signature: "ShowPrinter", return_type: None },
+        MethodDef { name: "ShowHelp", description: "Runs WinHelp with HelpFile", signature: "ShowHelp", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// MSFlexGrid control (MSFlexGridLib)
+pub static MSFLEXGRID_DEF: ControlDef = ControlDef {
+    name: "MSFlexGrid",
+    full_name: "MSFlexGridLib.MSFlexGrid",
+    description: "A grid that displays and sorts tabular data",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BackColor", description: "Returns/sets the background color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ForeColor", description: "Returns/sets the text color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Rows", description: "Returns/sets the number of rows", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Cols", description: "Returns/sets the number of columns", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FixedRows", description: "Returns/sets the number of fixed (header) rows", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FixedCols", description: "Returns/sets the number of fixed (header) columns", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Row", description: "Returns/sets the active row", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Col", description: "Returns/sets the active column", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "RowSel", description: "Returns/sets the last row of the selection", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ColSel", description: "Returns/sets the last column of the selection", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Text", description: "Returns/sets the text of the active cell", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TextMatrix", description: "Returns/sets the text of a cell: TextMatrix(Row, Col)", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ColWidth", description: "Returns/sets the width of a column: ColWidth(Index)", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "RowHeight", description: "Returns/sets the height of a row: RowHeight(Index)", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CellBackColor", description: "Returns/sets the background color of the selected cells", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CellForeColor", description: "Returns/sets the text color of the selected cells", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CellAlignment", description: "Returns/sets the alignment of the selected cells", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FormatString", description: "Returns/sets the column headers, widths, and alignments", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Redraw", description: "Returns/sets whether the grid repaints after each change", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TopRow", description: "Returns/sets the first visible row", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LeftCol", description: "Returns/sets the first visible column", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MouseRow", description: "Returns the row under the mouse", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MouseCol", description: "Returns the column under the mouse", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelectionMode", description: "Returns/sets whether cells, rows, or columns are selected", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "AllowUserResizing", description: "Returns/sets whether the user can resize rows and columns", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "GridLines", description: "Returns/sets the style of the grid lines", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Sort", description: "Sorts the selected rows (write-only)", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Clip", description: "Returns/sets the contents of the selection as tab-separated text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "EnterCell", description: "Occurs when a cell becomes active", parameters: "" },
+        EventDef { name: "LeaveCell", description: "Occurs before another cell becomes active", parameters: "" },
+        EventDef { name: "RowColChange", description: "Occurs when the active cell changes", parameters: "" },
+        EventDef { name: "SelChange", description: "Occurs when the selection changes", parameters: "" },
+        EventDef { name: "Scroll", description: "Occurs when the grid is scrolled", parameters: "" },
+        EventDef { name: "Compare", description: "Occurs during a custom sort to compare two rows", parameters: "ByVal Row1 As Long, ByVal Row2 As Long, Cmp As Integer" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+        MethodDef { name: "AddItem", description: "Adds a row", signature: "AddItem Item, [Index]", return_type: None },
+        MethodDef { name: "RemoveItem", description: "Removes a row", signature: "RemoveItem Index", return_type: None },
+        MethodDef { name: "Clear", description: "Clears the contents of all cells", signature: "Clear", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// MSComm control (MSCommLib)
+pub static MSCOMM_DEF: ControlDef = ControlDef {
+    name: "MSComm",
+    full_name: "MSCommLib.MSComm",
+    description: "Serial port communications",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CommPort", description: "Returns/sets the number of the serial port", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Settings", description: "Returns/sets the baud rate, parity, data bits, and stop bits, e.g. \"9600,n,8,1\"", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "PortOpen", description: "Returns/sets whether the port is open", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Input", description: "Returns and removes data from the receive buffer", property_type: PropertyType::Variant, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Output", description: "Writes data to the transmit buffer", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "InputLen", description: "Returns/sets the number of characters Input reads (0 = all)", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "InputMode", description: "Returns/sets whether Input returns text or binary data", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "RThreshold", description: "Returns/sets the number of received characters that fire OnComm", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SThreshold", description: "Returns/sets the transmit buffer level that fires OnComm", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Handshaking", description: "Returns/sets the handshaking protocol", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "InBufferCount", description: "Returns the number of characters waiting in the receive buffer", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "OutBufferCount", description: "Returns the number of characters waiting in the transmit buffer", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "InBufferSize", description: "Returns/sets the size of the receive buffer", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "OutBufferSize", description: "Returns/sets the size of the transmit buffer", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CommEvent", description: "Returns the most recent communication event or error", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "DTREnable", description: "Returns/sets whether Data Terminal Ready is enabled", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "RTSEnable", description: "Returns/sets whether Request To Send is enabled", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "CDHolding", description: "Returns whether the Carrier Detect line is high", property_type: PropertyType::Boolean, read_only: true, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "CTSHolding", description: "Returns whether the Clear To Send line is high", property_type: PropertyType::Boolean, read_only: true, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "DSRHolding", description: "Returns whether the Data Set Ready line is high", property_type: PropertyType::Boolean, read_only: true, default_value: None, valid_values: BOOLEAN_VALUES },
+    ],
+    events: &[
+        EventDef { name: "OnComm", description: "Occurs when a communication event or error occurs", parameters: "" },
+    ],
+    methods: &[],
+    is_container: false,
+};
+
+/// Winsock control (MSWinsockLib)
+pub static WINSOCK_DEF: ControlDef = ControlDef {
+    name: "Winsock",
+    full_name: "MSWinsockLib.Winsock",
+    description: "A TCP or UDP network connection",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Protocol", description: "Returns/sets whether TCP or UDP is used", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "RemoteHost", description: "Returns/sets the remote computer", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "RemotePort", description: "Returns/sets the remote port", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "RemoteHostIP", description: "Returns the IP address of the remote computer", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LocalPort", description: "Returns/sets the local port", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LocalIP", description: "Returns the IP address of the local computer", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LocalHostName", description: "Returns the name of the local computer", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "State", description: "Returns the state of the connection", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BytesReceived", description: "Returns the number of bytes waiting to be read", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SocketHandle", description: "Returns the socket handle", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Connect", description: "Occurs when a connection completes", parameters: "" },
+        EventDef { name: "ConnectionRequest", description: "Occurs when a remote computer requests a connection", parameters: "ByVal requestID As Long" },
+        EventDef { name: "DataArrival", description: "Occurs when data arrives", parameters: "ByVal bytesTotal As Long" },
+        EventDef { name: "SendComplete", description: "Occurs when a send completes", parameters: "" },
+        EventDef { name: "SendProgress", description: "Occurs while data is sent", parameters: "ByVal bytesSent As Long, ByVal bytesRemaining As Long" },
+        EventDef { name: "Close", description: "Occurs when the remote computer closes the connection", parameters: "" },
+        EventDef { name: "Error", description: "Occurs when a network error occurs", parameters: "ByVal Number As Integer, Description As String, ByVal Scode As Long, ByVal Source As String, ByVal HelpFile As String, ByVal HelpContext As Long, CancelDisplay As Boolean" },
+    ],
+    methods: &[
+        MethodDef { name: "Connect", description: "Connects to a remote computer", signature: "Connect [RemoteHost], [RemotePort]", return_type: None },
+        MethodDef { name: "Listen", description: "Waits for incoming connections", signature: "Listen", return_type: None },
+        MethodDef { name: "Accept", description: "Accepts an incoming connection", signature: "Accept requestID", return_type: None },
+        MethodDef { name: "Bind", description: "Binds to a local port for UDP", signature: "Bind [LocalPort], [LocalIP]", return_type: None },
+        MethodDef { name: "SendData", description: "Sends data", signature: "SendData data", return_type: None },
+        MethodDef { name: "GetData", description: "Reads and removes received data", signature: "GetData data, [type], [maxLen]", return_type: None },
+        MethodDef { name: "PeekData", description: "Reads received data without removing it", signature: "PeekData data, [type], [maxLen]", return_type: None },
+        MethodDef { name: "Close", description: "Closes the connection", signature: "Close", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// ListView control (MSComctlLib)
+pub static LISTVIEW_DEF: ControlDef = ControlDef {
+    name: "ListView",
+    full_name: "MSComctlLib.ListView",
+    description: "Displays items as icons, a list, or a report with columns",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BackColor", description: "Returns/sets the background color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ForeColor", description: "Returns/sets the text color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BorderStyle", description: "Returns/sets the border style", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Appearance", description: "Returns/sets whether the control is painted flat or 3D", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "View", description: "Returns/sets whether items show as icons, a list, or a report", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ListItems", description: "Returns the collection of items", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ColumnHeaders", description: "Returns the collection of report columns", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelectedItem", description: "Returns/sets the selected item", property_type: PropertyType::Object, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Icons", description: "Returns/sets the ImageList of large icons", property_type: PropertyType::Object, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SmallIcons", description: "Returns/sets the ImageList of small icons", property_type: PropertyType::Object, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Sorted", description: "Returns/sets whether items are sorted", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "SortKey", description: "Returns/sets the column to sort by", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SortOrder", description: "Returns/sets ascending or descending order", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LabelEdit", description: "Returns/sets whether labels can be edited", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MultiSelect", description: "Returns/sets whether several items can be selected", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "FullRowSelect", description: "Returns/sets whether a whole report row is selected", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "GridLines", description: "Returns/sets whether report grid lines are shown", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "HideSelection", description: "Returns/sets whether the selection is hidden without focus", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Checkboxes", description: "Returns/sets whether items have check boxes", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "ItemClick", description: "Occurs when an item is clicked", parameters: "ByVal Item As MSComctlLib.ListItem" },
+        EventDef { name: "ItemCheck", description: "Occurs when the check box of an item changes", parameters: "ByVal Item As MSComctlLib.ListItem" },
+        EventDef { name: "ColumnClick", description: "Occurs when a column header is clicked", parameters: "ByVal ColumnHeader As MSComctlLib.ColumnHeader" },
+        EventDef { name: "BeforeLabelEdit", description: "Occurs before a label is edited", parameters: "Cancel As Integer" },
+        EventDef { name: "AfterLabelEdit", description: "Occurs after a label is edited", parameters: "Cancel As Integer, NewString As String" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+        MethodDef { name: "FindItem", description: "Finds an item by text", signature: "FindItem(sz, [Where], [Index], [fPartial])", return_type: Some("ListItem") },
+        MethodDef { name: "HitTest", description: "Returns the item at a point", signature: "HitTest(x, y)", return_type: Some("ListItem") },
+        MethodDef { name: "StartLabelEdit", description: "Begins editing the label of the selected item", signature: "StartLabelEdit", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// TreeView control (MSComctlLib)
+pub static TREEVIEW_DEF: ControlDef = ControlDef {
+    name: "TreeView",
+    full_name: "MSComctlLib.TreeView",
+    description: "Displays a hierarchy of nodes",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BorderStyle", description: "Returns/sets the border style", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Appearance", description: "Returns/sets whether the control is painted flat or 3D", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Nodes", description: "Returns the collection of nodes", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelectedItem", description: "Returns/sets the selected node", property_type: PropertyType::Object, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ImageList", description: "Returns/sets the ImageList of node images", property_type: PropertyType::Object, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Style", description: "Returns/sets which of lines, images, and plus/minus signs are shown", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LineStyle", description: "Returns/sets whether root nodes are connected by lines", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Indentation", description: "Returns/sets the indentation of child nodes", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LabelEdit", description: "Returns/sets whether labels can be edited", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "HideSelection", description: "Returns/sets whether the selection is hidden without focus", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Checkboxes", description: "Returns/sets whether nodes have check boxes", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Sorted", description: "Returns/sets whether root nodes are sorted", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "PathSeparator", description: "Returns/sets the separator of FullPath", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "NodeClick", description: "Occurs when a node is clicked", parameters: "ByVal Node As MSComctlLib.Node" },
+        EventDef { name: "NodeCheck", description: "Occurs when the check box of a node changes", parameters: "ByVal Node As MSComctlLib.Node" },
+        EventDef { name: "Expand", description: "Occurs when a node is expanded", parameters: "ByVal Node As MSComctlLib.Node" },
+        EventDef { name: "Collapse", description: "Occurs when a node is collapsed", parameters: "ByVal Node As MSComctlLib.Node" },
+        EventDef { name: "BeforeLabelEdit", description: "Occurs before a label is edited", parameters: "Cancel As Integer" },
+        EventDef { name: "AfterLabelEdit", description: "Occurs after a label is edited", parameters: "Cancel As Integer, NewString As String" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+        MethodDef { name: "HitTest", description: "Returns the node at a point", signature: "HitTest(x, y)", return_type: Some("Node") },
+        MethodDef { name: "GetVisibleCount", description: "Returns the number of nodes that fit in the control", signature: "GetVisibleCount()", return_type: Some("Long") },
+        MethodDef { name: "StartLabelEdit", description: "Begins editing the label of the selected node", signature: "StartLabelEdit", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// ImageList control (MSComctlLib)
+pub static IMAGELIST_DEF: ControlDef = ControlDef {
+    name: "ImageList",
+    full_name: "MSComctlLib.ImageList",
+    description: "Holds images for other controls",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BackColor", description: "Returns/sets the background color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ListImages", description: "Returns the collection of images", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ImageWidth", description: "Returns/sets the width of the images", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ImageHeight", description: "Returns/sets the height of the images", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MaskColor", description: "Returns/sets the transparent color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "UseMaskColor", description: "Returns/sets whether MaskColor is transparent", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "hImageList", description: "Returns the handle of the image list", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+    ],
+    events: &[],
+    methods: &[
+        MethodDef { name: "Overlay", description: "Combines two images", signature: "Overlay(Key1, Key2)", return_type: Some("StdPicture") },
+    ],
+    is_container: false,
+};
+
+/// ProgressBar control (MSComctlLib)
+pub static PROGRESSBAR_DEF: ControlDef = ControlDef {
+    name: "ProgressBar",
+    full_name: "MSComctlLib.ProgressBar",
+    description: "Shows the progress of a long operation",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BorderStyle", description: "Returns/sets the border style", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Appearance", description: "Returns/sets whether the control is painted flat or 3D", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Min", description: "Returns/sets the lowest value", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Max", description: "Returns/sets the highest value", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Value", description: "Returns/sets the current progress", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Orientation", description: "Returns/sets whether the bar is horizontal or vertical", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Scrolling", description: "Returns/sets whether progress is shown in blocks or smoothly", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+    ],
+    methods: &[
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// StatusBar control (MSComctlLib)
+pub static STATUSBAR_DEF: ControlDef = ControlDef {
+    name: "StatusBar",
+    full_name: "MSComctlLib.StatusBar",
+    description: "A bar of status panels, usually at the bottom of a form",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Panels", description: "Returns the collection of panels", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SimpleText", description: "Returns/sets the text shown when Style is simple", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Style", description: "Returns/sets whether panels or simple text are shown", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Align", description: "Returns/sets where the bar is docked on the form", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "PanelClick", description: "Occurs when a panel is clicked", parameters: "ByVal Panel As MSComctlLib.Panel" },
+        EventDef { name: "PanelDblClick", description: "Occurs when a panel is double-clicked", parameters: "ByVal Panel As MSComctlLib.Panel" },
+    ],
+    methods: &[
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// Toolbar control (MSComctlLib)
+pub static TOOLBAR_DEF: ControlDef = ControlDef {
+    name: "Toolbar",
+    full_name: "MSComctlLib.Toolbar",
+    description: "A bar of buttons, usually at the top of a form",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Buttons", description: "Returns the collection of buttons", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ImageList", description: "Returns/sets the ImageList of button images", property_type: PropertyType::Object, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Style", description: "Returns/sets whether the buttons are standard or flat", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Align", description: "Returns/sets where the bar is docked on the form", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ShowTips", description: "Returns/sets whether button tooltips are shown", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Wrappable", description: "Returns/sets whether buttons wrap onto more rows", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "ButtonHeight", description: "Returns/sets the height of the buttons", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ButtonWidth", description: "Returns/sets the width of the buttons", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "ButtonClick", description: "Occurs when a button is clicked", parameters: "ByVal Button As MSComctlLib.Button" },
+        EventDef { name: "ButtonMenuClick", description: "Occurs when a drop-down menu item of a button is clicked", parameters: "ByVal ButtonMenu As MSComctlLib.ButtonMenu" },
+    ],
+    methods: &[
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// Slider control (MSComctlLib)
+pub static SLIDER_DEF: ControlDef = ControlDef {
+    name: "Slider",
+    full_name: "MSComctlLib.Slider",
+    description: "A track bar for choosing a value from a range",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Min", description: "Returns/sets the lowest value", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Max", description: "Returns/sets the highest value", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Value", description: "Returns/sets the current value", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SmallChange", description: "Returns/sets the change for an arrow key", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "LargeChange", description: "Returns/sets the change for PageUp/PageDown", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TickFrequency", description: "Returns/sets the spacing of tick marks", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TickStyle", description: "Returns/sets where tick marks are drawn", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Orientation", description: "Returns/sets whether the slider is horizontal or vertical", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelectRange", description: "Returns/sets whether a range can be selected", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "SelStart", description: "Returns/sets the start of the selected range", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelLength", description: "Returns/sets the length of the selected range", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "Change", description: "Occurs when the value changes", parameters: "" },
+        EventDef { name: "Scroll", description: "Occurs while the slider is dragged", parameters: "" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// RichTextBox control (RichTextLib)
+pub static RICHTEXTBOX_DEF: ControlDef = ControlDef {
+    name: "RichTextBox",
+    full_name: "RichTextLib.RichTextBox",
+    description: "A text box that edits formatted (RTF) text",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BackColor", description: "Returns/sets the background color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BorderStyle", description: "Returns/sets the border style", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Appearance", description: "Returns/sets whether the control is painted flat or 3D", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Text", description: "Returns/sets the plain text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TextRTF", description: "Returns/sets the text in RTF format", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelText", description: "Returns/sets the selected text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelRTF", description: "Returns/sets the selected text in RTF format", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelStart", description: "Returns/sets the start of the selection", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelLength", description: "Returns/sets the length of the selection", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelBold", description: "Returns/sets whether the selection is bold", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelItalic", description: "Returns/sets whether the selection is italic", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelUnderline", description: "Returns/sets whether the selection is underlined", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelColor", description: "Returns/sets the color of the selection", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelFontName", description: "Returns/sets the font name of the selection", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelFontSize", description: "Returns/sets the font size of the selection", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Locked", description: "Returns/sets whether the text can be edited", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "MultiLine", description: "Returns/sets whether the control has several lines", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "ScrollBars", description: "Returns/sets which scroll bars are shown", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MaxLength", description: "Returns/sets the maximum number of characters (0 = no limit)", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FileName", description: "Returns/sets the file loaded into the control", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "Change", description: "Occurs when the value changes", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "SelChange", description: "Occurs when the selection changes", parameters: "" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+        MethodDef { name: "LoadFile", description: "Loads an RTF or text file", signature: "LoadFile pathname, [filetype]", return_type: None },
+        MethodDef { name: "SaveFile", description: "Saves the contents to a file", signature: "SaveFile pathname, [flags]", return_type: None },
+        MethodDef { name: "Find", description: "Finds text and selects it", signature: "Find(bstrString, [vStart], [vEnd], [vOptions])", return_type: Some("Long") },
+        MethodDef { name: "GetLineFromChar", description: "Returns the line containing a character", signature: "GetLineFromChar(lChar)", return_type: Some("Long") },
+        MethodDef { name: "SelPrint", description: "Prints the selection", signature: "SelPrint lHDC", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// SSTab control (TabDlg)
+pub static SSTAB_DEF: ControlDef = ControlDef {
+    name: "SSTab",
+    full_name: "TabDlg.SSTab",
+    description: "A tabbed dialog whose tabs each contain other controls",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BackColor", description: "Returns/sets the background color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ForeColor", description: "Returns/sets the text color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Tab", description: "Returns/sets the active tab", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Tabs", description: "Returns/sets the number of tabs", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabsPerRow", description: "Returns/sets the number of tabs in each row", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Caption", description: "Returns/sets the caption of the active tab", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabCaption", description: "Returns/sets the caption of a tab: TabCaption(Index)", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabEnabled", description: "Returns/sets whether a tab is enabled: TabEnabled(Index)", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabVisible", description: "Returns/sets whether a tab is visible: TabVisible(Index)", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabHeight", description: "Returns/sets the height of the tabs", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabOrientation", description: "Returns/sets on which side the tabs are", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Style", description: "Returns/sets the style of the tabs", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "Click", description: "Occurs when another tab becomes active", parameters: "PreviousTab As Integer" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: true,
+};
+
+/// MaskEdBox control (MSMask)
+pub static MASKEDBOX_DEF: ControlDef = ControlDef {
+    name: "MaskEdBox",
+    full_name: "MSMask.MaskEdBox",
+    description: "A text box that restricts input to a pattern",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BackColor", description: "Returns/sets the background color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ForeColor", description: "Returns/sets the text color", property_type: PropertyType::Color, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BorderStyle", description: "Returns/sets the border style", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Appearance", description: "Returns/sets whether the control is painted flat or 3D", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Mask", description: "Returns/sets the input pattern, e.g. \"##/##/####\"", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Text", description: "Returns/sets the text including prompt characters", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ClipText", description: "Returns the text without the literal characters of the mask", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "FormattedText", description: "Returns the text as shown when the control loses focus", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Format", description: "Returns/sets the display format", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "PromptChar", description: "Returns/sets the character shown for blank positions", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "PromptInclude", description: "Returns/sets whether Text includes prompt characters", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "AllowPrompt", description: "Returns/sets whether the prompt character is valid input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "AutoTab", description: "Returns/sets whether focus moves on when the mask is filled", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "MaxLength", description: "Returns/sets the maximum number of characters", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelStart", description: "Returns/sets the start of the selection", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelLength", description: "Returns/sets the length of the selection", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SelText", description: "Returns/sets the selected text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "Change", description: "Occurs when the value changes", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "ValidationError", description: "Occurs when input does not match the mask", parameters: "InvalidText As String, StartPosition As Integer" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// DTPicker control (MSComCtl2)
+pub static DTPICKER_DEF: ControlDef = ControlDef {
+    name: "DTPicker",
+    full_name: "MSComCtl2.DTPicker",
+    description: "A drop-down calendar for choosing a date or time",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Font", description: "Returns/sets the font", property_type: PropertyType::Font, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Value", description: "Returns/sets the selected date (Null when unchecked)", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MinDate", description: "Returns/sets the earliest date", property_type: PropertyType::Date, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "MaxDate", description: "Returns/sets the latest date", property_type: PropertyType::Date, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Format", description: "Returns/sets whether a long, short, time, or custom format is used", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CustomFormat", description: "Returns/sets the custom format, e.g. \"dd/MM/yyyy\"", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "CheckBox", description: "Returns/sets whether a check box allows no date", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "UpDown", description: "Returns/sets whether spin buttons replace the calendar", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Day", description: "Returns/sets the day of the selected date", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Month", description: "Returns/sets the month of the selected date", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Year", description: "Returns/sets the year of the selected date", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "Click", description: "Occurs when the user clicks the control", parameters: "" },
+        EventDef { name: "DblClick", description: "Occurs when the user double-clicks the control", parameters: "" },
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "Change", description: "Occurs when the value changes", parameters: "" },
+        EventDef { name: "KeyDown", description: "Occurs when a key is pressed", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyUp", description: "Occurs when a key is released", parameters: "KeyCode As Integer, Shift As Integer" },
+        EventDef { name: "KeyPress", description: "Occurs when a key is pressed and released", parameters: "KeyAscii As Integer" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "CloseUp", description: "Occurs when the calendar closes", parameters: "" },
+        EventDef { name: "DropDown", description: "Occurs when the calendar drops down", parameters: "" },
+    ],
+    methods: &[
+        MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// UpDown control (MSComCtl2)
+pub static UPDOWN_DEF: ControlDef = ControlDef {
+    name: "UpDown",
+    full_name: "MSComCtl2.UpDown",
+    description: "Spin buttons that step a value, often of a buddy text box",
+    properties: &[
+        PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Enabled", description: "Returns/sets whether the control responds to user input", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "hWnd", description: "Returns the window handle of the control", property_type: PropertyType::Long, read_only: true, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Value", description: "Returns/sets the current value", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Min", description: "Returns/sets the lowest value", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Max", description: "Returns/sets the highest value", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "Increment", description: "Returns/sets the step of each click", property_type: PropertyType::Long, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BuddyControl", description: "Returns/sets the control whose value is stepped", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "BuddyProperty", description: "Returns/sets the property of the buddy that is kept in sync", property_type: PropertyType::Variant, read_only: false, default_value: None, valid_values: &[] },
+        PropertyDef { name: "SyncBuddy", description: "Returns/sets whether the buddy is updated with Value", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "AutoBuddy", description: "Returns/sets whether the previous control in tab order is the buddy", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Wrap", description: "Returns/sets whether Value wraps between Min and Max", property_type: PropertyType::Boolean, read_only: false, default_value: None, valid_values: BOOLEAN_VALUES },
+        PropertyDef { name: "Orientation", description: "Returns/sets whether the buttons are vertical or horizontal", property_type: PropertyType::Enum, read_only: false, default_value: None, valid_values: &[] },
+    ],
+    events: &[
+        EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+        EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+        EventDef { name: "Change", description: "Occurs when the value changes", parameters: "" },
+        EventDef { name: "MouseDown", description: "Occurs when a mouse button is pressed", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseUp", description: "Occurs when a mouse button is released", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "MouseMove", description: "Occurs when the mouse moves", parameters: "Button As Integer, Shift As Integer, X As Single, Y As Single" },
+        EventDef { name: "UpClick", description: "Occurs when the up or right button is clicked", parameters: "" },
+        EventDef { name: "DownClick", description: "Occurs when the down or left button is clicked", parameters: "" },
+    ],
+    methods: &[
+        MethodDef { name: "Refresh", description: "Repaints the control", signature: "Refresh", return_type: None },
+        MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    ],
+    is_container: false,
+};
+
+/// The controls of each OCX, keyed by file name
+static OCX_CONTROLS: &[(&str, &[&ControlDef])] = &[
+    (
+        "MSCOMCTL.OCX",
+        &[
+            &LISTVIEW_DEF,
+            &TREEVIEW_DEF,
+            &IMAGELIST_DEF,
+            &PROGRESSBAR_DEF,
+            &STATUSBAR_DEF,
+            &TOOLBAR_DEF,
+            &SLIDER_DEF,
+        ],
+    ),
+    (
+        "COMCTL32.OCX",
+        &[
+            &LISTVIEW_DEF,
+            &TREEVIEW_DEF,
+            &IMAGELIST_DEF,
+            &PROGRESSBAR_DEF,
+            &STATUSBAR_DEF,
+            &TOOLBAR_DEF,
+            &SLIDER_DEF,
+        ],
+    ),
+    ("MSCOMCT2.OCX", &[&DTPICKER_DEF, &UPDOWN_DEF]),
+    ("COMDLG32.OCX", &[&COMMONDIALOG_DEF]),
+    ("MSFLXGRD.OCX", &[&MSFLEXGRID_DEF]),
+    ("MSCOMM32.OCX", &[&MSCOMM_DEF]),
+    ("MSWINSCK.OCX", &[&WINSOCK_DEF]),
+    ("RICHTX32.OCX", &[&RICHTEXTBOX_DEF]),
+    ("TABCTL32.OCX", &[&SSTAB_DEF]),
+    ("MSMASK32.OCX", &[&MASKEDBOX_DEF]),
+];
+
+/// The bundled definitions of the controls in an OCX file (e.g., "MSCOMCTL.OCX"),
+/// matched by file name whatever its folder or case
+pub fn ocx_controls(filename: &str) -> &'static [&'static ControlDef] {
+    let name = filename.rsplit(['\\', '/']).next().unwrap_or(filename).trim();
+    OCX_CONTROLS
+        .iter()
+        .find(|(ocx, _)| ocx.eq_ignore_ascii_case(name))
+        .map(|(_, controls)| *controls)
+        .unwrap_or_default()
+}
@@ -0,0 +1,237 @@
+//! User Control Registry
+//!
+//! UserControls (.ctl files) of the workspace are added to the runtime control
+//! registry, keyed by their `VB_Name`, so that instances placed on forms are
+//! looked up like the built-in controls.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+
+use super::properties::{PropertyDef, PropertyType};
+use super::{register_control, unregister_control, ControlDef, EventDef, MethodDef};
+
+/// A Public member of a UserControl
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UserControlMember {
+    /// A `Property Get` (with or without a `Let`/`Set`)
+    Property { name: String, type_name: Option<String>, read_only: bool },
+    /// A Public Sub or Function
+    Method { name: String, signature: String, return_type: Option<String> },
+    /// A Public Event
+    Event { name: String, parameters: String },
+}
+
+/// A registered control: the members it was built from and its definition
+struct Registration {
+    members: Vec<UserControlMember>,
+    def: &'static ControlDef,
+}
+
+/// Registered user controls indexed by lowercase name
+static USER_CONTROLS: Lazy<RwLock<HashMap<String, Registration>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Properties the container (the extender object) adds to every user control
+static EXTENDER_PROPERTIES: &[PropertyDef] = &[
+    PropertyDef { name: "Name", description: "Returns the name used in code to identify the control", property_type: PropertyType::String, read_only: true, default_value: None, valid_values: &[] },
+    PropertyDef { name: "Index", description: "Returns the number identifying the control in a control array", property_type: PropertyType::Integer, read_only: true, default_value: None, valid_values: &[] },
+    PropertyDef { name: "Left", description: "Returns/sets the distance from the left edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+    PropertyDef { name: "Top", description: "Returns/sets the distance from the top edge of the container", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+    PropertyDef { name: "Width", description: "Returns/sets the width of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+    PropertyDef { name: "Height", description: "Returns/sets the height of the control", property_type: PropertyType::Single, read_only: false, default_value: None, valid_values: &[] },
+    PropertyDef { name: "Visible", description: "Returns/sets whether the control is visible", property_type: PropertyType::Boolean, read_only: false, default_value: Some("True"), valid_values: &[] },
+    PropertyDef { name: "TabIndex", description: "Returns/sets tab order", property_type: PropertyType::Integer, read_only: false, default_value: None, valid_values: &[] },
+    PropertyDef { name: "TabStop", description: "Returns/sets whether Tab can move focus to the control", property_type: PropertyType::Boolean, read_only: false, default_value: Some("True"), valid_values: &[] },
+    PropertyDef { name: "Tag", description: "Returns/sets a user-defined value", property_type: PropertyType::String, read_only: false, default_value: Some(""), valid_values: &[] },
+    PropertyDef { name: "ToolTipText", description: "Returns/sets the tooltip text", property_type: PropertyType::String, read_only: false, default_value: Some(""), valid_values: &[] },
+    PropertyDef { name: "Container", description: "Returns the container of the control", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+    PropertyDef { name: "Parent", description: "Returns the form the control is on", property_type: PropertyType::Object, read_only: true, default_value: None, valid_values: &[] },
+];
+
+/// Methods the extender object adds to every user control
+static EXTENDER_METHODS: &[MethodDef] = &[
+    MethodDef { name: "Move", description: "Moves the control", signature: "Move Left, [Top], [Width], [Height]", return_type: None },
+    MethodDef { name: "SetFocus", description: "Gives focus to the control", signature: "SetFocus", return_type: None },
+    MethodDef { name: "ZOrder", description: "Sets the z-order of the control", signature: "ZOrder [Position]", return_type: None },
+];
+
+/// Events the extender object adds to every user control
+static EXTENDER_EVENTS: &[EventDef] = &[
+    EventDef { name: "GotFocus", description: "Occurs when the control receives focus", parameters: "" },
+    EventDef { name: "LostFocus", description: "Occurs when the control loses focus", parameters: "" },
+    EventDef { name: "Validate", description: "Occurs before focus moves to another control", parameters: "Cancel As Boolean" },
+    EventDef { name: "DragDrop", description: "Occurs when a drag-and-drop operation completes", parameters: "Source As Control, X As Single, Y As Single" },
+    EventDef { name: "DragOver", description: "Occurs during a drag-and-drop operation", parameters: "Source As Control, X As Single, Y As Single, State As Integer" },
+];
+
+/// Register (or update) a user control by its `VB_Name`
+pub fn register_user_control(name: &str, members: Vec<UserControlMember>) {
+    let key = name.to_lowercase();
+    let Ok(mut controls) = USER_CONTROLS.write() else {
+        return;
+    };
+
+    // Definitions are leaked, so only build a new one when the control changed
+    if let Some(existing) = controls.get(&key) {
+        if existing.def.name == name && existing.members == members {
+            return;
+        }
+    }
+
+    let def = register_control(build_control_def(name, &members));
+    controls.insert(key, Registration { members, def });
+}
+
+/// Remove a user control from the registry
+pub fn unregister_user_control(name: &str) {
+    let removed = USER_CONTROLS
+        .write()
+        .ok()
+        .and_then(|mut controls| controls.remove(&name.to_lowercase()));
+    if removed.is_some() {
+        unregister_control(name);
+    }
+}
+
+/// Get a registered user control by name (case-insensitive)
+pub fn get_user_control(name: &str) -> Option<&'static ControlDef> {
+    let controls = USER_CONTROLS.read().ok()?;
+    controls.get(&name.to_lowercase()).map(|registration| registration.def)
+}
+
+/// Build the definition of a user control from its members and the extender's
+fn build_control_def(name: &str, members: &[UserControlMember]) -> ControlDef {
+    let mut properties = EXTENDER_PROPERTIES.to_vec();
+    let mut methods = EXTENDER_METHODS.to_vec();
+    let mut events = EXTENDER_EVENTS.to_vec();
+
+    for member in members {
+        match member {
+            UserControlMember::Property { name: prop, type_name, read_only } => {
+                // The control's own property hides an extender property of the same name
+                properties.retain(|p| !p.name.eq_ignore_ascii_case(prop));
+                properties.push(PropertyDef {
+                    name: leak(prop),
+                    description: leak(&format!("Property of {}", name)),
+                    property_type: property_type(type_name.as_deref()),
+                    read_only: *read_only,
+                    default_value: None,
+                    valid_values: &[],
+                });
+            }
+            UserControlMember::Method { name: method, signature, return_type } => {
+                methods.retain(|m| !m.name.eq_ignore_ascii_case(method));
+                methods.push(MethodDef {
+                    name: leak(method),
+                    description: leak(&format!("Method of {}", name)),
+                    signature: leak(signature),
+                    return_type: return_type.as_deref().map(leak),
+                });
+            }
+            UserControlMember::Event { name: event, parameters } => {
+                events.retain(|e| !e.name.eq_ignore_ascii_case(event));
+                events.push(EventDef {
+                    name: leak(event),
+                    description: leak(&format!("Event raised by {}", name)),
+                    parameters: leak(parameters),
+                });
+            }
+        }
+    }
+
+    ControlDef {
+        name: leak(name),
+        full_name: leak(name),
+        description: leak(&format!("User control {}", name)),
+        properties: Box::leak(properties.into_boxed_slice()),
+        events: Box::leak(events.into_boxed_slice()),
+        methods: Box::leak(methods.into_boxed_slice()),
+        is_container: false,
+    }
+}
+
+/// The property type for a declared VB6 type name
+fn property_type(type_name: Option<&str>) -> PropertyType {
+    let Some(type_name) = type_name else {
+        return PropertyType::Variant;
+    };
+    match type_name.to_lowercase().as_str() {
+        "string" => PropertyType::String,
+        "integer" | "byte" => PropertyType::Integer,
+        "long" => PropertyType::Long,
+        "single" => PropertyType::Single,
+        "double" => PropertyType::Double,
+        "boolean" => PropertyType::Boolean,
+        "currency" => PropertyType::Currency,
+        "date" => PropertyType::Date,
+        "variant" => PropertyType::Variant,
+        "ole_color" => PropertyType::Color,
+        "stdfont" | "font" => PropertyType::Font,
+        "stdpicture" | "picture" | "ipicturedisp" => PropertyType::Picture,
+        _ => PropertyType::Object,
+    }
+}
+
+fn leak(text: &str) -> &'static str {
+    Box::leak(text.to_string().into_boxed_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controls::{get_control, get_event, get_method, get_property, is_control_event};
+
+    #[test]
+    fn test_register_user_control() {
+        register_user_control(
+            "ucRegistryTest",
+            vec![
+                UserControlMember::Property {
+                    name: "Rows".to_string(),
+                    type_name: Some("Long".to_string()),
+                    read_only: false,
+                },
+                UserControlMember::Property {
+                    name: "Tag".to_string(),
+                    type_name: Some("Variant".to_string()),
+                    read_only: true,
+                },
+                UserControlMember::Method {
+                    name: "Refresh".to_string(),
+                    signature: "Refresh()".to_string(),
+                    return_type: None,
+                },
+                UserControlMember::Event {
+                    name: "RowClick".to_string(),
+                    parameters: "Row As Long".to_string(),
+                },
+            ],
+        );
+
+        let def = get_control("UCREGISTRYTEST").unwrap();
+        assert_eq!(def.name, "ucRegistryTest");
+
+        let rows = get_property("ucRegistryTest", "rows").unwrap();
+        assert_eq!(rows.property_type, PropertyType::Long);
+        // The control's own Tag replaces the extender's
+        let tag = get_property("ucRegistryTest", "Tag").unwrap();
+        assert!(tag.read_only);
+        assert_eq!(def.properties.iter().filter(|p| p.name == "Tag").count(), 1);
+        assert!(get_property("ucRegistryTest", "Left").is_some());
+
+        assert!(get_method("ucRegistryTest", "Refresh").is_some());
+        assert!(get_method("ucRegistryTest", "SetFocus").is_some());
+        assert!(get_event("ucRegistryTest", "RowClick").is_some());
+        assert!(is_control_event("RowClick"));
+
+        // Re-registering unchanged members keeps the same definition
+        let members = USER_CONTROLS.read().unwrap()["ucregistrytest"].members.clone();
+        register_user_control("ucRegistryTest", members);
+        assert!(std::ptr::eq(def, get_user_control("ucRegistryTest").unwrap()));
+
+        unregister_user_control("ucRegistryTest");
+        assert!(get_control("ucRegistryTest").is_none());
+    }
+}
@@ -5,20 +5,20 @@
 use ropey::Rope;
 use tower_lsp::lsp_types::Position;
 
-/// Convert LSP Position to byte offset in a Rope
+/// Convert LSP Position (with a UTF-16 column) to a char index in a Rope
 pub fn position_to_offset(rope: &Rope, position: Position) -> Option<usize> {
     let line = position.line as usize;
     if line >= rope.len_lines() {
         return None;
     }
 
-    let line_start = rope.line_to_char(line);
+    let line_start = rope.char_to_utf16_cu(rope.line_to_char(line));
     let offset = line_start + position.character as usize;
 
-    if offset > rope.len_chars() {
+    if offset > rope.len_utf16_cu() {
         None
     } else {
-        Some(offset)
+        Some(rope.utf16_cu_to_char(offset))
     }
 }
 
@@ -0,0 +1,269 @@
+//! Document Colors
+//!
+//! Shows color swatches for the Color properties of form controls and for
+//! colors in code (`RGB(255, 0, 0)`, `QBColor(12)`, `&H00FF8000&`), and
+//! writes back the color picked in the editor in the same notation.
+//!
+//! System colors are previewed with their Windows Classic values, since the
+//! actual color depends on the user's settings.
+
+use tower_lsp::lsp_types::{Color, ColorInformation, ColorPresentation, Range, TextEdit};
+use tree_sitter::{Node, Point, Tree};
+
+use crate::controls::{get_property, PropertyType, VB6Color, VB6_COLOR_CONSTANTS};
+
+/// Find every color in a document
+pub fn document_colors(tree: &Tree, source: &str) -> Vec<ColorInformation> {
+    let mut colors = Vec::new();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        let color = match node.kind() {
+            "form_property_line" => {
+                // Colors of other properties (e.g., a Tag) are just numbers
+                if let Some((value, color)) = property_color(&node, source) {
+                    colors.push(color_information(&value, color));
+                }
+                continue;
+            }
+            "call_expression" => call_color(&node, source),
+            "color_literal" => code_literal_color(node_text(&node, source)),
+            _ => None,
+        };
+        match color {
+            Some(color) => colors.push(color_information(&node, color)),
+            None => {
+                let mut cursor = node.walk();
+                stack.extend(node.named_children(&mut cursor));
+            }
+        }
+    }
+
+    colors.sort_by_key(|info| (info.range.start.line, info.range.start.character));
+    colors
+}
+
+/// Ways to write a picked color in place of the color at `range`.
+///
+/// Form files only accept `&H00BBGGRR&`; in code, the notation already used
+/// comes first, followed by the others.
+pub fn color_presentations(
+    tree: &Tree,
+    source: &str,
+    color: Color,
+    range: Range,
+) -> Vec<ColorPresentation> {
+    let [red, green, blue] = [color.red, color.green, color.blue].map(to_byte);
+    let picked = VB6Color::Rgb { red, green, blue };
+    let hex = format!("&H{:08X}&", picked.to_u32());
+
+    let start = Point::new(range.start.line as usize, range.start.character as usize);
+    let node = tree.root_node().descendant_for_point_range(start, start);
+    let in_form = std::iter::successors(node, |n| n.parent())
+        .any(|n| n.kind() == "form_property_line");
+    if in_form {
+        return vec![presentation(hex, range)];
+    }
+
+    let rgb = format!("RGB({}, {}, {})", red, green, blue);
+    let mut texts = match current_text(source, range) {
+        Some(text) if text.to_lowercase().starts_with("rgb") => vec![rgb, hex],
+        _ => vec![hex, rgb],
+    };
+    if let Some((name, _)) = VB6_COLOR_CONSTANTS.iter().find(|(_, v)| *v == picked.to_u32()) {
+        texts.push(name.to_string());
+    }
+    texts.into_iter().map(|text| presentation(text, range)).collect()
+}
+
+/// The node holding the value of a Color property, and its color
+fn property_color<'t>(line: &Node<'t>, source: &str) -> Option<(Node<'t>, VB6Color)> {
+    let block = line.parent().and_then(|element| element.parent())?;
+    let control_type = block
+        .child_by_field_name("type")
+        .map(|t| node_text(&t, source))
+        .and_then(|t| t.rsplit('.').next())?;
+    let name = node_text(&line.named_child(0)?, source);
+    if get_property(control_type, name)?.property_type != PropertyType::Color {
+        return None;
+    }
+
+    let value = line.named_child(1).filter(|n| n.kind() == "form_property_value")?;
+    let value = value.named_child(0).unwrap_or(value);
+    let color = VB6Color::parse_literal(node_text(&value, source)).ok()?;
+    Some((value, color))
+}
+
+/// `RGB` or `QBColor` called with literal arguments
+fn call_color(node: &Node, source: &str) -> Option<VB6Color> {
+    let function = node_text(&node.child_by_field_name("function")?, source).to_lowercase();
+    let mut cursor = node.walk();
+    let arguments = node
+        .named_children(&mut cursor)
+        .find(|child| child.kind() == "argument_list")?
+        .named_child(0)?;
+    let mut cursor = arguments.walk();
+    let values: Vec<u32> = arguments
+        .named_children(&mut cursor)
+        .map(|argument| node_text(&argument, source).trim().parse().ok())
+        .collect::<Option<_>>()?;
+
+    match (function.as_str(), values.as_slice()) {
+        ("rgb", &[red, green, blue]) => Some(VB6Color::Rgb {
+            red: red.try_into().ok()?,
+            green: green.try_into().ok()?,
+            blue: blue.try_into().ok()?,
+        }),
+        ("qbcolor", &[number]) => VB6Color::qb_color(number),
+        _ => None,
+    }
+}
+
+/// A hex literal in code that looks like a color: six digits (`&HFF8000&`),
+/// or eight with a zero high byte or naming a system color
+fn code_literal_color(text: &str) -> Option<VB6Color> {
+    let digits = text.get(2..)?.trim_end_matches('&');
+    let color = VB6Color::parse_literal(text).ok()?;
+    match digits.len() {
+        6 => Some(color),
+        8 if digits.starts_with("00") || color.is_system_color() => Some(color),
+        _ => None,
+    }
+}
+
+fn color_information(node: &Node, color: VB6Color) -> ColorInformation {
+    let (red, green, blue) = color.to_rgb();
+    ColorInformation {
+        range: Range {
+            start: to_position(node.start_position()),
+            end: to_position(node.end_position()),
+        },
+        color: Color {
+            red: red as f32 / 255.0,
+            green: green as f32 / 255.0,
+            blue: blue as f32 / 255.0,
+            alpha: 1.0,
+        },
+    }
+}
+
+fn presentation(text: String, range: Range) -> ColorPresentation {
+    ColorPresentation {
+        label: text.clone(),
+        text_edit: Some(TextEdit {
+            range,
+            new_text: text,
+        }),
+        additional_text_edits: None,
+    }
+}
+
+fn current_text(source: &str, range: Range) -> Option<&str> {
+    if range.start.line != range.end.line {
+        return None;
+    }
+    let line = source.lines().nth(range.start.line as usize)?;
+    line.get(range.start.character as usize..range.end.character as usize)
+}
+
+fn to_byte(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn to_position(point: Point) -> tower_lsp::lsp_types::Position {
+    tower_lsp::lsp_types::Position {
+        line: point.row as u32,
+        character: point.column as u32,
+    }
+}
+
+fn node_text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+    use tower_lsp::lsp_types::Position;
+
+    const FORM: &str = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   BackColor       =   &H8000000F&\n\
+   Begin VB.Label lblTitle\n\
+      ForeColor       =   &H00FF8000&\n\
+      Tag             =   \"&H00FF8000&\"\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private Sub Form_Load()\n\
+    lblTitle.BackColor = RGB(255, 0, 128)\n\
+    lblTitle.ForeColor = QBColor(12)\n\
+    Me.BackColor = &HFF00&\n\
+    mask = &HFF&\n\
+    x = RGB(r, 0, 0)\n\
+    y = RGB(300, 0, 0)\n\
+End Sub\n";
+
+    fn parse(source: &str) -> Tree {
+        TreeSitterVb6Parser::new().unwrap().parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn test_document_colors() {
+        let tree = parse(FORM);
+        let colors: Vec<(u32, u32, [u8; 3])> = document_colors(&tree, FORM)
+            .into_iter()
+            .map(|info| {
+                let color = [info.color.red, info.color.green, info.color.blue].map(to_byte);
+                (info.range.start.line, info.range.start.character, color)
+            })
+            .collect();
+
+        assert_eq!(
+            colors,
+            vec![
+                (2, 20, [212, 208, 200]),
+                (4, 20, [0, 128, 255]),
+                (10, 21, [255, 0, 128]),
+                (11, 21, [255, 0, 0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_color_presentations() {
+        let tree = parse(FORM);
+        let orange = Color {
+            red: 1.0,
+            green: 0.5,
+            blue: 0.0,
+            alpha: 1.0,
+        };
+        let range = |line, start, end| Range {
+            start: Position { line, character: start },
+            end: Position { line, character: end },
+        };
+        let labels = |range| -> Vec<String> {
+            color_presentations(&tree, FORM, orange, range)
+                .into_iter()
+                .map(|p| p.text_edit.unwrap().new_text)
+                .collect()
+        };
+
+        assert_eq!(labels(range(4, 20, 31)), vec!["&H000080FF&"]);
+        assert_eq!(labels(range(10, 21, 37)), vec!["RGB(255, 128, 0)", "&H000080FF&"]);
+
+        let red = Color {
+            red: 1.0,
+            green: 0.0,
+            blue: 0.0,
+            alpha: 1.0,
+        };
+        let texts: Vec<String> = color_presentations(&tree, FORM, red, range(11, 21, 32))
+            .into_iter()
+            .map(|p| p.label)
+            .collect();
+        assert_eq!(texts, vec!["&H000000FF&", "RGB(255, 0, 0)", "vbRed"]);
+    }
+}
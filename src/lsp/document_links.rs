@@ -0,0 +1,242 @@
+//! Document Links
+//!
+//! Makes member paths in `.vbp` files and `Declare ... Lib "..."` library
+//! names clickable.
+
+use std::path::Path;
+
+use tower_lsp::lsp_types::{DocumentLink, Position, Range, Url};
+use tree_sitter::{Node, Tree};
+
+use crate::workspace::Vb6Project;
+
+/// Libraries shipped with Windows, linked to the Win32 API documentation
+const SYSTEM_LIBRARIES: &[&str] = &[
+    "advapi32", "comctl32", "comdlg32", "gdi32", "kernel32", "mpr", "msvbvm60", "netapi32",
+    "ntdll", "ole32", "oleaut32", "shell32", "shlwapi", "urlmon", "user32", "version",
+    "winspool.drv", "wininet", "winmm", "ws2_32", "wsock32",
+];
+
+/// Link each member entry of a VBP file to the file it names
+pub fn vbp_links(project: &Vb6Project, source: &str) -> Vec<DocumentLink> {
+    let lines: Vec<&str> = source.lines().collect();
+
+    project
+        .source_files()
+        .filter_map(|member| {
+            let range = member_path_range(lines.get(member.line)?, member.line)?;
+            let target = project
+                .member_file(member)
+                .and_then(|path| Url::from_file_path(path).ok());
+            let tooltip = match target {
+                Some(_) => None,
+                None => Some(format!("File not found: {}", member.relative_path.display())),
+            };
+            Some(DocumentLink {
+                range,
+                target,
+                tooltip,
+                data: None,
+            })
+        })
+        .collect()
+}
+
+/// Range of the path in a `Kind=Name; Path` or `Kind=Path` entry
+fn member_path_range(line: &str, row: usize) -> Option<Range> {
+    let value_start = line.find('=')? + 1;
+    let path_start = match line[value_start..].find(';') {
+        Some(semicolon) => value_start + semicolon + 1,
+        None => value_start,
+    };
+    let value = &line[path_start..];
+    let path = value.trim();
+    if path.is_empty() {
+        return None;
+    }
+
+    let start = line[..path_start + value.len() - value.trim_start().len()].chars().count();
+    let end = start + path.chars().count();
+    Some(Range {
+        start: Position::new(row as u32, start as u32),
+        end: Position::new(row as u32, end as u32),
+    })
+}
+
+/// Link the library of each `Declare` statement.
+///
+/// A library next to the source file links to that file, a Windows system
+/// library links to the documentation of the declared entry point, and
+/// anything else gets a link without a target explaining why.
+pub fn declare_links(tree: &Tree, source: &str, file_dir: Option<&Path>) -> Vec<DocumentLink> {
+    let mut links = Vec::new();
+    collect_declare_links(&tree.root_node(), source, file_dir, &mut links);
+    links
+}
+
+fn collect_declare_links(
+    node: &Node,
+    source: &str,
+    file_dir: Option<&Path>,
+    links: &mut Vec<DocumentLink>,
+) {
+    if node.kind() == "declare_statement" {
+        if let Some(link) = declare_link(node, source, file_dir) {
+            links.push(link);
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_declare_links(&child, source, file_dir, links);
+    }
+}
+
+fn declare_link(node: &Node, source: &str, file_dir: Option<&Path>) -> Option<DocumentLink> {
+    // The Lib string comes first, followed by the optional Alias string
+    let mut cursor = node.walk();
+    let mut strings = node
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "string_literal");
+    let lib_node = strings.next()?;
+    let alias = strings.next().map(|alias| unquote(&alias, source));
+
+    let library = unquote(&lib_node, source);
+    if library.is_empty() {
+        return None;
+    }
+    let entry_point = alias
+        .filter(|alias| !alias.is_empty() && !alias.starts_with('#'))
+        .or_else(|| {
+            node.child_by_field_name("name")
+                .and_then(|name| name.utf8_text(source.as_bytes()).ok())
+        });
+
+    let (target, tooltip) = match library_target(library, entry_point, file_dir) {
+        Some(target) => (Some(target), None),
+        None => (None, Some(format!("Library not found: {}", library))),
+    };
+
+    // Link the text between the quotes
+    let start = lib_node.start_position();
+    let end = lib_node.end_position();
+    Some(DocumentLink {
+        range: Range {
+            start: Position::new(start.row as u32, start.column as u32 + 1),
+            end: Position::new(end.row as u32, (end.column as u32).saturating_sub(1)),
+        },
+        target,
+        tooltip,
+        data: None,
+    })
+}
+
+/// Where a `Lib` name points: a local file if one exists, otherwise the docs
+/// of a system library
+fn library_target(
+    library: &str,
+    entry_point: Option<&str>,
+    file_dir: Option<&Path>,
+) -> Option<Url> {
+    let file_name = if Path::new(library).extension().is_some() {
+        library.to_string()
+    } else {
+        format!("{}.dll", library)
+    };
+
+    if let Some(dir) = file_dir {
+        let local = dir.join(file_name.replace('\\', "/"));
+        if local.is_file() {
+            return Url::from_file_path(local).ok();
+        }
+    }
+
+    let base = library.to_lowercase();
+    let base = base.strip_suffix(".dll").unwrap_or(&base);
+    if !SYSTEM_LIBRARIES.contains(&base) {
+        return None;
+    }
+
+    let mut url = Url::parse("https://learn.microsoft.com/en-us/search/").ok()?;
+    url.query_pairs_mut().append_pair("terms", entry_point.unwrap_or(base));
+    Some(url)
+}
+
+/// Text of a string literal without its quotes
+fn unquote<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes())
+        .unwrap_or("")
+        .trim_matches('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+    use crate::workspace::VbpFile;
+
+    fn links_for(source: &str, file_dir: Option<&Path>) -> Vec<DocumentLink> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        declare_links(&tree, source, file_dir)
+    }
+
+    #[test]
+    fn test_vbp_member_links() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_links_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("Forms")).unwrap();
+        std::fs::write(dir.join("ModMain.bas"), "").unwrap();
+        std::fs::write(dir.join("Forms").join("FrmMain.frm"), "").unwrap();
+
+        let content = "Type=Exe\n\
+Module=ModMain; ModMain.bas\n\
+Form=Forms\\frmMain.frm\n\
+Class=clsGone; clsGone.cls\n";
+        let vbp = VbpFile::parse_content(&dir.join("Test.vbp"), content).unwrap();
+        let mut links = vbp_links(&Vb6Project::from_parsed_vbp(vbp), content);
+        links.sort_by_key(|link| link.range.start.line);
+
+        let module = links[0].target.as_ref().unwrap().to_file_path().unwrap();
+        let form = links[1].target.as_ref().unwrap().to_file_path().unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].range, Range::new(Position::new(1, 16), Position::new(1, 27)));
+        assert_eq!(module.file_name().unwrap(), "ModMain.bas");
+        assert_eq!(links[1].range, Range::new(Position::new(2, 5), Position::new(2, 22)));
+        assert_eq!(form.file_name().unwrap(), "FrmMain.frm");
+        assert!(links[2].target.is_none());
+        assert_eq!(links[2].tooltip.as_deref(), Some("File not found: clsGone.cls"));
+    }
+
+    #[test]
+    fn test_declare_links() {
+        let source = "Private Declare Function GetWindowText Lib \"user32\" Alias \"GetWindowTextA\" \
+(ByVal hwnd As Long, ByVal s As String, ByVal n As Long) As Long\n\
+Private Declare Sub DoWork Lib \"mylib.dll\" ()\n";
+        let links = links_for(source, None);
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].range, Range::new(Position::new(0, 44), Position::new(0, 50)));
+        assert_eq!(
+            links[0].target.as_ref().map(Url::as_str),
+            Some("https://learn.microsoft.com/en-us/search/?terms=GetWindowTextA")
+        );
+        assert!(links[1].target.is_none());
+        assert_eq!(links[1].tooltip.as_deref(), Some("Library not found: mylib.dll"));
+    }
+
+    #[test]
+    fn test_declare_link_to_local_library() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_dll_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("helper.dll"), "").unwrap();
+
+        let links = links_for("Declare Sub Init Lib \"helper\" ()\n", Some(&dir));
+        std::fs::remove_dir_all(&dir).ok();
+
+        let target = links[0].target.as_ref().unwrap().to_file_path().unwrap();
+        assert_eq!(target, dir.join("helper.dll"));
+    }
+}
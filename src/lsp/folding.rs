@@ -0,0 +1,191 @@
+//! Folding Ranges
+//!
+//! Computes foldable regions for procedures, block statements, conditional
+//! compilation, comment blocks, and `'#Region` markers.
+
+use tower_lsp::lsp_types::{FoldingRange, FoldingRangeKind};
+use tree_sitter::{Node, Tree};
+
+/// Node kinds that fold from their first to their last line
+const FOLDABLE_KINDS: &[&str] = &[
+    "sub_declaration",
+    "function_declaration",
+    "property_declaration",
+    "type_declaration",
+    "enum_declaration",
+    "form_block",
+    "if_statement",
+    "elseif_clause",
+    "else_clause",
+    "for_statement",
+    "for_each_statement",
+    "do_statement",
+    "while_statement",
+    "with_statement",
+    "select_statement",
+    "case_clause",
+    "case_else_clause",
+    "preproc_if",
+    "preproc_elseif",
+    "preproc_else",
+    "preproc_if_statement",
+    "preproc_elseif_statement",
+    "preproc_else_statement",
+];
+
+/// Minimum number of consecutive comment lines that form a foldable block
+const MIN_COMMENT_BLOCK: usize = 3;
+
+/// Compute all folding ranges for a document
+pub fn folding_ranges(tree: &Tree, source: &str) -> Vec<FoldingRange> {
+    let mut ranges = Vec::new();
+    collect_node_ranges(&tree.root_node(), &mut ranges);
+
+    let lines: Vec<&str> = source.lines().collect();
+    collect_comment_ranges(&lines, &mut ranges);
+    collect_region_ranges(&lines, &mut ranges);
+
+    ranges.sort_by_key(|r| (r.start_line, r.end_line));
+    ranges
+}
+
+/// Fold multi-line declarations and block statements
+fn collect_node_ranges(node: &Node, ranges: &mut Vec<FoldingRange>) {
+    if FOLDABLE_KINDS.contains(&node.kind()) {
+        let start = node.start_position().row;
+        let end = last_row(node);
+        if end > start {
+            ranges.push(FoldingRange {
+                start_line: start as u32,
+                end_line: end as u32,
+                ..Default::default()
+            });
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_node_ranges(&child, ranges);
+    }
+}
+
+/// Last row containing text of a node (statements end with their newline terminator)
+fn last_row(node: &Node) -> usize {
+    let start = node.start_position();
+    let end = node.end_position();
+    if end.column == 0 && end.row > start.row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Fold runs of consecutive `'` comment lines
+fn collect_comment_ranges(lines: &[&str], ranges: &mut Vec<FoldingRange>) {
+    let mut run_start: Option<usize> = None;
+
+    // A trailing empty line closes the final run
+    for (i, line) in lines.iter().chain(std::iter::once(&"")).enumerate() {
+        let trimmed = line.trim_start();
+        let is_comment = trimmed.starts_with('\'') && region_marker(trimmed).is_none();
+
+        match (is_comment, run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                if i - start >= MIN_COMMENT_BLOCK {
+                    ranges.push(FoldingRange {
+                        start_line: start as u32,
+                        end_line: (i - 1) as u32,
+                        kind: Some(FoldingRangeKind::Comment),
+                        ..Default::default()
+                    });
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A `'#Region` or `'#End Region` marker comment
+#[derive(Debug, PartialEq, Eq)]
+enum RegionMarker {
+    Start,
+    End,
+}
+
+/// Recognize a region marker in a left-trimmed line
+fn region_marker(trimmed: &str) -> Option<RegionMarker> {
+    let text = trimmed.strip_prefix('\'')?.trim_start().to_lowercase();
+    if text.starts_with("#region") {
+        Some(RegionMarker::Start)
+    } else if text.starts_with("#end region") || text.starts_with("#endregion") {
+        Some(RegionMarker::End)
+    } else {
+        None
+    }
+}
+
+/// Fold matching `'#Region` / `'#End Region` marker comments
+fn collect_region_ranges(lines: &[&str], ranges: &mut Vec<FoldingRange>) {
+    let mut open: Vec<usize> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        match region_marker(line.trim_start()) {
+            Some(RegionMarker::Start) => open.push(i),
+            Some(RegionMarker::End) => {
+                if let Some(start) = open.pop() {
+                    ranges.push(FoldingRange {
+                        start_line: start as u32,
+                        end_line: i as u32,
+                        kind: Some(FoldingRangeKind::Region),
+                        ..Default::default()
+                    });
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn ranges_for(source: &str) -> Vec<(u32, u32, Option<FoldingRangeKind>)> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        folding_ranges(&tree, source)
+            .into_iter()
+            .map(|r| (r.start_line, r.end_line, r.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_procedure_and_blocks() {
+        let source = "Sub Main()\n    If x Then\n        y = 1\n    End If\n    With obj\n        .Value = 1\n    End With\nEnd Sub\n";
+        let ranges = ranges_for(source);
+
+        assert!(ranges.contains(&(0, 7, None)));
+        assert!(ranges.contains(&(1, 3, None)));
+        assert!(ranges.contains(&(4, 6, None)));
+    }
+
+    #[test]
+    fn test_comment_blocks() {
+        let source = "' one\n' two\n' three\nx = 1\n' short\n' block\n";
+        let ranges = ranges_for(source);
+
+        assert_eq!(ranges, vec![(0, 2, Some(FoldingRangeKind::Comment))]);
+    }
+
+    #[test]
+    fn test_regions_and_conditional_compilation() {
+        let source = "'#Region \"Helpers\"\n#If Win32 Then\nPrivate x As Long\n#End If\n'#End Region\n";
+        let ranges = ranges_for(source);
+
+        assert!(ranges.contains(&(0, 4, Some(FoldingRangeKind::Region))));
+        assert!(ranges.contains(&(1, 3, None)));
+    }
+}
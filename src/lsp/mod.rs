@@ -4,21 +4,78 @@
 
 mod capabilities;
 mod document;
+mod document_colors;
+mod document_links;
+mod folding;
 mod handlers;
-
-use std::sync::{Arc, RwLock};
+mod progress;
+mod selection_range;
+mod semantic_tokens;
+mod settings;
+mod vbp_completion;
+mod vbp_hover;
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
+use futures::{Stream, StreamExt};
 use ropey::Rope;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
-use crate::analysis::{build_symbol_table, Analyzer, SymbolTable};
-use crate::claude::ClaudeClient;
-use crate::parser::Vb6Parser;
+use crate::analysis::{
+    build_symbol_table_excluding, call_hierarchy_item, update_symbol_table, Analyzer,
+    CompletionData, SourcePosition, SymbolTable, Visibility,
+};
+use crate::claude::{get_code_context, ClaudeClient, StreamError, DEFAULT_MAX_TOKENS, DEFAULT_MODEL};
+use crate::parser::{
+    self, Conversion, FormatOptions, InactiveRegion, ParseError, Vb6FileKind, Vb6Parser,
+};
 use crate::utils::Encoding;
-use crate::workspace::WorkspaceManager;
+use progress::Progress;
+use settings::Settings;
+use crate::workspace::{
+    collect_diagnostics, dependency_graph, syntax_error, Debouncer, FileChange, FileEvent,
+    FsWatcher, ParsedFile, TypeLibReference, Vb6Project, VbpFile, WorkspaceManager,
+};
+
+/// Command that explains the selected code with Claude (arguments: uri, range)
+const EXPLAIN_CODE_COMMAND: &str = "vb6.explainCode";
+
+/// Command that writes documentation for the selected code (arguments: uri, range)
+const GENERATE_DOCUMENTATION_COMMAND: &str = "vb6.generateDocumentation";
+
+/// Command that converts the selected code (arguments: uri, range, optional target language)
+const SUGGEST_MIGRATION_COMMAND: &str = "vb6.suggestMigration";
+
+/// Command that returns the workspace's module dependency graph as JSON
+const DEPENDENCY_GRAPH_COMMAND: &str = "vb6.dependencyGraph";
+
+/// Command that shows and returns the workspace's size: projects, files, lines, symbols
+const WORKSPACE_STATS_COMMAND: &str = "vb6.workspaceStats";
+
+/// Commands backed by the Claude client
+const CLAUDE_COMMANDS: &[&str] = &[
+    EXPLAIN_CODE_COMMAND,
+    GENERATE_DOCUMENTATION_COMMAND,
+    SUGGEST_MIGRATION_COMMAND,
+];
+
+/// Lines of context around the cursor to explain when nothing is selected
+const EXPLAIN_CONTEXT_LINES: usize = 10;
+
+/// How often pending file changes are checked for having settled
+const FILE_EVENT_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a file must stay unchanged before its change is applied
+const FILE_CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Glob for the files the client is asked to watch
+const WATCHED_FILES_GLOB: &str = "**/*.{vbp,bas,cls,frm,ctl,dob,pag,dsr}";
 
 /// Document information stored in memory
 pub struct Document {
@@ -28,10 +85,16 @@ pub struct Document {
     pub version: i32,
     /// Detected encoding (UTF-8 or Windows-1252)
     pub encoding: Encoding,
+    /// File type, from the extension
+    pub kind: Vb6FileKind,
     /// Parsed AST (if available)
     pub ast: Option<crate::parser::Vb6Ast>,
     /// Tree-sitter tree for incremental parsing
     pub tree: Option<tree_sitter::Tree>,
+    /// Code excluded by `#If` directives at the last parse
+    pub inactive: Vec<InactiveRegion>,
+    /// Edits applied to the tree since the symbol table was built
+    pub edits: Vec<tree_sitter::InputEdit>,
     /// Symbol table (if available)
     pub symbol_table: Option<SymbolTable>,
 }
@@ -41,6 +104,7 @@ impl std::fmt::Debug for Document {
         f.debug_struct("Document")
             .field("content", &self.content)
             .field("version", &self.version)
+            .field("kind", &self.kind)
             .field("ast", &self.ast)
             .field("tree", &self.tree.as_ref().map(|_| "..."))
             .field("symbol_table", &self.symbol_table.as_ref().map(|t| format!("{} symbols", t.symbol_count())))
@@ -48,28 +112,107 @@ impl std::fmt::Debug for Document {
     }
 }
 
+impl Document {
+    /// Apply an LSP content change to the rope, keeping the tree-sitter tree in step.
+    ///
+    /// Ranged edits are fed to the stored tree so the next parse can reuse unchanged
+    /// subtrees; a full replacement drops the tree so the next parse starts fresh.
+    ///
+    /// Measured on a generated 5,000-line form (release build), tree-sitter re-parses
+    /// a one-line edit in about 67 ms against 84 ms from scratch. The gain is modest
+    /// because nodes built while the grammar's GLR conflicts are being resolved can't
+    /// be reused.
+    pub fn apply_change(&mut self, change: TextDocumentContentChangeEvent) {
+        let Some(range) = change.range else {
+            self.content = Rope::from_str(&change.text);
+            self.tree = None;
+            self.edits.clear();
+            return;
+        };
+
+        let start_idx =
+            self.content.line_to_char(range.start.line as usize) + range.start.character as usize;
+        let end_idx =
+            self.content.line_to_char(range.end.line as usize) + range.end.character as usize;
+        let start_byte = self.content.char_to_byte(start_idx);
+        let old_end_byte = self.content.char_to_byte(end_idx);
+        let start_position = byte_point(&self.content, start_byte);
+        let old_end_position = byte_point(&self.content, old_end_byte);
+
+        self.content.remove(start_idx..end_idx);
+        self.content.insert(start_idx, &change.text);
+
+        if let Some(tree) = self.tree.as_mut() {
+            let new_end_byte = start_byte + change.text.len();
+            let edit = tree_sitter::InputEdit {
+                start_byte,
+                old_end_byte,
+                new_end_byte,
+                start_position,
+                old_end_position,
+                new_end_position: byte_point(&self.content, new_end_byte),
+            };
+            tree.edit(&edit);
+            self.edits.push(edit);
+        }
+    }
+}
+
+/// Convert a byte offset in a rope to a tree-sitter point (row, byte column)
+fn byte_point(rope: &Rope, byte: usize) -> tree_sitter::Point {
+    let row = rope.byte_to_line(byte);
+    tree_sitter::Point {
+        row,
+        column: byte - rope.line_to_byte(row),
+    }
+}
+
 /// The VB6 Language Server
+#[derive(Clone)]
 pub struct Vb6LanguageServer {
     /// LSP client for sending notifications
     client: Client,
     /// Open documents
-    documents: DashMap<Url, Document>,
+    documents: Arc<DashMap<Url, Document>>,
     /// VB6 Parser (uses RwLock for incremental parsing support)
     parser: Arc<RwLock<Vb6Parser>>,
     /// Code analyzer
     analyzer: Arc<Analyzer>,
     /// Claude AI client (optional)
     claude: Option<Arc<ClaudeClient>>,
+    /// Client settings, replaced whenever the configuration changes
+    settings: Arc<RwLock<Settings>>,
     /// Workspace manager for multi-project support
     workspace: Arc<RwLock<WorkspaceManager>>,
+    /// File changes from disk and from the client, waiting to settle
+    file_events: Arc<Mutex<Debouncer>>,
+    /// Watches the workspace roots for changes made outside the editor
+    watcher: Arc<Mutex<Option<FsWatcher>>>,
+    /// Signalled on shutdown to stop the background tasks
+    shutdown: Arc<tokio::sync::Notify>,
+    /// Whether the client accepts nested document symbols, negotiated at initialize
+    hierarchical_document_symbols: Arc<AtomicBool>,
 }
 
 impl Vb6LanguageServer {
     pub fn new(client: Client) -> Self {
         // Try to create Claude client if API key is available
-        let claude = std::env::var("ANTHROPIC_API_KEY")
-            .ok()
-            .map(|key| Arc::new(ClaudeClient::new(key)));
+        let claude = std::env::var("ANTHROPIC_API_KEY").ok().map(|key| {
+            let model =
+                std::env::var("ANTHROPIC_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.to_string());
+            let max_tokens = match std::env::var("CLAUDE_MAX_TOKENS") {
+                Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+                    tracing::warn!(
+                        "Invalid CLAUDE_MAX_TOKENS '{}', using {}",
+                        value,
+                        DEFAULT_MAX_TOKENS
+                    );
+                    DEFAULT_MAX_TOKENS
+                }),
+                Err(_) => DEFAULT_MAX_TOKENS,
+            };
+            Arc::new(ClaudeClient::with_config(key, model, max_tokens))
+        });
 
         if claude.is_some() {
             tracing::info!("Claude AI integration enabled");
@@ -79,11 +222,16 @@ impl Vb6LanguageServer {
 
         Self {
             client,
-            documents: DashMap::new(),
+            documents: Arc::new(DashMap::new()),
             parser: Arc::new(RwLock::new(Vb6Parser::new())),
             analyzer: Arc::new(Analyzer::new()),
             claude,
+            settings: Arc::new(RwLock::new(Settings::default())),
             workspace: Arc::new(RwLock::new(WorkspaceManager::new())),
+            file_events: Arc::new(Mutex::new(Debouncer::new(FILE_CHANGE_DEBOUNCE))),
+            watcher: Arc::new(Mutex::new(None)),
+            shutdown: Arc::new(tokio::sync::Notify::new()),
+            hierarchical_document_symbols: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -92,47 +240,362 @@ impl Vb6LanguageServer {
         self.documents.get(uri)
     }
 
+    /// Apply client settings (from initialization options or workspace configuration).
+    ///
+    /// Invalid settings are logged and the current ones kept.
+    fn apply_settings(&self, value: &serde_json::Value) {
+        let settings = match Settings::from_value(value) {
+            Ok(settings) => settings,
+            Err(error) => {
+                tracing::warn!("Ignoring invalid settings: {}", error);
+                return;
+            }
+        };
+
+        self.analyzer.set_allowed_identifiers(&settings.allowed_identifiers);
+        self.parser
+            .write()
+            .unwrap()
+            .set_conditional_compilation(settings.conditional_compilation.clone());
+
+        if let Some(claude) = &self.claude {
+            if let Some(model) = &settings.claude.model {
+                claude.set_model(model.clone());
+            }
+            if let Some(max_tokens) = settings.claude.max_tokens {
+                claude.set_max_tokens(max_tokens);
+            }
+        }
+
+        *self.settings.write().unwrap() = settings;
+    }
+
+    /// The Claude client, unless there is no API key or the settings disable it
+    fn claude(&self) -> Option<Arc<ClaudeClient>> {
+        let enabled = self.settings.read().unwrap().claude.enabled;
+        self.claude.clone().filter(|_| enabled)
+    }
+
+    /// Formatter options from the editor's, with the configured indentation
+    fn format_options(&self, options: &FormattingOptions) -> FormatOptions {
+        let mut format = format_options(options);
+        self.settings.read().unwrap().apply_format(&mut format);
+        format
+    }
+
+    /// Report VBP entries whose files or references can't be found
+    async fn diagnose_project_file(&self, uri: &Url) {
+        let (Some(doc), Ok(path)) = (self.documents.get(uri), uri.to_file_path()) else {
+            return;
+        };
+        let version = doc.version;
+        let Ok(vbp) = VbpFile::parse_content(&path, &doc.content.to_string()) else {
+            return;
+        };
+
+        let diagnostics = Vb6Project::from_parsed_vbp(vbp)
+            .validate()
+            .into_iter()
+            .map(|problem| {
+                let length = doc
+                    .content
+                    .get_line(problem.line)
+                    .map(|line| line.to_string().trim_end().chars().count())
+                    .unwrap_or(0);
+                Diagnostic {
+                    range: Range {
+                        start: Position::new(problem.line as u32, 0),
+                        end: Position::new(problem.line as u32, length as u32),
+                    },
+                    severity: Some(problem.severity),
+                    message: problem.message,
+                    source: Some("vb6-lsp".to_string()),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        drop(doc);
+
+        self.client
+            .publish_diagnostics(uri.clone(), diagnostics, Some(version))
+            .await;
+    }
+
+    /// Find and load the projects under the workspace roots, reporting each
+    /// project found and loaded through `$/progress`
+    async fn scan_workspace(self, roots: Vec<PathBuf>) {
+        if roots.is_empty() {
+            return;
+        }
+        let progress = Progress::create(self.client.clone(), None, "scan").await;
+        progress.begin("Scanning VB6 projects", Some(0)).await;
+
+        // Walking the folders blocks, so found projects are streamed back from it
+        let mut discovered = Vec::new();
+        for root in roots {
+            let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+            let walk_root = root.clone();
+            let walk = tokio::task::spawn_blocking(move || {
+                WorkspaceManager::scan_for_vbp_files(&walk_root, |path| {
+                    let _ = sender.send(path.to_path_buf());
+                })
+            });
+            while let Some(path) = receiver.recv().await {
+                progress.report(Some(format!("Found {}", path.display())), Some(0)).await;
+            }
+            let found = walk.await.unwrap_or_default();
+            tracing::info!("Discovered {} VBP projects in {}", found.len(), root.display());
+            discovered.extend(found);
+        }
+
+        let total = discovered.len();
+        for (index, vbp_path) in discovered.into_iter().enumerate() {
+            let name = vbp_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let percentage = (index * 100 / total) as u32;
+            let message = format!("Loading {} ({}/{})", name, index + 1, total);
+            progress.report(Some(message), Some(percentage)).await;
+
+            let path = vbp_path.clone();
+            match tokio::task::spawn_blocking(move || Vb6Project::from_vbp(&path)).await {
+                Ok(Ok(project)) => self.workspace.write().unwrap().add_project(&vbp_path, project),
+                Ok(Err(e)) => tracing::warn!("Failed to load VBP {}: {}", vbp_path.display(), e),
+                Err(e) => tracing::warn!("Loading VBP {} panicked: {}", vbp_path.display(), e),
+            }
+        }
+
+        // Parse the members, so that cross-file checks see every file
+        let members = self.workspace.read().unwrap().unloaded_members();
+        let mut parser = Vb6Parser::new();
+        for (index, path) in members.iter().enumerate() {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let percentage = (index * 100 / members.len()) as u32;
+            progress.report(Some(format!("Indexing {}", name)), Some(percentage)).await;
+
+            let result = tokio::task::block_in_place(|| {
+                self.workspace.write().unwrap().reparse_file(path, &mut parser).map(|_| ())
+            });
+            if let Err(e) = result {
+                tracing::debug!("Could not parse {}: {}", path.display(), e);
+            }
+        }
+
+        let noun = if total == 1 { "project" } else { "projects" };
+        progress.end(Some(format!("Loaded {} VB6 {}", total, noun))).await;
+
+        // Documents opened during the scan now have their projects
+        let uris: Vec<Url> = self.documents.iter().map(|entry| entry.key().clone()).collect();
+        for uri in uris {
+            self.parse_and_diagnose(&uri).await;
+        }
+    }
+
+    /// Watch the workspace roots and apply settled file changes until shutdown
+    async fn watch_files(self) {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        match FsWatcher::new(move |event| {
+            let _ = sender.send(event);
+        }) {
+            Ok(watcher) => {
+                *self.watcher.lock().unwrap() = Some(watcher);
+                self.update_watched_roots();
+            }
+            Err(e) => tracing::warn!("Cannot watch files, relying on the client: {}", e),
+        }
+        let mut interval = tokio::time::interval(FILE_EVENT_FLUSH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown.notified() => break,
+                Some(event) = receiver.recv() => {
+                    self.file_events.lock().unwrap().push(event, Instant::now());
+                }
+                _ = interval.tick() => {
+                    let ready = self.file_events.lock().unwrap().take_ready(Instant::now());
+                    if !ready.is_empty() {
+                        self.apply_file_events(ready).await;
+                    }
+                }
+            }
+        }
+        self.watcher.lock().unwrap().take();
+    }
+
+    /// Point the file watcher at the current workspace roots
+    fn update_watched_roots(&self) {
+        let roots = self.workspace.read().unwrap().roots().to_vec();
+        if let Some(watcher) = self.watcher.lock().unwrap().as_mut() {
+            // Watching a folder registers every directory below it
+            tokio::task::block_in_place(|| watcher.set_roots(&roots));
+        }
+    }
+
+    /// Update the workspace for files changed on disk and re-check open documents.
+    ///
+    /// Open source files are owned by the editor, so only their projects are
+    /// refreshed, not their contents.
+    async fn apply_file_events(&self, events: Vec<FileEvent>) {
+        // Read the open documents first: parse_and_diagnose takes the workspace
+        // lock while holding a document, so never wait for a document under it
+        let open: Vec<(Url, std::path::PathBuf)> = self
+            .documents
+            .iter()
+            .map(|entry| entry.key().clone())
+            .filter(|uri| !uri.path().to_lowercase().ends_with(".vbp"))
+            .filter_map(|uri| uri.to_file_path().ok().map(|path| (uri, path)))
+            .collect();
+
+        let affected: Vec<std::path::PathBuf> = {
+            let mut workspace = self.workspace.write().unwrap();
+            let mut parser = None;
+            events
+                .iter()
+                .filter(|event| {
+                    event.is_project_file() || !open.iter().any(|(_, path)| *path == event.path)
+                })
+                .filter_map(|event| {
+                    tracing::debug!("{:?} on disk: {}", event.change, event.path.display());
+                    let affected = workspace.apply_file_event(event)?;
+                    // Keep closed members' names current for cross-file lookups
+                    if !event.is_project_file() && event.change != FileChange::Deleted {
+                        let parser = parser.get_or_insert_with(Vb6Parser::new);
+                        if let Err(e) = workspace.reparse_file(&event.path, parser) {
+                            tracing::debug!("Could not reparse {}: {}", event.path.display(), e);
+                        }
+                    }
+                    Some(affected)
+                })
+                .collect()
+        };
+        if affected.is_empty() {
+            return;
+        }
+
+        // Cross-file names may have changed for every open file of the projects
+        let uris: Vec<Url> = {
+            let workspace = self.workspace.read().unwrap();
+            open.into_iter()
+                .filter(|(_, path)| {
+                    workspace.project_for_file(path).is_none_or(|project| {
+                        affected.iter().any(|vbp| vbp == project.vbp_path())
+                    })
+                })
+                .map(|(uri, _)| uri)
+                .collect()
+        };
+        for uri in uris {
+            self.parse_and_diagnose(&uri).await;
+        }
+    }
+
+    /// Find references to a module-level Public symbol across the workspace.
+    ///
+    /// Works from the declaration or from a use in another file; returns None
+    /// for anything that stays file-local.
+    fn workspace_references(
+        &self,
+        table: &SymbolTable,
+        position: Position,
+    ) -> Option<Vec<Location>> {
+        let workspace = self.workspace.read().unwrap();
+        let pos = SourcePosition::from_lsp(position);
+
+        let (name, kind, defined_in) = match table.symbol_at_position(pos) {
+            Some(symbol) => {
+                let module_level = symbol.scope_id == table.module_scope;
+                if !module_level || symbol.visibility != Visibility::Public {
+                    return None;
+                }
+                (symbol.name.clone(), symbol.kind, table.uri.to_file_path().ok()?)
+            }
+            None => {
+                let path = table.uri.to_file_path().ok()?;
+                let member_use = table.member_uses().iter().find(|m| m.range.contains(pos));
+                let (symbol, defined_in) = match member_use {
+                    // A member named through the module declaring it
+                    Some(member_use) => {
+                        let declaring = workspace.module_symbol_table(&member_use.object, &path)?;
+                        let symbol =
+                            declaring.lookup_symbol(&member_use.member, declaring.module_scope)?;
+                        (symbol, declaring.uri.to_file_path().ok()?)
+                    }
+                    // A use of a symbol declared in another file
+                    None => {
+                        let name = &table
+                            .unresolved_references()
+                            .iter()
+                            .find(|unresolved| unresolved.range.contains(pos))?
+                            .name;
+                        let location = workspace.resolve_symbol(name, &path)?;
+                        let defined_in = location.uri.to_file_path().ok()?;
+                        let declaring = workspace.get_symbol_table(&defined_in)?;
+                        let start = SourcePosition::from_lsp(location.range.start);
+                        (declaring.symbol_at_position(start)?, defined_in)
+                    }
+                };
+                (symbol.name.clone(), symbol.kind, defined_in)
+            }
+        };
+
+        let references = workspace.find_all_references(&name, kind, &defined_in);
+        (!references.is_empty()).then_some(references)
+    }
+
     /// Parse a document and update diagnostics
     async fn parse_and_diagnose(&self, uri: &Url) {
-        if let Some(mut doc) = self.documents.get_mut(uri) {
-            let content = doc.content.to_string();
+        // Project files are checked for missing members instead of parsed as code
+        if uri.path().to_lowercase().ends_with(".vbp") {
+            self.diagnose_project_file(uri).await;
+            return;
+        }
 
-            // Parse the document using tree-sitter
-            let (parse_result, tree) = {
+        if let Some(mut doc) = self.documents.get_mut(uri) {
+            // Parse the document using tree-sitter, reusing its edited tree
+            let old_tree = doc.tree.take();
+            let edits = std::mem::take(&mut doc.edits);
+            // Only the parse needs the shared parser, and it reads the rope's chunks
+            let (tree, constants) = {
                 let mut parser = self.parser.write().unwrap();
-                let result = parser.parse(&content);
-                // Get the tree for symbol table building
-                let tree = parser.get_tree().cloned();
-                (result, tree)
+                let tree = parser.parse_rope(&doc.content, old_tree.as_ref()).cloned();
+                (tree, parser.conditional_compilation().clone())
             };
 
-            match parse_result {
-                Ok(ast) => {
-                    // Get any parse errors for diagnostics
-                    let parse_errors = {
-                        let mut parser = self.parser.write().unwrap();
-                        parser.get_errors(&content)
-                    };
-
-                    // Run analysis
-                    let mut diagnostics = self.analyzer.analyze(&ast);
-
-                    // Add parse errors as diagnostics
-                    for error in parse_errors {
-                        diagnostics.push(Diagnostic {
-                            range: error.range,
-                            severity: Some(DiagnosticSeverity::ERROR),
-                            message: error.message,
-                            source: Some("vb6-lsp".to_string()),
-                            ..Default::default()
-                        });
-                    }
+            // The AST conversion and analysis passes work on the text
+            let parse_result = match tree.as_ref() {
+                Some(tree) => {
+                    let content = doc.content.to_string();
+                    Ok((parser::convert(tree, &content, &constants), content))
+                }
+                None => Err(vec![ParseError {
+                    message: "Failed to parse source".to_string(),
+                    range: Range::default(),
+                }]),
+            };
 
-                    doc.ast = Some(ast);
+            match parse_result {
+                Ok((conversion, content)) => {
+                    let Conversion { mut ast, inactive_regions: inactive, errors: parse_errors } =
+                        conversion;
+                    ast.file_type = doc.kind;
 
                     // Build symbol table from tree-sitter tree
-                    if let Some(ref ts_tree) = tree {
-                        let symbol_table = build_symbol_table(uri.clone(), &content, ts_tree);
+                    let symbol_table = tree.as_ref().map(|ts_tree| {
+                        // Only the procedures touched since the last build are walked again
+                        match (doc.symbol_table.take(), old_tree.as_ref()) {
+                            (Some(previous), Some(old_tree)) if !edits.is_empty() => {
+                                update_symbol_table(
+                                    previous, &edits, old_tree, &content, ts_tree, &inactive,
+                                )
+                            }
+                            _ => build_symbol_table_excluding(
+                                uri.clone(),
+                                &content,
+                                ts_tree,
+                                &inactive,
+                            ),
+                        }
+                    });
+
+                    if let Some(ref symbol_table) = symbol_table {
                         tracing::debug!(
                             "Built symbol table with {} symbols, {} scopes",
                             symbol_table.symbol_count(),
@@ -142,13 +605,35 @@ impl Vb6LanguageServer {
                         // Register with workspace manager for cross-project navigation
                         if let Ok(file_path) = uri.to_file_path() {
                             let mut workspace = self.workspace.write().unwrap();
+                            if doc.kind == Vb6FileKind::Class {
+                                workspace
+                                    .set_class_attributes(&file_path, ast.class_attributes.clone());
+                            }
                             // Clone the symbol table for workspace (document keeps its own copy)
                             workspace.set_symbol_table(&file_path, symbol_table.clone());
                         }
-
-                        doc.symbol_table = Some(symbol_table);
                     }
 
+                    let mut diagnostics = {
+                        let workspace = self.workspace.read().unwrap();
+                        let file = ParsedFile {
+                            uri,
+                            source: &content,
+                            ast: &ast,
+                            errors: &parse_errors,
+                            inactive: &inactive,
+                            symbols: tree.as_ref().zip(symbol_table.as_ref()),
+                        };
+                        collect_diagnostics(&self.analyzer, &workspace, &file)
+                    };
+                    self.settings.read().unwrap().filter_diagnostics(&mut diagnostics);
+
+                    // Keep the tree for semantic tokens
+                    doc.ast = Some(ast);
+                    doc.symbol_table = symbol_table;
+                    doc.tree = tree;
+                    doc.inactive = inactive;
+
                     // Publish diagnostics
                     self.client
                         .publish_diagnostics(uri.clone(), diagnostics, Some(doc.version))
@@ -156,16 +641,8 @@ impl Vb6LanguageServer {
                 }
                 Err(errors) => {
                     // Convert parse errors to diagnostics
-                    let diagnostics: Vec<Diagnostic> = errors
-                        .into_iter()
-                        .map(|e| Diagnostic {
-                            range: e.range,
-                            severity: Some(DiagnosticSeverity::ERROR),
-                            message: e.message,
-                            source: Some("vb6-lsp".to_string()),
-                            ..Default::default()
-                        })
-                        .collect();
+                    let diagnostics: Vec<Diagnostic> =
+                        errors.into_iter().map(syntax_error).collect();
 
                     self.client
                         .publish_diagnostics(uri.clone(), diagnostics, Some(doc.version))
@@ -218,26 +695,114 @@ fn is_identifier_char(c: char) -> bool {
     c.is_alphanumeric() || c == '_'
 }
 
+/// Translate client formatting options (`vb6.splitStatements` is a custom property)
+fn format_options(options: &FormattingOptions) -> FormatOptions {
+    FormatOptions {
+        tab_size: options.tab_size as usize,
+        insert_spaces: options.insert_spaces,
+        split_statements: matches!(
+            options.properties.get("vb6.splitStatements"),
+            Some(FormattingProperty::Bool(true))
+        ),
+        trim_trailing_whitespace: options.trim_trailing_whitespace == Some(true),
+        insert_final_newline: options.insert_final_newline == Some(true),
+        trim_final_newlines: options.trim_final_newlines == Some(true),
+    }
+}
+
+/// The document URI and range a Claude command takes as its first arguments
+fn command_target(
+    arguments: &mut impl Iterator<Item = serde_json::Value>,
+) -> Result<(Url, Range)> {
+    let uri = arguments.next().and_then(|value| serde_json::from_value::<Url>(value).ok());
+    let range = arguments.next().and_then(|value| serde_json::from_value::<Range>(value).ok());
+    match (uri, range) {
+        (Some(uri), Some(range)) => Ok((uri, range)),
+        _ => Err(tower_lsp::jsonrpc::Error::invalid_params(
+            "expected a document URI and a range",
+        )),
+    }
+}
+
+/// The text of a document between two positions, clamped to its end
+fn text_in_range(content: &Rope, range: Range) -> String {
+    let index = |position| {
+        document::position_to_offset(content, position).unwrap_or(content.len_chars())
+    };
+    let (start, end) = (index(range.start), index(range.end));
+    content.slice(start..end.max(start)).to_string()
+}
+
+/// Collect a streamed Claude response, reporting its latest line through `$/progress`,
+/// then show the whole response
+async fn stream_with_progress(
+    client: Client,
+    token: Option<ProgressToken>,
+    title: String,
+    stream: impl Stream<Item = std::result::Result<String, StreamError>>,
+) {
+    let progress = Progress::create(client.clone(), token, "claude").await;
+    progress.begin(title.clone(), None).await;
+
+    let mut text = String::new();
+    let mut error = None;
+    futures::pin_mut!(stream);
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(chunk) => {
+                text.push_str(&chunk);
+                let latest = text.lines().rev().find(|line| !line.trim().is_empty());
+                progress.report(latest.map(str::to_string), None).await;
+            }
+            Err(e) => {
+                error = Some(e.to_string());
+                break;
+            }
+        }
+    }
+
+    progress.end(None).await;
+    match error {
+        Some(error) => {
+            client
+                .show_message(MessageType::ERROR, format!("{} failed: {}", title, error))
+                .await
+        }
+        None => client.show_message(MessageType::INFO, text).await,
+    }
+}
+
 #[tower_lsp::async_trait]
 impl LanguageServer for Vb6LanguageServer {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
         tracing::info!("Initializing VB6 Language Server");
 
-        // Scan workspace folders for VBP projects
+        if let Some(ref options) = params.initialization_options {
+            self.apply_settings(options);
+        }
+
+        let hierarchical = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.document_symbol.as_ref())
+            .and_then(|document_symbol| document_symbol.hierarchical_document_symbol_support)
+            .unwrap_or(false);
+        self.hierarchical_document_symbols.store(hierarchical, Ordering::Relaxed);
+
+        // Workspace folders are scanned for VBP projects once initialized,
+        // when progress can be reported
         if let Some(workspace_folders) = params.workspace_folders {
             let mut workspace = self.workspace.write().unwrap();
             for folder in workspace_folders {
                 if let Ok(path) = folder.uri.to_file_path() {
-                    let discovered = workspace.add_root(path);
-                    tracing::info!("Discovered {} VBP projects in {}", discovered.len(), folder.uri);
+                    workspace.register_root(path);
                 }
             }
         } else if let Some(root_uri) = params.root_uri {
             // Fallback to root_uri if workspace_folders not provided
             if let Ok(path) = root_uri.to_file_path() {
-                let mut workspace = self.workspace.write().unwrap();
-                let discovered = workspace.add_root(path);
-                tracing::info!("Discovered {} VBP projects in root", discovered.len());
+                self.workspace.write().unwrap().register_root(path);
             }
         }
 
@@ -268,20 +833,57 @@ impl LanguageServer for Vb6LanguageServer {
                 // Go to definition
                 definition_provider: Some(OneOf::Left(true)),
 
+                // Go to type definition
+                type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
+
+                // Go to the classes implementing an interface
+                implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+
                 // Find references
                 references_provider: Some(OneOf::Left(true)),
 
+                // Highlight occurrences of the symbol under the cursor
+                document_highlight_provider: Some(OneOf::Left(true)),
+
+                // Call hierarchy (incoming and outgoing procedure calls)
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+
                 // Document symbols (outline)
                 document_symbol_provider: Some(OneOf::Left(true)),
 
                 // Workspace symbols
                 workspace_symbol_provider: Some(OneOf::Left(true)),
 
+                // Workspace folders, watched and scanned as they are added
+                workspace: Some(WorkspaceServerCapabilities {
+                    workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                        supported: Some(true),
+                        change_notifications: Some(OneOf::Left(true)),
+                    }),
+                    file_operations: None,
+                }),
+
                 // Code actions (quick fixes, refactoring)
                 code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
 
+                // Commands (dependency graph, statistics; Claude explanations,
+                // documentation, and migration)
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: [DEPENDENCY_GRAPH_COMMAND, WORKSPACE_STATS_COMMAND]
+                        .iter()
+                        .chain(CLAUDE_COMMANDS)
+                        .map(|c| c.to_string())
+                        .collect(),
+                    work_done_progress_options: Default::default(),
+                }),
+
                 // Formatting
                 document_formatting_provider: Some(OneOf::Left(true)),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
+                document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                    first_trigger_character: "\n".to_string(),
+                    more_trigger_character: None,
+                }),
 
                 // Rename
                 rename_provider: Some(OneOf::Right(RenameOptions {
@@ -289,29 +891,31 @@ impl LanguageServer for Vb6LanguageServer {
                     work_done_progress_options: Default::default(),
                 })),
 
+                // Folding ranges
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+
+                // Selection ranges
+                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+
+                // Linked editing (Sub/End Sub keywords, For/Next loop variables)
+                linked_editing_range_provider: Some(
+                    LinkedEditingRangeServerCapabilities::Simple(true),
+                ),
+
+                // Document links
+                document_link_provider: Some(DocumentLinkOptions {
+                    resolve_provider: Some(false),
+                    work_done_progress_options: Default::default(),
+                }),
+
+                // Color swatches and picker
+                color_provider: Some(ColorProviderCapability::Simple(true)),
+
                 // Semantic tokens for syntax highlighting
                 semantic_tokens_provider: Some(
                     SemanticTokensServerCapabilities::SemanticTokensOptions(
                         SemanticTokensOptions {
-                            legend: SemanticTokensLegend {
-                                token_types: vec![
-                                    SemanticTokenType::KEYWORD,
-                                    SemanticTokenType::FUNCTION,
-                                    SemanticTokenType::VARIABLE,
-                                    SemanticTokenType::STRING,
-                                    SemanticTokenType::NUMBER,
-                                    SemanticTokenType::COMMENT,
-                                    SemanticTokenType::TYPE,
-                                    SemanticTokenType::CLASS,
-                                    SemanticTokenType::PROPERTY,
-                                    SemanticTokenType::PARAMETER,
-                                ],
-                                token_modifiers: vec![
-                                    SemanticTokenModifier::DECLARATION,
-                                    SemanticTokenModifier::DEFINITION,
-                                    SemanticTokenModifier::READONLY,
-                                ],
-                            },
+                            legend: semantic_tokens::legend(),
                             full: Some(SemanticTokensFullOptions::Bool(true)),
                             range: Some(true),
                             ..Default::default()
@@ -330,6 +934,27 @@ impl LanguageServer for Vb6LanguageServer {
 
     async fn initialized(&self, _params: InitializedParams) {
         tracing::info!("VB6 Language Server initialized");
+
+        // Let editors that watch files report changes too; the server's watcher covers the rest
+        let options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String(WATCHED_FILES_GLOB.to_string()),
+                kind: None,
+            }],
+        };
+        let registration = Registration {
+            id: "vb6-watched-files".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(options).ok(),
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            tracing::debug!("Client did not register file watchers: {}", e);
+        }
+        tokio::spawn(self.clone().watch_files());
+
+        let roots = self.workspace.read().unwrap().roots().to_vec();
+        tokio::spawn(self.clone().scan_workspace(roots));
+
         self.client
             .log_message(MessageType::INFO, "VB6 Language Server ready!")
             .await;
@@ -337,9 +962,66 @@ impl LanguageServer for Vb6LanguageServer {
 
     async fn shutdown(&self) -> Result<()> {
         tracing::info!("Shutting down VB6 Language Server");
+        self.shutdown.notify_one();
+        self.watcher.lock().unwrap().take();
         Ok(())
     }
 
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        self.apply_settings(&params.settings);
+
+        // Inactive #If branches may have changed, so re-check every open document
+        let uris: Vec<Url> = self.documents.iter().map(|entry| entry.key().clone()).collect();
+        for uri in uris {
+            self.parse_and_diagnose(&uri).await;
+        }
+    }
+
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        let mut added = Vec::new();
+        let removed = !params.event.removed.is_empty();
+        {
+            let mut workspace = self.workspace.write().unwrap();
+            for folder in params.event.removed {
+                if let Ok(path) = folder.uri.to_file_path() {
+                    workspace.remove_root(&path);
+                }
+            }
+            for folder in params.event.added {
+                if let Ok(path) = folder.uri.to_file_path() {
+                    workspace.register_root(path.clone());
+                    added.push(path);
+                }
+            }
+        }
+        self.update_watched_roots();
+
+        // Open files may have lost their projects
+        if removed {
+            let uris: Vec<Url> = self.documents.iter().map(|entry| entry.key().clone()).collect();
+            for uri in uris {
+                self.parse_and_diagnose(&uri).await;
+            }
+        }
+        tokio::spawn(self.clone().scan_workspace(added));
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let mut file_events = self.file_events.lock().unwrap();
+        let now = Instant::now();
+        for change in params.changes {
+            let Ok(path) = change.uri.to_file_path() else {
+                continue;
+            };
+            let change = match change.typ {
+                FileChangeType::CREATED => FileChange::Created,
+                FileChangeType::DELETED => FileChange::Deleted,
+                _ => FileChange::Changed,
+            };
+            file_events.push(FileEvent::new(path, change), now);
+        }
+    }
+
     // Document synchronization
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         let uri = params.text_document.uri;
@@ -348,15 +1030,20 @@ impl LanguageServer for Vb6LanguageServer {
 
         tracing::debug!("Document opened: {}", uri);
 
-        // Documents opened via LSP are already decoded by the client as UTF-8
+        // Documents opened via LSP are already decoded by the client as UTF-8.
+        // ANSI files need the editor's encoding set to Windows-1252 (or the
+        // file's codepage) to avoid mojibake, since the server never sees bytes.
         self.documents.insert(
             uri.clone(),
             Document {
                 content: Rope::from_str(&content),
                 version,
                 encoding: Encoding::Utf8, // LSP protocol uses UTF-8
+                kind: Vb6FileKind::from_path(uri.path()).unwrap_or(Vb6FileKind::Module),
                 ast: None,
                 tree: None,
+                inactive: Vec::new(),
+                edits: Vec::new(),
                 symbol_table: None,
             },
         );
@@ -371,22 +1058,7 @@ impl LanguageServer for Vb6LanguageServer {
             doc.version = params.text_document.version;
 
             for change in params.content_changes {
-                if let Some(range) = change.range {
-                    // Incremental update
-                    let start_line = range.start.line as usize;
-                    let start_char = range.start.character as usize;
-                    let end_line = range.end.line as usize;
-                    let end_char = range.end.character as usize;
-
-                    let start_idx = doc.content.line_to_char(start_line) + start_char;
-                    let end_idx = doc.content.line_to_char(end_line) + end_char;
-
-                    doc.content.remove(start_idx..end_idx);
-                    doc.content.insert(start_idx, &change.text);
-                } else {
-                    // Full replacement
-                    doc.content = Rope::from_str(&change.text);
-                }
+                doc.apply_change(change);
             }
         }
 
@@ -420,12 +1092,61 @@ impl LanguageServer for Vb6LanguageServer {
 
         tracing::debug!("Completion requested at {:?}", position);
 
+        // Project files complete their keys and values instead of code
+        if uri.path().to_lowercase().ends_with(".vbp") {
+            let (Some(content), Ok(path)) = (
+                self.documents.get(uri).map(|doc| doc.content.to_string()),
+                uri.to_file_path(),
+            ) else {
+                return Ok(None);
+            };
+            let Ok(vbp) = VbpFile::parse_content(&path, &content) else {
+                return Ok(None);
+            };
+            let project = Vb6Project::from_parsed_vbp(vbp);
+            let known_references: Vec<TypeLibReference> = self
+                .workspace
+                .read()
+                .unwrap()
+                .projects()
+                .flat_map(|project| project.references().iter().cloned())
+                .collect();
+
+            // Open modules may have unsaved changes
+            let items = vbp_completion::vbp_completions(
+                &project,
+                &content,
+                position,
+                &known_references,
+                |module| {
+                    let open = Url::from_file_path(&module.absolute_path)
+                        .ok()
+                        .and_then(|uri| self.documents.get(&uri))
+                        .map(|doc| doc.content.to_string());
+                    open.or_else(|| project.load_member_source(&module.absolute_path).ok())
+                },
+            );
+            return Ok(Some(CompletionResponse::Array(items)));
+        }
+
         // Get completions from analyzer
         if let Some(doc) = self.documents.get(uri) {
             let content = doc.content.to_string();
             // Prefer symbol table for context-aware completions
             if let Some(ref table) = doc.symbol_table {
-                let items = self.analyzer.get_completions_with_symbols(table, position, &content);
+                let workspace = self.workspace.read().unwrap();
+                let path = uri.to_file_path().ok();
+                let predeclared = path
+                    .as_ref()
+                    .map(|path| workspace.predeclared_objects(path))
+                    .unwrap_or_default();
+                let items = self.analyzer.get_completions_with_symbols(
+                    table,
+                    position,
+                    &content,
+                    &predeclared,
+                    |name| path.as_ref().and_then(|path| workspace.module_symbol_table(name, path)),
+                );
                 return Ok(Some(CompletionResponse::Array(items)));
             }
             // Fall back to AST-based completions
@@ -438,15 +1159,74 @@ impl LanguageServer for Vb6LanguageServer {
         Ok(Some(CompletionResponse::Array(vec![])))
     }
 
+    async fn completion_resolve(&self, item: CompletionItem) -> Result<CompletionItem> {
+        // Symbol completions carry the URI of the file that owns the symbol
+        let uri = item
+            .data
+            .clone()
+            .and_then(|d| serde_json::from_value::<CompletionData>(d).ok())
+            .and_then(|data| match data {
+                CompletionData::Symbol { uri, .. } => Some(uri),
+                CompletionData::ControlMember { .. } | CompletionData::Intrinsic { .. } => None,
+            });
+
+        if let Some(uri) = uri {
+            if let Some(doc) = self.documents.get(&uri) {
+                return Ok(self.analyzer.resolve_completion(item, doc.symbol_table.as_ref()));
+            }
+            return Ok(item);
+        }
+
+        Ok(self.analyzer.resolve_completion(item, None))
+    }
+
     // Hover
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
         let uri = &params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
 
+        // Project files get hover for their entries instead of code analysis
+        if uri.path().to_lowercase().ends_with(".vbp") {
+            let (Some(doc), Ok(path)) = (self.documents.get(uri), uri.to_file_path()) else {
+                return Ok(None);
+            };
+            let content = doc.content.to_string();
+            let Ok(vbp) = VbpFile::parse_content(&path, &content) else {
+                return Ok(None);
+            };
+            let project = Vb6Project::from_parsed_vbp(vbp);
+            return Ok(vbp_hover::vbp_hover(&project, &content, position));
+        }
+
         if let Some(doc) = self.documents.get(uri) {
+            // Color values in form designer blocks
+            if let Some(ref tree) = doc.tree {
+                let content = doc.content.to_string();
+                if let Some(hover) = self.analyzer.get_color_hover(tree, &content, position) {
+                    return Ok(Some(hover));
+                }
+            }
             // Prefer symbol table for precise hover
             if let Some(ref table) = doc.symbol_table {
-                return Ok(self.analyzer.get_hover_with_symbols(table, position));
+                let content = doc.content.to_string();
+                let hover = self.analyzer.get_hover_with_symbols(table, &content, position);
+                if hover.is_some() {
+                    return Ok(hover);
+                }
+
+                // Class names show their instancing, from this file's header or the project
+                let workspace = self.workspace.read().unwrap();
+                let file_path = uri.to_file_path().ok();
+                return Ok(self.analyzer.get_class_hover(&content, position, |name| {
+                    let own = doc
+                        .ast
+                        .as_ref()
+                        .filter(|_| doc.kind == Vb6FileKind::Class)
+                        .map(|ast| &ast.class_attributes)
+                        .filter(|a| a.name.as_ref().is_some_and(|n| n.eq_ignore_ascii_case(name)));
+                    own.or_else(|| workspace.class_attributes(name, file_path.as_ref()?))
+                        .cloned()
+                }));
             }
             // Fall back to AST-based hover
             if let Some(ref ast) = doc.ast {
@@ -457,6 +1237,21 @@ impl LanguageServer for Vb6LanguageServer {
         Ok(None)
     }
 
+    // Signature help
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref table) = doc.symbol_table {
+                let content = doc.content.to_string();
+                return Ok(self.analyzer.get_signature_help_with_symbols(table, &content, position));
+            }
+        }
+
+        Ok(None)
+    }
+
     // Go to definition
     async fn goto_definition(
         &self,
@@ -469,20 +1264,23 @@ impl LanguageServer for Vb6LanguageServer {
             let content = doc.content.to_string();
             // Prefer symbol table for precise definition lookup
             if let Some(ref table) = doc.symbol_table {
-                // Try local lookup first
-                if let Some(result) = self.analyzer.get_definition_with_symbols(table, &content, position) {
-                    return Ok(Some(result));
-                }
-
-                // If local lookup failed, try workspace-wide lookup
-                let word = self.get_word_at_position(&content, position);
-                if let Some(word) = word {
-                    if let Ok(file_path) = uri.to_file_path() {
-                        let workspace = self.workspace.read().unwrap();
-                        if let Some(location) = workspace.resolve_symbol(&word, &file_path) {
-                            return Ok(Some(GotoDefinitionResponse::Scalar(location)));
+                // Names not defined locally are resolved across the workspace
+                let workspace = self.workspace.read().unwrap();
+                let file_path = uri.to_file_path().ok();
+                let result = self.analyzer.get_definition_with_symbols(
+                    table,
+                    &content,
+                    position,
+                    |qualifier, name| {
+                        let path = file_path.as_ref()?;
+                        match qualifier {
+                            Some(module) => workspace.resolve_qualified_symbol(module, name, path),
+                            None => workspace.resolve_symbol(name, path),
                         }
-                    }
+                    },
+                );
+                if result.is_some() {
+                    return Ok(result);
                 }
             }
             // Fall back to AST-based definition
@@ -494,6 +1292,56 @@ impl LanguageServer for Vb6LanguageServer {
         Ok(None)
     }
 
+    // Go to type definition
+    async fn goto_type_definition(
+        &self,
+        params: request::GotoTypeDefinitionParams,
+    ) -> Result<Option<request::GotoTypeDefinitionResponse>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let Some(doc) = self.documents.get(uri) else {
+            return Ok(None);
+        };
+        let Some(ref table) = doc.symbol_table else {
+            return Ok(None);
+        };
+        let content = doc.content.to_string();
+        // Classes, forms, and the Public types of other modules are resolved across the workspace
+        let workspace = self.workspace.read().unwrap();
+        let file_path = uri.to_file_path().ok();
+        Ok(self.analyzer.get_type_definition_with_symbols(table, &content, position, |name| {
+            workspace.resolve_symbol(name, file_path.as_ref()?)
+        }))
+    }
+
+    // Go to implementation
+    async fn goto_implementation(
+        &self,
+        params: request::GotoImplementationParams,
+    ) -> Result<Option<request::GotoImplementationResponse>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let Some(doc) = self.documents.get(uri) else {
+            return Ok(None);
+        };
+        let (Some(table), Ok(file_path)) = (doc.symbol_table.as_ref(), uri.to_file_path()) else {
+            return Ok(None);
+        };
+        let content = doc.content.to_string();
+        let workspace = self.workspace.read().unwrap();
+        let module = workspace.module_name(&file_path).unwrap_or_default();
+
+        let Some((interface, member)) =
+            self.analyzer.implementation_target(table, &content, position, &module)
+        else {
+            return Ok(None);
+        };
+        let locations = workspace.find_implementations(&interface, member.as_deref());
+        Ok((!locations.is_empty()).then_some(request::GotoImplementationResponse::Array(locations)))
+    }
+
     // Find references
     async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
         let uri = &params.text_document_position.text_document.uri;
@@ -502,17 +1350,99 @@ impl LanguageServer for Vb6LanguageServer {
         if let Some(doc) = self.documents.get(uri) {
             // Prefer symbol table for precise references
             if let Some(ref table) = doc.symbol_table {
+                if let Some(mut references) = self.workspace_references(table, position) {
+                    // The declaration comes first
+                    if !params.context.include_declaration {
+                        references.remove(0);
+                    }
+                    return Ok(Some(references));
+                }
                 return Ok(Some(self.analyzer.get_references_with_symbols(table, position)));
             }
-            // Fall back to AST-based references
+            // Fall back to text-based references
             if let Some(ref ast) = doc.ast {
-                return Ok(Some(self.analyzer.get_references(ast, position, uri)));
+                let content = doc.content.to_string();
+                return Ok(Some(self.analyzer.get_references(ast, &content, position, uri)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Document highlight
+    async fn document_highlight(
+        &self,
+        params: DocumentHighlightParams,
+    ) -> Result<Option<Vec<DocumentHighlight>>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref table) = doc.symbol_table {
+                let highlights = self.analyzer.get_document_highlights_with_symbols(table, position);
+                if !highlights.is_empty() {
+                    return Ok(Some(highlights));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Call hierarchy
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref table) = doc.symbol_table {
+                // A procedure declared or called in this document
+                if let Some(symbol) = table.symbol_at_position(SourcePosition::from_lsp(position)) {
+                    if symbol.kind.is_procedure() {
+                        return Ok(Some(vec![call_hierarchy_item(table, symbol)]));
+                    }
+                    return Ok(None);
+                }
+
+                // A call to a procedure in another module
+                let content = doc.content.to_string();
+                if let (Some(word), Ok(file_path)) =
+                    (self.get_word_at_position(&content, position), uri.to_file_path())
+                {
+                    let workspace = self.workspace.read().unwrap();
+                    if let Some(location) = workspace.resolve_symbol(&word, &file_path) {
+                        if let Some((table, symbol)) =
+                            workspace.procedure_at(&location.uri, location.range.start)
+                        {
+                            return Ok(Some(vec![call_hierarchy_item(table, symbol)]));
+                        }
+                    }
+                }
             }
         }
 
         Ok(None)
     }
 
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        let workspace = self.workspace.read().unwrap();
+        Ok(Some(workspace.incoming_calls(&params.item)))
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        let workspace = self.workspace.read().unwrap();
+        Ok(Some(workspace.outgoing_calls(&params.item)))
+    }
+
     // Document symbols
     async fn document_symbol(
         &self,
@@ -523,6 +1453,10 @@ impl LanguageServer for Vb6LanguageServer {
         if let Some(doc) = self.documents.get(uri) {
             // Prefer symbol table for precise document symbols
             if let Some(ref table) = doc.symbol_table {
+                if !self.hierarchical_document_symbols.load(Ordering::Relaxed) {
+                    let symbols = self.analyzer.get_flat_document_symbols(table);
+                    return Ok(Some(DocumentSymbolResponse::Flat(symbols)));
+                }
                 let symbols = self.analyzer.get_document_symbols_with_symbols(table);
                 return Ok(Some(DocumentSymbolResponse::Nested(symbols)));
             }
@@ -536,21 +1470,316 @@ impl LanguageServer for Vb6LanguageServer {
         Ok(None)
     }
 
+    // Semantic tokens
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        let uri = &params.text_document.uri;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref tree) = doc.tree {
+                let content = doc.content.to_string();
+                let data = semantic_tokens::semantic_tokens(tree, &content, None);
+                return Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+                    result_id: None,
+                    data,
+                })));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        let uri = &params.text_document.uri;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref tree) = doc.tree {
+                let content = doc.content.to_string();
+                let data = semantic_tokens::semantic_tokens(tree, &content, Some(params.range));
+                return Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+                    result_id: None,
+                    data,
+                })));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Folding ranges
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let uri = &params.text_document.uri;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref tree) = doc.tree {
+                let content = doc.content.to_string();
+                return Ok(Some(folding::folding_ranges(tree, &content)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Linked editing of block keyword pairs
+    async fn linked_editing_range(
+        &self,
+        params: LinkedEditingRangeParams,
+    ) -> Result<Option<LinkedEditingRanges>> {
+        let uri = &params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let Some(doc) = self.documents.get(uri) else {
+            return Ok(None);
+        };
+        let content = doc.content.to_string();
+        Ok(self
+            .analyzer
+            .get_linked_editing_ranges(uri, &content, &doc.inactive, position)
+            .map(|ranges| LinkedEditingRanges {
+                ranges,
+                word_pattern: None,
+            }))
+    }
+
+    // Document colors
+    async fn document_color(&self, params: DocumentColorParams) -> Result<Vec<ColorInformation>> {
+        let uri = &params.text_document.uri;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref tree) = doc.tree {
+                let content = doc.content.to_string();
+                return Ok(document_colors::document_colors(tree, &content));
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    // Color presentations
+    async fn color_presentation(
+        &self,
+        params: ColorPresentationParams,
+    ) -> Result<Vec<ColorPresentation>> {
+        let uri = &params.text_document.uri;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref tree) = doc.tree {
+                let content = doc.content.to_string();
+                return Ok(document_colors::color_presentations(
+                    tree,
+                    &content,
+                    params.color,
+                    params.range,
+                ));
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    // Selection ranges
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        let uri = &params.text_document.uri;
+        let Some(doc) = self.documents.get(uri) else {
+            return Ok(None);
+        };
+        let Some(ref tree) = doc.tree else {
+            return Ok(None);
+        };
+        let content = doc.content.to_string();
+
+        Ok(Some(
+            params
+                .positions
+                .into_iter()
+                .map(|position| selection_range::selection_range(tree, &content, position))
+                .collect(),
+        ))
+    }
+
+    // Document links
+    async fn document_link(&self, params: DocumentLinkParams) -> Result<Option<Vec<DocumentLink>>> {
+        let uri = &params.text_document.uri;
+        let Some(doc) = self.documents.get(uri) else {
+            return Ok(None);
+        };
+        let content = doc.content.to_string();
+        let path = uri.to_file_path().ok();
+
+        if uri.path().to_lowercase().ends_with(".vbp") {
+            let Some(path) = path else {
+                return Ok(None);
+            };
+            let Ok(vbp) = VbpFile::parse_content(&path, &content) else {
+                return Ok(None);
+            };
+            let project = Vb6Project::from_parsed_vbp(vbp);
+            return Ok(Some(document_links::vbp_links(&project, &content)));
+        }
+
+        let Some(ref tree) = doc.tree else {
+            return Ok(None);
+        };
+        let file_dir = path.as_deref().and_then(|path| path.parent());
+        Ok(Some(document_links::declare_links(tree, &content, file_dir)))
+    }
+
+    // Workspace symbols
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let workspace = self.workspace.read().unwrap();
+
+        let symbols = workspace
+            .find_symbols_matching(&params.query)
+            .into_iter()
+            .map(|m| {
+                #[allow(deprecated)]
+                SymbolInformation {
+                    name: m.name,
+                    kind: m.kind,
+                    tags: None,
+                    deprecated: None,
+                    location: m.location,
+                    container_name: m.container_name,
+                }
+            })
+            .collect();
+
+        Ok(Some(symbols))
+    }
+
     // Code actions
     async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
         let uri = &params.text_document.uri;
         let range = params.range;
 
         if let Some(doc) = self.documents.get(uri) {
-            if let Some(ref ast) = doc.ast {
-                let actions = self.analyzer.get_code_actions(ast, range, &params.context);
+            let content = doc.content.to_string();
+            let mut actions = self.analyzer.get_code_actions(
+                uri,
+                &content,
+                doc.tree.as_ref(),
+                doc.symbol_table.as_ref(),
+                range,
+                &params.context,
+            );
+
+            // If Claude is available, add AI-powered actions
+            if self.claude().is_some() {
+                let title = "Explain with Claude (AI)".to_string();
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: title.clone(),
+                    kind: Some(CodeActionKind::SOURCE),
+                    command: Some(Command {
+                        title,
+                        command: EXPLAIN_CODE_COMMAND.to_string(),
+                        arguments: Some(vec![
+                            serde_json::json!(uri),
+                            serde_json::json!(range),
+                        ]),
+                    }),
+                    ..Default::default()
+                }));
+            }
 
-                // If Claude is available, add AI-powered actions
-                if let Some(ref _claude) = self.claude {
-                    // TODO: Add Claude-powered code actions
-                }
+            return Ok(Some(actions));
+        }
+
+        Ok(None)
+    }
 
-                return Ok(Some(actions));
+    // Commands
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> Result<Option<serde_json::Value>> {
+        if params.command == DEPENDENCY_GRAPH_COMMAND {
+            let mut workspace = self.workspace.write().unwrap();
+            workspace.load_all_members(&mut Vb6Parser::new());
+            return Ok(serde_json::to_value(dependency_graph(&workspace)).ok());
+        }
+
+        if params.command == WORKSPACE_STATS_COMMAND {
+            let report = {
+                let mut workspace = self.workspace.write().unwrap();
+                workspace.load_all_members(&mut Vb6Parser::new());
+                workspace.stats().report()
+            };
+            self.client.show_message(MessageType::INFO, &report).await;
+            return Ok(Some(serde_json::Value::String(report)));
+        }
+
+        if !CLAUDE_COMMANDS.contains(&params.command.as_str()) {
+            return Ok(None);
+        }
+
+        let Some(claude) = self.claude() else {
+            let message = match self.claude {
+                Some(_) => "Claude is disabled by the vb6.claude.enabled setting",
+                None => "Claude is not available: set ANTHROPIC_API_KEY to enable it",
+            };
+            self.client.show_message(MessageType::WARNING, message).await;
+            return Ok(None);
+        };
+
+        let mut arguments = params.arguments.into_iter();
+        let (uri, range) = command_target(&mut arguments)?;
+
+        // The selection, or the code around the cursor
+        let code = match self.documents.get(&uri) {
+            Some(doc) if range.start != range.end => text_in_range(&doc.content, range),
+            Some(doc) => {
+                let line = range.start.line as usize;
+                let column = document::position_to_offset(&doc.content, range.start)
+                    .map_or(0, |offset| offset - doc.content.line_to_char(line));
+                get_code_context(&doc.content.to_string(), line, column, EXPLAIN_CONTEXT_LINES)
+            }
+            None => return Ok(None),
+        };
+
+        // Answer without waiting for the API, so the editor isn't blocked
+        let client = self.client.clone();
+        let token = params.work_done_progress_params.work_done_token;
+        match params.command.as_str() {
+            GENERATE_DOCUMENTATION_COMMAND => {
+                let stream = claude.generate_documentation(&code);
+                let title = "Generating documentation".to_string();
+                tokio::spawn(stream_with_progress(client, token, title, stream));
+            }
+            SUGGEST_MIGRATION_COMMAND => {
+                let target = arguments
+                    .next()
+                    .and_then(|value| value.as_str().map(str::to_string))
+                    .unwrap_or_else(|| "VB.NET".to_string());
+                let stream = claude.suggest_migration(&code, &target);
+                let title = format!("Converting to {}", target);
+                tokio::spawn(stream_with_progress(client, token, title, stream));
+            }
+            _ => {
+                tokio::spawn(async move {
+                    let explanation = claude.explain_code(&code).await.map_err(|e| e.to_string());
+                    match explanation {
+                        Ok(text) => client.show_message(MessageType::INFO, text).await,
+                        Err(error) => {
+                            client
+                                .show_message(
+                                    MessageType::ERROR,
+                                    format!("Claude could not explain the code: {}", error),
+                                )
+                                .await
+                        }
+                    }
+                });
             }
         }
 
@@ -564,7 +1793,63 @@ impl LanguageServer for Vb6LanguageServer {
         if let Some(doc) = self.documents.get(uri) {
             let content = doc.content.to_string();
             let parser = self.parser.read().unwrap();
-            return Ok(parser.format(&content));
+            return Ok(parser.format(&content, &self.format_options(&params.options)));
+        }
+
+        Ok(None)
+    }
+
+    // Range formatting
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = &params.text_document.uri;
+
+        if let Some(doc) = self.documents.get(uri) {
+            let content = doc.content.to_string();
+            let options = self.format_options(&params.options);
+            let parser = self.parser.read().unwrap();
+            return Ok(parser.format_range(&content, params.range, &options));
+        }
+
+        Ok(None)
+    }
+
+    // On-type formatting
+    async fn on_type_formatting(
+        &self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = &params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        if let Some(doc) = self.documents.get(uri) {
+            let content = doc.content.to_string();
+            let options = self.format_options(&params.options);
+            let parser = self.parser.read().unwrap();
+            return Ok(parser.format_on_type(&content, position, &options));
+        }
+
+        Ok(None)
+    }
+
+    // Prepare rename
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let uri = &params.text_document.uri;
+        let position = params.position;
+
+        if let Some(doc) = self.documents.get(uri) {
+            if let Some(ref table) = doc.symbol_table {
+                return self
+                    .analyzer
+                    .prepare_rename_with_symbols(table, position)
+                    .map(|range| Some(PrepareRenameResponse::Range(range)))
+                    .map_err(tower_lsp::jsonrpc::Error::invalid_params);
+            }
         }
 
         Ok(None)
@@ -577,6 +1862,14 @@ impl LanguageServer for Vb6LanguageServer {
         let new_name = params.new_name;
 
         if let Some(doc) = self.documents.get(uri) {
+            // Prefer symbol table for scope-aware rename
+            if let Some(ref table) = doc.symbol_table {
+                return self
+                    .analyzer
+                    .rename_with_symbols(table, position, &new_name)
+                    .map_err(tower_lsp::jsonrpc::Error::invalid_params);
+            }
+            // Fall back to AST-based rename
             if let Some(ref ast) = doc.ast {
                 return Ok(self.analyzer.rename(ast, position, &new_name, uri));
             }
@@ -585,3 +1878,172 @@ impl LanguageServer for Vb6LanguageServer {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(source: &str, parser: &mut Vb6Parser) -> Document {
+        parser.parse_edited(source, None).unwrap();
+        Document {
+            content: Rope::from_str(source),
+            version: 1,
+            encoding: Encoding::Utf8,
+            kind: Vb6FileKind::Module,
+            ast: None,
+            tree: parser.get_tree().cloned(),
+            inactive: Vec::new(),
+            edits: Vec::new(),
+            symbol_table: None,
+        }
+    }
+
+    fn change(range: Option<Range>, text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range,
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_apply_change_reparses_incrementally() {
+        let mut parser = Vb6Parser::new();
+        let mut doc = open("Sub Main()\n    x = 1\nEnd Sub\n\nSub Other()\nEnd Sub\n", &mut parser);
+
+        // Replace "1" with "total + 2" and add a line to the second procedure
+        let range = Range {
+            start: Position { line: 1, character: 8 },
+            end: Position { line: 1, character: 9 },
+        };
+        doc.apply_change(change(Some(range), "total + 2"));
+        let insert = Position { line: 4, character: 11 };
+        doc.apply_change(change(Some(Range { start: insert, end: insert }), "\n    y = \"é\""));
+        assert!(doc.tree.as_ref().unwrap().root_node().has_changes());
+        assert_eq!(doc.edits.len(), 2);
+
+        let source = doc.content.to_string();
+        assert_eq!(source, "Sub Main()\n    x = total + 2\nEnd Sub\n\nSub Other()\n    y = \"é\"\nEnd Sub\n");
+
+        let old_tree = doc.tree.take();
+        parser.parse_edited(&source, old_tree.as_ref()).unwrap();
+        let incremental = parser.get_tree().unwrap().root_node().to_sexp();
+        parser.parse_edited(&source, None).unwrap();
+        assert_eq!(incremental, parser.get_tree().unwrap().root_node().to_sexp());
+    }
+
+    #[test]
+    fn test_full_replacement_clears_tree() {
+        let mut parser = Vb6Parser::new();
+        let mut doc = open("Sub Main()\nEnd Sub\n", &mut parser);
+
+        doc.apply_change(change(None, "Sub Other()\nEnd Sub\n"));
+        assert!(doc.tree.is_none());
+        assert!(doc.edits.is_empty());
+        assert_eq!(doc.content.to_string(), "Sub Other()\nEnd Sub\n");
+    }
+
+    #[test]
+    fn test_command_target() {
+        let uri = Url::parse("file:///project/Module1.bas").unwrap();
+        let range = Range {
+            start: Position { line: 1, character: 4 },
+            end: Position { line: 2, character: 0 },
+        };
+        let mut arguments = vec![
+            serde_json::to_value(&uri).unwrap(),
+            serde_json::to_value(range).unwrap(),
+            serde_json::json!("C#"),
+        ]
+        .into_iter();
+
+        assert_eq!(command_target(&mut arguments).unwrap(), (uri.clone(), range));
+        // Further arguments are left for the command
+        assert_eq!(arguments.next(), Some(serde_json::json!("C#")));
+    }
+
+    #[test]
+    fn test_command_target_rejects_bad_arguments() {
+        let uri = Url::parse("file:///project/Module1.bas").unwrap();
+        let cases = [
+            vec![],
+            vec![serde_json::to_value(&uri).unwrap()],
+            vec![serde_json::json!("not a uri"), serde_json::json!({})],
+            vec![serde_json::to_value(&uri).unwrap(), serde_json::json!({ "start": 1 })],
+        ];
+        for arguments in cases {
+            let error = command_target(&mut arguments.into_iter()).unwrap_err();
+            assert_eq!(error.code, tower_lsp::jsonrpc::ErrorCode::InvalidParams);
+        }
+    }
+
+    #[test]
+    fn test_text_in_range_uses_utf16_columns() {
+        // "😀" is two UTF-16 code units but one char
+        let content = Rope::from_str("s = \"😀\" & name\nx = 1\n");
+        let range = Range {
+            start: Position { line: 0, character: 11 },
+            end: Position { line: 0, character: 15 },
+        };
+        assert_eq!(text_in_range(&content, range), "name");
+
+        // Past the end is clamped
+        let range = Range {
+            start: Position { line: 1, character: 0 },
+            end: Position { line: 9, character: 0 },
+        };
+        assert_eq!(text_in_range(&content, range), "x = 1\n");
+    }
+
+    #[test]
+    fn test_file_events_while_reparsing_open_document() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_open_events_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let vbp = dir.join("Project1.vbp");
+        let module = dir.join("Module1.bas");
+        let helpers = dir.join("Module2.bas");
+        std::fs::write(&vbp, "Type=Exe\nModule=Module1; Module1.bas\nModule=Module2; Module2.bas\n")
+            .unwrap();
+        std::fs::write(&module, "Sub Main()\n    DoWork\nEnd Sub\n").unwrap();
+        std::fs::write(&helpers, "Public Sub DoWork()\nEnd Sub\n").unwrap();
+
+        let (service, _socket) = tower_lsp::LspService::new(Vb6LanguageServer::new);
+        let server = service.inner().clone();
+        server.workspace.write().unwrap().add_root(dir.clone());
+        let uri = Url::from_file_path(&module).unwrap();
+        let source = std::fs::read_to_string(&module).unwrap();
+        server.documents.insert(uri.clone(), open(&source, &mut Vb6Parser::new()));
+
+        // Watcher batches and edits to the open file run side by side
+        let (done, finished) = std::sync::mpsc::channel();
+        let run = |task: Box<dyn FnOnce(Vb6LanguageServer) + Send>| {
+            let (server, done) = (server.clone(), done.clone());
+            std::thread::spawn(move || {
+                task(server);
+                done.send(()).unwrap();
+            });
+        };
+        run(Box::new(move |server| {
+            futures::executor::block_on(async {
+                for _ in 0..200 {
+                    server.parse_and_diagnose(&uri).await;
+                }
+            })
+        }));
+        run(Box::new(move |server| {
+            futures::executor::block_on(async {
+                for _ in 0..200 {
+                    let event = FileEvent::new(helpers.clone(), FileChange::Changed);
+                    server.apply_file_events(vec![event]).await;
+                }
+            })
+        }));
+
+        for _ in 0..2 {
+            let result = finished.recv_timeout(Duration::from_secs(30));
+            assert!(result.is_ok(), "file events deadlocked with the open document");
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,82 @@
+//! Work Done Progress
+//!
+//! Reports long-running server work through `$/progress`. The token is the
+//! client's when its request carried one; otherwise the server asks the
+//! client to create one, and reports nothing if the client declines.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tower_lsp::lsp_types::{
+    notification, request, NumberOrString, ProgressParams, ProgressParamsValue, ProgressToken,
+    WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    WorkDoneProgressReport,
+};
+use tower_lsp::Client;
+
+/// Source of progress tokens for server-initiated progress
+static NEXT_PROGRESS_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+/// A progress indicator shown by the client
+pub struct Progress {
+    client: Client,
+    token: Option<ProgressToken>,
+}
+
+impl Progress {
+    /// Use the client's token, or ask it to create one named after `kind`
+    pub async fn create(client: Client, token: Option<ProgressToken>, kind: &str) -> Self {
+        let token = match token {
+            Some(token) => Some(token),
+            None => {
+                let token = NumberOrString::String(format!(
+                    "vb6-{}-{}",
+                    kind,
+                    NEXT_PROGRESS_TOKEN.fetch_add(1, Ordering::Relaxed)
+                ));
+                client
+                    .send_request::<request::WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
+                        token: token.clone(),
+                    })
+                    .await
+                    .ok()
+                    .map(|_| token)
+            }
+        };
+        Self { client, token }
+    }
+
+    /// Show the indicator; a `percentage` makes it a progress bar rather than a spinner
+    pub async fn begin(&self, title: impl Into<String>, percentage: Option<u32>) {
+        self.send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: title.into(),
+            percentage,
+            ..Default::default()
+        }))
+        .await;
+    }
+
+    pub async fn report(&self, message: Option<String>, percentage: Option<u32>) {
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            message,
+            percentage,
+            ..Default::default()
+        }))
+        .await;
+    }
+
+    pub async fn end(&self, message: Option<String>) {
+        self.send(WorkDoneProgress::End(WorkDoneProgressEnd { message }))
+            .await;
+    }
+
+    async fn send(&self, value: WorkDoneProgress) {
+        if let Some(ref token) = self.token {
+            self.client
+                .send_notification::<notification::Progress>(ProgressParams {
+                    token: token.clone(),
+                    value: ProgressParamsValue::WorkDone(value),
+                })
+                .await;
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Selection Ranges
+//!
+//! Expands a selection structurally by walking up the syntax tree from the
+//! node at the cursor (identifier -> expression -> statement -> block ->
+//! procedure -> module).
+
+use tower_lsp::lsp_types::{Position, Range, SelectionRange};
+use tree_sitter::{Node, Point, Tree};
+
+/// Build the chain of enclosing ranges for a position, innermost first
+pub fn selection_range(tree: &Tree, source: &str, position: Position) -> SelectionRange {
+    let point = Point::new(position.line as usize, position.character as usize);
+    let mut ranges: Vec<Range> = Vec::new();
+    let mut push = |range: Range| {
+        if ranges.last() != Some(&range) {
+            ranges.push(range);
+        }
+    };
+
+    let mut node = tree.root_node().named_descendant_for_point_range(point, point);
+    while let Some(current) = node {
+        // The text between the quotes or parentheses comes before the whole node
+        if matches!(current.kind(), "string_literal" | "argument_list" | "parameter_list") {
+            if let Some(inner) = inner_range(&current, source, 1) {
+                push(inner);
+            }
+        }
+        if let Some(range) = trimmed_range(&current, source) {
+            push(range);
+        }
+        node = current.parent();
+    }
+
+    if ranges.is_empty() {
+        ranges.push(Range::new(position, position));
+    }
+
+    // Nest from the outermost range inwards
+    ranges
+        .into_iter()
+        .rev()
+        .fold(None, |parent, range| {
+            Some(SelectionRange {
+                range,
+                parent: parent.map(Box::new),
+            })
+        })
+        .expect("at least one range")
+}
+
+/// Range of a node without leading and trailing whitespace (statements
+/// include their newline terminator)
+fn trimmed_range(node: &Node, source: &str) -> Option<Range> {
+    byte_range(source, node.start_byte(), node.end_byte())
+}
+
+/// Range of a node with `delimiter` bytes removed from each end
+fn inner_range(node: &Node, source: &str, delimiter: usize) -> Option<Range> {
+    let (start, end) = (node.start_byte() + delimiter, node.end_byte().checked_sub(delimiter)?);
+    if start >= end {
+        return None;
+    }
+    byte_range(source, start, end)
+}
+
+/// Range of a byte span, trimmed of surrounding whitespace
+fn byte_range(source: &str, start: usize, end: usize) -> Option<Range> {
+    let text = source.get(start..end)?;
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let start = start + (text.len() - text.trim_start().len());
+    let end = start + trimmed.len();
+    Some(Range::new(position_at(source, start), position_at(source, end)))
+}
+
+/// Position of a byte offset (columns in bytes, matching tree-sitter points)
+fn position_at(source: &str, byte: usize) -> Position {
+    let before = &source[..byte];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    Position::new(line as u32, (byte - line_start) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn chain(source: &str, line: u32, character: u32) -> Vec<String> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let lines: Vec<&str> = source.lines().collect();
+
+        let mut texts = Vec::new();
+        let mut current = Some(selection_range(&tree, source, Position::new(line, character)));
+        while let Some(selection) = current {
+            let Range { start, end } = selection.range;
+            texts.push(if start.line == end.line {
+                lines[start.line as usize][start.character as usize..end.character as usize]
+                    .to_string()
+            } else {
+                format!("{}-{}", start.line, end.line)
+            });
+            current = selection.parent.map(|parent| *parent);
+        }
+        texts
+    }
+
+    #[test]
+    fn test_identifier_to_module() {
+        let source = "Option Explicit\n\nSub Main()\n    Dim total As Long\n    total = total + 1\nEnd Sub\n";
+        let texts = chain(source, 4, 13);
+
+        assert_eq!(texts.first().map(String::as_str), Some("total"));
+        assert!(texts.contains(&"total + 1".to_string()));
+        assert!(texts.contains(&"total = total + 1".to_string()));
+        assert!(texts.contains(&"3-4".to_string()));
+        assert!(texts.contains(&"2-5".to_string()));
+        assert_eq!(texts.last().map(String::as_str), Some("0-5"));
+    }
+
+    #[test]
+    fn test_string_and_arguments() {
+        let source = "Sub Main()\n    MsgBox Format(\"Hello World\", 1)\nEnd Sub\n";
+        let texts = chain(source, 1, 22);
+
+        assert_eq!(texts[0], "Hello World");
+        assert_eq!(texts[1], "\"Hello World\"");
+        assert_eq!(texts[2], "\"Hello World\", 1");
+        assert_eq!(texts[3], "(\"Hello World\", 1)");
+        assert_eq!(texts[4], "Format(\"Hello World\", 1)");
+    }
+}
@@ -0,0 +1,307 @@
+//! Semantic Tokens
+//!
+//! Produces LSP semantic tokens from the tree-sitter tree using the
+//! grammar's highlights query.
+
+use once_cell::sync::Lazy;
+use tower_lsp::lsp_types::{
+    Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend,
+};
+use tree_sitter::{Query, Tree};
+
+use crate::parser::VB6QueryRunner;
+
+// Token type indices (must match the order in `legend()`)
+const KEYWORD: u32 = 0;
+const FUNCTION: u32 = 1;
+const VARIABLE: u32 = 2;
+const STRING: u32 = 3;
+const NUMBER: u32 = 4;
+const COMMENT: u32 = 5;
+const TYPE: u32 = 6;
+const PROPERTY: u32 = 8;
+const PARAMETER: u32 = 9;
+
+// Token modifier bits (must match the order in `legend()`)
+const DECLARATION: u32 = 1 << 0;
+const DEFINITION: u32 = 1 << 1;
+const READONLY: u32 = 1 << 2;
+
+/// Compiled highlights query (None if it fails to compile against the grammar)
+static HIGHLIGHTS: Lazy<Option<Query>> = Lazy::new(|| {
+    VB6QueryRunner::new()
+        .create_query(tree_sitter_vb6::HIGHLIGHTS_QUERY)
+        .map_err(|e| tracing::error!("Failed to compile highlights query: {}", e))
+        .ok()
+});
+
+/// The semantic tokens legend advertised in server capabilities
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: vec![
+            SemanticTokenType::KEYWORD,
+            SemanticTokenType::FUNCTION,
+            SemanticTokenType::VARIABLE,
+            SemanticTokenType::STRING,
+            SemanticTokenType::NUMBER,
+            SemanticTokenType::COMMENT,
+            SemanticTokenType::TYPE,
+            SemanticTokenType::CLASS,
+            SemanticTokenType::PROPERTY,
+            SemanticTokenType::PARAMETER,
+        ],
+        token_modifiers: vec![
+            SemanticTokenModifier::DECLARATION,
+            SemanticTokenModifier::DEFINITION,
+            SemanticTokenModifier::READONLY,
+        ],
+    }
+}
+
+/// A highlighted node before delta encoding
+struct RawToken {
+    start_byte: usize,
+    end_byte: usize,
+    start: (usize, usize),
+    end: (usize, usize),
+    pattern_index: usize,
+    token_type: u32,
+    modifiers: u32,
+}
+
+/// A single-line token with UTF-16 columns
+struct LineToken {
+    line: u32,
+    start: u32,
+    length: u32,
+    token_type: u32,
+    modifiers: u32,
+}
+
+/// Compute delta-encoded semantic tokens, optionally limited to a range
+pub fn semantic_tokens(tree: &Tree, source: &str, range: Option<Range>) -> Vec<SemanticToken> {
+    let query = match HIGHLIGHTS.as_ref() {
+        Some(q) => q,
+        None => return Vec::new(),
+    };
+
+    let runner = VB6QueryRunner::new();
+    let mut raw = Vec::new();
+
+    for m in runner.run_query(query, tree, source.as_bytes()) {
+        for capture in &m.captures {
+            if let Some((token_type, modifiers)) = classify(&capture.name) {
+                let node = capture.node;
+                if node.start_byte() == node.end_byte() {
+                    continue;
+                }
+                raw.push(RawToken {
+                    start_byte: node.start_byte(),
+                    end_byte: node.end_byte(),
+                    start: (node.start_position().row, node.start_position().column),
+                    end: (node.end_position().row, node.end_position().column),
+                    pattern_index: m.pattern_index,
+                    token_type,
+                    modifiers,
+                });
+            }
+        }
+    }
+
+    // Earlier patterns are more specific, so they win when captures overlap
+    raw.sort_by_key(|t| (t.start_byte, t.pattern_index));
+
+    let lines: Vec<&str> = source.split('\n').map(|l| l.trim_end_matches('\r')).collect();
+    let mut tokens = Vec::new();
+    let mut last_end = 0;
+
+    for token in raw {
+        if token.start_byte < last_end {
+            continue;
+        }
+        last_end = token.end_byte;
+
+        // Tokens spanning several lines are split into one token per line
+        for row in token.start.0..=token.end.0 {
+            let line = match lines.get(row) {
+                Some(l) => *l,
+                None => break,
+            };
+            let start_col = if row == token.start.0 { token.start.1 } else { 0 };
+            let end_col = if row == token.end.0 { token.end.1 } else { line.len() };
+
+            let start = utf16_column(line, start_col);
+            let end = utf16_column(line, end_col);
+            if end <= start {
+                continue;
+            }
+
+            tokens.push(LineToken {
+                line: row as u32,
+                start,
+                length: end - start,
+                token_type: token.token_type,
+                modifiers: token.modifiers,
+            });
+        }
+    }
+
+    if let Some(range) = range {
+        tokens.retain(|t| overlaps(t, &range));
+    }
+
+    encode(&tokens)
+}
+
+/// Map a highlights capture name to a legend token type and modifiers
+fn classify(capture: &str) -> Option<(u32, u32)> {
+    let token_type = match capture {
+        "variable.parameter" => PARAMETER,
+        "variable.member" | "variable.field" => PROPERTY,
+        "constant.definition" => VARIABLE,
+        "constant.builtin" => KEYWORD,
+        _ => match capture.split('.').next()? {
+            "keyword" => KEYWORD,
+            "function" => FUNCTION,
+            "variable" => VARIABLE,
+            "string" => STRING,
+            "number" => NUMBER,
+            "comment" => COMMENT,
+            "type" => TYPE,
+            _ => return None,
+        },
+    };
+
+    let mut modifiers = 0;
+    if capture.ends_with(".definition") {
+        modifiers |= DECLARATION | DEFINITION;
+    }
+    if capture.starts_with("constant.") {
+        modifiers |= READONLY;
+    }
+
+    Some((token_type, modifiers))
+}
+
+/// Convert a byte column to a UTF-16 column within a line
+fn utf16_column(line: &str, byte_col: usize) -> u32 {
+    let byte_col = byte_col.min(line.len());
+    match line.get(..byte_col) {
+        Some(prefix) => prefix.encode_utf16().count() as u32,
+        None => line.encode_utf16().count() as u32,
+    }
+}
+
+/// Check if a token overlaps the requested range
+fn overlaps(token: &LineToken, range: &Range) -> bool {
+    let end = token.start + token.length;
+    let starts_before_range_end = (token.line, token.start) < (range.end.line, range.end.character);
+    let ends_after_range_start = (token.line, end) > (range.start.line, range.start.character);
+    starts_before_range_end && ends_after_range_start
+}
+
+/// Delta-encode tokens sorted by line then character
+fn encode(tokens: &[LineToken]) -> Vec<SemanticToken> {
+    let mut result = Vec::with_capacity(tokens.len());
+    let mut prev_line = 0;
+    let mut prev_start = 0;
+
+    for token in tokens {
+        let delta_line = token.line - prev_line;
+        let delta_start = if delta_line == 0 {
+            token.start - prev_start
+        } else {
+            token.start
+        };
+
+        result.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: token.length,
+            token_type: token.token_type,
+            token_modifiers_bitset: token.modifiers,
+        });
+
+        prev_line = token.line;
+        prev_start = token.start;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+    use tower_lsp::lsp_types::Position;
+
+    /// Decode delta tokens back to absolute (line, start, length, type)
+    fn decode(tokens: &[SemanticToken]) -> Vec<(u32, u32, u32, u32)> {
+        let mut line = 0;
+        let mut start = 0;
+        tokens
+            .iter()
+            .map(|t| {
+                if t.delta_line > 0 {
+                    line += t.delta_line;
+                    start = t.delta_start;
+                } else {
+                    start += t.delta_start;
+                }
+                (line, start, t.length, t.token_type)
+            })
+            .collect()
+    }
+
+    fn tokens_for(source: &str, range: Option<Range>) -> Vec<(u32, u32, u32, u32)> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        decode(&semantic_tokens(&tree, source, range))
+    }
+
+    #[test]
+    fn test_highlights_query_compiles() {
+        assert!(HIGHLIGHTS.is_some());
+    }
+
+    #[test]
+    fn test_basic_tokens() {
+        let source = "' Comment\nSub Main()\n    x = Foo(\"a\", 42)\nEnd Sub\n";
+        let tokens = tokens_for(source, None);
+
+        assert!(tokens.contains(&(0, 0, 9, COMMENT)));
+        assert!(tokens.contains(&(1, 0, 3, KEYWORD)));
+        assert!(tokens.contains(&(1, 4, 4, FUNCTION)));
+        assert!(tokens.contains(&(2, 8, 3, FUNCTION)));
+        assert!(tokens.contains(&(2, 12, 3, STRING)));
+        assert!(tokens.contains(&(2, 17, 2, NUMBER)));
+
+        // Sorted by line then character, no overlaps
+        for pair in tokens.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert!(a.0 < b.0 || (a.0 == b.0 && a.1 + a.2 <= b.1));
+        }
+    }
+
+    #[test]
+    fn test_line_continuation() {
+        let source = "Sub Main()\n    Call Foo(1, _\n        \"two\")\nEnd Sub\n";
+        let tokens = tokens_for(source, None);
+
+        assert!(tokens.contains(&(1, 13, 1, NUMBER)));
+        assert!(tokens.contains(&(2, 8, 5, STRING)));
+    }
+
+    #[test]
+    fn test_range_request() {
+        let source = "Sub A()\nEnd Sub\nSub B()\nEnd Sub\n";
+        let range = Range {
+            start: Position { line: 2, character: 0 },
+            end: Position { line: 3, character: 0 },
+        };
+        let tokens = tokens_for(source, Some(range));
+
+        assert!(!tokens.is_empty());
+        assert!(tokens.iter().all(|t| t.0 == 2));
+    }
+}
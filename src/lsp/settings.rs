@@ -0,0 +1,322 @@
+//! Server Settings
+//!
+//! Settings come from the client's `initializationOptions` and from
+//! `workspace/didChangeConfiguration`, either at the top level or under a
+//! `vb6` section. Every field is optional:
+//!
+//! ```json
+//! {
+//!   "vb6": {
+//!     "diagnostics": {
+//!       "undeclaredVariables": true,
+//!       "unbalancedBlocks": true,
+//!       "unreachableCode": true,
+//!       "errorHandling": true,
+//!       "labels": true,
+//!       "arrays": true,
+//!       "duplicates": true,
+//!       "propertyValues": true,
+//!       "setAssignments": true,
+//!       "byrefMismatches": true,
+//!       "namedArguments": true,
+//!       "integerArithmetic": true,
+//!       "plusConcatenation": true,
+//!       "circularConstants": true,
+//!       "eventHandlers": true,
+//!       "ptrSafe": true,
+//!       "shadowing": true,
+//!       "unusedModules": true
+//!     },
+//!     "optionExplicit": "information",
+//!     "allowedIdentifiers": ["gApp"],
+//!     "conditionalCompilation": { "Win32": true, "DEBUG_LEVEL": 2 },
+//!     "claude": { "enabled": true, "model": "claude-sonnet-4-20250514", "maxTokens": 4096 },
+//!     "format": { "indentSize": 4 }
+//!   }
+//! }
+//! ```
+//!
+//! `optionExplicit` is the severity of the hint to add `Option Explicit`:
+//! "error", "warning", "information", "hint", or "off".
+
+use serde::{Deserialize, Deserializer};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+
+use crate::analysis::{
+    BYREF_MISMATCH, CIRCULAR_CONSTANT, DUPLICATE_PROCEDURE, INTEGER_DIVISION, INTEGER_OVERFLOW,
+    INVALID_PROPERTY_VALUE, MISSING_OPTION_EXPLICIT, MISSING_SET, NOT_PTR_SAFE, PLUS_CONCATENATION,
+    REDIM_FIXED_ARRAY, REDIM_PRESERVE_DIMENSIONS, REDIM_UNDECLARED, RESUME_NEXT_NOT_RESET,
+    SET_ON_VALUE, SHADOWED_SYMBOL, UNBALANCED_BLOCK, UNDECLARED_VARIABLE, UNDEFINED_LABEL,
+    UNKNOWN_CONTROL, UNKNOWN_EVENT, UNKNOWN_NAMED_ARGUMENT, UNREACHABLE_CODE, UNUSED_LABEL,
+};
+use crate::parser::{CompilationConstants, FormatOptions};
+use crate::workspace::{DUPLICATE_PUBLIC_NAME, UNUSED_MODULE};
+
+/// Settings of the language server
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    pub diagnostics: DiagnosticSettings,
+    /// Severity of the hint to add `Option Explicit`
+    pub option_explicit: Severity,
+    /// Extra names to treat as declared under Option Explicit
+    pub allowed_identifiers: Vec<String>,
+    /// Project-level `#Const` values; booleans become -1 and 0
+    #[serde(deserialize_with = "compilation_constants")]
+    pub conditional_compilation: CompilationConstants,
+    pub claude: ClaudeSettings,
+    pub format: FormatSettings,
+}
+
+/// Which diagnostic passes run
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiagnosticSettings {
+    pub undeclared_variables: bool,
+    pub unbalanced_blocks: bool,
+    pub unreachable_code: bool,
+    pub error_handling: bool,
+    pub labels: bool,
+    pub arrays: bool,
+    pub duplicates: bool,
+    pub property_values: bool,
+    pub set_assignments: bool,
+    pub byref_mismatches: bool,
+    pub named_arguments: bool,
+    pub integer_arithmetic: bool,
+    pub plus_concatenation: bool,
+    pub circular_constants: bool,
+    pub event_handlers: bool,
+    pub ptr_safe: bool,
+    pub shadowing: bool,
+    pub unused_modules: bool,
+}
+
+impl Default for DiagnosticSettings {
+    fn default() -> Self {
+        Self {
+            undeclared_variables: true,
+            unbalanced_blocks: true,
+            unreachable_code: true,
+            error_handling: true,
+            labels: true,
+            arrays: true,
+            duplicates: true,
+            property_values: true,
+            set_assignments: true,
+            byref_mismatches: true,
+            named_arguments: true,
+            integer_arithmetic: true,
+            plus_concatenation: true,
+            circular_constants: true,
+            event_handlers: true,
+            ptr_safe: true,
+            shadowing: true,
+            unused_modules: true,
+        }
+    }
+}
+
+impl DiagnosticSettings {
+    /// Whether the pass reporting diagnostics with this code is enabled.
+    /// Syntax errors and other diagnostics without a pass are always reported.
+    pub fn is_enabled(&self, code: &str) -> bool {
+        match code {
+            UNDECLARED_VARIABLE => self.undeclared_variables,
+            UNBALANCED_BLOCK => self.unbalanced_blocks,
+            UNREACHABLE_CODE => self.unreachable_code,
+            RESUME_NEXT_NOT_RESET => self.error_handling,
+            UNDEFINED_LABEL | UNUSED_LABEL => self.labels,
+            REDIM_FIXED_ARRAY | REDIM_UNDECLARED | REDIM_PRESERVE_DIMENSIONS => self.arrays,
+            DUPLICATE_PROCEDURE | DUPLICATE_PUBLIC_NAME => self.duplicates,
+            INVALID_PROPERTY_VALUE => self.property_values,
+            MISSING_SET | SET_ON_VALUE => self.set_assignments,
+            BYREF_MISMATCH => self.byref_mismatches,
+            UNKNOWN_NAMED_ARGUMENT => self.named_arguments,
+            INTEGER_OVERFLOW | INTEGER_DIVISION => self.integer_arithmetic,
+            PLUS_CONCATENATION => self.plus_concatenation,
+            CIRCULAR_CONSTANT => self.circular_constants,
+            UNKNOWN_CONTROL | UNKNOWN_EVENT => self.event_handlers,
+            NOT_PTR_SAFE => self.ptr_safe,
+            SHADOWED_SYMBOL => self.shadowing,
+            UNUSED_MODULE => self.unused_modules,
+            _ => true,
+        }
+    }
+}
+
+/// A diagnostic severity, or "off" to not report it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    #[default]
+    Information,
+    Hint,
+    Off,
+}
+
+impl Severity {
+    pub fn to_lsp(self) -> Option<DiagnosticSeverity> {
+        match self {
+            Severity::Error => Some(DiagnosticSeverity::ERROR),
+            Severity::Warning => Some(DiagnosticSeverity::WARNING),
+            Severity::Information => Some(DiagnosticSeverity::INFORMATION),
+            Severity::Hint => Some(DiagnosticSeverity::HINT),
+            Severity::Off => None,
+        }
+    }
+}
+
+/// Claude integration, which also needs `ANTHROPIC_API_KEY`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClaudeSettings {
+    pub enabled: bool,
+    /// Model to use instead of the default (or `ANTHROPIC_MODEL`)
+    pub model: Option<String>,
+    /// Response token limit
+    pub max_tokens: Option<u64>,
+}
+
+impl Default for ClaudeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: None,
+            max_tokens: None,
+        }
+    }
+}
+
+/// Formatter options that override the editor's
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FormatSettings {
+    /// Spaces per indentation level, instead of the editor's tab size
+    pub indent_size: Option<usize>,
+}
+
+impl Settings {
+    /// Read settings from a client payload, nested under `vb6` or not
+    pub fn from_value(value: &serde_json::Value) -> Result<Self, serde_json::Error> {
+        let value = value.get("vb6").unwrap_or(value);
+        if value.is_null() {
+            return Ok(Self::default());
+        }
+        Self::deserialize(value)
+    }
+
+    /// Drop diagnostics of disabled passes and apply the Option Explicit severity
+    pub fn filter_diagnostics(&self, diagnostics: &mut Vec<Diagnostic>) {
+        diagnostics.retain_mut(|diagnostic| {
+            let Some(NumberOrString::String(code)) = &diagnostic.code else {
+                return true;
+            };
+            if code == MISSING_OPTION_EXPLICIT {
+                diagnostic.severity = self.option_explicit.to_lsp();
+                return diagnostic.severity.is_some();
+            }
+            self.diagnostics.is_enabled(code)
+        });
+    }
+
+    /// Apply the formatter settings to the editor's options
+    pub fn apply_format(&self, options: &mut FormatOptions) {
+        if let Some(indent_size) = self.format.indent_size {
+            options.tab_size = indent_size;
+            options.insert_spaces = true;
+        }
+    }
+}
+
+/// `#Const` values given as booleans, numbers, or numeric strings
+fn compilation_constants<'de, D>(deserializer: D) -> Result<CompilationConstants, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = serde_json::Map::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::Bool(b) => Some(if b { -1 } else { 0 }),
+                serde_json::Value::Number(n) => n.as_i64(),
+                serde_json::Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            };
+            value.map(|v| (name, v))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_settings_from_value() {
+        let settings = Settings::from_value(&json!({
+            "vb6": {
+                "diagnostics": { "plusConcatenation": false },
+                "optionExplicit": "warning",
+                "conditionalCompilation": { "Win32": true, "DEBUG_LEVEL": "2", "Bad": [] },
+                "claude": { "enabled": false },
+                "format": { "indentSize": 2 }
+            }
+        }))
+        .unwrap();
+
+        assert!(!settings.diagnostics.plus_concatenation);
+        assert!(settings.diagnostics.undeclared_variables);
+        assert_eq!(settings.option_explicit, Severity::Warning);
+        assert_eq!(
+            settings.conditional_compilation,
+            CompilationConstants::from([("Win32".to_string(), -1), ("DEBUG_LEVEL".to_string(), 2)])
+        );
+        assert!(!settings.claude.enabled);
+        assert_eq!(settings.format.indent_size, Some(2));
+
+        assert_eq!(Settings::from_value(&json!(null)).unwrap(), Settings::default());
+        assert_eq!(Settings::from_value(&json!({})).unwrap(), Settings::default());
+        assert!(Settings::from_value(&json!({ "optionExplicit": "loud" })).is_err());
+    }
+
+    #[test]
+    fn test_filter_diagnostics() {
+        let diagnostic = |code: Option<&str>| Diagnostic {
+            code: code.map(|c| NumberOrString::String(c.to_string())),
+            severity: Some(DiagnosticSeverity::INFORMATION),
+            ..Default::default()
+        };
+        let codes = |settings: &Settings| {
+            let mut diagnostics = vec![
+                diagnostic(None),
+                diagnostic(Some(MISSING_OPTION_EXPLICIT)),
+                diagnostic(Some(PLUS_CONCATENATION)),
+                diagnostic(Some(UNKNOWN_EVENT)),
+            ];
+            settings.filter_diagnostics(&mut diagnostics);
+            diagnostics
+                .into_iter()
+                .map(|d| (d.code, d.severity))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(codes(&Settings::default()).len(), 4);
+
+        let settings = Settings::from_value(&json!({
+            "optionExplicit": "error",
+            "diagnostics": { "eventHandlers": false }
+        }))
+        .unwrap();
+        let filtered = codes(&settings);
+        assert_eq!(filtered.len(), 3);
+        assert_eq!(filtered[1].1, Some(DiagnosticSeverity::ERROR));
+
+        let settings = Settings::from_value(&json!({ "optionExplicit": "off" })).unwrap();
+        assert_eq!(codes(&settings).len(), 3);
+    }
+}
@@ -0,0 +1,416 @@
+//! Project File Completion
+//!
+//! Completion inside `.vbp` files: the keys of the project section, with the
+//! format of their values, and values for `Type`, `Startup` (the project's
+//! forms and `Sub Main`), and `Reference` (common type libraries and those
+//! referenced by other projects of the workspace).
+
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, MarkupContent,
+    MarkupKind, Position, Range, TextEdit,
+};
+
+use crate::parser::Vb6FileKind;
+use crate::workspace::{ProjectMember, TypeLibReference, Vb6Project};
+
+/// A key of the project section
+struct VbpKey {
+    name: &'static str,
+    /// The format of the value
+    format: &'static str,
+    description: &'static str,
+    /// Whether the key may appear more than once
+    repeatable: bool,
+}
+
+const fn key(name: &'static str, format: &'static str, description: &'static str) -> VbpKey {
+    VbpKey { name, format, description, repeatable: false }
+}
+
+const fn member(name: &'static str, format: &'static str, description: &'static str) -> VbpKey {
+    VbpKey { name, format, description, repeatable: true }
+}
+
+static KEYS: &[VbpKey] = &[
+    key("Type", "Exe | OleDll | OleExe | Control", "Kind of project"),
+    member("Reference", "*\\G{GUID}#version#lcid#path#description", "Type library reference"),
+    member("Object", "{GUID}#version#0; file.ocx", "ActiveX control reference"),
+    member("Module", "Name; path.bas", "Standard module"),
+    member("Class", "Name; path.cls", "Class module"),
+    member("Form", "path.frm", "Form"),
+    member("UserControl", "path.ctl", "User control"),
+    member("PropertyPage", "path.pag", "Property page"),
+    member("UserDocument", "path.dob", "User document"),
+    member("Designer", "path.dsr", "ActiveX designer"),
+    member("RelatedDoc", "path", "Related document, not compiled"),
+    key("ResFile32", "\"path.res\"", "Resource file compiled into the project"),
+    key("IconForm", "\"FormName\"", "Form whose icon the executable uses"),
+    key("Startup", "\"FormName\" | \"Sub Main\" | \"(None)\"", "What runs when the project starts"),
+    key("HelpFile", "\"path.hlp\"", "Help file of the project"),
+    key("Title", "\"text\"", "Application title (App.Title)"),
+    key("ExeName32", "\"file.exe\"", "Output file name"),
+    key("Command32", "\"arguments\"", "Command line arguments when run from the IDE"),
+    key("Name", "\"ProjectName\"", "Project name, used to qualify its public names"),
+    key("MajorVer", "number", "Major version"),
+    key("MinorVer", "number", "Minor version"),
+    key("RevisionVer", "number", "Revision"),
+    key("AutoIncrementVer", "0 | 1", "Increment the revision on each build"),
+    key("VersionCompanyName", "\"text\"", "Company name in the version information"),
+    key("VersionFileDescription", "\"text\"", "File description in the version information"),
+    key("VersionLegalCopyright", "\"text\"", "Copyright in the version information"),
+    key("VersionLegalTrademarks", "\"text\"", "Trademarks in the version information"),
+    key("VersionProductName", "\"text\"", "Product name in the version information"),
+    key("VersionComments", "\"text\"", "Comments in the version information"),
+    key("CompilationType", "0 | -1", "0 compiles to native code, -1 to P-Code"),
+    key("OptimizationType", "0 | 1 | 2", "No optimization, fast code, or small code"),
+    key("FavorPentiumPro(tm)", "0 | -1", "Optimize for the Pentium Pro"),
+    key("CodeViewDebugInfo", "0 | -1", "Create symbolic debug info"),
+    key("NoAliasing", "0 | -1", "Assume no aliasing"),
+    key("BoundsCheck", "0 | -1", "-1 removes array bounds checks"),
+    key("OverflowCheck", "0 | -1", "-1 removes integer overflow checks"),
+    key("FlPointCheck", "0 | -1", "-1 removes floating point error checks"),
+    key("FDIVCheck", "0 | -1", "-1 removes safe Pentium FDIV checks"),
+    key("UnroundedFP", "0 | -1", "Allow unrounded floating point operations"),
+    key("CondComp", "\"NAME = value : NAME = value\"", "Conditional compilation arguments"),
+    key("StartMode", "0 | 1", "Start as a stand-alone program or an ActiveX component"),
+    key("Unattended", "0 | -1", "Run without user interaction"),
+    key("Retained", "0 | 1", "Retain the project in memory"),
+    key("ThreadPerObject", "number", "Thread per object (ActiveX EXE)"),
+    key("MaxNumberOfThreads", "number", "Size of the thread pool (ActiveX EXE)"),
+    key("ThreadingModel", "0 | 1", "Single threaded or apartment threaded"),
+    key("CompatibleMode", "\"0\" | \"1\" | \"2\"", "No, project, or binary compatibility"),
+    key("CompatibleEXE32", "\"path\"", "Component to stay compatible with"),
+    key("NoControlUpgrade", "0 | 1", "1 keeps ActiveX controls from being upgraded"),
+    key("RemoveUnusedControlInfo", "0 | 1", "Remove information about unused controls"),
+    key("ServerSupportFiles", "0 | 1", "Create remote server files"),
+];
+
+const PROJECT_TYPES: &[(&str, &str)] = &[
+    ("Exe", "Standard EXE"),
+    ("OleDll", "ActiveX DLL"),
+    ("OleExe", "ActiveX EXE"),
+    ("Control", "ActiveX Control"),
+];
+
+/// Type libraries found on every Windows system:
+/// (GUID, version, path, description)
+const COMMON_TYPE_LIBRARIES: &[(&str, &str, &str, &str)] = &[
+    (
+        "{00020430-0000-0000-C000-000000000046}",
+        "2.0",
+        "C:\\Windows\\System32\\stdole2.tlb",
+        "OLE Automation",
+    ),
+    (
+        "{420B2830-E718-11CF-893D-00A0C9054228}",
+        "1.0",
+        "C:\\Windows\\System32\\scrrun.dll",
+        "Microsoft Scripting Runtime",
+    ),
+    (
+        "{2A75196C-D9EB-4129-B803-931327F72D5C}",
+        "2.8",
+        "C:\\Program Files\\Common Files\\System\\ado\\msado28.tlb",
+        "Microsoft ActiveX Data Objects 2.8 Library",
+    ),
+    (
+        "{F5078F18-C551-11D3-89B9-0000F81FE221}",
+        "6.0",
+        "C:\\Windows\\System32\\msxml6.dll",
+        "Microsoft XML, v6.0",
+    ),
+    (
+        "{3F4DACA7-160D-11D2-A8E9-00104B365C9F}",
+        "5.5",
+        "C:\\Windows\\System32\\vbscript.dll\\3",
+        "Microsoft VBScript Regular Expressions 5.5",
+    ),
+    (
+        "{50A7E9B0-70EF-11D1-B75A-00A0C90564FE}",
+        "1.0",
+        "C:\\Windows\\System32\\shell32.dll",
+        "Microsoft Shell Controls And Automation",
+    ),
+];
+
+/// Completions at `position` in a VBP file.
+///
+/// `known_references` are offered as `Reference` values alongside the common
+/// type libraries, and `module_source` reads a module to look for `Sub Main`.
+pub fn vbp_completions(
+    project: &Vb6Project,
+    source: &str,
+    position: Position,
+    known_references: &[TypeLibReference],
+    module_source: impl Fn(&ProjectMember) -> Option<String>,
+) -> Vec<CompletionItem> {
+    let lines: Vec<&str> = source.lines().collect();
+    let row = position.line as usize;
+    let line = lines.get(row).copied().unwrap_or_default();
+    let before: String = line.chars().take(position.character as usize).collect();
+
+    // Keys of other sections ([MS Transaction Server], ...) are not known
+    if lines[..row.min(lines.len())].iter().any(|l| l.trim_start().starts_with('[')) {
+        return Vec::new();
+    }
+
+    match before.split_once('=') {
+        Some((key, value)) => {
+            let column = before.chars().count() - value.chars().count();
+            let start = Position::new(position.line, column as u32);
+            let edit = Range::new(start, position);
+            match key.trim().to_lowercase().as_str() {
+                "type" => type_completions(edit),
+                "startup" => startup_completions(project, edit, module_source),
+                "reference" => reference_completions(project, edit, known_references),
+                _ => Vec::new(),
+            }
+        }
+        None if !before.trim_start().starts_with('[') => {
+            let indent = before.chars().count() - before.trim_start().chars().count();
+            let edit = Range::new(Position::new(position.line, indent as u32), position);
+            key_completions(&lines, row, edit)
+        }
+        None => Vec::new(),
+    }
+}
+
+fn key_completions(lines: &[&str], row: usize, edit: Range) -> Vec<CompletionItem> {
+    let present = |name: &str| {
+        lines.iter().enumerate().any(|(i, line)| {
+            i != row
+                && line
+                    .split_once('=')
+                    .is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        })
+    };
+
+    KEYS.iter()
+        .filter(|key| key.repeatable || !present(key.name))
+        .enumerate()
+        .map(|(i, key)| CompletionItem {
+            label: key.name.to_string(),
+            kind: Some(CompletionItemKind::PROPERTY),
+            detail: Some(key.format.to_string()),
+            documentation: Some(markdown(format!(
+                "{}\n\n```\n{}={}\n```",
+                key.description, key.name, key.format
+            ))),
+            // Keep the order of a project file VB6 saves
+            sort_text: Some(format!("{:02}", i)),
+            text_edit: Some(text_edit(edit, format!("{}=", key.name))),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn type_completions(edit: Range) -> Vec<CompletionItem> {
+    PROJECT_TYPES
+        .iter()
+        .map(|(value, description)| CompletionItem {
+            label: value.to_string(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            detail: Some(description.to_string()),
+            text_edit: Some(text_edit(edit, value.to_string())),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// The forms of the project, and `Sub Main` if a module declares one
+fn startup_completions(
+    project: &Vb6Project,
+    edit: Range,
+    module_source: impl Fn(&ProjectMember) -> Option<String>,
+) -> Vec<CompletionItem> {
+    let kind_of = |member: &ProjectMember| {
+        Vb6FileKind::from_path(&member.relative_path.to_string_lossy())
+    };
+
+    let mut items: Vec<CompletionItem> = project
+        .source_files()
+        .filter(|member| kind_of(member) == Some(Vb6FileKind::Form))
+        .map(|form| CompletionItem {
+            label: form.name.clone(),
+            kind: Some(CompletionItemKind::CLASS),
+            detail: Some(format!("Form ({})", form.relative_path.display())),
+            text_edit: Some(text_edit(edit, format!("\"{}\"", form.name))),
+            ..Default::default()
+        })
+        .collect();
+
+    let sub_main = project
+        .source_files()
+        .filter(|member| kind_of(member) == Some(Vb6FileKind::Module))
+        .find(|module| module_source(module).is_some_and(|source| declares_sub_main(&source)));
+    if let Some(module) = sub_main {
+        items.push(CompletionItem {
+            label: "Sub Main".to_string(),
+            kind: Some(CompletionItemKind::FUNCTION),
+            detail: Some(format!("Declared in {}", module.name)),
+            text_edit: Some(text_edit(edit, "\"Sub Main\"".to_string())),
+            ..Default::default()
+        });
+    }
+
+    items.push(CompletionItem {
+        label: "(None)".to_string(),
+        kind: Some(CompletionItemKind::VALUE),
+        detail: Some("No startup object, as in most DLLs and controls".to_string()),
+        text_edit: Some(text_edit(edit, "\"(None)\"".to_string())),
+        ..Default::default()
+    });
+    items
+}
+
+/// Whether a module declares a `Sub Main` VB6 can start from (not Private)
+fn declares_sub_main(source: &str) -> bool {
+    source.lines().any(|line| {
+        let mut words = line
+            .split(|c: char| c.is_whitespace() || c == '(')
+            .filter(|word| !word.is_empty());
+        let mut first = words.next();
+        if first.is_some_and(|word| word.eq_ignore_ascii_case("public")) {
+            first = words.next();
+        }
+        first.is_some_and(|word| word.eq_ignore_ascii_case("sub"))
+            && words.next().is_some_and(|word| word.eq_ignore_ascii_case("main"))
+    })
+}
+
+/// Type libraries the project does not reference yet
+fn reference_completions(
+    project: &Vb6Project,
+    edit: Range,
+    known_references: &[TypeLibReference],
+) -> Vec<CompletionItem> {
+    let common = COMMON_TYPE_LIBRARIES
+        .iter()
+        .map(|&(guid, version, path, description)| {
+            (guid.to_string(), version.to_string(), path.to_string(), description.to_string())
+        });
+    let known = known_references.iter().filter_map(|reference| match reference {
+        TypeLibReference::Compiled { uuid, version, path, description, .. } => Some((
+            uuid.braced().to_string().to_uppercase(),
+            version.clone(),
+            path.as_ref().map(|p| p.display().to_string()).unwrap_or_default(),
+            description.clone(),
+        )),
+        TypeLibReference::SubProject { .. } => None,
+    });
+
+    let mut seen: Vec<String> = project
+        .references()
+        .iter()
+        .filter_map(|reference| Some(reference.uuid()?.braced().to_string().to_uppercase()))
+        .collect();
+    let mut items = Vec::new();
+    for (guid, version, path, description) in common.chain(known) {
+        if seen.contains(&guid) {
+            continue;
+        }
+        items.push(CompletionItem {
+            label: description.clone(),
+            kind: Some(CompletionItemKind::REFERENCE),
+            detail: Some(format!("{} {}", guid, version)),
+            documentation: Some(markdown(format!("`{}`", path))),
+            text_edit: Some(text_edit(
+                edit,
+                format!("*\\G{}#{}#0#{}#{}", guid, version, path, description),
+            )),
+            ..Default::default()
+        });
+        seen.push(guid);
+    }
+    items
+}
+
+fn text_edit(range: Range, new_text: String) -> CompletionTextEdit {
+    CompletionTextEdit::Edit(TextEdit { range, new_text })
+}
+
+fn markdown(value: String) -> Documentation {
+    Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::VbpFile;
+    use std::path::Path;
+
+    const PROJECT: &str = "Type=Exe\n\
+Reference=*\\G{420B2830-E718-11CF-893D-00A0C9054228}#1.0#0#scrrun.dll#Microsoft Scripting Runtime\n\
+Form=Forms\\frmMain.frm\n\
+Form=frmAbout.frm\n\
+Module=modMain; modMain.bas\n\
+Startup=\n\
+\n\
+[MS Transaction Server]\n\
+AutoRefresh=1\n";
+
+    fn complete(line: u32, character: u32) -> Vec<CompletionItem> {
+        let vbp = VbpFile::parse_content(Path::new("/project/Test.vbp"), PROJECT).unwrap();
+        let project = Vb6Project::from_parsed_vbp(vbp);
+        vbp_completions(&project, PROJECT, Position::new(line, character), &[], |module| {
+            assert_eq!(module.name, "modMain");
+            Some("Option Explicit\nPublic Sub Main()\nEnd Sub\n".to_string())
+        })
+    }
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|item| item.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_vbp_key_completions() {
+        let items = complete(6, 0);
+        let labels = labels(&items);
+        assert!(labels.contains(&"Form"));
+        assert!(labels.contains(&"ExeName32"));
+        // Single keys already in the file are not offered again
+        assert!(!labels.contains(&"Type"));
+        assert!(!labels.contains(&"Startup"));
+
+        let form = items.iter().find(|item| item.label == "Form").unwrap();
+        assert_eq!(form.detail.as_deref(), Some("path.frm"));
+        match &form.text_edit {
+            Some(CompletionTextEdit::Edit(edit)) => assert_eq!(edit.new_text, "Form="),
+            _ => panic!("expected a text edit"),
+        }
+
+        // Keys of other sections are unknown
+        assert!(complete(8, 0).is_empty());
+    }
+
+    #[test]
+    fn test_vbp_value_completions() {
+        assert_eq!(labels(&complete(5, 8)), vec!["frmMain", "frmAbout", "Sub Main", "(None)"]);
+        assert_eq!(labels(&complete(0, 5)), vec!["Exe", "OleDll", "OleExe", "Control"]);
+
+        let items = complete(1, 10);
+        let labels = labels(&items);
+        assert!(labels.contains(&"OLE Automation"));
+        // Already referenced
+        assert!(!labels.contains(&"Microsoft Scripting Runtime"));
+        match &items[0].text_edit {
+            Some(CompletionTextEdit::Edit(edit)) => {
+                assert_eq!(edit.range.start, Position::new(1, 10));
+                let ole = "*\\G{00020430-0000-0000-C000-000000000046}#2.0#0#";
+                assert!(edit.new_text.starts_with(ole));
+            }
+            _ => panic!("expected a text edit"),
+        }
+    }
+
+    #[test]
+    fn test_declares_sub_main() {
+        assert!(declares_sub_main("sub main()\nend sub\n"));
+        assert!(declares_sub_main("Public Sub Main ()\nEnd Sub\n"));
+        assert!(!declares_sub_main("Private Sub Main()\nEnd Sub\n"));
+        assert!(!declares_sub_main("Sub MainLoop()\nEnd Sub\n"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Project File Hover
+//!
+//! Hover for the entries of a `.vbp` file: where a member's path resolves to
+//! and whether the file is there, and what a `Reference` entry refers to.
+
+use std::path::Path;
+
+use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
+
+use crate::workspace::{TypeLibReference, Vb6Project};
+
+/// Hover for the VBP entry on the line of `position`
+pub fn vbp_hover(project: &Vb6Project, source: &str, position: Position) -> Option<Hover> {
+    let row = position.line as usize;
+    let line = source.lines().nth(row)?;
+    let key = line.split_once('=')?.0.trim();
+
+    let markdown = if let Some(member) = project.source_files().find(|m| m.line == row) {
+        let (path, found) = match project.member_file(member) {
+            Some(path) => (path, true),
+            None => (member.absolute_path.clone(), false),
+        };
+        format!(
+            "**{}** `{}`\n\n{}\n\n{}",
+            key,
+            member.name,
+            path_line(&path),
+            if found { "File found" } else { "⚠ File not found" }
+        )
+    } else if let Some(reference) = project.references().iter().find(|r| r.line() == row) {
+        reference_markdown(project, reference)
+    } else {
+        return None;
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: markdown,
+        }),
+        range: Some(Range {
+            start: Position::new(position.line, 0),
+            end: Position::new(position.line, line.trim_end().chars().count() as u32),
+        }),
+    })
+}
+
+fn reference_markdown(project: &Vb6Project, reference: &TypeLibReference) -> String {
+    let found = project.reference_file(reference);
+    match reference {
+        TypeLibReference::SubProject { path, .. } => format!(
+            "**Project reference** `{}`\n\n{}\n\n{}",
+            reference.description(),
+            path_line(found.as_deref().unwrap_or(path)),
+            if found.is_some() { "File found" } else { "⚠ File not found" }
+        ),
+        TypeLibReference::Compiled { uuid, version, lcid, path, description, .. } => {
+            let mut markdown = format!(
+                "**Reference** {}\n\n- GUID: `{}`\n- Version: {}\n- Locale: {}",
+                description,
+                uuid.braced().to_string().to_uppercase(),
+                version,
+                lcid
+            );
+            if let Some(path) = path {
+                markdown.push_str(&format!("\n- Path: `{}`", path.display()));
+                // Registered type library paths are only meaningful on Windows
+                if cfg!(windows) && found.is_none() {
+                    markdown.push_str("\n\n⚠ Type library not found");
+                }
+            }
+            markdown
+        }
+    }
+}
+
+fn path_line(path: &Path) -> String {
+    format!("Path: `{}`", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::VbpFile;
+
+    fn hover_text(content: &str, dir: &Path, line: u32) -> Option<String> {
+        let vbp = VbpFile::parse_content(&dir.join("Test.vbp"), content).unwrap();
+        let project = Vb6Project::from_parsed_vbp(vbp);
+        let hover = vbp_hover(&project, content, Position::new(line, 3))?;
+        match hover.contents {
+            HoverContents::Markup(markup) => Some(markup.value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_vbp_hover() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_vbp_hover_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("frmMain.frm"), "").unwrap();
+
+        let content = "Type=Exe\n\
+Reference=*\\G{00020430-0000-0000-C000-000000000046}#2.0#0#\
+C:\\Windows\\system32\\stdole2.tlb#OLE Automation\n\
+Reference=*\\A..\\Missing\\Lib.vbp\n\
+Form=frmMain.frm\n\
+Class=clsGone; clsGone.cls\n";
+
+        let form = hover_text(content, &dir, 3).unwrap();
+        assert!(form.starts_with("**Form** `frmMain`"));
+        assert!(form.contains(&dir.join("frmMain.frm").display().to_string()));
+        assert!(form.ends_with("File found"));
+
+        let class = hover_text(content, &dir, 4).unwrap();
+        assert!(class.starts_with("**Class** `clsGone`"));
+        assert!(class.ends_with("⚠ File not found"));
+
+        let reference = hover_text(content, &dir, 1).unwrap();
+        assert!(reference.starts_with("**Reference** OLE Automation"));
+        assert!(reference.contains("- GUID: `{00020430-0000-0000-C000-000000000046}`"));
+        assert!(reference.contains("- Version: 2.0"));
+        assert!(reference.contains("stdole2.tlb"));
+
+        let project = hover_text(content, &dir, 2).unwrap();
+        assert!(project.starts_with("**Project reference** `Lib`"));
+        assert!(project.ends_with("⚠ File not found"));
+
+        assert_eq!(hover_text(content, &dir, 0), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,6 +4,9 @@
 //! with Claude AI integration for intelligent code assistance.
 
 use std::env;
+
+use base64::Engine;
+use ropey::Rope;
 use tower_lsp::{LspService, Server};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -15,8 +18,18 @@ mod parser;
 mod utils;
 mod workspace;
 
+use analysis::{build_symbol_table, build_symbol_table_excluding, Analyzer, SymbolTable};
 use lsp::Vb6LanguageServer;
-use workspace::{read_res_file, write_res_file, parse_string_table, ResourceEntry, ResourceId, ResourceType};
+use parser::{Conversion, ParseError, TreeSitterVb6Parser, Vb6FileKind, Vb6Parser};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Url};
+use utils::{decode_vb6_bytes, VB6FileReader};
+use workspace::{
+    analyze_project, build_string_table_block, collect_diagnostics, dependency_graph,
+    parse_form_layout, syntax_error, ParsedFile,
+    read_frx_resource, read_res_file, replace_resource, res_to_rc, write_res_file,
+    parse_string_table, FrxResourceKind, MemoryFlags, ResourceEntry, ResourceId, ResourceType,
+    StringTableEntry, WorkspaceManager,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -78,6 +91,10 @@ fn handle_cli_command(args: &[String]) -> anyhow::Result<()> {
                         })
                     },
                     "language_id": r.language_id,
+                    "memory_flags": memory_flags_to_json(r.memory_flags),
+                    "data_version": r.data_version,
+                    "version": r.version,
+                    "characteristics": r.characteristics,
                     "data_size": r.data.len(),
                     "data_base64": base64::encode(&r.data)
                 })
@@ -127,7 +144,15 @@ fn handle_cli_command(args: &[String]) -> anyhow::Result<()> {
                         .ok_or_else(|| anyhow::anyhow!("Missing data_base64"))?;
                     let data = base64::decode(data_base64)?;
 
-                    resources.push(ResourceEntry::new(resource_type, name, language_id, data));
+                    let mut entry = ResourceEntry::new(resource_type, name, language_id, data);
+                    if !res["memory_flags"].is_null() {
+                        entry.memory_flags = memory_flags_from_json(&res["memory_flags"])?;
+                    }
+                    entry.data_version = header_field(res, "data_version")?;
+                    entry.version = header_field(res, "version")?;
+                    entry.characteristics = header_field(res, "characteristics")?;
+
+                    resources.push(entry);
                 }
             }
 
@@ -181,18 +206,497 @@ fn handle_cli_command(args: &[String]) -> anyhow::Result<()> {
             Ok(())
         }
 
+        "build-string-table" => {
+            if args.len() < 4 {
+                eprintln!(
+                    "Usage: vb6-lsp build-string-table <strings.json|strings.csv> <block_id> \
+                     <out.res>"
+                );
+                std::process::exit(1);
+            }
+
+            let block_id: u16 = args[2].parse()
+                .map_err(|_| anyhow::anyhow!("Invalid block_id: must be a number"))?;
+            let output_file = &args[3];
+
+            let strings = read_string_entries(&args[1])?;
+            let data = build_string_table_block(&strings, block_id)?;
+
+            // Merge into an existing file, keeping the language of the block it replaces
+            let mut resources = if std::path::Path::new(output_file).exists() {
+                read_res_file(output_file)?
+            } else {
+                Vec::new()
+            };
+            let language_id = resources
+                .iter()
+                .find(|r| {
+                    r.resource_type == ResourceType::String
+                        && matches!(r.name, ResourceId::Id(id) if id == block_id)
+                })
+                .map_or(0x0409, |r| r.language_id);
+
+            let entry = ResourceEntry::new(
+                ResourceType::String,
+                ResourceId::Id(block_id),
+                language_id,
+                data,
+            );
+            let replaced = replace_resource(&mut resources, entry);
+            write_res_file(output_file, &resources)?;
+
+            println!("{}", serde_json::json!({
+                "success": true,
+                "file": output_file,
+                "blockId": block_id,
+                "stringCount": strings.len(),
+                "replaced": replaced,
+                "resourceCount": resources.len()
+            }));
+
+            Ok(())
+        }
+
+        "res-to-rc" => {
+            if args.len() < 3 {
+                eprintln!("Usage: vb6-lsp res-to-rc <file.res> <output.rc>");
+                std::process::exit(1);
+            }
+
+            let output = std::path::Path::new(&args[2]);
+            let prefix = output
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("resource");
+            let directory = output.parent().unwrap_or(std::path::Path::new(""));
+
+            let resources = read_res_file(&args[1])?;
+            let rc = res_to_rc(&resources, prefix)?;
+
+            // Binary payloads go next to the script, which names them relatively
+            for (name, data) in &rc.files {
+                std::fs::write(directory.join(name), data)?;
+            }
+            std::fs::write(output, &rc.script)?;
+
+            println!("{}", serde_json::json!({
+                "success": true,
+                "file": args[2],
+                "resourceCount": resources.len(),
+                "files": rc.files.iter().map(|(name, _)| name).collect::<Vec<_>>()
+            }));
+
+            Ok(())
+        }
+
+        "read-frx" => {
+            if args.len() < 3 {
+                eprintln!("Usage: vb6-lsp read-frx <file.frx> <offset>");
+                std::process::exit(1);
+            }
+
+            let file_path = &args[1];
+            // Offsets are hex, as written in .frm references ($"frmMain.frx":00AC)
+            let offset = usize::from_str_radix(args[2].trim_start_matches("0x"), 16)
+                .map_err(|_| anyhow::anyhow!("Invalid offset: must be a hex number"))?;
+
+            let resource = read_frx_resource(file_path, offset)?;
+
+            println!("{}", serde_json::json!({
+                "offset": resource.offset,
+                "kind": format!("{:?}", resource.kind),
+                "image_type": resource.image_type,
+                "items": resource.items,
+                "text": (resource.kind == FrxResourceKind::Text)
+                    .then(|| decode_vb6_bytes(&resource.data)),
+                "data_size": resource.data.len(),
+                "data_base64": base64::engine::general_purpose::STANDARD.encode(&resource.data)
+            }));
+
+            Ok(())
+        }
+
+        "form-layout" => {
+            if args.len() < 2 {
+                eprintln!("Usage: vb6-lsp form-layout <file.frm>");
+                std::process::exit(1);
+            }
+
+            let source = VB6FileReader::read_to_string(std::path::Path::new(&args[1]))?;
+            let layout = parse_form_layout(&source)
+                .ok_or_else(|| anyhow::anyhow!("No form designer block in {}", args[1]))?;
+
+            println!("{}", serde_json::to_string(&layout)?);
+
+            Ok(())
+        }
+
+        "dependency-graph" => {
+            if args.len() < 2 {
+                eprintln!("Usage: vb6-lsp dependency-graph <file.vbp>");
+                std::process::exit(1);
+            }
+
+            let vbp_path = std::fs::canonicalize(&args[1])?;
+            let mut workspace = WorkspaceManager::new();
+            workspace.load_project(&vbp_path)?;
+            workspace.load_all_members(&mut Vb6Parser::new());
+
+            println!("{}", serde_json::to_string(&dependency_graph(&workspace))?);
+
+            Ok(())
+        }
+
+        "dump-symbols" => {
+            let include_references = args[1..].iter().any(|a| a == "--include-references");
+            let Some(file_path) = args[1..].iter().find(|a| !a.starts_with("--")) else {
+                eprintln!("Usage: vb6-lsp dump-symbols <file.bas|cls|frm> [--include-references]");
+                std::process::exit(1);
+            };
+
+            let path = std::fs::canonicalize(file_path)?;
+            let source = VB6FileReader::read_to_string(&path)?;
+            let uri = Url::from_file_path(&path)
+                .map_err(|_| anyhow::anyhow!("Invalid file path: {}", path.display()))?;
+
+            let mut parser = TreeSitterVb6Parser::new().map_err(|e| anyhow::anyhow!(e))?;
+            let tree = parser
+                .parse(&source, None)
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", file_path))?;
+            let table = build_symbol_table(uri, &source, &tree);
+
+            let file = path.display().to_string();
+            println!("{}", symbols_to_json(&table, &file, include_references));
+
+            Ok(())
+        }
+
+        "outline" => {
+            if args.len() < 2 {
+                eprintln!("Usage: vb6-lsp outline <file.bas|cls|frm>");
+                std::process::exit(1);
+            }
+
+            let source = VB6FileReader::read_to_string(std::path::Path::new(&args[1]))?;
+            let items = Vb6Parser::new().outline(&source);
+
+            println!("{}", serde_json::to_string(&items)?);
+
+            Ok(())
+        }
+
+        "lint" => {
+            let github = args[1..].iter().any(|a| a == "--format=github");
+            let Some(file_path) = args[1..].iter().find(|a| !a.starts_with("--")) else {
+                eprintln!("Usage: vb6-lsp lint <file> [--format=github]");
+                std::process::exit(1);
+            };
+
+            let path = std::fs::canonicalize(file_path)?;
+            let diagnostics = lint_file(&path)?;
+
+            if github {
+                for d in &diagnostics {
+                    let level = match d.severity {
+                        Some(DiagnosticSeverity::ERROR) => "error",
+                        Some(DiagnosticSeverity::WARNING) => "warning",
+                        _ => "notice",
+                    };
+                    // Annotation lines and columns are 1-based
+                    println!(
+                        "::{} file={},line={},col={},endLine={},endColumn={}::{}",
+                        level,
+                        file_path,
+                        d.range.start.line + 1,
+                        d.range.start.character + 1,
+                        d.range.end.line + 1,
+                        d.range.end.character + 1,
+                        escape_annotation(&d.message)
+                    );
+                }
+            } else {
+                let json_diagnostics: Vec<serde_json::Value> = diagnostics.iter().map(|d| {
+                    serde_json::json!({
+                        "severity": match d.severity {
+                            Some(DiagnosticSeverity::ERROR) => "error",
+                            Some(DiagnosticSeverity::WARNING) => "warning",
+                            Some(DiagnosticSeverity::INFORMATION) => "information",
+                            _ => "hint",
+                        },
+                        "code": d.code,
+                        "message": d.message,
+                        "range": d.range,
+                        "source": d.source
+                    })
+                }).collect();
+                println!("{}", serde_json::Value::Array(json_diagnostics));
+            }
+
+            if diagnostics.iter().any(|d| d.severity == Some(DiagnosticSeverity::ERROR)) {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+
+        "analyze-project" => {
+            if args.len() < 2 {
+                eprintln!("Usage: vb6-lsp analyze-project <file.vbp>");
+                std::process::exit(1);
+            }
+
+            let vbp_path = std::fs::canonicalize(&args[1])?;
+            let mut workspace = WorkspaceManager::new();
+            let report = analyze_project(&mut workspace, &vbp_path)?;
+
+            let json_files: Vec<serde_json::Value> = report.iter().map(|(file, issues)| {
+                serde_json::json!({
+                    "file": file.display().to_string(),
+                    "issues": issues.iter().map(|issue| serde_json::json!({
+                        "kind": issue.kind.code(),
+                        "line": issue.line + 1,
+                        "message": issue.message
+                    })).collect::<Vec<_>>()
+                })
+            }).collect();
+
+            let stats = workspace.stats();
+            println!("{}", serde_json::json!({
+                "project": vbp_path.display().to_string(),
+                "filesParsed": stats.loaded_symbol_tables,
+                "issueCount": report.values().map(Vec::len).sum::<usize>(),
+                "files": json_files
+            }));
+
+            Ok(())
+        }
+
         _ => {
             eprintln!("Unknown command: {}", args[0]);
             eprintln!("Available commands:");
             eprintln!("  read-res <file.res>                    - Read a .res file");
             eprintln!("  write-res <input.json> <output.res>    - Write a .res file");
             eprintln!("  parse-string-table <file.res> <id>     - Parse string table");
+            eprintln!("  build-string-table <strings> <id> <out.res> - Write a string table block");
+            eprintln!("  res-to-rc <file.res> <output.rc>       - Convert .res to a .rc script");
+            eprintln!("  read-frx <file.frx> <offset>           - Read an .frx resource (hex offset)");
+            eprintln!("  form-layout <file.frm>                 - Print a form's control layout");
+            eprintln!("  dependency-graph <file.vbp>            - Print the module dependency graph");
+            eprintln!("  dump-symbols <file> [--include-references] - Print a file's symbol table");
+            eprintln!("  outline <file>                         - Print a file's declarations");
+            eprintln!("  lint <file> [--format=github]          - Print a file's diagnostics");
+            eprintln!("  analyze-project <file.vbp>             - Report project-wide issues");
             std::process::exit(1);
         }
     }
 }
 
+/// Read string table entries from a JSON or CSV file.
+///
+/// JSON is an array of `{"id", "value"}` objects, or the `{"strings": [...]}`
+/// printed by `parse-string-table`. CSV has one `id,value` pair per line, with
+/// an optional header line; spaces after the comma are ignored, and a value in
+/// double quotes may contain commas and `""` for a quote, but not line breaks.
+fn read_string_entries(path: &str) -> anyhow::Result<Vec<StringTableEntry>> {
+    let text = std::fs::read_to_string(path)?;
+
+    if path.to_lowercase().ends_with(".csv") {
+        let mut entries = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (id, value) = line
+                .split_once(',')
+                .ok_or_else(|| anyhow::anyhow!("Line {}: expected id,value", i + 1))?;
+            let Ok(id) = id.trim().parse::<u16>() else {
+                if i == 0 {
+                    continue; // Header
+                }
+                anyhow::bail!("Line {}: invalid string ID '{}'", i + 1, id.trim());
+            };
+            let value = value.trim_start();
+            let value = match value.trim_end().strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                Some(quoted) => quoted.replace("\"\"", "\""),
+                None => value.to_string(),
+            };
+            entries.push(StringTableEntry { id, value });
+        }
+        return Ok(entries);
+    }
+
+    let json: serde_json::Value = serde_json::from_str(&text)?;
+    let strings = json.get("strings").unwrap_or(&json);
+    let items = strings
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Expected an array of {{\"id\", \"value\"}} objects"))?;
+    items
+        .iter()
+        .map(|item| {
+            let id = item["id"]
+                .as_u64()
+                .and_then(|id| u16::try_from(id).ok())
+                .ok_or_else(|| anyhow::anyhow!("Invalid string ID in {}", item))?;
+            let value = item["value"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing string value in {}", item))?;
+            Ok(StringTableEntry { id, value: value.to_string() })
+        })
+        .collect()
+}
+
+/// Run the editor's diagnostic passes over a file.
+///
+/// The other files of a project in the same directory are indexed first, so
+/// names they declare publicly are not reported as undeclared.
+fn lint_file(path: &std::path::Path) -> anyhow::Result<Vec<Diagnostic>> {
+    let source = VB6FileReader::read_to_string(path)?;
+    let uri = Url::from_file_path(path)
+        .map_err(|_| anyhow::anyhow!("Invalid file path: {}", path.display()))?;
+
+    let mut workspace = WorkspaceManager::new();
+    let mut parser = Vb6Parser::new();
+    if let Some(dir) = path.parent() {
+        for entry in std::fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+            let vbp_path = entry.path();
+            if vbp_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vbp")) {
+                if let Err(e) = workspace.load_project(&vbp_path) {
+                    eprintln!("Failed to load {}: {}", vbp_path.display(), e);
+                }
+            }
+        }
+    }
+    if let Some(project) = workspace.project_for_file(path) {
+        parser.set_conditional_compilation(project.conditional_constants());
+    }
+
+    let members: Vec<std::path::PathBuf> = workspace
+        .projects()
+        .flat_map(|project| project.source_files().map(|m| m.absolute_path.clone()))
+        .collect();
+    let mut member_parser = Vb6Parser::new();
+    for member in members {
+        // Missing or unreadable members just contribute no names
+        let _ = workspace.reparse_file(&member, &mut member_parser);
+    }
+
+    let Some(tree) = parser.parse_rope(&Rope::from_str(&source), None).cloned() else {
+        let error = ParseError {
+            message: "Failed to parse source".to_string(),
+            range: Default::default(),
+        };
+        return Ok(vec![syntax_error(error)]);
+    };
+    let Conversion { mut ast, inactive_regions: inactive, errors } =
+        parser::convert(&tree, &source, parser.conditional_compilation());
+    if let Some(kind) = path.to_str().and_then(Vb6FileKind::from_path) {
+        ast.file_type = kind;
+    }
+
+    let table = build_symbol_table_excluding(uri.clone(), &source, &tree, &inactive);
+    let file = ParsedFile {
+        uri: &uri,
+        source: &source,
+        ast: &ast,
+        errors: &errors,
+        inactive: &inactive,
+        symbols: Some((&tree, &table)),
+    };
+    let mut diagnostics = collect_diagnostics(&Analyzer::new(), &workspace, &file);
+    diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+
+    Ok(diagnostics)
+}
+
+/// The symbols of a file as JSON, with the references to them if asked for
+fn symbols_to_json(table: &SymbolTable, file: &str, include_references: bool) -> serde_json::Value {
+    let json_symbols: Vec<serde_json::Value> = table.all_symbols().map(|s| {
+        serde_json::json!({
+            "id": s.id.0,
+            "name": s.name,
+            "kind": format!("{:?}", s.kind),
+            "visibility": format!("{:?}", s.visibility),
+            "type": s.type_info.as_ref().map(|t| t.display()),
+            "scope_id": s.scope_id.0,
+            "definition_range": s.definition_range.to_lsp(),
+            "name_range": s.name_range.to_lsp(),
+            "members": s.members.iter().map(|m| m.0).collect::<Vec<_>>()
+        })
+    }).collect();
+
+    let mut output = serde_json::json!({
+        "file": file,
+        "symbols": json_symbols
+    });
+
+    if include_references {
+        output["references"] = table.all_references().iter().map(|r| {
+            serde_json::json!({
+                "symbol_id": r.symbol_id.0,
+                "range": r.range.to_lsp(),
+                "scope_id": r.scope_id.0,
+                "is_assignment": r.is_assignment
+            })
+        }).collect();
+    }
+
+    output
+}
+
+/// Escape a message for a GitHub Actions workflow command
+fn escape_annotation(message: &str) -> String {
+    message.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
 /// Parse a resource type string (e.g., "Bitmap", "Icon", "Named(\"CUSTOM\")")
+/// Memory flags as JSON: the flags with a name, plus any other bits as a number
+fn memory_flags_to_json(flags: MemoryFlags) -> serde_json::Value {
+    let named = MemoryFlags::MOVEABLE | MemoryFlags::PURE | MemoryFlags::PRELOAD
+        | MemoryFlags::DISCARDABLE;
+    serde_json::json!({
+        "moveable": flags.0 & MemoryFlags::MOVEABLE != 0,
+        "pure": flags.0 & MemoryFlags::PURE != 0,
+        "preload": flags.0 & MemoryFlags::PRELOAD != 0,
+        "discardable": flags.0 & MemoryFlags::DISCARDABLE != 0,
+        "other": flags.0 & !named
+    })
+}
+
+/// Read memory flags written by `memory_flags_to_json`; a missing flag is off
+fn memory_flags_from_json(json: &serde_json::Value) -> anyhow::Result<MemoryFlags> {
+    let mut flags = match &json["other"] {
+        serde_json::Value::Null => 0,
+        other => other
+            .as_u64()
+            .and_then(|bits| u16::try_from(bits).ok())
+            .ok_or_else(|| anyhow::anyhow!("Invalid memory_flags.other: {}", other))?,
+    };
+    for (key, flag) in [
+        ("moveable", MemoryFlags::MOVEABLE),
+        ("pure", MemoryFlags::PURE),
+        ("preload", MemoryFlags::PRELOAD),
+        ("discardable", MemoryFlags::DISCARDABLE),
+    ] {
+        match &json[key] {
+            serde_json::Value::Null | serde_json::Value::Bool(false) => {}
+            serde_json::Value::Bool(true) => flags |= flag,
+            value => anyhow::bail!("Invalid memory_flags.{}: {}", key, value),
+        }
+    }
+    Ok(MemoryFlags(flags))
+}
+
+/// An optional numeric header field of a resource, 0 when missing
+fn header_field(res: &serde_json::Value, key: &str) -> anyhow::Result<u32> {
+    match &res[key] {
+        serde_json::Value::Null => Ok(0),
+        value => value
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .ok_or_else(|| anyhow::anyhow!("Invalid {}: {}", key, value)),
+    }
+}
+
 fn parse_resource_type(s: &str) -> anyhow::Result<ResourceType> {
     Ok(match s {
         "Cursor" => ResourceType::Cursor,
@@ -230,3 +734,102 @@ fn parse_resource_type(s: &str) -> anyhow::Result<ResourceType> {
         _ => anyhow::bail!("Unknown resource type: {}", s)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = concat!(
+        "Private total As Long\n\nSub Add()\n",
+        "    Dim step As Long\n    total = total + step\nEnd Sub\n",
+    );
+
+    fn table_for(source: &str) -> SymbolTable {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        build_symbol_table(Url::parse("file:///test.bas").unwrap(), source, &tree)
+    }
+
+    /// The scope of the procedure declared as the symbol with the given JSON id
+    fn procedure_scope(table: &SymbolTable, id: &serde_json::Value) -> u32 {
+        table
+            .all_scopes()
+            .find(|scope| scope.defining_symbol.is_some_and(|s| id == &serde_json::json!(s.0)))
+            .unwrap()
+            .id
+            .0
+    }
+
+    #[test]
+    fn test_symbols_to_json() {
+        let table = table_for(SOURCE);
+        let json = symbols_to_json(&table, "/project/Module1.bas", false);
+
+        assert_eq!(json["file"], "/project/Module1.bas");
+        assert!(json.get("references").is_none());
+        let symbols = json["symbols"].as_array().unwrap();
+        let symbol = |name: &str| symbols.iter().find(|s| s["name"] == name).unwrap();
+
+        let total = symbol("total");
+        assert_eq!(total["kind"], "Variable");
+        assert_eq!(total["visibility"], "Private");
+        assert_eq!(total["type"], "Long");
+        assert_eq!(total["scope_id"], table.module_scope.0);
+        assert_eq!(total["name_range"]["start"], serde_json::json!({ "line": 0, "character": 8 }));
+
+        // A local belongs to the scope of its procedure
+        let step = symbol("step");
+        assert_eq!(step["kind"], "LocalVariable");
+        assert_eq!(step["scope_id"], procedure_scope(&table, &symbol("Add")["id"]));
+        assert_ne!(step["scope_id"], table.module_scope.0);
+    }
+
+    #[test]
+    fn test_symbols_to_json_with_references() {
+        let table = table_for(SOURCE);
+        let json = symbols_to_json(&table, "/project/Module1.bas", true);
+
+        let symbols = json["symbols"].as_array().unwrap();
+        let id_of = |name: &str| &symbols.iter().find(|s| s["name"] == name).unwrap()["id"];
+        let references = json["references"].as_array().unwrap();
+        let to = |name: &str| -> Vec<&serde_json::Value> {
+            references.iter().filter(|r| &r["symbol_id"] == id_of(name)).collect()
+        };
+
+        // `total = total + step` writes and reads total, and reads step, in Add
+        let add_scope = procedure_scope(&table, id_of("Add"));
+        let total = to("total");
+        let assignments: Vec<&serde_json::Value> =
+            total.iter().map(|r| &r["is_assignment"]).collect();
+        assert_eq!(assignments, vec![true, false]);
+        assert!(total.iter().all(|r| r["scope_id"] == add_scope));
+
+        let step = to("step");
+        assert_eq!(step.len(), 1);
+        assert_eq!(step[0]["range"]["start"], serde_json::json!({ "line": 4, "character": 20 }));
+        assert_eq!(step[0]["scope_id"], add_scope);
+    }
+
+    #[test]
+    fn test_read_string_entries_from_csv() {
+        let path = std::env::temp_dir().join(format!("vb6_lsp_strings_{}.csv", std::process::id()));
+        std::fs::write(
+            &path,
+            "id,value\r\n1, \"Hello, \"\"world\"\"\" \r\n2,Plain text\r\n\r\n3,  Spaced\r\n",
+        )
+        .unwrap();
+        let entries = read_string_entries(path.to_str().unwrap());
+        std::fs::remove_file(&path).ok();
+
+        let entries: Vec<(u16, String)> =
+            entries.unwrap().into_iter().map(|e| (e.id, e.value)).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (1, "Hello, \"world\"".to_string()),
+                (2, "Plain text".to_string()),
+                (3, "Spaced".to_string()),
+            ]
+        );
+    }
+}
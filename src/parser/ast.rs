@@ -5,9 +5,11 @@ use std::collections::HashMap;
 /// Complete VB6 AST for a source file
 #[derive(Debug, Clone)]
 pub struct Vb6Ast {
-    pub file_type: FileType,
+    pub file_type: Vb6FileKind,
     pub options: Vec<String>,
     pub attributes: Vec<String>,
+    /// Instancing attributes from the header of a class or form
+    pub class_attributes: ClassAttributes,
     pub comments: HashMap<usize, String>,
     pub imports: Vec<String>,
     pub variables: Vec<Variable>,
@@ -15,15 +17,18 @@ pub struct Vb6Ast {
     pub user_types: Vec<UserType>,
     pub enums: Vec<Enumeration>,
     pub procedures: Vec<Procedure>,
+    /// `Declare` statements, which also appear in `procedures`
+    pub declarations: Vec<ApiDeclaration>,
     pub statements: HashMap<usize, String>,
 }
 
 impl Vb6Ast {
     pub fn new() -> Self {
         Self {
-            file_type: FileType::Module,
+            file_type: Vb6FileKind::Module,
             options: Vec::new(),
             attributes: Vec::new(),
+            class_attributes: ClassAttributes::default(),
             comments: HashMap::new(),
             imports: Vec::new(),
             variables: Vec::new(),
@@ -31,6 +36,7 @@ impl Vb6Ast {
             user_types: Vec::new(),
             enums: Vec::new(),
             procedures: Vec::new(),
+            declarations: Vec::new(),
             statements: HashMap::new(),
         }
     }
@@ -67,6 +73,10 @@ impl Vb6Ast {
         self.procedures.push(proc);
     }
 
+    pub fn add_declaration(&mut self, declaration: ApiDeclaration) {
+        self.declarations.push(declaration);
+    }
+
     pub fn add_statement(&mut self, line: usize, content: &str) {
         self.statements.insert(line, content.to_string());
     }
@@ -79,12 +89,180 @@ impl Default for Vb6Ast {
 }
 
 /// VB6 file type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vb6FileKind {
+    Module,       // .bas
+    Class,        // .cls
+    Form,         // .frm
+    UserControl,  // .ctl
+    PropertyPage, // .pag
+}
+
+impl Vb6FileKind {
+    /// Get the kind of a file from its extension (case-insensitive)
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "bas" => Some(Self::Module),
+            "cls" => Some(Self::Class),
+            "frm" => Some(Self::Form),
+            "ctl" => Some(Self::UserControl),
+            "pag" => Some(Self::PropertyPage),
+            _ => None,
+        }
+    }
+
+    /// Get the kind of a file from its path
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+        Self::from_extension(extension)
+    }
+
+    /// Check if files of this kind start with a designer (`Begin ... End`) block
+    pub fn has_designer(self) -> bool {
+        matches!(self, Self::Form | Self::UserControl | Self::PropertyPage)
+    }
+}
+
+/// Instancing attributes from a class or form header (`Attribute VB_Creatable = False`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassAttributes {
+    /// `VB_Name`: the class name
+    pub name: Option<String>,
+    /// `VB_GlobalNameSpace`: members can be used without qualification
+    pub global_namespace: bool,
+    /// `VB_Creatable`: other projects can create instances
+    pub creatable: bool,
+    /// `VB_PredeclaredId`: a default instance is available under the class name
+    pub predeclared_id: bool,
+    /// `VB_Exposed`: the class is visible outside its project
+    pub exposed: bool,
+}
+
+impl ClassAttributes {
+    /// Record an `Attribute name = value` line; other attributes are ignored
+    pub fn set(&mut self, name: &str, value: &str) {
+        let value = value.trim();
+        let flag = value.eq_ignore_ascii_case("true") || value == "-1";
+
+        match name.trim().to_lowercase().as_str() {
+            "vb_name" => self.name = Some(value.trim_matches('"').to_string()),
+            "vb_globalnamespace" => self.global_namespace = flag,
+            "vb_creatable" => self.creatable = flag,
+            "vb_predeclaredid" => self.predeclared_id = flag,
+            "vb_exposed" => self.exposed = flag,
+            _ => {}
+        }
+    }
+
+    /// The Instancing property these attributes correspond to in the VB6 IDE
+    pub fn instancing(&self) -> &'static str {
+        match (self.exposed, self.creatable, self.global_namespace) {
+            (false, _, _) => "Private",
+            (true, false, _) => "PublicNotCreatable",
+            (true, true, true) => "GlobalMultiUse",
+            (true, true, false) => "MultiUse",
+        }
+    }
+}
+
+/// A VB6 file split into its header, designer, and code sections
+#[derive(Debug, Clone)]
+pub struct Vb6File {
+    pub kind: Vb6FileKind,
+    /// Value of the `VERSION` line (e.g., "5.00")
+    pub version: Option<String>,
+    /// Class settings from the `BEGIN ... END` header block (e.g., MultiUse = -1)
+    pub class_settings: Vec<(String, String)>,
+    /// `Object = ...` references to ActiveX controls
+    pub object_references: Vec<HeaderReference>,
+    /// `Reference = ...` references to type libraries and other projects
+    pub references: Vec<HeaderReference>,
+    /// Root designer block of a form, user control, or property page
+    pub designer: Option<DesignerControl>,
+    /// `Attribute` values in the header, by name (e.g., VB_Name -> "frmMain")
+    pub attributes: Vec<(String, String)>,
+    /// Line where the code section starts (0-indexed)
+    pub code_start_line: usize,
+    /// The code section
+    pub code: Vb6Ast,
+}
+
+impl Vb6File {
+    /// Get a header attribute value by name (case-insensitive), with quotes removed
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim_matches('"'))
+    }
+}
+
+/// An `Object = ...` or `Reference = ...` line of a file header
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum FileType {
-    Module,      // .bas
-    Class,       // .cls
-    Form,        // .frm
-    UserControl, // .ctl
+pub struct HeaderReference {
+    /// GUID of the control or type library, with braces; None for a
+    /// reference to another project (`*\A...`)
+    pub guid: Option<String>,
+    /// Version of the control or type library (e.g., "2.0")
+    pub version: Option<String>,
+    /// OCX of an `Object` line, type library path of a `Reference` line, or
+    /// the path of a referenced project
+    pub file: Option<String>,
+    /// Value after the `=`, as written
+    pub value: String,
+    /// Line of the reference (0-indexed)
+    pub line: usize,
+}
+
+impl HeaderReference {
+    /// Parse the value of an `Object` line (e.g., `"{GUID}#2.0#0"; "MSCOMCTL.OCX"`)
+    pub fn parse_object(value: &str, line: usize) -> Self {
+        let value = value.trim();
+        let (target, file) = match value.split_once(';') {
+            Some((target, file)) => (target, Some(file.trim().trim_matches('"').to_string())),
+            None => (value, None),
+        };
+        Self::parse_target(target.trim().trim_matches('"'), file, value, line)
+    }
+
+    /// Parse the value of a `Reference` line
+    /// (e.g., `*\G{GUID}#2.0#0#C:\Windows\System32\stdole2.tlb#OLE Automation`)
+    pub fn parse_reference(value: &str, line: usize) -> Self {
+        let value = value.trim();
+        let file = value.split('#').nth(3).filter(|path| !path.is_empty()).map(str::to_string);
+        Self::parse_target(value, file, value, line)
+    }
+
+    /// Read the GUID and version of `*\G{GUID}#version#...` or `{GUID}#version#...`,
+    /// or the project path of `*\A<path>`
+    fn parse_target(target: &str, file: Option<String>, value: &str, line: usize) -> Self {
+        if let Some(project) = target.strip_prefix("*\\A") {
+            return Self {
+                guid: None,
+                version: None,
+                file: Some(project.trim().to_string()),
+                value: value.to_string(),
+                line,
+            };
+        }
+
+        let mut parts = target.trim_start_matches("*\\G").split('#');
+        let guid = parts.next().filter(|guid| guid.starts_with('{')).map(str::to_string);
+        let version = parts.next().filter(|version| !version.is_empty()).map(str::to_string);
+        Self { guid, version, file, value: value.to_string(), line }
+    }
+}
+
+/// A `Begin <type> <name> ... End` block in a designer
+#[derive(Debug, Clone)]
+pub struct DesignerControl {
+    /// Control type as written (e.g., "VB.CommandButton")
+    pub control_type: String,
+    pub name: String,
+    /// Property values as written; `BeginProperty` blocks are flattened (e.g., "Font.Name")
+    pub properties: Vec<(String, String)>,
+    pub children: Vec<DesignerControl>,
+    pub line: usize,
 }
 
 /// Visibility modifier
@@ -103,6 +281,64 @@ pub struct Variable {
     pub visibility: Visibility,
     pub line: usize,
     pub is_array: bool,
+    /// Declared bounds of a fixed-size array; empty for a dynamic array `()`
+    pub dimensions: Vec<ArrayDimension>,
+}
+
+/// The bounds of one array dimension, as written (`1 To 10` or `10`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayDimension {
+    /// Lower bound; without one it is `Option Base` (0 unless set to 1)
+    pub lower: Option<String>,
+    pub upper: String,
+}
+
+impl ArrayDimension {
+    /// Parse the bounds of one dimension (e.g., "1 To 10", "MAX_ITEMS")
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        match text.to_ascii_lowercase().find(" to ") {
+            Some(pos) => Self {
+                lower: Some(text[..pos].trim().to_string()),
+                upper: text[pos + 4..].trim().to_string(),
+            },
+            None => Self { lower: None, upper: text.to_string() },
+        }
+    }
+
+    /// Parse the dimensions between the parentheses of a declaration
+    /// (e.g., "1 To 10, 5"); a dynamic array `()` has none
+    pub fn parse_list(text: &str) -> Vec<Self> {
+        let mut dimensions = Vec::new();
+        let mut depth = 0;
+        let mut start = 0;
+        for (i, c) in text.char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    dimensions.push(&text[start..i]);
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        dimensions.push(&text[start..]);
+
+        if dimensions.iter().all(|d| d.trim().is_empty()) {
+            return Vec::new();
+        }
+        dimensions.into_iter().map(Self::parse).collect()
+    }
+}
+
+impl std::fmt::Display for ArrayDimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.lower {
+            Some(ref lower) => write!(f, "{} To {}", lower, self.upper),
+            None => write!(f, "{}", self.upper),
+        }
+    }
 }
 
 /// Constant declaration
@@ -128,6 +364,7 @@ pub struct UserType {
 pub struct TypeMember {
     pub name: String,
     pub member_type: String,
+    pub is_array: bool,
 }
 
 /// Enumeration
@@ -168,6 +405,24 @@ pub struct Procedure {
     pub end_line: Option<usize>,
 }
 
+/// External API declaration (`Declare Function ... Lib "..."`)
+#[derive(Debug, Clone)]
+pub struct ApiDeclaration {
+    pub name: String,
+    pub visibility: Visibility,
+    /// Function rather than Sub
+    pub is_function: bool,
+    /// Marked `PtrSafe` for 64-bit VBA
+    pub ptr_safe: bool,
+    /// DLL name from the `Lib` clause, without quotes
+    pub library: String,
+    /// Entry point name from the `Alias` clause, without quotes
+    pub alias: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<String>,
+    pub line: usize,
+}
+
 /// Parameter
 #[derive(Debug, Clone)]
 pub struct Parameter {
@@ -175,6 +430,39 @@ pub struct Parameter {
     pub param_type: Option<String>,
     pub by_ref: bool,
     pub optional: bool,
+    /// `ParamArray`, which takes all remaining arguments
+    pub is_param_array: bool,
+    /// Default value of an optional parameter
+    pub default_value: Option<String>,
+}
+
+impl Parameter {
+    /// Format the parameter as declared (e.g., "Optional ByVal x As Long = 0")
+    pub fn format_signature(&self) -> String {
+        let mut parts = Vec::new();
+        if self.optional {
+            parts.push("Optional");
+        }
+        if self.is_param_array {
+            parts.push("ParamArray");
+        } else if self.by_ref {
+            parts.push("ByRef");
+        } else {
+            parts.push("ByVal");
+        }
+
+        let mut signature = format!("{} {}", parts.join(" "), self.name);
+        if self.is_param_array {
+            signature.push_str("()");
+        }
+        if let Some(ref param_type) = self.param_type {
+            signature.push_str(&format!(" As {}", param_type));
+        }
+        if let Some(ref default) = self.default_value {
+            signature.push_str(&format!(" = {}", default));
+        }
+        signature
+    }
 }
 
 /// Symbol information for LSP operations
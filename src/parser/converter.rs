@@ -5,23 +5,196 @@
 
 use tree_sitter::{Node, Tree};
 use super::ast::*;
+use super::preprocessor::InactiveRegion;
 
-/// Convert a tree-sitter tree to a Vb6Ast
-pub fn convert_tree(tree: &Tree, source: &str) -> Vb6Ast {
+/// Convert a tree-sitter tree to a Vb6Ast, skipping code excluded by #If directives
+pub fn convert_tree(tree: &Tree, source: &str, inactive: &[InactiveRegion]) -> Vb6Ast {
     let mut ast = Vb6Ast::new();
     let root = tree.root_node();
 
     // Walk all top-level children
     let mut cursor = root.walk();
     for child in root.children(&mut cursor) {
-        convert_node(&child, source, &mut ast);
+        convert_node(&child, source, inactive, &mut ast);
     }
 
     ast
 }
 
+/// Top-level node kinds that make up a file's header
+const HEADER_KINDS: &[&str] = &[
+    "module_header",
+    "module_config",
+    "module_reference",
+    "form_block",
+    "attribute_statement",
+];
+
+/// Split a parsed file into its header, designer, and code sections
+pub fn convert_file(tree: &Tree, source: &str, kind: Vb6FileKind, code: Vb6Ast) -> Vb6File {
+    let mut file = Vb6File {
+        kind,
+        version: None,
+        class_settings: Vec::new(),
+        object_references: Vec::new(),
+        references: header_references(source),
+        designer: None,
+        attributes: Vec::new(),
+        code_start_line: source.lines().count(),
+        code,
+    };
+
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for child in root.named_children(&mut cursor) {
+        match child.kind() {
+            "module_header" => {
+                file.version = child
+                    .named_child(0)
+                    .map(|version| node_text(&version, source).to_string());
+            }
+            "module_config" if kind == Vb6FileKind::Class => {
+                let mut inner = child.walk();
+                for element in child.named_children(&mut inner) {
+                    if let Some(setting) = name_value(&element, source) {
+                        file.class_settings.push(setting);
+                    }
+                }
+            }
+            "module_reference" => {
+                let text = node_text(&child, source);
+                let value = text.split_once('=').map_or("", |(_, value)| value);
+                let reference = HeaderReference::parse_object(value, node_line(&child));
+                file.object_references.push(reference);
+            }
+            "form_block" if kind.has_designer() && file.designer.is_none() => {
+                file.designer = Some(convert_designer_control(&child, source));
+            }
+            "attribute_statement" => {
+                if let Some((name, value)) = attribute_name_value(&child, source) {
+                    file.attributes.push((name.to_string(), value.to_string()));
+                }
+            }
+            other if HEADER_KINDS.contains(&other) => {}
+            _ if file.references.iter().any(|r| r.line == node_line(&child)) => {}
+            _ => {
+                file.code_start_line = node_line(&child);
+                break;
+            }
+        }
+    }
+
+    file
+}
+
+/// The `Reference = ...` lines of a file header
+pub fn header_references(source: &str) -> Vec<HeaderReference> {
+    reference_lines(source)
+        .map(|(line, _, value)| HeaderReference::parse_reference(value, line))
+        .collect()
+}
+
+/// The `Reference = ...` lines of a file header, which are kept out of the
+/// analysis of the tree like code excluded by `#If`
+pub fn reference_regions(source: &str) -> Vec<InactiveRegion> {
+    reference_lines(source)
+        .map(|(line, bytes, _)| InactiveRegion {
+            start_byte: bytes.start,
+            end_byte: bytes.end,
+            start_line: line,
+            end_line: line,
+        })
+        .collect()
+}
+
+/// Find the `Reference = *\...` lines: their line, bytes (with the line
+/// break), and value. The grammar does not parse them, so they are read
+/// from the text, stopping where the header ends.
+fn reference_lines(
+    source: &str,
+) -> impl Iterator<Item = (usize, std::ops::Range<usize>, &str)> + '_ {
+    let mut depth = 0;
+    let mut offset = 0;
+    let header = source.split_inclusive('\n').enumerate().take_while(move |(_, text)| {
+        let upper = text.trim().to_uppercase();
+        upper.is_empty() || super::Vb6Parser::is_header_line(&upper, &mut depth)
+    });
+    header.filter_map(move |(line, text)| {
+        let start = offset;
+        offset += text.len();
+
+        let trimmed = text.trim_start();
+        let keyword = trimmed.get(..9).filter(|k| k.eq_ignore_ascii_case("reference"))?;
+        let value = trimmed[keyword.len()..].trim_start().strip_prefix('=')?.trim();
+        value.starts_with("*\\").then_some((line, start..offset, value))
+    })
+}
+
+/// Get the name and value of a `Name = Value` element, skipping trailing comments
+fn name_value(node: &Node, source: &str) -> Option<(String, String)> {
+    let mut cursor = node.walk();
+    let mut parts = node
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() != "comment")
+        .map(|child| node_text(&child, source).trim().to_string());
+    Some((parts.next()?, parts.next()?))
+}
+
+/// Convert a designer `Begin ... End` block and the controls nested in it
+fn convert_designer_control(node: &Node, source: &str) -> DesignerControl {
+    let mut control = DesignerControl {
+        control_type: find_field(node, "type")
+            .map(|n| node_text(&n, source).to_string())
+            .unwrap_or_default(),
+        name: find_field(node, "name")
+            .map(|n| node_text(&n, source).to_string())
+            .unwrap_or_default(),
+        properties: Vec::new(),
+        children: Vec::new(),
+        line: node_line(node),
+    };
+    collect_designer_elements(node, source, "", &mut control);
+    control
+}
+
+/// Collect the property lines and nested controls of a designer block.
+/// Properties inside `BeginProperty` blocks are prefixed with the block's name.
+fn collect_designer_elements(
+    node: &Node,
+    source: &str,
+    prefix: &str,
+    control: &mut DesignerControl,
+) {
+    let mut cursor = node.walk();
+    for element in find_children_by_kind(node, "form_element") {
+        for child in element.named_children(&mut cursor) {
+            match child.kind() {
+                "form_property_line" => {
+                    if let Some((name, value)) = name_value(&child, source) {
+                        control.properties.push((format!("{}{}", prefix, name), value));
+                    }
+                }
+                "form_property_block" => {
+                    let name = find_children_by_kind(&child, "form_property_name")
+                        .first()
+                        .map(|n| node_text(n, source).to_string())
+                        .unwrap_or_default();
+                    let prefix = format!("{}{}.", prefix, name);
+                    collect_designer_elements(&child, source, &prefix, control);
+                }
+                "form_block" => control.children.push(convert_designer_control(&child, source)),
+                _ => {}
+            }
+        }
+    }
+}
+
 /// Convert a single node and its relevant children
-fn convert_node(node: &Node, source: &str, ast: &mut Vb6Ast) {
+fn convert_node(node: &Node, source: &str, inactive: &[InactiveRegion], ast: &mut Vb6Ast) {
+    if inactive.iter().any(|r| r.contains_node(node)) {
+        return;
+    }
+
     match node.kind() {
         "option_statement" => convert_option(node, source, ast),
         "attribute_statement" => convert_attribute(node, source, ast),
@@ -37,7 +210,7 @@ fn convert_node(node: &Node, source: &str, ast: &mut Vb6Ast) {
         "function_declaration" => convert_function(node, source, ast),
         "property_declaration" => convert_property(node, source, ast),
         "preproc_const" => convert_preproc_const(node, source, ast),
-        "preproc_if" | "preproc_elseif" | "preproc_else" => convert_preproc_if(node, source, ast),
+        "preproc_if" | "preproc_elseif" | "preproc_else" => convert_preproc_if(node, source, inactive, ast),
         "comment" => convert_comment(node, source, ast),
         _ => {}
     }
@@ -62,10 +235,15 @@ fn convert_preproc_const(node: &Node, source: &str, ast: &mut Vb6Ast) {
 }
 
 /// Convert preprocessor if block - recursively process children
-fn convert_preproc_if(node: &Node, source: &str, ast: &mut Vb6Ast) {
+fn convert_preproc_if(node: &Node, source: &str, inactive: &[InactiveRegion], ast: &mut Vb6Ast) {
     // Process all children nodes within the preprocessor block
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
+        // Skip branches excluded by the directive's condition
+        if inactive.iter().any(|r| r.contains_node(&child)) {
+            continue;
+        }
+
         match child.kind() {
             // Recursively process module elements inside preprocessor blocks
             "variable_declaration" => convert_variable(&child, source, ast),
@@ -79,7 +257,7 @@ fn convert_preproc_if(node: &Node, source: &str, ast: &mut Vb6Ast) {
             "sub_declaration" => convert_sub(&child, source, ast),
             "function_declaration" => convert_function(&child, source, ast),
             "property_declaration" => convert_property(&child, source, ast),
-            "preproc_elseif" | "preproc_else" => convert_preproc_if(&child, source, ast),
+            "preproc_elseif" | "preproc_else" => convert_preproc_if(&child, source, inactive, ast),
             "comment" => convert_comment(&child, source, ast),
             _ => {}
         }
@@ -106,6 +284,17 @@ fn convert_option(node: &Node, source: &str, ast: &mut Vb6Ast) {
 fn convert_attribute(node: &Node, source: &str, ast: &mut Vb6Ast) {
     let text = node_text(node, source);
     ast.add_attribute(node_line(node), text);
+
+    if let Some((name, value)) = attribute_name_value(node, source) {
+        ast.class_attributes.set(name, value);
+    }
+}
+
+/// Split `Attribute Name = Value` into its trimmed name and value
+fn attribute_name_value<'a>(node: &Node, source: &'a str) -> Option<(&'a str, &'a str)> {
+    let text = node_text(node, source).get("Attribute".len()..)?;
+    let (name, value) = text.split_once('=')?;
+    Some((name.trim(), value.trim()))
 }
 
 /// Convert comment
@@ -116,35 +305,57 @@ fn convert_comment(node: &Node, source: &str, ast: &mut Vb6Ast) {
 
 /// Convert Declare statement (external API declaration)
 fn convert_declare(node: &Node, source: &str, ast: &mut Vb6Ast) {
-    let visibility = extract_visibility(node, source);
-    let line = node_line(node);
-
-    if let Some(name_node) = find_field(node, "name") {
-        let name = node_text(&name_node, source).to_string();
-        let parameters = convert_parameters(node, source);
+    let Some(declaration) = convert_declare_statement(node, source) else {
+        return;
+    };
 
-        // Check if it's a Sub or Function by looking for return type
-        let proc_type = if find_children_by_kind(node, "as_clause").is_empty() {
-            ProcedureType::Sub
-        } else {
+    // Declare statements have no body, so they end on their own line
+    ast.add_procedure(Procedure {
+        name: format!("Declare {}", declaration.name),  // Prefix to indicate it's a Declare
+        proc_type: if declaration.is_function {
             ProcedureType::Function
-        };
+        } else {
+            ProcedureType::Sub
+        },
+        visibility: declaration.visibility,
+        line: declaration.line,
+        parameters: declaration.parameters.clone(),
+        return_type: declaration.return_type.clone(),
+        end_line: Some(declaration.line),
+    });
+    ast.add_declaration(declaration);
+}
 
-        let return_type = find_children_by_kind(node, "as_clause")
-            .first()
-            .and_then(|ac| extract_type_from_as_clause(ac, source));
+/// Read a `declare_statement` node into its name, library, alias, and signature
+pub fn convert_declare_statement(node: &Node, source: &str) -> Option<ApiDeclaration> {
+    let name_node = find_field(node, "name")?;
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    let is_keyword =
+        |child: &Node, keyword: &str| node_text(child, source).eq_ignore_ascii_case(keyword);
+
+    // The library is the first string after Lib, the alias the one after Alias
+    let string_after = |keyword: &str| {
+        let position = children.iter().position(|child| is_keyword(child, keyword))?;
+        children
+            .get(position + 1)
+            .filter(|child| child.kind() == "string_literal")
+            .map(|literal| node_text(literal, source).trim_matches('"').to_string())
+    };
 
-        // Declare statements don't have end lines
-        ast.add_procedure(Procedure {
-            name: format!("Declare {}", name),  // Prefix to indicate it's a Declare
-            proc_type,
-            visibility,
-            line,
-            parameters,
-            return_type,
-            end_line: None,
-        });
-    }
+    Some(ApiDeclaration {
+        name: node_text(&name_node, source).to_string(),
+        visibility: extract_visibility(node, source),
+        is_function: children.iter().any(|child| is_keyword(child, "function")),
+        ptr_safe: children.iter().any(|child| is_keyword(child, "ptrsafe")),
+        library: string_after("lib").unwrap_or_default(),
+        alias: string_after("alias"),
+        parameters: convert_parameters(node, source),
+        return_type: find_children_by_kind(node, "as_clause")
+            .first()
+            .and_then(|ac| extract_type_from_as_clause(ac, source)),
+        line: node_line(node),
+    })
 }
 
 /// Convert Event statement
@@ -164,7 +375,7 @@ fn convert_event(node: &Node, source: &str, ast: &mut Vb6Ast) {
             line,
             parameters,
             return_type: None,
-            end_line: None,
+            end_line: Some(line),
         });
     }
 }
@@ -236,7 +447,12 @@ fn convert_variable(node: &Node, source: &str, ast: &mut Vb6Ast) {
                 let name = node_text(&name_node, source).to_string();
 
                 // Check for array bounds
-                let is_array = find_children_by_kind(&vd, "array_bounds").len() > 0;
+                let bounds = find_children_by_kind(&vd, "array_bounds");
+                let is_array = !bounds.is_empty();
+                let dimensions = bounds
+                    .first()
+                    .map(|bounds| array_dimensions(bounds, source))
+                    .unwrap_or_default();
 
                 // Get type from as_clause
                 let var_type = find_children_by_kind(&vd, "as_clause")
@@ -249,12 +465,35 @@ fn convert_variable(node: &Node, source: &str, ast: &mut Vb6Ast) {
                     visibility,
                     line,
                     is_array,
+                    dimensions,
                 });
             }
         }
     }
 }
 
+/// The dimensions of an `array_bounds` node, one per subscript
+pub fn array_dimensions(bounds: &Node, source: &str) -> Vec<ArrayDimension> {
+    find_children_by_kind(bounds, "subscript")
+        .iter()
+        .filter_map(|subscript| {
+            let mut cursor = subscript.walk();
+            let bounds: Vec<&str> = subscript
+                .named_children(&mut cursor)
+                .map(|expression| node_text(&expression, source))
+                .collect();
+            match bounds.as_slice() {
+                [lower, upper] => Some(ArrayDimension {
+                    lower: Some(lower.to_string()),
+                    upper: upper.to_string(),
+                }),
+                [upper] => Some(ArrayDimension { lower: None, upper: upper.to_string() }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
 /// Convert constant declaration
 fn convert_constant(node: &Node, source: &str, ast: &mut Vb6Ast) {
     let visibility = extract_visibility(node, source);
@@ -293,14 +532,27 @@ fn convert_type(node: &Node, source: &str, ast: &mut Vb6Ast) {
             if let Some(member_name_node) = find_field(&tm, "name") {
                 let member_name = node_text(&member_name_node, source).to_string();
 
-                let member_type = find_children_by_kind(&tm, "as_clause")
-                    .first()
+                let as_clause = find_children_by_kind(&tm, "as_clause").into_iter().next();
+                let mut member_type = as_clause
+                    .as_ref()
                     .and_then(|ac| extract_type_from_as_clause(ac, source))
                     .unwrap_or_else(|| "Variant".to_string());
 
+                // Fixed-length string (e.g., "String * 32")
+                if let Some(length) = as_clause
+                    .as_ref()
+                    .and_then(|ac| find_children_by_kind(ac, "field_length").into_iter().next())
+                {
+                    let length = node_text(&length, source).trim_start_matches('*').trim();
+                    member_type = format!("{} * {}", member_type, length);
+                }
+
+                let is_array = !find_children_by_kind(&tm, "array_bounds").is_empty();
+
                 members.push(TypeMember {
                     name: member_name,
                     member_type,
+                    is_array,
                 });
             }
         }
@@ -359,16 +611,22 @@ fn convert_parameters(node: &Node, source: &str) -> Vec<Parameter> {
 
                 let by_ref = !param_text.contains("BYVAL");
                 let optional = param_text.contains("OPTIONAL");
+                let is_param_array = param_text.starts_with("PARAMARRAY");
 
                 let param_type = find_children_by_kind(&param, "as_clause")
                     .first()
                     .and_then(|ac| extract_type_from_as_clause(ac, source));
 
+                let default_value = find_field(&param, "default")
+                    .map(|v| node_text(&v, source).to_string());
+
                 params.push(Parameter {
                     name,
                     param_type,
                     by_ref,
                     optional,
+                    is_param_array,
+                    default_value,
                 });
             }
         }
@@ -522,7 +780,7 @@ mod tests {
     fn parse_and_convert(source: &str) -> Vb6Ast {
         let mut parser = TreeSitterVb6Parser::new().unwrap();
         let tree = parser.parse(source, None).unwrap();
-        convert_tree(&tree, source)
+        convert_tree(&tree, source, &[])
     }
 
     #[test]
@@ -547,6 +805,46 @@ End Sub
         assert_eq!(ast.procedures[0].proc_type, ProcedureType::Sub);
     }
 
+    #[test]
+    fn test_convert_declare() {
+        let source = r#"Private Declare Function FindWindow Lib "user32" Alias "FindWindowA" (ByVal lpClassName As String, ByVal lpWindowName As String) As Long
+Declare Sub Sleep Lib "kernel32" (ByVal ms As Long)
+"#;
+        let ast = parse_and_convert(source);
+        assert_eq!(ast.declarations.len(), 2);
+
+        let find = &ast.declarations[0];
+        assert_eq!(find.name, "FindWindow");
+        assert!(find.is_function && !find.ptr_safe);
+        assert_eq!(find.visibility, Visibility::Private);
+        assert_eq!(find.library, "user32");
+        assert_eq!(find.alias.as_deref(), Some("FindWindowA"));
+        assert_eq!(find.parameters.len(), 2);
+        assert_eq!(find.return_type.as_deref(), Some("Long"));
+
+        let sleep = &ast.declarations[1];
+        assert_eq!((sleep.name.as_str(), sleep.is_function, sleep.line), ("Sleep", false, 1));
+        assert_eq!(sleep.alias, None);
+        assert_eq!(ast.procedures[1].name, "Declare Sleep");
+    }
+
+    #[test]
+    fn test_convert_optional_and_param_array_parameters() {
+        let source = r#"Sub Log(Optional ByVal level As Long = 2, ParamArray args() As Variant)
+End Sub
+"#;
+        let ast = parse_and_convert(source);
+        let parameters: Vec<String> = ast.procedures[0]
+            .parameters
+            .iter()
+            .map(|p| p.format_signature())
+            .collect();
+        assert_eq!(
+            parameters,
+            vec!["Optional ByVal level As Long = 2", "ParamArray args() As Variant"]
+        );
+    }
+
     #[test]
     fn test_convert_function() {
         let source = r#"
@@ -561,4 +859,43 @@ End Function
         assert_eq!(ast.procedures[0].parameters.len(), 2);
         assert_eq!(ast.procedures[0].return_type, Some("Integer".to_string()));
     }
+
+    #[test]
+    fn test_convert_class_attributes() {
+        let ast = parse_and_convert(
+            "Attribute VB_Name = \"clsLogger\"\n\
+Attribute VB_GlobalNameSpace = False\n\
+Attribute VB_Creatable = True\n\
+Attribute VB_PredeclaredId = True\n\
+Attribute VB_Exposed = True\n",
+        );
+        let attributes = &ast.class_attributes;
+
+        assert_eq!(attributes.name.as_deref(), Some("clsLogger"));
+        assert!(!attributes.global_namespace);
+        assert!(attributes.creatable);
+        assert!(attributes.predeclared_id);
+        assert_eq!(attributes.instancing(), "MultiUse");
+        assert_eq!(ast.attributes.len(), 5);
+
+        let private = parse_and_convert("Attribute VB_Name = \"clsItem\"\nAttribute VB_Exposed = False\n");
+        assert!(!private.class_attributes.predeclared_id);
+        assert_eq!(private.class_attributes.instancing(), "Private");
+    }
+
+    #[test]
+    fn test_reference_regions_stop_at_code() {
+        let source = "VERSION 1.0 CLASS\n\
+BEGIN\n\
+  MultiUse = -1  'True\n\
+END\n\
+Reference = *\\G{00020430-0000-0000-C000-000000000046}#2.0#0#stdole2.tlb#OLE Automation\n\
+Attribute VB_Name = \"clsFoo\"\n\
+Sub Go()\n\
+End Sub\n\
+Reference = *\\G{00000000-0000-0000-0000-000000000000}#1.0#0#late.tlb#Late\n";
+        let regions = reference_regions(source);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].start_line, 4);
+    }
 }
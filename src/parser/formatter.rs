@@ -0,0 +1,900 @@
+//! Source Formatting
+//!
+//! Re-indents VB6 code by block structure and normalizes spacing around
+//! operators. Each line is tokenized first, so string literals, comments,
+//! type hints, and single-line `If` statements are never mistaken for
+//! block structure.
+
+/// Options controlling the formatter
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// Spaces per indentation level
+    pub tab_size: usize,
+    /// Indent with spaces instead of tabs
+    pub insert_spaces: bool,
+    /// Put each `:`-separated statement on its own line
+    pub split_statements: bool,
+    /// Remove trailing whitespace from every line, including comments and designer blocks
+    pub trim_trailing_whitespace: bool,
+    /// End the document with a newline
+    pub insert_final_newline: bool,
+    /// Remove blank lines at the end of the document
+    pub trim_final_newlines: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            tab_size: 4,
+            insert_spaces: true,
+            split_statements: false,
+            trim_trailing_whitespace: false,
+            insert_final_newline: false,
+            trim_final_newlines: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    fn indent(&self, depth: usize) -> String {
+        if self.insert_spaces {
+            " ".repeat(self.tab_size * depth)
+        } else {
+            "\t".repeat(depth)
+        }
+    }
+}
+
+/// Formatted output for a run of source lines (one logical line or designer line)
+struct Chunk {
+    /// First source line (0-indexed)
+    start_line: usize,
+    /// Number of source lines covered
+    line_count: usize,
+    /// Formatted replacement lines
+    lines: Vec<String>,
+    /// Indentation of the first line (None for designer lines, which are kept verbatim)
+    indent: Option<String>,
+}
+
+/// Format a whole document, returning the new text
+pub fn format_source(source: &str, options: &FormatOptions) -> String {
+    let eol = if source.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut output: Vec<String> = format_chunks(source, options)
+        .into_iter()
+        .flat_map(|chunk| chunk.lines)
+        .collect();
+
+    if options.trim_final_newlines {
+        while output.last().is_some_and(|l| l.trim().is_empty()) {
+            output.pop();
+        }
+    }
+
+    let mut formatted = output.join(eol);
+    if (source.ends_with('\n') || options.insert_final_newline) && !output.is_empty() {
+        formatted.push_str(eol);
+    }
+    formatted
+}
+
+/// Format the logical lines that lie entirely within `start_line..=end_line`.
+///
+/// Indentation is computed from the block structure of the whole document, so
+/// a range starting mid-block gets the right base indent. Returns the first
+/// source line replaced, the number of lines replaced, and the new lines, or
+/// `None` if no logical line fits in the range.
+pub fn format_range(
+    source: &str,
+    options: &FormatOptions,
+    start_line: usize,
+    end_line: usize,
+) -> Option<(usize, usize, Vec<String>)> {
+    let chunks: Vec<Chunk> = format_chunks(source, options)
+        .into_iter()
+        .filter(|c| c.start_line >= start_line && c.start_line + c.line_count - 1 <= end_line)
+        .collect();
+
+    let first = chunks.first()?.start_line;
+    let last = chunks.last().map(|c| c.start_line + c.line_count)?;
+    let lines = chunks.into_iter().flat_map(|chunk| chunk.lines).collect();
+    Some((first, last - first, lines))
+}
+
+/// Indentation the formatter gives a line, based on the blocks enclosing it.
+///
+/// Blank lines get the indentation of a statement at that point, so this also
+/// tells where to put the cursor on a freshly inserted line.
+pub fn line_indent(source: &str, options: &FormatOptions, line: usize) -> Option<String> {
+    // An extra newline makes a trailing empty line visible to `lines()`
+    let source = format!("{}\n", source);
+    let chunk = format_chunks(&source, options)
+        .into_iter()
+        .find(|c| line >= c.start_line && line < c.start_line + c.line_count)?;
+
+    if line == chunk.start_line {
+        chunk.indent
+    } else {
+        // Continuation line
+        chunk.lines.get(line - chunk.start_line).map(|l| l[..l.len() - l.trim_start().len()].to_string())
+    }
+}
+
+/// Check if a line closes a block or starts a new clause of one (End If, Next, Else, Case)
+pub fn is_block_end(line: &str) -> bool {
+    let tokens = tokenize(line);
+    let code: Vec<&Token> = tokens.iter().filter(|t| t.kind != TokenKind::Comment).collect();
+    let statement = split_statements(&code).into_iter().next().unwrap_or_default();
+    matches!(
+        classify(&statement),
+        Effect::Close(..) | Effect::Middle(_) | Effect::Case
+    )
+}
+
+/// Format every logical line of the document
+fn format_chunks(source: &str, options: &FormatOptions) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut designer_depth = 0usize;
+
+    let mut physical: Vec<Vec<Token>> = Vec::new();
+    let mut start_line = 0;
+    for (line_num, line) in source.lines().enumerate() {
+        // Designer blocks (Begin VB.Form ... End) are kept verbatim
+        if physical.is_empty() && (designer_depth > 0 || is_designer_begin(line)) {
+            designer_depth = designer_line_depth(line, designer_depth);
+            chunks.push(Chunk {
+                start_line: line_num,
+                line_count: 1,
+                lines: vec![line.to_string()],
+                indent: None,
+            });
+            continue;
+        }
+
+        if physical.is_empty() {
+            start_line = line_num;
+        }
+        let tokens = tokenize(line);
+        let continued = tokens.last().is_some_and(|t| t.kind == TokenKind::Continuation);
+        physical.push(tokens);
+
+        if !continued {
+            chunks.push(format_chunk(&physical, start_line, &mut blocks, options));
+            physical.clear();
+        }
+    }
+    // A continuation on the last line has nothing to join with
+    if !physical.is_empty() {
+        chunks.push(format_chunk(&physical, start_line, &mut blocks, options));
+    }
+
+    if options.trim_trailing_whitespace {
+        for line in chunks.iter_mut().flat_map(|c| c.lines.iter_mut()) {
+            line.truncate(line.trim_end().len());
+        }
+    }
+    chunks
+}
+
+fn format_chunk(
+    physical: &[Vec<Token>],
+    start_line: usize,
+    blocks: &mut Vec<Block>,
+    options: &FormatOptions,
+) -> Chunk {
+    let depth = blocks.len();
+    let mut lines = Vec::new();
+    format_logical_line(physical, blocks, options, &mut lines);
+
+    let indent = match lines.first() {
+        Some(first) if !first.trim().is_empty() => first[..first.len() - first.trim_start().len()].to_string(),
+        _ => options.indent(depth),
+    };
+    Chunk {
+        start_line,
+        line_count: physical.len(),
+        lines,
+        indent: Some(indent),
+    }
+}
+
+/// Check if a line opens a designer block (`Begin VB.Form Form1`, or `BEGIN` in a class header)
+fn is_designer_begin(line: &str) -> bool {
+    line.split_whitespace()
+        .next()
+        .is_some_and(|first| first.eq_ignore_ascii_case("begin"))
+}
+
+/// Designer nesting depth after a line inside a designer block
+fn designer_line_depth(line: &str, depth: usize) -> usize {
+    let first = line.split_whitespace().next().unwrap_or("").to_lowercase();
+    match first.as_str() {
+        "begin" | "beginproperty" => depth + 1,
+        "end" | "endproperty" => depth.saturating_sub(1),
+        _ => depth,
+    }
+}
+
+// ==========================================
+// Tokenizer
+// ==========================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Word,
+    Number,
+    Str,
+    Date,
+    Operator,
+    Punct,
+    Comment,
+    Continuation,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    kind: TokenKind,
+    text: &'a str,
+    /// Whitespace that preceded the token in the source
+    space_before: &'a str,
+}
+
+impl Token<'_> {
+    fn is(&self, kind: TokenKind, text: &str) -> bool {
+        self.kind == kind && self.text.eq_ignore_ascii_case(text)
+    }
+
+    fn is_word(&self, text: &str) -> bool {
+        self.is(TokenKind::Word, text)
+    }
+}
+
+fn is_word_start(c: char) -> bool {
+    c.is_alphabetic()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Split one physical line into tokens
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let len = chars.len();
+    let byte = |i: usize| chars.get(i).map(|(b, _)| *b).unwrap_or(line.len());
+    let char_at = |i: usize| chars.get(i).map(|(_, c)| *c);
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i = 0;
+
+    while i < len {
+        let ws_start = i;
+        while char_at(i).is_some_and(|c| c == ' ' || c == '\t') {
+            i += 1;
+        }
+        if i == len {
+            break;
+        }
+
+        let start = i;
+        let c = chars[i].1;
+        let after_word = start > 0 && is_word_char(chars[start - 1].1);
+        let at_statement_start = tokens.last().is_none_or(|t| t.is(TokenKind::Punct, ":"));
+
+        let kind = match c {
+            '\'' => {
+                i = len;
+                TokenKind::Comment
+            }
+            '"' => {
+                i += 1;
+                while i < len {
+                    if chars[i].1 == '"' {
+                        // "" is an escaped quote
+                        if char_at(i + 1) == Some('"') {
+                            i += 2;
+                            continue;
+                        }
+                        i += 1;
+                        break;
+                    }
+                    i += 1;
+                }
+                TokenKind::Str
+            }
+            '[' => {
+                while i < len && chars[i].1 != ']' {
+                    i += 1;
+                }
+                i = (i + 1).min(len);
+                TokenKind::Word
+            }
+            c if is_word_start(c) => {
+                while char_at(i).is_some_and(is_word_char) {
+                    i += 1;
+                }
+                // Type hint suffix (Name$, Count&)
+                if char_at(i).is_some_and(|h| "%$@!#&".contains(h))
+                    && !char_at(i + 1).is_some_and(|n| is_word_char(n) || n == '"')
+                {
+                    i += 1;
+                }
+
+                if at_statement_start && line[byte(start)..byte(i)].eq_ignore_ascii_case("rem") {
+                    i = len;
+                    TokenKind::Comment
+                } else {
+                    TokenKind::Word
+                }
+            }
+            c if c.is_ascii_digit()
+                || (c == '.' && !after_word && char_at(i + 1).is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                while char_at(i).is_some_and(|d| d.is_ascii_digit() || d == '.') {
+                    i += 1;
+                }
+                // Exponent (1.5E+10)
+                if char_at(i).is_some_and(|e| matches!(e, 'e' | 'E' | 'd' | 'D'))
+                    && char_at(i + 1).is_some_and(|n| n.is_ascii_digit() || n == '+' || n == '-')
+                {
+                    i += 2;
+                    while char_at(i).is_some_and(|d| d.is_ascii_digit()) {
+                        i += 1;
+                    }
+                }
+                if char_at(i).is_some_and(|h| "%&!#@".contains(h))
+                    && !char_at(i + 1).is_some_and(|n| is_word_char(n) || n == '"')
+                {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            '&' if !after_word
+                && char_at(i + 1).is_some_and(|r| matches!(r, 'h' | 'H' | 'o' | 'O'))
+                && char_at(i + 2).is_some_and(|d| d.is_ascii_hexdigit()) =>
+            {
+                i += 2;
+                while char_at(i).is_some_and(|d| d.is_ascii_hexdigit()) {
+                    i += 1;
+                }
+                if char_at(i) == Some('&') {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            '#' if tokens.is_empty() && char_at(i + 1).is_some_and(is_word_start) => {
+                // Directive (#If, #Const, ...)
+                i += 1;
+                while char_at(i).is_some_and(is_word_char) {
+                    i += 1;
+                }
+                TokenKind::Word
+            }
+            '#' if !after_word && date_literal_end(&chars, i).is_some() => {
+                i = date_literal_end(&chars, i).unwrap_or(i + 1);
+                TokenKind::Date
+            }
+            '_' if (start == 0 || chars[start - 1].1.is_whitespace())
+                && line[byte(start + 1)..].trim().is_empty() =>
+            {
+                i += 1;
+                TokenKind::Continuation
+            }
+            ':' if char_at(i + 1) == Some('=') => {
+                i += 2;
+                TokenKind::Operator
+            }
+            '<' | '>' => {
+                i += 1;
+                if char_at(i).is_some_and(|n| n == '=' || (c == '<' && n == '>')) {
+                    i += 1;
+                }
+                TokenKind::Operator
+            }
+            '=' | '+' | '-' | '*' | '/' | '\\' | '^' | '&' => {
+                i += 1;
+                TokenKind::Operator
+            }
+            _ => {
+                i += 1;
+                TokenKind::Punct
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            text: &line[byte(start)..byte(i)],
+            space_before: &line[byte(ws_start)..byte(start)],
+        });
+    }
+
+    tokens
+}
+
+/// End index (exclusive) of a `#1/1/2000#` date literal starting at `start`
+fn date_literal_end(chars: &[(usize, char)], start: usize) -> Option<usize> {
+    let close = (start + 1..chars.len()).find(|&i| chars[i].1 == '#')?;
+    let content: String = chars[start + 1..close].iter().map(|(_, c)| *c).collect();
+    let looks_like_date = content.starts_with(|c: char| c.is_ascii_digit())
+        && content.chars().all(|c| c.is_ascii_digit() || " /:-.,APMapm".contains(c));
+    looks_like_date.then_some(close + 1)
+}
+
+// ==========================================
+// Spacing
+// ==========================================
+
+/// Keywords after which `-`/`+` is a sign rather than a binary operator
+const SIGN_CONTEXT_KEYWORDS: &[&str] = &[
+    "and", "or", "not", "xor", "eqv", "imp", "mod", "is", "like", "to", "step", "then", "else",
+    "elseif", "if", "case", "while", "until", "return", "print",
+];
+
+/// How an operator token is spaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OperatorRole {
+    /// Not an operator
+    None,
+    /// One space on each side
+    Binary,
+    /// Sign (-1): no space after
+    Unary,
+    /// Named argument (Prompt:="Hi"): no spaces
+    NamedArgument,
+}
+
+fn operator_role(tokens: &[&Token], i: usize) -> OperatorRole {
+    let token = tokens[i];
+    if token.kind != TokenKind::Operator {
+        return OperatorRole::None;
+    }
+    if token.text == ":=" {
+        return OperatorRole::NamedArgument;
+    }
+    if token.text != "-" && token.text != "+" {
+        return OperatorRole::Binary;
+    }
+
+    let Some(prev) = i.checked_sub(1).map(|p| tokens[p]) else {
+        return OperatorRole::Unary;
+    };
+    let unary = match prev.kind {
+        TokenKind::Operator => true,
+        TokenKind::Punct => matches!(prev.text, "(" | "," | ";" | ":" | "#"),
+        TokenKind::Word => {
+            SIGN_CONTEXT_KEYWORDS.contains(&prev.text.to_lowercase().as_str())
+                // Implicit call with a negative argument (Foo -1)
+                || (is_statement_start(tokens, i - 1)
+                    && !token.space_before.is_empty()
+                    && tokens.get(i + 1).is_some_and(|next| next.space_before.is_empty()))
+        }
+        _ => false,
+    };
+
+    if unary {
+        OperatorRole::Unary
+    } else {
+        OperatorRole::Binary
+    }
+}
+
+/// Check if a token begins a statement
+fn is_statement_start(tokens: &[&Token], i: usize) -> bool {
+    i == 0 || tokens[i - 1].is(TokenKind::Punct, ":")
+}
+
+/// Render tokens on one output line with normalized spacing
+fn render(tokens: &[&Token]) -> String {
+    let roles: Vec<OperatorRole> = (0..tokens.len()).map(|i| operator_role(tokens, i)).collect();
+    let mut out = String::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 {
+            out.push_str(spacing(tokens[i - 1], roles[i - 1], token, roles[i]));
+        }
+        out.push_str(token.text);
+    }
+
+    out
+}
+
+/// Whitespace to put between two adjacent tokens
+fn spacing<'a>(prev: &Token, prev_role: OperatorRole, cur: &Token<'a>, cur_role: OperatorRole) -> &'a str {
+    // Keep trailing comments aligned as written
+    if cur.kind == TokenKind::Comment {
+        return if cur.space_before.is_empty() { " " } else { cur.space_before };
+    }
+    if cur.kind == TokenKind::Continuation {
+        return " ";
+    }
+
+    let collapsed = if cur.space_before.is_empty() { "" } else { " " };
+    if prev_role == OperatorRole::NamedArgument || cur_role == OperatorRole::NamedArgument {
+        return "";
+    }
+    if cur_role == OperatorRole::Binary || prev_role == OperatorRole::Binary {
+        return " ";
+    }
+    if prev_role == OperatorRole::Unary {
+        return "";
+    }
+    // Omitted arguments (Foo(, , x))
+    if prev.is(TokenKind::Punct, ",") && cur.is(TokenKind::Punct, ",") {
+        return " ";
+    }
+    if cur.kind == TokenKind::Punct && matches!(cur.text, "," | ";" | ")" | ":") {
+        return "";
+    }
+    if prev.kind == TokenKind::Punct {
+        match prev.text {
+            "(" | "." | "!" | "#" => return "",
+            "," | ";" | ":" => return " ",
+            _ => {}
+        }
+    }
+
+    collapsed
+}
+
+// ==========================================
+// Indentation
+// ==========================================
+
+/// An open block that indents its body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Block {
+    Procedure,
+    If,
+    For,
+    Do,
+    While,
+    Select,
+    Case,
+    With,
+    Type,
+    Enum,
+}
+
+/// Effect of a statement on the block structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Effect {
+    None,
+    Open(Block),
+    /// Closes the innermost matching block `count` times (Next j, i)
+    Close(Block, usize),
+    /// Else/ElseIf: printed at the level of its If
+    Middle(Block),
+    /// Case clause: closes the previous clause of the Select
+    Case,
+}
+
+/// Modifiers that may precede a declaration keyword
+const MODIFIERS: &[&str] = &["public", "private", "friend", "static", "global"];
+
+/// Classify a statement (comments and continuations already removed)
+fn classify(statement: &[&Token]) -> Effect {
+    let start = statement
+        .iter()
+        .position(|t| !(t.kind == TokenKind::Word && MODIFIERS.contains(&t.text.to_lowercase().as_str())))
+        .unwrap_or(statement.len());
+    let Some(first) = statement.get(start) else {
+        return Effect::None;
+    };
+    if first.kind != TokenKind::Word {
+        return Effect::None;
+    }
+    let second = statement.get(start + 1).map(|t| t.text.to_lowercase()).unwrap_or_default();
+
+    match first.text.to_lowercase().as_str() {
+        "sub" | "function" => Effect::Open(Block::Procedure),
+        "property" if matches!(second.as_str(), "get" | "let" | "set") => Effect::Open(Block::Procedure),
+        "type" => Effect::Open(Block::Type),
+        "enum" => Effect::Open(Block::Enum),
+        "if" if !is_single_line_if(statement) => Effect::Open(Block::If),
+        "for" => Effect::Open(Block::For),
+        "do" => Effect::Open(Block::Do),
+        "while" => Effect::Open(Block::While),
+        "select" => Effect::Open(Block::Select),
+        "with" => Effect::Open(Block::With),
+        "else" | "elseif" => Effect::Middle(Block::If),
+        "case" => Effect::Case,
+        "next" => {
+            let counters = statement.iter().filter(|t| t.is(TokenKind::Punct, ",")).count() + 1;
+            Effect::Close(Block::For, counters)
+        }
+        "loop" => Effect::Close(Block::Do, 1),
+        "wend" => Effect::Close(Block::While, 1),
+        "end" => match second.as_str() {
+            "sub" | "function" | "property" => Effect::Close(Block::Procedure, 1),
+            "if" => Effect::Close(Block::If, 1),
+            "select" => Effect::Close(Block::Select, 1),
+            "with" => Effect::Close(Block::With, 1),
+            "type" => Effect::Close(Block::Type, 1),
+            "enum" => Effect::Close(Block::Enum, 1),
+            _ => Effect::None,
+        },
+        _ => Effect::None,
+    }
+}
+
+/// `If cond Then stmt` (as opposed to a block If, where Then ends the line)
+fn is_single_line_if(statement: &[&Token]) -> bool {
+    statement
+        .iter()
+        .position(|t| t.is_word("then"))
+        .is_some_and(|then| then + 1 < statement.len())
+}
+
+/// Apply a statement's effect to the block stack, returning the depth to print it at
+fn apply_effect(blocks: &mut Vec<Block>, effect: Effect) -> usize {
+    let close = |blocks: &mut Vec<Block>, block: Block| {
+        if let Some(pos) = blocks.iter().rposition(|b| *b == block) {
+            blocks.truncate(pos);
+        }
+    };
+
+    match effect {
+        Effect::None => blocks.len(),
+        Effect::Open(block) => {
+            let depth = blocks.len();
+            blocks.push(block);
+            depth
+        }
+        Effect::Close(block, count) => {
+            for _ in 0..count {
+                close(blocks, block);
+            }
+            blocks.len()
+        }
+        Effect::Middle(block) => {
+            if blocks.contains(&block) {
+                close(blocks, block);
+                let depth = blocks.len();
+                blocks.push(block);
+                depth
+            } else {
+                blocks.len()
+            }
+        }
+        Effect::Case => {
+            if blocks.last() == Some(&Block::Case) {
+                blocks.pop();
+            }
+            let depth = blocks.len();
+            if blocks.last() == Some(&Block::Select) {
+                blocks.push(Block::Case);
+            }
+            depth
+        }
+    }
+}
+
+/// Split a logical line's code tokens into `:`-separated statements.
+///
+/// Everything after the `Then` of a single-line If belongs to that If.
+fn split_statements<'t, 'a>(tokens: &[&'t Token<'a>]) -> Vec<Vec<&'t Token<'a>>> {
+    let mut statements: Vec<Vec<&'t Token<'a>>> = vec![Vec::new()];
+
+    for (i, token) in tokens.iter().enumerate() {
+        let current = statements.last_mut().unwrap();
+        let in_single_line_if = current.first().is_some_and(|t| t.is_word("if"))
+            && current.iter().any(|t| t.is_word("then"));
+
+        // A leading label (Handler:) is its own statement
+        let ends_label = i == 1 && tokens[0].kind == TokenKind::Word && classify(&tokens[..1]) == Effect::None;
+
+        if token.is(TokenKind::Punct, ":") && !in_single_line_if {
+            if ends_label {
+                current.push(token);
+            }
+            statements.push(Vec::new());
+        } else {
+            current.push(token);
+        }
+    }
+
+    statements.retain(|s| !s.is_empty());
+    statements
+}
+
+/// Check if a statement is a label (`Handler:`)
+fn is_label(statement: &[&Token]) -> bool {
+    statement.len() == 2 && statement[0].kind == TokenKind::Word && statement[1].is(TokenKind::Punct, ":")
+}
+
+/// Format one logical line (one or more physical lines joined by ` _`)
+fn format_logical_line(
+    physical: &[Vec<Token>],
+    blocks: &mut Vec<Block>,
+    options: &FormatOptions,
+    output: &mut Vec<String>,
+) {
+    let code: Vec<&Token> = physical
+        .iter()
+        .flatten()
+        .filter(|t| !matches!(t.kind, TokenKind::Comment | TokenKind::Continuation))
+        .collect();
+    let comment = physical.last().and_then(|line| line.iter().find(|t| t.kind == TokenKind::Comment));
+
+    if code.is_empty() {
+        // Blank or comment-only line
+        let line = comment.map(|c| format!("{}{}", options.indent(blocks.len()), c.text));
+        output.push(line.unwrap_or_default());
+        return;
+    }
+
+    let statements = split_statements(&code);
+    let depths: Vec<usize> = statements
+        .iter()
+        .map(|s| if is_label(s) { 0 } else { apply_effect(blocks, classify(s)) })
+        .collect();
+
+    if options.split_statements && physical.len() == 1 && statements.len() > 1 {
+        let last = statements.len() - 1;
+        for (i, (statement, depth)) in statements.iter().zip(&depths).enumerate() {
+            let mut tokens = statement.clone();
+            if i == last {
+                tokens.extend(comment);
+            }
+            output.push(format!("{}{}", options.indent(*depth), render(&tokens)));
+        }
+        return;
+    }
+
+    // Continuation lines are indented one level deeper than the statement
+    let depth = depths[0];
+    for (i, line) in physical.iter().enumerate() {
+        let tokens: Vec<&Token> = line.iter().collect();
+        let level = if i == 0 { depth } else { depth + 1 };
+        output.push(format!("{}{}", options.indent(level), render(&tokens)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(source: &str) -> String {
+        format_source(source, &FormatOptions::default())
+    }
+
+    #[test]
+    fn test_golden_sample() {
+        let input = include_str!("../../tests/fixtures/format/sample.bas");
+        let expected = include_str!("../../tests/fixtures/format/sample.formatted.bas");
+
+        assert_eq!(format(input), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn test_single_line_if_does_not_indent() {
+        let source = "Sub Main()\nIf x Then y = 1\nIf x Then\ny = 2\nEnd If\nEnd Sub\n";
+        assert_eq!(
+            format(source),
+            "Sub Main()\n    If x Then y = 1\n    If x Then\n        y = 2\n    End If\nEnd Sub\n"
+        );
+    }
+
+    #[test]
+    fn test_operator_spacing_preserves_strings() {
+        let source = "x=a+b*-2&\"a=b  +c\"\ns=Mid$(t,1,-n) 'a+b\nMsgBox Prompt:=\"Hi\"\n";
+        assert_eq!(
+            format(source),
+            "x = a + b * -2 & \"a=b  +c\"\ns = Mid$(t, 1, -n) 'a+b\nMsgBox Prompt:=\"Hi\"\n"
+        );
+    }
+
+    #[test]
+    fn test_indent_options() {
+        let source = "Sub Main()\nIf x Then\ny = 1\nEnd If\nEnd Sub\n";
+        let tabs = FormatOptions {
+            tab_size: 2,
+            insert_spaces: false,
+            ..Default::default()
+        };
+        let spaces = FormatOptions {
+            tab_size: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            format_source(source, &tabs),
+            "Sub Main()\n\tIf x Then\n\t\ty = 1\n\tEnd If\nEnd Sub\n"
+        );
+        assert_eq!(
+            format_source(source, &spaces),
+            "Sub Main()\n  If x Then\n    y = 1\n  End If\nEnd Sub\n"
+        );
+    }
+
+    #[test]
+    fn test_whitespace_options() {
+        let source = "' note   \nx = 1\n\n\n";
+        let options = FormatOptions {
+            trim_trailing_whitespace: true,
+            trim_final_newlines: true,
+            ..Default::default()
+        };
+        assert_eq!(format_source(source, &options), "' note\nx = 1\n");
+
+        // Comments keep their trailing whitespace unless asked
+        assert_eq!(format("' note   \nx = 1"), "' note   \nx = 1");
+
+        let options = FormatOptions {
+            insert_final_newline: true,
+            ..Default::default()
+        };
+        assert_eq!(format_source("x = 1", &options), "x = 1\n");
+    }
+
+    #[test]
+    fn test_format_range_uses_enclosing_indent() {
+        let source = "Sub Main()\nIf x Then\ny=1\nz  =  2\nEnd If\nEnd Sub\n";
+
+        assert_eq!(
+            format_range(source, &FormatOptions::default(), 2, 3),
+            Some((2, 2, vec!["        y = 1".to_string(), "        z = 2".to_string()]))
+        );
+        assert_eq!(
+            format_range(source, &FormatOptions::default(), 4, 4),
+            Some((4, 1, vec!["    End If".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_format_range_skips_partial_logical_lines() {
+        let source = "x = 1 + _\n2\ny=3\n";
+
+        // The continued statement starts before the range
+        assert_eq!(
+            format_range(source, &FormatOptions::default(), 1, 2),
+            Some((2, 1, vec!["y = 3".to_string()]))
+        );
+        assert_eq!(format_range(source, &FormatOptions::default(), 1, 1), None);
+    }
+
+    #[test]
+    fn test_line_indent() {
+        let options = FormatOptions::default();
+        let source = "Sub Main()\n    If x Then\n\n        End If\n";
+
+        assert_eq!(line_indent(source, &options, 2).as_deref(), Some("        "));
+        assert_eq!(line_indent(source, &options, 3).as_deref(), Some("    "));
+        assert_eq!(line_indent(source, &options, 4).as_deref(), Some("    "));
+        assert_eq!(line_indent(source, &options, 9), None);
+    }
+
+    #[test]
+    fn test_is_block_end() {
+        assert!(is_block_end("    End Sub"));
+        assert!(is_block_end("Next i"));
+        assert!(is_block_end("ElseIf x Then"));
+        assert!(is_block_end("Case Else ' fallback"));
+        assert!(!is_block_end("End"));
+        assert!(!is_block_end("x = 1"));
+    }
+
+    #[test]
+    fn test_split_colon_statements() {
+        let source = "Sub Main()\nFor i = 1 To 3: x = x + i: Next\nIf a Then b: c\nEnd Sub\n";
+        let options = FormatOptions {
+            split_statements: true,
+            ..Default::default()
+        };
+
+        let formatted = format_source(source, &options);
+        assert_eq!(
+            formatted,
+            "Sub Main()\n    For i = 1 To 3\n        x = x + i\n    Next\n    If a Then b: c\nEnd Sub\n"
+        );
+        assert_eq!(format_source(&formatted, &options), formatted);
+
+        // Without the option the statements stay on one line
+        assert_eq!(
+            format(source),
+            "Sub Main()\n    For i = 1 To 3: x = x + i: Next\n    If a Then b: c\nEnd Sub\n"
+        );
+    }
+}
@@ -9,11 +9,18 @@ mod lexer;
 mod ast;
 mod tree_sitter;
 mod converter;
+mod preprocessor;
+mod formatter;
+mod outline;
 
 pub use ast::*;
 pub use tree_sitter::{TreeSitterVb6Parser, VB6QueryRunner};
-pub use converter::ParseErrorInfo;
+pub use converter::{array_dimensions, convert_declare_statement, ParseErrorInfo};
+pub use preprocessor::{CompilationConstants, InactiveRegion};
+pub use formatter::FormatOptions;
+pub use outline::OutlineItem;
 
+use ropey::Rope;
 use tower_lsp::lsp_types::{Position, Range, TextEdit};
 
 /// Parse error with location information
@@ -23,11 +30,60 @@ pub struct ParseError {
     pub range: Range,
 }
 
+/// A parsed tree converted into an AST
+#[derive(Debug)]
+pub struct Conversion {
+    pub ast: Vb6Ast,
+    /// Regions excluded by #If directives, and the `Reference = ...` header
+    /// lines, which are not code
+    pub inactive_regions: Vec<InactiveRegion>,
+    /// Syntax errors in the tree
+    pub errors: Vec<ParseError>,
+}
+
+/// Convert a parsed tree of `source` into an AST.
+///
+/// Needs no parser, so a caller sharing one can release it once the tree is
+/// parsed.
+pub fn convert(
+    tree: &::tree_sitter::Tree,
+    source: &str,
+    constants: &CompilationConstants,
+) -> Conversion {
+    // Find header lines the grammar cannot parse and code excluded by #If directives
+    let mut inactive_regions = converter::reference_regions(source);
+    inactive_regions.extend(preprocessor::find_inactive_regions(tree, source, constants));
+
+    Conversion {
+        ast: converter::convert_tree(tree, source, &inactive_regions),
+        inactive_regions,
+        errors: syntax_errors(tree, source),
+    }
+}
+
+fn syntax_errors(tree: &::tree_sitter::Tree, source: &str) -> Vec<ParseError> {
+    converter::extract_errors(tree, source)
+        .into_iter()
+        .map(|e| ParseError {
+            message: e.message,
+            range: Range {
+                start: Position { line: e.line as u32, character: e.column as u32 },
+                end: Position { line: e.end_line as u32, character: e.end_column as u32 },
+            },
+        })
+        .collect()
+}
+
 /// VB6 Parser using tree-sitter for incremental parsing
 pub struct Vb6Parser {
     ts_parser: TreeSitterVb6Parser,
     /// Stored tree for incremental parsing
     last_tree: Option<::tree_sitter::Tree>,
+    /// Project-level conditional compilation constants (e.g., Win32 = True)
+    conditional_compilation: CompilationConstants,
+    /// Regions excluded by #If directives in the last parsed source, and its
+    /// `Reference = ...` header lines
+    inactive_regions: Vec<InactiveRegion>,
 }
 
 impl Vb6Parser {
@@ -35,46 +91,77 @@ impl Vb6Parser {
         Self {
             ts_parser: TreeSitterVb6Parser::new().expect("Failed to create tree-sitter parser"),
             last_tree: None,
+            conditional_compilation: CompilationConstants::new(),
+            inactive_regions: Vec::new(),
         }
     }
 
+    /// Set the conditional compilation constants used to evaluate #If directives
+    pub fn set_conditional_compilation(&mut self, constants: CompilationConstants) {
+        self.conditional_compilation = constants
+            .into_iter()
+            .map(|(name, value)| (name.to_lowercase(), value))
+            .collect();
+    }
+
     /// Parse VB6 source code into an AST using tree-sitter
     pub fn parse(&mut self, source: &str) -> std::result::Result<Vb6Ast, Vec<ParseError>> {
         // Use incremental parsing if we have a previous tree
-        let tree = self.ts_parser.parse(source, self.last_tree.as_ref());
+        let old_tree = self.last_tree.take();
+        self.parse_edited(source, old_tree.as_ref())
+    }
 
-        match tree {
-            Some(tree) => {
-                // Convert tree-sitter tree to our AST
-                let ast = converter::convert_tree(&tree, source);
-
-                // Extract any parse errors
-                let error_infos = converter::extract_errors(&tree, source);
-                let errors: Vec<ParseError> = error_infos.into_iter().map(|e| ParseError {
-                    message: e.message,
-                    range: Range {
-                        start: Position {
-                            line: e.line as u32,
-                            character: e.column as u32,
-                        },
-                        end: Position {
-                            line: e.end_line as u32,
-                            character: e.end_column as u32,
-                        },
-                    },
-                }).collect();
+    /// Parse VB6 source code, reusing the document's previous tree.
+    ///
+    /// `old_tree` must have had every edit since it was parsed applied with
+    /// `Tree::edit`, or be None to parse from scratch.
+    pub fn parse_edited(
+        &mut self,
+        source: &str,
+        old_tree: Option<&::tree_sitter::Tree>,
+    ) -> std::result::Result<Vb6Ast, Vec<ParseError>> {
+        self.last_tree = self.ts_parser.parse(source, old_tree);
+        self.convert(source)
+    }
 
-                // Store tree for incremental parsing
-                self.last_tree = Some(tree);
+    /// Parse a file of a known kind, separating its header attributes,
+    /// designer tree, and code section
+    pub fn parse_as(
+        &mut self,
+        source: &str,
+        kind: Vb6FileKind,
+    ) -> std::result::Result<Vb6File, Vec<ParseError>> {
+        let mut code = self.parse_edited(source, None)?;
+        code.file_type = kind;
+
+        let tree = self.last_tree.as_ref().expect("a successful parse stores its tree");
+        Ok(converter::convert_file(tree, source, kind, code))
+    }
 
-                // Tree-sitter provides partial AST even with errors
-                if errors.is_empty() {
-                    Ok(ast)
-                } else {
-                    // Return AST anyway for error-tolerant parsing
-                    // The LSP can still use the partial AST while showing errors
-                    Ok(ast)
-                }
+    /// Parse a document held in a rope into a tree-sitter tree, reusing its edited tree.
+    ///
+    /// Tree-sitter reads the rope's chunks, so no String of the document is built.
+    /// Pass the tree and the document's text to [`convert`] to get the AST and
+    /// inactive regions.
+    pub fn parse_rope(
+        &mut self,
+        rope: &Rope,
+        old_tree: Option<&::tree_sitter::Tree>,
+    ) -> Option<&::tree_sitter::Tree> {
+        self.last_tree = self.ts_parser.parse_rope(rope, old_tree);
+        self.last_tree.as_ref()
+    }
+
+    /// Convert the last parsed tree of `source` into an AST
+    pub fn convert(&mut self, source: &str) -> std::result::Result<Vb6Ast, Vec<ParseError>> {
+        match self.last_tree.as_ref() {
+            Some(tree) => {
+                let conversion = convert(tree, source, &self.conditional_compilation);
+                self.inactive_regions = conversion.inactive_regions;
+
+                // Tree-sitter provides partial AST even with errors, which the
+                // LSP can still use while showing them
+                Ok(conversion.ast)
             }
             None => Err(vec![ParseError {
                 message: "Failed to parse source".to_string(),
@@ -88,26 +175,27 @@ impl Vb6Parser {
 
     /// Get parse errors without failing the entire parse
     pub fn get_errors(&mut self, source: &str) -> Vec<ParseError> {
-        if let Some(tree) = self.ts_parser.parse(source, self.last_tree.as_ref()) {
-            let error_infos = converter::extract_errors(&tree, source);
-            error_infos.into_iter().map(|e| ParseError {
-                message: e.message,
-                range: Range {
-                    start: Position {
-                        line: e.line as u32,
-                        character: e.column as u32,
-                    },
-                    end: Position {
-                        line: e.end_line as u32,
-                        character: e.end_column as u32,
-                    },
-                },
-            }).collect()
-        } else {
-            vec![]
+        match self.ts_parser.parse(source, self.last_tree.as_ref()) {
+            Some(tree) => syntax_errors(&tree, source),
+            None => vec![],
         }
     }
 
+    /// Get the conditional compilation constants used to evaluate #If directives
+    pub fn conditional_compilation(&self) -> &CompilationConstants {
+        &self.conditional_compilation
+    }
+
+    /// List the declarations of a file from a single query over its syntax
+    /// tree, without building the AST or a symbol table. The cached tree used
+    /// for incremental parsing is left untouched.
+    pub fn outline(&mut self, source: &str) -> Vec<OutlineItem> {
+        self.ts_parser
+            .parse(source, None)
+            .map(|tree| outline::outline(&tree, source))
+            .unwrap_or_default()
+    }
+
     /// Clear the cached tree (useful when document is closed)
     pub fn clear_cache(&mut self) {
         self.last_tree = None;
@@ -118,15 +206,23 @@ impl Vb6Parser {
         self.last_tree.as_ref()
     }
 
+    /// Get the regions excluded by #If directives in the last parsed source, and
+    /// its `Reference = ...` header lines, which are not code
+    pub fn get_inactive_regions(&self) -> &[InactiveRegion] {
+        &self.inactive_regions
+    }
+
     /// Parse VB6 source code using the legacy line-based parser
     /// Kept for compatibility but tree-sitter is preferred
     pub fn parse_legacy(&self, source: &str) -> std::result::Result<Vb6Ast, Vec<ParseError>> {
         let mut ast = Vb6Ast::new();
         let mut errors = Vec::new();
+        // Type/Enum whose member lines are currently being read
+        let mut open_block: Option<LegacyBlock> = None;
+        // Depth of the header's BEGIN ... END blocks, or None once the code starts
+        let mut header_depth = Some(0);
 
-        let lines: Vec<&str> = source.lines().collect();
-
-        for (line_num, line) in lines.iter().enumerate() {
+        for (line_num, line) in Self::join_continued_lines(source) {
             let trimmed = line.trim();
 
             // Skip empty lines and comments
@@ -134,10 +230,40 @@ impl Vb6Parser {
                 continue;
             }
 
+            let upper = trimmed.to_uppercase();
+
+            // The header (VERSION, designer blocks, Object and Reference lines)
+            // is not code; its attributes are kept
+            if let Some(depth) = header_depth.as_mut() {
+                if Self::is_header_line(&upper, depth) {
+                    if upper.starts_with("ATTRIBUTE ") {
+                        ast.add_attribute(line_num, trimmed);
+                    }
+                    continue;
+                }
+                header_depth = None;
+            }
+
+            if let Some(block) = open_block {
+                if upper.starts_with("END TYPE") || upper.starts_with("END ENUM") {
+                    open_block = None;
+                } else if upper.starts_with('\'') || upper.starts_with("REM ") {
+                    ast.add_comment(line_num, trimmed);
+                } else {
+                    match block {
+                        LegacyBlock::Type => self.parse_type_member(trimmed, &mut ast),
+                        LegacyBlock::Enum => self.parse_enum_member(trimmed, &mut ast),
+                    }
+                }
+                continue;
+            }
+
             // Parse the line
             if let Err(e) = self.parse_line(trimmed, line_num, &mut ast) {
                 errors.push(e);
             }
+
+            open_block = Self::opens_block(&upper);
         }
 
         if errors.is_empty() {
@@ -147,6 +273,33 @@ impl Vb6Parser {
         }
     }
 
+    /// Check if a line belongs to the header of a file, tracking the depth of
+    /// its `BEGIN ... END` blocks
+    fn is_header_line(upper: &str, depth: &mut usize) -> bool {
+        let keyword = upper.split_whitespace().next().unwrap_or_default();
+        // Exactly, so that code such as `BeginTrans` is not taken for a block
+        if keyword == "BEGIN" || keyword == "BEGINPROPERTY" {
+            *depth += 1;
+            return true;
+        }
+        if *depth > 0 {
+            if keyword == "END" || keyword == "ENDPROPERTY" {
+                *depth -= 1;
+            }
+            return true;
+        }
+
+        let assigned = |name: &str| {
+            upper
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        };
+        keyword == "VERSION"
+            || keyword == "ATTRIBUTE"
+            || assigned("OBJECT")
+            || assigned("REFERENCE")
+    }
+
     /// Parse a single line of VB6 code
     fn parse_line(
         &self,
@@ -162,6 +315,17 @@ impl Vb6Parser {
             return Ok(());
         }
 
+        // End of a procedure closes the most recent open one
+        if upper.starts_with("END SUB")
+            || upper.starts_with("END FUNCTION")
+            || upper.starts_with("END PROPERTY")
+        {
+            if let Some(proc) = ast.procedures.iter_mut().rev().find(|p| p.end_line.is_none()) {
+                proc.end_line = Some(line_num);
+            }
+            return Ok(());
+        }
+
         // Option statements
         if upper.starts_with("OPTION ") {
             ast.add_option(line_num, line);
@@ -174,6 +338,18 @@ impl Vb6Parser {
             return Ok(());
         }
 
+        // Sub/Function/Property with a visibility modifier (before variable declarations)
+        if Self::is_procedure_header(&upper) {
+            return self.parse_procedure(line, line_num, ast);
+        }
+
+        // Type and Enum declarations (before variable declarations)
+        match Self::opens_block(&upper) {
+            Some(LegacyBlock::Type) => return self.parse_type(line, line_num, ast),
+            Some(LegacyBlock::Enum) => return self.parse_enum(line, line_num, ast),
+            None => {}
+        }
+
         // Variable declarations
         if upper.starts_with("DIM ")
             || upper.starts_with("PRIVATE ")
@@ -189,16 +365,6 @@ impl Vb6Parser {
             return self.parse_const(line, line_num, ast);
         }
 
-        // Type declarations
-        if upper.starts_with("TYPE ") || upper.starts_with("PRIVATE TYPE ") || upper.starts_with("PUBLIC TYPE ") {
-            return self.parse_type(line, line_num, ast);
-        }
-
-        // Enum declarations
-        if upper.starts_with("ENUM ") || upper.starts_with("PRIVATE ENUM ") || upper.starts_with("PUBLIC ENUM ") {
-            return self.parse_enum(line, line_num, ast);
-        }
-
         // Sub/Function/Property declarations
         if upper.contains("SUB ") || upper.contains("FUNCTION ") || upper.contains("PROPERTY ") {
             return self.parse_procedure(line, line_num, ast);
@@ -238,15 +404,22 @@ impl Vb6Parser {
             };
 
             if let Some(name) = name_part {
-                let var_name = name.trim_end_matches(',');
+                let var_name = name.trim_end_matches(',').split('(').next().unwrap_or_default();
                 let var_type = self.extract_type(line);
 
+                // Bounds are between the parentheses after the name
+                let bounds = line
+                    .split_once('(')
+                    .and_then(|(_, rest)| rest.rsplit_once(')'))
+                    .map(|(bounds, _)| bounds);
+
                 ast.add_variable(Variable {
                     name: var_name.to_string(),
                     var_type,
                     visibility,
                     line: line_num,
-                    is_array: line.contains("("),
+                    is_array: bounds.is_some(),
+                    dimensions: bounds.map(ArrayDimension::parse_list).unwrap_or_default(),
                 });
             }
         }
@@ -346,6 +519,51 @@ impl Vb6Parser {
         Ok(())
     }
 
+    /// Parse a member line inside a Type block (e.g., "Name As String * 32", "Values(1 To 10) As Long")
+    fn parse_type_member(&self, line: &str, ast: &mut Vb6Ast) {
+        let line = Self::strip_comment(line);
+        let upper = line.to_uppercase();
+
+        let (declarator, member_type) = match upper.find(" AS ") {
+            Some(pos) => (&line[..pos], Self::normalize_fixed_length(line[pos + 4..].trim())),
+            None => (line, "Variant".to_string()),
+        };
+
+        let is_array = declarator.contains('(');
+        let name = declarator.split('(').next().unwrap_or("").trim();
+        if name.is_empty() {
+            return;
+        }
+
+        if let Some(user_type) = ast.user_types.last_mut() {
+            user_type.members.push(TypeMember {
+                name: name.to_string(),
+                member_type,
+                is_array,
+            });
+        }
+    }
+
+    /// Parse a member line inside an Enum block (e.g., "Red = 1")
+    fn parse_enum_member(&self, line: &str, ast: &mut Vb6Ast) {
+        let line = Self::strip_comment(line);
+
+        let (name, value) = match line.split_once('=') {
+            Some((name, value)) => (name.trim(), Self::parse_integer(value.trim())),
+            None => (line.trim(), None),
+        };
+        if name.is_empty() {
+            return;
+        }
+
+        if let Some(enumeration) = ast.enums.last_mut() {
+            enumeration.members.push(EnumMember {
+                name: name.to_string(),
+                value,
+            });
+        }
+    }
+
     /// Parse a Sub/Function/Property declaration
     fn parse_procedure(
         &self,
@@ -381,6 +599,8 @@ impl Vb6Parser {
             ProcedureType::Sub
         };
 
+        let is_declare = upper.starts_with("DECLARE ") || upper.contains(" DECLARE ");
+
         // Extract name and parameters
         if let Some(paren_start) = line.find('(') {
             let before_paren = &line[..paren_start];
@@ -397,7 +617,8 @@ impl Vb6Parser {
                     line: line_num,
                     parameters: params,
                     return_type,
-                    end_line: None, // Will be set when End Sub/Function is found
+                    // Set when End Sub/Function is found (Declare has no body)
+                    end_line: is_declare.then_some(line_num),
                 });
             }
         }
@@ -438,18 +659,28 @@ impl Vb6Parser {
                         continue;
                     }
 
-                    let upper = param.to_uppercase();
-                    let by_ref = !upper.starts_with("BYVAL");
-                    let optional = upper.contains("OPTIONAL");
+                    // `= default` of an optional parameter
+                    let (param, default_value) = match param.split_once('=') {
+                        Some((declaration, default)) => {
+                            (declaration.trim(), Some(default.trim().to_string()))
+                        }
+                        None => (param, None),
+                    };
 
                     let parts: Vec<&str> = param.split_whitespace().collect();
+                    let is_keyword = |word: &str, keyword: &str| word.eq_ignore_ascii_case(keyword);
+                    let by_ref = !parts.iter().any(|p| is_keyword(p, "ByVal"));
+                    let optional = parts.iter().any(|p| is_keyword(p, "Optional"));
+                    let is_param_array = parts.iter().any(|p| is_keyword(p, "ParamArray"));
+
                     let name = parts
                         .iter()
                         .find(|p| {
-                            let u = p.to_uppercase();
-                            u != "BYVAL" && u != "BYREF" && u != "OPTIONAL" && u != "AS"
+                            !["ByVal", "ByRef", "Optional", "ParamArray", "As"]
+                                .iter()
+                                .any(|keyword| is_keyword(p, keyword))
                         })
-                        .map(|s| s.to_string())
+                        .map(|s| s.trim_end_matches("()").to_string())
                         .unwrap_or_default();
 
                     let param_type = self.extract_type(param);
@@ -460,6 +691,8 @@ impl Vb6Parser {
                             param_type,
                             by_ref,
                             optional,
+                            is_param_array,
+                            default_value,
                         });
                     }
                 }
@@ -479,74 +712,204 @@ impl Vb6Parser {
         None
     }
 
-    /// Format VB6 source code
-    pub fn format(&self, source: &str) -> Option<Vec<TextEdit>> {
-        let mut edits = Vec::new();
-        let lines: Vec<&str> = source.lines().collect();
-        let mut indent_level: usize = 0;
+    /// Check if an uppercased line starts a Sub/Function/Property after optional modifiers
+    fn is_procedure_header(upper: &str) -> bool {
+        upper
+            .split_whitespace()
+            .find(|word| !matches!(*word, "PRIVATE" | "PUBLIC" | "FRIEND" | "STATIC"))
+            .is_some_and(|word| matches!(word, "SUB" | "FUNCTION" | "PROPERTY"))
+    }
 
-        for (line_num, line) in lines.iter().enumerate() {
-            let trimmed = line.trim();
-            let upper = trimmed.to_uppercase();
+    /// Check if an uppercased line opens a Type or Enum block
+    fn opens_block(upper: &str) -> Option<LegacyBlock> {
+        match upper
+            .split_whitespace()
+            .find(|word| !matches!(*word, "PRIVATE" | "PUBLIC"))?
+        {
+            "TYPE" => Some(LegacyBlock::Type),
+            "ENUM" => Some(LegacyBlock::Enum),
+            _ => None,
+        }
+    }
 
-            // Decrease indent before these keywords
-            if upper.starts_with("END ")
-                || upper == "END"
-                || upper.starts_with("ELSE")
-                || upper.starts_with("ELSEIF")
-                || upper.starts_with("CASE ")
-                || upper.starts_with("LOOP")
-                || upper.starts_with("NEXT")
-                || upper.starts_with("WEND")
-            {
-                indent_level = indent_level.saturating_sub(1);
+    /// Remove a trailing ' comment, ignoring apostrophes inside string literals
+    fn strip_comment(line: &str) -> &str {
+        let mut in_string = false;
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => in_string = !in_string,
+                '\'' if !in_string => return line[..i].trim_end(),
+                _ => {}
             }
+        }
+        line
+    }
 
-            // Calculate expected indentation
-            let expected_indent = "    ".repeat(indent_level);
-            let expected_line = format!("{}{}", expected_indent, trimmed);
-
-            // Create edit if line differs
-            if *line != expected_line && !trimmed.is_empty() {
-                edits.push(TextEdit {
-                    range: Range {
-                        start: Position {
-                            line: line_num as u32,
-                            character: 0,
-                        },
-                        end: Position {
-                            line: line_num as u32,
-                            character: line.len() as u32,
-                        },
-                    },
-                    new_text: expected_line,
-                });
+    /// Normalize a fixed-length string type (e.g., "String*32" -> "String * 32")
+    fn normalize_fixed_length(type_text: &str) -> String {
+        match type_text.split_once('*') {
+            Some((base, length)) => format!("{} * {}", base.trim(), length.trim()),
+            None => type_text.to_string(),
+        }
+    }
+
+    /// Parse a decimal or &H/&O integer literal
+    fn parse_integer(text: &str) -> Option<i32> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, text),
+        };
+        let digits = digits.trim_end_matches('&');
+        let upper = digits.to_uppercase();
+
+        let value = if let Some(hex) = upper.strip_prefix("&H") {
+            i64::from_str_radix(hex, 16).ok()?
+        } else if let Some(octal) = upper.strip_prefix("&O") {
+            i64::from_str_radix(octal, 8).ok()?
+        } else {
+            digits.parse::<i64>().ok()?
+        };
+
+        i32::try_from(if negative { -value } else { value }).ok()
+    }
+
+    /// Join physical lines ending with the " _" continuation marker into logical lines.
+    /// Each logical line is paired with the physical line number it starts on.
+    fn join_continued_lines(source: &str) -> Vec<(usize, String)> {
+        let mut logical = Vec::new();
+        let mut pending: Option<(usize, String)> = None;
+
+        for (line_num, line) in source.lines().enumerate() {
+            let (start, mut text) = pending.take().unwrap_or((line_num, String::new()));
+
+            match Self::strip_continuation(line) {
+                Some(stripped) => {
+                    text.push_str(stripped);
+                    text.push(' ');
+                    pending = Some((start, text));
+                }
+                None => {
+                    text.push_str(line);
+                    logical.push((start, text));
+                }
             }
+        }
+
+        // A continuation on the last line has nothing to join with
+        if let Some(rest) = pending {
+            logical.push(rest);
+        }
+
+        logical
+    }
+
+    /// Strip a trailing " _" continuation marker, ignoring underscores inside string literals
+    fn strip_continuation(line: &str) -> Option<&str> {
+        let trimmed = line.trim_end();
+        let before = trimmed.strip_suffix('_')?;
+
+        // The marker must be separated from the preceding token by whitespace
+        if !before.is_empty() && !before.ends_with(char::is_whitespace) {
+            return None;
+        }
+
+        // An odd number of quotes means the underscore is inside an unterminated string
+        if before.matches('"').count() % 2 == 1 {
+            return None;
+        }
+
+        Some(before.trim_end())
+    }
+
+    /// Format VB6 source code, returning a whole-document edit if anything changed
+    pub fn format(&self, source: &str, options: &FormatOptions) -> Option<Vec<TextEdit>> {
+        let formatted = formatter::format_source(source, options);
+        if formatted == source {
+            return None;
+        }
+
+        // Replace the whole document
+        Some(vec![TextEdit {
+            range: Range {
+                start: Position { line: 0, character: 0 },
+                end: Self::document_end(source),
+            },
+            new_text: formatted,
+        }])
+    }
+
+    /// Format the lines of `range`, returning a single edit over the reformatted lines
+    pub fn format_range(
+        &self,
+        source: &str,
+        range: Range,
+        options: &FormatOptions,
+    ) -> Option<Vec<TextEdit>> {
+        // A selection ending at column 0 doesn't include that line
+        let mut end_line = range.end.line as usize;
+        if range.end.character == 0 && range.end.line > range.start.line {
+            end_line -= 1;
+        }
+
+        let (first, count, lines) =
+            formatter::format_range(source, options, range.start.line as usize, end_line)?;
+        let original: Vec<&str> = source.lines().skip(first).take(count).collect();
+        if original == lines {
+            return None;
+        }
+
+        let eol = if source.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut new_text = lines.join(eol);
+        let last = first + count;
+        let end = if last < source.lines().count() || source.ends_with('\n') {
+            new_text.push_str(eol);
+            Position { line: last as u32, character: 0 }
+        } else {
+            Self::document_end(source)
+        };
+
+        Some(vec![TextEdit {
+            range: Range {
+                start: Position { line: first as u32, character: 0 },
+                end,
+            },
+            new_text,
+        }])
+    }
+
+    /// Re-indent after Enter: the line just finished (if it ends a block) and the new line
+    pub fn format_on_type(
+        &self,
+        source: &str,
+        position: Position,
+        options: &FormatOptions,
+    ) -> Option<Vec<TextEdit>> {
+        let lines: Vec<&str> = source.lines().collect();
+        let line = position.line as usize;
+        let mut edits = Vec::new();
+
+        let reindent = |line_num: usize, text: &str| -> Option<TextEdit> {
+            let expected = formatter::line_indent(source, options, line_num)?;
+            let current = &text[..text.len() - text.trim_start().len()];
+            (current != expected).then(|| TextEdit {
+                range: Range {
+                    start: Position { line: line_num as u32, character: 0 },
+                    end: Position {
+                        line: line_num as u32,
+                        character: current.encode_utf16().count() as u32,
+                    },
+                },
+                new_text: expected,
+            })
+        };
 
-            // Increase indent after these keywords
-            if upper.starts_with("IF ") && upper.contains(" THEN") && !upper.contains(" THEN ")
-                || upper.starts_with("FOR ")
-                || upper.starts_with("DO ")
-                || upper.starts_with("DO")
-                || upper.starts_with("WHILE ")
-                || upper.starts_with("SELECT CASE")
-                || upper.starts_with("WITH ")
-                || upper.starts_with("SUB ")
-                || upper.starts_with("FUNCTION ")
-                || upper.starts_with("PROPERTY ")
-                || upper.starts_with("TYPE ")
-                || upper.starts_with("ENUM ")
-                || upper.starts_with("PRIVATE SUB ")
-                || upper.starts_with("PRIVATE FUNCTION ")
-                || upper.starts_with("PUBLIC SUB ")
-                || upper.starts_with("PUBLIC FUNCTION ")
-                || upper.starts_with("ELSE")
-                || upper.starts_with("ELSEIF")
-                || upper.starts_with("CASE ")
-            {
-                indent_level += 1;
+        // Dedent a just-typed End Sub, Next, Else, ...
+        if let Some(previous) = line.checked_sub(1).and_then(|l| lines.get(l)) {
+            if formatter::is_block_end(previous) {
+                edits.extend(reindent(line - 1, previous));
             }
         }
+        edits.extend(reindent(line, lines.get(line).copied().unwrap_or("")));
 
         if edits.is_empty() {
             None
@@ -554,6 +917,22 @@ impl Vb6Parser {
             Some(edits)
         }
     }
+
+    /// Position just past the last character of a document
+    fn document_end(source: &str) -> Position {
+        let tail = &source[source.rfind('\n').map(|i| i + 1).unwrap_or(0)..];
+        Position {
+            line: source.matches('\n').count() as u32,
+            character: tail.encode_utf16().count() as u32,
+        }
+    }
+}
+
+/// Block whose body lines are parsed as members by the legacy parser
+#[derive(Debug, Clone, Copy)]
+enum LegacyBlock {
+    Type,
+    Enum,
 }
 
 impl Default for Vb6Parser {
@@ -593,6 +972,192 @@ End Function
         assert_eq!(ast.procedures.len(), 2);
     }
 
+    #[test]
+    fn test_legacy_line_continuation() {
+        let parser = Vb6Parser::new();
+        let source = r#"Option Explicit
+
+Private Function Foo(a As Long, _
+                     b As Long) As Long
+    Foo = a + b
+End Function
+
+Sub Bar()
+    MsgBox "a _
+End Sub
+"#;
+        let ast = parser.parse_legacy(source).unwrap();
+
+        let foo = &ast.procedures[0];
+        assert_eq!(foo.name, "Foo");
+        assert_eq!(foo.line, 2);
+        assert_eq!(foo.parameters.len(), 2);
+        assert_eq!(foo.parameters[1].name, "b");
+        assert_eq!(foo.return_type.as_deref(), Some("Long"));
+
+        // An underscore inside a string is not a continuation
+        let bar = &ast.procedures[1];
+        assert_eq!(bar.name, "Bar");
+        assert_eq!(bar.line, 7);
+    }
+
+    #[test]
+    fn test_legacy_optional_and_param_array_parameters() {
+        let parser = Vb6Parser::new();
+        let source = "Sub Log(Optional ByVal level As Long = 2, ParamArray args() As Variant)\n\
+End Sub\n";
+        let ast = parser.parse_legacy(source).unwrap();
+
+        let parameters = &ast.procedures[0].parameters;
+        assert_eq!(parameters[0].name, "level");
+        assert!(parameters[0].optional && !parameters[0].by_ref);
+        assert_eq!(parameters[0].default_value.as_deref(), Some("2"));
+        assert_eq!(parameters[1].name, "args");
+        assert!(parameters[1].is_param_array);
+        assert_eq!(parameters[1].format_signature(), "ParamArray args() As Variant");
+    }
+
+    #[test]
+    fn test_legacy_type_and_enum_members() {
+        let parser = Vb6Parser::new();
+        let source = r#"Private Type Customer
+    Name As String * 32 ' fixed length
+    Scores(1 To 10) As Long
+    Active As Boolean
+End Type
+
+Public Enum Color
+    Red = 1
+    Green
+    Blue = &H10
+End Enum
+
+Dim c As Customer
+"#;
+        let ast = parser.parse_legacy(source).unwrap();
+
+        let members = &ast.user_types[0].members;
+        assert_eq!(members.len(), 3);
+        assert_eq!(members[0].name, "Name");
+        assert_eq!(members[0].member_type, "String * 32");
+        assert_eq!(members[1].name, "Scores");
+        assert_eq!(members[1].member_type, "Long");
+        assert!(members[1].is_array);
+        assert!(!members[2].is_array);
+
+        let members = &ast.enums[0].members;
+        assert_eq!(members.len(), 3);
+        assert_eq!(members[0].value, Some(1));
+        assert_eq!(members[1].name, "Green");
+        assert_eq!(members[1].value, None);
+        assert_eq!(members[2].value, Some(16));
+
+        // Lines after End Type/End Enum are parsed normally
+        assert_eq!(ast.variables.len(), 1);
+        assert_eq!(ast.variables[0].var_type.as_deref(), Some("Customer"));
+    }
+
+    #[test]
+    fn test_array_dimensions() {
+        let source = "Dim a(1 To 10, 5) As Long\nDim b() As String\n";
+        let dimensions = vec![
+            ArrayDimension { lower: Some("1".to_string()), upper: "10".to_string() },
+            ArrayDimension { lower: None, upper: "5".to_string() },
+        ];
+
+        let mut parser = Vb6Parser::new();
+        for ast in [parser.parse(source).unwrap(), parser.parse_legacy(source).unwrap()] {
+            assert_eq!(ast.variables[0].name, "a");
+            assert!(ast.variables[0].is_array);
+            assert_eq!(ast.variables[0].dimensions, dimensions);
+            assert!(ast.variables[1].is_array);
+            assert!(ast.variables[1].dimensions.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_legacy_procedure_end_line() {
+        let parser = Vb6Parser::new();
+        let source = r#"Declare Function GetTickCount Lib "kernel32" () As Long
+
+Public Sub Main()
+    x = 1
+End Sub
+
+Private Function Add(a As Long, b As Long) As Long
+    Add = a + b
+End Function
+
+Sub Unterminated()
+    y = 2
+"#;
+        let ast = parser.parse_legacy(source).unwrap();
+        let end_lines: Vec<Option<usize>> = ast.procedures.iter().map(|p| p.end_line).collect();
+
+        assert_eq!(end_lines, vec![Some(0), Some(4), Some(8), None]);
+    }
+
+    #[test]
+    fn test_legacy_header_blocks() {
+        let parser = Vb6Parser::new();
+        let source = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   BeginProperty Font\n\
+      Name = \"Tahoma\"\n\
+      BeginProperty Inner\n\
+      EndProperty\n\
+   EndProperty\n\
+   Begin VB.CommandButton cmdOK\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private Sub Form_Load()\n\
+End Sub\n";
+        let ast = parser.parse_legacy(source).unwrap();
+        assert_eq!(ast.attributes.len(), 1);
+        let names: Vec<&str> = ast.procedures.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Form_Load"]);
+
+        // A call whose name starts with Begin is code, not a header block
+        let source = "BeginUpdate\nSub Refresh()\nEnd Sub\n";
+        let ast = parser.parse_legacy(source).unwrap();
+        let names: Vec<&str> = ast.procedures.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Refresh"]);
+
+        let mut depth = 0;
+        assert!(!Vb6Parser::is_header_line("BEGINTRANS", &mut depth));
+        assert_eq!(depth, 0);
+    }
+
+    #[test]
+    fn test_convert_parsed_tree() {
+        let source = "#If Win32 Then\nPrivate a As Long\n#Else\nPrivate b As Integer\n#End If\n\
+Sub Broken(\n";
+        let mut ts_parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+        let constants = CompilationConstants::from([("win32".to_string(), -1)]);
+
+        let conversion = convert(&tree, source, &constants);
+        let names: Vec<&str> = conversion.ast.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["a"]);
+        assert_eq!(conversion.inactive_regions.len(), 1);
+        assert!(!conversion.errors.is_empty());
+    }
+
+    #[test]
+    fn test_conditional_compilation_skips_inactive_code() {
+        let mut parser = Vb6Parser::new();
+        parser.set_conditional_compilation(CompilationConstants::from([("Win32".to_string(), -1)]));
+        let source = "#If Win32 Then\nPrivate a As Long\n#Else\nPrivate b As Integer\n#End If\n";
+
+        let ast = parser.parse(source).unwrap();
+        let names: Vec<&str> = ast.variables.iter().map(|v| v.name.as_str()).collect();
+
+        assert_eq!(names, vec!["a"]);
+        assert_eq!(parser.get_inactive_regions().len(), 1);
+        assert_eq!(parser.get_inactive_regions()[0].start_line, 3);
+    }
+
     #[test]
     fn test_incremental_parse() {
         let mut parser = Vb6Parser::new();
@@ -609,4 +1174,227 @@ End Function
         let ast = result.unwrap();
         assert_eq!(ast.variables.len(), 2);
     }
+
+    #[test]
+    fn test_format_range_edit() {
+        let parser = Vb6Parser::new();
+        let source = "Sub Main()\nx=1\ny=2\nEnd Sub";
+        let range = Range {
+            start: Position { line: 2, character: 0 },
+            end: Position { line: 3, character: 0 },
+        };
+
+        let edits = parser.format_range(source, range, &FormatOptions::default()).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start, Position { line: 2, character: 0 });
+        assert_eq!(edits[0].range.end, Position { line: 3, character: 0 });
+        assert_eq!(edits[0].new_text, "    y = 2\n");
+
+        // The last line of a document without a trailing newline
+        let range = Range {
+            start: Position { line: 3, character: 0 },
+            end: Position { line: 3, character: 7 },
+        };
+        assert!(parser.format_range(source, range, &FormatOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_format_on_type() {
+        let parser = Vb6Parser::new();
+        let options = FormatOptions::default();
+
+        // Enter after a block opener indents the new line
+        let source = "Sub Main()\n    For i = 1 To 3\n    ";
+        let edits = parser.format_on_type(source, Position { line: 2, character: 4 }, &options).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.end, Position { line: 2, character: 4 });
+        assert_eq!(edits[0].new_text, "        ");
+
+        // Enter after Next dedents it
+        let source = "Sub Main()\n    For i = 1 To 3\n        x = i\n        Next\n        ";
+        let edits = parser.format_on_type(source, Position { line: 4, character: 8 }, &options).unwrap();
+        assert_eq!(edits.len(), 2);
+        assert_eq!(edits[0].range.start.line, 3);
+        assert_eq!(edits[0].new_text, "    ");
+        assert_eq!(edits[1].new_text, "    ");
+
+        // Already indented correctly
+        let source = "Sub Main()\n    x = 1\n    ";
+        assert!(parser.format_on_type(source, Position { line: 2, character: 4 }, &options).is_none());
+    }
+
+    #[test]
+    fn test_parse_as_class() {
+        let mut parser = Vb6Parser::new();
+        let source = "VERSION 1.0 CLASS\n\
+BEGIN\n\
+  MultiUse = -1  'True\n\
+  Persistable = 0  'NotPersistable\n\
+END\n\
+Reference = *\\G{00020430-0000-0000-C000-000000000046}#2.0#0#..\\stdole2.tlb#OLE Automation\n\
+Attribute VB_Name = \"clsFoo\"\n\
+Attribute VB_Exposed = False\n\
+Option Explicit\n\
+Public Sub Go()\n\
+End Sub\n";
+        let file = parser.parse_as(source, Vb6FileKind::Class).unwrap();
+
+        assert_eq!(file.kind, Vb6FileKind::Class);
+        assert_eq!(file.code.file_type, Vb6FileKind::Class);
+        assert_eq!(file.version.as_deref(), Some("1.0"));
+        assert_eq!(
+            file.class_settings,
+            vec![
+                ("MultiUse".to_string(), "-1".to_string()),
+                ("Persistable".to_string(), "0".to_string()),
+            ]
+        );
+        assert_eq!(file.attribute("vb_name"), Some("clsFoo"));
+        assert_eq!(file.attribute("VB_Exposed"), Some("False"));
+        assert!(file.designer.is_none());
+        assert_eq!(file.code_start_line, 8);
+        assert_eq!(file.code.procedures.len(), 1);
+
+        // The Reference line is read from the text and kept out of the code
+        assert_eq!(
+            file.references,
+            vec![HeaderReference {
+                guid: Some("{00020430-0000-0000-C000-000000000046}".to_string()),
+                version: Some("2.0".to_string()),
+                file: Some("..\\stdole2.tlb".to_string()),
+                value: source.lines().nth(5).unwrap()["Reference = ".len()..].to_string(),
+                line: 5,
+            }]
+        );
+        assert!(file.code.statements.is_empty());
+        assert_eq!(parser.get_inactive_regions()[0].start_line, 5);
+        assert_eq!(file.attribute("VB_Name"), Some("clsFoo"));
+
+        // The legacy parser skips the header too
+        let ast = parser.parse_legacy(source).unwrap();
+        assert_eq!(ast.attributes.len(), 2);
+        assert!(ast.statements.is_empty());
+        assert_eq!(ast.procedures.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_as_form() {
+        let mut parser = Vb6Parser::new();
+        let source = "VERSION 5.00\n\
+Object = \"{831FDD16-0C5C-11D2-A9FC-0000F8754DA1}#2.0#0\"; \"MSCOMCTL.OCX\"\n\
+Begin VB.Form frmMain\n\
+   Caption = \"Main\"\n\
+   BeginProperty Font\n\
+      Name = \"Tahoma\"\n\
+   EndProperty\n\
+   Begin VB.CommandButton cmdOK\n\
+      Caption = \"OK\"\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n\
+Private Sub cmdOK_Click()\n\
+End Sub\n";
+        let file = parser.parse_as(source, Vb6FileKind::Form).unwrap();
+
+        assert_eq!(file.version.as_deref(), Some("5.00"));
+        assert_eq!(file.object_references.len(), 1);
+        let object = &file.object_references[0];
+        assert_eq!(object.guid.as_deref(), Some("{831FDD16-0C5C-11D2-A9FC-0000F8754DA1}"));
+        assert_eq!(object.version.as_deref(), Some("2.0"));
+        assert_eq!(object.file.as_deref(), Some("MSCOMCTL.OCX"));
+        assert_eq!(object.line, 1);
+
+        let form = file.designer.as_ref().unwrap();
+        assert_eq!(form.control_type, "VB.Form");
+        assert_eq!(form.name, "frmMain");
+        assert_eq!(
+            form.properties,
+            vec![
+                ("Caption".to_string(), "\"Main\"".to_string()),
+                ("Font.Name".to_string(), "\"Tahoma\"".to_string()),
+            ]
+        );
+        assert_eq!(form.children.len(), 1);
+        assert_eq!(form.children[0].name, "cmdOK");
+        assert_eq!(form.children[0].line, 7);
+
+        assert_eq!(file.attribute("VB_Name"), Some("frmMain"));
+        assert_eq!(file.code_start_line, 12);
+
+        // The same designer block is not a designer in a module
+        let file = parser.parse_as(source, Vb6FileKind::Module).unwrap();
+        assert!(file.designer.is_none());
+    }
+
+    #[test]
+    fn test_file_kind_from_path() {
+        assert_eq!(Vb6FileKind::from_path("/src/frmMain.FRM"), Some(Vb6FileKind::Form));
+        assert_eq!(Vb6FileKind::from_path("/src/clsFoo.cls"), Some(Vb6FileKind::Class));
+        assert_eq!(Vb6FileKind::from_path("/src/ucGrid.ctl"), Some(Vb6FileKind::UserControl));
+        assert_eq!(Vb6FileKind::from_path("/src/ppGeneral.pag"), Some(Vb6FileKind::PropertyPage));
+        assert_eq!(Vb6FileKind::from_path("/src/modMain.bas"), Some(Vb6FileKind::Module));
+        assert_eq!(Vb6FileKind::from_path("/src/Project1.vbp"), None);
+    }
+
+    #[test]
+    fn test_outline() {
+        use outline::OutlineKind;
+
+        let mut parser = Vb6Parser::new();
+        let source = "Option Explicit\n\
+Private Const MAX_ITEMS As Long = 10\n\
+Private mCount As Long, mName As String\n\
+Public Event Changed()\n\
+Private Declare Function GetTickCount Lib \"kernel32\" () As Long\n\
+Private Type Point\n\
+X As Long\n\
+Y As Long\n\
+End Type\n\
+Public Enum Color\n\
+Red = 1\n\
+End Enum\n\
+Public Property Get Count() As Long\n\
+Count = mCount\n\
+End Property\n\
+Public Property Let Count(ByVal value As Long)\n\
+mCount = value\n\
+End Property\n\
+Public Sub Main()\n\
+Dim i As Long\n\
+Const LOCAL_LIMIT = 5\n\
+End Sub\n\
+Private Function Total() As Long\n\
+End Function\n";
+
+        let items = parser.outline(source);
+        let summary: Vec<(&str, OutlineKind, u32)> = items
+            .iter()
+            .map(|item| (item.name.as_str(), item.kind, item.selection_range.start.line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("MAX_ITEMS", OutlineKind::Constant, 1),
+                ("mCount", OutlineKind::Variable, 2),
+                ("mName", OutlineKind::Variable, 2),
+                ("Changed", OutlineKind::Event, 3),
+                ("GetTickCount", OutlineKind::Declare, 4),
+                ("Point", OutlineKind::Type, 5),
+                ("X", OutlineKind::Field, 6),
+                ("Y", OutlineKind::Field, 7),
+                ("Color", OutlineKind::Enum, 9),
+                ("Red", OutlineKind::EnumMember, 10),
+                ("Count", OutlineKind::PropertyGet, 12),
+                ("Count", OutlineKind::PropertyLet, 15),
+                ("Main", OutlineKind::Sub, 18),
+                ("Total", OutlineKind::Function, 22),
+            ]
+        );
+
+        assert_eq!(items[6].container.as_deref(), Some("Point"));
+        assert_eq!(items[12].range.start.line, 18);
+        assert_eq!(items[12].range.end.line, 21);
+        // The outline does not replace the tree kept for incremental parsing
+        assert!(parser.get_tree().is_none());
+    }
 }
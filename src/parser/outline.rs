@@ -0,0 +1,181 @@
+//! Declaration Outline
+//!
+//! Lists the declarations of a file with a single pass of the grammar's tags
+//! query over the syntax tree. Nothing is converted to the AST and no scopes
+//! are built, which makes it much cheaper than the symbol table when only the
+//! names and positions of the declarations are wanted.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use streaming_iterator::StreamingIterator;
+use tower_lsp::lsp_types::{Position, Range};
+use tree_sitter::{Node, Query, QueryCursor, Tree};
+
+use super::VB6QueryRunner;
+
+/// Compiled tags query (None if it fails to compile against the grammar)
+static TAGS: Lazy<Option<Query>> = Lazy::new(|| {
+    VB6QueryRunner::new()
+        .create_query(tree_sitter_vb6::TAGS_QUERY)
+        .map_err(|e| tracing::error!("Failed to compile tags query: {}", e))
+        .ok()
+});
+
+const PROCEDURES: &[&str] = &["sub_declaration", "function_declaration", "property_declaration"];
+
+/// What an outline item declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OutlineKind {
+    Sub,
+    Function,
+    PropertyGet,
+    PropertyLet,
+    PropertySet,
+    Declare,
+    Event,
+    Type,
+    Enum,
+    Constant,
+    Variable,
+    /// A member of a Type
+    Field,
+    EnumMember,
+}
+
+/// A module-level declaration, or a member of a Type or Enum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineItem {
+    pub name: String,
+    pub kind: OutlineKind,
+    /// The whole declaration
+    pub range: Range,
+    /// The declared name
+    pub selection_range: Range,
+    /// The Type or Enum a member belongs to
+    pub container: Option<String>,
+}
+
+/// The declarations of a parsed file, in source order. Variables and
+/// constants local to a procedure are left out.
+pub fn outline(tree: &Tree, source: &str) -> Vec<OutlineItem> {
+    let Some(query) = TAGS.as_ref() else {
+        return Vec::new();
+    };
+    let capture_names = query.capture_names();
+
+    let mut items = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+    while let Some(m) = matches.next() {
+        let mut name = None;
+        let mut definition = None;
+        for capture in m.captures {
+            match capture_names[capture.index as usize] {
+                "name" => name = Some(capture.node),
+                capture_name if capture_name.starts_with("definition.") => {
+                    definition = Some(capture.node)
+                }
+                _ => {}
+            }
+        }
+        let (Some(name), Some(definition)) = (name, definition) else {
+            continue;
+        };
+        let Some(kind) = outline_kind(&definition, source) else {
+            continue;
+        };
+        let local = matches!(kind, OutlineKind::Variable | OutlineKind::Constant)
+            && in_procedure(&definition);
+        if local {
+            continue;
+        }
+
+        items.push(OutlineItem {
+            name: node_text(&name, source).to_string(),
+            kind,
+            range: node_range(&definition, source),
+            selection_range: node_range(&name, source),
+            container: container_name(&definition, source),
+        });
+    }
+
+    items.sort_by_key(|item| {
+        (item.selection_range.start.line, item.selection_range.start.character)
+    });
+    items
+}
+
+fn outline_kind(definition: &Node, source: &str) -> Option<OutlineKind> {
+    let kind = match definition.kind() {
+        "sub_declaration" => OutlineKind::Sub,
+        "function_declaration" => OutlineKind::Function,
+        "property_declaration" => {
+            let accessor = definition.child_by_field_name("accessor")?;
+            match node_text(&accessor, source).to_lowercase().as_str() {
+                "let" => OutlineKind::PropertyLet,
+                "set" => OutlineKind::PropertySet,
+                _ => OutlineKind::PropertyGet,
+            }
+        }
+        "declare_statement" => OutlineKind::Declare,
+        "event_statement" => OutlineKind::Event,
+        "type_declaration" => OutlineKind::Type,
+        "enum_declaration" => OutlineKind::Enum,
+        "constant_declaration" => OutlineKind::Constant,
+        "variable_declaration" => OutlineKind::Variable,
+        "type_member" => OutlineKind::Field,
+        "enum_member" => OutlineKind::EnumMember,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+fn in_procedure(node: &Node) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if PROCEDURES.contains(&parent.kind()) {
+            return true;
+        }
+        current = parent.parent();
+    }
+    false
+}
+
+/// The name of the Type or Enum declaring a member
+fn container_name(definition: &Node, source: &str) -> Option<String> {
+    if !matches!(definition.kind(), "type_member" | "enum_member") {
+        return None;
+    }
+    let mut current = definition.parent();
+    while let Some(parent) = current {
+        if matches!(parent.kind(), "type_declaration" | "enum_declaration") {
+            let name = parent.child_by_field_name("name")?;
+            return Some(node_text(&name, source).to_string());
+        }
+        current = parent.parent();
+    }
+    None
+}
+
+fn node_text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or_default()
+}
+
+fn node_range(node: &Node, source: &str) -> Range {
+    let start = node.start_position();
+    let mut end = node.end_position();
+    // A statement ends at column 0 of the next line, after its terminator
+    if end.row > start.row && end.column == 0 {
+        let text = source[node.start_byte()..node.end_byte()].trim_end_matches(['\r', '\n']);
+        end.row = start.row + text.matches('\n').count();
+        end.column = match text.rfind('\n') {
+            Some(i) => text.len() - i - 1,
+            None => start.column + text.len(),
+        };
+    }
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
@@ -0,0 +1,256 @@
+//! Conditional Compilation
+//!
+//! Evaluates `#Const` definitions and `#If`/`#ElseIf`/`#Else` directives to
+//! find the regions of a file that are compiled out.
+
+use std::collections::HashMap;
+
+use tree_sitter::{Node, Tree};
+
+/// Compilation constants, keyed by lowercase name (e.g., "win32" -> -1)
+pub type CompilationConstants = HashMap<String, i64>;
+
+/// A span of source excluded by conditional compilation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InactiveRegion {
+    pub start_byte: usize,
+    pub end_byte: usize,
+    /// First line of the region (0-indexed)
+    pub start_line: usize,
+    /// Last line of the region (0-indexed, inclusive)
+    pub end_line: usize,
+}
+
+impl InactiveRegion {
+    /// Check if a node lies entirely within this region
+    pub fn contains_node(&self, node: &Node) -> bool {
+        node.start_byte() >= self.start_byte && node.end_byte() <= self.end_byte
+    }
+
+    /// Check if a line lies within this region
+    pub fn contains_line(&self, line: usize) -> bool {
+        line >= self.start_line && line <= self.end_line
+    }
+}
+
+/// Find the inactive regions of a file.
+///
+/// `constants` are the project-level constants (e.g., from the VBP or the
+/// client configuration); `#Const` directives in active code extend them.
+pub fn find_inactive_regions(
+    tree: &Tree,
+    source: &str,
+    constants: &CompilationConstants,
+) -> Vec<InactiveRegion> {
+    let mut evaluator = Evaluator {
+        source,
+        constants: constants.clone(),
+        regions: Vec::new(),
+    };
+    evaluator.visit(&tree.root_node());
+    evaluator.regions
+}
+
+/// Walks the tree in document order, tracking `#Const` values
+struct Evaluator<'a> {
+    source: &'a str,
+    constants: CompilationConstants,
+    regions: Vec<InactiveRegion>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn visit(&mut self, node: &Node) {
+        match node.kind() {
+            "preproc_const" => self.define_constant(node),
+            "preproc_if" | "preproc_if_statement" => self.visit_conditional(node),
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.visit(&child);
+                }
+            }
+        }
+    }
+
+    /// Record a `#Const NAME = value` definition
+    fn define_constant(&mut self, node: &Node) {
+        if let Some(name) = node.child_by_field_name("name") {
+            let value = node
+                .child_by_field_name("value")
+                .map(|v| self.evaluate(&v))
+                .unwrap_or(0);
+            self.constants.insert(self.text(&name).to_lowercase(), value);
+        }
+    }
+
+    /// Pick the first branch whose condition holds; every other branch is inactive
+    fn visit_conditional(&mut self, node: &Node) {
+        let mut branches = vec![*node];
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if matches!(
+                child.kind(),
+                "preproc_elseif" | "preproc_else" | "preproc_elseif_statement" | "preproc_else_statement"
+            ) {
+                branches.push(child);
+            }
+        }
+
+        let mut taken = false;
+        for branch in branches {
+            let holds = match branch.child_by_field_name("condition") {
+                Some(condition) => self.evaluate(&condition) != 0,
+                // #Else
+                None => true,
+            };
+
+            let body = branch_body(&branch);
+            if holds && !taken {
+                taken = true;
+                for child in body {
+                    self.visit(&child);
+                }
+            } else if let (Some(first), Some(last)) = (body.first(), body.last()) {
+                self.regions.push(InactiveRegion {
+                    start_byte: first.start_byte(),
+                    end_byte: last.end_byte(),
+                    start_line: first.start_position().row,
+                    end_line: last_row(last),
+                });
+            }
+        }
+    }
+
+    /// Evaluate a preprocessor expression (True is -1, False is 0)
+    fn evaluate(&self, node: &Node) -> i64 {
+        match node.kind() {
+            "identifier" => self
+                .constants
+                .get(&self.text(node).to_lowercase())
+                .copied()
+                .unwrap_or(0),
+            "integer_literal" => parse_integer(self.text(node)).unwrap_or(0),
+            "boolean_literal" if self.text(node).eq_ignore_ascii_case("true") => -1,
+            "preproc_parenthesized" => node
+                .named_child(0)
+                .map(|inner| self.evaluate(&inner))
+                .unwrap_or(0),
+            "preproc_unary_expression" => node
+                .named_child(0)
+                .map(|operand| !self.evaluate(&operand))
+                .unwrap_or(0),
+            "preproc_binary_expression" => {
+                let (left, op, right) = match (node.child(0), node.child(1), node.child(2)) {
+                    (Some(l), Some(o), Some(r)) => (l, o, r),
+                    _ => return 0,
+                };
+                let (a, b) = (self.evaluate(&left), self.evaluate(&right));
+                let truth = |holds: bool| if holds { -1 } else { 0 };
+
+                match self.text(&op).to_lowercase().as_str() {
+                    "and" => a & b,
+                    "or" => a | b,
+                    "=" => truth(a == b),
+                    "<>" => truth(a != b),
+                    "<" => truth(a < b),
+                    ">" => truth(a > b),
+                    "<=" => truth(a <= b),
+                    ">=" => truth(a >= b),
+                    _ => 0,
+                }
+            }
+            _ => 0,
+        }
+    }
+
+    fn text(&self, node: &Node) -> &'a str {
+        node.utf8_text(self.source.as_bytes()).unwrap_or("")
+    }
+}
+
+/// The code nodes of a directive branch (excluding its condition and later branches)
+fn branch_body<'t>(branch: &Node<'t>) -> Vec<Node<'t>> {
+    let condition = branch.child_by_field_name("condition").map(|c| c.id());
+    let mut cursor = branch.walk();
+    branch
+        .named_children(&mut cursor)
+        .filter(|child| Some(child.id()) != condition)
+        .filter(|child| {
+            !matches!(
+                child.kind(),
+                "preproc_elseif" | "preproc_else" | "preproc_elseif_statement" | "preproc_else_statement"
+            )
+        })
+        .collect()
+}
+
+/// Last row containing text of a node (statements end with their newline terminator)
+fn last_row(node: &Node) -> usize {
+    let start = node.start_position();
+    let end = node.end_position();
+    if end.column == 0 && end.row > start.row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Parse a decimal or &H/&O integer literal
+fn parse_integer(text: &str) -> Option<i64> {
+    let upper = text.to_uppercase();
+    if let Some(hex) = upper.strip_prefix("&H") {
+        i64::from_str_radix(hex, 16).ok()
+    } else if let Some(octal) = upper.strip_prefix("&O") {
+        i64::from_str_radix(octal, 8).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TreeSitterVb6Parser;
+
+    fn inactive_lines(source: &str, constants: &[(&str, i64)]) -> Vec<(usize, usize)> {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let constants = constants
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), *value))
+            .collect();
+        find_inactive_regions(&tree, source, &constants)
+            .iter()
+            .map(|r| (r.start_line, r.end_line))
+            .collect()
+    }
+
+    #[test]
+    fn test_if_else_with_project_constant() {
+        let source = "#If Win32 Then\nPrivate a As Long\n#Else\nPrivate a As Integer\n#End If\n";
+
+        assert_eq!(inactive_lines(source, &[("Win32", -1)]), vec![(3, 3)]);
+        assert_eq!(inactive_lines(source, &[]), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_const_directive_and_elseif() {
+        let source = "#Const DEBUG_MODE = 2\n\
+#If DEBUG_MODE = 1 Then\n\
+Private a As Long\n\
+#ElseIf DEBUG_MODE > 1 And Not False Then\n\
+Private b As Long\n\
+#Else\n\
+Private c As Long\n\
+#End If\n";
+
+        assert_eq!(inactive_lines(source, &[]), vec![(2, 2), (6, 6)]);
+    }
+
+    #[test]
+    fn test_statement_level_directive() {
+        let source = "Sub Main()\n#If Win64 Then\n    x = 1\n#Else\n    x = 2\n#End If\nEnd Sub\n";
+
+        assert_eq!(inactive_lines(source, &[("Win64", 0)]), vec![(2, 2)]);
+    }
+}
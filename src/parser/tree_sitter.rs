@@ -2,6 +2,7 @@
 //!
 //! Provides incremental parsing capabilities using the tree-sitter-vb6 grammar.
 
+use ropey::Rope;
 use tree_sitter::{Parser, Tree, Language, Query, QueryCursor};
 use streaming_iterator::StreamingIterator;
 
@@ -27,6 +28,21 @@ impl TreeSitterVb6Parser {
         self.parser.parse(source, old_tree)
     }
 
+    /// Parse VB6 source held in a rope, reading its chunks directly rather than
+    /// collecting the document into a String
+    pub fn parse_rope(&mut self, rope: &Rope, old_tree: Option<&Tree>) -> Option<Tree> {
+        self.parser.parse_with(
+            &mut |byte, _| {
+                if byte >= rope.len_bytes() {
+                    return &[][..];
+                }
+                let (chunk, chunk_start, _, _) = rope.chunk_at_byte(byte);
+                &chunk.as_bytes()[byte - chunk_start..]
+            },
+            old_tree,
+        )
+    }
+
     /// Get the tree-sitter language for queries
     pub fn language(&self) -> Language {
         tree_sitter_vb6::LANGUAGE.into()
@@ -134,4 +150,21 @@ End Sub
         let root = tree.root_node();
         assert_eq!(root.kind(), "source_file");
     }
+
+    #[test]
+    fn test_parse_rope_matches_string_parse() {
+        let mut parser = TreeSitterVb6Parser::new().unwrap();
+        // Large enough to span many rope chunks, with multi-byte text at chunk boundaries
+        let source: String = (0..500)
+            .map(|i| format!("Private Sub P{}()\n    s = \"héllo wörld {}\"\nEnd Sub\n", i, i))
+            .collect();
+        let rope = Rope::from_str(&source);
+        assert!(rope.chunks().count() > 1);
+
+        let from_rope = parser.parse_rope(&rope, None).unwrap();
+        let from_string = parser.parse(&source, None).unwrap();
+        assert!(!from_rope.root_node().has_error());
+        assert_eq!(from_rope.root_node().end_byte(), source.len());
+        assert_eq!(from_rope.root_node().to_sexp(), from_string.root_node().to_sexp());
+    }
 }
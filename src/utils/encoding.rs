@@ -46,6 +46,16 @@ impl Encoding {
     }
 }
 
+/// Decode VB6 text: UTF-8 if the bytes are valid UTF-8 (with or without a
+/// BOM), otherwise Windows-1252, the ANSI codepage the VB6 IDE saves in
+pub fn decode_vb6_bytes(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => WINDOWS_1252.decode_without_bom_handling(bytes).0.into_owned(),
+    }
+}
+
 /// Represents the content of a VB6 file along with its detected encoding
 #[derive(Debug, Clone)]
 pub struct VB6FileContent {
@@ -287,6 +297,19 @@ mod tests {
         assert!(!content.text.is_empty());
     }
 
+    #[test]
+    fn test_decode_vb6_bytes() {
+        // "£5 © Café" as saved by the VB6 IDE
+        let ansi = b"\xA35 \xA9 Caf\xE9";
+        assert_eq!(decode_vb6_bytes(ansi), "£5 © Café");
+
+        let utf8 = "£5 © Café";
+        assert_eq!(decode_vb6_bytes(utf8.as_bytes()), utf8);
+        let mut with_bom = vec![0xEF, 0xBB, 0xBF];
+        with_bom.extend_from_slice(utf8.as_bytes());
+        assert_eq!(decode_vb6_bytes(&with_bom), utf8);
+    }
+
     #[test]
     fn test_encode_utf8() {
         let text = "Option Explicit";
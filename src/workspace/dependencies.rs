@@ -0,0 +1,209 @@
+//! Dependency Graph
+//!
+//! Which files of the workspace use which: an edge goes from a file to each
+//! module, class, or form whose name or Public members it refers to, weighted
+//! by the number of references. Classes that depend on each other in a cycle
+//! are reported, as they are the hardest part of a codebase to split up.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::{normalize_path, WorkspaceManager};
+use crate::parser::Vb6FileKind;
+
+/// Files of the workspace and the references between them
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    /// Groups of classes that depend on each other, by name
+    pub class_cycles: Vec<Vec<String>>,
+}
+
+/// A module, class, or form
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyNode {
+    /// Module name from the project, or the file name for files outside one
+    pub name: String,
+    pub path: PathBuf,
+    /// File kind (e.g., "Class"), if known from the extension
+    pub kind: Option<String>,
+}
+
+/// References from one file to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyEdge {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub count: usize,
+    /// Whether the edge is part of a cycle between classes
+    pub cyclic: bool,
+}
+
+/// Build the dependency graph of every file with a loaded symbol table
+pub fn dependency_graph(workspace: &WorkspaceManager) -> DependencyGraph {
+    let mut nodes = BTreeMap::new();
+    for table in workspace.symbol_tables() {
+        let Ok(path) = table.uri.to_file_path() else {
+            continue;
+        };
+        nodes.insert(normalize_path(&path), node(workspace, &path));
+    }
+
+    // Names the file doesn't declare, and the class names it uses as types
+    let mut counts: BTreeMap<(PathBuf, PathBuf), usize> = BTreeMap::new();
+    for table in workspace.symbol_tables() {
+        let Ok(path) = table.uri.to_file_path() else {
+            continue;
+        };
+        let from = normalize_path(&path);
+        let names = table.unresolved_references().iter().chain(table.type_references());
+        for reference in names {
+            let target = workspace
+                .resolve_symbol(&reference.name, &path)
+                .and_then(|location| location.uri.to_file_path().ok())
+                .map(|target| normalize_path(&target));
+            if let Some(to) = target.filter(|to| *to != from && nodes.contains_key(to)) {
+                *counts.entry((from.clone(), to)).or_default() += 1;
+            }
+        }
+    }
+
+    let class_cycles = class_cycles(&nodes, &counts);
+    let in_cycle = |from: &PathBuf, to: &PathBuf| {
+        class_cycles.iter().any(|cycle| cycle.contains(from) && cycle.contains(to))
+    };
+    let edges = counts
+        .iter()
+        .map(|((from, to), &count)| DependencyEdge {
+            from: nodes[from].path.clone(),
+            to: nodes[to].path.clone(),
+            count,
+            cyclic: in_cycle(from, to),
+        })
+        .collect();
+
+    DependencyGraph {
+        class_cycles: class_cycles
+            .iter()
+            .map(|cycle| cycle.iter().map(|path| nodes[path].name.clone()).collect())
+            .collect(),
+        nodes: nodes.into_values().collect(),
+        edges,
+    }
+}
+
+fn node(workspace: &WorkspaceManager, path: &Path) -> DependencyNode {
+    DependencyNode {
+        name: workspace.module_name(path).unwrap_or_default(),
+        path: path.to_path_buf(),
+        kind: Vb6FileKind::from_path(&path.to_string_lossy()).map(|kind| format!("{:?}", kind)),
+    }
+}
+
+/// Groups of classes that can each reach the others, in path order
+fn class_cycles(
+    nodes: &BTreeMap<PathBuf, DependencyNode>,
+    counts: &BTreeMap<(PathBuf, PathBuf), usize>,
+) -> Vec<Vec<PathBuf>> {
+    let is_class = |path: &PathBuf| nodes[path].kind.as_deref() == Some("Class");
+    let classes: Vec<&PathBuf> = nodes.keys().filter(|path| is_class(path)).collect();
+    let reachable = |start: &PathBuf| {
+        let mut seen = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(current) = stack.pop() {
+            for (from, to) in counts.keys() {
+                if from == current && is_class(to) && seen.insert(to) {
+                    stack.push(to);
+                }
+            }
+        }
+        seen
+    };
+    let reach: Vec<HashSet<&PathBuf>> = classes.iter().map(|class| reachable(class)).collect();
+
+    let mut cycles: Vec<Vec<PathBuf>> = Vec::new();
+    for (i, class) in classes.iter().enumerate() {
+        if cycles.iter().any(|cycle| cycle.contains(class)) {
+            continue;
+        }
+        let mut cycle: Vec<PathBuf> = classes
+            .iter()
+            .enumerate()
+            .filter(|&(j, other)| i != j && reach[i].contains(other) && reach[j].contains(class))
+            .map(|(_, other)| (*other).clone())
+            .collect();
+        if !cycle.is_empty() {
+            cycle.push((*class).clone());
+            cycle.sort();
+            cycles.push(cycle);
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Vb6Parser;
+
+    #[test]
+    fn test_dependency_graph() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_deps_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let vbp = dir.join("Project1.vbp");
+        std::fs::write(
+            &vbp,
+            "Type=Exe\nModule=Module1; Module1.bas\nClass=Class1; Class1.cls\n\
+Class=Class2; Class2.cls\nClass=Logger; Logger.cls\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("Module1.bas"), "Public Sub Log(ByVal s As String)\nEnd Sub\n")
+            .unwrap();
+        std::fs::write(
+            dir.join("Class1.cls"),
+            "Private mOther As Class2\nPublic Sub Run()\n    Log \"run\"\n\
+    Module1.Log \"done\"\nEnd Sub\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("Class2.cls"),
+            "Private mOwner As Object\nPublic Sub Attach()\n    Set mOwner = New Class1\nEnd Sub\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("Logger.cls"), "Implements Class1\n").unwrap();
+
+        let mut workspace = WorkspaceManager::new();
+        workspace.load_project(&vbp).unwrap();
+        workspace.load_all_members(&mut Vb6Parser::new());
+        let graph = dependency_graph(&workspace);
+        std::fs::remove_dir_all(&dir).ok();
+
+        let names: Vec<&str> = graph.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["Class1", "Class2", "Logger", "Module1"]);
+        assert_eq!(graph.nodes[0].kind.as_deref(), Some("Class"));
+
+        let edges: Vec<(String, String, usize, bool)> = graph
+            .edges
+            .iter()
+            .map(|edge| {
+                let name = |path: &Path| path.file_stem().unwrap().to_string_lossy().into_owned();
+                (name(&edge.from), name(&edge.to), edge.count, edge.cyclic)
+            })
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("Class1".to_string(), "Class2".to_string(), 1, true),
+                ("Class1".to_string(), "Module1".to_string(), 2, false),
+                ("Class2".to_string(), "Class1".to_string(), 1, true),
+                ("Logger".to_string(), "Class1".to_string(), 1, false),
+            ]
+        );
+        assert_eq!(graph.class_cycles, vec![vec!["Class1".to_string(), "Class2".to_string()]]);
+    }
+}
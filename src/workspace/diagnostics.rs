@@ -0,0 +1,105 @@
+//! Document Diagnostics
+//!
+//! Runs the diagnostic passes over a parsed file, checking its names against
+//! the other files of its project. Shared by the language server and the
+//! `lint` command, so that both report the same problems.
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Url};
+use tree_sitter::Tree;
+
+use crate::analysis::{remove_suppressed, Analyzer, SymbolTable, SYNTAX_ERROR};
+use crate::parser::{InactiveRegion, ParseError, Vb6Ast};
+
+use super::WorkspaceManager;
+
+/// A parsed file to collect diagnostics for
+pub struct ParsedFile<'a> {
+    pub uri: &'a Url,
+    pub source: &'a str,
+    pub ast: &'a Vb6Ast,
+    /// Syntax errors found in the tree
+    pub errors: &'a [ParseError],
+    /// Code excluded by `#If` directives, which is not reported on
+    pub inactive: &'a [InactiveRegion],
+    /// The tree and the symbol table built from it, for the symbol-based passes
+    pub symbols: Option<(&'a Tree, &'a SymbolTable)>,
+}
+
+/// Report a syntax error as a diagnostic
+pub fn syntax_error(error: ParseError) -> Diagnostic {
+    Diagnostic {
+        range: error.range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(SYNTAX_ERROR.to_string())),
+        message: error.message,
+        source: Some("vb6-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Run every diagnostic pass over a file.
+///
+/// Diagnostics silenced by suppression comments or inside inactive `#If`
+/// code are dropped; filtering by client settings is left to the caller.
+pub fn collect_diagnostics(
+    analyzer: &Analyzer,
+    workspace: &WorkspaceManager,
+    file: &ParsedFile,
+) -> Vec<Diagnostic> {
+    let ParsedFile { uri, source, ast, errors, inactive, symbols } = *file;
+    let mut diagnostics = analyzer.analyze(ast);
+    diagnostics.extend(errors.iter().cloned().map(syntax_error));
+
+    // If/For/Do/... blocks without their closing keyword
+    diagnostics.extend(analyzer.find_unbalanced_blocks(uri, source, inactive));
+
+    if let Some((tree, table)) = symbols {
+        diagnostics.extend(analyzer.analyze_tree(tree, source));
+
+        // Names may be declared, or be classes, in other project files
+        let path = uri.to_file_path().ok();
+        let is_global_name = |name: &str| {
+            path.as_ref().is_some_and(|path| workspace.is_global_name(name, path))
+        };
+        diagnostics.extend(analyzer.find_undeclared_with_symbols(
+            table,
+            tree,
+            source,
+            is_global_name,
+        ));
+        diagnostics.extend(analyzer.find_array_problems_with_symbols(
+            table,
+            tree,
+            source,
+            is_global_name,
+        ));
+        diagnostics.extend(analyzer.find_set_mismatches_with_symbols(table, tree, source, |name| {
+            path.as_ref().is_some_and(|path| workspace.module_symbol_table(name, path).is_some())
+        }));
+        if let Some(ref path) = path {
+            diagnostics.extend(workspace.find_unused_module(path));
+            diagnostics.extend(workspace.find_duplicate_public_names(table, path));
+        }
+
+        diagnostics.extend(analyzer.find_byref_mismatches_with_symbols(table, tree, source));
+        diagnostics.extend(analyzer.find_unknown_named_arguments_with_symbols(table, tree, source));
+        diagnostics.extend(analyzer.find_integer_arithmetic_problems_with_symbols(
+            table, tree, source,
+        ));
+        diagnostics.extend(analyzer.find_plus_concatenation_with_symbols(table, tree, source));
+        diagnostics.extend(analyzer.find_circular_constants_with_symbols(table));
+        diagnostics.extend(analyzer.find_label_problems_with_symbols(table));
+        diagnostics.extend(analyzer.find_duplicate_procedures_with_symbols(table));
+        diagnostics.extend(analyzer.find_event_handler_problems_with_symbols(table));
+        diagnostics.extend(analyzer.find_shadowed_symbols_with_symbols(table));
+    }
+
+    remove_suppressed(&mut diagnostics, source);
+
+    // Code excluded by #If directives is not compiled, so don't report on it
+    diagnostics.retain(|d| {
+        let line = d.range.start.line as usize;
+        !inactive.iter().any(|r| r.contains_line(line))
+    });
+    diagnostics
+}
@@ -0,0 +1,187 @@
+//! Form Layout
+//!
+//! Extracts the geometry of a form and its controls from the designer block
+//! of a `.frm` (or `.ctl`/`.pag`) file, for tools that preview forms.
+//!
+//! Positions and sizes are returned as stored in the file: twips, unless a
+//! container's ScaleMode says otherwise.
+
+use serde::Serialize;
+
+use crate::parser::{DesignerControl, Vb6FileKind, Vb6Parser};
+
+/// The designer root of a form and the controls on it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormLayout {
+    pub name: String,
+    /// Designer type as written (e.g., "VB.Form")
+    pub control_type: String,
+    pub caption: Option<String>,
+    /// Size of the client area
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub controls: Vec<ControlLayout>,
+}
+
+/// A control and the controls it contains
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlLayout {
+    pub name: String,
+    /// Control type as written (e.g., "VB.CommandButton")
+    pub control_type: String,
+    /// Element index within a control array
+    pub index: Option<i64>,
+    /// Name of the form or container control the control sits on
+    pub parent: String,
+    pub left: Option<i64>,
+    pub top: Option<i64>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub caption: Option<String>,
+    pub tab_index: Option<i64>,
+    pub children: Vec<ControlLayout>,
+}
+
+/// Parse the layout of a form, user control, or property page.
+/// Returns None when the source has no designer block.
+pub fn parse_form_layout(source: &str) -> Option<FormLayout> {
+    let file = Vb6Parser::new().parse_as(source, Vb6FileKind::Form).ok()?;
+    file.designer.as_ref().map(FormLayout::from_designer)
+}
+
+impl FormLayout {
+    /// Build the layout from an already parsed designer block
+    pub fn from_designer(root: &DesignerControl) -> Self {
+        Self {
+            name: root.name.clone(),
+            control_type: root.control_type.clone(),
+            caption: text_property(root, "Caption"),
+            width: number_property(root, "ClientWidth").or_else(|| number_property(root, "Width")),
+            height: number_property(root, "ClientHeight")
+                .or_else(|| number_property(root, "Height")),
+            controls: root
+                .children
+                .iter()
+                .map(|child| ControlLayout::from_designer(child, &root.name))
+                .collect(),
+        }
+    }
+}
+
+impl ControlLayout {
+    fn from_designer(control: &DesignerControl, parent: &str) -> Self {
+        Self {
+            name: control.name.clone(),
+            control_type: control.control_type.clone(),
+            index: number_property(control, "Index"),
+            parent: parent.to_string(),
+            left: number_property(control, "Left"),
+            top: number_property(control, "Top"),
+            width: number_property(control, "Width"),
+            height: number_property(control, "Height"),
+            caption: text_property(control, "Caption"),
+            tab_index: number_property(control, "TabIndex"),
+            children: control
+                .children
+                .iter()
+                .map(|child| ControlLayout::from_designer(child, &control.name))
+                .collect(),
+        }
+    }
+}
+
+fn property<'a>(control: &'a DesignerControl, name: &str) -> Option<&'a str> {
+    control
+        .properties
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn number_property(control: &DesignerControl, name: &str) -> Option<i64> {
+    let value = property(control, name)?;
+    value
+        .parse()
+        .ok()
+        .or_else(|| value.parse::<f64>().ok().map(|v| v.round() as i64))
+}
+
+/// A string property with its quotes removed; FRX references are kept as written
+fn text_property(control: &DesignerControl, name: &str) -> Option<String> {
+    let value = property(control, name)?;
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(text) => Some(text.replace("\"\"", "\"")),
+        None => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_form_layout() {
+        let source = "VERSION 5.00\n\
+Begin VB.Form frmMain\n\
+   Caption         =   \"Say \"\"Hi\"\"\"\n\
+   ClientHeight    =   3090\n\
+   ClientWidth     =   4680\n\
+   Begin VB.Frame fraOptions\n\
+      Caption         =   \"Options\"\n\
+      Height          =   1215\n\
+      Left            =   120\n\
+      TabIndex        =   1\n\
+      Top             =   120\n\
+      Width           =   2055\n\
+      Begin VB.OptionButton optMode\n\
+         Caption         =   \"Fast\"\n\
+         Height          =   255\n\
+         Index           =   1\n\
+         Left            =   120\n\
+         TabIndex        =   2\n\
+         Top             =   600\n\
+         Width           =   1215\n\
+      End\n\
+   End\n\
+   Begin VB.CommandButton cmdOK\n\
+      Caption         =   $\"frmMain.frx\":0000\n\
+      Height          =   375\n\
+      Left            =   3240\n\
+      TabIndex        =   0\n\
+      Top             =   2520\n\
+      Width           =   1215\n\
+   End\n\
+End\n\
+Attribute VB_Name = \"frmMain\"\n";
+
+        let layout = parse_form_layout(source).unwrap();
+        assert_eq!(layout.name, "frmMain");
+        assert_eq!(layout.caption.as_deref(), Some("Say \"Hi\""));
+        assert_eq!((layout.width, layout.height), (Some(4680), Some(3090)));
+        assert_eq!(layout.controls.len(), 2);
+
+        let frame = &layout.controls[0];
+        assert_eq!((frame.name.as_str(), frame.parent.as_str()), ("fraOptions", "frmMain"));
+        assert_eq!(
+            (frame.left, frame.top, frame.width, frame.height),
+            (Some(120), Some(120), Some(2055), Some(1215))
+        );
+
+        let option = &frame.children[0];
+        assert_eq!(option.control_type, "VB.OptionButton");
+        assert_eq!(option.parent, "fraOptions");
+        assert_eq!((option.index, option.tab_index), (Some(1), Some(2)));
+        assert_eq!(option.caption.as_deref(), Some("Fast"));
+
+        let button = &layout.controls[1];
+        assert_eq!(button.caption.as_deref(), Some("$\"frmMain.frx\":0000"));
+        assert_eq!(button.index, None);
+
+        let json = serde_json::to_value(&layout).unwrap();
+        assert_eq!(json["controls"][0]["children"][0]["tabIndex"], 2);
+
+        assert!(parse_form_layout("Attribute VB_Name = \"Module1\"\n").is_none());
+    }
+}
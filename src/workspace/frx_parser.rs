@@ -23,6 +23,8 @@
 
 use std::io;
 
+use crate::utils::decode_vb6_bytes;
+
 /// Resolves a resource from a VB6 FRX file at the given offset.
 ///
 /// This function works identically for all VB6 visual designer file types:
@@ -89,14 +91,27 @@ use std::io;
 ///    - Bytes 1+: Actual data
 ///
 pub fn resource_file_resolver(file_path: &str, offset: usize) -> Result<Vec<u8>, io::Error> {
-    // Load the entire FRX file
-    let buffer = std::fs::read(file_path).map_err(|err| {
+    let buffer = read_frx_file(file_path)?;
+    resolve_record(&buffer, file_path, offset)
+}
+
+/// Load an entire FRX file
+fn read_frx_file(file_path: &str) -> Result<Vec<u8>, io::Error> {
+    std::fs::read(file_path).map_err(|err| {
         io::Error::new(
             io::ErrorKind::NotFound,
             format!("Failed to read resource file {}: {}", file_path, err),
         )
-    })?;
+    })
+}
 
+/// Check if the record at an offset is a list record (signature 0x03 0x00 or 0x07 0x00)
+fn is_list_record(buffer: &[u8], offset: usize) -> bool {
+    buffer.len() >= offset + 4 && matches!(buffer[offset + 2..offset + 4], [0x03, 0x00] | [0x07, 0x00])
+}
+
+/// Extract the record data at an offset of a loaded FRX file
+fn resolve_record(buffer: &[u8], file_path: &str, offset: usize) -> Result<Vec<u8>, io::Error> {
     // Validate offset
     if offset >= buffer.len() {
         return Err(io::Error::new(
@@ -110,30 +125,91 @@ pub fn resource_file_resolver(file_path: &str, offset: usize) -> Result<Vec<u8>,
     if buffer.len() >= offset + 12 {
         let signature = &buffer[offset + 4..offset + 8];
         if signature == b"lt\0\0" {
-            return parse_12_byte_header(&buffer, file_path, offset);
+            return parse_12_byte_header(buffer, file_path, offset);
         }
     }
 
     // Check for 16-bit record (starts with 0xFF)
     if buffer[offset] == 0xFF {
-        return parse_16bit_record(&buffer, file_path, offset);
+        return parse_16bit_record(buffer, file_path, offset);
     }
 
     // Check for list record
-    if buffer.len() >= offset + 4 {
-        let list_signature = &buffer[offset + 2..offset + 4];
-        if list_signature == [0x03, 0x00] || list_signature == [0x07, 0x00] {
-            return parse_list_record(&buffer, file_path, offset);
-        }
+    if is_list_record(buffer, offset) {
+        return parse_list_record(buffer, file_path, offset);
     }
 
     // Check for 4-byte header (contains null bytes)
     if buffer.len() >= offset + 12 && buffer[offset..offset + 4].contains(&0u8) {
-        return parse_4_byte_header(&buffer, file_path, offset);
+        return parse_4_byte_header(buffer, file_path, offset);
     }
 
     // Default: 8-bit record
-    parse_8bit_record(&buffer, file_path, offset)
+    parse_8bit_record(buffer, file_path, offset)
+}
+
+/// The kind of payload stored in an FRX record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrxResourceKind {
+    /// A picture or icon (Picture, Icon, MouseIcon, ...)
+    Image,
+    /// A list of strings (ListBox/ComboBox `List`, `ItemData`)
+    List,
+    /// Long text (multi-line Text, Caption, Tag)
+    Text,
+    /// Any other binary data
+    Binary,
+}
+
+/// A decoded FRX record
+#[derive(Debug, Clone)]
+pub struct FrxResource {
+    /// Offset of the record in the FRX file
+    pub offset: usize,
+    /// What the record holds
+    pub kind: FrxResourceKind,
+    /// Image format for pictures (e.g., "BMP", "ICO")
+    pub image_type: Option<&'static str>,
+    /// The items of a list record
+    pub items: Vec<String>,
+    /// The record data, without its header
+    pub data: Vec<u8>,
+}
+
+/// Read and classify the record at an offset of an FRX file.
+///
+/// `offset` is the value after the colon in a `.frm` reference such as
+/// `$"frmMain.frx":00AC` (already converted from hex).
+pub fn read_frx_resource(file_path: &str, offset: usize) -> anyhow::Result<FrxResource> {
+    let buffer = read_frx_file(file_path)?;
+    let data = resolve_record(&buffer, file_path, offset)?;
+
+    let image_type = crate::controls::frx::detect_image_type(&data);
+    let (kind, items) = if is_list_record(&buffer, offset) {
+        (FrxResourceKind::List, list_resolver(&data))
+    } else if image_type.is_some() {
+        (FrxResourceKind::Image, Vec::new())
+    } else if is_text(&data) {
+        (FrxResourceKind::Text, Vec::new())
+    } else {
+        (FrxResourceKind::Binary, Vec::new())
+    };
+
+    Ok(FrxResource {
+        offset,
+        kind,
+        image_type,
+        items,
+        data,
+    })
+}
+
+/// Check if record data looks like text (printable characters and line breaks)
+fn is_text(data: &[u8]) -> bool {
+    !data.is_empty()
+        && data
+            .iter()
+            .all(|&b| b >= 0x20 || matches!(b, b'\r' | b'\n' | b'\t'))
 }
 
 /// Parse a 12-byte header record with "lt\0\0" signature
@@ -395,8 +471,8 @@ pub fn list_resolver(buffer: &[u8]) -> Vec<String> {
 
         let item_bytes = &buffer[item_start..item_end];
 
-        // Try to convert to UTF-8, fallback to lossy conversion
-        let item_string = String::from_utf8_lossy(item_bytes).to_string();
+        // List items are stored in the form's ANSI codepage
+        let item_string = decode_vb6_bytes(item_bytes);
         list_items.push(item_string);
 
         record_offset += list_item_header_size + list_item_size;
@@ -519,6 +595,32 @@ mod tests {
         assert_eq!(result, vec![0x41, 0x42, 0x43]);
     }
 
+    #[test]
+    fn test_read_frx_resource() {
+        let path = std::env::temp_dir().join(format!("vb6_lsp_test_{}.frx", std::process::id()));
+        let mut data = vec![
+            0x02, 0x00,              // 2 items
+            0x03, 0x00,              // signature
+            0x03, 0x00, b'O', b'n', b'e',
+            0x03, 0x00, b'T', b'w', b'o',
+        ];
+        let text_offset = data.len();
+        data.extend_from_slice(&[0x05, b'H', b'e', b'l', b'l', b'o']);
+        std::fs::write(&path, &data).unwrap();
+        let file_path = path.to_str().unwrap();
+
+        let list = read_frx_resource(file_path, 0).unwrap();
+        assert_eq!(list.kind, FrxResourceKind::List);
+        assert_eq!(list.items, vec!["One", "Two"]);
+
+        let text = read_frx_resource(file_path, text_offset).unwrap();
+        assert_eq!(text.kind, FrxResourceKind::Text);
+        assert_eq!(text.data, b"Hello");
+
+        assert!(read_frx_resource(file_path, data.len()).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_4byte_header() {
         // 4-byte header with 6 bytes of data
@@ -0,0 +1,436 @@
+//! Project Health Report
+//!
+//! Cross-file checks over a whole VBP project: missing member files, public
+//! procedures that are never called, forms that are never shown, and public
+//! names declared by more than one standard module.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use tower_lsp::lsp_types::Url;
+
+use crate::analysis::{build_symbol_table, Symbol, SymbolKind, SymbolTable, Visibility};
+use crate::parser::TreeSitterVb6Parser;
+
+use super::vbp_parser::{ProjectMember, ProjectType, StartupObject, VbpParseError};
+use super::{Vb6Project, WorkspaceManager, DUPLICATE_PUBLIC_NAME};
+
+/// Kind of project-wide problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectIssueKind {
+    /// A member listed in the VBP does not exist
+    MissingFile,
+    /// A public procedure is not used by any file
+    UnusedProcedure,
+    /// A form is not the startup object and no other file refers to it
+    UnshownForm,
+    /// Two standard modules declare the same public name
+    DuplicatePublicName,
+}
+
+impl ProjectIssueKind {
+    /// Stable identifier for reports (e.g., "unused-procedure")
+    pub fn code(self) -> &'static str {
+        match self {
+            ProjectIssueKind::MissingFile => "missing-file",
+            ProjectIssueKind::UnusedProcedure => "unused-procedure",
+            ProjectIssueKind::UnshownForm => "form-never-shown",
+            ProjectIssueKind::DuplicatePublicName => DUPLICATE_PUBLIC_NAME,
+        }
+    }
+}
+
+/// A project-wide problem found in one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectIssue {
+    pub kind: ProjectIssueKind,
+    /// Line in the file (0-indexed)
+    pub line: usize,
+    pub message: String,
+}
+
+/// Problems of a project, keyed by the file they were found in (the VBP
+/// itself for missing members)
+pub type ProjectReport = BTreeMap<PathBuf, Vec<ProjectIssue>>;
+
+/// A parsed member file
+struct ParsedMember {
+    /// Path as listed in the VBP, which the workspace indexes by
+    path: PathBuf,
+    table: SymbolTable,
+    /// Lowercase identifiers used anywhere in the file's code
+    identifiers: HashSet<String>,
+}
+
+/// Load a project, parse all of its members into the workspace, and report
+/// its cross-file problems.
+///
+/// Members are parsed on one thread per CPU, since projects can have
+/// hundreds of files.
+pub fn analyze_project(
+    workspace: &mut WorkspaceManager,
+    vbp_path: &Path,
+) -> Result<ProjectReport, VbpParseError> {
+    workspace.load_project(vbp_path)?;
+    let project = workspace
+        .get_project(vbp_path)
+        .expect("a loaded project is registered under its path");
+
+    let mut report = ProjectReport::new();
+    let mut files = Vec::new();
+    for member in project.source_files() {
+        match project.member_file(member) {
+            Some(file) => files.push((member.absolute_path.clone(), file)),
+            None => report.entry(vbp_path.to_path_buf()).or_default().push(ProjectIssue {
+                kind: ProjectIssueKind::MissingFile,
+                line: member.line,
+                message: format!(
+                    "File not found for '{}': {}",
+                    member.name,
+                    member.relative_path.display()
+                ),
+            }),
+        }
+    }
+
+    let parsed = parse_members(project, &files);
+    let startup = project.startup_object();
+    let startup_form = match &startup {
+        StartupObject::Form(name) => Some(name.to_lowercase()),
+        _ => None,
+    };
+    // The public members of a library's classes are its interface
+    let classes_are_api = project.vbp.project_type != ProjectType::Exe;
+    let kind_of = |path: &Path| member_kind(&project.vbp, path);
+
+    for member in &parsed {
+        let kind = kind_of(&member.path);
+        if kind == MemberKind::Class && classes_are_api {
+            continue;
+        }
+        for procedure in unused_procedures(member, &parsed, startup == StartupObject::SubMain) {
+            report.entry(member.path.clone()).or_default().push(procedure);
+        }
+    }
+
+    // Missing forms are already reported
+    for form in project.vbp.forms.iter().filter(|form| project.member_file(form).is_some()) {
+        let name = form.name.to_lowercase();
+        let mentioned = parsed
+            .iter()
+            .filter(|other| !same_file(&other.path, &form.absolute_path))
+            .any(|other| other.identifiers.contains(&name));
+        if startup_form.as_ref() != Some(&name) && !mentioned {
+            report.entry(form.absolute_path.clone()).or_default().push(ProjectIssue {
+                kind: ProjectIssueKind::UnshownForm,
+                line: 0,
+                message: format!("Form '{}' is never shown or referenced", form.name),
+            });
+        }
+    }
+
+    let modules: Vec<&ParsedMember> =
+        parsed.iter().filter(|m| kind_of(&m.path) == MemberKind::Module).collect();
+    for (path, issue) in duplicate_public_names(&modules) {
+        report.entry(path).or_default().push(issue);
+    }
+
+    for issues in report.values_mut() {
+        issues.sort_by_key(|issue| issue.line);
+    }
+
+    for member in parsed {
+        workspace.set_symbol_table(&member.path, member.table);
+    }
+
+    Ok(report)
+}
+
+/// Parse member files in parallel, skipping files that can't be read
+fn parse_members(project: &Vb6Project, files: &[(PathBuf, PathBuf)]) -> Vec<ParsedMember> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = files.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let Ok(mut parser) = TreeSitterVb6Parser::new() else {
+                        return Vec::new();
+                    };
+                    chunk
+                        .iter()
+                        .filter_map(|(listed, file)| {
+                            parse_member(project, &mut parser, listed, file)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+fn parse_member(
+    project: &Vb6Project,
+    parser: &mut TreeSitterVb6Parser,
+    listed: &Path,
+    file: &Path,
+) -> Option<ParsedMember> {
+    let source = project.load_member_source(listed).ok()?;
+    let tree = parser.parse(&source, None)?;
+    let uri = Url::from_file_path(file).ok()?;
+
+    Some(ParsedMember {
+        path: listed.to_path_buf(),
+        table: build_symbol_table(uri, &source, &tree),
+        identifiers: identifiers(&source),
+    })
+}
+
+/// Public procedures of a member that neither it nor any other file uses
+fn unused_procedures(
+    member: &ParsedMember,
+    parsed: &[ParsedMember],
+    sub_main_starts: bool,
+) -> Vec<ProjectIssue> {
+    let table = &member.table;
+    // Called by VB6 rather than by code: event handlers and Implements
+    // methods (IShape_Draw)
+    let is_event_handler = |symbol: &Symbol| {
+        table.is_event_handler(symbol)
+            || symbol.name.split_once('_').is_some_and(|(object, _)| {
+                parsed.iter().any(|other| {
+                    other.path.file_stem().is_some_and(|stem| {
+                        stem.to_string_lossy().eq_ignore_ascii_case(object)
+                    })
+                })
+            })
+    };
+
+    let mut reported = HashSet::new();
+    table
+        .module_symbols()
+        .into_iter()
+        .filter(|s| {
+            matches!(
+                s.kind,
+                SymbolKind::Sub
+                    | SymbolKind::Function
+                    | SymbolKind::PropertyGet
+                    | SymbolKind::PropertyLet
+                    | SymbolKind::PropertySet
+            ) && s.visibility != Visibility::Private
+        })
+        .filter(|s| !(sub_main_starts && s.name.eq_ignore_ascii_case("main")))
+        .filter(|s| !is_event_handler(s))
+        .filter(|s| {
+            // Property Get/Let/Set share a name, and any of them counts as a use
+            let name = s.name.to_lowercase();
+            let used_here = table
+                .module_symbols()
+                .into_iter()
+                .filter(|other| other.name.eq_ignore_ascii_case(&name))
+                .any(|other| !table.get_references(other.id).is_empty());
+            let used_elsewhere = parsed
+                .iter()
+                .filter(|other| other.path != member.path)
+                .any(|other| other.identifiers.contains(&name));
+            !used_here && !used_elsewhere && reported.insert(name)
+        })
+        .map(|s| ProjectIssue {
+            kind: ProjectIssueKind::UnusedProcedure,
+            line: s.name_range.start.line as usize,
+            message: format!("Public {} '{}' is never called", kind_label(s.kind), s.name),
+        })
+        .collect()
+}
+
+/// Public names declared by more than one standard module, reported in each
+fn duplicate_public_names(modules: &[&ParsedMember]) -> Vec<(PathBuf, ProjectIssue)> {
+    // Lowercase name -> (module, name as declared, line)
+    let mut declarations: HashMap<String, Vec<(&ParsedMember, &str, usize)>> = HashMap::new();
+    for module in modules {
+        for symbol in module.table.module_symbols() {
+            if symbol.visibility != Visibility::Public
+                || matches!(symbol.kind, SymbolKind::TypeMember | SymbolKind::EnumMember)
+            {
+                continue;
+            }
+            let entry = declarations.entry(symbol.name.to_lowercase()).or_default();
+            // Property Get/Let/Set of one module declare a single name
+            if !entry.iter().any(|(other, _, _)| other.path == module.path) {
+                entry.push((module, &symbol.name, symbol.name_range.start.line as usize));
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+    for modules in declarations.values().filter(|modules| modules.len() > 1) {
+        for (module, name, line) in modules {
+            let others: Vec<String> = modules
+                .iter()
+                .filter(|(other, _, _)| other.path != module.path)
+                .map(|(other, _, _)| file_name(&other.path))
+                .collect();
+            issues.push((
+                module.path.clone(),
+                ProjectIssue {
+                    kind: ProjectIssueKind::DuplicatePublicName,
+                    line: *line,
+                    message: format!("Public '{}' is also declared in {}", name, others.join(", ")),
+                },
+            ));
+        }
+    }
+    issues
+}
+
+/// Kind of project member, as far as the checks care
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MemberKind {
+    Module,
+    Class,
+    Other,
+}
+
+fn member_kind(vbp: &super::VbpFile, path: &Path) -> MemberKind {
+    let listed = |members: &[ProjectMember]| members.iter().any(|m| m.absolute_path == path);
+    if listed(&vbp.modules) {
+        MemberKind::Module
+    } else if listed(&vbp.classes) {
+        MemberKind::Class
+    } else {
+        MemberKind::Other
+    }
+}
+
+fn kind_label(kind: SymbolKind) -> &'static str {
+    match kind {
+        SymbolKind::Sub => "Sub",
+        SymbolKind::Function => "Function",
+        _ => "Property",
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a.to_string_lossy().eq_ignore_ascii_case(&b.to_string_lossy())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Collect the lowercase identifiers of a file's code, skipping comments,
+/// string literals, and the designer header
+fn identifiers(source: &str) -> HashSet<String> {
+    let mut identifiers = HashSet::new();
+
+    for line in source.lines() {
+        let trimmed = line.trim_start().to_lowercase();
+        if trimmed.starts_with("rem ") || trimmed.starts_with("attribute ") {
+            continue;
+        }
+
+        let mut in_string = false;
+        let mut word = String::new();
+        for c in line.chars().chain(std::iter::once(' ')) {
+            if in_string {
+                in_string = c != '"';
+                continue;
+            }
+            if c.is_alphanumeric() || c == '_' {
+                word.push(c);
+                continue;
+            }
+            if !word.is_empty() {
+                identifiers.insert(word.to_lowercase());
+                word.clear();
+            }
+            match c {
+                '"' => in_string = true,
+                '\'' => break,
+                _ => {}
+            }
+        }
+    }
+
+    identifiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_report() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_health_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("Test.vbp"),
+            "Type=Exe\nModule=ModMain; ModMain.bas\nModule=ModUtil; ModUtil.bas\n\
+Form=frmMain.frm\nForm=frmAbout.frm\nForm=frmGone.frm\nStartup=\"Sub Main\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("ModMain.bas"),
+            "Attribute VB_Name = \"ModMain\"\nPublic Const APP_NAME = \"x\"\n\
+Public Sub Main()\n    frmMain.Show\n    Helper\nEnd Sub\n\
+Public Sub Orphan()\nEnd Sub\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("ModUtil.bas"),
+            "Attribute VB_Name = \"ModUtil\"\nPublic Const APP_NAME = \"y\"\n\
+Public Sub Helper()\nEnd Sub\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("frmMain.frm"),
+            "VERSION 5.00\nBegin VB.Form frmMain\nEnd\nAttribute VB_Name = \"frmMain\"\n\
+Private Sub Form_Load()\n    ' frmAbout.Show\nEnd Sub\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("frmAbout.frm"),
+            "VERSION 5.00\nBegin VB.Form frmAbout\nEnd\nAttribute VB_Name = \"frmAbout\"\n",
+        )
+        .unwrap();
+
+        let mut workspace = WorkspaceManager::new();
+        let report = analyze_project(&mut workspace, &dir.join("Test.vbp")).unwrap();
+        let indexed = workspace.get_symbol_table(&dir.join("ModUtil.bas")).is_some();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let issues = |file: &str| -> Vec<(ProjectIssueKind, usize)> {
+            report
+                .get(&dir.join(file))
+                .map(|issues| issues.iter().map(|i| (i.kind, i.line)).collect())
+                .unwrap_or_default()
+        };
+        assert!(indexed);
+        assert_eq!(issues("Test.vbp"), vec![(ProjectIssueKind::MissingFile, 5)]);
+        assert_eq!(
+            issues("ModMain.bas"),
+            vec![
+                (ProjectIssueKind::DuplicatePublicName, 1),
+                (ProjectIssueKind::UnusedProcedure, 6),
+            ]
+        );
+        assert_eq!(issues("ModUtil.bas"), vec![(ProjectIssueKind::DuplicatePublicName, 1)]);
+        // A form mentioned only in a comment is never shown
+        assert_eq!(issues("frmAbout.frm"), vec![(ProjectIssueKind::UnshownForm, 0)]);
+        assert!(issues("frmMain.frm").is_empty());
+        assert_eq!(
+            report[&dir.join("ModMain.bas")][0].message,
+            "Public 'APP_NAME' is also declared in ModUtil.bas"
+        );
+    }
+}
@@ -2,28 +2,67 @@
 //!
 //! Handles multi-project workspaces with VBP discovery and cross-project navigation.
 
+mod dependencies;
+mod diagnostics;
+mod form_layout;
 mod frx_parser;
+mod health;
 mod project;
+mod rc_script;
 mod res_parser;
 mod vbp_parser;
+mod watcher;
 
-pub use frx_parser::{list_resolver, resource_file_resolver};
-pub use project::{ProjectStats, Vb6Project};
+pub use dependencies::{dependency_graph, DependencyEdge, DependencyGraph, DependencyNode};
+pub use diagnostics::{collect_diagnostics, syntax_error, ParsedFile};
+pub use form_layout::{parse_form_layout, ControlLayout, FormLayout};
+pub use frx_parser::{
+    list_resolver, read_frx_resource, resource_file_resolver, FrxResource, FrxResourceKind,
+};
+pub use health::{analyze_project, ProjectIssue, ProjectIssueKind, ProjectReport};
+pub use project::{ProjectDiagnostic, ProjectStats, Vb6Project};
+pub use rc_script::res_to_rc;
 pub use res_parser::{
-    create_string_table, parse_string_table, read_res_file, write_res_file, MemoryFlags,
-    ResHeader, ResourceEntry, ResourceId, ResourceType, StringTableEntry,
+    build_string_table_block, create_string_table, parse_string_table, read_res_file,
+    replace_resource, write_res_file, MemoryFlags, ResHeader, ResourceEntry, ResourceId,
+    ResourceType, StringTableEntry,
 };
 pub use vbp_parser::{
-    ObjectReference, ProjectMember, ProjectType, TypeLibReference, VbpFile, VbpParseError,
+    ObjectReference, ProjectMember, ProjectType, StartupObject, TypeLibReference, VbpFile,
+    VbpParseError, VersionInfo,
 };
+pub use watcher::{Debouncer, FileChange, FileEvent, FsWatcher};
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-use tower_lsp::lsp_types::{Location, Url};
+use tower_lsp::lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Diagnostic,
+    DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Position, Range,
+    SymbolKind as LspSymbolKind, Url,
+};
 use walkdir::WalkDir;
 
-use crate::analysis::{SymbolKind, SymbolTable};
+use crate::parser::{ClassAttributes, Vb6Ast, Vb6Parser};
+
+use crate::analysis::{
+    call_hierarchy_item, find_call_sites, register_user_control, user_control_name, CallSite,
+    SourcePosition, SourceRange, Symbol, SymbolId, SymbolKind, SymbolTable, Visibility,
+};
+
+/// Diagnostic code for a form or class that no other file uses
+pub const UNUSED_MODULE: &str = "unused-module";
+
+/// Diagnostic code for a Public name that another standard module also declares
+pub const DUPLICATE_PUBLIC_NAME: &str = "duplicate-public-name";
+
+/// Unresolved identifier uses in a file, keyed by lowercase name, with the
+/// object qualifying a member use (`modUtils.DoThing`)
+type ReferenceIndex = HashMap<String, Vec<(SourceRange, Option<String>)>>;
+
+/// Files of the classes implementing an interface, keyed by lowercase interface name
+type InterfaceIndex = HashMap<String, Vec<PathBuf>>;
 
 /// Manages all VB6 projects in a workspace
 #[derive(Debug)]
@@ -39,6 +78,12 @@ pub struct WorkspaceManager {
 
     /// Files that don't belong to any VBP (orphans)
     orphan_files: HashMap<PathBuf, SymbolTable>,
+
+    /// Per-file reference indices for find references, built on demand
+    reference_indices: Mutex<HashMap<Url, Arc<ReferenceIndex>>>,
+
+    /// Implementing classes of each interface, built on demand
+    interface_index: Mutex<Option<Arc<InterfaceIndex>>>,
 }
 
 impl WorkspaceManager {
@@ -49,12 +94,14 @@ impl WorkspaceManager {
             projects: HashMap::new(),
             file_to_project: HashMap::new(),
             orphan_files: HashMap::new(),
+            reference_indices: Mutex::new(HashMap::new()),
+            interface_index: Mutex::new(None),
         }
     }
 
     /// Add a workspace root and scan for VBP files
     pub fn add_root(&mut self, root: PathBuf) -> Vec<PathBuf> {
-        let discovered = self.scan_for_vbp_files(&root);
+        let discovered = Self::scan_for_vbp_files(&root, |_| {});
 
         for vbp_path in &discovered {
             if let Err(e) = self.load_project(vbp_path) {
@@ -62,10 +109,18 @@ impl WorkspaceManager {
             }
         }
 
-        self.roots.push(root);
+        self.register_root(root);
         discovered
     }
 
+    /// Add a workspace root without scanning it, for callers that load its
+    /// projects themselves (e.g., in the background)
+    pub fn register_root(&mut self, root: PathBuf) {
+        if !self.roots.contains(&root) {
+            self.roots.push(root);
+        }
+    }
+
     /// Remove a workspace root
     pub fn remove_root(&mut self, root: &Path) {
         self.roots.retain(|r| r != root);
@@ -83,8 +138,8 @@ impl WorkspaceManager {
         }
     }
 
-    /// Scan a directory recursively for .vbp files
-    fn scan_for_vbp_files(&self, root: &Path) -> Vec<PathBuf> {
+    /// Scan a directory recursively for .vbp files, calling `on_found` as each is found
+    pub fn scan_for_vbp_files(root: &Path, mut on_found: impl FnMut(&Path)) -> Vec<PathBuf> {
         let mut vbp_files = Vec::new();
 
         for entry in WalkDir::new(root)
@@ -96,6 +151,7 @@ impl WorkspaceManager {
             if path.extension().map_or(false, |ext| {
                 ext.eq_ignore_ascii_case("vbp")
             }) {
+                on_found(path);
                 vbp_files.push(path.to_path_buf());
             }
         }
@@ -112,7 +168,12 @@ impl WorkspaceManager {
     /// Load a VBP project
     pub fn load_project(&mut self, vbp_path: &Path) -> Result<(), VbpParseError> {
         let project = Vb6Project::from_vbp(vbp_path)?;
+        self.add_project(vbp_path, project);
+        Ok(())
+    }
 
+    /// Add a project parsed from its VBP file
+    pub fn add_project(&mut self, vbp_path: &Path, project: Vb6Project) {
         // Build file-to-project index
         for member in project.source_files() {
             let normalized = normalize_path(&member.absolute_path);
@@ -120,6 +181,13 @@ impl WorkspaceManager {
                 .insert(normalized, vbp_path.to_path_buf());
         }
 
+        // Forms of the project can use the controls of the OCXs it references
+        for filename in project.vbp.objects.iter().filter_map(|o| o.filename.as_deref()) {
+            if crate::controls::register_ocx(filename) == 0 {
+                tracing::debug!("No bundled control definitions for {}", filename);
+            }
+        }
+
         tracing::info!(
             "Loaded project '{}' with {} source files",
             project.name(),
@@ -127,7 +195,6 @@ impl WorkspaceManager {
         );
 
         self.projects.insert(vbp_path.to_path_buf(), project);
-        Ok(())
     }
 
     /// Unload a VBP project
@@ -143,6 +210,78 @@ impl WorkspaceManager {
         }
     }
 
+    /// Workspace root folders
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Bring the workspace up to date with a file changed outside the editor.
+    ///
+    /// A changed VBP is reloaded (dropping its symbol tables), a deleted one
+    /// unloaded, and a changed or deleted source file loses its stale symbol
+    /// table. Returns the VBP of the affected project, if any.
+    pub fn apply_file_event(&mut self, event: &FileEvent) -> Option<PathBuf> {
+        if event.is_project_file() {
+            let vbp_path = self
+                .projects
+                .keys()
+                .find(|path| normalize_path(path) == normalize_path(&event.path))
+                .cloned()
+                .unwrap_or_else(|| event.path.clone());
+            let loaded = self.projects.contains_key(&vbp_path);
+
+            match event.change {
+                FileChange::Deleted if loaded => self.unload_project(&vbp_path),
+                FileChange::Deleted => return None,
+                // New projects are only picked up under a workspace root
+                _ if !loaded && !self.roots.iter().any(|root| vbp_path.starts_with(root)) => {
+                    return None;
+                }
+                _ => {
+                    self.unload_project(&vbp_path);
+                    if let Err(e) = self.load_project(&vbp_path) {
+                        tracing::warn!("Failed to reload VBP {}: {}", vbp_path.display(), e);
+                    }
+                }
+            }
+            return Some(vbp_path);
+        }
+
+        let normalized = normalize_path(&event.path);
+        // A closed UserControl stays registered; a deleted one is gone
+        if event.change == FileChange::Deleted {
+            let deleted = self.get_symbol_table(&event.path).and_then(user_control_name);
+            if let Some(name) = deleted {
+                crate::controls::unregister_user_control(&name);
+            }
+        }
+        self.remove_symbol_table(&event.path);
+        match event.change {
+            FileChange::Deleted => self.file_to_project.remove(&normalized),
+            // A file listed by a project may be back after being deleted
+            FileChange::Created => {
+                let vbp_path = self
+                    .projects
+                    .iter()
+                    .find(|(_, project)| project.contains_file(&event.path))
+                    .map(|(vbp_path, _)| vbp_path.clone());
+                if let Some(vbp_path) = &vbp_path {
+                    self.file_to_project.insert(normalized, vbp_path.clone());
+                }
+                vbp_path
+            }
+            FileChange::Changed => self.file_to_project.get(&normalized).cloned(),
+        }
+    }
+
+    /// The name of a file's module: its project member name, or else its file name
+    pub fn module_name(&self, file_path: &Path) -> Option<String> {
+        self.project_for_file(file_path)
+            .and_then(|project| project.get_member(file_path))
+            .map(|member| member.name.clone())
+            .or_else(|| Some(file_path.file_stem()?.to_string_lossy().into_owned()))
+    }
+
     /// Get the project that contains a file
     pub fn project_for_file(&self, file_path: &Path) -> Option<&Vb6Project> {
         let normalized = normalize_path(file_path);
@@ -170,6 +309,9 @@ impl WorkspaceManager {
     /// Store a symbol table for a file
     pub fn set_symbol_table(&mut self, file_path: &Path, table: SymbolTable) {
         let normalized = normalize_path(file_path);
+        self.invalidate_reference_index(&table.uri);
+        // Forms see the members of a UserControl once it is indexed
+        register_user_control(&table);
 
         if let Some(project) = self.project_for_file_mut(file_path) {
             project.set_symbol_table(normalized, table);
@@ -193,6 +335,9 @@ impl WorkspaceManager {
     /// Remove a symbol table
     pub fn remove_symbol_table(&mut self, file_path: &Path) {
         let normalized = normalize_path(file_path);
+        if let Some(uri) = self.get_symbol_table(file_path).map(|table| table.uri.clone()) {
+            self.invalidate_reference_index(&uri);
+        }
 
         if let Some(project) = self.project_for_file_mut(file_path) {
             project.remove_symbol_table(&normalized);
@@ -201,6 +346,47 @@ impl WorkspaceManager {
         }
     }
 
+    /// Re-read and parse a project member from disk (see `Vb6Project::reparse_member`)
+    pub fn reparse_file(
+        &mut self,
+        file_path: &Path,
+        parser: &mut Vb6Parser,
+    ) -> std::io::Result<&Vb6Ast> {
+        if let Ok(uri) = Url::from_file_path(file_path) {
+            self.invalidate_reference_index(&uri);
+        }
+        let project = self.project_for_file_mut(file_path).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} is not in a loaded project", file_path.display()),
+            )
+        })?;
+        project.reparse_member(file_path, parser)
+    }
+
+    /// Parse every project member that has no symbol table yet, so that
+    /// workspace-wide queries see all files
+    pub fn load_all_members(&mut self, parser: &mut Vb6Parser) {
+        for path in self.unloaded_members() {
+            if let Err(e) = self.reparse_file(&path, parser) {
+                tracing::debug!("Could not parse {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Project members that exist on disk but have no symbol table yet
+    pub fn unloaded_members(&self) -> Vec<PathBuf> {
+        self.projects
+            .values()
+            .flat_map(|project| {
+                project
+                    .source_files()
+                    .filter(|member| !is_member_loaded(project, member))
+                    .map(|member| member.absolute_path.clone())
+            })
+            .collect()
+    }
+
     /// Resolve a symbol across the workspace
     /// First checks the current file's project, then other projects
     pub fn resolve_symbol(&self, name: &str, from_file: &Path) -> Option<Location> {
@@ -241,6 +427,469 @@ impl WorkspaceManager {
         None
     }
 
+    /// Resolve a module-qualified reference like `modUtils.DoThing`
+    /// to a non-Private member of that module
+    pub fn resolve_qualified_symbol(
+        &self,
+        module: &str,
+        member: &str,
+        from_file: &Path,
+    ) -> Option<Location> {
+        let table = self.module_symbol_table(module, from_file)?;
+        let symbol = table.lookup_symbol(member, table.module_scope)?;
+        if symbol.visibility == crate::analysis::Visibility::Private {
+            return None;
+        }
+
+        Some(Location {
+            uri: table.uri.clone(),
+            range: symbol.name_range.to_lsp(),
+        })
+    }
+
+    /// Find the symbol table of a module by name, preferring the current file's project.
+    /// Orphan files are matched by file name.
+    pub fn module_symbol_table(&self, module: &str, from_file: &Path) -> Option<&SymbolTable> {
+        let projects = self.project_for_file(from_file).into_iter().chain(self.projects.values());
+        for project in projects {
+            let table = project.get_member_by_name(module).and_then(|member| {
+                project.get_symbol_table(&normalize_path(&member.absolute_path))
+            });
+            if table.is_some() {
+                return table;
+            }
+        }
+
+        self.orphan_files
+            .iter()
+            .find(|(path, _)| {
+                path.file_stem()
+                    .is_some_and(|stem| stem.to_string_lossy().eq_ignore_ascii_case(module))
+            })
+            .map(|(_, table)| table)
+    }
+
+    /// Find every reference to a module-level Public symbol across the workspace,
+    /// starting with its declaration.
+    ///
+    /// Uses in other files count only if they resolve to this declaration, so
+    /// shadowing locals and same-named symbols in closer projects are skipped.
+    /// Private symbols aren't visible outside their file and yield nothing.
+    pub fn find_all_references(
+        &self,
+        name: &str,
+        kind: SymbolKind,
+        defined_in: &Path,
+    ) -> Vec<Location> {
+        let Some(table) = self.get_symbol_table(defined_in) else {
+            return Vec::new();
+        };
+        let Some(symbol) = table
+            .lookup_symbol(name, table.module_scope)
+            .filter(|symbol| symbol.kind == kind && symbol.visibility == Visibility::Public)
+        else {
+            return Vec::new();
+        };
+        let declaration = Location {
+            uri: table.uri.clone(),
+            range: symbol.name_range.to_lsp(),
+        };
+
+        let mut locations = vec![declaration.clone()];
+        locations.extend(table.get_references(symbol.id).into_iter().map(|reference| Location {
+            uri: table.uri.clone(),
+            range: reference.range.to_lsp(),
+        }));
+
+        let key = name.to_lowercase();
+        for other in self.symbol_tables().filter(|other| other.uri != table.uri) {
+            let index = self.reference_index(other);
+            let Some(uses) = index.get(&key) else {
+                continue;
+            };
+            let Ok(other_path) = other.uri.to_file_path() else {
+                continue;
+            };
+            // Plain uses must resolve to the declaration, qualified ones name its module
+            let resolves = self.resolve_symbol(name, &other_path).as_ref() == Some(&declaration);
+            let names_module = |module: &str| {
+                self.module_symbol_table(module, &other_path)
+                    .is_some_and(|module_table| module_table.uri == table.uri)
+            };
+            locations.extend(
+                uses.iter()
+                    .filter(|(_, object)| match object {
+                        Some(object) => names_module(object),
+                        None => resolves,
+                    })
+                    .map(|(range, _)| Location {
+                        uri: other.uri.clone(),
+                        range: range.to_lsp(),
+                    }),
+            );
+        }
+
+        locations
+    }
+
+    /// Get the reference index of a file, building it if it isn't cached
+    fn reference_index(&self, table: &SymbolTable) -> Arc<ReferenceIndex> {
+        let mut indices = self.reference_indices.lock().unwrap();
+        indices
+            .entry(table.uri.clone())
+            .or_insert_with(|| {
+                let mut index = ReferenceIndex::new();
+                for unresolved in table.unresolved_references() {
+                    index
+                        .entry(unresolved.name.to_lowercase())
+                        .or_default()
+                        .push((unresolved.range, None));
+                }
+                for member_use in table.member_uses() {
+                    index
+                        .entry(member_use.member.to_lowercase())
+                        .or_default()
+                        .push((member_use.range, Some(member_use.object.clone())));
+                }
+                Arc::new(index)
+            })
+            .clone()
+    }
+
+    /// Drop the cached reference index of a file after it changes, and the
+    /// interface index, which it may have entered or left
+    fn invalidate_reference_index(&self, uri: &Url) {
+        self.reference_indices.lock().unwrap().remove(uri);
+        *self.interface_index.lock().unwrap() = None;
+    }
+
+    /// Find the implementations of an interface in the loaded classes: the
+    /// `Implements` statements, or with `member`, the `Interface_Member` procedures
+    pub fn find_implementations(&self, interface: &str, member: Option<&str>) -> Vec<Location> {
+        let index = self.interface_index();
+        let interface = unqualified(interface);
+        let Some(paths) = index.get(&interface.to_lowercase()) else {
+            return Vec::new();
+        };
+
+        let mut locations = Vec::new();
+        for table in paths.iter().filter_map(|path| self.get_symbol_table(path)) {
+            let range = match member {
+                Some(member) => {
+                    let procedure = format!("{}_{}", interface, member);
+                    table
+                        .lookup_symbol(&procedure, table.module_scope)
+                        .filter(|symbol| symbol.kind.is_procedure())
+                        .map(|symbol| symbol.name_range)
+                }
+                None => table
+                    .interfaces()
+                    .iter()
+                    .find(|i| unqualified(&i.name).eq_ignore_ascii_case(interface))
+                    .map(|i| i.range),
+            };
+            if let Some(range) = range {
+                locations.push(Location {
+                    uri: table.uri.clone(),
+                    range: range.to_lsp(),
+                });
+            }
+        }
+        locations
+    }
+
+    /// Report a form that is never shown, loaded, or referenced, or a class that
+    /// is never instantiated or referenced, by any other file of the workspace.
+    ///
+    /// A use is any mention of the name in another file: `frmAbout.Show`,
+    /// `Load frmAbout`, `As Invoice`, `New Invoice`, or a call on a predeclared
+    /// instance. The startup form is used by VB6 itself, members of a global
+    /// class are called unqualified, and the exposed classes of a library are
+    /// used by other projects, so none of those are reported. Nothing is
+    /// reported until every member of the project is loaded.
+    pub fn find_unused_module(&self, file_path: &Path) -> Option<Diagnostic> {
+        let project = self.project_for_file(file_path)?;
+        let normalized = normalize_path(file_path);
+        let is_file = |member: &&ProjectMember| normalize_path(&member.absolute_path) == normalized;
+
+        let (member, is_form) = match project.vbp.forms.iter().find(is_file) {
+            Some(form) => (form, true),
+            None => (project.vbp.classes.iter().find(is_file)?, false),
+        };
+        if !project.source_files().all(|member| is_member_loaded(project, member)) {
+            return None;
+        }
+
+        if is_form {
+            if matches!(project.startup_object(), StartupObject::Form(name)
+                if name.eq_ignore_ascii_case(&member.name))
+            {
+                return None;
+            }
+        } else if let Some(attributes) = project.get_class_attributes(&member.name) {
+            let is_library = project.vbp.project_type != ProjectType::Exe;
+            if attributes.global_namespace
+                || (is_library && (attributes.exposed || attributes.creatable))
+            {
+                return None;
+            }
+        }
+
+        let key = member.name.to_lowercase();
+        let uri = Url::from_file_path(&normalized).ok();
+        let used = self
+            .symbol_tables()
+            .filter(|table| Some(&table.uri) != uri.as_ref())
+            .any(|table| {
+                self.reference_index(table)
+                    .get(&key)
+                    .is_some_and(|uses| uses.iter().any(|(_, object)| object.is_none()))
+                    || table
+                        .type_references()
+                        .iter()
+                        .any(|reference| unqualified(&reference.name).eq_ignore_ascii_case(&key))
+            });
+        if used {
+            return None;
+        }
+
+        let message = if is_form {
+            format!("Form '{}' is never shown, loaded, or referenced", member.name)
+        } else {
+            format!("Class '{}' is never instantiated or referenced", member.name)
+        };
+        Some(Diagnostic {
+            range: Range::new(Position::new(0, 0), Position::new(1, 0)),
+            severity: Some(DiagnosticSeverity::INFORMATION),
+            code: Some(NumberOrString::String(UNUSED_MODULE.to_string())),
+            message,
+            source: Some("vb6-lsp".to_string()),
+            ..Default::default()
+        })
+    }
+
+    /// Report the Public names of a standard module that another standard
+    /// module of the project also declares, since unqualified uses of them are
+    /// ambiguous. `table` is the file's current symbol table; each report
+    /// points at the other declarations.
+    pub fn find_duplicate_public_names(
+        &self,
+        table: &SymbolTable,
+        file_path: &Path,
+    ) -> Vec<Diagnostic> {
+        let Some(project) = self.project_for_file(file_path) else {
+            return Vec::new();
+        };
+        let normalized = normalize_path(file_path);
+        let is_file = |member: &ProjectMember| normalize_path(&member.absolute_path) == normalized;
+        if !project.vbp.modules.iter().any(is_file) {
+            return Vec::new();
+        }
+
+        let others: Vec<&SymbolTable> = project
+            .vbp
+            .modules
+            .iter()
+            .filter(|member| !is_file(member))
+            .filter_map(|member| project.get_symbol_table(&normalize_path(&member.absolute_path)))
+            .collect();
+
+        let mut diagnostics = Vec::new();
+        for symbol in public_module_symbols(table) {
+            let related: Vec<DiagnosticRelatedInformation> = others
+                .iter()
+                .flat_map(|other| {
+                    public_module_symbols(other)
+                        .filter(|s| s.name.eq_ignore_ascii_case(&symbol.name))
+                        .map(|s| DiagnosticRelatedInformation {
+                            location: Location::new(other.uri.clone(), s.name_range.to_lsp()),
+                            message: format!("'{}' is also declared here", s.name),
+                        })
+                })
+                .collect();
+            if related.is_empty() {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                range: symbol.name_range.to_lsp(),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(DUPLICATE_PUBLIC_NAME.to_string())),
+                message: format!(
+                    "Public '{}' is also declared in another module; unqualified uses of it \
+                     are ambiguous",
+                    symbol.name
+                ),
+                source: Some("vb6-lsp".to_string()),
+                related_information: Some(related),
+                ..Default::default()
+            });
+        }
+        diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+        diagnostics
+    }
+
+    /// Get the interface index, building it if it isn't cached
+    fn interface_index(&self) -> Arc<InterfaceIndex> {
+        let mut cached = self.interface_index.lock().unwrap();
+        cached
+            .get_or_insert_with(|| {
+                let mut index = InterfaceIndex::new();
+                for table in self.symbol_tables() {
+                    let Ok(path) = table.uri.to_file_path() else {
+                        continue;
+                    };
+                    for interface in table.interfaces() {
+                        let key = unqualified(&interface.name).to_lowercase();
+                        index.entry(key).or_default().push(path.clone());
+                    }
+                }
+                Arc::new(index)
+            })
+            .clone()
+    }
+
+    /// Store the instancing attributes of a class in its project
+    pub fn set_class_attributes(&mut self, file_path: &Path, attributes: ClassAttributes) {
+        if let Some(project) = self.project_for_file_mut(file_path) {
+            project.set_class_attributes(attributes);
+        }
+    }
+
+    /// Get the instancing attributes of a loaded class, preferring the current file's project
+    pub fn class_attributes(&self, name: &str, from_file: &Path) -> Option<&ClassAttributes> {
+        self.project_for_file(from_file)
+            .into_iter()
+            .chain(self.projects.values())
+            .find_map(|project| project.get_class_attributes(name))
+    }
+
+    /// Check if a name can be used as a value from a file without declaring it there.
+    ///
+    /// Like `resolve_symbol`, except that a class without `VB_PredeclaredId = True`
+    /// has no default instance to refer to.
+    pub fn is_global_name(&self, name: &str, from_file: &Path) -> bool {
+        let predeclared = self
+            .project_for_file(from_file)
+            .into_iter()
+            .chain(self.projects.values())
+            .find_map(|project| project.is_predeclared_object(name));
+        if predeclared == Some(false) {
+            return false;
+        }
+
+        self.resolve_symbol(name, from_file).is_some()
+    }
+
+    /// Get the forms and predeclared classes usable as objects from a file's project
+    pub fn predeclared_objects(&self, from_file: &Path) -> Vec<String> {
+        self.project_for_file(from_file)
+            .map(|project| project.predeclared_objects().into_iter().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get all loaded symbol tables (project files and orphans)
+    pub fn symbol_tables(&self) -> impl Iterator<Item = &SymbolTable> {
+        self.projects
+            .values()
+            .flat_map(|project| project.symbol_tables())
+            .chain(self.orphan_files.values())
+    }
+
+    /// Find the procedure declared or referenced at a position in a loaded file
+    pub fn procedure_at(&self, uri: &Url, position: Position) -> Option<(&SymbolTable, &Symbol)> {
+        let table = self.get_symbol_table(&uri.to_file_path().ok()?)?;
+        let symbol = table.symbol_at_position(SourcePosition::from_lsp(position))?;
+        symbol.kind.is_procedure().then_some((table, symbol))
+    }
+
+    /// Resolve a call site to the procedure it calls, in this file or another
+    fn resolve_call<'a>(
+        &'a self,
+        table: &'a SymbolTable,
+        site: &CallSite,
+    ) -> Option<(&'a SymbolTable, &'a Symbol)> {
+        if let Some(callee) = site.callee {
+            return table.get_symbol(callee).map(|symbol| (table, symbol));
+        }
+
+        let from_file = table.uri.to_file_path().ok()?;
+        let location = self.resolve_symbol(&site.callee_name, &from_file)?;
+        self.procedure_at(&location.uri, location.range.start)
+    }
+
+    /// Find the procedures that call the given one, across all loaded files
+    pub fn incoming_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+        let Some((target_table, target)) = self.procedure_at(&item.uri, item.selection_range.start) else {
+            return Vec::new();
+        };
+
+        let mut calls = Vec::new();
+        for table in self.symbol_tables() {
+            let mut callers: Vec<(SymbolId, Vec<Range>)> = Vec::new();
+
+            for site in find_call_sites(table) {
+                if !site.callee_name.eq_ignore_ascii_case(&target.name) {
+                    continue;
+                }
+                let Some((callee_table, callee)) = self.resolve_call(table, &site) else {
+                    continue;
+                };
+                if callee_table.uri != target_table.uri || callee.id != target.id {
+                    continue;
+                }
+
+                match callers.iter_mut().find(|(caller, _)| *caller == site.caller) {
+                    Some((_, ranges)) => ranges.push(site.range.to_lsp()),
+                    None => callers.push((site.caller, vec![site.range.to_lsp()])),
+                }
+            }
+
+            for (caller, from_ranges) in callers {
+                if let Some(symbol) = table.get_symbol(caller) {
+                    calls.push(CallHierarchyIncomingCall {
+                        from: call_hierarchy_item(table, symbol),
+                        from_ranges,
+                    });
+                }
+            }
+        }
+
+        calls
+    }
+
+    /// Find the procedures called by the given one
+    pub fn outgoing_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+        let Some((table, target)) = self.procedure_at(&item.uri, item.selection_range.start) else {
+            return Vec::new();
+        };
+
+        let mut callees: Vec<(&SymbolTable, &Symbol, Vec<Range>)> = Vec::new();
+        for site in find_call_sites(table) {
+            if site.caller != target.id {
+                continue;
+            }
+            let Some((callee_table, callee)) = self.resolve_call(table, &site) else {
+                continue;
+            };
+
+            match callees
+                .iter_mut()
+                .find(|(t, s, _)| t.uri == callee_table.uri && s.id == callee.id)
+            {
+                Some((_, _, ranges)) => ranges.push(site.range.to_lsp()),
+                None => callees.push((callee_table, callee, vec![site.range.to_lsp()])),
+            }
+        }
+
+        callees
+            .into_iter()
+            .map(|(callee_table, callee, from_ranges)| CallHierarchyOutgoingCall {
+                to: call_hierarchy_item(callee_table, callee),
+                from_ranges,
+            })
+            .collect()
+    }
+
     /// Find all public symbols matching a prefix (for workspace-wide completion)
     pub fn find_symbols_with_prefix(&self, prefix: &str) -> Vec<(String, PathBuf, SymbolKind)> {
         let mut results = Vec::new();
@@ -254,6 +903,32 @@ impl WorkspaceManager {
         results
     }
 
+    /// Find symbols matching a query across the workspace (for workspace symbol search)
+    /// Matching is case-insensitive and fuzzy, so "frm" finds "frmMain" and "fMn" too
+    pub fn find_symbols_matching(&self, query: &str) -> Vec<SymbolMatch> {
+        let mut results = Vec::new();
+
+        for project in self.projects.values() {
+            results.extend(project.find_symbols_matching(query));
+        }
+
+        for (file_path, table) in &self.orphan_files {
+            let container = file_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string());
+            collect_matching_symbols(table, container.as_deref(), query, &mut results);
+        }
+
+        // Best matches first: prefix matches, then shorter names
+        let query_lower = query.to_lowercase();
+        results.sort_by_cached_key(|m| {
+            let name_lower = m.name.to_lowercase();
+            (!name_lower.starts_with(&query_lower), name_lower.len(), name_lower)
+        });
+
+        results
+    }
+
     /// Get all projects
     pub fn projects(&self) -> impl Iterator<Item = &Vb6Project> {
         self.projects.values()
@@ -266,21 +941,35 @@ impl WorkspaceManager {
 
     /// Get workspace statistics
     pub fn stats(&self) -> WorkspaceStats {
-        let mut total_files = 0;
-        let mut loaded_tables = 0;
+        let mut projects: Vec<(String, ProjectStats)> = self
+            .projects
+            .values()
+            .map(|project| (project.name().to_string(), project.stats()))
+            .collect();
+        projects.sort_by_key(|(name, _)| name.to_lowercase());
 
-        for project in self.projects.values() {
-            let stats = project.stats();
-            total_files += stats.module_count + stats.class_count + stats.form_count + stats.user_control_count;
-            loaded_tables += stats.loaded_symbol_tables;
-        }
+        let sum = |count: fn(&ProjectStats) -> usize| -> usize {
+            projects.iter().map(|(_, stats)| count(stats)).sum()
+        };
+        let orphans = self.orphan_files.values();
 
         WorkspaceStats {
             root_count: self.roots.len(),
             project_count: self.projects.len(),
-            total_source_files: total_files,
-            loaded_symbol_tables: loaded_tables,
+            total_source_files: sum(ProjectStats::source_file_count),
+            loaded_symbol_tables: sum(|s| s.loaded_symbol_tables),
             orphan_files: self.orphan_files.len(),
+            module_count: sum(|s| s.module_count),
+            class_count: sum(|s| s.class_count),
+            form_count: sum(|s| s.form_count),
+            user_control_count: sum(|s| s.user_control_count),
+            symbol_count: sum(|s| s.symbol_count)
+                + orphans.clone().map(|t| t.symbol_count()).sum::<usize>(),
+            line_count: sum(|s| s.line_count)
+                + orphans.clone().map(|t| t.line_count()).sum::<usize>(),
+            control_count: sum(|s| s.control_count)
+                + orphans.map(|t| t.control_count()).sum::<usize>(),
+            projects,
         }
     }
 }
@@ -299,9 +988,123 @@ pub struct WorkspaceStats {
     pub total_source_files: usize,
     pub loaded_symbol_tables: usize,
     pub orphan_files: usize,
+    pub module_count: usize,
+    pub class_count: usize,
+    pub form_count: usize,
+    pub user_control_count: usize,
+    /// Symbols, lines, and form controls of the loaded files, orphans included
+    pub symbol_count: usize,
+    pub line_count: usize,
+    pub control_count: usize,
+    /// Statistics of each project, by project name
+    pub projects: Vec<(String, ProjectStats)>,
+}
+
+impl WorkspaceStats {
+    /// A plain-text summary of the workspace's size
+    pub fn report(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "{} in {}",
+                plural(self.project_count, "project"),
+                plural(self.root_count, "folder")
+            ),
+            format!(
+                "Source files: {} ({}, {}, {}, {})",
+                self.total_source_files,
+                plural(self.module_count, "module"),
+                plural(self.class_count, "class"),
+                plural(self.form_count, "form"),
+                plural(self.user_control_count, "user control")
+            ),
+            format!("Lines: {}", self.line_count),
+            format!("Symbols: {}", self.symbol_count),
+            format!("Form controls: {}", self.control_count),
+            format!("Files outside any project: {}", self.orphan_files),
+        ];
+        for (name, stats) in &self.projects {
+            lines.push(format!(
+                "{}: {}, {} lines, {}",
+                name,
+                plural(stats.source_file_count(), "file"),
+                stats.line_count,
+                plural(stats.control_count, "control")
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// "1 project", "2 projects", "3 classes"
+fn plural(count: usize, noun: &str) -> String {
+    match (count, noun.ends_with('s')) {
+        (1, _) => format!("1 {}", noun),
+        (_, true) => format!("{} {}es", count, noun),
+        (_, false) => format!("{} {}s", count, noun),
+    }
+}
+
+/// A symbol found by a workspace symbol query
+#[derive(Debug, Clone)]
+pub struct SymbolMatch {
+    pub name: String,
+    pub kind: LspSymbolKind,
+    pub location: Location,
+    /// Name of the containing module, class, or form
+    pub container_name: Option<String>,
+}
+
+/// Collect module-level symbols from a table that match a query
+fn collect_matching_symbols(
+    table: &SymbolTable,
+    container: Option<&str>,
+    query: &str,
+    results: &mut Vec<SymbolMatch>,
+) {
+    for symbol in table.module_symbols() {
+        if fuzzy_match(&symbol.name, query) {
+            results.push(SymbolMatch {
+                name: symbol.name.clone(),
+                kind: symbol.kind.to_lsp(),
+                location: Location {
+                    uri: table.uri.clone(),
+                    range: symbol.name_range.to_lsp(),
+                },
+                container_name: container.map(|c| c.to_string()),
+            });
+        }
+    }
+}
+
+/// Case-insensitive fuzzy match: all query characters appear in order in the name
+fn fuzzy_match(name: &str, query: &str) -> bool {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| name_chars.any(|n| n == q))
+}
+
+/// A type name without its library or project qualifier (`Lib.IShape` -> `IShape`)
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
 }
 
 /// Normalize a path for comparison (lowercase on Windows)
+/// Whether a member has a symbol table, or can't have one because its file is missing
+/// The Public names a standard module declares; a property's accessors count once
+fn public_module_symbols(table: &SymbolTable) -> impl Iterator<Item = &Symbol> {
+    table.module_symbols().into_iter().filter(|symbol| {
+        matches!(symbol.visibility, Visibility::Public | Visibility::Global)
+            && !matches!(symbol.kind, SymbolKind::TypeMember | SymbolKind::EnumMember)
+    })
+}
+
+fn is_member_loaded(project: &Vb6Project, member: &ProjectMember) -> bool {
+    project.get_symbol_table(&normalize_path(&member.absolute_path)).is_some()
+        || project.member_file(member).is_none()
+}
+
 fn normalize_path(path: &Path) -> PathBuf {
     let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
 
@@ -320,9 +1123,383 @@ fn normalize_path(path: &Path) -> PathBuf {
 mod tests {
     use super::*;
 
+    /// Parse a file and give the manager its symbol table, without touching the disk
+    fn load(manager: &mut WorkspaceManager, name: &str, source: &str) -> PathBuf {
+        let path = PathBuf::from(format!("/nonexistent/{}", name));
+        let uri = Url::from_file_path(&path).unwrap();
+        let mut parser = crate::parser::TreeSitterVb6Parser::new().unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = crate::analysis::build_symbol_table(uri, source, &tree);
+        manager.set_symbol_table(&path, table);
+        path
+    }
+
     #[test]
     fn test_workspace_manager_creation() {
         let manager = WorkspaceManager::new();
         assert_eq!(manager.projects().count(), 0);
     }
+
+    #[test]
+    fn test_apply_file_event() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_events_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let vbp = dir.join("Project1.vbp");
+        let module = dir.join("Module1.bas");
+        std::fs::write(&vbp, "Type=Exe\nModule=Module1; Module1.bas\n").unwrap();
+        std::fs::write(&module, "Public Sub DoWork()\nEnd Sub\n").unwrap();
+
+        let mut manager = WorkspaceManager::new();
+        manager.add_root(dir.clone());
+        assert!(manager.is_file_in_project(&module));
+
+        // Adding a member to the VBP reloads the project
+        let class = dir.join("Class1.cls");
+        std::fs::write(&class, "Option Explicit\n").unwrap();
+        std::fs::write(&vbp, "Type=Exe\nModule=Module1; Module1.bas\nClass=Class1; Class1.cls\n")
+            .unwrap();
+        let affected = manager.apply_file_event(&FileEvent::new(vbp.clone(), FileChange::Changed));
+        assert_eq!(affected.as_ref(), Some(&vbp));
+        assert!(manager.is_file_in_project(&class));
+
+        // Deleting a member drops its index entry
+        std::fs::remove_file(&module).unwrap();
+        manager.apply_file_event(&FileEvent::new(module.clone(), FileChange::Deleted));
+        assert!(!manager.is_file_in_project(&module));
+
+        // Deleting the VBP unloads the project
+        std::fs::remove_file(&vbp).unwrap();
+        manager.apply_file_event(&FileEvent::new(vbp.clone(), FileChange::Deleted));
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(manager.projects().count(), 0);
+        assert!(!manager.is_file_in_project(&class));
+    }
+
+    #[test]
+    fn test_scan_and_add_projects() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_scan_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let vbp = dir.join("nested").join("Project1.vbp");
+        let module = dir.join("nested").join("Module1.bas");
+        let object = "Object={248DD890-BB45-11CF-9ABC-0080C7E7B78D}#1.0#0; MSWINSCK.OCX";
+        std::fs::write(&vbp, format!("Type=Exe\nModule=Module1; Module1.bas\n{}\n", object))
+            .unwrap();
+        std::fs::write(&module, "Public Sub DoWork()\nEnd Sub\n").unwrap();
+
+        let mut found = Vec::new();
+        let discovered =
+            WorkspaceManager::scan_for_vbp_files(&dir, |path| found.push(path.to_path_buf()));
+        assert_eq!(discovered, vec![vbp.clone()]);
+        assert_eq!(found, discovered);
+
+        let mut manager = WorkspaceManager::new();
+        manager.register_root(dir.clone());
+        manager.register_root(dir.clone());
+        manager.add_project(&vbp, Vb6Project::from_vbp(&vbp).unwrap());
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(manager.roots(), &[dir]);
+        assert!(manager.is_file_in_project(&module));
+        // The OCXs the project references are registered as controls
+        assert!(crate::controls::get_method("Winsock", "SendData").is_some());
+    }
+
+    #[test]
+    fn test_stats_report() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_stats_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let vbp = dir.join("Billing.vbp");
+        std::fs::write(&vbp, "Type=Exe\nModule=Module1; Module1.bas\nClass=Invoice; Invoice.cls\n")
+            .unwrap();
+        std::fs::write(dir.join("Module1.bas"), "Public Sub DoWork()\nEnd Sub\n").unwrap();
+        std::fs::write(dir.join("Invoice.cls"), "Public Total As Currency\n").unwrap();
+
+        let mut manager = WorkspaceManager::new();
+        manager.register_root(dir.clone());
+        manager.add_project(&vbp, Vb6Project::from_vbp(&vbp).unwrap());
+        manager.load_all_members(&mut Vb6Parser::new());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let stats = manager.stats();
+        assert_eq!(stats.total_source_files, 2);
+        assert_eq!((stats.module_count, stats.class_count), (1, 1));
+        assert_eq!(stats.line_count, 3);
+        assert_eq!(stats.projects.len(), 1);
+
+        let report = stats.report();
+        assert!(report.starts_with("1 project in 1 folder\n"));
+        assert!(report.contains("Source files: 2 (1 module, 1 class, 0 forms, 0 user controls)"));
+        assert!(report.contains("Lines: 3"));
+        assert!(report.contains("Billing: 2 files, 3 lines, 0 controls"));
+    }
+
+    #[test]
+    fn test_find_unused_module() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_unused_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let files = [
+            ("frmMain.frm", "Private Sub Form_Load()\n    frmUsed.Show\nEnd Sub\n"),
+            ("frmUsed.frm", "Private Sub Form_Load()\nEnd Sub\n"),
+            ("frmAbout.frm", "Private Sub Form_Load()\n    frmAbout.Caption = \"Hi\"\nEnd Sub\n"),
+            ("Module1.bas", "Public Sub Run()\n    Dim i As Invoice\nEnd Sub\n"),
+            ("Invoice.cls", "Public Total As Currency\n"),
+            ("Unused.cls", "Public Sub DoWork()\nEnd Sub\n"),
+            (
+                "Helpers.cls",
+                "Attribute VB_Name = \"Helpers\"\nAttribute VB_GlobalNameSpace = True\n",
+            ),
+        ];
+        for (name, source) in files {
+            std::fs::write(dir.join(name), source).unwrap();
+        }
+        let vbp = dir.join("Project1.vbp");
+        std::fs::write(
+            &vbp,
+            "Type=Exe\nForm=frmMain.frm\nForm=frmUsed.frm\nForm=frmAbout.frm\n\
+             Module=Module1; Module1.bas\nClass=Invoice; Invoice.cls\nClass=Unused; Unused.cls\n\
+             Class=Helpers; Helpers.cls\nStartup=\"frmMain\"\n",
+        )
+        .unwrap();
+
+        let mut manager = WorkspaceManager::new();
+        manager.add_project(&vbp, Vb6Project::from_vbp(&vbp).unwrap());
+        // Nothing is known to be unused until every member is loaded
+        assert!(manager.find_unused_module(&dir.join("Unused.cls")).is_none());
+
+        manager.load_all_members(&mut Vb6Parser::new());
+        std::fs::remove_dir_all(&dir).ok();
+        let unused: Vec<String> = files
+            .iter()
+            .filter_map(|(name, _)| manager.find_unused_module(&dir.join(name)))
+            .map(|diagnostic| diagnostic.message)
+            .collect();
+        assert_eq!(
+            unused,
+            vec![
+                "Form 'frmAbout' is never shown, loaded, or referenced",
+                "Class 'Unused' is never instantiated or referenced",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate_public_names() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_duplicates_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let files = [
+            ("ModA.bas", "Public Sub Log()\nEnd Sub\nPublic Const LIMIT = 1\nDim mCount As Long\n"),
+            ("ModB.bas", "Sub log()\nEnd Sub\nConst LIMIT = 2\nPublic mCount As Long\n"),
+            ("Logger.cls", "Public Sub Log()\nEnd Sub\n"),
+        ];
+        for (name, source) in files {
+            std::fs::write(dir.join(name), source).unwrap();
+        }
+        let vbp = dir.join("Project1.vbp");
+        std::fs::write(
+            &vbp,
+            "Type=Exe\nModule=ModA; ModA.bas\nModule=ModB; ModB.bas\nClass=Logger; Logger.cls\n",
+        )
+        .unwrap();
+
+        let mut manager = WorkspaceManager::new();
+        manager.add_project(&vbp, Vb6Project::from_vbp(&vbp).unwrap());
+        manager.load_all_members(&mut Vb6Parser::new());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let duplicates = |name: &str| {
+            let path = dir.join(name);
+            let table = manager.get_symbol_table(&path).unwrap();
+            manager.find_duplicate_public_names(table, &path)
+        };
+
+        // Both modules report the Sub, each pointing at the other
+        let a = duplicates("ModA.bas");
+        assert_eq!(a.len(), 1);
+        assert_eq!(a[0].range.start.line, 0);
+        let related = a[0].related_information.as_ref().unwrap();
+        assert_eq!(related.len(), 1);
+        assert!(related[0].location.uri.path().ends_with("ModB.bas"));
+        assert_eq!(duplicates("ModB.bas").len(), 1);
+
+        // A class's members are not global names
+        assert!(duplicates("Logger.cls").is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("frmMain", "frm"));
+        assert!(fuzzy_match("frmMain", "FRMMAIN"));
+        assert!(fuzzy_match("frmMain", "main"));
+        assert!(fuzzy_match("frmMain", "fmn"));
+        assert!(fuzzy_match("frmMain", ""));
+        assert!(!fuzzy_match("frmMain", "mainf"));
+    }
+
+    #[test]
+    fn test_find_symbols_matching_orphan() {
+        let source = "Public Sub DoWork()\nEnd Sub\n\nPrivate Function Helper() As Long\nEnd Function\n";
+        let mut manager = WorkspaceManager::new();
+        let path = load(&mut manager, "modUtils.bas", source);
+        let uri = Url::from_file_path(&path).unwrap();
+
+        let matches = manager.find_symbols_matching("work");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].name, "DoWork");
+        assert_eq!(matches[0].location.uri, uri);
+        assert_eq!(matches[0].container_name.as_deref(), Some("modUtils"));
+
+        assert_eq!(manager.find_symbols_matching("").len(), 2);
+    }
+
+    #[test]
+    fn test_call_hierarchy_across_files() {
+        let mut manager = WorkspaceManager::new();
+        let utils = load(
+            &mut manager,
+            "modUtils.bas",
+            "Public Sub Log(ByVal msg As String)\nEnd Sub\n\nPublic Function Twice(ByVal n As Long) As Long\n    Log \"twice\"\n    Twice = n * 2\nEnd Function\n",
+        );
+        load(
+            &mut manager,
+            "modMain.bas",
+            "Sub Main()\n    Call LOG(\"start\")\n    x = Twice(2)\n    log \"done\"\nEnd Sub\n",
+        );
+
+        let table = manager.get_symbol_table(&utils).unwrap();
+        let log = table.procedures().find(|s| s.name == "Log").unwrap();
+        let twice = table.procedures().find(|s| s.name == "Twice").unwrap();
+        let log_item = call_hierarchy_item(table, log);
+        let twice_item = call_hierarchy_item(table, twice);
+
+        let mut incoming: Vec<(String, usize)> = manager
+            .incoming_calls(&log_item)
+            .into_iter()
+            .map(|call| (call.from.name, call.from_ranges.len()))
+            .collect();
+        incoming.sort();
+        assert_eq!(incoming, vec![("Main".to_string(), 2), ("Twice".to_string(), 1)]);
+
+        let outgoing: Vec<String> = manager
+            .outgoing_calls(&twice_item)
+            .into_iter()
+            .map(|call| call.to.name)
+            .collect();
+        assert_eq!(outgoing, vec!["Log"]);
+    }
+
+    #[test]
+    fn test_find_implementations() {
+        let mut manager = WorkspaceManager::new();
+        load(&mut manager, "IShape.cls", "Public Sub Draw()\nEnd Sub\n");
+        let circle = load(
+            &mut manager,
+            "Circle.cls",
+            "Implements IShape\n\nPrivate Sub IShape_Draw()\nEnd Sub\n",
+        );
+        let circle = Url::from_file_path(circle).unwrap();
+        let square = load(&mut manager, "Square.cls", "Implements Shapes.ishape\n");
+        let square = Url::from_file_path(square).unwrap();
+        load(&mut manager, "Other.cls", "Private Sub IShape_Draw()\nEnd Sub\n");
+
+        let mut classes: Vec<(String, u32)> = manager
+            .find_implementations("IShape", None)
+            .into_iter()
+            .map(|location| (location.uri.path().to_string(), location.range.start.character))
+            .collect();
+        classes.sort();
+        assert_eq!(
+            classes,
+            vec![(circle.path().to_string(), 11), (square.path().to_string(), 11)]
+        );
+
+        let methods = manager.find_implementations("IShape", Some("Draw"));
+        assert_eq!(methods.len(), 1);
+        assert_eq!((methods[0].uri.clone(), methods[0].range.start.line), (circle, 2));
+
+        // Tables replaced later are indexed again
+        load(&mut manager, "Square.cls", "Option Explicit\n");
+        assert_eq!(manager.find_implementations("IShape", None).len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_qualified_symbol() {
+        let mut parser = crate::parser::TreeSitterVb6Parser::new().unwrap();
+        let source = "Public Sub DoThing()\nEnd Sub\n\nPrivate Sub Hidden()\nEnd Sub\n";
+        let path = PathBuf::from("/nonexistent/modUtils.bas");
+        let uri = Url::from_file_path(&path).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        let table = crate::analysis::build_symbol_table(uri.clone(), source, &tree);
+
+        let mut manager = WorkspaceManager::new();
+        manager.set_symbol_table(&path, table);
+        let from = Path::new("/nonexistent/modMain.bas");
+
+        let location = manager.resolve_qualified_symbol("MODUTILS", "dothing", from).unwrap();
+        assert_eq!(location.uri, uri);
+        assert_eq!(location.range.start.line, 0);
+
+        assert!(manager.resolve_qualified_symbol("modUtils", "Hidden", from).is_none());
+        assert!(manager.resolve_qualified_symbol("modOther", "DoThing", from).is_none());
+    }
+
+    #[test]
+    fn test_resolve_declares_across_files() {
+        let mut manager = WorkspaceManager::new();
+        load(
+            &mut manager,
+            "modApi.bas",
+            "Declare Function GetTickCount Lib \"kernel32\" () As Long\n\
+Private Declare Sub Sleep Lib \"kernel32\" (ByVal ms As Long)\n",
+        );
+        load(
+            &mut manager,
+            "clsTimer.cls",
+            "Declare Function timeGetTime Lib \"winmm\" () As Long\n",
+        );
+        let from = Path::new("/nonexistent/modMain.bas");
+
+        assert!(manager.resolve_symbol("GetTickCount", from).is_some());
+        assert!(manager.resolve_symbol("Sleep", from).is_none());
+        assert!(manager.resolve_symbol("timeGetTime", from).is_none());
+    }
+
+    #[test]
+    fn test_find_all_references_across_files() {
+        let mut manager = WorkspaceManager::new();
+        let utils = load(
+            &mut manager,
+            "modUtils.bas",
+            "Public Sub DoThing()\nEnd Sub\n\nPrivate Sub Hidden()\n    DoThing\nEnd Sub\n",
+        );
+        load(
+            &mut manager,
+            "modMain.bas",
+            "Sub Main()\n    DoThing\n    Hidden\n    modUtils.DoThing\n    obj.DoThing\nEnd Sub\n",
+        );
+        load(&mut manager, "modOther.bas", "Sub Other()\n    Dim DoThing As Long\n    DoThing = 1\nEnd Sub\n");
+
+        let references: Vec<(String, u32)> = manager
+            .find_all_references("DoThing", SymbolKind::Sub, &utils)
+            .iter()
+            .map(|l| (l.uri.path().rsplit('/').next().unwrap().to_string(), l.range.start.line))
+            .collect();
+        assert_eq!(
+            references,
+            vec![
+                ("modUtils.bas".to_string(), 0),
+                ("modUtils.bas".to_string(), 4),
+                ("modMain.bas".to_string(), 1),
+                ("modMain.bas".to_string(), 3),
+            ]
+        );
+        assert!(manager.find_all_references("Hidden", SymbolKind::Sub, &utils).is_empty());
+
+        // Edits replace the file's cached index
+        load(&mut manager, "modMain.bas", "Sub Main()\nEnd Sub\n");
+        assert_eq!(manager.find_all_references("DoThing", SymbolKind::Sub, &utils).len(), 2);
+    }
 }
@@ -3,13 +3,29 @@
 //! Represents a VB6 project with its parsed VBP file and symbol tables.
 
 use std::collections::HashMap;
+use std::io;
 use std::path::{Path, PathBuf};
 
-use tower_lsp::lsp_types::{Location, Url};
+use tower_lsp::lsp_types::{DiagnosticSeverity, Location, SymbolKind as LspSymbolKind, Url};
 
-use crate::analysis::{SymbolKind, SymbolTable, Visibility};
+use crate::analysis::{build_symbol_table_excluding, SymbolKind, SymbolTable, Visibility};
 
-use super::vbp_parser::{ProjectMember, VbpFile, VbpParseError};
+use crate::parser::{ClassAttributes, CompilationConstants, Vb6Ast, Vb6FileKind, Vb6Parser};
+use crate::utils::VB6FileReader;
+
+use super::vbp_parser::{
+    ProjectMember, StartupObject, TypeLibReference, VbpFile, VbpParseError, VersionInfo,
+};
+use super::{collect_matching_symbols, fuzzy_match, normalize_path, SymbolMatch};
+
+/// A problem with an entry of a VBP file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectDiagnostic {
+    /// Line of the entry in the VBP file (0-indexed)
+    pub line: usize,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
 
 /// A VB6 project loaded from a .vbp file
 #[derive(Debug)]
@@ -20,9 +36,15 @@ pub struct Vb6Project {
     /// Symbol tables for each file (keyed by absolute path)
     symbol_tables: HashMap<PathBuf, SymbolTable>,
 
+    /// ASTs of members parsed from disk, keyed like the symbol tables
+    asts: HashMap<PathBuf, Vb6Ast>,
+
     /// Index of public symbols: lowercase name -> (file_path, symbol_name)
     /// This is rebuilt when symbol tables change
     public_symbol_index: HashMap<String, Vec<(PathBuf, String)>>,
+
+    /// Instancing attributes of loaded classes, keyed by lowercase class name
+    class_attributes: HashMap<String, ClassAttributes>,
 }
 
 impl Vb6Project {
@@ -37,7 +59,9 @@ impl Vb6Project {
         Self {
             vbp,
             symbol_tables: HashMap::new(),
+            asts: HashMap::new(),
             public_symbol_index: HashMap::new(),
+            class_attributes: HashMap::new(),
         }
     }
 
@@ -46,6 +70,21 @@ impl Vb6Project {
         &self.vbp.name
     }
 
+    /// Get the startup object (form or Sub Main)
+    pub fn startup_object(&self) -> StartupObject {
+        self.vbp.startup_object()
+    }
+
+    /// Get the project's conditional compilation constants
+    pub fn conditional_constants(&self) -> CompilationConstants {
+        self.vbp.conditional_constants()
+    }
+
+    /// Get the project version
+    pub fn version(&self) -> &VersionInfo {
+        self.vbp.version()
+    }
+
     /// Get the VBP file path
     pub fn vbp_path(&self) -> &Path {
         &self.vbp.path
@@ -76,8 +115,152 @@ impl Vb6Project {
         self.vbp.all_source_files()
     }
 
+    /// Locate a member's file on disk, tolerating `\` separators and case differences
+    pub fn member_file(&self, member: &ProjectMember) -> Option<PathBuf> {
+        resolve_path(&member.absolute_path)
+    }
+
+    /// Get the type library and project references
+    pub fn references(&self) -> &[TypeLibReference] {
+        &self.vbp.references
+    }
+
+    /// Locate a referenced project or type library on disk
+    pub fn reference_file(&self, reference: &TypeLibReference) -> Option<PathBuf> {
+        match reference {
+            TypeLibReference::SubProject { path, .. } => resolve_path(path),
+            TypeLibReference::Compiled { path, .. } => resolve_path(path.as_ref()?),
+        }
+    }
+
+    /// Read a member's source from disk, decoding it as VB6 saved it
+    pub fn load_member_source(&self, file_path: &Path) -> io::Result<String> {
+        let member = self.get_member(file_path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not a member of project '{}'", file_path.display(), self.name()),
+            )
+        })?;
+        let file = self.member_file(member).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("File not found: {}", member.absolute_path.display()),
+            )
+        })?;
+        VB6FileReader::read_to_string(&file)
+    }
+
+    /// Re-read and parse a member from disk, replacing its symbol table.
+    ///
+    /// The parser is switched to the project's conditional compilation
+    /// constants. Returns the member's AST, which stays cached until its
+    /// symbol table is replaced or removed.
+    pub fn reparse_member(
+        &mut self,
+        file_path: &Path,
+        parser: &mut Vb6Parser,
+    ) -> io::Result<&Vb6Ast> {
+        let source = self.load_member_source(file_path)?;
+        let uri = Url::from_file_path(file_path).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid file path: {}", file_path.display()),
+            )
+        })?;
+
+        parser.set_conditional_compilation(self.conditional_constants());
+        let mut ast = parser.parse_edited(&source, None).map_err(|errors| {
+            let message = errors.into_iter().next().map(|e| e.message).unwrap_or_default();
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        })?;
+        ast.file_type = Vb6FileKind::from_path(&file_path.to_string_lossy())
+            .unwrap_or(Vb6FileKind::Module);
+        let tree = parser.get_tree().expect("a successful parse stores its tree");
+        let table = build_symbol_table_excluding(uri, &source, tree, parser.get_inactive_regions());
+
+        if ast.file_type == Vb6FileKind::Class {
+            self.set_class_attributes(ast.class_attributes.clone());
+        }
+        let key = normalize_path(file_path);
+        self.set_symbol_table(key.clone(), table);
+        Ok(self.asts.entry(key).or_insert(ast))
+    }
+
+    /// Get the cached AST of a member parsed with `reparse_member`
+    pub fn get_ast(&self, file_path: &Path) -> Option<&Vb6Ast> {
+        self.asts.get(&normalize_path(file_path))
+    }
+
+    /// Check that member files and references can be found
+    pub fn validate(&self) -> Vec<ProjectDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for member in self.source_files() {
+            if !path_exists(&member.absolute_path) {
+                diagnostics.push(ProjectDiagnostic {
+                    line: member.line,
+                    severity: DiagnosticSeverity::ERROR,
+                    message: format!(
+                        "File not found for '{}': {}",
+                        member.name,
+                        member.relative_path.display()
+                    ),
+                });
+            }
+        }
+
+        for reference in &self.vbp.references {
+            let missing = match reference {
+                TypeLibReference::SubProject { path, .. } if !path_exists(path) => Some((
+                    DiagnosticSeverity::ERROR,
+                    format!("Referenced project not found: {}", path.display()),
+                )),
+                // Registered type library paths are only meaningful on Windows
+                TypeLibReference::Compiled { path: Some(path), description, .. }
+                    if cfg!(windows) && !path_exists(path) =>
+                {
+                    Some((
+                        DiagnosticSeverity::WARNING,
+                        format!("Type library '{}' not found: {}", description, path.display()),
+                    ))
+                }
+                _ => None,
+            };
+            if let Some((severity, message)) = missing {
+                diagnostics.push(ProjectDiagnostic {
+                    line: reference.line(),
+                    severity,
+                    message,
+                });
+            }
+        }
+
+        for (line, value) in &self.vbp.invalid_references {
+            diagnostics.push(ProjectDiagnostic {
+                line: *line,
+                severity: DiagnosticSeverity::WARNING,
+                message: format!("Invalid reference (expected a valid GUID and path): {}", value),
+            });
+        }
+
+        for object in &self.vbp.objects {
+            if object.uuid.is_none() {
+                diagnostics.push(ProjectDiagnostic {
+                    line: object.line,
+                    severity: DiagnosticSeverity::WARNING,
+                    message: format!("Invalid GUID in control reference: {}", object.guid_string),
+                });
+            }
+        }
+
+        diagnostics.sort_by_key(|d| d.line);
+        diagnostics
+    }
+
     /// Store a symbol table for a file
     pub fn set_symbol_table(&mut self, file_path: PathBuf, table: SymbolTable) {
+        // A cached AST no longer matches the table
+        self.asts.remove(&file_path);
         self.symbol_tables.insert(file_path, table);
         self.rebuild_public_index();
     }
@@ -87,12 +270,63 @@ impl Vb6Project {
         self.symbol_tables.get(file_path)
     }
 
+    /// Get all loaded symbol tables
+    pub fn symbol_tables(&self) -> impl Iterator<Item = &SymbolTable> {
+        self.symbol_tables.values()
+    }
+
     /// Remove a symbol table (when file is closed or deleted)
     pub fn remove_symbol_table(&mut self, file_path: &Path) {
+        self.asts.remove(file_path);
         self.symbol_tables.remove(file_path);
         self.rebuild_public_index();
     }
 
+    /// Store the instancing attributes of a class, keyed by its `VB_Name`
+    pub fn set_class_attributes(&mut self, attributes: ClassAttributes) {
+        if let Some(name) = &attributes.name {
+            self.class_attributes.insert(name.to_lowercase(), attributes);
+        }
+    }
+
+    /// Get the instancing attributes of a loaded class
+    pub fn get_class_attributes(&self, name: &str) -> Option<&ClassAttributes> {
+        self.class_attributes.get(&name.to_lowercase())
+    }
+
+    /// Check if a name is an object usable without declaring it: a form, or a
+    /// class with `VB_PredeclaredId = True`.
+    ///
+    /// Returns None for other names and for classes whose attributes aren't loaded.
+    pub fn is_predeclared_object(&self, name: &str) -> Option<bool> {
+        let is_member = |members: &[ProjectMember]| {
+            members.iter().any(|member| member.name.eq_ignore_ascii_case(name))
+        };
+
+        if is_member(&self.vbp.forms) {
+            Some(true)
+        } else if is_member(&self.vbp.classes) {
+            self.get_class_attributes(name).map(|a| a.predeclared_id)
+        } else {
+            None
+        }
+    }
+
+    /// Get the names of the forms and loaded predeclared classes
+    pub fn predeclared_objects(&self) -> Vec<&str> {
+        let classes = self.vbp.classes.iter().filter(|class| {
+            self.get_class_attributes(&class.name)
+                .is_some_and(|a| a.predeclared_id)
+        });
+
+        self.vbp
+            .forms
+            .iter()
+            .chain(classes)
+            .map(|member| member.name.as_str())
+            .collect()
+    }
+
     /// Rebuild the public symbol index from all loaded symbol tables
     fn rebuild_public_index(&mut self) {
         self.public_symbol_index.clear();
@@ -166,6 +400,56 @@ impl Vb6Project {
         results
     }
 
+    /// Find project members and module-level symbols matching a query (for workspace symbol search)
+    pub fn find_symbols_matching(&self, query: &str) -> Vec<SymbolMatch> {
+        let mut results = Vec::new();
+
+        // Modules, classes, and forms themselves
+        let class_like = self
+            .vbp
+            .classes
+            .iter()
+            .chain(self.vbp.forms.iter())
+            .chain(self.vbp.user_controls.iter())
+            .chain(self.vbp.property_pages.iter())
+            .chain(self.vbp.user_documents.iter())
+            .chain(self.vbp.designers.iter());
+        let members = self
+            .vbp
+            .modules
+            .iter()
+            .map(|m| (m, LspSymbolKind::MODULE))
+            .chain(class_like.map(|m| (m, LspSymbolKind::CLASS)));
+
+        for (member, kind) in members {
+            if !fuzzy_match(&member.name, query) {
+                continue;
+            }
+            if let Ok(uri) = Url::from_file_path(&member.absolute_path) {
+                results.push(SymbolMatch {
+                    name: member.name.clone(),
+                    kind,
+                    location: Location {
+                        uri,
+                        range: tower_lsp::lsp_types::Range::default(),
+                    },
+                    container_name: Some(self.name().to_string()),
+                });
+            }
+        }
+
+        // Symbols from loaded files
+        for (file_path, table) in &self.symbol_tables {
+            let container = self
+                .get_member(file_path)
+                .map(|m| m.name.clone())
+                .or_else(|| file_path.file_stem().map(|s| s.to_string_lossy().to_string()));
+            collect_matching_symbols(table, container.as_deref(), query, &mut results);
+        }
+
+        results
+    }
+
     /// Resolve a symbol reference to a module/class in this project
     /// E.g., "ModMain" -> Location of ModMain.bas
     ///       "clsDatabase" -> Location of clsDatabase.cls
@@ -193,6 +477,9 @@ impl Vb6Project {
             user_control_count: self.vbp.user_controls.len(),
             loaded_symbol_tables: self.symbol_tables.len(),
             indexed_public_symbols: self.public_symbol_index.len(),
+            symbol_count: self.symbol_tables.values().map(|t| t.symbol_count()).sum(),
+            line_count: self.symbol_tables.values().map(|t| t.line_count()).sum(),
+            control_count: self.symbol_tables.values().map(|t| t.control_count()).sum(),
         }
     }
 }
@@ -206,6 +493,54 @@ pub struct ProjectStats {
     pub user_control_count: usize,
     pub loaded_symbol_tables: usize,
     pub indexed_public_symbols: usize,
+    /// Symbols, lines, and form controls of the loaded members
+    pub symbol_count: usize,
+    pub line_count: usize,
+    pub control_count: usize,
+}
+
+impl ProjectStats {
+    /// Number of source files listed in the VBP
+    pub fn source_file_count(&self) -> usize {
+        self.module_count + self.class_count + self.form_count + self.user_control_count
+    }
+}
+
+/// Check if a path from a VBP exists, allowing for `\` separators and
+/// names whose case differs from the file on disk
+fn path_exists(path: &Path) -> bool {
+    resolve_path(path).is_some()
+}
+
+/// Find the file on disk that a path from a VBP refers to
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+
+    let normalized = PathBuf::from(path.to_string_lossy().replace('\\', "/"));
+    let mut resolved = PathBuf::new();
+    for component in normalized.components() {
+        let candidate = resolved.join(component);
+        if candidate.exists() {
+            resolved = candidate;
+            continue;
+        }
+
+        let name = component.as_os_str().to_string_lossy().to_lowercase();
+        let dir = if resolved.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            resolved.as_path()
+        };
+        let entry = std::fs::read_dir(dir).ok().and_then(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .find(|entry| entry.file_name().to_string_lossy().to_lowercase() == name)
+        })?;
+        resolved = entry.path();
+    }
+    Some(resolved)
 }
 
 #[cfg(test)]
@@ -247,6 +582,27 @@ Form=frmMain.frm
         assert!(member.is_some());
     }
 
+    #[test]
+    fn test_predeclared_objects() {
+        let mut project = Vb6Project::from_parsed_vbp(create_test_vbp());
+
+        // Forms always have a default instance; classes only once known to be predeclared
+        assert_eq!(project.is_predeclared_object("FRMMAIN"), Some(true));
+        assert_eq!(project.is_predeclared_object("clsDatabase"), None);
+        assert_eq!(project.is_predeclared_object("ModMain"), None);
+        assert_eq!(project.predeclared_objects(), vec!["frmMain"]);
+
+        let mut attributes = ClassAttributes::default();
+        attributes.set("VB_Name", "\"clsDatabase\"");
+        project.set_class_attributes(attributes.clone());
+        assert_eq!(project.is_predeclared_object("clsDatabase"), Some(false));
+
+        attributes.set("VB_PredeclaredId", "True");
+        project.set_class_attributes(attributes);
+        assert_eq!(project.is_predeclared_object("clsDatabase"), Some(true));
+        assert_eq!(project.predeclared_objects(), vec!["frmMain", "clsDatabase"]);
+    }
+
     #[test]
     fn test_project_stats() {
         let vbp = create_test_vbp();
@@ -257,4 +613,65 @@ Form=frmMain.frm
         assert_eq!(stats.class_count, 1);
         assert_eq!(stats.form_count, 1);
     }
+
+    #[test]
+    fn test_validate_reports_missing_files() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_validate_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("Forms")).unwrap();
+        std::fs::write(dir.join("ModMain.bas"), "").unwrap();
+        std::fs::write(dir.join("Forms").join("FrmMain.frm"), "").unwrap();
+
+        let content = "Type=Exe\n\
+Module=ModMain; modmain.bas\n\
+Form=Forms\\frmMain.frm\n\
+Class=clsGone; clsGone.cls\n\
+Reference=*\\G{not-a-guid}#2.0#0#stdole2.tlb#OLE Automation\n\
+Object={BAD}#2.0#0; MSCOMCTL.OCX\n";
+        let vbp = VbpFile::parse_content(&dir.join("Test.vbp"), content).unwrap();
+        let diagnostics = Vb6Project::from_parsed_vbp(vbp).validate();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let lines: Vec<(usize, DiagnosticSeverity)> =
+            diagnostics.iter().map(|d| (d.line, d.severity)).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (3, DiagnosticSeverity::ERROR),
+                (4, DiagnosticSeverity::WARNING),
+                (5, DiagnosticSeverity::WARNING),
+            ]
+        );
+        assert_eq!(diagnostics[0].message, "File not found for 'clsGone': clsGone.cls");
+    }
+
+    #[test]
+    fn test_reparse_member() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_reparse_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let module = dir.join("ModMain.bas");
+        // Saved by VB6 as Windows-1252 ("Caf\xe9")
+        let mut bytes = b"Public Const NAME = \"Caf".to_vec();
+        bytes.extend_from_slice(b"\xe9\"\nPublic Sub Main()\nEnd Sub\n");
+        std::fs::write(&module, bytes).unwrap();
+
+        let vbp = VbpFile::parse_content(&dir.join("Test.vbp"), "Module=ModMain; ModMain.bas\n")
+            .unwrap();
+        let mut project = Vb6Project::from_parsed_vbp(vbp);
+        let source = project.load_member_source(&module).unwrap();
+        assert!(source.contains("Café"));
+        assert!(project.load_member_source(&dir.join("Other.bas")).is_err());
+
+        let mut parser = Vb6Parser::new();
+        let ast = project.reparse_member(&module, &mut parser).unwrap();
+        assert_eq!(ast.procedures.len(), 1);
+        assert!(project.find_public_symbol("Main").is_some());
+        assert!(project.get_ast(&module).is_some());
+
+        // A table built elsewhere replaces the cached AST
+        let table = project.get_symbol_table(&module).unwrap().clone();
+        project.set_symbol_table(module.clone(), table);
+        std::fs::remove_dir_all(&dir).ok();
+        assert!(project.get_ast(&module).is_none());
+    }
 }
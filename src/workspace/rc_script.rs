@@ -0,0 +1,409 @@
+//! Resource Script (.rc) Export
+//!
+//! Turns the entries of a compiled .res file back into a resource script for
+//! the Windows resource compiler. String tables become `STRINGTABLE` blocks;
+//! icon groups and bitmaps are written out as .ico and .bmp files for `ICON`
+//! and `BITMAP` statements; every other resource is kept as raw data in a file
+//! included with its type, which the compiler copies back unchanged.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Cursor};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use super::res_parser::{parse_string_table, ResourceEntry, ResourceId, ResourceType};
+
+/// A resource script and the files it includes
+#[derive(Debug, Clone)]
+pub struct RcScript {
+    /// Text of the script (UTF-8, announced with `#pragma code_page(65001)`)
+    pub script: String,
+    /// Included files, by name relative to the script, with their contents
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+/// Convert resource entries to a resource script.
+///
+/// Included files are named after `file_prefix` (e.g., "app" gives
+/// "app_bitmap_101.bmp").
+pub fn res_to_rc(entries: &[ResourceEntry], file_prefix: &str) -> io::Result<RcScript> {
+    // Icon images consumed by a group, by index in `entries`
+    let mut grouped_icons = HashSet::new();
+    let mut icon_files = BTreeMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.resource_type == ResourceType::GroupIcon {
+            if let Some((file, images)) = icon_file(entry, entries) {
+                grouped_icons.extend(images);
+                icon_files.insert(index, file);
+            }
+        }
+    }
+
+    // String tables are emitted as one block per language
+    let mut strings: BTreeMap<u16, Vec<(u16, String)>> = BTreeMap::new();
+    for entry in entries.iter().filter(|e| e.resource_type == ResourceType::String) {
+        let block_id = entry.name.as_id().unwrap_or_default();
+        let table = strings.entry(entry.language_id).or_default();
+        for string in parse_string_table(&entry.data, block_id)? {
+            table.push((string.id, string.value));
+        }
+    }
+
+    let mut writer = ScriptWriter::new(file_prefix);
+    for (index, entry) in entries.iter().enumerate() {
+        match &entry.resource_type {
+            ResourceType::String => {
+                if let Some(table) = strings.remove(&entry.language_id) {
+                    writer.string_table(entry.language_id, table);
+                }
+            }
+            ResourceType::Icon if grouped_icons.contains(&index) => {}
+            ResourceType::GroupIcon if icon_files.contains_key(&index) => {
+                let file = icon_files.remove(&index).unwrap_or_default();
+                writer.resource(entry, "ICON", "icon", "ico", file);
+            }
+            ResourceType::Bitmap => match bitmap_file(&entry.data) {
+                Some(file) => writer.resource(entry, "BITMAP", "bitmap", "bmp", file),
+                None => writer.resource(entry, "2", "bitmap", "bin", entry.data.clone()),
+            },
+            ResourceType::RcData => {
+                writer.resource(entry, "RCDATA", "rcdata", "bin", entry.data.clone())
+            }
+            resource_type => {
+                let rc_type = rc_type(resource_type);
+                let label = file_label(&rc_type);
+                writer.resource(entry, &rc_type, &label, "bin", entry.data.clone());
+            }
+        }
+    }
+
+    Ok(writer.finish())
+}
+
+/// Builds the script text and names the included files
+struct ScriptWriter<'a> {
+    file_prefix: &'a str,
+    script: String,
+    files: Vec<(String, Vec<u8>)>,
+    /// Language of the last `LANGUAGE` statement
+    language: Option<u16>,
+}
+
+impl<'a> ScriptWriter<'a> {
+    fn new(file_prefix: &'a str) -> Self {
+        Self {
+            file_prefix,
+            script: "// Resource script converted from a compiled .res file\n\
+                     #pragma code_page(65001)\n"
+                .to_string(),
+            files: Vec::new(),
+            language: None,
+        }
+    }
+
+    fn set_language(&mut self, language_id: u16) {
+        if self.language != Some(language_id) {
+            self.language = Some(language_id);
+            // LANGID: primary language in the low 10 bits, sublanguage above
+            self.script.push_str(&format!(
+                "\nLANGUAGE 0x{:02X}, 0x{:02X}\n",
+                language_id & 0x3FF,
+                language_id >> 10
+            ));
+        }
+    }
+
+    fn string_table(&mut self, language_id: u16, mut table: Vec<(u16, String)>) {
+        self.set_language(language_id);
+        table.sort_by_key(|(id, _)| *id);
+        self.script.push_str("\nSTRINGTABLE\nBEGIN\n");
+        for (id, value) in table {
+            self.script.push_str(&format!("    {}, {}\n", id, rc_string(&value)));
+        }
+        self.script.push_str("END\n");
+    }
+
+    /// `name TYPE "file"`, with the data written to a new file
+    fn resource(
+        &mut self,
+        entry: &ResourceEntry,
+        rc_type: &str,
+        label: &str,
+        extension: &str,
+        data: Vec<u8>,
+    ) {
+        self.set_language(entry.language_id);
+
+        let name = match &entry.name {
+            ResourceId::Id(id) => id.to_string(),
+            ResourceId::Name(name) => file_label(name),
+        };
+        let mut file_name = format!("{}_{}_{}.{}", self.file_prefix, label, name, extension);
+        if self.files.iter().any(|(existing, _)| *existing == file_name) {
+            file_name = format!(
+                "{}_{}_{}_{:04X}.{}",
+                self.file_prefix, label, name, entry.language_id, extension
+            );
+        }
+
+        self.script.push_str(&format!(
+            "{} {} {}\n",
+            rc_name(&entry.name),
+            rc_type,
+            rc_string(&file_name)
+        ));
+        self.files.push((file_name, data));
+    }
+
+    fn finish(self) -> RcScript {
+        RcScript { script: self.script, files: self.files }
+    }
+}
+
+/// Rebuild the .ico file of an icon group from its images.
+///
+/// Returns the file and the indexes of the images, or None if one is missing.
+fn icon_file(group: &ResourceEntry, entries: &[ResourceEntry]) -> Option<(Vec<u8>, Vec<usize>)> {
+    let mut reader = Cursor::new(&group.data);
+    let reserved = reader.read_u16::<LittleEndian>().ok()?;
+    let kind = reader.read_u16::<LittleEndian>().ok()?;
+    let count = reader.read_u16::<LittleEndian>().ok()?;
+
+    // GRPICONDIRENTRY: the first 12 bytes match ICONDIRENTRY, then the image ID
+    let mut directory = Vec::new();
+    let mut images = Vec::new();
+    for _ in 0..count {
+        let mut fields = [0u8; 12];
+        io::Read::read_exact(&mut reader, &mut fields).ok()?;
+        let id = reader.read_u16::<LittleEndian>().ok()?;
+        let index = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.resource_type == ResourceType::Icon && e.name.as_id() == Some(id))
+            .min_by_key(|(_, e)| e.language_id != group.language_id)
+            .map(|(index, _)| index)?;
+        directory.push(fields);
+        images.push(index);
+    }
+
+    let mut file = Vec::new();
+    file.write_u16::<LittleEndian>(reserved).ok()?;
+    file.write_u16::<LittleEndian>(kind).ok()?;
+    file.write_u16::<LittleEndian>(count).ok()?;
+    let mut offset = 6 + 16 * count as usize;
+    for (fields, &index) in directory.iter().zip(&images) {
+        let size = entries[index].data.len();
+        file.extend_from_slice(&fields[..8]);
+        file.write_u32::<LittleEndian>(size as u32).ok()?;
+        file.write_u32::<LittleEndian>(offset as u32).ok()?;
+        offset += size;
+    }
+    for &index in &images {
+        file.extend_from_slice(&entries[index].data);
+    }
+
+    Some((file, images))
+}
+
+/// Prefix a bitmap resource (a DIB) with the file header of a .bmp file
+fn bitmap_file(dib: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = Cursor::new(dib);
+    let header_size = reader.read_u32::<LittleEndian>().ok()? as usize;
+
+    // Colors of the palette and the size of each entry
+    let (palette, masks) = if header_size == 12 {
+        // BITMAPCOREHEADER: 3-byte palette entries
+        reader.set_position(10);
+        let bit_count = reader.read_u16::<LittleEndian>().ok()?;
+        let colors = if bit_count <= 8 { 1usize << bit_count } else { 0 };
+        (colors * 3, 0)
+    } else if header_size >= 40 {
+        reader.set_position(14);
+        let bit_count = reader.read_u16::<LittleEndian>().ok()?;
+        let compression = reader.read_u32::<LittleEndian>().ok()?;
+        reader.set_position(32);
+        let colors_used = reader.read_u32::<LittleEndian>().ok()? as usize;
+        let colors = match colors_used {
+            0 if bit_count <= 8 => 1usize << bit_count,
+            colors_used => colors_used,
+        };
+        // BI_BITFIELDS and BI_ALPHABITFIELDS masks follow a BITMAPINFOHEADER
+        let masks = match (header_size, compression) {
+            (40, 3) => 12,
+            (40, 6) => 16,
+            _ => 0,
+        };
+        (colors * 4, masks)
+    } else {
+        return None;
+    };
+
+    let offset = 14 + header_size + palette + masks;
+    if offset > 14 + dib.len() {
+        return None;
+    }
+
+    let mut file = Vec::with_capacity(14 + dib.len());
+    file.extend_from_slice(b"BM");
+    file.write_u32::<LittleEndian>((14 + dib.len()) as u32).ok()?;
+    file.write_u32::<LittleEndian>(0).ok()?;
+    file.write_u32::<LittleEndian>(offset as u32).ok()?;
+    file.extend_from_slice(dib);
+    Some(file)
+}
+
+/// A resource type as written in a script: its number, or its name
+fn rc_type(resource_type: &ResourceType) -> String {
+    match resource_type {
+        ResourceType::Named(name) => rc_name(&ResourceId::Name(name.clone())),
+        ResourceType::DlgInit => "240".to_string(),
+        ResourceType::Toolbar => "241".to_string(),
+        other => other.to_id().unwrap_or_default().to_string(),
+    }
+}
+
+/// A resource name as written in a script; names that are not identifiers are quoted
+fn rc_name(name: &ResourceId) -> String {
+    match name {
+        ResourceId::Id(id) => id.to_string(),
+        ResourceId::Name(name) => {
+            let identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if identifier {
+                name.clone()
+            } else {
+                rc_string(name)
+            }
+        }
+    }
+}
+
+/// A quoted script string, with quotes doubled and control characters escaped
+fn rc_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\"\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Part of a file name for a type or resource name
+fn file_label(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::res_parser::{create_string_table, StringTableEntry};
+
+    fn string(id: u16, value: &str) -> StringTableEntry {
+        StringTableEntry { id, value: value.to_string() }
+    }
+
+    #[test]
+    fn test_res_to_rc() {
+        // A 1x1 32-bit DIB
+        let mut dib = vec![0u8; 40];
+        dib[0] = 40;
+        dib[4] = 1;
+        dib[8] = 1;
+        dib[12] = 1;
+        dib[14] = 32;
+        dib.extend_from_slice(&[1, 2, 3, 4]);
+
+        // A group of one 16x16 icon image with ID 1
+        let mut group = vec![0, 0, 1, 0, 1, 0];
+        group.extend_from_slice(&[16, 16, 0, 0, 1, 0, 32, 0]);
+        group.extend_from_slice(&6u32.to_le_bytes());
+        group.extend_from_slice(&1u16.to_le_bytes());
+
+        let entries = vec![
+            ResourceEntry::new(ResourceType::Icon, ResourceId::Id(1), 1033, vec![9; 6]),
+            ResourceEntry::new(ResourceType::GroupIcon, ResourceId::Id(101), 1033, group),
+            ResourceEntry::new(
+                ResourceType::String,
+                ResourceId::Id(7),
+                1033,
+                create_string_table(&[string(101, "Say \"hi\"\n"), string(96, "First")]).unwrap(),
+            ),
+            ResourceEntry::new(ResourceType::Bitmap, ResourceId::Id(101), 1033, dib.clone()),
+            ResourceEntry::new(ResourceType::RcData, ResourceId::Id(5), 1033, vec![1, 2]),
+            ResourceEntry::new(
+                ResourceType::Named("CUSTOM".to_string()),
+                ResourceId::Name("My Data".to_string()),
+                1031,
+                vec![3],
+            ),
+        ];
+
+        let rc = res_to_rc(&entries, "app").unwrap();
+        assert_eq!(
+            rc.script,
+            "// Resource script converted from a compiled .res file\n\
+             #pragma code_page(65001)\n\
+             \n\
+             LANGUAGE 0x09, 0x01\n\
+             101 ICON \"app_icon_101.ico\"\n\
+             \n\
+             STRINGTABLE\n\
+             BEGIN\n\
+             \x20   96, \"First\"\n\
+             \x20   101, \"Say \"\"hi\"\"\\n\"\n\
+             END\n\
+             101 BITMAP \"app_bitmap_101.bmp\"\n\
+             5 RCDATA \"app_rcdata_5.bin\"\n\
+             \n\
+             LANGUAGE 0x07, 0x01\n\
+             \"My Data\" CUSTOM \"app_custom_my_data.bin\"\n"
+        );
+
+        let names: Vec<&str> = rc.files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "app_icon_101.ico",
+                "app_bitmap_101.bmp",
+                "app_rcdata_5.bin",
+                "app_custom_my_data.bin"
+            ]
+        );
+
+        // ICONDIR, one ICONDIRENTRY pointing past the directory, then the image
+        let icon = &rc.files[0].1;
+        assert_eq!(icon.len(), 6 + 16 + 6);
+        assert_eq!(&icon[14..22], &[6, 0, 0, 0, 22, 0, 0, 0]);
+        assert_eq!(&icon[22..], &[9; 6]);
+
+        // BITMAPFILEHEADER: size and offset of the pixels
+        let bitmap = &rc.files[1].1;
+        assert_eq!(&bitmap[..2], b"BM");
+        assert_eq!(u32::from_le_bytes(bitmap[2..6].try_into().unwrap()), 14 + 44);
+        assert_eq!(u32::from_le_bytes(bitmap[10..14].try_into().unwrap()), 14 + 40);
+        assert_eq!(&bitmap[14..], dib.as_slice());
+    }
+
+    #[test]
+    fn test_missing_icon_image_is_kept_raw() {
+        let mut group = vec![0, 0, 1, 0, 1, 0];
+        group.extend_from_slice(&[16, 16, 0, 0, 1, 0, 32, 0, 6, 0, 0, 0, 2, 0]);
+        let entries = vec![
+            ResourceEntry::new(ResourceType::Icon, ResourceId::Id(1), 1033, vec![9; 6]),
+            ResourceEntry::new(ResourceType::GroupIcon, ResourceId::Id(101), 1033, group),
+        ];
+
+        let rc = res_to_rc(&entries, "app").unwrap();
+        assert!(rc.script.ends_with("1 3 \"app_3_1.bin\"\n101 14 \"app_14_101.bin\"\n"));
+    }
+}
@@ -272,22 +272,19 @@ impl ResHeader {
 
         // Type field
         size += match &self.resource_type {
-            ResourceType::Named(name) => {
-                let str_len = (name.encode_utf16().count() + 1) * 2; // UTF-16 + null
-                align_to_dword(str_len)
-            }
+            ResourceType::Named(name) => (name.encode_utf16().count() + 1) * 2, // UTF-16 + null
             _ => 4, // 0xFFFF marker + u16 ID
         };
 
         // Name field
         size += match &self.name {
-            ResourceId::Name(name) => {
-                let str_len = (name.encode_utf16().count() + 1) * 2; // UTF-16 + null
-                align_to_dword(str_len)
-            }
+            ResourceId::Name(name) => (name.encode_utf16().count() + 1) * 2, // UTF-16 + null
             ResourceId::Id(_) => 4, // 0xFFFF marker + u16 ID
         };
 
+        // Type and name are padded together, not one by one
+        size = align_to_dword(size);
+
         // Fixed fields: DataVersion + MemoryFlags + LanguageId + Version + Characteristics
         size += 16;
 
@@ -528,6 +525,27 @@ pub fn write_res_file(file_path: &str, entries: &[ResourceEntry]) -> io::Result<
     Ok(())
 }
 
+/// Add a resource, replacing the one with the same type, name, and language
+///
+/// Returns whether an existing resource was replaced.
+pub fn replace_resource(entries: &mut Vec<ResourceEntry>, entry: ResourceEntry) -> bool {
+    let existing = entries.iter_mut().find(|e| {
+        e.resource_type == entry.resource_type
+            && e.name == entry.name
+            && e.language_id == entry.language_id
+    });
+    match existing {
+        Some(existing) => {
+            *existing = entry;
+            true
+        }
+        None => {
+            entries.push(entry);
+            false
+        }
+    }
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
@@ -778,6 +796,40 @@ pub fn create_string_table(entries: &[StringTableEntry]) -> io::Result<Vec<u8>>
     Ok(buffer)
 }
 
+/// Create the data of string table block `block_id`, checking that every
+/// string belongs to it
+///
+/// Block N holds string IDs (N - 1) * 16 to (N - 1) * 16 + 15.
+pub fn build_string_table_block(
+    entries: &[StringTableEntry],
+    block_id: u16,
+) -> io::Result<Vec<u8>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+    if !(1..=4096).contains(&block_id) {
+        return Err(invalid(format!("Block ID {} is not between 1 and 4096", block_id)));
+    }
+    if entries.is_empty() {
+        return Err(invalid(format!("No strings for block {}", block_id)));
+    }
+
+    let first = (block_id - 1) * 16;
+    let last = first + 15;
+    for (i, entry) in entries.iter().enumerate() {
+        if !(first..=last).contains(&entry.id) {
+            return Err(invalid(format!(
+                "String ID {} is not in block {}, which holds IDs {} to {}",
+                entry.id, block_id, first, last
+            )));
+        }
+        if entries[..i].iter().any(|other| other.id == entry.id) {
+            return Err(invalid(format!("String ID {} appears more than once", entry.id)));
+        }
+    }
+
+    create_string_table(entries)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -823,6 +875,10 @@ mod tests {
                 id: 101,
                 value: "World".to_string(),
             },
+            StringTableEntry {
+                id: 102,
+                value: "£5 © Café".to_string(),
+            },
         ];
 
         let data = create_string_table(&entries).unwrap();
@@ -832,11 +888,54 @@ mod tests {
         let block_id = (100 / 16) + 1;
         let parsed = parse_string_table(&data, block_id).unwrap();
 
-        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.len(), 3);
         assert_eq!(parsed[0].id, 100);
         assert_eq!(parsed[0].value, "Hello");
         assert_eq!(parsed[1].id, 101);
         assert_eq!(parsed[1].value, "World");
+        // String tables are UTF-16, so text outside the ANSI codepage survives
+        assert_eq!(parsed[2].value, "£5 © Café");
+    }
+
+    #[test]
+    fn test_build_string_table_block() {
+        let string = |id: u16, value: &str| StringTableEntry { id, value: value.to_string() };
+
+        // Block 7 holds IDs 96 to 111
+        let entries = [string(111, "Last"), string(96, "First")];
+        let data = build_string_table_block(&entries, 7).unwrap();
+        let parsed = parse_string_table(&data, 7).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!((parsed[0].id, parsed[0].value.as_str()), (96, "First"));
+        assert_eq!((parsed[1].id, parsed[1].value.as_str()), (111, "Last"));
+
+        let error = build_string_table_block(&[string(112, "Next block")], 7).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "String ID 112 is not in block 7, which holds IDs 96 to 111"
+        );
+        assert!(build_string_table_block(&[string(96, "a"), string(96, "b")], 7).is_err());
+        assert!(build_string_table_block(&[string(0, "a")], 0).is_err());
+        assert!(build_string_table_block(&[], 7).is_err());
+    }
+
+    #[test]
+    fn test_replace_resource() {
+        let mut entries = vec![
+            ResourceEntry::new(ResourceType::String, ResourceId::Id(1), 0x0409, vec![1]),
+            ResourceEntry::new(ResourceType::Bitmap, ResourceId::Id(1), 0x0409, vec![2]),
+        ];
+
+        let block = ResourceEntry::new(ResourceType::String, ResourceId::Id(1), 0x0409, vec![3]);
+        assert!(replace_resource(&mut entries, block));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].data, vec![3]);
+
+        // Another language is another resource
+        let block = ResourceEntry::new(ResourceType::String, ResourceId::Id(1), 0x0407, vec![4]);
+        assert!(!replace_resource(&mut entries, block));
+        assert_eq!(entries.len(), 3);
     }
 
     #[test]
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::parser::CompilationConstants;
 use crate::utils::VB6FileReader;
 
 /// A parsed VBP project file
@@ -24,9 +25,15 @@ pub struct VbpFile {
     /// Project type (Exe, OleDll, Control, OleExe)
     pub project_type: ProjectType,
 
-    /// Project name/title
+    /// Project name
     pub name: String,
 
+    /// Application title (App.Title)
+    pub title: Option<String>,
+
+    /// Command line arguments used when running from the IDE
+    pub command_line: Option<String>,
+
     /// Standard modules (.bas files)
     pub modules: Vec<ProjectMember>,
 
@@ -54,10 +61,13 @@ pub struct VbpFile {
     /// Type library references (including SubProject references)
     pub references: Vec<TypeLibReference>,
 
+    /// Reference entries that could not be parsed (line, raw value)
+    pub invalid_references: Vec<(usize, String)>,
+
     /// OCX/ActiveX object references
     pub objects: Vec<ObjectReference>,
 
-    /// Startup form or "Sub Main"
+    /// Startup form or "Sub Main" (see [`VbpFile::startup_object`])
     pub startup: Option<String>,
 
     /// Output executable name
@@ -78,10 +88,22 @@ pub struct VbpFile {
     /// Custom property sections (e.g., [MS Transaction Server])
     pub custom_sections: HashMap<String, HashMap<String, String>>,
 
-    /// All raw key-value pairs (for properties we don't specifically handle)
+    /// All raw key-value pairs (for properties we don't specifically handle),
+    /// kept as written so they can be round-tripped
     pub properties: HashMap<String, String>,
 }
 
+/// What runs when the project starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupObject {
+    /// No startup object (typical for DLLs and controls)
+    None,
+    /// The `Sub Main` procedure of a standard module
+    SubMain,
+    /// A form, by name
+    Form(String),
+}
+
 /// Project member (module, class, form, etc.)
 #[derive(Debug, Clone)]
 pub struct ProjectMember {
@@ -93,6 +115,9 @@ pub struct ProjectMember {
 
     /// Absolute path (resolved when VBP is parsed)
     pub absolute_path: PathBuf,
+
+    /// Line of the entry in the VBP file (0-indexed)
+    pub line: usize,
 }
 
 /// Type of VB6 project
@@ -136,11 +161,15 @@ pub enum TypeLibReference {
         path: Option<PathBuf>,
         /// Description/name of the library
         description: String,
+        /// Line of the entry in the VBP file (0-indexed)
+        line: usize,
     },
     /// Reference to another VB6 project
     SubProject {
         /// Path to the referenced .vbp file
         path: PathBuf,
+        /// Line of the entry in the VBP file (0-indexed)
+        line: usize,
     },
 }
 
@@ -157,10 +186,20 @@ impl TypeLibReference {
     pub fn description(&self) -> &str {
         match self {
             TypeLibReference::Compiled { description, .. } => description,
-            TypeLibReference::SubProject { path } => {
-                path.file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("SubProject")
+            // Split by hand: VBP paths use `\`, which is not a separator elsewhere
+            TypeLibReference::SubProject { path, .. } => path
+                .to_str()
+                .and_then(|path| path.rsplit(['\\', '/']).next())
+                .map(|name| name.rsplit_once('.').map_or(name, |(stem, _)| stem))
+                .unwrap_or("SubProject"),
+        }
+    }
+
+    /// Get the line of the entry in the VBP file (0-indexed)
+    pub fn line(&self) -> usize {
+        match self {
+            TypeLibReference::Compiled { line, .. } | TypeLibReference::SubProject { line, .. } => {
+                *line
             }
         }
     }
@@ -182,6 +221,8 @@ pub struct ObjectReference {
     pub version: String,
     /// Filename (e.g., "MSCOMCTL.OCX")
     pub filename: Option<String>,
+    /// Line of the entry in the VBP file (0-indexed)
+    pub line: usize,
 }
 
 /// Version information for the project
@@ -209,6 +250,12 @@ pub struct VersionInfo {
     pub comments: Option<String>,
 }
 
+impl std::fmt::Display for VersionInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.revision)
+    }
+}
+
 /// Compilation settings
 #[derive(Debug, Clone, Default)]
 pub struct CompilationSettings {
@@ -373,6 +420,8 @@ impl VbpFile {
                 .and_then(|s| s.to_str())
                 .unwrap_or("Untitled")
                 .to_string(),
+            title: None,
+            command_line: None,
             modules: Vec::new(),
             classes: Vec::new(),
             forms: Vec::new(),
@@ -382,6 +431,7 @@ impl VbpFile {
             designers: Vec::new(),
             related_documents: Vec::new(),
             references: Vec::new(),
+            invalid_references: Vec::new(),
             objects: Vec::new(),
             startup: None,
             exe_name: None,
@@ -434,41 +484,50 @@ impl VbpFile {
                     "Type" => {
                         vbp.project_type = ProjectType::from_str(value);
                     }
-                    "Name" | "Title" => {
+                    "Name" => {
                         vbp.name = unquote(value);
                     }
+                    "Title" => {
+                        vbp.title = Some(unquote(value));
+                    }
+                    "Command32" | "Command" => {
+                        let command = unquote(value);
+                        if !command.is_empty() {
+                            vbp.command_line = Some(command);
+                        }
+                    }
                     "Module" => {
-                        if let Some(member) = parse_member(value, vbp_dir, ".bas") {
+                        if let Some(member) = parse_member(value, vbp_dir, ".bas", line_num) {
                             vbp.modules.push(member);
                         }
                     }
                     "Class" => {
-                        if let Some(member) = parse_member(value, vbp_dir, ".cls") {
+                        if let Some(member) = parse_member(value, vbp_dir, ".cls", line_num) {
                             vbp.classes.push(member);
                         }
                     }
                     "Form" => {
-                        if let Some(member) = parse_member(value, vbp_dir, ".frm") {
+                        if let Some(member) = parse_member(value, vbp_dir, ".frm", line_num) {
                             vbp.forms.push(member);
                         }
                     }
                     "UserControl" => {
-                        if let Some(member) = parse_member(value, vbp_dir, ".ctl") {
+                        if let Some(member) = parse_member(value, vbp_dir, ".ctl", line_num) {
                             vbp.user_controls.push(member);
                         }
                     }
                     "PropertyPage" => {
-                        if let Some(member) = parse_member(value, vbp_dir, ".pag") {
+                        if let Some(member) = parse_member(value, vbp_dir, ".pag", line_num) {
                             vbp.property_pages.push(member);
                         }
                     }
                     "UserDocument" => {
-                        if let Some(member) = parse_member(value, vbp_dir, ".dob") {
+                        if let Some(member) = parse_member(value, vbp_dir, ".dob", line_num) {
                             vbp.user_documents.push(member);
                         }
                     }
                     "Designer" => {
-                        if let Some(member) = parse_member(value, vbp_dir, ".dsr") {
+                        if let Some(member) = parse_member(value, vbp_dir, ".dsr", line_num) {
                             vbp.designers.push(member);
                         }
                     }
@@ -477,12 +536,13 @@ impl VbpFile {
                         vbp.related_documents.push(path);
                     }
                     "Reference" => {
-                        if let Some(reference) = parse_reference(value, vbp_dir) {
-                            vbp.references.push(reference);
+                        match parse_reference(value, vbp_dir, line_num) {
+                            Some(reference) => vbp.references.push(reference),
+                            None => vbp.invalid_references.push((line_num, value.to_string())),
                         }
                     }
                     "Object" => {
-                        if let Some(object) = parse_object(value) {
+                        if let Some(object) = parse_object(value, line_num) {
                             vbp.objects.push(object);
                         }
                     }
@@ -693,22 +753,67 @@ impl VbpFile {
     pub fn get_custom_section(&self, name: &str) -> Option<&HashMap<String, String>> {
         self.custom_sections.get(name)
     }
+
+    /// Get the startup object
+    pub fn startup_object(&self) -> StartupObject {
+        match self.startup.as_deref() {
+            None => StartupObject::None,
+            Some(startup) if startup.eq_ignore_ascii_case("Sub Main") => StartupObject::SubMain,
+            Some(form) => StartupObject::Form(form.to_string()),
+        }
+    }
+
+    /// Get the conditional compilation constants (`CondComp`), keyed by lowercase name
+    pub fn conditional_constants(&self) -> CompilationConstants {
+        self.compilation
+            .conditional_compile
+            .as_deref()
+            .map(parse_conditional_constants)
+            .unwrap_or_default()
+    }
+
+    /// Get the version information
+    pub fn version(&self) -> &VersionInfo {
+        &self.version_info
+    }
+}
+
+/// Parse conditional compilation arguments
+/// Format: "NAME = value : NAME = value" (values are integers; invalid entries are skipped)
+fn parse_conditional_constants(value: &str) -> CompilationConstants {
+    value
+        .split(':')
+        .filter_map(|entry| {
+            let (name, value) = entry.split_once('=')?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some((name.to_lowercase(), value.trim().parse().ok()?))
+        })
+        .collect()
 }
 
 /// Parse a project member entry (Module, Class, Form, etc.)
 /// Format: "name; path" or just "path" (name derived from filename)
-fn parse_member(value: &str, vbp_dir: &Path, default_ext: &str) -> Option<ProjectMember> {
+fn parse_member(
+    value: &str,
+    vbp_dir: &Path,
+    default_ext: &str,
+    line: usize,
+) -> Option<ProjectMember> {
     let (name, relative_path) = if let Some((n, p)) = value.split_once(';') {
         (n.trim().to_string(), PathBuf::from(p.trim()))
     } else {
         // No semicolon - value is just the path, derive name from filename
-        let path = PathBuf::from(value.trim());
-        let name = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("Unknown")
-            .to_string();
-        (name, path)
+        // (split by hand, as `\` is not a separator outside Windows)
+        let file_name = value.trim().rsplit(['\\', '/']).next().unwrap_or_default();
+        let name = match file_name.rsplit_once('.') {
+            Some((stem, _)) => stem,
+            None if file_name.is_empty() => "Unknown",
+            None => file_name,
+        };
+        (name.to_string(), PathBuf::from(value.trim()))
     };
 
     // Ensure extension is present
@@ -729,13 +834,14 @@ fn parse_member(value: &str, vbp_dir: &Path, default_ext: &str) -> Option<Projec
         name,
         relative_path,
         absolute_path,
+        line,
     })
 }
 
 /// Parse a Reference entry
 /// Format: *\G{GUID}#version#lcid#path#description (compiled)
 /// Format: *\A<path> (sub-project)
-fn parse_reference(value: &str, vbp_dir: &Path) -> Option<TypeLibReference> {
+fn parse_reference(value: &str, vbp_dir: &Path, line: usize) -> Option<TypeLibReference> {
     // Check for sub-project reference: *\A<path>
     if value.starts_with("*\\A") {
         let path_str = value.trim_start_matches("*\\A").trim();
@@ -744,7 +850,7 @@ fn parse_reference(value: &str, vbp_dir: &Path) -> Option<TypeLibReference> {
         } else {
             vbp_dir.join(path_str)
         };
-        return Some(TypeLibReference::SubProject { path });
+        return Some(TypeLibReference::SubProject { path, line });
     }
 
     // Parse compiled reference: *\G{GUID}#version#lcid#path#description
@@ -777,12 +883,13 @@ fn parse_reference(value: &str, vbp_dir: &Path) -> Option<TypeLibReference> {
         lcid,
         path,
         description,
+        line,
     })
 }
 
 /// Parse an Object entry
 /// Format: {GUID}#version#0; filename
-fn parse_object(value: &str) -> Option<ObjectReference> {
+fn parse_object(value: &str, line: usize) -> Option<ObjectReference> {
     let (guid_part, filename) = if let Some((g, f)) = value.split_once(';') {
         (g.trim(), Some(f.trim().to_string()))
     } else {
@@ -803,6 +910,7 @@ fn parse_object(value: &str) -> Option<ObjectReference> {
         guid_string: guid_str.to_string(),
         version,
         filename,
+        line,
     })
 }
 
@@ -877,7 +985,7 @@ ExeName32="TestProject.exe"
     fn test_parse_compiled_reference() {
         let ref_str =
             "*\\G{00020430-0000-0000-C000-000000000046}#2.0#0#C:\\Windows\\System32\\stdole2.tlb#OLE Automation";
-        let reference = parse_reference(ref_str, Path::new("C:\\Projects")).unwrap();
+        let reference = parse_reference(ref_str, Path::new("C:\\Projects"), 0).unwrap();
 
         match reference {
             TypeLibReference::Compiled {
@@ -900,10 +1008,10 @@ ExeName32="TestProject.exe"
     #[test]
     fn test_parse_subproject_reference() {
         let ref_str = "*\\ACommonLib.vbp";
-        let reference = parse_reference(ref_str, Path::new("C:\\Projects")).unwrap();
+        let reference = parse_reference(ref_str, Path::new("C:\\Projects"), 0).unwrap();
 
         match reference {
-            TypeLibReference::SubProject { path } => {
+            TypeLibReference::SubProject { path, .. } => {
                 assert!(path.ends_with("CommonLib.vbp"));
             }
             _ => panic!("Expected subproject reference"),
@@ -913,7 +1021,7 @@ ExeName32="TestProject.exe"
     #[test]
     fn test_parse_object() {
         let obj_str = "{831FDD16-0C5C-11D2-A9FC-0000F8754DA1}#2.0#0; MSCOMCTL.OCX";
-        let object = parse_object(obj_str).unwrap();
+        let object = parse_object(obj_str, 0).unwrap();
 
         assert!(object.uuid.is_some());
         assert_eq!(object.guid_string, "831FDD16-0C5C-11D2-A9FC-0000F8754DA1");
@@ -924,7 +1032,7 @@ ExeName32="TestProject.exe"
     #[test]
     fn test_member_without_semicolon() {
         // Some VBP files use just the filename without "name; path" format
-        let member = parse_member("frmMain.frm", Path::new("C:\\Projects"), ".frm").unwrap();
+        let member = parse_member("frmMain.frm", Path::new("C:\\Projects"), ".frm", 0).unwrap();
 
         assert_eq!(member.name, "frmMain");
         assert_eq!(member.relative_path, PathBuf::from("frmMain.frm"));
@@ -1008,6 +1116,48 @@ CondComp="DEBUG=1"
         );
     }
 
+    #[test]
+    fn test_project_metadata_accessors() {
+        let content = r#"
+Type=Exe
+Name="Inventory"
+Title="Inventory Manager"
+Command32="/debug /user:admin"
+Startup="frmMain"
+CondComp="DEBUG_MODE = 1 : WIN32 = -1 : BAD = x"
+MajorVer=2
+MinorVer=5
+RevisionVer=17
+HelpContextID="0"
+"#;
+
+        let vbp = VbpFile::parse_content(Path::new("C:\\Projects\\Inv.vbp"), content).unwrap();
+
+        assert_eq!(vbp.name, "Inventory");
+        assert_eq!(vbp.title, Some("Inventory Manager".to_string()));
+        assert_eq!(vbp.command_line, Some("/debug /user:admin".to_string()));
+        assert_eq!(vbp.startup_object(), StartupObject::Form("frmMain".to_string()));
+        assert_eq!(
+            vbp.conditional_constants(),
+            CompilationConstants::from([("debug_mode".to_string(), 1), ("win32".to_string(), -1)])
+        );
+        assert_eq!(vbp.version().to_string(), "2.5.17");
+        assert_eq!(vbp.properties.get("HelpContextID"), Some(&"\"0\"".to_string()));
+    }
+
+    #[test]
+    fn test_startup_object() {
+        let parse = |startup: &str| {
+            let content = format!("Type=OleDll\nStartup=\"{}\"\n", startup);
+            VbpFile::parse_content(Path::new("Test.vbp"), &content)
+                .unwrap()
+                .startup_object()
+        };
+
+        assert_eq!(parse("Sub Main"), StartupObject::SubMain);
+        assert_eq!(parse("(None)"), StartupObject::None);
+    }
+
     #[test]
     fn test_threading_settings_parsing() {
         let content = r#"
@@ -0,0 +1,276 @@
+//! File Watching
+//!
+//! Detects VBP and source file edits made outside the editor from the file
+//! system events under the workspace roots, and debounces the bursts of
+//! changes that tools produce when they rewrite several files at once.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Extensions of the files the workspace keeps track of
+const WATCHED_EXTENSIONS: &[&str] = &["vbp", "bas", "cls", "frm", "ctl", "dob", "pag", "dsr"];
+
+/// How a file changed on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Created,
+    Changed,
+    Deleted,
+}
+
+/// A change to a single file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEvent {
+    pub path: PathBuf,
+    pub change: FileChange,
+}
+
+impl FileEvent {
+    pub fn new(path: PathBuf, change: FileChange) -> Self {
+        Self { path, change }
+    }
+
+    /// Whether the event is for a project file rather than a source file
+    pub fn is_project_file(&self) -> bool {
+        has_extension(&self.path, "vbp")
+    }
+}
+
+/// Whether a path is a file the workspace watches
+fn is_watched_file(path: &Path) -> bool {
+    WATCHED_EXTENSIONS.iter().any(|ext| has_extension(path, ext))
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+/// Holds events until their file has been quiet for the debounce delay
+#[derive(Debug)]
+pub struct Debouncer {
+    delay: Duration,
+    pending: HashMap<PathBuf, (FileChange, Instant)>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Record an event, merging it with one already pending for the file
+    pub fn push(&mut self, event: FileEvent, now: Instant) {
+        let change = match (self.pending.get(&event.path), event.change) {
+            // Still new to anyone who hasn't seen it yet
+            (Some((FileChange::Created, _)), FileChange::Changed) => FileChange::Created,
+            // Replaced by a save that deletes and rewrites the file
+            (Some((FileChange::Deleted, _)), FileChange::Created) => FileChange::Changed,
+            (_, change) => change,
+        };
+        self.pending.insert(event.path, (change, now));
+    }
+
+    /// Take the events whose file has not changed within the delay
+    pub fn take_ready(&mut self, now: Instant) -> Vec<FileEvent> {
+        let mut ready = Vec::new();
+        self.pending.retain(|path, (change, last)| {
+            if now.duration_since(*last) < self.delay {
+                return true;
+            }
+            ready.push(FileEvent::new(path.clone(), *change));
+            false
+        });
+        ready.sort_by(|a, b| a.path.cmp(&b.path));
+        ready
+    }
+}
+
+/// Reports changes to watched files under the workspace roots as they happen
+pub struct FsWatcher {
+    watcher: RecommendedWatcher,
+    roots: Vec<PathBuf>,
+}
+
+impl FsWatcher {
+    /// Create a watcher that passes each change to a watched file to `handler`
+    pub fn new<F>(mut handler: F) -> notify::Result<Self>
+    where
+        F: FnMut(FileEvent) + Send + 'static,
+    {
+        // Symlinked folders are left to the client, rather than risk watching loops
+        let config = Config::default().with_follow_symlinks(false);
+        let watcher = RecommendedWatcher::new(
+            move |result: notify::Result<Event>| match result {
+                Ok(event) => file_events(&event).into_iter().for_each(&mut handler),
+                Err(e) => tracing::debug!("File watcher error: {}", e),
+            },
+            config,
+        )?;
+        Ok(Self {
+            watcher,
+            roots: Vec::new(),
+        })
+    }
+
+    /// Watch exactly the given roots, dropping the ones no longer listed
+    pub fn set_roots(&mut self, roots: &[PathBuf]) {
+        for root in &self.roots {
+            if !roots.contains(root) {
+                if let Err(e) = self.watcher.unwatch(root) {
+                    tracing::debug!("Could not stop watching {}: {}", root.display(), e);
+                }
+            }
+        }
+        self.roots.retain(|root| roots.contains(root));
+
+        for root in roots {
+            if self.roots.contains(root) {
+                continue;
+            }
+            match self.watcher.watch(root, RecursiveMode::Recursive) {
+                Ok(()) => self.roots.push(root.clone()),
+                Err(e) => tracing::warn!("Could not watch {}: {}", root.display(), e),
+            }
+        }
+    }
+}
+
+/// The changes to watched files described by a file system event
+fn file_events(event: &Event) -> Vec<FileEvent> {
+    let changes: Vec<(&PathBuf, FileChange)> = match event.kind {
+        EventKind::Create(_) => event.paths.iter().map(|p| (p, FileChange::Created)).collect(),
+        EventKind::Remove(_) => event.paths.iter().map(|p| (p, FileChange::Deleted)).collect(),
+        EventKind::Modify(ModifyKind::Name(mode)) => event
+            .paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let change = match mode {
+                    RenameMode::From => FileChange::Deleted,
+                    RenameMode::To => FileChange::Created,
+                    // Renamed from the first path to the second
+                    RenameMode::Both if index == 0 => FileChange::Deleted,
+                    RenameMode::Both => FileChange::Created,
+                    _ if path.exists() => FileChange::Created,
+                    _ => FileChange::Deleted,
+                };
+                (path, change)
+            })
+            .collect(),
+        // Permission and timestamp changes leave the contents alone
+        EventKind::Modify(ModifyKind::Metadata(_)) => Vec::new(),
+        EventKind::Modify(_) => event.paths.iter().map(|p| (p, FileChange::Changed)).collect(),
+        EventKind::Access(_) | EventKind::Any | EventKind::Other => Vec::new(),
+    };
+
+    changes
+        .into_iter()
+        .filter(|(path, _)| is_watched_file(path))
+        .map(|(path, change)| FileEvent::new(path.clone(), change))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debouncer_merges_bursts() {
+        let start = Instant::now();
+        let delay = Duration::from_millis(100);
+        let mut debouncer = Debouncer::new(delay);
+
+        let path = PathBuf::from("/project/Module1.bas");
+        debouncer.push(FileEvent::new(path.clone(), FileChange::Deleted), start);
+        debouncer.push(FileEvent::new(path.clone(), FileChange::Created), start + delay / 2);
+        assert!(debouncer.take_ready(start + delay).is_empty());
+
+        let ready = debouncer.take_ready(start + delay * 2);
+        assert_eq!(ready, vec![FileEvent::new(path, FileChange::Changed)]);
+        assert!(debouncer.take_ready(start + delay * 3).is_empty());
+    }
+
+    #[test]
+    fn test_file_events_from_notify() {
+        use notify::event::{CreateKind, DataChange, MetadataKind, RemoveKind};
+
+        let project = PathBuf::from("/project/Project1.vbp");
+        let module = PathBuf::from("/project/Module1.bas");
+        let notes = PathBuf::from("/project/notes.txt");
+
+        let created = Event::new(EventKind::Create(CreateKind::File))
+            .add_path(project.clone())
+            .add_path(notes.clone());
+        assert_eq!(
+            file_events(&created),
+            vec![FileEvent::new(project.clone(), FileChange::Created)]
+        );
+
+        let written = Event::new(EventKind::Modify(ModifyKind::Data(DataChange::Content)))
+            .add_path(module.clone());
+        assert_eq!(
+            file_events(&written),
+            vec![FileEvent::new(module.clone(), FileChange::Changed)]
+        );
+
+        let touched = Event::new(EventKind::Modify(ModifyKind::Metadata(MetadataKind::WriteTime)))
+            .add_path(module.clone());
+        assert!(file_events(&touched).is_empty());
+
+        let renamed = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(module.clone())
+            .add_path(PathBuf::from("/project/Module2.bas"));
+        assert_eq!(
+            file_events(&renamed),
+            vec![
+                FileEvent::new(module.clone(), FileChange::Deleted),
+                FileEvent::new(PathBuf::from("/project/Module2.bas"), FileChange::Created),
+            ]
+        );
+
+        let removed = Event::new(EventKind::Remove(RemoveKind::File)).add_path(module.clone());
+        assert_eq!(
+            file_events(&removed),
+            vec![FileEvent::new(module, FileChange::Deleted)]
+        );
+    }
+
+    #[test]
+    fn test_fs_watcher_reports_changes() {
+        let dir = std::env::temp_dir().join(format!("vb6_lsp_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut watcher = FsWatcher::new(move |event| {
+            let _ = sender.send(event);
+        })
+        .unwrap();
+        watcher.set_roots(std::slice::from_ref(&dir));
+
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        std::fs::write(dir.join("Project1.vbp"), "Type=Exe\n").unwrap();
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.recv_timeout(Duration::from_secs(5)) {
+            let done = event.path == dir.join("Project1.vbp");
+            events.push(event);
+            if done {
+                break;
+            }
+        }
+
+        watcher.set_roots(&[]);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(events.iter().all(|event| event.path == dir.join("Project1.vbp")));
+        assert_eq!(
+            events.first(),
+            Some(&FileEvent::new(dir.join("Project1.vbp"), FileChange::Created))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+Attribute VB_Name = "Sample"
+Option Explicit
+
+' Sample module exercising the formatter
+Private Type Point
+X As Long
+  Y As Long
+End Type
+
+Public Enum Direction
+dirUp=1
+dirDown   =   2
+End Enum
+
+Private Declare Function GetTickCount Lib "kernel32" () As Long
+
+Public Function Clamp(ByVal value As Long,ByVal lo As Long,ByVal hi As Long) As Long
+If value<lo Then Clamp=lo: Exit Function
+If value>hi Then
+Clamp=hi
+ElseIf value=0 Then
+Clamp=-1
+Else
+Clamp=value
+End If
+End Function
+
+Public Sub Main()
+Dim i As Long, j As Long
+Dim s As String * 10
+Dim total&
+On Error GoTo Handler
+For i=1 To 10 Step -1
+For j = 0 To i
+total=total+i*j   ' accumulate
+Next j, i
+Select Case total
+Case Is>100
+Debug.Print "big: " & total
+Case 1 To 100
+Debug.Print "x=""" & total & """"
+Case Else
+Debug.Print #1, -total
+End Select
+With Me
+.Caption = "Formatted: " & Format$(Now, "hh:mm")
+End With
+Do While total > 0
+total = total \ 2: DoEvents
+Loop
+MsgBox Prompt:="Done", Buttons:=vbOKOnly, _
+Title:="Sample"
+If #1/1/2000# < Now Then Debug.Print "later"
+Exit Sub
+Handler:
+Resume Next
+End Sub
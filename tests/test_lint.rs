@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+/// Write a module into a fresh directory of its own
+fn write_module(name: &str, source: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vb6_lsp_lint_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.bas", name));
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+/// Run `vb6-lsp lint` and return its exit code and output
+fn run_lint(path: &PathBuf, args: &[&str]) -> (Option<i32>, String) {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vb6-lsp"))
+        .arg("lint")
+        .arg(path)
+        .args(args)
+        .output()
+        .expect("Failed to run vb6-lsp");
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    (output.status.code(), String::from_utf8(output.stdout).unwrap())
+}
+
+#[test]
+fn test_lint_json_output() {
+    let path = write_module(
+        "JsonFormat",
+        "Attribute VB_Name = \"JsonFormat\"\r\nOption Explicit\r\n\r\nSub Main()\r\n\
+    Dim x As Integer\r\n    x = 1 + \"a\"\r\nEnd Sub\r\n",
+    );
+    let (code, output) = run_lint(&path, &[]);
+
+    // Only information, so the lint passes
+    assert_eq!(code, Some(0));
+    let diagnostics: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(
+        diagnostics,
+        serde_json::json!([{
+            "severity": "information",
+            "code": "plus-concatenation",
+            "message": "Use '&' to concatenate strings; '+' adds when an operand is numeric",
+            "range": {
+                "start": { "line": 5, "character": 10 },
+                "end": { "line": 5, "character": 11 }
+            },
+            "source": "vb6-lsp"
+        }])
+    );
+}
+
+#[test]
+fn test_lint_github_output() {
+    let path = write_module(
+        "GithubFormat",
+        "Attribute VB_Name = \"GithubFormat\"\r\nOption Explicit\r\n\r\nSub Main()\r\n\
+    Dim x As Integer\r\n    x = 1 + \"a\"\r\n    y = 2\r\nEnd Sub\r\n",
+    );
+    let file = path.to_str().unwrap().to_string();
+    let (code, output) = run_lint(&path, &["--format=github"]);
+
+    // Annotations are 1-based, and the undeclared variable fails the lint
+    assert_eq!(code, Some(1));
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            format!(
+                "::notice file={},line=6,col=11,endLine=6,endColumn=12::Use '&' to concatenate \
+strings; '+' adds when an operand is numeric",
+                file
+            ),
+            format!(
+                "::error file={},line=7,col=5,endLine=7,endColumn=6::Variable not defined: 'y'",
+                file
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_lint_fails_on_errors() {
+    let path = write_module("Errors", "Attribute VB_Name = \"Errors\"\r\nSub Broken(\r\n");
+    let (code, output) = run_lint(&path, &[]);
+
+    assert_eq!(code, Some(1));
+    let diagnostics: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let diagnostics = diagnostics.as_array().unwrap();
+    assert!(diagnostics.iter().any(|d| d["severity"] == "error" && d["code"] == "syntax-error"));
+}
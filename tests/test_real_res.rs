@@ -248,3 +248,90 @@ fn test_compare_both_res_files() {
     assert!(!game2048_resources.is_empty());
     assert!(!sheep_resources.is_empty());
 }
+
+#[test]
+fn test_convert_sheep_res_to_rc() {
+    let res_path = fixture_path("sheep.res");
+    let resources = read_res_file(res_path.to_str().unwrap()).unwrap();
+
+    let rc = res_to_rc(&resources, "sheep").unwrap();
+
+    // The icon images are folded into one .ico for the group
+    assert!(rc.script.contains("\n101 ICON \"sheep_icon_101.ico\"\n"));
+    assert!(!rc.script.lines().any(|line| line.starts_with("1 3 ")));
+    assert!(rc.script.contains("\n101 BITMAP \"sheep_bitmap_101.bmp\"\n"));
+
+    // One file per resource, apart from the grouped images
+    let icons = resources.iter().filter(|r| r.resource_type == ResourceType::Icon).count();
+    assert_eq!(rc.files.len(), resources.len() - icons);
+    for (name, data) in &rc.files {
+        if name.ends_with(".bmp") {
+            assert_eq!(&data[..2], b"BM");
+        }
+    }
+}
+
+/// Run a command of the vb6-lsp binary and return its output
+fn run_cli(args: &[&str]) -> String {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vb6-lsp"))
+        .args(args)
+        .output()
+        .expect("Failed to run vb6-lsp");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_json_roundtrip_is_byte_identical() {
+    let fixtures = [("Game2048.RES", "Game2048_json.RES"), ("sheep.res", "sheep_json.res")];
+    for (fixture, copy) in fixtures {
+        let res_path = fixture_path(fixture);
+        let json_path = fixture_path(&format!("{}.json", copy));
+        let output_path = fixture_path(copy);
+
+        let json = run_cli(&["read-res", res_path.to_str().unwrap()]);
+        std::fs::write(&json_path, json).unwrap();
+        run_cli(&["write-res", json_path.to_str().unwrap(), output_path.to_str().unwrap()]);
+
+        let original = std::fs::read(&res_path).unwrap();
+        let written = std::fs::read(&output_path).unwrap();
+        let _ = std::fs::remove_file(&json_path);
+        let _ = std::fs::remove_file(&output_path);
+        assert!(original == written, "{} changed in a read-res/write-res round trip", fixture);
+    }
+}
+
+#[test]
+fn test_json_keeps_memory_flags_and_versions() {
+    let json_path = fixture_path("flags.json");
+    let output_path = fixture_path("flags.res");
+    let json = serde_json::json!({
+        "resources": [{
+            "resource_type": "RcData",
+            "name": {"type": "Id", "value": 1},
+            "language_id": 1033,
+            "memory_flags": {"moveable": false, "preload": true, "discardable": true, "other": 4},
+            "data_version": 1,
+            "version": 7,
+            "characteristics": 9,
+            "data_base64": "AQID"
+        }]
+    });
+    std::fs::write(&json_path, json.to_string()).unwrap();
+    run_cli(&["write-res", json_path.to_str().unwrap(), output_path.to_str().unwrap()]);
+
+    let resources = read_res_file(output_path.to_str().unwrap()).unwrap();
+    let read = run_cli(&["read-res", output_path.to_str().unwrap()]);
+    let _ = std::fs::remove_file(&json_path);
+    let _ = std::fs::remove_file(&output_path);
+
+    let entry = &resources[0];
+    assert_eq!(entry.memory_flags.0, MemoryFlags::PRELOAD | MemoryFlags::DISCARDABLE | 4);
+    assert_eq!((entry.data_version, entry.version, entry.characteristics), (1, 7, 9));
+
+    let read: serde_json::Value = serde_json::from_str(&read).unwrap();
+    let flags = &read["resources"][0]["memory_flags"];
+    assert_eq!(flags, &serde_json::json!({
+        "moveable": false, "pure": false, "preload": true, "discardable": true, "other": 4
+    }));
+}
@@ -10,16 +10,8 @@
 (function_declaration
   name: (identifier) @name) @definition.function
 
-; Procedures - Property Get
-(property_get_declaration
-  name: (identifier) @name) @definition.function
-
-; Procedures - Property Let
-(property_let_declaration
-  name: (identifier) @name) @definition.function
-
-; Procedures - Property Set
-(property_set_declaration
+; Procedures - Property Get/Let/Set
+(property_declaration
   name: (identifier) @name) @definition.function
 
 ; External API declarations
@@ -27,7 +19,7 @@
   name: (identifier) @name) @definition.function
 
 ; Events
-(event_declaration
+(event_statement
   name: (identifier) @name) @definition.function
 
 ; Types (UDT)
@@ -40,8 +32,9 @@
 
 ; Module-level variables (Public/Private)
 (variable_declaration
-  (variable_declarator
-    name: (identifier) @name)) @definition.variable
+  (variable_list
+    (variable_declarator
+      name: (identifier) @name))) @definition.variable
 
 ; Constants
 (constant_declaration
@@ -61,9 +54,9 @@
   function: (identifier) @name) @reference.call
 
 (call_expression
-  function: (member_access_expression
+  function: (member_expression
     member: (identifier) @name)) @reference.call
 
 ; Implements (interface reference)
 (implements_statement
-  interface: (qualified_identifier) @name) @reference.type
+  (dotted_name) @name) @reference.type